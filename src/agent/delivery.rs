@@ -0,0 +1,333 @@
+//! Notification Delivery Retry Queue
+//!
+//! Keeps scheduled notifications that failed to send:
+//! - Bounded retry queue with exponential backoff (via `RetryPolicy`)
+//! - Permanently failed deliveries kept for inspection (`/remind failed`)
+//! - Persisted to JSON so a restart doesn't drop undelivered reminders
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{error, warn};
+
+use super::recovery::RetryPolicy;
+use super::scheduler::Reminder;
+
+/// A notification waiting to be re-sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingDelivery {
+    pub reminder: Reminder,
+    /// Failed attempts so far
+    pub attempts: u32,
+    /// Next retry (unix timestamp)
+    pub next_attempt_at: i64,
+    /// Most recent send error
+    pub last_error: String,
+}
+
+/// A notification that exhausted its retries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedDelivery {
+    pub reminder: Reminder,
+    pub attempts: u32,
+    pub error: String,
+    /// When it was given up on (unix timestamp)
+    pub failed_at: i64,
+}
+
+impl FailedDelivery {
+    /// Format for display
+    pub fn format(&self) -> String {
+        let failed = chrono::DateTime::from_timestamp(self.failed_at, 0)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        format!(
+            "{} {} (failed {} after {} attempts: {})",
+            self.reminder.notification_type.emoji(),
+            self.reminder.message,
+            failed,
+            self.attempts,
+            self.error
+        )
+    }
+}
+
+/// Retry queue configuration
+#[derive(Debug, Clone)]
+pub struct DeliveryConfig {
+    /// Send attempts before giving up (including the first)
+    pub max_attempts: u32,
+    /// Backoff between attempts
+    pub retry_policy: RetryPolicy,
+    /// Maximum notifications awaiting retry
+    pub max_pending: usize,
+    /// Maximum permanently failed notifications kept
+    pub max_failed: usize,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            retry_policy: RetryPolicy {
+                max_retries: 4,
+                initial_delay: Duration::from_secs(5),
+                max_delay: Duration::from_secs(600),
+                backoff_multiplier: 3.0,
+                add_jitter: true,
+                jitter_factor: 0.2,
+            },
+            max_pending: 100,
+            max_failed: 50,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueueState {
+    pending: VecDeque<PendingDelivery>,
+    failed: VecDeque<FailedDelivery>,
+}
+
+/// Bounded retry queue for failed notification sends
+pub struct DeliveryQueue {
+    config: DeliveryConfig,
+    state: Mutex<QueueState>,
+    path: Option<PathBuf>,
+}
+
+impl DeliveryQueue {
+    /// Create an in-memory queue
+    pub fn new(config: DeliveryConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(QueueState::default()),
+            path: None,
+        }
+    }
+
+    /// Open a queue persisted at `path`, restoring undelivered notifications
+    ///
+    /// Restored pending notifications are retried immediately.
+    pub fn open(path: &Path, config: DeliveryConfig) -> Self {
+        let mut state: QueueState = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| match serde_json::from_str(&s) {
+                Ok(state) => Some(state),
+                Err(e) => {
+                    warn!("Ignoring unreadable delivery queue {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        let now = chrono::Utc::now().timestamp();
+        for pending in state.pending.iter_mut() {
+            pending.next_attempt_at = now;
+        }
+
+        Self {
+            config,
+            state: Mutex::new(state),
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    /// Record a failed send, scheduling a retry or giving up
+    ///
+    /// `attempts` is the number of attempts already made for this notification.
+    /// Returns true if the notification will be retried.
+    pub fn record_failure(&self, reminder: Reminder, attempts: u32, error: &str) -> bool {
+        let now = chrono::Utc::now().timestamp();
+        let retry = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+            if attempts < self.config.max_attempts && state.pending.len() < self.config.max_pending {
+                let delay = self.config.retry_policy.delay_for_attempt(attempts.saturating_sub(1) as usize);
+                warn!(
+                    "Notification {} failed (attempt {}/{}), retrying in {}s: {}",
+                    reminder.id, attempts, self.config.max_attempts, delay.as_secs(), error
+                );
+                state.pending.push_back(PendingDelivery {
+                    reminder,
+                    attempts,
+                    next_attempt_at: now + delay.as_secs().max(1) as i64,
+                    last_error: error.to_string(),
+                });
+                true
+            } else {
+                error!(
+                    "Notification {} for user {} permanently failed after {} attempts: {}",
+                    reminder.id, reminder.user_id, attempts, error
+                );
+                if state.failed.len() >= self.config.max_failed {
+                    state.failed.pop_front();
+                }
+                state.failed.push_back(FailedDelivery {
+                    reminder,
+                    attempts,
+                    error: error.to_string(),
+                    failed_at: now,
+                });
+                false
+            }
+        };
+
+        self.persist();
+        retry
+    }
+
+    /// Remove and return notifications whose retry time has come
+    pub fn take_due(&self) -> Vec<PendingDelivery> {
+        let now = chrono::Utc::now().timestamp();
+        let due: Vec<PendingDelivery> = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let (due, waiting): (VecDeque<_>, VecDeque<_>) = state
+                .pending
+                .drain(..)
+                .partition(|p| p.next_attempt_at <= now);
+            state.pending = waiting;
+            due.into()
+        };
+
+        if !due.is_empty() {
+            self.persist();
+        }
+        due
+    }
+
+    /// Notifications awaiting retry
+    pub fn pending_count(&self) -> usize {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).pending.len()
+    }
+
+    /// Permanently failed notifications for a user, newest first
+    pub fn failed_for_user(&self, user_id: i64) -> Vec<FailedDelivery> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .failed
+            .iter()
+            .rev()
+            .filter(|f| f.reminder.user_id == user_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Notifications awaiting retry for a user
+    pub fn pending_for_user(&self, user_id: i64) -> Vec<PendingDelivery> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .pending
+            .iter()
+            .filter(|p| p.reminder.user_id == user_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Forget a user's permanently failed notifications
+    pub fn clear_failed(&self, user_id: i64) -> usize {
+        let removed = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let before = state.failed.len();
+            state.failed.retain(|f| f.reminder.user_id != user_id);
+            before - state.failed.len()
+        };
+        if removed > 0 {
+            self.persist();
+        }
+        removed
+    }
+
+    /// Write queue state to disk (no-op for in-memory queues)
+    fn persist(&self) {
+        let Some(ref path) = self.path else { return };
+        let json = {
+            let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            match serde_json::to_string_pretty(&*state) {
+                Ok(json) => json,
+                Err(e) => {
+                    warn!("Failed to serialize delivery queue: {}", e);
+                    return;
+                }
+            }
+        };
+        if let Err(e) = std::fs::write(path, json) {
+            warn!("Failed to persist delivery queue to {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fast_config(max_attempts: u32) -> DeliveryConfig {
+        DeliveryConfig {
+            max_attempts,
+            retry_policy: RetryPolicy {
+                initial_delay: Duration::from_secs(0),
+                add_jitter: false,
+                ..RetryPolicy::default()
+            },
+            ..DeliveryConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_retry_then_give_up() {
+        let queue = DeliveryQueue::new(fast_config(2));
+        let reminder = Reminder::once(1, 1, "Ping", chrono::Utc::now().timestamp());
+
+        assert!(queue.record_failure(reminder.clone(), 1, "network"));
+        assert_eq!(queue.pending_count(), 1);
+        assert!(queue.failed_for_user(1).is_empty());
+
+        assert!(!queue.record_failure(reminder, 2, "network"));
+        let failed = queue.failed_for_user(1);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, 2);
+        assert!(failed[0].format().contains("Ping"));
+
+        assert_eq!(queue.clear_failed(1), 1);
+        assert!(queue.failed_for_user(1).is_empty());
+    }
+
+    #[test]
+    fn test_take_due_respects_backoff() {
+        let config = DeliveryConfig {
+            retry_policy: RetryPolicy {
+                initial_delay: Duration::from_secs(3600),
+                add_jitter: false,
+                ..RetryPolicy::default()
+            },
+            ..DeliveryConfig::default()
+        };
+        let queue = DeliveryQueue::new(config);
+        queue.record_failure(Reminder::once(1, 1, "Later", 0), 1, "blip");
+
+        assert!(queue.take_due().is_empty());
+        assert_eq!(queue.pending_count(), 1);
+    }
+
+    #[test]
+    fn test_persistence_restores_pending() {
+        let path = PathBuf::from("/tmp/claudebot_test_delivery_queue.json");
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let queue = DeliveryQueue::open(&path, DeliveryConfig::default());
+            queue.record_failure(Reminder::once(5, 5, "Survive restart", 0), 1, "offline");
+            queue.record_failure(Reminder::once(5, 5, "Gone", 0), 5, "offline");
+        }
+
+        let restored = DeliveryQueue::open(&path, DeliveryConfig::default());
+        assert_eq!(restored.failed_for_user(5).len(), 1);
+        let due = restored.take_due();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].reminder.message, "Survive restart");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -19,11 +19,15 @@ pub mod planner;
 pub mod streaming;
 pub mod scheduler;
 pub mod recovery;
+pub mod delivery;
+pub mod notify;
 
-pub use reflection::{ReflectionEngine, ReflectionResult, QualityScore};
+pub use reflection::{Criterion, ReflectionConfig, ReflectionCriteria, ReflectionEngine, ReflectionResult, QualityScore};
 pub use orchestrator::{AgentOrchestrator, SubAgent, AgentTask, AgentResult};
 pub use tools::{ToolRegistry, Tool, ToolCall, ToolResult, ToolSchema};
 pub use planner::{PlanningEngine, Plan, PlanStep, PlanStatus, ApprovalState};
 pub use streaming::{StreamingResponse, StreamChunk, StreamHandle};
-pub use scheduler::{Scheduler, ScheduledTask, Reminder, NotificationType, Priority};
+pub use scheduler::{Scheduler, SchedulerConfig, ScheduledTask, Reminder, NotificationType, Priority, QuietHours};
 pub use recovery::{RecoveryStrategy, RetryPolicy, CircuitBreaker, RecoveryAction};
+pub use delivery::{DeliveryQueue, DeliveryConfig, PendingDelivery, FailedDelivery};
+pub use notify::{NotificationSink, NotificationRouter, DiscordWebhookSink, EmailSink};
@@ -0,0 +1,355 @@
+//! Notification Transports
+//!
+//! Delivers scheduler notifications through pluggable sinks:
+//! - Telegram (the bot itself, see `telegram.rs`)
+//! - Discord (incoming webhook)
+//! - Email (local `sendmail`)
+//!
+//! A notification goes only to the sink for its origin channel (Telegram when
+//! unset), so a personal reminder never lands in a shared webhook or inbox.
+//! Types listed in `NOTIFY_BROADCAST_TYPES` fan out to every configured sink.
+//! Each sink formats the message for its platform from the `NotificationType`.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use super::scheduler::{NotificationType, Reminder};
+use crate::channels::ChannelType;
+
+/// A transport that can deliver a notification
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    /// Short name for logs (`telegram`, `discord`, `email`)
+    fn name(&self) -> &'static str;
+
+    /// Channel whose reminders this sink delivers when routing by origin
+    /// (None for sinks like email that only receive fan-out)
+    fn channel(&self) -> Option<ChannelType>;
+
+    async fn send(&self, reminder: &Reminder) -> Result<()>;
+}
+
+/// Plain-text rendering shared by sinks without rich formatting
+pub fn plain_text(reminder: &Reminder) -> String {
+    format!(
+        "{} {}\n\n{}",
+        reminder.notification_type.emoji(),
+        reminder.notification_type.title(),
+        reminder.message
+    )
+}
+
+/// Routes notifications to the configured sinks
+#[derive(Clone, Default)]
+pub struct NotificationRouter {
+    sinks: Vec<Arc<dyn NotificationSink>>,
+    /// Types sent to every sink regardless of origin
+    broadcast_types: Vec<NotificationType>,
+}
+
+impl NotificationRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_sink(mut self, sink: Arc<dyn NotificationSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Send these notification types to every sink
+    pub fn with_broadcast_types(mut self, types: Vec<NotificationType>) -> Self {
+        self.broadcast_types = types;
+        self
+    }
+
+    /// `NOTIFY_BROADCAST_TYPES` (comma-separated type names, default none)
+    pub fn broadcast_types_from_env() -> Vec<NotificationType> {
+        std::env::var("NOTIFY_BROADCAST_TYPES")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .filter_map(|name| {
+                let parsed = NotificationType::parse(name);
+                if parsed.is_none() {
+                    warn!("Ignoring unknown notification type in NOTIFY_BROADCAST_TYPES: {}", name);
+                }
+                parsed
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Names of the configured sinks
+    pub fn sink_names(&self) -> Vec<&'static str> {
+        self.sinks.iter().map(|s| s.name()).collect()
+    }
+
+    /// Sinks a reminder goes to: all of them for broadcast types, else only
+    /// the origin channel's sink (the owning Telegram chat when unset)
+    fn targets(&self, reminder: &Reminder) -> Vec<&Arc<dyn NotificationSink>> {
+        if self.broadcast_types.contains(&reminder.notification_type) {
+            return self.sinks.iter().collect();
+        }
+        let channel = reminder.origin.unwrap_or(ChannelType::Telegram);
+        self.sinks.iter().filter(|s| s.channel() == Some(channel)).collect()
+    }
+
+    /// Deliver a reminder. Succeeds if at least one target sink accepted it,
+    /// so a retry never re-sends to sinks that already delivered.
+    pub async fn deliver(&self, reminder: &Reminder) -> Result<()> {
+        let mut errors = Vec::new();
+        let mut delivered = false;
+        for sink in self.targets(reminder) {
+            match sink.send(reminder).await {
+                Ok(()) => delivered = true,
+                Err(e) => {
+                    warn!("{} notification {} failed: {}", sink.name(), reminder.id, e);
+                    errors.push(format!("{}: {}", sink.name(), e));
+                }
+            }
+        }
+        if delivered {
+            Ok(())
+        } else if errors.is_empty() {
+            anyhow::bail!("No notification sink for this reminder's channel")
+        } else {
+            anyhow::bail!("{}", errors.join("; "))
+        }
+    }
+}
+
+/// Posts notifications to a Discord incoming webhook
+pub struct DiscordWebhookSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl DiscordWebhookSink {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// `NOTIFY_DISCORD_WEBHOOK`
+    pub fn from_env() -> Option<Self> {
+        std::env::var("NOTIFY_DISCORD_WEBHOOK")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| Self::new(url.trim()))
+    }
+
+    /// Discord markdown: bold title, message truncated to the 2000-char limit
+    pub fn format(reminder: &Reminder) -> String {
+        let text = format!(
+            "{} **{}**\n\n{}",
+            reminder.notification_type.emoji(),
+            reminder.notification_type.title(),
+            reminder.message
+        );
+        if text.chars().count() <= 2000 {
+            text
+        } else {
+            format!("{}…", text.chars().take(1999).collect::<String>())
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for DiscordWebhookSink {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn channel(&self) -> Option<ChannelType> {
+        Some(ChannelType::Discord)
+    }
+
+    async fn send(&self, reminder: &Reminder) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({ "content": Self::format(reminder) }))
+            .send()
+            .await
+            .context("Discord webhook request failed")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            anyhow::bail!("Discord webhook returned {}: {}", status, response.text().await.unwrap_or_default());
+        }
+        Ok(())
+    }
+}
+
+/// Emails notifications through the local `sendmail`
+pub struct EmailSink {
+    to: String,
+    from: Option<String>,
+    sendmail: String,
+}
+
+impl EmailSink {
+    pub fn new(to: &str, from: Option<&str>, sendmail: &str) -> Self {
+        Self {
+            to: to.to_string(),
+            from: from.map(str::to_string),
+            sendmail: sendmail.to_string(),
+        }
+    }
+
+    /// `NOTIFY_EMAIL_TO`, `NOTIFY_EMAIL_FROM` and `NOTIFY_SENDMAIL`
+    /// (default `/usr/sbin/sendmail`)
+    pub fn from_env() -> Option<Self> {
+        let to = std::env::var("NOTIFY_EMAIL_TO").ok().filter(|v| !v.trim().is_empty())?;
+        let from = std::env::var("NOTIFY_EMAIL_FROM").ok().filter(|v| !v.trim().is_empty());
+        let sendmail = std::env::var("NOTIFY_SENDMAIL").unwrap_or_else(|_| "/usr/sbin/sendmail".to_string());
+        Some(Self::new(to.trim(), from.as_deref().map(str::trim), &sendmail))
+    }
+
+    /// RFC 5322 message: subject from the notification type, plain-text body
+    pub fn format(&self, reminder: &Reminder) -> String {
+        let first_line = reminder.message.lines().next().unwrap_or("");
+        let mut subject = format!("{}: {}", reminder.notification_type.title(), first_line);
+        if subject.chars().count() > 78 {
+            subject = format!("{}…", subject.chars().take(77).collect::<String>());
+        }
+        let mut headers = format!("To: {}\n", self.to);
+        if let Some(from) = &self.from {
+            headers.push_str(&format!("From: {}\n", from));
+        }
+        format!(
+            "{}Subject: {}\nContent-Type: text/plain; charset=utf-8\n\n{}\n",
+            headers,
+            subject.replace(['\r', '\n'], " "),
+            plain_text(reminder)
+        )
+    }
+}
+
+#[async_trait]
+impl NotificationSink for EmailSink {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn channel(&self) -> Option<ChannelType> {
+        None
+    }
+
+    async fn send(&self, reminder: &Reminder) -> Result<()> {
+        let mut child = Command::new(&self.sendmail)
+            .arg("-t")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run {}", self.sendmail))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(self.format(reminder).as_bytes()).await?;
+        }
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            anyhow::bail!("sendmail failed: {}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::scheduler::NotificationType;
+    use std::sync::Mutex;
+
+    struct RecordingSink {
+        name: &'static str,
+        channel: Option<ChannelType>,
+        fail: bool,
+        sent: Mutex<Vec<String>>,
+    }
+
+    impl RecordingSink {
+        fn new(name: &'static str, channel: Option<ChannelType>, fail: bool) -> Arc<Self> {
+            Arc::new(Self { name, channel, fail, sent: Mutex::new(Vec::new()) })
+        }
+
+        fn sent(&self) -> usize {
+            self.sent.lock().unwrap().len()
+        }
+    }
+
+    #[async_trait]
+    impl NotificationSink for RecordingSink {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn channel(&self) -> Option<ChannelType> {
+            self.channel
+        }
+
+        async fn send(&self, reminder: &Reminder) -> Result<()> {
+            if self.fail {
+                anyhow::bail!("down");
+            }
+            self.sent.lock().unwrap().push(reminder.id.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_router_fans_out_or_follows_origin() {
+        let telegram = RecordingSink::new("telegram", Some(ChannelType::Telegram), false);
+        let discord = RecordingSink::new("discord", Some(ChannelType::Discord), false);
+        let email = RecordingSink::new("email", None, true);
+        let router = NotificationRouter::new()
+            .with_sink(telegram.clone())
+            .with_sink(discord.clone())
+            .with_sink(email.clone());
+
+        // No origin: the owning Telegram chat only
+        router.deliver(&Reminder::once(1, 1, "stand up", 0)).await.unwrap();
+        assert_eq!((telegram.sent(), discord.sent()), (1, 0));
+
+        // Discord origin: Discord only
+        let reminder = Reminder::once(1, 1, "deploy", 0).from_channel(ChannelType::Discord);
+        router.deliver(&reminder).await.unwrap();
+        assert_eq!((telegram.sent(), discord.sent()), (1, 1));
+
+        // Origin without a sink isn't delivered anywhere else
+        let reminder = Reminder::once(1, 1, "hi", 0).from_channel(ChannelType::Slack);
+        assert!(router.deliver(&reminder).await.is_err());
+        assert_eq!((telegram.sent(), discord.sent()), (1, 1));
+
+        // Broadcast types: all sinks; the failing email sink doesn't fail delivery
+        let router = router.with_broadcast_types(vec![NotificationType::SystemStatus]);
+        let alert = Reminder::once(1, 1, "disk full", 0).with_type(NotificationType::SystemStatus);
+        router.deliver(&alert).await.unwrap();
+        assert_eq!((telegram.sent(), discord.sent()), (2, 2));
+
+        let only_email = NotificationRouter::new().with_sink(email);
+        assert!(only_email.deliver(&Reminder::once(1, 1, "hi", 0)).await.is_err());
+        assert!(NotificationRouter::new().deliver(&alert).await.is_err());
+    }
+
+    #[test]
+    fn test_sink_formatting() {
+        let reminder = Reminder::once(1, 1, "Check build\nthen deploy", 0).with_type(NotificationType::TaskResult);
+        assert_eq!(DiscordWebhookSink::format(&reminder), "✅ **Task Result**\n\nCheck build\nthen deploy");
+
+        let email = EmailSink::new("me@example.com", Some("bot@example.com"), "sendmail");
+        let message = email.format(&reminder);
+        assert!(message.starts_with("To: me@example.com\nFrom: bot@example.com\nSubject: Task Result: Check build\n"));
+        assert!(message.ends_with("\n\n✅ Task Result\n\nCheck build\nthen deploy\n"));
+    }
+}
@@ -6,11 +6,17 @@
 //! - Critique generation and response revision
 //! - Multi-dimensional scoring (accuracy, helpfulness, safety)
 //!
+//! How dimensions combine is operator-configurable: `CLAUDEBOT_REFLECT_WEIGHTS`
+//! sets relative weights (`accuracy:0.5,clarity:0.1`) and
+//! `CLAUDEBOT_REFLECT_MIN_SCORES` sets per-dimension floors
+//! (`accuracy:0.7`) below which an answer is retried whatever its overall
+//! score.
+//!
 //! Industry standard: Anthropic's Constitutional AI, OpenAI's RLHF
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Quality dimensions for evaluation
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -41,6 +47,20 @@ impl QualityDimension {
         }
     }
 
+    /// Parse a dimension name (`as_str`, plus `correctness` / `instruction`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "accuracy" | "correctness" => Some(Self::Accuracy),
+            "helpfulness" => Some(Self::Helpfulness),
+            "safety" => Some(Self::Safety),
+            "clarity" => Some(Self::Clarity),
+            "instruction_following" | "instruction" => Some(Self::Instruction),
+            "completeness" => Some(Self::Completeness),
+            _ => None,
+        }
+    }
+
+    /// Default weight
     pub fn weight(&self) -> f64 {
         match self {
             Self::Accuracy => 0.25,
@@ -53,6 +73,140 @@ impl QualityDimension {
     }
 }
 
+/// Weight and optional retry floor for one dimension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Criterion {
+    pub dimension: QualityDimension,
+    /// Relative weight in `overall` (0 leaves the dimension out)
+    pub weight: f64,
+    /// Retry whenever the dimension scores below this, regardless of overall
+    pub min_score: Option<f64>,
+}
+
+/// How dimension scores combine into `overall` and when to retry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflectionCriteria {
+    pub criteria: Vec<Criterion>,
+}
+
+impl Default for ReflectionCriteria {
+    /// The dimensions the judge scores, at their default weights, no floors
+    fn default() -> Self {
+        let criteria = [
+            QualityDimension::Accuracy,
+            QualityDimension::Helpfulness,
+            QualityDimension::Safety,
+            QualityDimension::Clarity,
+            QualityDimension::Instruction,
+        ]
+        .into_iter()
+        .map(|dimension| Criterion {
+            dimension,
+            weight: dimension.weight(),
+            min_score: None,
+        })
+        .collect();
+        Self { criteria }
+    }
+}
+
+impl ReflectionCriteria {
+    pub fn get(&self, dimension: QualityDimension) -> Option<&Criterion> {
+        self.criteria.iter().find(|c| c.dimension == dimension)
+    }
+
+    /// Weight of a dimension (its default weight if unconfigured)
+    pub fn weight(&self, dimension: QualityDimension) -> f64 {
+        self.get(dimension).map_or(dimension.weight(), |c| c.weight)
+    }
+
+    /// Scores whose weight is above 0 (all of them if every weight is 0)
+    pub fn counted<'a>(&self, dimensions: &'a [DimensionScore]) -> Vec<&'a DimensionScore> {
+        let weighted: Vec<_> = dimensions.iter().filter(|d| self.weight(d.dimension) > 0.0).collect();
+        if weighted.is_empty() {
+            dimensions.iter().collect()
+        } else {
+            weighted
+        }
+    }
+
+    fn entry(&mut self, dimension: QualityDimension) -> &mut Criterion {
+        if let Some(i) = self.criteria.iter().position(|c| c.dimension == dimension) {
+            return &mut self.criteria[i];
+        }
+        self.criteria.push(Criterion {
+            dimension,
+            weight: dimension.weight(),
+            min_score: None,
+        });
+        self.criteria.last_mut().unwrap()
+    }
+
+    /// Apply `name:weight,...`; unlisted dimensions keep their weight
+    pub fn with_weights(mut self, spec: &str) -> Result<Self> {
+        for (dimension, weight) in parse_dimension_values(spec)? {
+            if !weight.is_finite() || weight < 0.0 {
+                anyhow::bail!("Weight for {} must be 0 or more, got {}", dimension.as_str(), weight);
+            }
+            self.entry(dimension).weight = weight;
+        }
+        if self.criteria.iter().all(|c| c.weight == 0.0) {
+            anyhow::bail!("At least one dimension needs a weight above 0");
+        }
+        Ok(self)
+    }
+
+    /// Apply `name:min_score,...` floors (0.0 - 1.0)
+    pub fn with_min_scores(mut self, spec: &str) -> Result<Self> {
+        for (dimension, min) in parse_dimension_values(spec)? {
+            if !(0.0..=1.0).contains(&min) {
+                anyhow::bail!("Minimum for {} must be between 0 and 1, got {}", dimension.as_str(), min);
+            }
+            self.entry(dimension).min_score = Some(min);
+        }
+        Ok(self)
+    }
+
+    /// Format the active weights and floors for display
+    pub fn format(&self) -> String {
+        let total: f64 = self.criteria.iter().map(|c| c.weight).sum();
+        let mut s = "Reflection criteria\n\n".to_string();
+        for c in &self.criteria {
+            let share = if total > 0.0 { c.weight / total * 100.0 } else { 0.0 };
+            s.push_str(&format!("  {}: {:.0}%", c.dimension.as_str(), share));
+            if let Some(min) = c.min_score {
+                s.push_str(&format!(" (retry below {:.0}%)", min * 100.0));
+            }
+            s.push('\n');
+        }
+        s.push_str(
+            "\nSet with CLAUDEBOT_REFLECT_WEIGHTS (e.g. accuracy:0.5,clarity:0.1)\n\
+            and CLAUDEBOT_REFLECT_MIN_SCORES (e.g. accuracy:0.7).",
+        );
+        s
+    }
+}
+
+/// Parse `name:value` pairs separated by commas
+fn parse_dimension_values(spec: &str) -> Result<Vec<(QualityDimension, f64)>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, value) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("Expected name:value, got '{}'", entry))?;
+            let dimension = QualityDimension::parse(name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown reflection dimension '{}'", name.trim()))?;
+            let value = value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| anyhow::anyhow!("Invalid number for {}: '{}'", name.trim(), value.trim()))?;
+            Ok((dimension, value))
+        })
+        .collect()
+}
+
 /// Score for a single quality dimension (0.0 - 1.0)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DimensionScore {
@@ -61,6 +215,9 @@ pub struct DimensionScore {
     pub critique: Option<String>,
 }
 
+/// A dimension scored below this warrants a retry whatever the overall score
+const WEAK_DIMENSION_SCORE: f64 = 0.4;
+
 /// Overall quality score with breakdown
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityScore {
@@ -74,6 +231,9 @@ pub struct QualityScore {
     pub improvements: Vec<String>,
     /// Should this response be retried?
     pub should_retry: bool,
+    /// Dimensions below their configured floor
+    #[serde(default)]
+    pub below_min: Vec<QualityDimension>,
 }
 
 impl QualityScore {
@@ -85,24 +245,52 @@ impl QualityScore {
             critique: "Response meets all criteria.".to_string(),
             improvements: vec![],
             should_retry: false,
+            below_min: vec![],
         }
     }
 
-    /// Create score from dimension scores
+    /// Create score from dimension scores with the default weights
     pub fn from_dimensions(dimensions: Vec<DimensionScore>, critique: String) -> Self {
-        let overall: f64 = dimensions
-            .iter()
-            .map(|d| d.score * d.dimension.weight())
-            .sum::<f64>()
-            / dimensions.iter().map(|d| d.dimension.weight()).sum::<f64>();
+        Self::with_criteria(dimensions, critique, &ReflectionCriteria::default())
+    }
 
-        let improvements: Vec<String> = dimensions
+    /// Create score from dimension scores, weighted and floored by `criteria`
+    ///
+    /// Dimensions with weight 0 are ignored, for `overall` as well as
+    /// improvements and retries. If every scored dimension has weight 0,
+    /// `overall` is the plain mean.
+    pub fn with_criteria(dimensions: Vec<DimensionScore>, critique: String, criteria: &ReflectionCriteria) -> Self {
+        let total_weight: f64 = dimensions.iter().map(|d| criteria.weight(d.dimension)).sum();
+        let overall = if total_weight > 0.0 {
+            dimensions
+                .iter()
+                .map(|d| d.score * criteria.weight(d.dimension))
+                .sum::<f64>()
+                / total_weight
+        } else {
+            dimensions.iter().map(|d| d.score).sum::<f64>() / dimensions.len().max(1) as f64
+        };
+
+        let counted = criteria.counted(&dimensions);
+        let improvements: Vec<String> = counted
             .iter()
             .filter(|d| d.score < 0.7)
             .filter_map(|d| d.critique.clone())
             .collect();
 
-        let should_retry = overall < 0.6 || dimensions.iter().any(|d| d.score < 0.4);
+        let below_min: Vec<QualityDimension> = dimensions
+            .iter()
+            .filter(|d| {
+                criteria
+                    .get(d.dimension)
+                    .and_then(|c| c.min_score)
+                    .is_some_and(|min| d.score < min)
+            })
+            .map(|d| d.dimension)
+            .collect();
+
+        let should_retry =
+            overall < 0.6 || counted.iter().any(|d| d.score < WEAK_DIMENSION_SCORE) || !below_min.is_empty();
 
         Self {
             overall,
@@ -110,6 +298,7 @@ impl QualityScore {
             critique,
             improvements,
             should_retry,
+            below_min,
         }
     }
 
@@ -174,6 +363,37 @@ pub struct ReflectionConfig {
     pub dimensions: Vec<QualityDimension>,
     /// Enable critique generation
     pub generate_critique: bool,
+    /// Auto-retry (when enabled per user) only below this overall score
+    pub retry_threshold: f64,
+    /// Dimension weights and per-dimension retry floors
+    pub criteria: ReflectionCriteria,
+}
+
+impl ReflectionConfig {
+    /// Defaults with `CLAUDEBOT_REFLECT_RETRY_THRESHOLD`, `CLAUDEBOT_REFLECT_WEIGHTS`
+    /// and `CLAUDEBOT_REFLECT_MIN_SCORES` applied (invalid criteria are ignored)
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(threshold) = std::env::var("CLAUDEBOT_REFLECT_RETRY_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+        {
+            config.retry_threshold = threshold.clamp(0.0, 1.0);
+        }
+        if let Ok(spec) = std::env::var("CLAUDEBOT_REFLECT_WEIGHTS") {
+            match config.criteria.clone().with_weights(&spec) {
+                Ok(criteria) => config.criteria = criteria,
+                Err(e) => warn!("Ignoring CLAUDEBOT_REFLECT_WEIGHTS: {}", e),
+            }
+        }
+        if let Ok(spec) = std::env::var("CLAUDEBOT_REFLECT_MIN_SCORES") {
+            match config.criteria.clone().with_min_scores(&spec) {
+                Ok(criteria) => config.criteria = criteria,
+                Err(e) => warn!("Ignoring CLAUDEBOT_REFLECT_MIN_SCORES: {}", e),
+            }
+        }
+        config
+    }
 }
 
 impl Default for ReflectionConfig {
@@ -191,6 +411,8 @@ impl Default for ReflectionConfig {
                 QualityDimension::Instruction,
             ],
             generate_critique: true,
+            retry_threshold: 0.6,
+            criteria: ReflectionCriteria::default(),
         }
     }
 }
@@ -212,6 +434,44 @@ impl ReflectionEngine {
         Self { config }
     }
 
+    /// Active configuration
+    pub fn config(&self) -> &ReflectionConfig {
+        &self.config
+    }
+
+    /// Whether a scored response warrants re-running the prompt
+    ///
+    /// Any of: a dimension under its configured floor, a dimension scored
+    /// very low, or an overall score below `retry_threshold`.
+    pub fn should_auto_retry(&self, quality: &QualityScore) -> bool {
+        let below_threshold = quality.overall < self.config.retry_threshold;
+        let weak_dimension = self
+            .config
+            .criteria
+            .counted(&quality.dimensions)
+            .iter()
+            .any(|d| d.score < WEAK_DIMENSION_SCORE);
+        !quality.below_min.is_empty() || below_threshold || weak_dimension
+    }
+
+    /// Original prompt with the evaluation's improvement suggestions appended
+    pub fn retry_prompt(&self, prompt: &str, response: &str, quality: &QualityScore) -> String {
+        let previous = response.char_indices().nth(2000).map_or(response, |(i, _)| &response[..i]);
+        let mut suggestions: Vec<String> = quality.improvements.iter().map(|i| format!("- {}", i)).collect();
+        if suggestions.is_empty() {
+            suggestions.push(format!("- {}", quality.critique));
+        }
+
+        format!(
+            "{}\n\n[A previous answer to this request scored {:.0}% on review. \
+            Answer again, addressing these issues:]\n{}\n\n[Previous answer:]\n{}",
+            prompt,
+            quality.overall * 100.0,
+            suggestions.join("\n"),
+            previous
+        )
+    }
+
     /// Check if response should be evaluated
     pub fn should_evaluate(&self, response: &str, is_command: bool) -> bool {
         if is_command && !self.config.evaluate_simple {
@@ -334,9 +594,10 @@ JSON only:"#,
             // Parsing failed, assume acceptable
             Ok(QualityScore::perfect())
         } else {
-            Ok(QualityScore::from_dimensions(
+            Ok(QualityScore::with_criteria(
                 dimensions,
                 "Evaluated by LLM-as-judge".to_string(),
+                &self.config.criteria,
             ))
         }
     }
@@ -576,6 +837,124 @@ mod tests {
         assert!(!score.improvements.is_empty());
     }
 
+    #[test]
+    fn test_auto_retry_threshold() {
+        let engine = ReflectionEngine::with_config(ReflectionConfig {
+            retry_threshold: 0.5,
+            ..Default::default()
+        });
+        let score = |accuracy: f64| {
+            QualityScore::from_dimensions(
+                vec![DimensionScore {
+                    dimension: QualityDimension::Accuracy,
+                    score: accuracy,
+                    critique: Some("Cite the actual API".to_string()),
+                }],
+                "Test".to_string(),
+            )
+        };
+
+        // should_retry but above the configured threshold
+        assert!(!engine.should_auto_retry(&score(0.55)));
+        let low = score(0.3);
+        assert!(engine.should_auto_retry(&low));
+
+        // A threshold above the default 0.6 applies to the overall score too
+        let strict = ReflectionEngine::with_config(ReflectionConfig {
+            retry_threshold: 0.8,
+            ..Default::default()
+        });
+        let middling = score(0.7);
+        assert!(!middling.should_retry);
+        assert!(strict.should_auto_retry(&middling));
+        assert!(!strict.should_auto_retry(&score(0.85)));
+
+        let prompt = engine.retry_prompt("How do I parse JSON?", "Use magic.", &low);
+        assert!(prompt.starts_with("How do I parse JSON?"));
+        assert!(prompt.contains("scored 30%"));
+        assert!(prompt.contains("- Cite the actual API"));
+        assert!(prompt.ends_with("Use magic."));
+    }
+
+    #[test]
+    fn test_configured_criteria() {
+        let criteria = ReflectionCriteria::default()
+            .with_weights("correctness:0.8, clarity:0.2, helpfulness:0, safety:0, instruction:0")
+            .unwrap()
+            .with_min_scores("accuracy:0.7")
+            .unwrap();
+        assert!(ReflectionCriteria::default().with_weights("tone:1").is_err());
+        assert!(ReflectionCriteria::default().with_min_scores("accuracy:2").is_err());
+        assert!(ReflectionCriteria::default().with_weights("accuracy").is_err());
+
+        let dim = |dimension, score| DimensionScore { dimension, score, critique: None };
+        let scored = |accuracy| {
+            QualityScore::with_criteria(
+                vec![
+                    dim(QualityDimension::Accuracy, accuracy),
+                    dim(QualityDimension::Clarity, 1.0),
+                    dim(QualityDimension::Helpfulness, 0.0),
+                ],
+                "Test".to_string(),
+                &criteria,
+            )
+        };
+
+        // Helpfulness has weight 0, so only accuracy and clarity count
+        let good = scored(1.0);
+        assert!((good.overall - 1.0).abs() < 1e-9);
+        assert!(good.below_min.is_empty());
+        assert!(!good.should_retry);
+
+        // Accuracy below its floor retries even though overall is above the threshold
+        let engine = ReflectionEngine::with_config(ReflectionConfig {
+            retry_threshold: 0.5,
+            criteria: criteria.clone(),
+            ..Default::default()
+        });
+        let weak = scored(0.65);
+        assert!(weak.overall > 0.7);
+        assert_eq!(weak.below_min, vec![QualityDimension::Accuracy]);
+        assert!(engine.should_auto_retry(&weak));
+
+        let shown = criteria.format();
+        assert!(shown.contains("accuracy: 80% (retry below 70%)"));
+        assert!(shown.contains("clarity: 20%"));
+    }
+
+    #[test]
+    fn test_zero_weight_dimension_is_ignored() {
+        let criteria = ReflectionCriteria::default().with_weights("helpfulness:0").unwrap();
+        let scores = vec![
+            DimensionScore {
+                dimension: QualityDimension::Accuracy,
+                score: 0.9,
+                critique: None,
+            },
+            DimensionScore {
+                dimension: QualityDimension::Helpfulness,
+                score: 0.1,
+                critique: Some("Doesn't answer the question".to_string()),
+            },
+        ];
+
+        let quality = QualityScore::with_criteria(scores.clone(), "Test".to_string(), &criteria);
+        assert!((quality.overall - 0.9).abs() < 1e-9);
+        assert!(!quality.should_retry);
+        assert!(quality.improvements.is_empty());
+        let engine = ReflectionEngine::with_config(ReflectionConfig {
+            criteria,
+            ..Default::default()
+        });
+        assert!(!engine.should_auto_retry(&quality));
+
+        // Counted with its default weight, the same score retries
+        let default = QualityScore::from_dimensions(scores, "Test".to_string());
+        assert!(default.should_retry);
+        assert_eq!(default.improvements, vec!["Doesn't answer the question".to_string()]);
+        assert!(ReflectionEngine::new().should_auto_retry(&default));
+    }
+
     #[test]
     fn test_extract_json() {
         let text = "Here is the evaluation: {\"score\": 8} and more text";
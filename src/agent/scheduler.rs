@@ -5,18 +5,26 @@
 //! - One-time and recurring reminders
 //! - Priority-based notification queue
 //! - User preference-aware delivery
+//! - Per-user quiet hours: non-urgent notifications are held until the
+//!   window ends; saved to SQLite when the scheduler has a database
+//! - Pending reminders can be saved to a file before a restart and restored
+//!   on the next start
 //!
 //! Industry standard: Temporal workflows, Celery beat
 
-use chrono::Timelike;
+use chrono::{DateTime, FixedOffset, NaiveTime, Timelike, Utc};
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{info, warn};
 
+use crate::channels::ChannelType;
+
 /// Type of notification
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NotificationType {
@@ -32,6 +40,8 @@ pub enum NotificationType {
     TaskResult,
     /// Proactive suggestion
     Suggestion,
+    /// Daily digest
+    Digest,
 }
 
 impl NotificationType {
@@ -43,9 +53,25 @@ impl NotificationType {
             Self::SystemStatus => "system",
             Self::TaskResult => "task",
             Self::Suggestion => "suggestion",
+            Self::Digest => "digest",
         }
     }
 
+    /// Parse the `as_str` name
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            Self::Reminder,
+            Self::GoalUpdate,
+            Self::LearningInsight,
+            Self::SystemStatus,
+            Self::TaskResult,
+            Self::Suggestion,
+            Self::Digest,
+        ]
+        .into_iter()
+        .find(|t| t.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+
     pub fn emoji(&self) -> &'static str {
         match self {
             Self::Reminder => "⏰",
@@ -54,6 +80,20 @@ impl NotificationType {
             Self::SystemStatus => "ℹ️",
             Self::TaskResult => "✅",
             Self::Suggestion => "💬",
+            Self::Digest => "📰",
+        }
+    }
+
+    /// Heading shown above the notification body
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Reminder => "Reminder",
+            Self::GoalUpdate => "Goal Update",
+            Self::LearningInsight => "Insight",
+            Self::SystemStatus => "System",
+            Self::TaskResult => "Task Result",
+            Self::Suggestion => "Suggestion",
+            Self::Digest => "Daily Digest",
         }
     }
 }
@@ -88,6 +128,10 @@ pub struct Reminder {
     pub recurring: Option<RecurrenceRule>,
     /// Creation timestamp
     pub created_at: i64,
+    /// Channel the reminder was created from (routes delivery to that sink;
+    /// None means the owning Telegram chat)
+    #[serde(default)]
+    pub origin: Option<ChannelType>,
 }
 
 impl Reminder {
@@ -103,9 +147,16 @@ impl Reminder {
             priority: Priority::Normal,
             recurring: None,
             created_at: chrono::Utc::now().timestamp(),
+            origin: None,
         }
     }
 
+    /// Deliver through the sink for this channel
+    pub fn from_channel(mut self, channel: ChannelType) -> Self {
+        self.origin = Some(channel);
+        self
+    }
+
     /// Set priority
     pub fn with_priority(mut self, priority: Priority) -> Self {
         self.priority = priority;
@@ -149,6 +200,90 @@ impl Reminder {
     }
 }
 
+/// A user's quiet-hours window, in their own UTC offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub offset: FixedOffset,
+}
+
+impl QuietHours {
+    /// Parse `22:00-08:00` with an optional offset (`+02:00`, `UTC-5`);
+    /// without one the server's current offset is used
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let mut parts = spec.split_whitespace();
+        let window = parts.next().ok_or_else(|| anyhow::anyhow!("Expected a window like 22:00-08:00"))?;
+        let (start, end) = window
+            .split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("Expected a window like 22:00-08:00"))?;
+        let time = |s: &str| {
+            NaiveTime::parse_from_str(s.trim(), "%H:%M").map_err(|_| anyhow::anyhow!("Invalid time: {}", s))
+        };
+        let (start, end) = (time(start)?, time(end)?);
+        if start == end {
+            anyhow::bail!("Quiet hours must start and end at different times");
+        }
+        let offset = match parts.next() {
+            Some(offset) => parse_utc_offset(offset)?,
+            None => *chrono::Local::now().offset(),
+        };
+        Ok(Self { start, end, offset })
+    }
+
+    /// If `now` falls inside the window, the timestamp at which it ends
+    pub fn window_end(&self, now: DateTime<Utc>) -> Option<i64> {
+        let local = now.with_timezone(&self.offset);
+        let time = local.time();
+        let inside = if self.start < self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        };
+        if !inside {
+            return None;
+        }
+        let mut date = local.date_naive();
+        if time >= self.end {
+            date = date.succ_opt()?;
+        }
+        date.and_time(self.end)
+            .and_local_timezone(self.offset)
+            .single()
+            .map(|end| end.timestamp())
+    }
+
+    pub fn format(&self) -> String {
+        format!("{}-{} (UTC{})", self.start.format("%H:%M"), self.end.format("%H:%M"), self.offset)
+    }
+}
+
+/// `+02:00`, `-0530`, `UTC+2`, `UTC`
+fn parse_utc_offset(raw: &str) -> anyhow::Result<FixedOffset> {
+    let invalid = || anyhow::anyhow!("Invalid UTC offset: {} (e.g. +02:00 or UTC-5)", raw);
+    let s = raw.trim();
+    let s = s.strip_prefix("UTC").or_else(|| s.strip_prefix("utc")).unwrap_or(s);
+    if s.is_empty() {
+        return FixedOffset::east_opt(0).ok_or_else(invalid);
+    }
+    let (sign, digits) = match s.as_bytes()[0] {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes >= 60 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
 /// Recurrence rule for repeating reminders
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RecurrenceRule {
@@ -324,6 +459,8 @@ pub struct SchedulerConfig {
     pub quiet_end: u8,
     /// Enable quiet hours
     pub enable_quiet_hours: bool,
+    /// Types delivered even during a user's quiet hours (`Urgent` priority always is)
+    pub urgent_types: Vec<NotificationType>,
 }
 
 impl Default for SchedulerConfig {
@@ -334,8 +471,50 @@ impl Default for SchedulerConfig {
             quiet_start: 22,
             quiet_end: 8,
             enable_quiet_hours: false,
+            urgent_types: vec![NotificationType::SystemStatus],
+        }
+    }
+}
+
+impl SchedulerConfig {
+    /// Defaults with `CLAUDEBOT_QUIET_URGENT_TYPES` (comma-separated type names)
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(raw) = std::env::var("CLAUDEBOT_QUIET_URGENT_TYPES") {
+            config.urgent_types = raw
+                .split(',')
+                .filter(|name| !name.trim().is_empty())
+                .filter_map(|name| {
+                    let parsed = NotificationType::parse(name);
+                    if parsed.is_none() {
+                        warn!("Ignoring unknown notification type in CLAUDEBOT_QUIET_URGENT_TYPES: {}", name);
+                    }
+                    parsed
+                })
+                .collect();
         }
+        config
+    }
+}
+
+/// Per-user quiet-hours windows
+type QuietHoursMap = HashMap<i64, QuietHours>;
+
+/// When a reminder held by its user's quiet hours should go out, None to send now
+fn quiet_until(quiet_hours: &QuietHoursMap, urgent_types: &[NotificationType], reminder: &Reminder, now: DateTime<Utc>) -> Option<i64> {
+    if reminder.priority == Priority::Urgent || urgent_types.contains(&reminder.notification_type) {
+        return None;
     }
+    quiet_hours.get(&reminder.user_id)?.window_end(now)
+}
+
+/// One-off copy of a reminder, due when the quiet window ends
+fn held_copy(reminder: &Reminder, due_at: i64) -> Reminder {
+    let mut held = reminder.clone();
+    held.id = uuid::Uuid::new_v4().to_string();
+    held.due_at = due_at;
+    held.recurring = None;
+    held
 }
 
 /// The scheduler for managing reminders and tasks
@@ -344,6 +523,9 @@ pub struct Scheduler {
     reminders: Arc<RwLock<HashMap<String, Reminder>>>,
     tasks: Arc<RwLock<HashMap<String, ScheduledTask>>>,
     queue: Arc<RwLock<BinaryHeap<QueueEntry>>>,
+    quiet_hours: Arc<RwLock<QuietHoursMap>>,
+    /// Where quiet hours are saved (None = memory only)
+    quiet_hours_db: Option<Arc<Mutex<Connection>>>,
     notification_tx: mpsc::Sender<Reminder>,
     running: Arc<RwLock<bool>>,
 }
@@ -363,6 +545,8 @@ impl Scheduler {
             reminders: Arc::new(RwLock::new(HashMap::new())),
             tasks: Arc::new(RwLock::new(HashMap::new())),
             queue: Arc::new(RwLock::new(BinaryHeap::new())),
+            quiet_hours: Arc::new(RwLock::new(HashMap::new())),
+            quiet_hours_db: None,
             notification_tx: tx,
             running: Arc::new(RwLock::new(false)),
         };
@@ -370,6 +554,44 @@ impl Scheduler {
         (scheduler, rx)
     }
 
+    /// Load quiet hours from the SQLite database at `path` and save changes
+    /// there, so they survive restarts
+    pub fn with_quiet_hours_db(mut self, path: &Path) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS quiet_hours (
+                user_id INTEGER PRIMARY KEY,
+                start TEXT NOT NULL,
+                end TEXT NOT NULL,
+                utc_offset_secs INTEGER NOT NULL
+            );
+            "#,
+        )?;
+
+        let mut loaded = HashMap::new();
+        {
+            let mut stmt = conn.prepare("SELECT user_id, start, end, utc_offset_secs FROM quiet_hours")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, i32>(3)?))
+            })?;
+            for row in rows {
+                let (user_id, start, end, offset) = row?;
+                let time = |s: &str| NaiveTime::parse_from_str(s, "%H:%M").ok();
+                match (time(&start), time(&end), FixedOffset::east_opt(offset)) {
+                    (Some(start), Some(end), Some(offset)) => {
+                        loaded.insert(user_id, QuietHours { start, end, offset });
+                    }
+                    _ => warn!("Ignoring unreadable quiet hours for user {}", user_id),
+                }
+            }
+        }
+
+        self.quiet_hours = Arc::new(RwLock::new(loaded));
+        self.quiet_hours_db = Some(Arc::new(Mutex::new(conn)));
+        Ok(self)
+    }
+
     /// Schedule a reminder
     pub async fn schedule_reminder(&self, reminder: Reminder) -> String {
         let id = reminder.id.clone();
@@ -425,68 +647,104 @@ impl Scheduler {
             .collect()
     }
 
-    /// Check if in quiet hours
-    fn is_quiet_hour(&self) -> bool {
-        if !self.config.enable_quiet_hours {
-            return false;
-        }
+    /// Write every pending reminder to `path` (JSON), returning how many
+    pub async fn save_reminders(&self, path: &std::path::Path) -> anyhow::Result<usize> {
+        let reminders: Vec<Reminder> = self.reminders.read().await.values().cloned().collect();
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&reminders)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(reminders.len())
+    }
 
-        let hour = chrono::Local::now().hour() as u8;
-        if self.config.quiet_start < self.config.quiet_end {
-            hour >= self.config.quiet_start && hour < self.config.quiet_end
-        } else {
-            hour >= self.config.quiet_start || hour < self.config.quiet_end
+    /// Schedule the reminders saved at `path` and remove the file
+    ///
+    /// A missing file restores nothing. Reminders that came due while the bot
+    /// was down are delivered on the next poll.
+    pub async fn restore_reminders(&self, path: &std::path::Path) -> anyhow::Result<usize> {
+        let data = match std::fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let reminders: Vec<Reminder> = serde_json::from_str(&data)?;
+        let count = reminders.len();
+        for reminder in reminders {
+            self.schedule_reminder(reminder).await;
         }
+        std::fs::remove_file(path)?;
+        Ok(count)
     }
 
-    /// Process due reminders
-    async fn process_due(&self) -> usize {
-        if self.is_quiet_hour() {
-            return 0;
+    /// Set or clear a user's quiet hours
+    ///
+    /// The change applies even if saving it fails; the error says it won't
+    /// survive a restart.
+    pub async fn set_quiet_hours(&self, user_id: i64, quiet: Option<QuietHours>) -> anyhow::Result<()> {
+        {
+            let mut quiet_hours = self.quiet_hours.write().await;
+            match quiet {
+                Some(quiet) => quiet_hours.insert(user_id, quiet),
+                None => quiet_hours.remove(&user_id),
+            };
         }
 
-        let mut processed = 0;
-
-        // Collect due reminders
-        let due_ids: Vec<String> = {
-            let reminders = self.reminders.read().await;
-            reminders
-                .values()
-                .filter(|r| r.is_due())
-                .map(|r| r.id.clone())
-                .collect()
+        // Written after the map's lock is released, so delivery never waits on disk
+        let Some(db) = self.quiet_hours_db.clone() else {
+            return Ok(());
         };
-
-        for id in due_ids {
-            let reminder = {
-                let mut reminders = self.reminders.write().await;
-                if let Some(reminder) = reminders.remove(&id) {
-                    // Handle recurrence
-                    if let Some(rule) = &reminder.recurring {
-                        if rule.has_more() {
-                            let mut next_rule = rule.clone();
-                            next_rule.occurrences += 1;
-                            let mut next = reminder.clone();
-                            next.id = uuid::Uuid::new_v4().to_string();
-                            next.due_at = rule.next_from(reminder.due_at);
-                            next.recurring = Some(next_rule);
-                            reminders.insert(next.id.clone(), next);
-                        }
-                    }
-                    Some(reminder)
-                } else {
-                    None
-                }
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+            match quiet {
+                Some(quiet) => conn.execute(
+                    "INSERT OR REPLACE INTO quiet_hours (user_id, start, end, utc_offset_secs) VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        user_id,
+                        quiet.start.format("%H:%M").to_string(),
+                        quiet.end.format("%H:%M").to_string(),
+                        quiet.offset.local_minus_utc()
+                    ],
+                )?,
+                None => conn.execute("DELETE FROM quiet_hours WHERE user_id = ?1", params![user_id])?,
             };
+            Ok(())
+        })
+        .await?
+    }
 
-            if let Some(reminder) = reminder {
-                if self.notification_tx.send(reminder).await.is_ok() {
-                    processed += 1;
-                }
-            }
+    /// A user's quiet hours, if set
+    pub async fn quiet_hours(&self, user_id: i64) -> Option<QuietHours> {
+        self.quiet_hours.read().await.get(&user_id).copied()
+    }
+
+    /// Deliver a notification now, or when the user's quiet hours end
+    pub async fn notify(&self, reminder: Reminder) -> bool {
+        let held = {
+            let quiet_hours = self.quiet_hours.read().await;
+            quiet_until(&quiet_hours, &self.config.urgent_types, &reminder, Utc::now())
+        };
+        if let Some(due_at) = held {
+            info!("Holding {} notification for user {} until quiet hours end", reminder.notification_type.as_str(), reminder.user_id);
+            self.schedule_reminder(held_copy(&reminder, due_at)).await;
+            return true;
+        }
+        if self.notification_tx.send(reminder).await.is_err() {
+            warn!("Failed to send notification");
+            return false;
         }
+        true
+    }
+
+    /// Check if in quiet hours
+    fn is_quiet_hour(&self) -> bool {
+        global_quiet_hour(&self.config)
+    }
 
-        processed
+    /// Process due reminders
+    async fn process_due(&self) -> usize {
+        if self.is_quiet_hour() {
+            return 0;
+        }
+        deliver_due(&self.reminders, &self.quiet_hours, &self.config.urgent_types, &self.notification_tx).await
     }
 
     /// Start the scheduler loop
@@ -494,68 +752,20 @@ impl Scheduler {
         *self.running.write().await = true;
 
         let running = self.running.clone();
-        let poll_interval = self.config.poll_interval;
+        let config = self.config.clone();
         let reminders = self.reminders.clone();
+        let quiet_hours = self.quiet_hours.clone();
         let notification_tx = self.notification_tx.clone();
-        let quiet_start = self.config.quiet_start;
-        let quiet_end = self.config.quiet_end;
-        let enable_quiet = self.config.enable_quiet_hours;
 
         tokio::spawn(async move {
             info!("Scheduler started");
 
             while *running.read().await {
-                // Check quiet hours
-                let is_quiet = if enable_quiet {
-                    let hour = chrono::Local::now().hour() as u8;
-                    if quiet_start < quiet_end {
-                        hour >= quiet_start && hour < quiet_end
-                    } else {
-                        hour >= quiet_start || hour < quiet_end
-                    }
-                } else {
-                    false
-                };
-
-                if !is_quiet {
-                    let now = chrono::Utc::now().timestamp();
-
-                    // Find due reminders
-                    let due: Vec<Reminder> = {
-                        let r = reminders.read().await;
-                        r.values()
-                            .filter(|rem| rem.due_at <= now)
-                            .cloned()
-                            .collect()
-                    };
-
-                    for reminder in due {
-                        let id = reminder.id.clone();
-
-                        // Send notification
-                        if notification_tx.send(reminder.clone()).await.is_err() {
-                            warn!("Failed to send notification");
-                            continue;
-                        }
-
-                        // Handle recurrence or remove
-                        let mut reminders_guard = reminders.write().await;
-                        if let Some(ref rule) = reminder.recurring {
-                            if rule.has_more() {
-                                let mut next_rule = rule.clone();
-                                next_rule.occurrences += 1;
-                                let mut next = reminder.clone();
-                                next.id = uuid::Uuid::new_v4().to_string();
-                                next.due_at = rule.next_from(next.due_at);
-                                next.recurring = Some(next_rule);
-                                reminders_guard.insert(next.id.clone(), next);
-                            }
-                        }
-                        reminders_guard.remove(&id);
-                    }
+                if !global_quiet_hour(&config) {
+                    deliver_due(&reminders, &quiet_hours, &config.urgent_types, &notification_tx).await;
                 }
 
-                tokio::time::sleep(poll_interval).await;
+                tokio::time::sleep(config.poll_interval).await;
             }
 
             info!("Scheduler stopped");
@@ -577,6 +787,87 @@ impl Scheduler {
     }
 }
 
+/// Whether the server-wide quiet hours are in effect
+fn global_quiet_hour(config: &SchedulerConfig) -> bool {
+    if !config.enable_quiet_hours {
+        return false;
+    }
+
+    let hour = chrono::Local::now().hour() as u8;
+    if config.quiet_start < config.quiet_end {
+        hour >= config.quiet_start && hour < config.quiet_end
+    } else {
+        hour >= config.quiet_start || hour < config.quiet_end
+    }
+}
+
+/// Send due reminders, scheduling the next occurrence of recurring ones
+///
+/// Reminders caught by their user's quiet hours are re-queued as one-off
+/// copies due when the window ends; recurrence continues on the original
+/// schedule.
+async fn deliver_due(
+    reminders: &RwLock<HashMap<String, Reminder>>,
+    quiet_hours: &RwLock<QuietHoursMap>,
+    urgent_types: &[NotificationType],
+    notification_tx: &mpsc::Sender<Reminder>,
+) -> usize {
+    let mut processed = 0;
+
+    // Collect due reminders
+    let due_ids: Vec<String> = {
+        let reminders = reminders.read().await;
+        reminders
+            .values()
+            .filter(|r| r.is_due())
+            .map(|r| r.id.clone())
+            .collect()
+    };
+
+    for id in due_ids {
+        let reminder = {
+            let mut reminders = reminders.write().await;
+            if let Some(reminder) = reminders.remove(&id) {
+                // Handle recurrence
+                if let Some(rule) = &reminder.recurring {
+                    if rule.has_more() {
+                        let mut next_rule = rule.clone();
+                        next_rule.occurrences += 1;
+                        let mut next = reminder.clone();
+                        next.id = uuid::Uuid::new_v4().to_string();
+                        next.due_at = rule.next_from(reminder.due_at);
+                        next.recurring = Some(next_rule);
+                        reminders.insert(next.id.clone(), next);
+                    }
+                }
+                Some(reminder)
+            } else {
+                None
+            }
+        };
+
+        let Some(reminder) = reminder else {
+            continue;
+        };
+        let held = {
+            let quiet_hours = quiet_hours.read().await;
+            quiet_until(&quiet_hours, urgent_types, &reminder, Utc::now())
+        };
+        if let Some(due_at) = held {
+            let held = held_copy(&reminder, due_at);
+            reminders.write().await.insert(held.id.clone(), held);
+            continue;
+        }
+        if notification_tx.send(reminder).await.is_ok() {
+            processed += 1;
+        } else {
+            warn!("Failed to send notification");
+        }
+    }
+
+    processed
+}
+
 /// Scheduler statistics
 #[derive(Debug, Clone)]
 pub struct SchedulerStats {
@@ -620,6 +911,25 @@ mod tests {
         assert!(reminder.next_occurrence().is_some());
     }
 
+    #[tokio::test]
+    async fn test_save_and_restore_reminders() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reminders.json");
+        let (scheduler, _rx) = Scheduler::new(10);
+        let due = chrono::Utc::now().timestamp() + 3600;
+        scheduler.schedule_reminder(Reminder::once(7, 7, "Review PR", due).recurring(RecurrenceRule::daily())).await;
+        scheduler.schedule_reminder(Reminder::once(8, 8, "Deploy", due)).await;
+        assert_eq!(scheduler.save_reminders(&path).await.unwrap(), 2);
+
+        let (restarted, _rx) = Scheduler::new(10);
+        assert_eq!(restarted.restore_reminders(&path).await.unwrap(), 2);
+        let restored = restarted.get_user_reminders(7).await;
+        assert_eq!(restored[0].message, "Review PR");
+        assert!(restored[0].recurring.is_some());
+        assert!(!path.exists());
+        assert_eq!(restarted.restore_reminders(&path).await.unwrap(), 0);
+    }
+
     #[tokio::test]
     async fn test_scheduler() {
         let (scheduler, mut rx) = Scheduler::new(10);
@@ -660,6 +970,94 @@ mod tests {
         assert_eq!(user1_reminders.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_notify_immediate() {
+        let (scheduler, mut rx) = Scheduler::new(10);
+
+        let digest = Reminder::once(1, 1, "Digest body", chrono::Utc::now().timestamp())
+            .with_type(NotificationType::Digest);
+        assert!(scheduler.notify(digest).await);
+
+        let received = rx.try_recv().unwrap();
+        assert_eq!(received.notification_type, NotificationType::Digest);
+        assert_eq!(scheduler.stats().await.pending_reminders, 0);
+    }
+
+    #[tokio::test]
+    async fn test_quiet_hours_hold_non_urgent() {
+        let (scheduler, mut rx) = Scheduler::new(10);
+        let offset = FixedOffset::east_opt(0).unwrap();
+
+        // 22:00-08:00 UTC: 23:30 is inside, ends at 08:00 the next morning
+        let quiet = QuietHours::parse("22:00-08:00 UTC").unwrap();
+        assert_eq!(quiet.offset, offset);
+        let late = DateTime::parse_from_rfc3339("2026-03-01T23:30:00Z").unwrap().with_timezone(&Utc);
+        let morning = DateTime::parse_from_rfc3339("2026-03-02T08:00:00Z").unwrap().timestamp();
+        assert_eq!(quiet.window_end(late), Some(morning));
+        let early = DateTime::parse_from_rfc3339("2026-03-02T03:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(quiet.window_end(early), Some(morning));
+        let noon = DateTime::parse_from_rfc3339("2026-03-02T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(quiet.window_end(noon), None);
+        assert!(QuietHours::parse("22:00-22:00").is_err());
+        assert!(QuietHours::parse("25:00-08:00").is_err());
+        assert_eq!(QuietHours::parse("09:00-17:00 +05:30").unwrap().offset.local_minus_utc(), 5 * 3600 + 1800);
+        assert_eq!(QuietHours::parse("09:00-17:00 UTC-5").unwrap().offset.local_minus_utc(), -5 * 3600);
+
+        // A window covering the whole day except one minute keeps the test time-independent
+        let now = Utc::now().with_timezone(&offset).time();
+        let always = QuietHours {
+            start: now - chrono::Duration::minutes(1),
+            end: now - chrono::Duration::minutes(2),
+            offset,
+        };
+        scheduler.set_quiet_hours(1, Some(always)).await.unwrap();
+
+        let digest = Reminder::once(1, 1, "Digest", Utc::now().timestamp()).with_type(NotificationType::Digest);
+        assert!(scheduler.notify(digest).await);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(scheduler.get_user_reminders(1).await.len(), 1);
+
+        let alert = Reminder::once(1, 1, "Spike", Utc::now().timestamp()).with_priority(Priority::Urgent);
+        assert!(scheduler.notify(alert).await);
+        let status = Reminder::once(1, 1, "Status", Utc::now().timestamp()).with_type(NotificationType::SystemStatus);
+        assert!(scheduler.notify(status).await);
+        assert_eq!(rx.try_recv().unwrap().message, "Spike");
+        assert_eq!(rx.try_recv().unwrap().message, "Status");
+
+        // Due reminders are held too; other users are unaffected
+        scheduler.schedule_reminder(Reminder::once(1, 1, "Quiet", Utc::now().timestamp() - 1)).await;
+        scheduler.schedule_reminder(Reminder::once(2, 2, "Loud", Utc::now().timestamp() - 1)).await;
+        assert_eq!(scheduler.process_due().await, 1);
+        assert_eq!(rx.try_recv().unwrap().message, "Loud");
+        assert_eq!(scheduler.get_user_reminders(1).await.len(), 2);
+
+        scheduler.set_quiet_hours(1, None).await.unwrap();
+        assert!(scheduler.quiet_hours(1).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quiet_hours_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.db");
+
+        let (scheduler, _rx) = Scheduler::new(10);
+        let scheduler = scheduler.with_quiet_hours_db(&path).unwrap();
+        let evening = QuietHours::parse("22:00-08:00 +02:00").unwrap();
+        scheduler.set_quiet_hours(1, Some(evening)).await.unwrap();
+        scheduler.set_quiet_hours(2, Some(QuietHours::parse("13:00-14:00 UTC").unwrap())).await.unwrap();
+        let night = QuietHours::parse("23:30-07:15 UTC-5").unwrap();
+        scheduler.set_quiet_hours(2, Some(night)).await.unwrap();
+        scheduler.set_quiet_hours(3, Some(evening)).await.unwrap();
+        scheduler.set_quiet_hours(3, None).await.unwrap();
+        drop(scheduler);
+
+        let (restarted, _rx) = Scheduler::new(10);
+        let restarted = restarted.with_quiet_hours_db(&path).unwrap();
+        assert_eq!(restarted.quiet_hours(1).await, Some(evening));
+        assert_eq!(restarted.quiet_hours(2).await, Some(night));
+        assert!(restarted.quiet_hours(3).await.is_none());
+    }
+
     #[test]
     fn test_priority_ordering() {
         use std::collections::BinaryHeap;
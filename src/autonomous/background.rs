@@ -5,20 +5,28 @@
 //! - Embedding backfill (generate missing embeddings)
 //! - Stale memory cleanup (remove old, unused memories)
 //! - Contradiction detection and resolution
+//! - Conversation retention (expire old messages, keeping summaries as memories)
+//! - HNSW index rebuild (reclaim tombstones left by deleted memories)
+//!
+//! Each cycle has a time budget (`cycle_budget`). Backfill and consolidation
+//! stop between items once it's spent and pick up where they left off on the
+//! next cycle, so a long run can't keep the bot busy when a message arrives.
 //!
 //! Industry standard: Event-driven background processing with graceful degradation
 
 use anyhow::Result;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tokio::time::interval;
 use tracing::{debug, info, warn};
 
+use crate::conversation::ConversationStore;
 use crate::embeddings::EmbeddingStore;
 use crate::llama_worker::LlamaWorker;
-use crate::memory::MemoryStore;
+use crate::memory::{IndexRebuild, MemoryStore};
 
 /// Configuration for background processing
 #[derive(Debug, Clone)]
@@ -41,6 +49,12 @@ pub struct BackgroundConfig {
     pub consolidation_similarity: f32,
     /// Enable background processing
     pub enabled: bool,
+    /// Conversation retention policy
+    pub retention: RetentionConfig,
+    /// Rebuild the HNSW index once this fraction of it is tombstones
+    pub index_rebuild_threshold: f64,
+    /// Time one idle cycle may spend before yielding (None = no limit)
+    pub cycle_budget: Option<Duration>,
 }
 
 impl Default for BackgroundConfig {
@@ -55,8 +69,157 @@ impl Default for BackgroundConfig {
             stale_min_access_count: 2,
             consolidation_similarity: 0.85,
             enabled: true,
+            retention: RetentionConfig::default(),
+            index_rebuild_threshold: 0.2,
+            cycle_budget: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl BackgroundConfig {
+    /// Create config from environment variables
+    ///
+    /// Reads the retention policy, `CLAUDEBOT_INDEX_REBUILD_THRESHOLD`
+    /// (0.0-1.0; 0 disables rebuilds), `CLAUDEBOT_CONSOLIDATION_BATCH_SIZE`,
+    /// `CLAUDEBOT_BACKFILL_BATCH_SIZE` and `CLAUDEBOT_BACKGROUND_CYCLE_SECS`
+    /// (0 = no limit).
+    pub fn from_env() -> Self {
+        let mut config = Self {
+            retention: RetentionConfig::from_env(),
+            ..Self::default()
+        };
+        if let Some(threshold) = std::env::var("CLAUDEBOT_INDEX_REBUILD_THRESHOLD")
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+        {
+            config.index_rebuild_threshold = threshold.clamp(0.0, 1.0);
+        }
+        let count = |name: &str| std::env::var(name).ok().and_then(|s| s.trim().parse::<usize>().ok());
+        if let Some(n) = count("CLAUDEBOT_CONSOLIDATION_BATCH_SIZE").filter(|n| *n > 0) {
+            config.consolidation_batch_size = n;
+        }
+        if let Some(n) = count("CLAUDEBOT_BACKFILL_BATCH_SIZE").filter(|n| *n > 0) {
+            config.backfill_batch_size = n;
+        }
+        if let Some(secs) = count("CLAUDEBOT_BACKGROUND_CYCLE_SECS") {
+            config.cycle_budget = (secs > 0).then(|| Duration::from_secs(secs as u64));
+        }
+        config
+    }
+}
+
+/// Whether a cycle's budget is spent
+pub fn out_of_time(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|d| Instant::now() >= d)
+}
+
+/// Conversation retention policy
+///
+/// Messages older than the retention window are deleted. With `compress`
+/// set, expired messages are first summarized into a memory; if the local
+/// model is unavailable, deletion is deferred to a later run so nothing is
+/// lost without a summary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetentionConfig {
+    /// How long to keep messages (None = keep forever)
+    pub max_age: Option<Duration>,
+    /// Per-chat retention (None = keep that chat forever)
+    pub chat_overrides: HashMap<i64, Option<Duration>>,
+    /// Summarize expired messages into memory before deleting them
+    pub compress: bool,
+    /// Interval between retention runs
+    pub interval: Duration,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            max_age: None,
+            chat_overrides: HashMap::new(),
+            compress: true,
+            interval: Duration::from_secs(3600), // 1 hour
+        }
+    }
+}
+
+impl RetentionConfig {
+    /// Create config from environment variables
+    ///
+    /// `CLAUDEBOT_RETENTION_CHATS` takes `chat_id:seconds` pairs separated
+    /// by commas, where 0 seconds keeps that chat forever.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(secs) = std::env::var("CLAUDEBOT_RETENTION_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+        {
+            config.max_age = (secs > 0).then_some(Duration::from_secs(secs));
+        }
+
+        if let Ok(value) = std::env::var("CLAUDEBOT_RETENTION_COMPRESS") {
+            config.compress = !matches!(value.to_lowercase().as_str(), "false" | "0" | "no" | "off");
+        }
+
+        if let Some(secs) = std::env::var("CLAUDEBOT_RETENTION_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+        {
+            config.interval = Duration::from_secs(secs.max(60));
+        }
+
+        if let Ok(value) = std::env::var("CLAUDEBOT_RETENTION_CHATS") {
+            for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let parsed = pair
+                    .split_once(':')
+                    .and_then(|(chat, secs)| Some((chat.trim().parse::<i64>().ok()?, secs.trim().parse::<u64>().ok()?)));
+                match parsed {
+                    Some((chat_id, secs)) => {
+                        config
+                            .chat_overrides
+                            .insert(chat_id, (secs > 0).then_some(Duration::from_secs(secs)));
+                    }
+                    None => warn!("Ignoring invalid CLAUDEBOT_RETENTION_CHATS entry: {}", pair),
+                }
+            }
+        }
+
+        config
+    }
+
+    /// Effective retention for a chat (override, then global)
+    pub fn for_chat(&self, chat_id: i64) -> Option<Duration> {
+        match self.chat_overrides.get(&chat_id) {
+            Some(retention) => *retention,
+            None => self.max_age,
         }
     }
+
+    /// Shortest retention across the global policy and all overrides
+    ///
+    /// Used to fetch candidate chats in one query before applying each
+    /// chat's own window. None if every chat is kept forever.
+    pub fn shortest(&self) -> Option<Duration> {
+        self.chat_overrides
+            .values()
+            .flatten()
+            .chain(self.max_age.iter())
+            .min()
+            .copied()
+    }
+}
+
+/// Outcome of a conversation retention run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetentionReport {
+    /// Chats that had messages deleted
+    pub chats_expired: usize,
+    /// Messages deleted
+    pub messages_deleted: usize,
+    /// Summaries saved as memories
+    pub summaries_saved: usize,
+    /// Chats skipped because their summary couldn't be generated
+    pub chats_deferred: usize,
 }
 
 /// Background task types
@@ -66,6 +229,8 @@ pub enum BackgroundTask {
     EmbeddingBackfill,
     StaleCleanup,
     ContradictionCheck,
+    ConversationRetention,
+    IndexRebuild,
 }
 
 impl BackgroundTask {
@@ -75,10 +240,55 @@ impl BackgroundTask {
             BackgroundTask::EmbeddingBackfill => "embedding_backfill",
             BackgroundTask::StaleCleanup => "stale_cleanup",
             BackgroundTask::ContradictionCheck => "contradiction_check",
+            BackgroundTask::ConversationRetention => "conversation_retention",
+            BackgroundTask::IndexRebuild => "index_rebuild",
         }
     }
 }
 
+/// Progress of a task's current (or last) pass, which may span cycles
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskProgress {
+    /// Items handled in this pass
+    pub done: usize,
+    /// Items left for later cycles
+    pub remaining: usize,
+    /// Cycles this pass has taken
+    pub cycles: u32,
+    /// The last cycle ran out of budget; the pass resumes next cycle
+    pub yielded: bool,
+    /// Unix timestamp of the last cycle
+    pub updated_at: i64,
+}
+
+impl TaskProgress {
+    pub fn format(&self, task: BackgroundTask) -> String {
+        if self.yielded {
+            format!(
+                "{}: {} done, {} left after {} cycle(s), resuming next cycle",
+                task.as_str(), self.done, self.remaining, self.cycles
+            )
+        } else {
+            format!("{}: last pass {} done in {} cycle(s)", task.as_str(), self.done, self.cycles)
+        }
+    }
+}
+
+/// What one cycle of a task got through
+#[derive(Debug, Clone, Copy, Default)]
+struct TaskRun {
+    count: usize,
+    remaining: usize,
+    yielded: bool,
+}
+
+/// Two similar memories waiting to be merged
+#[derive(Debug, Clone)]
+struct ConsolidationPair {
+    category: String,
+    contents: [String; 2],
+}
+
 /// Statistics for background processing
 #[derive(Debug, Default)]
 pub struct BackgroundStats {
@@ -89,6 +299,9 @@ pub struct BackgroundStats {
     pub cleanups_run: AtomicU64,
     pub memories_removed: AtomicU64,
     pub contradictions_found: AtomicU64,
+    pub retention_runs: AtomicU64,
+    pub conversation_messages_expired: AtomicU64,
+    pub index_rebuilds: AtomicU64,
 }
 
 /// Background processor for maintenance tasks
@@ -98,6 +311,16 @@ pub struct BackgroundProcessor {
     running: AtomicBool,
     /// Last run timestamps for each task
     last_runs: Arc<RwLock<std::collections::HashMap<BackgroundTask, i64>>>,
+    /// Retention policy, including runtime per-chat overrides
+    retention: std::sync::RwLock<RetentionConfig>,
+    /// Most recent retention run (unix timestamp, report)
+    last_retention: std::sync::Mutex<Option<(i64, RetentionReport)>>,
+    /// Most recent HNSW rebuild (unix timestamp, report)
+    last_index_rebuild: std::sync::Mutex<Option<(i64, IndexRebuild)>>,
+    /// Pairs left over from a consolidation pass that ran out of budget
+    consolidation_queue: std::sync::Mutex<VecDeque<ConsolidationPair>>,
+    /// Per-task progress across cycles
+    progress: std::sync::Mutex<HashMap<BackgroundTask, TaskProgress>>,
 }
 
 impl BackgroundProcessor {
@@ -109,6 +332,11 @@ impl BackgroundProcessor {
     /// Create with custom config
     pub fn with_config(config: BackgroundConfig) -> Self {
         Self {
+            retention: std::sync::RwLock::new(config.retention.clone()),
+            last_retention: std::sync::Mutex::new(None),
+            last_index_rebuild: std::sync::Mutex::new(None),
+            consolidation_queue: std::sync::Mutex::new(VecDeque::new()),
+            progress: std::sync::Mutex::new(HashMap::new()),
             config,
             stats: Arc::new(BackgroundStats::default()),
             running: AtomicBool::new(false),
@@ -121,12 +349,42 @@ impl BackgroundProcessor {
         self.running.load(Ordering::SeqCst)
     }
 
+    /// When a cycle starting now has to yield
+    pub fn cycle_deadline(&self) -> Option<Instant> {
+        self.config.cycle_budget.map(|budget| Instant::now() + budget)
+    }
+
+    /// Progress of each task that has run, in task order
+    pub fn progress(&self) -> Vec<(BackgroundTask, TaskProgress)> {
+        let progress = self.progress.lock().unwrap_or_else(|e| e.into_inner());
+        let mut tasks: Vec<_> = progress.iter().map(|(task, p)| (*task, p.clone())).collect();
+        tasks.sort_by_key(|(task, _)| *task as u8);
+        tasks
+    }
+
+    /// Fold a cycle into the task's progress; a pass that didn't yield last
+    /// time means this cycle starts a new one
+    fn record_progress(&self, task: BackgroundTask, run: TaskRun) {
+        let mut progress = self.progress.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = progress.entry(task).or_default();
+        if !entry.yielded {
+            *entry = TaskProgress::default();
+        }
+        entry.done += run.count;
+        entry.remaining = run.remaining;
+        entry.cycles += 1;
+        entry.yielded = run.yielded;
+        entry.updated_at = chrono::Utc::now().timestamp();
+    }
+
     /// Run a single iteration of background tasks
     ///
-    /// Call this during idle periods (e.g., from lifecycle manager)
+    /// Call this during idle periods (e.g., from lifecycle manager). Stops
+    /// after `cycle_budget`; a task that yielded stays due, so it resumes on
+    /// the next call.
     pub async fn run_once(
         &self,
-        memory: &std::sync::Mutex<MemoryStore>,
+        memory: &MemoryStore,
         llama: &LlamaWorker,
     ) -> Result<Vec<(BackgroundTask, usize)>> {
         if !self.config.enabled {
@@ -137,6 +395,7 @@ impl BackgroundProcessor {
         let mut results = Vec::new();
 
         let now = chrono::Utc::now().timestamp();
+        let deadline = self.cycle_deadline();
 
         // Check which tasks are due
         let mut last_runs = self.last_runs.write().await;
@@ -144,21 +403,33 @@ impl BackgroundProcessor {
         // Embedding backfill (most frequent)
         let last_backfill = last_runs.get(&BackgroundTask::EmbeddingBackfill).copied().unwrap_or(0);
         if now - last_backfill >= self.config.backfill_interval.as_secs() as i64 {
-            let count = self.run_embedding_backfill(memory).await?;
-            results.push((BackgroundTask::EmbeddingBackfill, count));
-            last_runs.insert(BackgroundTask::EmbeddingBackfill, now);
+            let run = self.run_embedding_backfill(memory, deadline).await?;
+            results.push((BackgroundTask::EmbeddingBackfill, run.count));
+            self.record_progress(BackgroundTask::EmbeddingBackfill, run);
+            if !run.yielded {
+                last_runs.insert(BackgroundTask::EmbeddingBackfill, now);
+            }
             self.stats.backfills_run.fetch_add(1, Ordering::Relaxed);
-            self.stats.embeddings_generated.fetch_add(count as u64, Ordering::Relaxed);
+            self.stats.embeddings_generated.fetch_add(run.count as u64, Ordering::Relaxed);
         }
 
         // Memory consolidation
         let last_consolidation = last_runs.get(&BackgroundTask::Consolidation).copied().unwrap_or(0);
         if now - last_consolidation >= self.config.consolidation_interval.as_secs() as i64 {
-            let count = self.run_consolidation(memory, llama).await?;
-            results.push((BackgroundTask::Consolidation, count));
-            last_runs.insert(BackgroundTask::Consolidation, now);
-            self.stats.consolidations_run.fetch_add(1, Ordering::Relaxed);
-            self.stats.memories_consolidated.fetch_add(count as u64, Ordering::Relaxed);
+            if out_of_time(deadline) {
+                debug!("Background cycle budget spent, consolidation waits for the next cycle");
+            } else {
+                let run = self.run_consolidation(memory, llama, deadline).await?;
+                results.push((BackgroundTask::Consolidation, run.count));
+                self.record_progress(BackgroundTask::Consolidation, run);
+                if run.yielded {
+                    info!("Consolidation yielded after its cycle budget, {} pairs left", run.remaining);
+                } else {
+                    last_runs.insert(BackgroundTask::Consolidation, now);
+                }
+                self.stats.consolidations_run.fetch_add(1, Ordering::Relaxed);
+                self.stats.memories_consolidated.fetch_add(run.count as u64, Ordering::Relaxed);
+            }
         }
 
         // Stale cleanup (least frequent)
@@ -171,14 +442,186 @@ impl BackgroundProcessor {
             self.stats.memories_removed.fetch_add(count as u64, Ordering::Relaxed);
         }
 
+        // Index rebuild: cheap to check, so every run (after cleanup adds tombstones)
+        if let Some(rebuild) = self.run_index_rebuild_if_needed(memory)? {
+            results.push((BackgroundTask::IndexRebuild, rebuild.reclaimed));
+            last_runs.insert(BackgroundTask::IndexRebuild, now);
+        }
+
         self.running.store(false, Ordering::SeqCst);
         Ok(results)
     }
 
+    /// Current retention policy
+    pub fn retention_config(&self) -> RetentionConfig {
+        self.retention.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Set a chat's retention (None = keep forever)
+    pub fn set_chat_retention(&self, chat_id: i64, retention: Option<Duration>) {
+        let mut config = self.retention.write().unwrap_or_else(|e| e.into_inner());
+        config.chat_overrides.insert(chat_id, retention);
+    }
+
+    /// Remove a chat's retention override. Returns true if one existed.
+    pub fn clear_chat_retention(&self, chat_id: i64) -> bool {
+        let mut config = self.retention.write().unwrap_or_else(|e| e.into_inner());
+        config.chat_overrides.remove(&chat_id).is_some()
+    }
+
+    /// Most recent HNSW rebuild (unix timestamp, report)
+    pub fn last_index_rebuild(&self) -> Option<(i64, IndexRebuild)> {
+        self.last_index_rebuild.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Rebuild threshold as a tombstone fraction (0 = never)
+    pub fn index_rebuild_threshold(&self) -> f64 {
+        self.config.index_rebuild_threshold
+    }
+
+    /// Rebuild the HNSW index if its tombstone ratio exceeds the threshold
+    fn run_index_rebuild_if_needed(&self, memory: &MemoryStore) -> Result<Option<IndexRebuild>> {
+        let threshold = self.config.index_rebuild_threshold;
+        let health = memory.index_health();
+        if threshold <= 0.0 || health.tombstones == 0 || health.tombstone_ratio() < threshold {
+            return Ok(None);
+        }
+
+        let rebuild = memory.rebuild_hnsw_index()?;
+        info!(
+            "Rebuilt HNSW index: reclaimed {} tombstones ({:.0}%), {} vectors in {}ms",
+            rebuild.reclaimed,
+            health.tombstone_ratio() * 100.0,
+            rebuild.indexed,
+            rebuild.duration_ms
+        );
+        self.stats.index_rebuilds.fetch_add(1, Ordering::Relaxed);
+        *self.last_index_rebuild.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((chrono::Utc::now().timestamp(), rebuild.clone()));
+        Ok(Some(rebuild))
+    }
+
+    /// Most recent retention run (unix timestamp, report)
+    pub fn last_retention(&self) -> Option<(i64, RetentionReport)> {
+        self.last_retention.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Run conversation retention if its interval has elapsed
+    pub async fn run_retention_if_due(
+        &self,
+        conversations: &ConversationStore,
+        memory: &MemoryStore,
+        llama: &LlamaWorker,
+    ) -> Result<Option<RetentionReport>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        let interval = self.retention_config().interval;
+
+        let now = chrono::Utc::now().timestamp();
+        {
+            let mut last_runs = self.last_runs.write().await;
+            let last = last_runs.get(&BackgroundTask::ConversationRetention).copied().unwrap_or(0);
+            if now - last < interval.as_secs() as i64 {
+                return Ok(None);
+            }
+            last_runs.insert(BackgroundTask::ConversationRetention, now);
+        }
+
+        self.run_retention(conversations, memory, llama).await.map(Some)
+    }
+
+    /// Expire conversation messages past their retention window
+    ///
+    /// Expired messages are summarized into a `conversation_summary` memory
+    /// first when the policy asks for it.
+    pub async fn run_retention(
+        &self,
+        conversations: &ConversationStore,
+        memory: &MemoryStore,
+        llama: &LlamaWorker,
+    ) -> Result<RetentionReport> {
+        let policy = self.retention_config();
+        let mut report = RetentionReport::default();
+
+        if let Some(shortest) = policy.shortest() {
+            let candidates = conversations.chats_with_messages_older_than(shortest.as_secs() as i64)?;
+
+            let mut llama_available = None;
+            for chat_id in candidates {
+                let Some(retention) = policy.for_chat(chat_id) else {
+                    continue;
+                };
+                // One cutoff for reading and deleting: messages crossing it while
+                // the summary runs wait for the next run instead of going unsummarized
+                let cutoff = ConversationStore::cutoff_millis(retention.as_secs() as i64);
+
+                let expired = conversations.get_messages_before(chat_id, cutoff)?;
+                if expired.is_empty() {
+                    continue;
+                }
+
+                // Even a few messages are summarized: deleting them otherwise loses them
+                if policy.compress {
+                    if llama_available.is_none() {
+                        llama_available = Some(llama.is_available().await);
+                    }
+                    if llama_available != Some(true) {
+                        report.chats_deferred += 1;
+                        continue;
+                    }
+
+                    let context: Vec<(&str, &str)> = expired
+                        .iter()
+                        .map(|m| (m.role.as_str(), m.content.as_str()))
+                        .collect();
+                    match llama.compress_context(&context, 0.3).await {
+                        Ok(summary) => {
+                            memory.learn(
+                                &format!("Conversation summary: {}", summary),
+                                "conversation_summary",
+                                &format!("chat_{}", chat_id),
+                                Some(chat_id),
+                                0.8,
+                            )?;
+                            report.summaries_saved += 1;
+                        }
+                        Err(e) => {
+                            debug!("Retention summary failed for chat {}: {}", chat_id, e);
+                            report.chats_deferred += 1;
+                            continue;
+                        }
+                    }
+                }
+
+                let deleted = conversations.expire_chat_before(chat_id, cutoff)?;
+                if deleted > 0 {
+                    report.chats_expired += 1;
+                    report.messages_deleted += deleted;
+                }
+            }
+        }
+
+        if report.messages_deleted > 0 || report.chats_deferred > 0 {
+            info!(
+                "Conversation retention: deleted {} messages from {} chats ({} summaries, {} deferred)",
+                report.messages_deleted, report.chats_expired, report.summaries_saved, report.chats_deferred
+            );
+        }
+        self.stats.retention_runs.fetch_add(1, Ordering::Relaxed);
+        self.stats
+            .conversation_messages_expired
+            .fetch_add(report.messages_deleted as u64, Ordering::Relaxed);
+        *self.last_retention.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((chrono::Utc::now().timestamp(), report.clone()));
+
+        Ok(report)
+    }
+
     /// Start continuous background processing loop
     pub async fn run_continuous(
         self: Arc<Self>,
-        memory: Arc<std::sync::Mutex<MemoryStore>>,
+        memory: Arc<MemoryStore>,
         llama: Arc<LlamaWorker>,
         shutdown: tokio::sync::watch::Receiver<bool>,
     ) {
@@ -209,33 +652,42 @@ impl BackgroundProcessor {
     }
 
     /// Run embedding backfill for memories without embeddings
+    ///
+    /// A batch cut short by the deadline leaves the checkpoint alone; the
+    /// memories it didn't reach still have no embedding, so the next batch
+    /// picks them up.
     async fn run_embedding_backfill(
         &self,
-        memory: &std::sync::Mutex<MemoryStore>,
-    ) -> Result<usize> {
+        memory: &MemoryStore,
+        deadline: Option<Instant>,
+    ) -> Result<TaskRun> {
         // Get embedder and memories needing backfill
-        let (embedder, memories) = {
-            let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-            if !store.has_embeddings() {
-                return Ok(0);
+        let (embedder, batch) = {
+            if !memory.has_embeddings() {
+                return Ok(TaskRun::default());
             }
-            let embedder = match store.get_embedder() {
+            let embedder = match memory.get_embedder() {
                 Some(e) => e,
-                None => return Ok(0),
+                None => return Ok(TaskRun::default()),
             };
-            let memories = store.get_memories_needing_embeddings(self.config.backfill_batch_size)?;
-            (embedder, memories)
+            let batch = memory.next_backfill_batch(self.config.backfill_batch_size)?;
+            (embedder, batch)
         };
 
-        if memories.is_empty() {
-            return Ok(0);
+        if batch.memories.is_empty() {
+            return Ok(TaskRun::default());
         }
 
-        debug!("Backfilling {} embeddings", memories.len());
+        debug!("Backfilling {} embeddings", batch.memories.len());
 
         // Generate embeddings (async, outside lock)
         let mut embeddings: Vec<(String, Vec<f32>)> = Vec::new();
-        for (id, content) in &memories {
+        let mut handled = 0;
+        for (id, content) in &batch.memories {
+            if handled > 0 && out_of_time(deadline) {
+                break;
+            }
+            handled += 1;
             match embedder.read().await.embed(content).await {
                 Ok(embedding) => {
                     embeddings.push((id.clone(), embedding));
@@ -246,105 +698,107 @@ impl BackgroundProcessor {
             }
         }
 
-        // Store embeddings (quick lock)
-        let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        // Store embeddings
         let mut count = 0;
         for (id, embedding) in &embeddings {
-            if store.store_embedding(id, embedding).is_ok() {
+            if memory.store_embedding(id, embedding).is_ok() {
                 count += 1;
             }
         }
+        let remaining = batch.memories.len() - handled;
+        if remaining == 0 {
+            memory.save_backfill_checkpoint(batch.checkpoint)?;
+        }
 
         if count > 0 {
             info!("Background backfilled {} embeddings", count);
         }
 
-        Ok(count)
+        Ok(TaskRun { count, remaining, yielded: remaining > 0 })
     }
 
     /// Run memory consolidation to merge similar memories
+    ///
+    /// A pass finds the similar pairs among recent memories, then merges
+    /// them one at a time. Pairs not reached before the deadline are kept
+    /// for the next cycle instead of being searched for again.
     async fn run_consolidation(
         &self,
-        memory: &std::sync::Mutex<MemoryStore>,
+        memory: &MemoryStore,
         llama: &LlamaWorker,
-    ) -> Result<usize> {
+        deadline: Option<Instant>,
+    ) -> Result<TaskRun> {
         if !llama.is_available().await {
-            return Ok(0);
+            return Ok(TaskRun::default());
         }
 
-        // Get candidates for consolidation (recent, similar category)
-        let candidates = {
-            let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-            store.get_recent(self.config.consolidation_batch_size)?
-        };
+        let mut queue = std::mem::take(&mut *self.consolidation_queue.lock().unwrap_or_else(|e| e.into_inner()));
+        if queue.is_empty() {
+            queue = self.find_consolidation_pairs(memory)?;
+        }
+
+        let mut run = TaskRun::default();
+        let mut handled = 0;
+        while let Some(pair) = queue.front() {
+            if handled > 0 && out_of_time(deadline) {
+                run.yielded = true;
+                break;
+            }
+            handled += 1;
+            let contents = [pair.contents[0].as_str(), pair.contents[1].as_str()];
+            if let Ok(summary) = llama.summarize_memories(&contents).await {
+                // We could delete originals here, but for safety we keep them
+                let _ = memory.learn(&summary, &pair.category, "consolidation", None, 0.9);
+                run.count += 1;
+            }
+            queue.pop_front();
+        }
+        run.remaining = queue.len();
+        *self.consolidation_queue.lock().unwrap_or_else(|e| e.into_inner()) = queue;
 
-        if candidates.len() < 2 {
-            return Ok(0);
+        if run.count > 0 {
+            info!("Consolidated {} memory pairs", run.count);
         }
 
+        Ok(run)
+    }
+
+    /// Similar pairs within each category among the most recent memories
+    fn find_consolidation_pairs(&self, memory: &MemoryStore) -> Result<VecDeque<ConsolidationPair>> {
+        // Get candidates for consolidation (recent, similar category)
+        let candidates = memory.get_recent(self.config.consolidation_batch_size)?;
+
         // Group by category
-        let mut by_category: std::collections::HashMap<String, Vec<_>> = std::collections::HashMap::new();
+        let mut by_category: HashMap<String, Vec<_>> = HashMap::new();
         for mem in candidates {
             by_category.entry(mem.category.clone()).or_default().push(mem);
         }
 
-        let mut consolidated = 0;
-
-        for (_category, memories) in by_category {
-            if memories.len() < 2 {
-                continue;
-            }
-
+        let mut pairs = VecDeque::new();
+        for (category, memories) in by_category {
             // Find similar pairs using embeddings
             for i in 0..memories.len() {
                 for j in (i + 1)..memories.len() {
-                    if let (Some(ref emb_i), Some(ref emb_j)) =
-                        (&memories[i].embedding, &memories[j].embedding)
-                    {
-                        let similarity = EmbeddingStore::cosine_similarity(emb_i, emb_j);
-                        if similarity >= self.config.consolidation_similarity {
-                            // Consolidate these memories
-                            let contents: Vec<&str> = vec![
-                                &memories[i].content,
-                                &memories[j].content,
-                            ];
-
-                            if let Ok(summary) = llama.summarize_memories(&contents).await {
-                                // Store consolidated and mark old ones
-                                let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-
-                                // Add consolidated memory
-                                let _ = store.learn(
-                                    &summary,
-                                    &memories[i].category,
-                                    "consolidation",
-                                    0.9,
-                                );
-
-                                // We could delete originals here, but for safety we keep them
-                                consolidated += 1;
-                            }
+                    if let (Some(emb_i), Some(emb_j)) = (&memories[i].embedding, &memories[j].embedding) {
+                        if EmbeddingStore::cosine_similarity(emb_i, emb_j) >= self.config.consolidation_similarity {
+                            pairs.push_back(ConsolidationPair {
+                                category: category.clone(),
+                                contents: [memories[i].content.clone(), memories[j].content.clone()],
+                            });
                         }
                     }
                 }
             }
         }
-
-        if consolidated > 0 {
-            info!("Consolidated {} memory pairs", consolidated);
-        }
-
-        Ok(consolidated)
+        Ok(pairs)
     }
 
     /// Remove stale, unused memories
-    fn run_stale_cleanup(&self, memory: &std::sync::Mutex<MemoryStore>) -> Result<usize> {
-        let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-
+    fn run_stale_cleanup(&self, memory: &MemoryStore) -> Result<usize> {
         let cutoff = chrono::Utc::now().timestamp() - (self.config.stale_age_days * 86400);
 
         // Get memories older than cutoff with low access count
-        let stale = store.get_recent(1000)?
+        let stale = memory.get_recent(1000)?
             .into_iter()
             .filter(|m| {
                 m.created_at < cutoff && m.access_count < self.config.stale_min_access_count
@@ -353,7 +807,7 @@ impl BackgroundProcessor {
 
         let mut removed = 0;
         for mem in stale {
-            if store.forget(&mem.id).is_ok() {
+            if memory.forget(&mem.id).is_ok() {
                 removed += 1;
             }
         }
@@ -389,9 +843,127 @@ mod tests {
         assert_eq!(config.backfill_batch_size, 50);
     }
 
+    #[test]
+    fn test_consolidation_pairs_and_progress() {
+        let mem_path = std::path::PathBuf::from("/tmp/claudebot_test_consolidation_pairs.db");
+        let _ = std::fs::remove_file(&mem_path);
+        let store = MemoryStore::open(&mem_path).unwrap();
+        for (content, category, emb) in [
+            ("Deploys go to ar-2", "fact", [1.0f32, 0.0, 0.0]),
+            ("The deploy host is ar-2", "fact", [0.99, 0.05, 0.0]),
+            ("Prefers short answers", "preference", [0.98, 0.1, 0.0]),
+            ("Uses vim", "fact", [0.0, 0.0, 1.0]),
+        ] {
+            let id = store.learn(content, category, "test", None, 0.9).unwrap();
+            store.store_embedding(&id, &emb).unwrap();
+        }
+        let processor = BackgroundProcessor::new();
+
+        // Only the same-category near-duplicates pair up
+        let pairs = processor.find_consolidation_pairs(&store).unwrap();
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].category, "fact");
+
+        // A yielded pass accumulates across cycles; the next one starts fresh
+        processor.record_progress(BackgroundTask::Consolidation, TaskRun { count: 4, remaining: 6, yielded: true });
+        processor.record_progress(BackgroundTask::Consolidation, TaskRun { count: 6, remaining: 0, yielded: false });
+        let (task, progress) = processor.progress().pop().unwrap();
+        assert_eq!(task, BackgroundTask::Consolidation);
+        assert_eq!((progress.done, progress.remaining, progress.cycles, progress.yielded), (10, 0, 2, false));
+        assert!(progress.format(task).contains("last pass 10 done in 2 cycle(s)"));
+        processor.record_progress(BackgroundTask::Consolidation, TaskRun { count: 1, remaining: 2, yielded: true });
+        assert_eq!(processor.progress()[0].1.cycles, 1);
+
+        assert!(!out_of_time(None));
+        assert!(out_of_time(Some(Instant::now())));
+    }
+
     #[test]
     fn test_task_names() {
         assert_eq!(BackgroundTask::Consolidation.as_str(), "consolidation");
         assert_eq!(BackgroundTask::EmbeddingBackfill.as_str(), "embedding_backfill");
     }
+
+    #[test]
+    fn test_retention_overrides() {
+        let day = Duration::from_secs(86400);
+        let mut config = RetentionConfig {
+            max_age: Some(day * 30),
+            ..RetentionConfig::default()
+        };
+        config.chat_overrides.insert(1, Some(day));
+        config.chat_overrides.insert(2, None);
+
+        assert_eq!(config.for_chat(1), Some(day));
+        assert_eq!(config.for_chat(2), None);
+        assert_eq!(config.for_chat(3), Some(day * 30));
+        assert_eq!(config.shortest(), Some(day));
+
+        assert_eq!(RetentionConfig::default().shortest(), None);
+    }
+
+    #[tokio::test]
+    async fn test_retention_deletes_expired_messages() {
+        let conv_path = std::path::PathBuf::from("/tmp/claudebot_test_retention_conv.db");
+        let mem_path = std::path::PathBuf::from("/tmp/claudebot_test_retention_mem.db");
+        let _ = std::fs::remove_file(&conv_path);
+        let _ = std::fs::remove_file(&mem_path);
+
+        let conversations = ConversationStore::open(&conv_path).unwrap();
+        conversations.add_exchange(5, "Hello", "Hi there").unwrap();
+        conversations.add_exchange(6, "Keep me", "Kept").unwrap();
+        let memory = MemoryStore::open(&mem_path).unwrap();
+
+        let processor = BackgroundProcessor::with_config(BackgroundConfig {
+            retention: RetentionConfig {
+                max_age: Some(Duration::ZERO),
+                compress: false,
+                ..RetentionConfig::default()
+            },
+            ..BackgroundConfig::default()
+        });
+        processor.set_chat_retention(6, None);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let llama = LlamaWorker::new();
+        let report = processor.run_retention(&conversations, &memory, &llama).await.unwrap();
+        assert_eq!(report.messages_deleted, 2);
+        assert_eq!(report.chats_expired, 1);
+        assert_eq!(processor.last_retention().unwrap().1, report);
+
+        assert!(conversations.get_history(5, 10).unwrap().is_empty());
+        assert_eq!(conversations.get_history(6, 10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_index_rebuild_threshold() {
+        let mem_path = std::path::PathBuf::from("/tmp/claudebot_test_index_rebuild.db");
+        let _ = std::fs::remove_file(&mem_path);
+        let store = MemoryStore::open(&mem_path).unwrap();
+        let ids: Vec<String> = (0..5)
+            .map(|i| {
+                let id = store.learn(&format!("memory {}", i), "fact", "test", None, 0.9).unwrap();
+                let mut emb = vec![0.01f32; 8];
+                emb[i] = 1.0;
+                store.store_embedding(&id, &emb).unwrap();
+                id
+            })
+            .collect();
+        let processor = BackgroundProcessor::with_config(BackgroundConfig {
+            index_rebuild_threshold: 0.3,
+            ..BackgroundConfig::default()
+        });
+
+        // 1 of 5 tombstoned: below the threshold
+        store.forget(&ids[0]).unwrap();
+        assert!(processor.run_index_rebuild_if_needed(&store).unwrap().is_none());
+
+        // 2 of 5: rebuilt
+        store.forget(&ids[1]).unwrap();
+        let rebuild = processor.run_index_rebuild_if_needed(&store).unwrap().unwrap();
+        assert_eq!((rebuild.reclaimed, rebuild.indexed), (2, 3));
+        assert_eq!(store.index_health().tombstones, 0);
+        assert_eq!(processor.stats().index_rebuilds.load(Ordering::Relaxed), 1);
+        assert!(processor.last_index_rebuild().is_some());
+    }
 }
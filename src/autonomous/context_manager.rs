@@ -2,29 +2,87 @@
 //!
 //! Automatically enriches prompts with relevant context from:
 //! - Memory store (semantic facts)
-//! - Knowledge graph (entities and relations)
+//! - Knowledge graph (entities and relations, and with the `graph` strategy
+//!   the facts one relation away from the retrieved memories)
 //! - Conversation history (recent messages)
 //! - Active goals (ongoing tasks)
 //!
 //! Industry standard: Retrieval-Augmented Generation (RAG) with multi-source fusion
 
-use std::collections::HashSet;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tracing::{debug, warn};
 
-use crate::conversation::ConversationStore;
+use crate::conversation::{ConversationMessage, ConversationStore};
 use crate::graph::GraphStore;
 use crate::llama_worker::LlamaWorker;
-use crate::memory::{MemoryStore, ScoredMemory};
+use crate::memory::{MemoryEntry, MemoryScope, MemoryScopeMode, MemoryStore, ScoredMemory};
+use crate::tokenizer::TokenCounter;
 
 use super::goals::{Goal, GoalTracker};
 
+/// How much retrieval runs before a prompt goes to Claude
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextStrategy {
+    /// HyDE for questions, hybrid (keyword + vector) memory search, graph,
+    /// goals and identity
+    #[default]
+    Full,
+    /// Keyword-only memory search: no HyDE call and no query embedding
+    Fast,
+    /// Full, plus facts linked to entities related to the ones the retrieved
+    /// memories mention (one hop), so connected knowledge surfaces even when
+    /// it doesn't match the query text
+    Graph,
+    /// No context: the bare prompt
+    None,
+}
+
+impl ContextStrategy {
+    pub const ALL: [ContextStrategy; 4] = [Self::Full, Self::Fast, Self::Graph, Self::None];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Fast => "fast",
+            Self::Graph => "graph",
+            Self::None => "none",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "full" => Some(Self::Full),
+            "fast" | "keyword" => Some(Self::Fast),
+            "graph" => Some(Self::Graph),
+            "none" | "off" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Full => "HyDE + hybrid memory search, graph, goals, identity",
+            Self::Fast => "keyword memory search only, no extra Llama or embedding calls",
+            Self::Graph => "full, plus facts one graph relation away from the memories found",
+            Self::None => "bare prompt, no context",
+        }
+    }
+}
+
 /// Configuration for context enrichment
 #[derive(Debug, Clone)]
 pub struct ContextConfig {
     /// Maximum memories to include
     pub max_memories: usize,
-    /// Maximum conversation turns to include
-    pub max_conversation_turns: usize,
+    /// Recent conversation messages to include (per-chat overridable)
+    pub context_window_messages: usize,
+    /// Token ceiling for included conversation history; the oldest
+    /// messages in the window are dropped first
+    pub max_conversation_tokens: usize,
     /// Maximum entities from graph to include
     pub max_entities: usize,
     /// Include active goals in context
@@ -35,22 +93,122 @@ pub struct ContextConfig {
     pub min_relevance: f64,
     /// Include user identity context
     pub include_identity: bool,
+    /// Whether users see each other's memories
+    pub memory_scope: MemoryScopeMode,
+    /// Token ceiling for the whole context block (0 = unlimited); lower
+    /// value items are dropped to fit
+    pub max_context_tokens: usize,
+    /// Tell the user when context was trimmed to fit
+    pub notify_trimmed: bool,
+    /// Retrieval strategy for users without their own (`/context strategy`)
+    pub strategy: ContextStrategy,
+    /// Most connected facts the `graph` strategy adds
+    pub max_graph_facts: usize,
+    /// Token ceiling for the connected facts, within `max_context_tokens`
+    pub max_graph_tokens: usize,
 }
 
 impl Default for ContextConfig {
     fn default() -> Self {
         Self {
             max_memories: 5,
-            max_conversation_turns: 5,
+            context_window_messages: 10,
+            max_conversation_tokens: 4000,
             max_entities: 3,
             include_goals: true,
             use_hyde: true,
             min_relevance: 0.1,
             include_identity: true,
+            memory_scope: MemoryScopeMode::default(),
+            max_context_tokens: 8000,
+            notify_trimmed: false,
+            strategy: ContextStrategy::Full,
+            max_graph_facts: 3,
+            max_graph_tokens: 500,
         }
     }
 }
 
+/// Upper bound for per-chat context window overrides
+pub const MAX_CONTEXT_WINDOW_MESSAGES: usize = 200;
+
+impl ContextConfig {
+    /// Load from environment, falling back to defaults
+    ///
+    /// `CLAUDEBOT_CONTEXT_WINDOW_MESSAGES`, `CLAUDEBOT_CONTEXT_MAX_TOKENS`,
+    /// `CLAUDEBOT_CONTEXT_TOTAL_TOKENS`, `CLAUDEBOT_CONTEXT_TRIM_NOTICE`,
+    /// `CLAUDEBOT_CONTEXT_STRATEGY`, `CLAUDEBOT_CONTEXT_GRAPH_FACTS`,
+    /// `CLAUDEBOT_CONTEXT_GRAPH_TOKENS` and `CLAUDEBOT_MEMORY_SCOPE`.
+    pub fn from_env() -> Self {
+        let mut config = Self {
+            memory_scope: MemoryScopeMode::from_env(),
+            ..Self::default()
+        };
+        if let Some(n) = std::env::var("CLAUDEBOT_CONTEXT_WINDOW_MESSAGES")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+        {
+            config.context_window_messages = n.min(MAX_CONTEXT_WINDOW_MESSAGES);
+        }
+        if let Some(n) = std::env::var("CLAUDEBOT_CONTEXT_MAX_TOKENS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.max_conversation_tokens = n;
+        }
+        if let Some(n) = std::env::var("CLAUDEBOT_CONTEXT_TOTAL_TOKENS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.max_context_tokens = n;
+        }
+        if let Ok(v) = std::env::var("CLAUDEBOT_CONTEXT_TRIM_NOTICE") {
+            config.notify_trimmed = v == "true" || v == "1";
+        }
+        if let Some(strategy) = std::env::var("CLAUDEBOT_CONTEXT_STRATEGY")
+            .ok()
+            .and_then(|v| ContextStrategy::parse(&v))
+        {
+            config.strategy = strategy;
+        }
+        if let Some(n) = std::env::var("CLAUDEBOT_CONTEXT_GRAPH_FACTS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.max_graph_facts = n;
+        }
+        if let Some(n) = std::env::var("CLAUDEBOT_CONTEXT_GRAPH_TOKENS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.max_graph_tokens = n;
+        }
+        config
+    }
+}
+
+/// Fixed token cost of the context block's section headers
+const CONTEXT_OVERHEAD_TOKENS: usize = 50;
+
+/// Keep the newest messages that fit within `max_tokens`, in chronological order
+pub fn fit_to_token_budget(
+    messages: Vec<ConversationMessage>,
+    max_tokens: usize,
+    counter: &TokenCounter,
+) -> Vec<ConversationMessage> {
+    let mut used = 0;
+    let mut kept: Vec<ConversationMessage> = messages
+        .into_iter()
+        .rev()
+        .take_while(|m| {
+            used += counter.count(&m.content);
+            used <= max_tokens
+        })
+        .collect();
+    kept.reverse();
+    kept
+}
+
 /// Enriched context ready for prompt injection
 #[derive(Debug, Clone)]
 pub struct EnrichedContext {
@@ -60,14 +218,64 @@ pub struct EnrichedContext {
     pub conversation: Vec<(String, String)>, // (role, content)
     /// Relevant entities from graph
     pub entities: Vec<GraphEntity>,
+    /// Facts connected to the memories through the graph (`graph` strategy)
+    pub connected: Vec<ConnectedFact>,
     /// Active goals
     pub goals: Vec<Goal>,
     /// User identity context (if available)
     pub identity: Option<String>,
     /// Whether HyDE was used
     pub hyde_used: bool,
+    /// Strategy the context was built with
+    pub strategy: ContextStrategy,
     /// Total tokens estimated for context
     pub estimated_tokens: usize,
+    /// What was dropped to fit `max_context_tokens`
+    pub trimmed: ContextTrim,
+}
+
+/// Items dropped from a context to fit its token budget
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextTrim {
+    pub identity: bool,
+    pub messages: usize,
+    pub memories: usize,
+    pub goals: usize,
+    pub entities: usize,
+    pub connected: usize,
+    /// Estimated tokens removed
+    pub tokens: usize,
+}
+
+impl ContextTrim {
+    pub fn is_empty(&self) -> bool {
+        self.tokens == 0
+    }
+
+    /// e.g. "3 memories, 1 entity (~420 tokens)"
+    pub fn summary(&self) -> String {
+        let plural = |n: usize, one: &str, many: &str| format!("{} {}", n, if n == 1 { one } else { many });
+        let mut parts = Vec::new();
+        if self.identity {
+            parts.push("identity".to_string());
+        }
+        if self.messages > 0 {
+            parts.push(plural(self.messages, "message", "messages"));
+        }
+        if self.memories > 0 {
+            parts.push(plural(self.memories, "memory", "memories"));
+        }
+        if self.goals > 0 {
+            parts.push(plural(self.goals, "goal", "goals"));
+        }
+        if self.entities > 0 {
+            parts.push(plural(self.entities, "entity", "entities"));
+        }
+        if self.connected > 0 {
+            parts.push(plural(self.connected, "connected fact", "connected facts"));
+        }
+        format!("{} (~{} tokens)", parts.join(", "), self.tokens)
+    }
 }
 
 /// Entity from knowledge graph
@@ -78,6 +286,16 @@ pub struct GraphEntity {
     pub relations: Vec<String>,
 }
 
+/// Memory reached through the graph rather than by matching the query
+#[derive(Debug, Clone)]
+pub struct ConnectedFact {
+    pub entry: MemoryEntry,
+    /// e.g. "auth system -uses- JWT"
+    pub path: String,
+    /// Weight of the relation followed
+    pub weight: f64,
+}
+
 impl EnrichedContext {
     /// Format context for prompt injection
     pub fn format_for_prompt(&self) -> String {
@@ -141,6 +359,17 @@ impl EnrichedContext {
             parts.push(format!("[Related Entities]\n{}", entities_text));
         }
 
+        // Facts connected through the graph
+        if !self.connected.is_empty() {
+            let connected_text = self
+                .connected
+                .iter()
+                .map(|f| format!("- {} ({})", f.entry.content, f.path))
+                .collect::<Vec<_>>()
+                .join("\n");
+            parts.push(format!("[Connected Facts]\n{}", connected_text));
+        }
+
         if parts.is_empty() {
             String::new()
         } else {
@@ -153,14 +382,30 @@ impl EnrichedContext {
         self.memories.is_empty()
             && self.conversation.is_empty()
             && self.entities.is_empty()
+            && self.connected.is_empty()
             && self.goals.is_empty()
             && self.identity.is_none()
     }
 }
 
+/// `/context` settings kept across restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ContextOverrides {
+    /// Per-chat context window (`/context window <N>`)
+    #[serde(default)]
+    windows: HashMap<i64, usize>,
+    /// Per-user retrieval strategy (`/context strategy <name>`)
+    #[serde(default)]
+    strategies: HashMap<i64, ContextStrategy>,
+}
+
 /// Context manager for proactive memory integration
 pub struct ContextManager {
     config: ContextConfig,
+    overrides: RwLock<ContextOverrides>,
+    /// Where overrides are saved (None = memory only)
+    overrides_path: Option<PathBuf>,
+    counter: TokenCounter,
 }
 
 impl ContextManager {
@@ -171,19 +416,135 @@ impl ContextManager {
 
     /// Create with custom config
     pub fn with_config(config: ContextConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            overrides: RwLock::new(ContextOverrides::default()),
+            overrides_path: None,
+            counter: TokenCounter::new(),
+        }
+    }
+
+    /// Create a manager whose overrides are loaded from and saved to `path`,
+    /// so they survive restarts
+    ///
+    /// A missing or unreadable file starts with no overrides.
+    pub fn with_overrides_file(config: ContextConfig, path: &Path) -> Self {
+        let overrides = match std::fs::read_to_string(path) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable context overrides {:?}: {}", path, e);
+                ContextOverrides::default()
+            }),
+            Err(_) => ContextOverrides::default(),
+        };
+        let mut manager = Self::with_config(config);
+        manager.overrides = RwLock::new(overrides);
+        manager.overrides_path = Some(path.to_path_buf());
+        manager
+    }
+
+    /// Active configuration
+    pub fn config(&self) -> &ContextConfig {
+        &self.config
+    }
+
+    /// Conversation messages included for a chat
+    pub fn window_for(&self, chat_id: i64) -> usize {
+        self.overrides
+            .read()
+            .windows
+            .get(&chat_id)
+            .copied()
+            .unwrap_or(self.config.context_window_messages)
+    }
+
+    /// True if the chat has its own window size
+    pub fn has_window_override(&self, chat_id: i64) -> bool {
+        self.overrides.read().windows.contains_key(&chat_id)
+    }
+
+    /// Set (Some) or clear (None) a chat's window size, returning the effective size
+    pub fn set_window_override(&self, chat_id: i64, messages: Option<usize>) -> usize {
+        let mut overrides = self.overrides.write();
+        let effective = match messages {
+            Some(n) => {
+                let n = n.min(MAX_CONTEXT_WINDOW_MESSAGES);
+                overrides.windows.insert(chat_id, n);
+                n
+            }
+            None => {
+                overrides.windows.remove(&chat_id);
+                self.config.context_window_messages
+            }
+        };
+        self.save_overrides(&overrides);
+        effective
+    }
+
+    fn save_overrides(&self, overrides: &ContextOverrides) {
+        let Some(path) = &self.overrides_path else {
+            return;
+        };
+        let result = serde_json::to_vec_pretty(overrides)
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, path)
+            });
+        if let Err(e) = result {
+            warn!("Failed to save context overrides to {:?}: {}", path, e);
+        }
+    }
+
+    /// Retrieval strategy for a user
+    pub fn strategy_for(&self, user_id: i64) -> ContextStrategy {
+        self.overrides
+            .read()
+            .strategies
+            .get(&user_id)
+            .copied()
+            .unwrap_or(self.config.strategy)
+    }
+
+    /// True if the user picked their own strategy
+    pub fn has_strategy_override(&self, user_id: i64) -> bool {
+        self.overrides.read().strategies.contains_key(&user_id)
+    }
+
+    /// Set (Some) or clear (None) a user's strategy, returning the effective one
+    pub fn set_strategy_override(&self, user_id: i64, strategy: Option<ContextStrategy>) -> ContextStrategy {
+        let mut overrides = self.overrides.write();
+        let effective = match strategy {
+            Some(strategy) => {
+                overrides.strategies.insert(user_id, strategy);
+                strategy
+            }
+            None => {
+                overrides.strategies.remove(&user_id);
+                self.config.strategy
+            }
+        };
+        self.save_overrides(&overrides);
+        effective
+    }
+
+    /// Recent history for a chat, limited by its window and the token ceiling
+    pub fn recent_history(&self, chat_id: i64, store: &ConversationStore) -> anyhow::Result<Vec<ConversationMessage>> {
+        let messages = store.get_history(chat_id, self.window_for(chat_id))?;
+        Ok(fit_to_token_budget(messages, self.config.max_conversation_tokens, &self.counter))
     }
 
     /// Build enriched context for a user prompt
     ///
     /// This is the main entry point - call before sending to Claude API.
+    /// How much runs depends on the user's `ContextStrategy`.
     pub async fn build_context(
         &self,
         prompt: &str,
         user_id: i64,
         chat_id: i64,
-        memory: &std::sync::Mutex<MemoryStore>,
-        conversation: &std::sync::Mutex<ConversationStore>,
+        memory: &MemoryStore,
+        conversation: &ConversationStore,
         graph: &std::sync::Mutex<GraphStore>,
         goals: Option<&GoalTracker>,
         llama: &LlamaWorker,
@@ -192,15 +553,24 @@ impl ContextManager {
             memories: vec![],
             conversation: vec![],
             entities: vec![],
+            connected: vec![],
             goals: vec![],
             identity: None,
             hyde_used: false,
+            strategy: self.strategy_for(user_id),
             estimated_tokens: 0,
+            trimmed: ContextTrim::default(),
         };
+        if context.strategy == ContextStrategy::None {
+            return context;
+        }
+        let full = matches!(context.strategy, ContextStrategy::Full | ContextStrategy::Graph);
+
+        let scope = self.config.memory_scope.scope_for(user_id);
 
         // 1. Get user identity context (highest priority)
         if self.config.include_identity {
-            context.identity = self.get_identity_context(user_id, memory);
+            context.identity = self.get_identity_context(scope, memory);
         }
 
         // 2. Get recent conversation history FIRST (needed for memory search)
@@ -210,11 +580,14 @@ impl ContextManager {
         let search_query = self.build_search_query(prompt, &context.conversation);
 
         // 4. Retrieve relevant memories using expanded query
-        context.memories = self.retrieve_memories(&search_query, memory, llama).await;
-        context.hyde_used = self.config.use_hyde && llama.is_available().await;
+        context.memories = self.retrieve_memories(&search_query, scope, memory, llama, full).await;
+        context.hyde_used = full && self.config.use_hyde && llama.is_available().await;
 
         // 4. Find related entities from graph
         context.entities = self.find_related_entities(prompt, &context.memories, graph);
+        if context.strategy == ContextStrategy::Graph {
+            context.connected = self.connected_facts(&context.memories, scope, memory, graph);
+        }
 
         // 5. Include active goals if configured
         if self.config.include_goals {
@@ -223,16 +596,25 @@ impl ContextManager {
             }
         }
 
-        // Estimate token count
+        // Fit the budget, then estimate token count
+        context.trimmed = self.enforce_budget(&mut context);
         context.estimated_tokens = self.estimate_tokens(&context);
 
         debug!(
-            "Built context: {} memories, {} entities, {} goals, ~{} tokens",
+            "Built context: {} memories, {} entities, {} connected, {} goals, ~{} tokens",
             context.memories.len(),
             context.entities.len(),
+            context.connected.len(),
             context.goals.len(),
             context.estimated_tokens
         );
+        if !context.trimmed.is_empty() {
+            debug!(
+                "Context over {} token budget, dropped {}",
+                self.config.max_context_tokens,
+                context.trimmed.summary()
+            );
+        }
 
         context
     }
@@ -244,14 +626,9 @@ impl ContextManager {
         I have access to the Claude CLI for coding tasks and can execute commands autonomously.";
 
     /// Get identity context for user
-    fn get_identity_context(&self, _user_id: i64, memory: &std::sync::Mutex<MemoryStore>) -> Option<String> {
-        let store = match memory.lock() {
-            Ok(s) => s,
-            Err(_) => return Some(Self::BASELINE_IDENTITY.to_string()),
-        };
-
+    fn get_identity_context(&self, scope: MemoryScope, memory: &MemoryStore) -> Option<String> {
         // First, try to find explicit identity memories by category
-        if let Ok(results) = store.get_by_category("identity", 3) {
+        if let Ok(results) = memory.get_by_category("identity", 3, scope) {
             for result in &results {
                 let content = result.content.to_lowercase();
                 if content.contains("i am ") || content.contains("my name is") {
@@ -261,8 +638,8 @@ impl ContextManager {
         }
 
         // Fallback: search by keywords
-        if let Ok(results) = store.search("identity user name role", 5) {
-            for result in &results {
+        if let Ok(results) = memory.search("identity user name role", 5) {
+            for result in results.iter().filter(|r| scope.allows(&r.entry)) {
                 let content = result.entry.content.to_lowercase();
                 if content.contains("i am ") || content.contains("my name is") || content.contains("identify as") {
                     return Some(result.entry.content.clone());
@@ -275,20 +652,21 @@ impl ContextManager {
     }
 
     /// Retrieve relevant memories using hybrid search with optional HyDE
+    ///
+    /// Without `vectors` the search is keyword-only: no HyDE and no embedding.
     async fn retrieve_memories(
         &self,
         prompt: &str,
-        memory: &std::sync::Mutex<MemoryStore>,
+        scope: MemoryScope,
+        memory: &MemoryStore,
         llama: &LlamaWorker,
+        vectors: bool,
     ) -> Vec<ScoredMemory> {
         // Get embedder for vector search
-        let embedder = {
-            let store = memory.lock().ok();
-            store.and_then(|s| s.get_embedder())
-        };
+        let embedder = memory.get_embedder().filter(|_| vectors);
 
         // Optionally use HyDE for question-like prompts
-        let search_text = if self.config.use_hyde && self.is_question(prompt) {
+        let search_text = if vectors && self.config.use_hyde && self.is_question(prompt) {
             match llama.generate_hyde(prompt).await {
                 Ok(hyde) => hyde,
                 Err(_) => prompt.to_string(),
@@ -297,28 +675,27 @@ impl ContextManager {
             prompt.to_string()
         };
 
-        // Compute embedding outside the lock
+        // Compute the query embedding before searching
         let query_embedding = if let Some(ref embedder) = embedder {
             embedder.read().await.embed(&search_text).await.ok()
         } else {
             None
         };
 
-        // Perform hybrid search
-        let store = match memory.lock() {
-            Ok(s) => s,
-            Err(e) => {
-                warn!("Failed to lock memory store: {}", e);
-                return vec![];
-            }
+        // One ranking alone scores at most half of a match in both (RRF)
+        let min_relevance = if query_embedding.is_some() {
+            self.config.min_relevance
+        } else {
+            self.config.min_relevance / 2.0
         };
 
-        match store.search_hybrid_sync(prompt, query_embedding, self.config.max_memories, 0.4) {
+        // Perform hybrid search
+        match memory.search_hybrid_sync(prompt, query_embedding, self.config.max_memories, 0.4, scope) {
             Ok(results) => {
                 debug!(
                     "Memory search returned {} results (min_relevance: {})",
                     results.len(),
-                    self.config.min_relevance
+                    min_relevance
                 );
                 if !results.is_empty() {
                     debug!(
@@ -329,7 +706,7 @@ impl ContextManager {
                 }
                 results
                     .into_iter()
-                    .filter(|r| r.score >= self.config.min_relevance)
+                    .filter(|r| r.score >= min_relevance)
                     .collect()
             }
             Err(e) => {
@@ -385,14 +762,9 @@ impl ContextManager {
     fn get_conversation_history(
         &self,
         chat_id: i64,
-        conversation: &std::sync::Mutex<ConversationStore>,
+        conversation: &ConversationStore,
     ) -> Vec<(String, String)> {
-        let store = match conversation.lock() {
-            Ok(s) => s,
-            Err(_) => return vec![],
-        };
-
-        match store.get_history(chat_id, self.config.max_conversation_turns) {
+        match self.recent_history(chat_id, conversation) {
             Ok(messages) => messages
                 .into_iter()
                 .map(|m| (m.role, m.content))
@@ -463,6 +835,61 @@ impl ContextManager {
         entities
     }
 
+    /// Memories one graph relation away from `memories`, visible in `scope`
+    ///
+    /// Bounded by `max_graph_facts` and `max_graph_tokens`; the strongest
+    /// relations are taken first.
+    pub fn connected_facts(
+        &self,
+        memories: &[ScoredMemory],
+        scope: MemoryScope,
+        memory: &MemoryStore,
+        graph: &std::sync::Mutex<GraphStore>,
+    ) -> Vec<ConnectedFact> {
+        if memories.is_empty() || self.config.max_graph_facts == 0 {
+            return vec![];
+        }
+        let ids: Vec<&str> = memories.iter().map(|m| m.entry.id.as_str()).collect();
+        // Room for candidates that turn out invisible or over the token budget
+        let candidates = match graph.lock() {
+            Ok(store) => store.connected_memories(&ids, self.config.max_graph_facts * 4),
+            Err(_) => return vec![],
+        };
+        let candidates = match candidates {
+            Ok(candidates) => candidates,
+            Err(e) => {
+                warn!("Graph expansion failed: {}", e);
+                return vec![];
+            }
+        };
+
+        let mut facts = Vec::new();
+        let mut tokens = 0;
+        for candidate in candidates {
+            let Ok(Some(entry)) = memory.get_by_id(&candidate.memory_id) else {
+                continue;
+            };
+            if !scope.allows(&entry) {
+                continue;
+            }
+            let fact = ConnectedFact {
+                path: format!("{} -{}- {}", candidate.from, candidate.relation_type, candidate.to),
+                weight: candidate.weight,
+                entry,
+            };
+            let fact_tokens = self.connected_tokens(&fact);
+            if tokens + fact_tokens > self.config.max_graph_tokens {
+                continue;
+            }
+            tokens += fact_tokens;
+            facts.push(fact);
+            if facts.len() >= self.config.max_graph_facts {
+                break;
+            }
+        }
+        facts
+    }
+
     /// Check if prompt is a question
     fn is_question(&self, prompt: &str) -> bool {
         prompt.contains('?')
@@ -479,31 +906,98 @@ impl ContextManager {
 
     /// Estimate token count for context
     fn estimate_tokens(&self, context: &EnrichedContext) -> usize {
-        let mut tokens = 0;
+        let count = |text: &str| self.counter.count(text);
+        let identity = context.identity.as_deref().map(count).unwrap_or(0);
+        let conversation: usize = context.conversation.iter().map(|(_, content)| count(content)).sum();
+        let memories: usize = context.memories.iter().map(|m| count(&m.entry.content)).sum();
+        let goals: usize = context.goals.iter().map(|g| count(&g.description)).sum();
+        let entities: usize = context.entities.iter().map(|e| self.entity_tokens(e)).sum();
+        let connected: usize = context.connected.iter().map(|f| self.connected_tokens(f)).sum();
 
-        // Rough estimation: ~4 characters per token
-        if let Some(ref identity) = context.identity {
-            tokens += identity.len() / 4;
-        }
+        // Add overhead for formatting
+        identity + conversation + memories + goals + entities + connected + CONTEXT_OVERHEAD_TOKENS
+    }
 
-        for memory in &context.memories {
-            tokens += memory.entry.content.len() / 4;
-        }
+    fn entity_tokens(&self, entity: &GraphEntity) -> usize {
+        self.counter.count(&entity.name)
+            + self.counter.count(&entity.entity_type)
+            + self.counter.count(&entity.relations.join(", "))
+            + 5
+    }
 
-        for (_, content) in &context.conversation {
-            tokens += content.len() / 4;
+    fn connected_tokens(&self, fact: &ConnectedFact) -> usize {
+        self.counter.count(&fact.entry.content) + self.counter.count(&fact.path) + 3
+    }
+
+    /// Drop context items until it fits `max_context_tokens`
+    ///
+    /// Items are admitted greedily in priority order: identity, conversation
+    /// (newest first), memories by score, goals, entities, then connected
+    /// facts. Anything that doesn't fit the remaining budget is dropped.
+    fn enforce_budget(&self, context: &mut EnrichedContext) -> ContextTrim {
+        let budget = self.config.max_context_tokens;
+        let mut trim = ContextTrim::default();
+        if budget == 0 {
+            return trim;
         }
+        let mut used = CONTEXT_OVERHEAD_TOKENS;
+        let mut admit = |tokens: usize, trim: &mut ContextTrim| {
+            if used + tokens <= budget {
+                used += tokens;
+                true
+            } else {
+                trim.tokens += tokens;
+                false
+            }
+        };
 
-        for entity in &context.entities {
-            tokens += (entity.name.len() + entity.entity_type.len() + 20) / 4;
+        if let Some(identity) = &context.identity {
+            if !admit(self.counter.count(identity), &mut trim) {
+                context.identity = None;
+                trim.identity = true;
+            }
         }
 
-        for goal in &context.goals {
-            tokens += goal.description.len() / 4;
+        // Once a message is dropped, older ones go too so history stays contiguous
+        let mut kept = Vec::with_capacity(context.conversation.len());
+        let mut dropping = false;
+        for message in context.conversation.drain(..).rev() {
+            let tokens = self.counter.count(&message.1);
+            if !dropping && admit(tokens, &mut trim) {
+                kept.push(message);
+            } else {
+                if dropping {
+                    trim.tokens += tokens;
+                }
+                dropping = true;
+                trim.messages += 1;
+            }
         }
+        kept.reverse();
+        context.conversation = kept;
 
-        // Add overhead for formatting
-        tokens + 50
+        context
+            .memories
+            .sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        let before = context.memories.len();
+        context
+            .memories
+            .retain(|m| admit(self.counter.count(&m.entry.content), &mut trim));
+        trim.memories = before - context.memories.len();
+
+        let before = context.goals.len();
+        context.goals.retain(|g| admit(self.counter.count(&g.description), &mut trim));
+        trim.goals = before - context.goals.len();
+
+        let before = context.entities.len();
+        context.entities.retain(|e| admit(self.entity_tokens(e), &mut trim));
+        trim.entities = before - context.entities.len();
+
+        let before = context.connected.len();
+        context.connected.retain(|f| admit(self.connected_tokens(f), &mut trim));
+        trim.connected = before - context.connected.len();
+
+        trim
     }
 }
 
@@ -528,16 +1022,214 @@ mod tests {
         assert!(!manager.is_question("Update the code"));
     }
 
+    #[test]
+    fn test_window_override_and_token_budget() {
+        let manager = ContextManager::with_config(ContextConfig {
+            context_window_messages: 4,
+            max_conversation_tokens: 20,
+            ..ContextConfig::default()
+        });
+
+        assert_eq!(manager.window_for(1), 4);
+        assert_eq!(manager.set_window_override(1, Some(1000)), MAX_CONTEXT_WINDOW_MESSAGES);
+        assert_eq!(manager.window_for(2), 4);
+        assert_eq!(manager.set_window_override(1, None), 4);
+        assert!(!manager.has_window_override(1));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("context.json");
+        let config = ContextConfig { context_window_messages: 4, ..ContextConfig::default() };
+        let manager = ContextManager::with_overrides_file(config.clone(), &path);
+        manager.set_window_override(1, Some(6));
+        manager.set_window_override(2, Some(8));
+        manager.set_window_override(2, None);
+        manager.set_strategy_override(1, Some(ContextStrategy::Graph));
+        let manager = ContextManager::with_overrides_file(config, &path);
+        assert_eq!(manager.window_for(1), 6);
+        assert!(!manager.has_window_override(2));
+        assert_eq!(manager.strategy_for(1), ContextStrategy::Graph);
+        assert!(!manager.has_strategy_override(2));
+
+        let message = |content: &str| ConversationMessage {
+            role: "user".to_string(),
+            content: content.to_string(),
+            timestamp: 0,
+            usage: None,
+        };
+        let long = "word ".repeat(40);
+        let messages = vec![message(&long), message("older"), message("newest")];
+        let kept = fit_to_token_budget(messages, 20, &TokenCounter::new());
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[1].content, "newest");
+    }
+
+    #[tokio::test]
+    async fn test_strategy_override_and_lighter_strategies() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemoryStore::open(&dir.path().join("memory.db")).unwrap();
+        memory.learn("Deploys go through the staging cluster", "fact", "telegram_user_2", Some(2), 0.9).unwrap();
+        let conversation = ConversationStore::open(&dir.path().join("conversations.db")).unwrap();
+        let graph = std::sync::Mutex::new(GraphStore::open(&dir.path().join("graph.db")).unwrap());
+        let llama = LlamaWorker::new();
+
+        let manager = ContextManager::with_config(ContextConfig {
+            strategy: ContextStrategy::Fast,
+            ..ContextConfig::default()
+        });
+        assert_eq!(manager.strategy_for(1), ContextStrategy::Fast);
+        assert_eq!(manager.set_strategy_override(1, Some(ContextStrategy::None)), ContextStrategy::None);
+        assert!(manager.has_strategy_override(1));
+        assert_eq!(manager.strategy_for(2), ContextStrategy::Fast);
+
+        // Fast: keyword search still finds the memory, without HyDE
+        let fast = manager
+            .build_context("Which staging cluster do deploys use?", 2, 20, &memory, &conversation, &graph, None, &llama)
+            .await;
+        assert_eq!(fast.strategy, ContextStrategy::Fast);
+        assert!(!fast.hyde_used);
+        assert_eq!(fast.memories.len(), 1);
+        assert_eq!(fast.memories[0].vector_score, 0.0);
+
+        let bare = manager
+            .build_context("How do deploys work?", 1, 10, &memory, &conversation, &graph, None, &llama)
+            .await;
+        assert!(bare.format_for_prompt().is_empty());
+        assert_eq!(manager.set_strategy_override(1, None), ContextStrategy::Fast);
+        assert_eq!(ContextStrategy::parse(" Keyword "), Some(ContextStrategy::Fast));
+    }
+
+    #[tokio::test]
+    async fn test_graph_strategy_adds_connected_facts() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemoryStore::open(&dir.path().join("memory.db")).unwrap();
+        let auth_fact = memory.learn("The auth system issues session tokens", "fact", "telegram_user_2", Some(2), 0.9).unwrap();
+        let jwt_fact = memory.learn("JWT signing keys rotate every month", "fact", "telegram_user_2", Some(2), 0.9).unwrap();
+        let redis_fact = memory.learn("Redis keeps a blocklist of revoked tokens", "fact", "telegram_user_2", Some(2), 0.9).unwrap();
+        let private = memory.learn("Bcrypt cost factor is twelve", "fact", "telegram_user_3", Some(3), 0.9).unwrap();
+        let conversation = ConversationStore::open(&dir.path().join("conversations.db")).unwrap();
+        let store = GraphStore::open(&dir.path().join("graph.db")).unwrap();
+        let auth = store.add_entity("project", "auth system", None).unwrap();
+        let jwt = store.add_entity("technology", "JWT", None).unwrap();
+        let redis = store.add_entity("technology", "Redis", None).unwrap();
+        let bcrypt = store.add_entity("technology", "Bcrypt", None).unwrap();
+        store.add_relation(&auth, &jwt, "uses", Some(0.9)).unwrap();
+        store.add_relation(&auth, &redis, "uses", Some(0.4)).unwrap();
+        store.add_relation(&auth, &bcrypt, "uses", Some(0.8)).unwrap();
+        store.link_to_memory(&auth, &auth_fact).unwrap();
+        store.link_to_memory(&jwt, &jwt_fact).unwrap();
+        store.link_to_memory(&redis, &redis_fact).unwrap();
+        store.link_to_memory(&bcrypt, &private).unwrap();
+        let graph = std::sync::Mutex::new(store);
+        let llama = LlamaWorker::new();
+
+        let manager = ContextManager::with_config(ContextConfig {
+            strategy: ContextStrategy::Graph,
+            memory_scope: MemoryScopeMode::PerUser,
+            max_graph_facts: 1,
+            ..ContextConfig::default()
+        });
+        let context = manager
+            .build_context("Tell me about the auth system", 2, 20, &memory, &conversation, &graph, None, &llama)
+            .await;
+        assert_eq!(context.memories.len(), 1);
+        // The strongest relation wins; user 3's memory is out of scope
+        assert_eq!(context.connected.len(), 1);
+        assert_eq!(context.connected[0].entry.id, jwt_fact);
+        assert_eq!(context.connected[0].path, "auth system -uses- JWT");
+        assert!(context.format_for_prompt().contains("[Connected Facts]\n- JWT signing keys rotate every month"));
+
+        let roomier = ContextManager::with_config(ContextConfig {
+            memory_scope: MemoryScopeMode::PerUser,
+            max_graph_facts: 5,
+            ..ContextConfig::default()
+        });
+        let facts = roomier.connected_facts(&context.memories, MemoryScope::User(2), &memory, &graph);
+        assert_eq!(facts.iter().map(|f| f.entry.id.clone()).collect::<Vec<_>>(), vec![jwt_fact.clone(), redis_fact]);
+        let tight = ContextManager::with_config(ContextConfig { max_graph_tokens: 1, ..ContextConfig::default() });
+        assert!(tight.connected_facts(&context.memories, MemoryScope::Global, &memory, &graph).is_empty());
+
+        // The default strategy doesn't expand
+        let full = roomier
+            .build_context("Tell me about the auth system", 2, 20, &memory, &conversation, &graph, None, &llama)
+            .await;
+        assert!(full.connected.is_empty());
+    }
+
+    #[test]
+    fn test_context_budget_keeps_best() {
+        let manager = ContextManager::with_config(ContextConfig {
+            max_context_tokens: 80,
+            ..ContextConfig::default()
+        });
+        let counter = TokenCounter::new();
+        let memory = |content: &str, score: f64| ScoredMemory {
+            entry: crate::memory::MemoryEntry {
+                id: content.to_string(),
+                content: content.to_string(),
+                category: "fact".to_string(),
+                source: "test".to_string(),
+                confidence: 0.9,
+                created_at: 0,
+                access_count: 0,
+                embedding: None,
+                shared: false,
+                owner_id: None,
+            },
+            score,
+            keyword_score: score,
+            vector_score: score,
+        };
+        let filler = "alpha beta gamma delta ".repeat(10);
+        let mut context = EnrichedContext {
+            memories: vec![memory(&filler, 0.2), memory("short best", 0.9), memory(&filler, 0.5)],
+            conversation: vec![
+                ("user".to_string(), filler.clone()),
+                ("assistant".to_string(), "latest reply".to_string()),
+            ],
+            entities: vec![],
+            connected: vec![],
+            goals: vec![],
+            identity: Some("User is a developer".to_string()),
+            hyde_used: false,
+            strategy: ContextStrategy::Full,
+            estimated_tokens: 0,
+            trimmed: ContextTrim::default(),
+        };
+        let memory_tokens = counter.count(&filler);
+
+        let trim = manager.enforce_budget(&mut context);
+        assert!(manager.estimate_tokens(&context) <= 80);
+        assert!(context.identity.is_some());
+        assert_eq!(context.conversation.len(), 1);
+        assert_eq!(context.conversation[0].1, "latest reply");
+        // Only the best-scored memory fits after the history
+        assert_eq!(context.memories.len(), 1);
+        assert_eq!(context.memories[0].entry.content, "short best");
+        assert_eq!(trim.messages, 1);
+        assert_eq!(trim.memories, 2);
+        assert!(trim.tokens >= 3 * memory_tokens);
+        assert!(trim.summary().contains("1 message"));
+
+        let unlimited = ContextManager::with_config(ContextConfig {
+            max_context_tokens: 0,
+            ..ContextConfig::default()
+        });
+        assert!(unlimited.enforce_budget(&mut context.clone()).is_empty());
+    }
+
     #[test]
     fn test_context_formatting() {
         let context = EnrichedContext {
             memories: vec![],
             conversation: vec![],
             entities: vec![],
+            connected: vec![],
             goals: vec![],
             identity: Some("User is Eliot, a developer".to_string()),
             hyde_used: false,
+            strategy: ContextStrategy::Full,
             estimated_tokens: 100,
+            trimmed: ContextTrim::default(),
         };
 
         let formatted = context.format_for_prompt();
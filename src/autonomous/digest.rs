@@ -0,0 +1,322 @@
+//! Daily Digest
+//!
+//! Summarizes what the bot knows about a user once per day:
+//! - Active goals (from the goal tracker)
+//! - Upcoming reminders (from the scheduler)
+//! - Notable memories learned in the last 24h
+//!
+//! The digest is generated during lifecycle idle processing once the
+//! configured time of day has passed, and delivered through the
+//! scheduler's notification channel. The date it was last sent is saved so a
+//! restart later the same day doesn't send it again.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::goals::{Goal, GoalTracker};
+use crate::agent::scheduler::{NotificationType, Reminder, Scheduler};
+use crate::memory::{MemoryEntry, MemoryScope, MemoryStore};
+
+/// Which sections to include in the digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigestSections {
+    pub goals: bool,
+    pub reminders: bool,
+    pub memories: bool,
+}
+
+impl Default for DigestSections {
+    fn default() -> Self {
+        Self {
+            goals: true,
+            reminders: true,
+            memories: true,
+        }
+    }
+}
+
+impl DigestSections {
+    /// Parse a comma-separated list like "goals,memories"
+    pub fn parse(s: &str) -> Self {
+        let mut sections = Self {
+            goals: false,
+            reminders: false,
+            memories: false,
+        };
+        for part in s.split(',').map(|p| p.trim().to_lowercase()) {
+            match part.as_str() {
+                "goals" => sections.goals = true,
+                "reminders" => sections.reminders = true,
+                "memories" => sections.memories = true,
+                "all" => sections = Self::default(),
+                _ => {}
+            }
+        }
+        sections
+    }
+}
+
+/// Configuration for the daily digest
+#[derive(Debug, Clone)]
+pub struct DigestConfig {
+    /// Send the digest at all (default: false)
+    pub enabled: bool,
+    /// Local time of day after which the digest is sent (default: 08:00)
+    pub time: NaiveTime,
+    /// Sections to include
+    pub sections: DigestSections,
+    /// Maximum items listed per section
+    pub max_items: usize,
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: NaiveTime::from_hms_opt(8, 0, 0).expect("valid time"),
+            sections: DigestSections::default(),
+            max_items: 5,
+        }
+    }
+}
+
+impl DigestConfig {
+    /// Load from environment variables
+    ///
+    /// - `CLAUDEBOT_DIGEST_ENABLED` - "true"/"1" to enable
+    /// - `CLAUDEBOT_DIGEST_TIME` - local time as HH:MM
+    /// - `CLAUDEBOT_DIGEST_SECTIONS` - comma-separated: goals,reminders,memories
+    /// - `CLAUDEBOT_DIGEST_MAX_ITEMS` - items per section
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("CLAUDEBOT_DIGEST_ENABLED")
+                .map(|s| s == "true" || s == "1")
+                .unwrap_or(defaults.enabled),
+            time: std::env::var("CLAUDEBOT_DIGEST_TIME")
+                .ok()
+                .and_then(|s| NaiveTime::parse_from_str(s.trim(), "%H:%M").ok())
+                .unwrap_or(defaults.time),
+            sections: std::env::var("CLAUDEBOT_DIGEST_SECTIONS")
+                .ok()
+                .map(|s| DigestSections::parse(&s))
+                .unwrap_or(defaults.sections),
+            max_items: std::env::var("CLAUDEBOT_DIGEST_MAX_ITEMS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.max_items),
+        }
+    }
+
+    /// Whether a digest should be sent now, given the date it was last sent
+    pub fn is_due<Tz: TimeZone>(&self, now: &DateTime<Tz>, last_sent: Option<NaiveDate>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let today = now.date_naive();
+        last_sent != Some(today) && now.time() >= self.time
+    }
+
+    /// Convenience wrapper for `is_due` using local time
+    pub fn is_due_now(&self, last_sent: Option<NaiveDate>) -> bool {
+        self.is_due(&Local::now(), last_sent)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DigestState {
+    last_sent: Option<NaiveDate>,
+}
+
+/// Generated digest contents
+#[derive(Debug, Clone, Default)]
+pub struct Digest {
+    pub goals: Vec<Goal>,
+    pub reminders: Vec<Reminder>,
+    pub memories: Vec<MemoryEntry>,
+}
+
+impl Digest {
+    /// Date the digest was last sent, from the state file at `path`
+    ///
+    /// A missing or unreadable file counts as never sent.
+    pub fn load_last_sent(path: &Path) -> Option<NaiveDate> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<DigestState>(&data).ok())
+            .and_then(|state| state.last_sent)
+    }
+
+    /// Record the date the digest was sent
+    pub fn save_last_sent(path: &Path, date: NaiveDate) -> anyhow::Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&DigestState { last_sent: Some(date) })?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Gather digest sections for a user
+    ///
+    /// `scope` limits the new memories to ones the user may see.
+    pub async fn build(
+        config: &DigestConfig,
+        user_id: i64,
+        goal_tracker: &GoalTracker,
+        scheduler: &Scheduler,
+        memory_store: &MemoryStore,
+        scope: MemoryScope,
+    ) -> Self {
+        let mut digest = Self::default();
+
+        if config.sections.goals {
+            digest.goals = goal_tracker.get_active_goals(user_id).await;
+            digest.goals.truncate(config.max_items);
+        }
+
+        if config.sections.reminders {
+            let mut reminders = scheduler.get_user_reminders(user_id).await;
+            reminders.sort_by_key(|r| r.due_at);
+            reminders.truncate(config.max_items);
+            digest.reminders = reminders;
+        }
+
+        if config.sections.memories {
+            let since = chrono::Utc::now().timestamp() - 86400;
+            digest.memories = memory_store.get_since(since, config.max_items, scope).unwrap_or_default();
+        }
+
+        digest
+    }
+
+    /// True if no section has any content
+    pub fn is_empty(&self) -> bool {
+        self.goals.is_empty() && self.reminders.is_empty() && self.memories.is_empty()
+    }
+
+    /// Format as plain text for delivery
+    pub fn format(&self) -> String {
+        let mut sections = Vec::new();
+
+        if !self.goals.is_empty() {
+            let lines: Vec<String> = self.goals.iter().map(|g| format!("  {}", g.format_short())).collect();
+            sections.push(format!("Active goals:\n{}", lines.join("\n")));
+        }
+
+        if !self.reminders.is_empty() {
+            let lines: Vec<String> = self.reminders.iter().map(|r| format!("  {}", r.format())).collect();
+            sections.push(format!("Upcoming reminders:\n{}", lines.join("\n")));
+        }
+
+        if !self.memories.is_empty() {
+            let lines: Vec<String> = self
+                .memories
+                .iter()
+                .map(|m| {
+                    let content: String = m.content.chars().take(120).collect();
+                    format!("  • [{}] {}", m.category, content)
+                })
+                .collect();
+            sections.push(format!("Learned in the last 24h:\n{}", lines.join("\n")));
+        }
+
+        if sections.is_empty() {
+            return "Nothing new today.".to_string();
+        }
+        sections.join("\n\n")
+    }
+
+    /// Wrap as a notification for the scheduler channel
+    pub fn into_notification(self, user_id: i64, chat_id: i64) -> Reminder {
+        Reminder::once(user_id, chat_id, &self.format(), chrono::Utc::now().timestamp())
+            .with_type(NotificationType::Digest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_sections_parse() {
+        let s = DigestSections::parse("goals, Memories");
+        assert!(s.goals);
+        assert!(!s.reminders);
+        assert!(s.memories);
+
+        assert_eq!(DigestSections::parse("all"), DigestSections::default());
+    }
+
+    #[test]
+    fn test_is_due() {
+        let config = DigestConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let morning = chrono::Utc.with_ymd_and_hms(2026, 3, 2, 7, 59, 0).unwrap();
+        let later = chrono::Utc.with_ymd_and_hms(2026, 3, 2, 9, 30, 0).unwrap();
+
+        assert!(!config.is_due(&morning, None));
+        assert!(config.is_due(&later, None));
+        assert!(!config.is_due(&later, Some(later.date_naive())));
+        assert!(config.is_due(&later, later.date_naive().pred_opt()));
+
+        let disabled = DigestConfig::default();
+        assert!(!disabled.is_due(&later, None));
+    }
+
+    #[test]
+    fn test_last_sent_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("digest.json");
+        assert_eq!(Digest::load_last_sent(&path), None);
+
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        Digest::save_last_sent(&path, today).unwrap();
+        assert_eq!(Digest::load_last_sent(&path), Some(today));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(Digest::load_last_sent(&path), None);
+    }
+
+    #[tokio::test]
+    async fn test_build_digest() {
+        let path = PathBuf::from("/tmp/claudebot_test_digest.db");
+        let _ = std::fs::remove_file(&path);
+        let store = MemoryStore::open(&path).unwrap();
+        store.learn("User prefers dark mode", "preferences", "telegram_user_7", Some(7), 0.9).unwrap();
+        store.learn("Other user's VPN password hint", "facts", "telegram_user_8", Some(8), 0.9).unwrap();
+
+        let goals = GoalTracker::new();
+        let (scheduler, _rx) = Scheduler::new(10);
+        scheduler
+            .schedule_reminder(Reminder::once(7, 7, "Call mom", chrono::Utc::now().timestamp() + 3600))
+            .await;
+
+        let config = DigestConfig {
+            enabled: true,
+            sections: DigestSections::parse("reminders,memories"),
+            ..Default::default()
+        };
+        let shared = Digest::build(&config, 7, &goals, &scheduler, &store, MemoryScope::Global).await;
+        assert_eq!(shared.memories.len(), 2);
+
+        // Per-user scope: each recipient only sees their own new memories
+        let digest = Digest::build(&config, 7, &goals, &scheduler, &store, MemoryScope::User(7)).await;
+        assert!(digest.goals.is_empty());
+        assert_eq!(digest.reminders.len(), 1);
+        assert_eq!(digest.memories.len(), 1);
+        let other = Digest::build(&config, 8, &goals, &scheduler, &store, MemoryScope::User(8)).await;
+        assert_eq!(other.memories.len(), 1);
+        assert!(other.memories[0].content.contains("VPN"));
+
+        let text = digest.format();
+        assert!(text.contains("Call mom"));
+        assert!(text.contains("dark mode"));
+
+        let notification = digest.into_notification(7, 7);
+        assert_eq!(notification.notification_type, NotificationType::Digest);
+    }
+}
@@ -0,0 +1,397 @@
+//! Prompt A/B Experiments
+//!
+//! Compares two prompt instructions on live traffic:
+//! - Each user is assigned variant A or B for the whole experiment (hash of
+//!   the experiment start time and user id, so the split is fresh each run)
+//! - The chosen instruction is appended to the prompt
+//! - Reflection scores, thumbs feedback and cost are tallied per variant
+//!
+//! Tallies are saved to a JSON file (see `ExperimentTracker::open`) so a
+//! restart doesn't lose them, along with the results of the last stopped
+//! experiment. Feedback on responses sent before a restart isn't credited.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Responses whose variant is remembered for late feedback (oldest dropped first)
+const MAX_TRACKED_RESPONSES: usize = 500;
+
+/// |t| above this counts as significant (~95% two-sided for large samples)
+const SIGNIFICANCE_T: f64 = 1.96;
+
+/// Quality samples needed per variant before a significance call is made
+const MIN_SAMPLES: u64 = 5;
+
+/// Which arm a response belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Variant {
+    A,
+    B,
+}
+
+impl Variant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Variant::A => "A",
+            Variant::B => "B",
+        }
+    }
+}
+
+/// Running tallies for one variant
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VariantStats {
+    /// Instruction appended to the prompt (empty = control)
+    pub instruction: String,
+    pub responses: u64,
+    pub quality_samples: u64,
+    quality_sum: f64,
+    quality_sum_sq: f64,
+    pub thumbs_up: u64,
+    pub thumbs_down: u64,
+    pub cost_usd: f64,
+}
+
+impl VariantStats {
+    fn new(instruction: &str) -> Self {
+        Self {
+            instruction: instruction.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Mean reflection score, None without samples
+    pub fn mean_quality(&self) -> Option<f64> {
+        (self.quality_samples > 0).then(|| self.quality_sum / self.quality_samples as f64)
+    }
+
+    /// Sample variance of the reflection score
+    fn quality_variance(&self) -> Option<f64> {
+        let n = self.quality_samples as f64;
+        (self.quality_samples > 1).then(|| ((self.quality_sum_sq - self.quality_sum * self.quality_sum / n) / (n - 1.0)).max(0.0))
+    }
+
+    pub fn mean_cost(&self) -> Option<f64> {
+        (self.responses > 0).then(|| self.cost_usd / self.responses as f64)
+    }
+}
+
+/// Snapshot of a running experiment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentStats {
+    pub name: String,
+    pub started_at: i64,
+    pub a: VariantStats,
+    pub b: VariantStats,
+}
+
+impl ExperimentStats {
+    /// Welch's t statistic for mean quality (B minus A), once both arms have
+    /// `MIN_SAMPLES` scores
+    pub fn quality_t(&self) -> Option<f64> {
+        if self.a.quality_samples < MIN_SAMPLES || self.b.quality_samples < MIN_SAMPLES {
+            return None;
+        }
+        let se = (self.a.quality_variance()? / self.a.quality_samples as f64
+            + self.b.quality_variance()? / self.b.quality_samples as f64)
+            .sqrt();
+        let diff = self.b.mean_quality()? - self.a.mean_quality()?;
+        if se == 0.0 {
+            return Some(if diff == 0.0 { 0.0 } else { diff.signum() * f64::INFINITY });
+        }
+        Some(diff / se)
+    }
+
+    /// Plain-language verdict on the quality difference
+    pub fn verdict(&self) -> String {
+        match self.quality_t() {
+            None => format!("Not enough data (need {} scored responses per variant)", MIN_SAMPLES),
+            Some(t) if t.abs() >= SIGNIFICANCE_T => format!(
+                "Variant {} scores higher (significant, t = {:.2})",
+                if t > 0.0 { "B" } else { "A" },
+                t
+            ),
+            Some(t) => format!("No significant difference (t = {:.2})", t),
+        }
+    }
+
+    pub fn format(&self) -> String {
+        let describe = |label: &str, v: &VariantStats| {
+            format!(
+                "Variant {}: {}\n\
+                - Responses: {}\n\
+                - Quality: {} ({} scored)\n\
+                - Thumbs: 👍 {} / 👎 {}\n\
+                - Avg cost: {}",
+                label,
+                if v.instruction.is_empty() { "(control, no instruction)".to_string() } else { format!("\"{}\"", v.instruction) },
+                v.responses,
+                v.mean_quality().map(|q| format!("{:.0}%", q * 100.0)).unwrap_or_else(|| "-".to_string()),
+                v.quality_samples,
+                v.thumbs_up,
+                v.thumbs_down,
+                v.mean_cost().map(|c| format!("${:.4}", c)).unwrap_or_else(|| "-".to_string()),
+            )
+        };
+        format!(
+            "🧪 Experiment: {}\n\n{}\n\n{}\n\n{}",
+            self.name,
+            describe("A", &self.a),
+            describe("B", &self.b),
+            self.verdict()
+        )
+    }
+}
+
+#[derive(Debug)]
+struct Experiment {
+    stats: ExperimentStats,
+    /// retrieval id -> variant, for quality/thumbs that arrive later
+    responses: HashMap<String, Variant>,
+    order: VecDeque<String>,
+}
+
+impl Experiment {
+    fn arm(&mut self, variant: Variant) -> &mut VariantStats {
+        match variant {
+            Variant::A => &mut self.stats.a,
+            Variant::B => &mut self.stats.b,
+        }
+    }
+}
+
+/// What `ExperimentTracker` saves to disk
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedExperiments {
+    current: Option<ExperimentStats>,
+    last: Option<ExperimentStats>,
+}
+
+/// Shared experiment state (cheap to clone into background tasks)
+#[derive(Clone, Default)]
+pub struct ExperimentTracker {
+    current: Arc<RwLock<Option<Experiment>>>,
+    /// Final results of the last stopped experiment
+    last: Arc<RwLock<Option<ExperimentStats>>>,
+    /// Where tallies are saved (None = memory only)
+    path: Option<Arc<PathBuf>>,
+}
+
+impl ExperimentTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resume the experiment saved at `path` and save every change back to it
+    ///
+    /// A missing or unreadable file starts with no experiment.
+    pub fn open(path: &Path) -> Self {
+        let saved: SavedExperiments = match std::fs::read_to_string(path) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable experiment state {:?}: {}", path, e);
+                SavedExperiments::default()
+            }),
+            Err(_) => SavedExperiments::default(),
+        };
+        let current = saved.current.map(|stats| Experiment {
+            stats,
+            responses: HashMap::new(),
+            order: VecDeque::new(),
+        });
+        Self {
+            current: Arc::new(RwLock::new(current)),
+            last: Arc::new(RwLock::new(saved.last)),
+            path: Some(Arc::new(path.to_path_buf())),
+        }
+    }
+
+    /// Start a new experiment, discarding any previous one
+    pub fn start(&self, name: &str, instruction_a: &str, instruction_b: &str) {
+        let experiment = Experiment {
+            stats: ExperimentStats {
+                name: name.to_string(),
+                started_at: chrono::Utc::now().timestamp_millis(),
+                a: VariantStats::new(instruction_a.trim()),
+                b: VariantStats::new(instruction_b.trim()),
+            },
+            responses: HashMap::new(),
+            order: VecDeque::new(),
+        };
+        *self.current.write().unwrap() = Some(experiment);
+        self.save();
+    }
+
+    /// Stop the experiment, returning its final stats
+    pub fn stop(&self) -> Option<ExperimentStats> {
+        let stats = self.current.write().unwrap().take().map(|e| e.stats)?;
+        *self.last.write().unwrap() = Some(stats.clone());
+        self.save();
+        Some(stats)
+    }
+
+    /// Final results of the last stopped experiment
+    pub fn last_result(&self) -> Option<ExperimentStats> {
+        self.last.read().unwrap().clone()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.current.read().unwrap().is_some()
+    }
+
+    /// The user's variant and its instruction, if an experiment is running
+    pub fn assign(&self, user_id: i64) -> Option<(Variant, String)> {
+        let current = self.current.read().unwrap();
+        let experiment = current.as_ref()?;
+        let mut hasher = DefaultHasher::new();
+        (experiment.stats.started_at, user_id).hash(&mut hasher);
+        let variant = if hasher.finish() & 1 == 0 { Variant::A } else { Variant::B };
+        let instruction = match variant {
+            Variant::A => experiment.stats.a.instruction.clone(),
+            Variant::B => experiment.stats.b.instruction.clone(),
+        };
+        Some((variant, instruction))
+    }
+
+    /// Count a response for its variant so later feedback can find it
+    pub fn record_response(&self, retrieval_id: &str, variant: Variant, cost_usd: f64) {
+        let mut current = self.current.write().unwrap();
+        let Some(experiment) = current.as_mut() else {
+            return;
+        };
+        let arm = experiment.arm(variant);
+        arm.responses += 1;
+        arm.cost_usd += cost_usd;
+        if experiment.order.len() >= MAX_TRACKED_RESPONSES {
+            if let Some(oldest) = experiment.order.pop_front() {
+                experiment.responses.remove(&oldest);
+            }
+        }
+        experiment.order.push_back(retrieval_id.to_string());
+        experiment.responses.insert(retrieval_id.to_string(), variant);
+        drop(current);
+        self.save();
+    }
+
+    /// Add a reflection score (0-1) for a tracked response
+    pub fn record_quality(&self, retrieval_id: &str, quality: f64) {
+        self.with_response(retrieval_id, |arm| {
+            arm.quality_samples += 1;
+            arm.quality_sum += quality;
+            arm.quality_sum_sq += quality * quality;
+        });
+    }
+
+    /// Add thumbs feedback for a tracked response
+    pub fn record_thumbs(&self, retrieval_id: &str, positive: bool) {
+        self.with_response(retrieval_id, |arm| {
+            if positive {
+                arm.thumbs_up += 1;
+            } else {
+                arm.thumbs_down += 1;
+            }
+        });
+    }
+
+    fn with_response(&self, retrieval_id: &str, update: impl FnOnce(&mut VariantStats)) {
+        let mut current = self.current.write().unwrap();
+        let Some(experiment) = current.as_mut() else {
+            return;
+        };
+        let Some(&variant) = experiment.responses.get(retrieval_id) else {
+            return;
+        };
+        update(experiment.arm(variant));
+        drop(current);
+        self.save();
+    }
+
+    pub fn stats(&self) -> Option<ExperimentStats> {
+        self.current.read().unwrap().as_ref().map(|e| e.stats.clone())
+    }
+
+    /// Write the running and last experiment to `path`, if set
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let saved = SavedExperiments {
+            current: self.stats(),
+            last: self.last_result(),
+        };
+        let result = serde_json::to_vec_pretty(&saved)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, path.as_path())?;
+                Ok(())
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to save experiment state to {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sticky_assignment_and_stats() {
+        let tracker = ExperimentTracker::new();
+        assert!(tracker.assign(1).is_none());
+        tracker.start("concise", "", "Answer in at most three sentences.");
+
+        let (variant, _) = tracker.assign(42).unwrap();
+        assert!((0..10).all(|_| tracker.assign(42).unwrap().0 == variant));
+        let users: Vec<Variant> = (0..64).map(|u| tracker.assign(u).unwrap().0).collect();
+        assert!(users.contains(&Variant::A) && users.contains(&Variant::B));
+
+        for i in 0..6 {
+            let a = format!("a{}", i);
+            let b = format!("b{}", i);
+            tracker.record_response(&a, Variant::A, 0.01);
+            tracker.record_response(&b, Variant::B, 0.02);
+            tracker.record_quality(&a, 0.5 + 0.01 * i as f64);
+            tracker.record_quality(&b, 0.8 + 0.01 * i as f64);
+        }
+        tracker.record_thumbs("b0", true);
+        tracker.record_thumbs("unknown", false);
+
+        let stats = tracker.stats().unwrap();
+        assert_eq!((stats.a.responses, stats.b.thumbs_up, stats.a.thumbs_down), (6, 1, 0));
+        assert!((stats.b.mean_cost().unwrap() - 0.02).abs() < 1e-9);
+        assert!(stats.quality_t().unwrap() > SIGNIFICANCE_T);
+        assert!(stats.verdict().starts_with("Variant B scores higher"));
+        assert!(stats.format().contains("(control, no instruction)"));
+
+        assert!(tracker.stop().is_some());
+        assert!(!tracker.is_running());
+    }
+
+    #[test]
+    fn test_experiment_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("experiments.json");
+
+        let tracker = ExperimentTracker::open(&path);
+        tracker.start("concise", "", "Be brief.");
+        tracker.record_response("r1", Variant::B, 0.03);
+        tracker.record_quality("r1", 0.9);
+
+        let reopened = ExperimentTracker::open(&path);
+        let stats = reopened.stats().unwrap();
+        assert_eq!(stats.name, "concise");
+        assert_eq!((stats.b.responses, stats.b.quality_samples), (1, 1));
+        assert_eq!(reopened.assign(7).unwrap().0, tracker.assign(7).unwrap().0);
+
+        reopened.stop();
+        let reopened = ExperimentTracker::open(&path);
+        assert!(!reopened.is_running());
+        assert_eq!(reopened.last_result().unwrap().b.responses, 1);
+    }
+}
@@ -10,12 +10,20 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info};
 
-use crate::memory::MemoryStore;
+use super::experiment::ExperimentTracker;
+use crate::memory::{FeedbackReset, MemoryStore};
+
+/// Responses kept for thumbs-up/down feedback (oldest dropped first)
+const MAX_TRACKED_RESPONSES: usize = 200;
+
+/// (retrieval ID, memory IDs injected into the response), oldest first
+type ResponseRetrievals = VecDeque<(String, Vec<String>)>;
 
 /// Types of feedback signals
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -74,6 +82,10 @@ pub struct FeedbackStats {
     pub adjustments_made: u64,
     pub memories_boosted: u64,
     pub memories_penalized: u64,
+    /// Responses rated helpful via thumbs-up
+    pub thumbs_up: u64,
+    /// Responses rated unhelpful via thumbs-down
+    pub thumbs_down: u64,
 }
 
 /// Configuration for feedback loop
@@ -112,6 +124,12 @@ pub struct FeedbackLoop {
     stats: Arc<RwLock<FeedbackStats>>,
     /// Recently retrieved memory IDs (for tracking ignored signals)
     recent_retrievals: Arc<RwLock<Vec<(String, i64)>>>,
+    /// Memory IDs injected into each response, by retrieval ID
+    response_retrievals: Arc<RwLock<ResponseRetrievals>>,
+    /// Sequence for retrieval IDs
+    next_retrieval: AtomicU64,
+    /// Prompt A/B experiment, credited with thumbs feedback
+    experiments: ExperimentTracker,
 }
 
 impl FeedbackLoop {
@@ -127,20 +145,138 @@ impl FeedbackLoop {
             pending_signals: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(FeedbackStats::default())),
             recent_retrievals: Arc::new(RwLock::new(Vec::new())),
+            response_retrievals: Arc::new(RwLock::new(VecDeque::new())),
+            next_retrieval: AtomicU64::new(0),
+            experiments: ExperimentTracker::new(),
         }
     }
 
+    /// Use a tracker opened from disk so experiments survive restarts
+    pub fn with_experiments(mut self, experiments: ExperimentTracker) -> Self {
+        self.experiments = experiments;
+        self
+    }
+
+    /// The prompt experiment tracker (shares state with this loop)
+    pub fn experiments(&self) -> &ExperimentTracker {
+        &self.experiments
+    }
+
     /// Record that memories were retrieved for a query
-    pub async fn record_retrieval(&self, memory_ids: &[String]) {
+    ///
+    /// Returns a retrieval ID that explicit feedback on the response can refer to.
+    pub async fn record_retrieval(&self, memory_ids: &[String]) -> String {
         let now = chrono::Utc::now().timestamp();
-        let mut recent = self.recent_retrievals.write().await;
+        {
+            let mut recent = self.recent_retrievals.write().await;
+
+            for id in memory_ids {
+                recent.push((id.clone(), now));
+            }
 
-        for id in memory_ids {
-            recent.push((id.clone(), now));
+            // Keep only last 5 minutes of retrievals
+            recent.retain(|(_, ts)| now - ts < 300);
         }
 
-        // Keep only last 5 minutes of retrievals
-        recent.retain(|(_, ts)| now - ts < 300);
+        let retrieval_id = format!(
+            "{}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            self.next_retrieval.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut responses = self.response_retrievals.write().await;
+        if responses.len() >= MAX_TRACKED_RESPONSES {
+            responses.pop_front();
+        }
+        responses.push_back((retrieval_id.clone(), memory_ids.to_vec()));
+        retrieval_id
+    }
+
+    /// Apply explicit thumbs-up/down feedback on a response
+    ///
+    /// Adjusts the confidence of the memories injected into that response right
+    /// away - explicit feedback doesn't wait for `signals_threshold`. Each
+    /// response can be rated once; returns None if the retrieval is unknown,
+    /// expired or already rated, otherwise the number of memories adjusted.
+    /// Adjustments are logged per user, see `reset`.
+    pub async fn record_response_feedback(
+        &self,
+        retrieval_id: &str,
+        positive: bool,
+        user_id: i64,
+        memory: &MemoryStore,
+    ) -> Result<Option<usize>> {
+        let memory_ids = {
+            let mut responses = self.response_retrievals.write().await;
+            let Some(pos) = responses.iter().position(|(id, _)| id == retrieval_id) else {
+                return Ok(None);
+            };
+            responses.remove(pos).map(|(_, ids)| ids).unwrap_or_default()
+        };
+        self.experiments.record_thumbs(retrieval_id, positive);
+
+        let signal = if positive { FeedbackSignal::Positive } else { FeedbackSignal::Negative };
+        let delta = signal.confidence_delta();
+        let mut adjusted = 0;
+        for id in &memory_ids {
+            if let Some(confidence) = memory.apply_feedback(
+                id,
+                user_id,
+                positive,
+                delta,
+                self.config.min_confidence,
+                self.config.max_confidence,
+            )? {
+                debug!(
+                    "Explicit {} feedback for {}: confidence now {:.2}",
+                    signal.as_str(),
+                    &id[..8.min(id.len())],
+                    confidence
+                );
+                adjusted += 1;
+            }
+        }
+
+        let mut stats = self.stats.write().await;
+        stats.total_signals += memory_ids.len() as u64;
+        stats.adjustments_made += adjusted as u64;
+        if positive {
+            stats.thumbs_up += 1;
+            stats.positive_count += memory_ids.len() as u64;
+            stats.memories_boosted += adjusted as u64;
+        } else {
+            stats.thumbs_down += 1;
+            stats.negative_count += memory_ids.len() as u64;
+            stats.memories_penalized += adjusted as u64;
+        }
+
+        info!("Response rated {} ({} memories adjusted)", signal.as_str(), adjusted);
+        Ok(Some(adjusted))
+    }
+
+    /// Undo a user's thumbs-up/down adjustments, all of them or on one memory
+    ///
+    /// Restores the affected memories' confidence, removes the ratings from
+    /// the persisted log and drops signals still pending for those memories.
+    pub async fn reset(&self, user_id: i64, memory_id: Option<&str>, memory: &MemoryStore) -> Result<FeedbackReset> {
+        let reset = memory.reset_feedback(
+            user_id,
+            memory_id,
+            self.config.min_confidence,
+            self.config.max_confidence,
+        )?;
+
+        let mut pending = self.pending_signals.write().await;
+        for id in reset.memory_ids.iter().map(String::as_str).chain(memory_id) {
+            pending.remove(id);
+        }
+
+        info!(
+            "Feedback reset for user {}: {} ratings on {} memories",
+            user_id,
+            reset.ratings,
+            reset.memory_ids.len()
+        );
+        Ok(reset)
     }
 
     /// Record feedback signal for a memory
@@ -179,7 +315,7 @@ impl FeedbackLoop {
     }
 
     /// Process pending signals and adjust confidence scores
-    pub async fn process_signals(&self, memory: &std::sync::Mutex<MemoryStore>) -> Result<usize> {
+    pub async fn process_signals(&self, memory: &MemoryStore) -> Result<usize> {
         if !self.config.auto_adjust {
             return Ok(0);
         }
@@ -200,9 +336,7 @@ impl FeedbackLoop {
             }
 
             // Apply adjustment
-            let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-
-            if let Ok(Some(entry)) = store.get_by_id(memory_id) {
+            if let Ok(Some(entry)) = memory.get_by_id(memory_id) {
                 let new_confidence = (entry.confidence + delta)
                     .max(self.config.min_confidence)
                     .min(self.config.max_confidence);
@@ -281,7 +415,7 @@ impl FeedbackLoop {
         &self,
         correction: &str,
         related_memory_ids: &[String],
-        memory: &std::sync::Mutex<MemoryStore>,
+        memory: &MemoryStore,
         user_id: i64,
     ) -> Result<()> {
         if !self.config.learn_corrections {
@@ -295,12 +429,11 @@ impl FeedbackLoop {
         }
 
         // Store the correction as new knowledge
-        let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-
-        let _ = store.learn(
+        let _ = memory.learn(
             correction,
             "correction",
             &format!("user_correction_{}", user_id),
+            Some(user_id),
             0.95, // High confidence for explicit corrections
         );
 
@@ -349,6 +482,7 @@ impl FeedbackLoop {
         let stats = self.stats.read().await;
         format!(
             "Feedback Loop Statistics\n\n\
+            Responses rated: 👍 {} / 👎 {}\n\
             Total signals: {}\n\
             Positive: {}\n\
             Negative: {}\n\
@@ -356,6 +490,8 @@ impl FeedbackLoop {
             Adjustments made: {}\n\
             Memories boosted: {}\n\
             Memories penalized: {}",
+            stats.thumbs_up,
+            stats.thumbs_down,
             stats.total_signals,
             stats.positive_count,
             stats.negative_count,
@@ -410,4 +546,34 @@ mod tests {
         assert_eq!(stats.total_signals, 1);
         assert_eq!(stats.positive_count, 1);
     }
+
+    #[tokio::test]
+    async fn test_thumbs_down_penalizes_retrieved_memories() {
+        let path = std::path::PathBuf::from("/tmp/claudebot_test_feedback_thumbs.db");
+        let _ = std::fs::remove_file(&path);
+        let store = MemoryStore::open(&path).unwrap();
+        let used = store.learn("Deploys go through staging", "facts", "test", None, 0.8).unwrap();
+        let other = store.learn("Unrelated fact", "facts", "test", None, 0.8).unwrap();
+
+        let feedback = FeedbackLoop::new();
+        let retrieval = feedback.record_retrieval(std::slice::from_ref(&used)).await;
+
+        let adjusted = feedback.record_response_feedback(&retrieval, false, 7, &store).await.unwrap();
+        assert_eq!(adjusted, Some(1));
+        // Already rated
+        assert_eq!(feedback.record_response_feedback(&retrieval, true, 7, &store).await.unwrap(), None);
+
+        assert!((store.get_by_id(&used).unwrap().unwrap().confidence - 0.7).abs() < 1e-9);
+        assert!((store.get_by_id(&other).unwrap().unwrap().confidence - 0.8).abs() < 1e-9);
+
+        let stats = feedback.stats().await;
+        assert_eq!(stats.thumbs_down, 1);
+        assert_eq!(stats.memories_penalized, 1);
+
+        feedback.record_signal(&used, FeedbackSignal::Ignored, None).await;
+        let reset = feedback.reset(7, None, &store).await.unwrap();
+        assert_eq!(reset.memory_ids, vec![used.clone()]);
+        assert!((store.get_by_id(&used).unwrap().unwrap().confidence - 0.8).abs() < 1e-9);
+        assert!(feedback.pending_signals.read().await.is_empty());
+    }
 }
@@ -178,6 +178,86 @@ impl Goal {
     }
 }
 
+/// Minimum score for a goal to match a free-text query
+pub const GOAL_MATCH_THRESHOLD: f64 = 0.5;
+
+/// Matches within this score of the best are considered ambiguous
+pub const GOAL_MATCH_MARGIN: f64 = 0.1;
+
+/// Filler words ignored when matching goal descriptions
+const MATCH_STOPWORDS: &[&str] = &[
+    "a", "an", "the", "to", "of", "for", "and", "on", "in", "my", "with", "goal",
+];
+
+/// A goal scored against a free-text query
+#[derive(Debug, Clone)]
+pub struct GoalMatch {
+    pub goal: Goal,
+    /// Match score in [0, 1]
+    pub score: f64,
+}
+
+/// Outcome of resolving a free-text query to a single goal
+#[derive(Debug, Clone)]
+pub enum GoalResolution {
+    /// One goal clearly matches best
+    Unique(Goal),
+    /// Several goals score within `GOAL_MATCH_MARGIN` of the best
+    Ambiguous(Vec<GoalMatch>),
+    NotFound,
+}
+
+/// Lowercased content words of a text
+fn match_tokens(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty() && !MATCH_STOPWORDS.contains(t))
+        .map(String::from)
+        .collect()
+}
+
+/// Whether two tokens refer to the same word (prefix or one typo)
+fn tokens_match(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let shorter = a.chars().count().min(b.chars().count());
+    (shorter >= 4 && (a.starts_with(b) || b.starts_with(a))) || (shorter >= 5 && edit_distance(a, b) <= 1)
+}
+
+/// Levenshtein distance
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// Score how well a query describes a goal
+///
+/// Weighted token overlap: mostly how much of the query is covered by the
+/// description, with a smaller share for how much of the description the
+/// query covers, so short queries still match and word order doesn't matter.
+pub fn goal_match_score(query: &str, description: &str) -> f64 {
+    let query = match_tokens(query);
+    let description = match_tokens(description);
+    if query.is_empty() || description.is_empty() {
+        return 0.0;
+    }
+
+    let covered = |from: &[String], by: &[String]| {
+        from.iter().filter(|t| by.iter().any(|o| tokens_match(t, o))).count() as f64 / from.len() as f64
+    };
+    0.7 * covered(&query, &description) + 0.3 * covered(&description, &query)
+}
+
 /// Goal tracker with SQLite persistence
 pub struct GoalTracker {
     conn: Mutex<Connection>,
@@ -384,6 +464,37 @@ impl GoalTracker {
         self.get_goal(goal_id).await
     }
 
+    /// Open goals scoring at least `GOAL_MATCH_THRESHOLD` for a query, best first
+    pub async fn find_matching_goals(&self, user_id: i64, query: &str) -> Vec<GoalMatch> {
+        let mut matches: Vec<GoalMatch> = self
+            .get_active_goals(user_id)
+            .await
+            .into_iter()
+            .map(|goal| GoalMatch {
+                score: goal_match_score(query, &goal.description),
+                goal,
+            })
+            .filter(|m| m.score >= GOAL_MATCH_THRESHOLD)
+            .collect();
+        matches.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        matches
+    }
+
+    /// Resolve a query to one open goal, or the close candidates if ambiguous
+    pub async fn resolve_goal(&self, user_id: i64, query: &str) -> GoalResolution {
+        let mut matches = self.find_matching_goals(user_id, query).await;
+        let Some(best) = matches.first().map(|m| m.score) else {
+            return GoalResolution::NotFound;
+        };
+
+        matches.retain(|m| best - m.score <= GOAL_MATCH_MARGIN);
+        if matches.len() == 1 {
+            GoalResolution::Unique(matches.remove(0).goal)
+        } else {
+            GoalResolution::Ambiguous(matches)
+        }
+    }
+
     /// Extract goals from a user message
     ///
     /// Looks for patterns indicating task intentions:
@@ -504,6 +615,12 @@ impl GoalTracker {
         s
     }
 
+    /// Database size and row counts per table
+    pub fn storage_stats(&self) -> Result<crate::storage::DbStorage> {
+        let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+        crate::storage::sqlite_storage(&conn, "goals")
+    }
+
     /// Get goal statistics for a user
     pub fn get_stats(&self, user_id: i64) -> Result<GoalStats> {
         let conn = self.conn.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
@@ -615,4 +732,28 @@ mod tests {
         assert_eq!(stats.active, 2);
         assert_eq!(stats.completed, 1);
     }
+
+    #[tokio::test]
+    async fn test_fuzzy_goal_resolution() {
+        let tracker = GoalTracker::new();
+        let auth = tracker.create_goal(123, "Refactor authentication").await;
+        tracker.create_goal(123, "Fix login bug").await;
+        tracker.create_goal(123, "Fix login page").await;
+        tracker.create_goal(123, "Write release notes").await;
+
+        assert!(goal_match_score("the auth refactor", "Refactor authentication") > 0.9);
+        assert!(goal_match_score("refactr authentication", "Refactor authentication") > 0.9);
+        assert_eq!(goal_match_score("deploy", "Refactor authentication"), 0.0);
+
+        match tracker.resolve_goal(123, "the auth refactor").await {
+            GoalResolution::Unique(goal) => assert_eq!(goal.id, auth.id),
+            other => panic!("expected unique match, got {:?}", other),
+        }
+        match tracker.resolve_goal(123, "fix login").await {
+            GoalResolution::Ambiguous(matches) => assert_eq!(matches.len(), 2),
+            other => panic!("expected ambiguous match, got {:?}", other),
+        }
+        assert!(matches!(tracker.resolve_goal(123, "deploy to prod").await, GoalResolution::NotFound));
+        assert!(matches!(tracker.resolve_goal(456, "the auth refactor").await, GoalResolution::NotFound));
+    }
 }
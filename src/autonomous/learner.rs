@@ -7,6 +7,8 @@
 //! - Entity recognition and graph building
 //! - Preference detection from behavior
 //! - Confidence scoring based on evidence
+//! - Category inference into a configurable taxonomy (LLM, batched, with a
+//!   keyword fallback when Ollama is unavailable)
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -34,6 +36,42 @@ pub struct LearningConfig {
     pub min_message_length: usize,
     /// Patterns to skip (greetings, confirmations)
     pub skip_patterns: Vec<String>,
+    /// Categories facts are classified into (the keyword fallback's
+    /// categories are used when they appear here)
+    pub taxonomy: Vec<String>,
+    /// LLM classifications below this confidence fall back to keywords
+    pub category_min_confidence: f32,
+    /// Facts classified per LLM call
+    pub category_batch_size: usize,
+}
+
+/// Default category taxonomy
+pub const DEFAULT_TAXONOMY: &[&str] =
+    &["preference", "project", "technical", "personal", "task", "decision", "note", "fact"];
+
+impl LearningConfig {
+    /// Defaults overridden by `CLAUDEBOT_CATEGORIES` (comma-separated),
+    /// `CLAUDEBOT_CATEGORY_MIN_CONFIDENCE` and `CLAUDEBOT_CATEGORY_BATCH_SIZE`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(raw) = std::env::var("CLAUDEBOT_CATEGORIES") {
+            let taxonomy: Vec<String> = raw
+                .split(',')
+                .map(|c| c.trim().to_lowercase())
+                .filter(|c| !c.is_empty())
+                .collect();
+            if !taxonomy.is_empty() {
+                config.taxonomy = taxonomy;
+            }
+        }
+        if let Some(v) = std::env::var("CLAUDEBOT_CATEGORY_MIN_CONFIDENCE").ok().and_then(|v| v.parse::<f32>().ok()) {
+            config.category_min_confidence = v.clamp(0.0, 1.0);
+        }
+        if let Some(v) = std::env::var("CLAUDEBOT_CATEGORY_BATCH_SIZE").ok().and_then(|v| v.parse::<usize>().ok()) {
+            config.category_batch_size = v.max(1);
+        }
+        config
+    }
 }
 
 impl Default for LearningConfig {
@@ -52,6 +90,9 @@ impl Default for LearningConfig {
                 "yes".to_string(),
                 "no".to_string(),
             ],
+            taxonomy: DEFAULT_TAXONOMY.iter().map(|c| c.to_string()).collect(),
+            category_min_confidence: 0.6,
+            category_batch_size: 10,
         }
     }
 }
@@ -66,6 +107,31 @@ pub struct LearnedFact {
     pub entities: Vec<String>,
 }
 
+/// A fact's inferred category
+#[derive(Debug, Clone, PartialEq)]
+pub struct Categorization {
+    pub category: String,
+    pub confidence: f32,
+    /// False when the keyword heuristic decided
+    pub by_llm: bool,
+}
+
+/// Keyword heuristic used when the LLM is unavailable or unsure
+pub fn categorize_by_keywords(fact: &str) -> &'static str {
+    let lower = fact.to_lowercase();
+    if lower.contains("prefer") || lower.contains("like") || lower.contains("want") {
+        "preference"
+    } else if lower.contains("project") || lower.contains("working on") || lower.contains("building") {
+        "project"
+    } else if lower.contains("remember") || lower.contains("note") || lower.contains("important") {
+        "note"
+    } else if lower.contains("api") || lower.contains("code") || lower.contains("function") {
+        "technical"
+    } else {
+        "fact"
+    }
+}
+
 /// Statistics about learning activity
 #[derive(Debug, Default, Clone)]
 pub struct LearningStats {
@@ -146,7 +212,7 @@ impl AutonomousLearner {
             if let Ok(extracted) = self.extract_facts(message, llama).await {
                 for fact in extracted {
                     if fact.confidence >= self.config.min_confidence {
-                        facts.push(fact);
+                        facts.push(self.normalize_category(fact));
                     }
                 }
             }
@@ -186,8 +252,14 @@ impl AutonomousLearner {
             return Ok(0);
         }
 
-        // Use Llama to extract entities
-        let entities = llama.extract_entities(message).await?;
+        // Use Llama to extract entities, constrained to the graph's taxonomy
+        let types = graph
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?
+            .taxonomy()
+            .types()
+            .to_vec();
+        let entities = llama.extract_entities(message, &types).await?;
 
         if entities.is_empty() {
             return Ok(0);
@@ -214,6 +286,15 @@ impl AutonomousLearner {
             }
         }
 
+        // Link entities mentioned together
+        let mentioned: Vec<(&str, &str)> = entities
+            .iter()
+            .map(|e| (e.entity_type.as_str(), e.name.as_str()))
+            .collect();
+        if let Err(e) = store.link_co_occurring(message, &mentioned) {
+            debug!("Failed to link co-occurring entities: {}", e);
+        }
+
         // Store relations
         for relation in relations {
             // Find entity IDs
@@ -237,29 +318,34 @@ impl AutonomousLearner {
         &self,
         facts: &[LearnedFact],
         user_id: i64,
-        memory: &std::sync::Mutex<MemoryStore>,
+        memory: &MemoryStore,
     ) -> Result<usize> {
         if facts.is_empty() {
             return Ok(0);
         }
 
-        let store = memory.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
         let source = format!("auto_learn_user_{}", user_id);
-        let mut stored = 0;
-
-        for fact in facts {
-            match store.learn(&fact.content, &fact.category, &source, fact.confidence as f64) {
-                Ok(id) => {
-                    debug!("Auto-stored fact: {} ({})", &id[..8], fact.category);
-                    stored += 1;
+        let entries: Vec<_> = facts
+            .iter()
+            .map(|fact| (fact.content.as_str(), fact.category.as_str(), source.as_str(), Some(user_id), fact.confidence as f64, None))
+            .collect();
+
+        // One transaction for the whole burst
+        match memory.learn_batch(&entries) {
+            Ok(batch) => {
+                for (index, reason) in &batch.rejected {
+                    debug!("Auto-learn skipped a {} fact: {}", facts[*index].category, reason);
                 }
-                Err(e) => {
-                    warn!("Failed to store fact: {}", e);
+                for id in &batch.ids {
+                    debug!("Auto-stored fact: {}", &id[..8]);
                 }
+                Ok(batch.ids.len())
+            }
+            Err(e) => {
+                warn!("Failed to store {} facts: {}", facts.len(), e);
+                Ok(0)
             }
         }
-
-        Ok(stored)
     }
 
     /// Extract facts using LLM
@@ -368,6 +454,125 @@ Facts (JSON only, empty array if no facts):"#,
         None
     }
 
+    /// Categorize one fact (see `categorize_batch`)
+    pub async fn categorize(&self, fact: &str, llama: &LlamaWorker) -> Categorization {
+        self.categorize_batch(&[fact], llama).await.pop().unwrap_or_else(|| self.keyword_category(fact))
+    }
+
+    /// Categorize facts into the taxonomy, `category_batch_size` per LLM call
+    ///
+    /// Falls back to the keyword heuristic per fact when Ollama is down, the
+    /// reply doesn't parse, or the classification is unsure or off-taxonomy.
+    pub async fn categorize_batch(&self, facts: &[&str], llama: &LlamaWorker) -> Vec<Categorization> {
+        if facts.is_empty() {
+            return vec![];
+        }
+        if !llama.is_available().await {
+            return facts.iter().map(|f| self.keyword_category(f)).collect();
+        }
+
+        let mut results = Vec::with_capacity(facts.len());
+        for batch in facts.chunks(self.config.category_batch_size.max(1)) {
+            let guesses = match llama.generate(&self.category_prompt(batch)).await {
+                Ok(response) => self.parse_categories(&response, batch.len()),
+                Err(e) => {
+                    debug!("Category inference failed: {}", e);
+                    vec![None; batch.len()]
+                }
+            };
+            results.extend(
+                batch
+                    .iter()
+                    .zip(guesses)
+                    .map(|(fact, guess)| guess.unwrap_or_else(|| self.keyword_category(fact))),
+            );
+        }
+        results
+    }
+
+    fn category_prompt(&self, facts: &[&str]) -> String {
+        let numbered: String = facts
+            .iter()
+            .enumerate()
+            .map(|(i, f)| format!("{}. {}\n", i + 1, f.replace('\n', " ")))
+            .collect();
+        format!(
+            r#"Classify each fact into exactly one category.
+
+Categories: {}
+
+Facts:
+{}
+Return a JSON array with one object per fact, e.g.
+[{{"index": 1, "category": "project", "confidence": 0.8}}]
+
+JSON only:"#,
+            self.config.taxonomy.join(", "),
+            numbered
+        )
+    }
+
+    /// Parse an LLM category reply; None where a fact got no usable answer
+    fn parse_categories(&self, response: &str, count: usize) -> Vec<Option<Categorization>> {
+        #[derive(Deserialize)]
+        struct RawCategory {
+            index: usize,
+            category: String,
+            #[serde(default)]
+            confidence: f32,
+        }
+
+        let mut results = vec![None; count];
+        let (Some(start), Some(end)) = (response.find('['), response.rfind(']')) else {
+            return results;
+        };
+        if end < start {
+            return results;
+        }
+        let Ok(raw) = serde_json::from_str::<Vec<RawCategory>>(&response[start..=end]) else {
+            return results;
+        };
+        for guess in raw {
+            let category = guess.category.trim().to_lowercase();
+            let usable = guess.confidence >= self.config.category_min_confidence
+                && self.config.taxonomy.contains(&category);
+            if let Some(slot) = guess.index.checked_sub(1).and_then(|i| results.get_mut(i)) {
+                if usable {
+                    *slot = Some(Categorization {
+                        category,
+                        confidence: guess.confidence.min(1.0),
+                        by_llm: true,
+                    });
+                }
+            }
+        }
+        results
+    }
+
+    /// Keyword heuristic, mapped to the last taxonomy entry when off-taxonomy
+    fn keyword_category(&self, fact: &str) -> Categorization {
+        let keyword = categorize_by_keywords(fact);
+        let category = if self.config.taxonomy.iter().any(|c| c == keyword) {
+            keyword.to_string()
+        } else {
+            self.config.taxonomy.last().cloned().unwrap_or_else(|| keyword.to_string())
+        };
+        Categorization {
+            category,
+            confidence: 0.5,
+            by_llm: false,
+        }
+    }
+
+    /// Keep an extracted fact's category if it's in the taxonomy, else use keywords
+    fn normalize_category(&self, mut fact: LearnedFact) -> LearnedFact {
+        fact.category = fact.category.trim().to_lowercase();
+        if !self.config.taxonomy.contains(&fact.category) {
+            fact.category = self.keyword_category(&fact.content).category;
+        }
+        fact
+    }
+
     /// Simple content hash for deduplication
     fn hash_content(&self, content: &str) -> String {
         use sha2::{Sha256, Digest};
@@ -405,6 +610,30 @@ mod tests {
         assert!(fact.content.contains("prefer"));
     }
 
+    #[test]
+    fn test_parse_categories_falls_back_per_fact() {
+        let learner = AutonomousLearner::new();
+        let response = r#"Sure: [{"index": 1, "category": "Decision", "confidence": 0.9},
+            {"index": 2, "category": "weather", "confidence": 0.95},
+            {"index": 3, "category": "task", "confidence": 0.2},
+            {"index": 9, "category": "task", "confidence": 0.9}]"#;
+        let parsed = learner.parse_categories(response, 4);
+        assert_eq!(parsed[0].as_ref().map(|c| c.category.as_str()), Some("decision"));
+        assert!(parsed[1].is_none(), "off-taxonomy");
+        assert!(parsed[2].is_none(), "below min confidence");
+        assert!(parsed[3].is_none(), "missing");
+        assert!(learner.parse_categories("no json here", 2).iter().all(Option::is_none));
+
+        let fallback = learner.keyword_category("We are building a new billing project");
+        assert_eq!((fallback.category.as_str(), fallback.by_llm), ("project", false));
+
+        let custom = AutonomousLearner::with_config(LearningConfig {
+            taxonomy: vec!["work".to_string(), "misc".to_string()],
+            ..LearningConfig::default()
+        });
+        assert_eq!(custom.keyword_category("I prefer tabs").category, "misc");
+    }
+
     #[test]
     fn test_skip_patterns() {
         let learner = AutonomousLearner::new();
@@ -6,6 +6,8 @@
 //! - Background processing (consolidation, cleanup)
 //! - Context continuity across sessions
 //! - Self-improvement through feedback loops
+//! - Daily digest of goals, reminders, and new memories
+//! - A/B experiments on prompt instructions
 //!
 //! Architecture follows the OODA loop (Observe-Orient-Decide-Act):
 //! 1. **Observe**: Extract facts, entities, and intents from messages
@@ -18,9 +20,18 @@ mod context_manager;
 mod background;
 mod goals;
 mod feedback_loop;
+mod digest;
+mod experiment;
 
-pub use learner::{AutonomousLearner, LearnedFact, LearningConfig};
-pub use context_manager::{ContextManager, EnrichedContext, ContextConfig};
-pub use background::{BackgroundProcessor, BackgroundConfig, BackgroundTask};
-pub use goals::{GoalTracker, Goal, GoalStatus, GoalStats};
+pub use learner::{
+    categorize_by_keywords, AutonomousLearner, Categorization, LearnedFact, LearningConfig, DEFAULT_TAXONOMY,
+};
+pub use context_manager::{ConnectedFact, ContextManager, EnrichedContext, ContextConfig, ContextStrategy, ContextTrim};
+pub use background::{
+    out_of_time, BackgroundProcessor, BackgroundConfig, BackgroundTask, RetentionConfig, RetentionReport,
+    TaskProgress,
+};
+pub use goals::{GoalTracker, Goal, GoalMatch, GoalResolution, GoalStatus, GoalStats};
 pub use feedback_loop::{FeedbackLoop, FeedbackSignal, MemoryFeedback};
+pub use digest::{Digest, DigestConfig, DigestSections};
+pub use experiment::{ExperimentStats, ExperimentTracker, Variant, VariantStats};
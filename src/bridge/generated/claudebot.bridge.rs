@@ -38,6 +38,10 @@ pub struct ExecuteRequest {
     pub chat_id: i64,
     #[prost(bool, tag = "5")]
     pub autonomous: bool,
+    /// Client-generated key, reused across retries of one logical request;
+    /// the server replays the cached result instead of re-running
+    #[prost(string, optional, tag = "6")]
+    pub idempotency_key: ::core::option::Option<::prost::alloc::string::String>,
 }
 /// Streaming chunk from Claude CLI
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -80,6 +84,56 @@ pub struct FileReadResponse {
     #[prost(string, optional, tag = "5")]
     pub error: ::core::option::Option<::prost::alloc::string::String>,
 }
+/// Write messages - path, mode and chat_id are read from the first chunk
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileWriteChunk {
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    #[prost(uint32, optional, tag = "3")]
+    pub mode: ::core::option::Option<u32>,
+    #[prost(int64, tag = "4")]
+    pub chat_id: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FileWriteResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(uint64, tag = "2")]
+    pub bytes_written: u64,
+    #[prost(string, optional, tag = "3")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// Shell messages - the command is split into arguments and run without a
+/// shell, so pipes and redirects are not interpreted
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecShellRequest {
+    #[prost(string, tag = "1")]
+    pub command: ::prost::alloc::string::String,
+    #[prost(int64, tag = "2")]
+    pub chat_id: i64,
+}
+/// Output as it arrives; the final chunk carries the outcome
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShellChunk {
+    #[prost(enumeration = "ShellStream", tag = "1")]
+    pub stream: i32,
+    #[prost(string, tag = "2")]
+    pub data: ::prost::alloc::string::String,
+    #[prost(bool, tag = "3")]
+    pub is_final: bool,
+    #[prost(int32, optional, tag = "4")]
+    pub exit_code: ::core::option::Option<i32>,
+    #[prost(bool, tag = "5")]
+    pub truncated: bool,
+    #[prost(bool, tag = "6")]
+    pub timed_out: bool,
+    #[prost(uint64, optional, tag = "7")]
+    pub duration_ms: ::core::option::Option<u64>,
+    #[prost(string, optional, tag = "8")]
+    pub error: ::core::option::Option<::prost::alloc::string::String>,
+}
 /// Spawn a new worker
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SpawnWorkerRequest {
@@ -225,6 +279,35 @@ impl ChunkType {
         }
     }
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ShellStream {
+    Unspecified = 0,
+    Stdout = 1,
+    Stderr = 2,
+}
+impl ShellStream {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unspecified => "SHELL_STREAM_UNSPECIFIED",
+            Self::Stdout => "SHELL_STREAM_STDOUT",
+            Self::Stderr => "SHELL_STREAM_STDERR",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "SHELL_STREAM_UNSPECIFIED" => Some(Self::Unspecified),
+            "SHELL_STREAM_STDOUT" => Some(Self::Stdout),
+            "SHELL_STREAM_STDERR" => Some(Self::Stderr),
+            _ => None,
+        }
+    }
+}
 /// Worker permission levels
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -494,6 +577,56 @@ pub mod bridge_service_client {
                 .insert(GrpcMethod::new("claudebot.bridge.BridgeService", "ReadFile"));
             self.inner.unary(req, path, codec).await
         }
+        /// Write file to remote server (client streaming, chunks in order)
+        pub async fn write_file(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::FileWriteChunk>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileWriteResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/claudebot.bridge.BridgeService/WriteFile",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("claudebot.bridge.BridgeService", "WriteFile"));
+            self.inner.client_streaming(req, path, codec).await
+        }
+        /// Run a shell command (no Claude) and stream its output
+        pub async fn exec_shell(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExecShellRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ShellChunk>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/claudebot.bridge.BridgeService/ExecShell",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("claudebot.bridge.BridgeService", "ExecShell"));
+            self.inner.server_streaming(req, path, codec).await
+        }
         /// Worker management
         pub async fn spawn_worker(
             &mut self,
@@ -667,6 +800,25 @@ pub mod bridge_service_server {
             tonic::Response<super::FileReadResponse>,
             tonic::Status,
         >;
+        /// Write file to remote server (client streaming, chunks in order)
+        async fn write_file(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::FileWriteChunk>>,
+        ) -> std::result::Result<
+            tonic::Response<super::FileWriteResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the ExecShell method.
+        type ExecShellStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::ShellChunk, tonic::Status>,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Run a shell command (no Claude) and stream its output
+        async fn exec_shell(
+            &self,
+            request: tonic::Request<super::ExecShellRequest>,
+        ) -> std::result::Result<tonic::Response<Self::ExecShellStream>, tonic::Status>;
         /// Worker management
         async fn spawn_worker(
             &self,
@@ -968,6 +1120,99 @@ pub mod bridge_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/claudebot.bridge.BridgeService/WriteFile" => {
+                    #[allow(non_camel_case_types)]
+                    struct WriteFileSvc<T: BridgeService>(pub Arc<T>);
+                    impl<
+                        T: BridgeService,
+                    > tonic::server::ClientStreamingService<super::FileWriteChunk>
+                    for WriteFileSvc<T> {
+                        type Response = super::FileWriteResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::FileWriteChunk>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as BridgeService>::write_file(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WriteFileSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.client_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/claudebot.bridge.BridgeService/ExecShell" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExecShellSvc<T: BridgeService>(pub Arc<T>);
+                    impl<
+                        T: BridgeService,
+                    > tonic::server::ServerStreamingService<super::ExecShellRequest>
+                    for ExecShellSvc<T> {
+                        type Response = super::ShellChunk;
+                        type ResponseStream = T::ExecShellStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExecShellRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as BridgeService>::exec_shell(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ExecShellSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/claudebot.bridge.BridgeService/SpawnWorker" => {
                     #[allow(non_camel_case_types)]
                     struct SpawnWorkerSvc<T: BridgeService>(pub Arc<T>);
@@ -1,17 +1,50 @@
 //! gRPC Bridge Client
 //!
 //! Streaming gRPC client with TLS for connecting to the bridge server.
+//!
+//! Calls go through a small pool of channels so concurrent `/bypass` runs
+//! don't share one HTTP/2 connection. A channel is health-checked before use
+//! when its last check is older than `health_check_secs`, and after a call
+//! fails with a transport error. A channel that fails the check is dropped
+//! and reconnected with exponential backoff, so the bot recovers by itself
+//! when the AR server restarts.
 
 use anyhow::Result;
+use std::future::Future;
 use std::path::PathBuf;
-use tonic::transport::{Certificate, Channel, ClientTlsConfig};
-use tracing::{debug, error, info};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
+use tracing::{debug, error, info, warn};
 
 use super::proto::{
-    bridge_service_client::BridgeServiceClient, ExecuteChunk, ExecuteRequest, FileReadRequest,
-    FileReadResponse, HealthRequest, StatusRequest, StatusResponse,
+    bridge_service_client::BridgeServiceClient, ExecShellRequest, ExecuteChunk, ExecuteRequest,
+    FileReadRequest, FileReadResponse, FileWriteChunk, FileWriteResponse, HealthRequest,
+    ShellChunk, StatusRequest, StatusResponse,
 };
 
+/// Chunk size for streamed file writes
+pub const WRITE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Largest file the client will send (the server enforces its own limit)
+pub const MAX_WRITE_BYTES: usize = 10 * 1024 * 1024;
+
+/// How long a health probe may take before the channel counts as down
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long connecting a channel may take; checkouts wait on it, so an
+/// unreachable bridge must fail fast instead of at the OS TCP timeout
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First reconnect delay; doubles per failed attempt up to `MAX_RECONNECT_BACKOFF`
+const RECONNECT_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Upper bound for `pool_size`
+const MAX_POOL_SIZE: usize = 8;
+
 /// gRPC client configuration
 #[derive(Debug, Clone)]
 pub struct GrpcBridgeClientConfig {
@@ -20,6 +53,13 @@ pub struct GrpcBridgeClientConfig {
     pub timeout_seconds: u64,
     pub ca_cert_path: Option<PathBuf>,
     pub domain: Option<String>,
+    /// Retries of a failed Execute (same idempotency key each time)
+    pub max_retries: u32,
+    /// Channels to the server, used round-robin
+    pub pool_size: usize,
+    /// A channel is health-checked before a call when its last check is
+    /// older than this (0 = before every call)
+    pub health_check_secs: u64,
 }
 
 impl Default for GrpcBridgeClientConfig {
@@ -30,24 +70,160 @@ impl Default for GrpcBridgeClientConfig {
             timeout_seconds: 300,
             ca_cert_path: None,
             domain: None,
+            max_retries: 2,
+            pool_size: 2,
+            health_check_secs: 30,
+        }
+    }
+}
+
+/// Delay before reconnect attempt number `failures` (1-based)
+fn reconnect_backoff(failures: u32) -> Duration {
+    RECONNECT_BACKOFF
+        .saturating_mul(1 << failures.saturating_sub(1).min(10))
+        .min(MAX_RECONNECT_BACKOFF)
+}
+
+/// One pooled channel, or when it may next try to connect
+#[derive(Default)]
+struct Slot {
+    client: Option<BridgeServiceClient<Channel>>,
+    /// Last successful health check or connect
+    checked_at: Option<Instant>,
+    /// Failed connects in a row
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+/// Health-checked channels to one endpoint
+struct ChannelPool {
+    endpoint: Endpoint,
+    slots: Vec<Mutex<Slot>>,
+    next: AtomicUsize,
+    health_interval: Duration,
+}
+
+impl ChannelPool {
+    fn new(endpoint: Endpoint, size: usize, health_interval: Duration) -> Self {
+        Self {
+            endpoint,
+            slots: (0..size.clamp(1, MAX_POOL_SIZE)).map(|_| Mutex::new(Slot::default())).collect(),
+            next: AtomicUsize::new(0),
+            health_interval,
+        }
+    }
+
+    /// A healthy client and its slot, reconnecting the slot if needed
+    ///
+    /// Slots waiting out a reconnect backoff are skipped for the next one;
+    /// the backoff error is returned only when every slot is backing off.
+    async fn checkout(&self) -> Result<(usize, BridgeServiceClient<Channel>)> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let mut backing_off = None;
+        for offset in 0..self.slots.len() {
+            let index = (start + offset) % self.slots.len();
+            match self.checkout_slot(index).await? {
+                Ok(client) => return Ok((index, client)),
+                Err(wait) => backing_off = Some(backing_off.map_or(wait, |w: Duration| w.min(wait))),
+            }
+        }
+        anyhow::bail!(
+            "Bridge unreachable, reconnecting in {:.1}s",
+            backing_off.unwrap_or_default().as_secs_f64()
+        )
+    }
+
+    /// The slot's client, or how long until it may reconnect
+    async fn checkout_slot(&self, index: usize) -> Result<Result<BridgeServiceClient<Channel>, Duration>> {
+        let mut slot = self.slots[index].lock().await;
+
+        if let Some(client) = slot.client.clone() {
+            let fresh = slot.checked_at.is_some_and(|at| at.elapsed() < self.health_interval);
+            if fresh || probe(client.clone()).await {
+                slot.checked_at = Some(Instant::now());
+                return Ok(Ok(client));
+            }
+            warn!("Bridge channel {} failed its health check, reconnecting", index);
+            slot.client = None;
+        }
+
+        if let Some(retry_at) = slot.retry_at.filter(|at| *at > Instant::now()) {
+            return Ok(Err(retry_at - Instant::now()));
+        }
+
+        match self.endpoint.connect().await {
+            Ok(channel) => {
+                if slot.failures > 0 {
+                    info!("Bridge channel {} reconnected after {} failed attempt(s)", index, slot.failures);
+                }
+                let client = BridgeServiceClient::new(channel);
+                *slot = Slot {
+                    client: Some(client.clone()),
+                    checked_at: Some(Instant::now()),
+                    ..Slot::default()
+                };
+                Ok(Ok(client))
+            }
+            Err(e) => {
+                slot.failures += 1;
+                let delay = reconnect_backoff(slot.failures);
+                slot.retry_at = Some(Instant::now() + delay);
+                Err(anyhow::anyhow!("Bridge unreachable: {} (retry in {:?})", e, delay))
+            }
+        }
+    }
+
+    /// Health-check the slot before its next use (a call on it failed)
+    async fn mark_suspect(&self, index: usize) {
+        self.slots[index].lock().await.checked_at = None;
+    }
+
+    /// Slots holding a channel
+    async fn connected(&self) -> usize {
+        let mut connected = 0;
+        for slot in &self.slots {
+            if slot.lock().await.client.is_some() {
+                connected += 1;
+            }
+        }
+        connected
+    }
+}
+
+/// True if the server answers Health as healthy within `HEALTH_TIMEOUT`
+async fn probe(mut client: BridgeServiceClient<Channel>) -> bool {
+    match tokio::time::timeout(HEALTH_TIMEOUT, client.health(tonic::Request::new(HealthRequest {}))).await {
+        Ok(Ok(response)) => response.into_inner().status == "healthy",
+        Ok(Err(e)) => {
+            debug!("Health check failed: {}", e);
+            false
+        }
+        Err(_) => {
+            debug!("Health check timed out after {:?}", HEALTH_TIMEOUT);
+            false
         }
     }
 }
 
 /// gRPC Bridge Client
 ///
-/// Clone is cheap - tonic clients share the underlying connection.
+/// Clone is cheap - clones share the channel pool.
 #[derive(Clone)]
 pub struct GrpcBridgeClient {
-    client: BridgeServiceClient<Channel>,
+    pool: Arc<ChannelPool>,
     api_key: String,
+    max_retries: u32,
 }
 
 impl GrpcBridgeClient {
     /// Create a new gRPC client
+    ///
+    /// Connects the first channel so a bad endpoint or TLS setup fails here;
+    /// the rest of the pool connects on first use.
     pub async fn new(config: GrpcBridgeClientConfig) -> Result<Self> {
         let mut channel_builder = Channel::from_shared(config.endpoint.clone())?
-            .timeout(std::time::Duration::from_secs(config.timeout_seconds));
+            .timeout(std::time::Duration::from_secs(config.timeout_seconds))
+            .connect_timeout(CONNECT_TIMEOUT);
 
         // Configure TLS if CA cert provided
         if let Some(ca_path) = &config.ca_cert_path {
@@ -64,12 +240,17 @@ impl GrpcBridgeClient {
             info!("TLS enabled for gRPC client");
         }
 
-        let channel = channel_builder.connect().await?;
-        let client = BridgeServiceClient::new(channel);
+        let pool = ChannelPool::new(
+            channel_builder,
+            config.pool_size,
+            Duration::from_secs(config.health_check_secs),
+        );
+        pool.checkout().await?;
 
         Ok(Self {
-            client,
+            pool: Arc::new(pool),
             api_key: config.api_key,
+            max_retries: config.max_retries,
         })
     }
 
@@ -89,12 +270,30 @@ impl GrpcBridgeClient {
         let ca_cert_path = std::env::var("BRIDGE_CA_CERT").ok().map(PathBuf::from);
         let domain = std::env::var("BRIDGE_TLS_DOMAIN").ok();
 
+        let max_retries = std::env::var("BRIDGE_MAX_RETRIES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(2);
+
+        let defaults = GrpcBridgeClientConfig::default();
+        let pool_size = std::env::var("BRIDGE_POOL_SIZE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.pool_size);
+        let health_check_secs = std::env::var("BRIDGE_HEALTH_CHECK_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.health_check_secs);
+
         let config = GrpcBridgeClientConfig {
             endpoint,
             api_key,
             timeout_seconds,
             ca_cert_path,
             domain,
+            max_retries,
+            pool_size,
+            health_check_secs,
         };
 
         Self::new(config).await
@@ -109,15 +308,33 @@ impl GrpcBridgeClient {
         request
     }
 
+    /// Run one RPC on a pooled client
+    ///
+    /// A transport failure marks the channel for a health check before its
+    /// next use; an unreachable server surfaces as `Unavailable`.
+    async fn call<T, F, Fut>(&self, rpc: F) -> Result<T, tonic::Status>
+    where
+        F: FnOnce(BridgeServiceClient<Channel>) -> Fut,
+        Fut: Future<Output = Result<T, tonic::Status>>,
+    {
+        let (slot, client) = self
+            .pool
+            .checkout()
+            .await
+            .map_err(|e| tonic::Status::unavailable(e.to_string()))?;
+        let result = rpc(client).await;
+        if let Err(status) = &result {
+            if is_retryable(status) {
+                self.pool.mark_suspect(slot).await;
+            }
+        }
+        result
+    }
+
     /// Health check
     pub async fn health_check(&self) -> Result<bool> {
-        let request = tonic::Request::new(HealthRequest {});
-
-        match self.client.clone().health(request).await {
-            Ok(response) => {
-                let status = response.into_inner();
-                Ok(status.status == "healthy")
-            }
+        match self.pool.checkout().await {
+            Ok((_, client)) => Ok(probe(client).await),
             Err(e) => {
                 debug!("Health check failed: {}", e);
                 Ok(false)
@@ -129,7 +346,7 @@ impl GrpcBridgeClient {
     pub async fn status(&self) -> Result<StatusResponse> {
         let request = self.add_auth(tonic::Request::new(StatusRequest {}));
 
-        let response = self.client.clone().status(request).await?;
+        let response = self.call(move |mut client| async move { client.status(request).await }).await?;
         Ok(response.into_inner())
     }
 
@@ -142,26 +359,65 @@ impl GrpcBridgeClient {
 
         let request = self.add_auth(tonic::Request::new(req));
 
-        let response = self.client.clone().execute(request).await?;
+        let response = self.call(move |mut client| async move { client.execute(request).await }).await?;
         Ok(response.into_inner())
     }
 
     /// Execute task and collect full response (convenience method)
+    ///
+    /// Transient failures are retried up to `max_retries` times with the
+    /// same idempotency key, so the server replays rather than re-runs.
     pub async fn execute_full(
         &self,
         chat_id: i64,
         task: &str,
         session_id: Option<String>,
     ) -> Result<ExecuteResult> {
-        let req = ExecuteRequest {
-            task: task.to_string(),
-            session_id,
-            working_dir: None,
-            chat_id,
-            autonomous: true,
-        };
+        let req = full_request(chat_id, task, session_id, crate::permissions::safe_mode_from_env());
+
+        let mut attempt = 0;
+        loop {
+            match self.collect(req.clone()).await {
+                Ok(result) => return Ok(result),
+                Err(status) if attempt < self.max_retries && is_retryable(&status) => {
+                    attempt += 1;
+                    let delay = std::time::Duration::from_millis(500 << (attempt - 1).min(6));
+                    warn!(
+                        "gRPC Execute failed ({}), retry {}/{} in {:?}",
+                        status.code(),
+                        attempt,
+                        self.max_retries,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(status) => return Err(status.into()),
+            }
+        }
+    }
+
+    /// One Execute attempt, collected into a result
+    async fn collect(&self, req: ExecuteRequest) -> Result<ExecuteResult, tonic::Status> {
+        let request = self.add_auth(tonic::Request::new(req));
+        let (slot, mut client) = self
+            .pool
+            .checkout()
+            .await
+            .map_err(|e| tonic::Status::unavailable(e.to_string()))?;
+        let collected = Self::collect_stream(&mut client, request).await;
+        if let Err(status) = &collected {
+            if is_retryable(status) {
+                self.pool.mark_suspect(slot).await;
+            }
+        }
+        collected
+    }
 
-        let mut stream = self.execute(req).await?;
+    async fn collect_stream(
+        client: &mut BridgeServiceClient<Channel>,
+        request: tonic::Request<ExecuteRequest>,
+    ) -> Result<ExecuteResult, tonic::Status> {
+        let mut stream = client.execute(request).await?.into_inner();
         let mut result = ExecuteResult::default();
 
         while let Some(chunk) = stream.message().await? {
@@ -203,7 +459,7 @@ impl GrpcBridgeClient {
 
         let request = self.add_auth(tonic::Request::new(req));
 
-        let response = self.client.clone().read_file(request).await?;
+        let response = self.call(move |mut client| async move { client.read_file(request).await }).await?;
         let inner = response.into_inner();
 
         if inner.success {
@@ -235,6 +491,58 @@ impl GrpcBridgeClient {
         self.read_file(req).await
     }
 
+    /// Write a file on the remote server, streamed in `WRITE_CHUNK_SIZE` chunks
+    ///
+    /// The server only accepts paths inside its write allowlist. `mode` is
+    /// the Unix permission bits (server default 0644).
+    pub async fn write_file(
+        &self,
+        chat_id: i64,
+        path: &str,
+        content: &[u8],
+        mode: Option<u32>,
+    ) -> Result<FileWriteResponse> {
+        if content.len() > MAX_WRITE_BYTES {
+            return Err(anyhow::anyhow!(
+                "File is {} bytes, limit is {} bytes",
+                content.len(),
+                MAX_WRITE_BYTES
+            ));
+        }
+        debug!("gRPC WriteFile: {} ({} bytes)", path, content.len());
+
+        let request = self.add_auth(tonic::Request::new(tokio_stream::iter(write_chunks(
+            chat_id, path, content, mode,
+        ))));
+
+        let response = self.call(move |mut client| async move { client.write_file(request).await }).await?;
+        let inner = response.into_inner();
+
+        if inner.success {
+            info!("File write successful: {} bytes", inner.bytes_written);
+        } else {
+            error!("File write failed: {:?}", inner.error);
+        }
+
+        Ok(inner)
+    }
+
+    /// Run a shell command on the remote server, streaming its output
+    ///
+    /// The server checks the command against its shell policy and rejects
+    /// it with `PermissionDenied` (or `FailedPrecondition` when disabled).
+    pub async fn exec_shell(&self, chat_id: i64, command: &str) -> Result<tonic::Streaming<ShellChunk>> {
+        debug!("gRPC ExecShell: {}", command);
+
+        let request = self.add_auth(tonic::Request::new(ExecShellRequest {
+            command: command.to_string(),
+            chat_id,
+        }));
+
+        let response = self.call(move |mut client| async move { client.exec_shell(request).await }).await?;
+        Ok(response.into_inner())
+    }
+
     /// Test connection
     pub async fn test_connection(&self) -> Result<String> {
         let healthy = self.health_check().await?;
@@ -244,15 +552,62 @@ impl GrpcBridgeClient {
 
         let status = self.status().await?;
         Ok(format!(
-            "gRPC Bridge v{} - {} requests processed, {} active sessions, uptime: {}s",
+            "gRPC Bridge v{} - {} requests processed, {} active sessions, uptime: {}s, {}/{} channels connected",
             status.version,
             status.requests_processed,
             status.active_sessions,
-            status.uptime_seconds
+            status.uptime_seconds,
+            self.pool.connected().await,
+            self.pool.slots.len()
         ))
     }
 }
 
+/// Whether a failed Execute is worth retrying (network trouble, not a rejection)
+fn is_retryable(status: &tonic::Status) -> bool {
+    matches!(
+        status.code(),
+        tonic::Code::Unavailable | tonic::Code::Unknown | tonic::Code::Cancelled | tonic::Code::DeadlineExceeded
+    )
+}
+
+/// Split content into write chunks; path, mode and chat_id go in the first
+fn write_chunks(chat_id: i64, path: &str, content: &[u8], mode: Option<u32>) -> Vec<FileWriteChunk> {
+    let mut chunks: Vec<FileWriteChunk> = content
+        .chunks(WRITE_CHUNK_SIZE)
+        .map(|data| FileWriteChunk {
+            path: String::new(),
+            data: data.to_vec(),
+            mode: None,
+            chat_id,
+        })
+        .collect();
+
+    if chunks.is_empty() {
+        chunks.push(FileWriteChunk {
+            path: String::new(),
+            data: Vec::new(),
+            mode: None,
+            chat_id,
+        });
+    }
+    chunks[0].path = path.to_string();
+    chunks[0].mode = mode;
+    chunks
+}
+
+/// Request for `execute_full`: autonomous unless the bot runs in safe mode
+fn full_request(chat_id: i64, task: &str, session_id: Option<String>, safe_mode: bool) -> ExecuteRequest {
+    ExecuteRequest {
+        task: task.to_string(),
+        session_id,
+        working_dir: None,
+        chat_id,
+        autonomous: !safe_mode,
+        idempotency_key: Some(uuid::Uuid::new_v4().to_string()),
+    }
+}
+
 /// Result from execute_full
 #[derive(Debug, Default)]
 pub struct ExecuteResult {
@@ -273,5 +628,66 @@ mod tests {
         let config = GrpcBridgeClientConfig::default();
         assert_eq!(config.endpoint, "http://localhost:9998");
         assert_eq!(config.timeout_seconds, 300);
+        assert_eq!(config.max_retries, 2);
+        assert_eq!(config.pool_size, 2);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_pool_backs_off() {
+        assert_eq!(reconnect_backoff(1), RECONNECT_BACKOFF);
+        assert_eq!(reconnect_backoff(3), RECONNECT_BACKOFF * 4);
+        assert_eq!(reconnect_backoff(40), MAX_RECONNECT_BACKOFF);
+
+        // Nothing listens on port 1: the connect fails, then waits out the backoff
+        let endpoint = Channel::from_static("http://127.0.0.1:1").connect_timeout(Duration::from_secs(2));
+        let pool = ChannelPool::new(endpoint, 1, Duration::from_secs(30));
+        let first = pool.checkout().await.err().unwrap().to_string();
+        assert!(first.contains("unreachable"), "{}", first);
+        let second = pool.checkout().await.err().unwrap().to_string();
+        assert!(second.contains("reconnecting in"), "{}", second);
+        assert_eq!(pool.connected().await, 0);
+        assert_eq!(pool.slots[0].lock().await.failures, 1);
+
+        // A slot in backoff hands the checkout to the next one
+        let endpoint = Channel::from_static("http://127.0.0.1:1").connect_timeout(Duration::from_secs(2));
+        let pool = ChannelPool::new(endpoint, 2, Duration::from_secs(30));
+        pool.slots[0].lock().await.retry_at = Some(Instant::now() + Duration::from_secs(60));
+        let err = pool.checkout().await.err().unwrap().to_string();
+        assert!(!err.contains("reconnecting in"), "{}", err);
+        assert_eq!(pool.slots[1].lock().await.failures, 1);
+        let err = pool.checkout().await.err().unwrap().to_string();
+        assert!(err.contains("reconnecting in"), "{}", err);
+        assert_eq!(ChannelPool::new(Channel::from_static("http://127.0.0.1:1"), 100, Duration::ZERO).slots.len(), MAX_POOL_SIZE);
+    }
+
+    #[test]
+    fn test_full_request_honours_safe_mode() {
+        assert!(full_request(1, "task", None, false).autonomous);
+        assert!(!full_request(1, "task", None, true).autonomous);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&tonic::Status::unavailable("connection reset")));
+        assert!(is_retryable(&tonic::Status::deadline_exceeded("timeout")));
+        assert!(!is_retryable(&tonic::Status::permission_denied("not admin")));
+        assert!(!is_retryable(&tonic::Status::resource_exhausted("rate limited")));
+    }
+
+    #[test]
+    fn test_write_chunks() {
+        let content = vec![b'x'; WRITE_CHUNK_SIZE * 2 + 1];
+        let chunks = write_chunks(7, "/srv/app.conf", &content, Some(0o600));
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].path, "/srv/app.conf");
+        assert_eq!(chunks[0].mode, Some(0o600));
+        assert!(chunks[1].path.is_empty());
+        assert_eq!(chunks[2].data.len(), 1);
+        assert!(chunks.iter().all(|c| c.chat_id == 7));
+
+        // Empty files still send the header chunk
+        let chunks = write_chunks(7, "/srv/empty", b"", None);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].path, "/srv/empty");
     }
 }
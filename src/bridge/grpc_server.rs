@@ -1,19 +1,29 @@
 //! gRPC Bridge Server
 //!
 //! Streaming gRPC server with TLS for remote Claude Code execution.
+//!
+//! `ExecShell` runs plain commands without Claude. It is off unless
+//! `BRIDGE_SHELL_ENABLED=true`, and commands are checked against a skills
+//! sandbox policy (`SandboxConfig`): allowlist/blocklist, blocked patterns,
+//! timeout and output limit. The policy starts from an allowlist of read-only
+//! commands (`DEFAULT_SHELL_COMMANDS`), or is loaded from the TOML file at
+//! `BRIDGE_SHELL_POLICY`, with `BRIDGE_SHELL_*` overrides named like the
+//! `SKILLS_SANDBOX_*` ones. Unlike the other admin-gated RPCs, which allow
+//! every chat while `BRIDGE_ALLOWED_ADMINS` is empty, ExecShell only serves
+//! chats that are listed there.
 
 use anyhow::Result;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::process::Stdio;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
-use tokio::sync::{mpsc, RwLock, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::{mpsc, watch, RwLock, Mutex};
 use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
 use tonic::transport::{Identity, Server, ServerTlsConfig};
 use tonic::{Request, Response, Status};
 use tracing::{debug, error, info, warn};
@@ -21,12 +31,16 @@ use tracing::{debug, error, info, warn};
 use super::proto::{
     bridge_service_server::{BridgeService, BridgeServiceServer},
     ChunkType, ExecuteChunk, ExecuteRequest, FileReadRequest, FileReadResponse,
+    FileWriteChunk, FileWriteResponse,
     HealthRequest, HealthResponse, StatusRequest, StatusResponse,
     SpawnWorkerRequest, SpawnWorkerResponse, KillWorkerRequest, KillWorkerResponse,
     ListWorkersRequest, ListWorkersResponse, WorkerStatusRequest, WorkerStatusResponse,
     ExecuteOnWorkerRequest, PoolStats as ProtoPoolStats, WorkerInfo as ProtoWorkerInfo,
+    ExecShellRequest, ShellChunk, ShellStream,
 };
 use super::types::ClaudeCliOutput;
+use crate::claude_cli::{split_args, ClaudeCli};
+use crate::skills::{SandboxConfig, SkillSandbox};
 use crate::worker_pool::{WorkerPool, WorkerConfig, PoolConfig, PermissionLevel as WPPermissionLevel, WorkerStatus};
 
 /// Convert WorkerStatus to proto WorkerState
@@ -65,6 +79,63 @@ pub struct GrpcBridgeConfig {
     pub allowed_admins: Vec<i64>,
     pub tls_cert_path: Option<PathBuf>,
     pub tls_key_path: Option<PathBuf>,
+    /// Directories WriteFile may write into (empty disables writes)
+    pub write_allowed_paths: Vec<PathBuf>,
+    /// Maximum size of a single written file
+    pub max_write_bytes: u64,
+    /// How long Execute results are kept for idempotent replay (0 disables)
+    pub idempotency_ttl_seconds: u64,
+    /// Command policy for ExecShell (None disables it)
+    pub shell_policy: Option<SandboxConfig>,
+}
+
+/// Default WriteFile size limit (10 MiB)
+pub const DEFAULT_MAX_WRITE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default idempotency window (10 minutes)
+pub const DEFAULT_IDEMPOTENCY_TTL_SECS: u64 = 600;
+
+/// Default ExecShell timeout
+pub const DEFAULT_SHELL_TIMEOUT_SECS: u64 = 60;
+
+/// Default ExecShell output limit (64 KiB, stdout and stderr combined)
+pub const DEFAULT_SHELL_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Read-only inspection commands ExecShell allows unless the policy says otherwise
+pub const DEFAULT_SHELL_COMMANDS: &[&str] = &[
+    "ls", "cat", "head", "tail", "grep", "wc", "df", "du", "free", "uptime", "ps", "date",
+    "pwd", "whoami",
+];
+
+/// Sandbox defaults with the shorter ExecShell timeout and output limit,
+/// allowing only `DEFAULT_SHELL_COMMANDS`
+pub fn default_shell_policy() -> SandboxConfig {
+    SandboxConfig {
+        timeout_secs: DEFAULT_SHELL_TIMEOUT_SECS,
+        max_output_bytes: DEFAULT_SHELL_MAX_OUTPUT_BYTES,
+        allowed_commands: DEFAULT_SHELL_COMMANDS.iter().map(|c| c.to_string()).collect(),
+        ..SandboxConfig::default()
+    }
+}
+
+/// ExecShell policy from `BRIDGE_SHELL_*`, None unless `BRIDGE_SHELL_ENABLED`
+fn shell_policy_from_env() -> Result<Option<SandboxConfig>> {
+    let enabled = std::env::var("BRIDGE_SHELL_ENABLED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    if !enabled {
+        return Ok(None);
+    }
+
+    let policy = match std::env::var("BRIDGE_SHELL_POLICY") {
+        Ok(path) => SandboxConfig::from_file(Path::new(&path))?,
+        Err(_) => default_shell_policy(),
+    };
+    let policy = policy.with_env_overrides(|key| {
+        std::env::var(key.replace("SKILLS_SANDBOX_", "BRIDGE_SHELL_")).ok()
+    })?;
+    policy.validate()?;
+    Ok(Some(policy))
 }
 
 impl Default for GrpcBridgeConfig {
@@ -78,6 +149,10 @@ impl Default for GrpcBridgeConfig {
             allowed_admins: Vec::new(),
             tls_cert_path: None,
             tls_key_path: None,
+            write_allowed_paths: Vec::new(),
+            max_write_bytes: DEFAULT_MAX_WRITE_BYTES,
+            idempotency_ttl_seconds: DEFAULT_IDEMPOTENCY_TTL_SECS,
+            shell_policy: None,
         }
     }
 }
@@ -88,6 +163,23 @@ struct RateLimitEntry {
     window_start: Instant,
 }
 
+/// Chunks of one Execute call, recorded for replay to duplicate requests
+type ExecutionRecord = Arc<watch::Sender<Vec<ExecuteChunk>>>;
+
+/// Execution registered under an idempotency key
+struct IdempotencyEntry {
+    created: Instant,
+    record: ExecutionRecord,
+}
+
+/// Result of registering an idempotency key
+enum IdempotencyClaim {
+    /// First request with this key - execute and record into it
+    New(ExecutionRecord),
+    /// Seen before (finished or still running) - replay it
+    Duplicate(ExecutionRecord),
+}
+
 /// Shared state for the gRPC server
 pub struct GrpcBridgeState {
     config: GrpcBridgeConfig,
@@ -97,6 +189,8 @@ pub struct GrpcBridgeState {
     rate_limits: RwLock<HashMap<i64, RateLimitEntry>>,
     /// Worker pool for distributed execution
     worker_pool: Arc<Mutex<WorkerPool>>,
+    /// Recent executions by (chat_id, idempotency key)
+    idempotency: Mutex<HashMap<(i64, String), IdempotencyEntry>>,
 }
 
 impl GrpcBridgeState {
@@ -114,6 +208,7 @@ impl GrpcBridgeState {
             sessions: RwLock::new(HashMap::new()),
             rate_limits: RwLock::new(HashMap::new()),
             worker_pool: Arc::new(Mutex::new(WorkerPool::new(pool_config))),
+            idempotency: Mutex::new(HashMap::new()),
         }
     }
 
@@ -147,9 +242,304 @@ impl GrpcBridgeState {
         true
     }
 
+    /// Register an idempotency key, or find the execution already using it
+    ///
+    /// Keys are scoped per chat; expired entries are purged on each call.
+    async fn claim_idempotency_key(&self, chat_id: i64, key: &str) -> IdempotencyClaim {
+        let ttl = Duration::from_secs(self.config.idempotency_ttl_seconds);
+        let mut entries = self.idempotency.lock().await;
+        entries.retain(|_, entry| entry.created.elapsed() < ttl);
+
+        let id = (chat_id, key.to_string());
+        if let Some(entry) = entries.get(&id) {
+            return IdempotencyClaim::Duplicate(entry.record.clone());
+        }
+        let record = Arc::new(watch::channel(Vec::new()).0);
+        entries.insert(
+            id,
+            IdempotencyEntry {
+                created: Instant::now(),
+                record: record.clone(),
+            },
+        );
+        IdempotencyClaim::New(record)
+    }
+
+    /// Forget a key whose request was rejected before executing
+    async fn release_idempotency_key(&self, chat_id: i64, key: &str) {
+        self.idempotency.lock().await.remove(&(chat_id, key.to_string()));
+    }
+
+    /// Listed chats are admins; an empty list allows every chat
     fn is_admin(&self, chat_id: i64) -> bool {
         self.config.allowed_admins.is_empty() || self.config.allowed_admins.contains(&chat_id)
     }
+
+    /// ExecShell needs the chat to be listed; an empty list means nobody
+    fn is_shell_admin(&self, chat_id: i64) -> bool {
+        self.config.allowed_admins.contains(&chat_id)
+    }
+
+    /// Check the `authorization: Bearer <key>` metadata against the API key
+    ///
+    /// Fails closed when no key is configured.
+    fn check_api_key(&self, metadata: &tonic::metadata::MetadataMap) -> bool {
+        let provided = metadata
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or("");
+        let expected = self.config.api_key.as_bytes();
+        !expected.is_empty() && constant_time_eq(provided.as_bytes(), expected)
+    }
+
+    /// Resolve a write target inside the write allowlist
+    ///
+    /// The parent directory must already exist; it is canonicalized so
+    /// symlinked directories can't escape the allowed roots, and an existing
+    /// symlink at the target itself is refused.
+    fn resolve_write_path(&self, path: &str) -> Result<PathBuf, String> {
+        if self.config.write_allowed_paths.is_empty() {
+            return Err("File writes are disabled on this bridge".to_string());
+        }
+
+        let requested = Path::new(path);
+        if !requested.is_absolute() {
+            return Err("Path must be absolute".to_string());
+        }
+        if path.contains("..") || path.contains('\0') {
+            warn!("Suspicious write path rejected: {}", path);
+            return Err("Invalid path".to_string());
+        }
+
+        let file_name = requested.file_name().ok_or_else(|| "Path has no file name".to_string())?;
+        let parent = requested.parent().ok_or_else(|| "Path has no parent directory".to_string())?;
+        let parent = std::fs::canonicalize(parent)
+            .map_err(|e| format!("Parent directory unavailable: {}", e))?;
+        let target = parent.join(file_name);
+
+        let allowed = self.config.write_allowed_paths.iter().any(|root| {
+            std::fs::canonicalize(root).is_ok_and(|root| target.starts_with(&root))
+        });
+        if !allowed {
+            warn!("Write outside allowlist rejected: {}", path);
+            return Err(format!("{} is outside the allowed write paths", path));
+        }
+
+        if std::fs::symlink_metadata(&target).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Err("Refusing to write through a symlink".to_string());
+        }
+        if target.is_dir() {
+            return Err("Path is a directory".to_string());
+        }
+        Ok(target)
+    }
+
+    /// Split an ExecShell command into arguments and check them against the policy
+    ///
+    /// The check runs on the unquoted arguments, so quoting can't disguise a
+    /// blocked program name.
+    fn shell_argv(&self, command: &str) -> Result<Vec<String>, String> {
+        let Some(policy) = &self.config.shell_policy else {
+            return Err("Shell execution is disabled on this bridge".to_string());
+        };
+        let argv = split_args(command).map_err(|e| format!("Invalid command: {}", e))?;
+        if argv.is_empty() {
+            return Err("Empty command".to_string());
+        }
+
+        let validation = SkillSandbox::new(policy.clone()).validate(&argv.join(" "));
+        if !validation.allowed {
+            let reason = validation.blocked_reasons.join("; ");
+            warn!("ExecShell blocked by policy: {} ({})", command, reason);
+            return Err(format!("Blocked by policy: {}", reason));
+        }
+        Ok(argv)
+    }
+}
+
+/// Compare secrets without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Destination for execution chunks: the caller's stream plus, for
+/// idempotent requests, the replay record
+struct ChunkSink {
+    tx: mpsc::Sender<Result<ExecuteChunk, Status>>,
+    record: Option<ExecutionRecord>,
+    client_open: bool,
+}
+
+impl ChunkSink {
+    fn new(tx: mpsc::Sender<Result<ExecuteChunk, Status>>, record: Option<ExecutionRecord>) -> Self {
+        Self {
+            tx,
+            record,
+            client_open: true,
+        }
+    }
+
+    /// Send a chunk; false once nobody is listening
+    ///
+    /// Recorded executions keep running after the client disconnects so
+    /// its retry can pick up the result.
+    async fn send(&mut self, chunk: ExecuteChunk) -> bool {
+        if let Some(record) = &self.record {
+            record.send_modify(|chunks| chunks.push(chunk.clone()));
+        }
+        if self.client_open && self.tx.send(Ok(chunk)).await.is_err() {
+            self.client_open = false;
+        }
+        self.client_open || self.record.is_some()
+    }
+}
+
+/// An execution that stops without its final chunk (task aborted or
+/// panicked) ends its record with an error, so replays attached to it finish
+impl Drop for ChunkSink {
+    fn drop(&mut self) {
+        let Some(record) = &self.record else {
+            return;
+        };
+        record.send_if_modified(|chunks| {
+            if chunks.last().is_some_and(|c| c.is_final) {
+                return false;
+            }
+            chunks.push(ExecuteChunk {
+                r#type: ChunkType::Error as i32,
+                content: String::new(),
+                session_id: None,
+                cost_usd: None,
+                duration_ms: None,
+                is_final: true,
+                error: Some("The original execution ended without a result; retry with a new key".to_string()),
+            });
+            true
+        });
+    }
+}
+
+/// Stream a recorded execution, following it until its final chunk
+async fn replay_execution(record: ExecutionRecord, tx: mpsc::Sender<Result<ExecuteChunk, Status>>) {
+    let mut rx = record.subscribe();
+    let mut sent = 0;
+    loop {
+        let pending: Vec<ExecuteChunk> = rx.borrow_and_update()[sent..].to_vec();
+        for chunk in pending {
+            sent += 1;
+            let is_final = chunk.is_final;
+            if tx.send(Ok(chunk)).await.is_err() || is_final {
+                return;
+            }
+        }
+        if rx.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Receive WriteFile chunks into a temp file and move it into place
+///
+/// The first chunk carries path, mode and chat_id. The size limit is
+/// enforced while streaming; nothing is left behind on failure.
+async fn write_file_chunks<S>(state: &GrpcBridgeState, mut chunks: S) -> Result<FileWriteResponse, Status>
+where
+    S: tokio_stream::Stream<Item = Result<FileWriteChunk, Status>> + Unpin,
+{
+    let failed = |error: String| FileWriteResponse {
+        success: false,
+        bytes_written: 0,
+        error: Some(error),
+    };
+
+    let first = match chunks.next().await {
+        Some(chunk) => chunk?,
+        None => return Ok(failed("Empty write request".to_string())),
+    };
+
+    if !state.is_admin(first.chat_id) {
+        warn!("Unauthorized write from chat_id: {}", first.chat_id);
+        return Err(Status::permission_denied(format!("Chat {} not authorized", first.chat_id)));
+    }
+    if !state.check_rate_limit(first.chat_id).await {
+        return Err(Status::resource_exhausted("Rate limit exceeded"));
+    }
+
+    let target = match state.resolve_write_path(&first.path) {
+        Ok(target) => target,
+        Err(e) => return Ok(failed(e)),
+    };
+    let mode = first.mode.unwrap_or(0o644) & 0o777;
+    let max_bytes = state.config.max_write_bytes;
+
+    let tmp_path = target.with_file_name(format!(
+        ".{}.{}.tmp",
+        target.file_name().and_then(|n| n.to_str()).unwrap_or("write"),
+        uuid::Uuid::new_v4()
+    ));
+
+    let result: Result<u64, String> = async {
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::File::create(&tmp_path)
+            .await
+            .map_err(|e| format!("Failed to create file: {}", e))?;
+        let mut written = 0u64;
+        let mut chunk = Some(first);
+
+        while let Some(current) = chunk {
+            written += current.data.len() as u64;
+            if written > max_bytes {
+                return Err(format!("File exceeds the {} byte write limit", max_bytes));
+            }
+            file.write_all(&current.data)
+                .await
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+
+            chunk = match chunks.next().await {
+                Some(Ok(next)) => Some(next),
+                Some(Err(status)) => return Err(format!("Stream error: {}", status.message())),
+                None => None,
+            };
+        }
+
+        file.flush().await.map_err(|e| format!("Failed to write file: {}", e))?;
+        drop(file);
+        set_mode(&tmp_path, mode).map_err(|e| format!("Failed to set permissions: {}", e))?;
+        tokio::fs::rename(&tmp_path, &target)
+            .await
+            .map_err(|e| format!("Failed to move file into place: {}", e))?;
+        Ok(written)
+    }
+    .await;
+
+    match result {
+        Ok(bytes_written) => {
+            info!("gRPC WriteFile: {} ({} bytes, mode {:o})", target.display(), bytes_written, mode);
+            Ok(FileWriteResponse {
+                success: true,
+                bytes_written,
+                error: None,
+            })
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+            warn!("gRPC WriteFile failed for {}: {}", target.display(), e);
+            Ok(failed(e))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Ok(())
 }
 
 /// gRPC Bridge Service implementation
@@ -164,6 +554,7 @@ impl GrpcBridgeServiceImpl {
 }
 
 type ExecuteStream = Pin<Box<dyn tokio_stream::Stream<Item = Result<ExecuteChunk, Status>> + Send>>;
+type ShellStreamOut = Pin<Box<dyn tokio_stream::Stream<Item = Result<ShellChunk, Status>> + Send>>;
 
 #[tonic::async_trait]
 impl BridgeService for GrpcBridgeServiceImpl {
@@ -208,9 +599,30 @@ impl BridgeService for GrpcBridgeServiceImpl {
             )));
         }
 
+        // Duplicate of an earlier request: replay instead of re-running
+        let idempotency_key = req
+            .idempotency_key
+            .clone()
+            .filter(|key| !key.is_empty() && self.state.config.idempotency_ttl_seconds > 0);
+        let record = match &idempotency_key {
+            Some(key) => match self.state.claim_idempotency_key(req.chat_id, key).await {
+                IdempotencyClaim::New(record) => Some(record),
+                IdempotencyClaim::Duplicate(record) => {
+                    info!("gRPC Execute for chat {}: replaying idempotency key {}", req.chat_id, key);
+                    let (tx, rx) = mpsc::channel(32);
+                    tokio::spawn(replay_execution(record, tx));
+                    return Ok(Response::new(Box::pin(ReceiverStream::new(rx))));
+                }
+            },
+            None => None,
+        };
+
         // Check rate limit
         if !self.state.check_rate_limit(req.chat_id).await {
             warn!("Rate limit exceeded for chat {}", req.chat_id);
+            if let Some(key) = &idempotency_key {
+                self.state.release_idempotency_key(req.chat_id, key).await;
+            }
             return Err(Status::resource_exhausted(format!(
                 "Rate limit exceeded: max {} requests per minute",
                 self.state.config.rate_limit_per_minute
@@ -247,6 +659,9 @@ impl BridgeService for GrpcBridgeServiceImpl {
         // Ensure directory exists
         if let Err(e) = std::fs::create_dir_all(&working_dir) {
             error!("Failed to create working directory: {}", e);
+            if let Some(key) = &idempotency_key {
+                self.state.release_idempotency_key(req.chat_id, key).await;
+            }
             return Err(Status::internal(format!(
                 "Failed to create working directory: {}",
                 e
@@ -258,8 +673,9 @@ impl BridgeService for GrpcBridgeServiceImpl {
         let timeout = Duration::from_secs(self.state.config.timeout_seconds);
 
         tokio::spawn(async move {
+            let mut sink = ChunkSink::new(tx, record);
             let result = execute_and_stream(
-                &tx,
+                &mut sink,
                 &req.task,
                 session_id,
                 &working_dir,
@@ -272,17 +688,16 @@ impl BridgeService for GrpcBridgeServiceImpl {
             .await;
 
             if let Err(e) = result {
-                let _ = tx
-                    .send(Ok(ExecuteChunk {
-                        r#type: ChunkType::Error as i32,
-                        content: String::new(),
-                        session_id: None,
-                        cost_usd: None,
-                        duration_ms: Some(start.elapsed().as_millis() as u64),
-                        is_final: true,
-                        error: Some(e.to_string()),
-                    }))
-                    .await;
+                sink.send(ExecuteChunk {
+                    r#type: ChunkType::Error as i32,
+                    content: String::new(),
+                    session_id: None,
+                    cost_usd: None,
+                    duration_ms: Some(start.elapsed().as_millis() as u64),
+                    is_final: true,
+                    error: Some(e.to_string()),
+                })
+                .await;
             }
         });
 
@@ -594,11 +1009,83 @@ impl BridgeService for GrpcBridgeServiceImpl {
             })),
         }
     }
+
+    async fn write_file(
+        &self,
+        request: Request<tonic::Streaming<FileWriteChunk>>,
+    ) -> Result<Response<FileWriteResponse>, Status> {
+        if !self.state.check_api_key(request.metadata()) {
+            return Err(Status::unauthenticated("Invalid API key"));
+        }
+        self.state.requests_processed.fetch_add(1, Ordering::Relaxed);
+
+        let response = write_file_chunks(&self.state, request.into_inner()).await?;
+        Ok(Response::new(response))
+    }
+
+    type ExecShellStream = ShellStreamOut;
+
+    async fn exec_shell(
+        &self,
+        request: Request<ExecShellRequest>,
+    ) -> Result<Response<Self::ExecShellStream>, Status> {
+        if !self.state.check_api_key(request.metadata()) {
+            return Err(Status::unauthenticated("Invalid API key"));
+        }
+        let req = request.into_inner();
+
+        if !self.state.is_shell_admin(req.chat_id) {
+            warn!("Unauthorized shell command from chat_id: {}", req.chat_id);
+            return Err(Status::permission_denied(format!("Chat {} not authorized", req.chat_id)));
+        }
+        if !self.state.check_rate_limit(req.chat_id).await {
+            return Err(Status::resource_exhausted("Rate limit exceeded"));
+        }
+
+        let Some(policy) = self.state.config.shell_policy.clone() else {
+            return Err(Status::failed_precondition("Shell execution is disabled on this bridge"));
+        };
+        let argv = self.state.shell_argv(&req.command).map_err(Status::permission_denied)?;
+        let working_dir = policy
+            .working_dir
+            .clone()
+            .unwrap_or_else(|| self.state.config.working_dir.clone());
+
+        self.state.requests_processed.fetch_add(1, Ordering::Relaxed);
+        info!("gRPC ExecShell for chat {}: {}", req.chat_id, req.command);
+
+        let (tx, rx) = mpsc::channel(32);
+        tokio::spawn(async move {
+            exec_shell_and_stream(&tx, &argv, &policy, &working_dir).await;
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+/// Claude CLI command for an Execute request
+///
+/// `autonomous` skips permission prompts unless this host runs in safe mode.
+fn claude_command(cli: &ClaudeCli, task: &str, session_id: Option<&str>, autonomous: bool) -> tokio::process::Command {
+    let mut cmd = cli.command();
+    cmd.arg("-p")
+        .arg(task)
+        .arg("--verbose")
+        .arg("--output-format")
+        .arg("stream-json");
+
+    if let Some(sid) = session_id {
+        cmd.arg("--resume").arg(sid);
+    }
+
+    cli.skip_permissions(&mut cmd, autonomous);
+    cli.add_extra_args(&mut cmd);
+    cmd
 }
 
 /// Execute Claude CLI and stream output chunks
 async fn execute_and_stream(
-    tx: &mpsc::Sender<Result<ExecuteChunk, Status>>,
+    sink: &mut ChunkSink,
     task: &str,
     session_id: Option<String>,
     working_dir: &PathBuf,
@@ -608,25 +1095,12 @@ async fn execute_and_stream(
     start: Instant,
     state: Arc<GrpcBridgeState>,
 ) -> Result<()> {
-    let mut cmd = Command::new("claude");
-    cmd.arg("-p")
-        .arg(task)
-        .arg("--verbose")
-        .arg("--output-format")
-        .arg("stream-json")
-        .current_dir(working_dir)
+    let mut cmd = claude_command(&ClaudeCli::from_env(), task, session_id.as_deref(), autonomous);
+    cmd.current_dir(working_dir)
         .stdin(Stdio::null())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
-    if let Some(ref sid) = session_id {
-        cmd.arg("--resume").arg(sid);
-    }
-
-    if autonomous {
-        cmd.arg("--dangerously-skip-permissions");
-    }
-
     let mut child = cmd.spawn()?;
     let stdout = child
         .stdout
@@ -712,7 +1186,7 @@ async fn execute_and_stream(
                     };
 
                     let is_final = chunk.is_final;
-                    if tx.send(Ok(chunk)).await.is_err() {
+                    if !sink.send(chunk).await {
                         break; // Client disconnected
                     }
                     if is_final {
@@ -761,6 +1235,153 @@ async fn execute_and_stream(
     Ok(())
 }
 
+/// Run an already-validated command and stream its output
+///
+/// Runs without a shell under the sandbox's sanitized environment. The
+/// process is killed once the output limit or timeout is hit; the final
+/// chunk reports the exit code and what was cut short.
+async fn exec_shell_and_stream(
+    tx: &mpsc::Sender<Result<ShellChunk, Status>>,
+    argv: &[String],
+    policy: &SandboxConfig,
+    working_dir: &Path,
+) {
+    let start = Instant::now();
+    let final_chunk = |exit_code: Option<i32>, truncated: bool, timed_out: bool, error: Option<String>| ShellChunk {
+        stream: ShellStream::Unspecified as i32,
+        data: String::new(),
+        is_final: true,
+        exit_code,
+        truncated,
+        timed_out,
+        duration_ms: Some(start.elapsed().as_millis() as u64),
+        error,
+    };
+
+    let mut cmd = tokio::process::Command::new(&argv[0]);
+    cmd.args(&argv[1..])
+        .current_dir(working_dir)
+        .env_clear()
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    for var in &policy.allowed_env_vars {
+        if let Ok(value) = std::env::var(var) {
+            cmd.env(var, value);
+        }
+    }
+    cmd.envs(policy.extra_env.iter().cloned());
+
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            let error = format!("Failed to start '{}': {}", argv[0], e);
+            let _ = tx.send(Ok(final_chunk(None, false, false, Some(error)))).await;
+            return;
+        }
+    };
+
+    let (line_tx, mut lines) = mpsc::channel(64);
+    if let Some(stdout) = child.stdout.take() {
+        tokio::spawn(forward_lines(stdout, ShellStream::Stdout, line_tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tokio::spawn(forward_lines(stderr, ShellStream::Stderr, line_tx));
+    }
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(policy.timeout_secs);
+    let mut sent_bytes = 0usize;
+    let mut truncated = false;
+    let mut timed_out = false;
+
+    loop {
+        tokio::select! {
+            line = lines.recv() => {
+                let Some((stream, bytes)) = line else { break };
+                let take = bytes.len().min(policy.max_output_bytes - sent_bytes);
+                sent_bytes += take;
+                if take > 0 {
+                    let chunk = ShellChunk {
+                        stream: stream as i32,
+                        data: String::from_utf8_lossy(&bytes[..take]).into_owned(),
+                        is_final: false,
+                        exit_code: None,
+                        truncated: false,
+                        timed_out: false,
+                        duration_ms: None,
+                        error: None,
+                    };
+                    if tx.send(Ok(chunk)).await.is_err() {
+                        debug!("ExecShell client disconnected, killing '{}'", argv[0]);
+                        let _ = child.kill().await;
+                        return;
+                    }
+                }
+                if take < bytes.len() {
+                    truncated = true;
+                    break;
+                }
+            }
+            _ = tokio::time::sleep_until(deadline) => {
+                timed_out = true;
+                break;
+            }
+        }
+    }
+
+    // Output closed doesn't mean exited; the deadline still applies
+    let status = if truncated || timed_out {
+        None
+    } else {
+        match tokio::time::timeout_at(deadline, child.wait()).await {
+            Ok(Ok(status)) => Some(status),
+            Ok(Err(e)) => {
+                let error = format!("Failed to wait for '{}': {}", argv[0], e);
+                let _ = tx.send(Ok(final_chunk(None, false, false, Some(error)))).await;
+                return;
+            }
+            Err(_) => {
+                timed_out = true;
+                None
+            }
+        }
+    };
+    if status.is_none() {
+        let _ = child.kill().await;
+    }
+
+    let exit_code = status.and_then(|s| s.code());
+    info!(
+        "gRPC ExecShell '{}' finished in {}ms (exit {:?}, truncated {}, timed out {})",
+        argv[0],
+        start.elapsed().as_millis(),
+        exit_code,
+        truncated,
+        timed_out
+    );
+    let _ = tx.send(Ok(final_chunk(exit_code, truncated, timed_out, None))).await;
+}
+
+/// Forward a pipe line by line (newlines kept, invalid UTF-8 tolerated)
+async fn forward_lines<R>(pipe: R, stream: ShellStream, tx: mpsc::Sender<(ShellStream, Vec<u8>)>)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut reader = BufReader::new(pipe);
+    loop {
+        let mut line = Vec::new();
+        match reader.read_until(b'\n', &mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                if tx.send((stream, line)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
 /// gRPC Bridge Server
 pub struct GrpcBridgeServer {
     state: Arc<GrpcBridgeState>,
@@ -805,6 +1426,26 @@ impl GrpcBridgeServer {
         let tls_cert_path = std::env::var("BRIDGE_TLS_CERT").ok().map(PathBuf::from);
         let tls_key_path = std::env::var("BRIDGE_TLS_KEY").ok().map(PathBuf::from);
 
+        let write_allowed_paths: Vec<PathBuf> = std::env::var("BRIDGE_WRITE_ALLOWED_PATHS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+            .collect();
+
+        let max_write_bytes = std::env::var("BRIDGE_MAX_WRITE_BYTES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_MAX_WRITE_BYTES);
+
+        let idempotency_ttl_seconds = std::env::var("BRIDGE_IDEMPOTENCY_TTL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_IDEMPOTENCY_TTL_SECS);
+
+        let shell_policy = shell_policy_from_env()?;
+
         let config = GrpcBridgeConfig {
             port,
             api_key,
@@ -814,6 +1455,10 @@ impl GrpcBridgeServer {
             allowed_admins,
             tls_cert_path,
             tls_key_path,
+            write_allowed_paths,
+            max_write_bytes,
+            idempotency_ttl_seconds,
+            shell_policy,
         };
 
         Ok(Self::new(config))
@@ -826,6 +1471,9 @@ impl GrpcBridgeServer {
         let addr = format!("0.0.0.0:{}", self.state.config.port).parse()?;
 
         info!("gRPC Bridge server starting on {}", addr);
+        if self.state.config.shell_policy.is_some() && self.state.config.allowed_admins.is_empty() {
+            warn!("BRIDGE_SHELL_ENABLED is set but BRIDGE_ALLOWED_ADMINS is empty; ExecShell will refuse every chat");
+        }
 
         let mut builder = Server::builder();
 
@@ -904,5 +1552,237 @@ mod tests {
         assert!(state.is_admin(111));
         assert!(state.is_admin(222));
         assert!(!state.is_admin(333));
+
+        assert!(state.is_shell_admin(111));
+        assert!(!state.is_shell_admin(333));
+
+        // An empty list keeps the open default, except for ExecShell
+        let open = GrpcBridgeState::new(GrpcBridgeConfig::default());
+        assert!(open.is_admin(111));
+        assert!(!open.is_shell_admin(111));
+    }
+
+    #[test]
+    fn test_api_key_check() {
+        let state = GrpcBridgeState::new(GrpcBridgeConfig {
+            api_key: "secret".to_string(),
+            ..Default::default()
+        });
+        let mut metadata = tonic::metadata::MetadataMap::new();
+        assert!(!state.check_api_key(&metadata));
+        metadata.insert("authorization", "Bearer wrong".parse().unwrap());
+        assert!(!state.check_api_key(&metadata));
+        metadata.insert("authorization", "Bearer secret".parse().unwrap());
+        assert!(state.check_api_key(&metadata));
+
+        let keyless = GrpcBridgeState::new(GrpcBridgeConfig::default());
+        metadata.insert("authorization", "Bearer ".parse().unwrap());
+        assert!(!keyless.check_api_key(&metadata));
+    }
+
+    fn chunk(content: &str, is_final: bool) -> ExecuteChunk {
+        ExecuteChunk {
+            r#type: ChunkType::Assistant as i32,
+            content: content.to_string(),
+            session_id: None,
+            cost_usd: None,
+            duration_ms: None,
+            is_final,
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_execute_replay() {
+        let state = GrpcBridgeState::new(GrpcBridgeConfig::default());
+        let IdempotencyClaim::New(record) = state.claim_idempotency_key(1, "key-1").await else {
+            panic!("first claim should be new");
+        };
+
+        // The original caller disconnects mid-stream; recording continues
+        let (tx, rx) = mpsc::channel(4);
+        drop(rx);
+        let mut sink = ChunkSink::new(tx, Some(record));
+        assert!(sink.send(chunk("hello ", false)).await);
+
+        // A retry attaches while the execution is still running
+        let IdempotencyClaim::Duplicate(record) = state.claim_idempotency_key(1, "key-1").await else {
+            panic!("retry should be a duplicate");
+        };
+        let (tx, mut rx) = mpsc::channel(4);
+        let replay = tokio::spawn(replay_execution(record, tx));
+        assert!(sink.send(chunk("world", true)).await);
+        replay.await.unwrap();
+
+        let mut replayed = Vec::new();
+        while let Ok(Ok(chunk)) = rx.try_recv() {
+            replayed.push(chunk.content);
+        }
+        assert_eq!(replayed, ["hello ", "world"]);
+
+        // A retry of an execution that was abandoned doesn't wait forever
+        let IdempotencyClaim::New(record) = state.claim_idempotency_key(1, "key-2").await else {
+            panic!("first claim should be new");
+        };
+        let (tx, _rx) = mpsc::channel(4);
+        let mut sink = ChunkSink::new(tx, Some(record));
+        assert!(sink.send(chunk("partial", false)).await);
+        let IdempotencyClaim::Duplicate(record) = state.claim_idempotency_key(1, "key-2").await else {
+            panic!("retry should be a duplicate");
+        };
+        let (tx, mut rx) = mpsc::channel(4);
+        let replay = tokio::spawn(replay_execution(record, tx));
+        drop(sink);
+        tokio::time::timeout(Duration::from_secs(5), replay).await.unwrap().unwrap();
+        assert_eq!(rx.try_recv().unwrap().unwrap().content, "partial");
+        let last = rx.try_recv().unwrap().unwrap();
+        assert!(last.is_final && last.error.is_some());
+
+        // Keys are per chat, and released keys can be claimed again
+        assert!(matches!(state.claim_idempotency_key(2, "key-1").await, IdempotencyClaim::New(_)));
+        state.release_idempotency_key(2, "key-1").await;
+        assert!(matches!(state.claim_idempotency_key(2, "key-1").await, IdempotencyClaim::New(_)));
+
+        // Expired entries are purged
+        let state = GrpcBridgeState::new(GrpcBridgeConfig {
+            idempotency_ttl_seconds: 0,
+            ..Default::default()
+        });
+        state.claim_idempotency_key(1, "key-1").await;
+        assert!(matches!(state.claim_idempotency_key(1, "key-1").await, IdempotencyClaim::New(_)));
+    }
+
+    fn write_chunk(path: &str, data: &[u8]) -> FileWriteChunk {
+        FileWriteChunk {
+            path: path.to_string(),
+            data: data.to_vec(),
+            mode: Some(0o600),
+            chat_id: 1,
+        }
+    }
+
+    async fn write(state: &GrpcBridgeState, chunks: Vec<FileWriteChunk>) -> FileWriteResponse {
+        write_file_chunks(state, tokio_stream::iter(chunks.into_iter().map(Ok))).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_write_file_jailed_and_chunked() {
+        let allowed = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let state = GrpcBridgeState::new(GrpcBridgeConfig {
+            allowed_admins: vec![1],
+            write_allowed_paths: vec![allowed.path().to_path_buf()],
+            max_write_bytes: 8,
+            ..Default::default()
+        });
+
+        let target = allowed.path().join("app.conf");
+        let target_str = target.to_str().unwrap();
+        let chunks = vec![write_chunk(target_str, b"key="), write_chunk("", b"1\n")];
+        let resp = write(&state, chunks).await;
+        assert!(resp.success, "{:?}", resp.error);
+        assert_eq!(resp.bytes_written, 6);
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "key=1\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&target).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        // Over the size limit: rejected and the original is untouched
+        let chunks = vec![write_chunk(target_str, b"12345"), write_chunk("", b"6789")];
+        let resp = write(&state, chunks).await;
+        assert!(!resp.success);
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "key=1\n");
+        assert_eq!(std::fs::read_dir(allowed.path()).unwrap().count(), 1);
+
+        // Outside the allowlist, or escaping it
+        let escaped = format!("{}/../x", allowed.path().display());
+        for path in [outside.path().join("x").to_str().unwrap(), escaped.as_str(), "relative.conf"] {
+            let resp = write(&state, vec![write_chunk(path, b"x")]).await;
+            assert!(!resp.success, "{} should be rejected", path);
+        }
+
+        #[cfg(unix)]
+        {
+            let link = allowed.path().join("link");
+            std::os::unix::fs::symlink(outside.path(), &link).unwrap();
+            let path = link.join("x");
+            let resp = write(&state, vec![write_chunk(path.to_str().unwrap(), b"x")]).await;
+            assert!(!resp.success);
+            assert!(!outside.path().join("x").exists());
+        }
+    }
+
+    #[test]
+    fn test_autonomous_execute_honours_safe_mode() {
+        let skips = |cli: &ClaudeCli| {
+            claude_command(cli, "task", Some("s1"), true).as_std().get_args()
+                .any(|a| a == crate::claude_cli::SKIP_PERMISSIONS_FLAG)
+        };
+        assert!(skips(&ClaudeCli::default()));
+        assert!(!skips(&ClaudeCli::default().with_safe_mode(true)));
+    }
+
+    async fn run_shell(policy: &SandboxConfig, argv: &[&str]) -> Vec<ShellChunk> {
+        let argv: Vec<String> = argv.iter().map(|a| a.to_string()).collect();
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, mut rx) = mpsc::channel(1024);
+        exec_shell_and_stream(&tx, &argv, policy, dir.path()).await;
+        drop(tx);
+        let mut chunks = Vec::new();
+        while let Some(Ok(chunk)) = rx.recv().await {
+            chunks.push(chunk);
+        }
+        chunks
+    }
+
+    #[tokio::test]
+    async fn test_exec_shell_policy_and_limits() {
+        let disabled = GrpcBridgeState::new(GrpcBridgeConfig::default());
+        assert!(disabled.shell_argv("ls").unwrap_err().contains("disabled"));
+
+        // The default policy is an allowlist, not the skills blocklist
+        let default = GrpcBridgeState::new(GrpcBridgeConfig {
+            shell_policy: Some(default_shell_policy()),
+            ..Default::default()
+        });
+        assert!(default.shell_argv("df -h").is_ok());
+        for refused in ["python3 -c 'print(1)'", "curl http://example.com", "perl -e 1"] {
+            assert!(default.shell_argv(refused).is_err(), "{} should be rejected", refused);
+        }
+
+        let mut policy = default_shell_policy();
+        policy.allowed_commands = ["echo", "seq", "sleep", "ls"].iter().map(|c| c.to_string()).collect();
+        let state = GrpcBridgeState::new(GrpcBridgeConfig {
+            shell_policy: Some(policy.clone()),
+            ..Default::default()
+        });
+        assert_eq!(state.shell_argv("echo 'hello world'").unwrap(), ["echo", "hello world"]);
+        for blocked in ["rm -rf /tmp/x", "'/bin/rm' x", "echo $(id)", "ls ../..", "", "echo 'open"] {
+            assert!(state.shell_argv(blocked).is_err(), "{} should be rejected", blocked);
+        }
+
+        let chunks = run_shell(&policy, &["echo", "hello"]).await;
+        let (last, output) = chunks.split_last().unwrap();
+        assert_eq!(output[0].data, "hello\n");
+        assert_eq!(output[0].stream, ShellStream::Stdout as i32);
+        assert!(last.is_final && !last.truncated && !last.timed_out);
+        assert_eq!(last.exit_code, Some(0));
+
+        // Output limit: cut at the byte limit and the process is killed
+        policy.max_output_bytes = 10;
+        let chunks = run_shell(&policy, &["seq", "1", "100000"]).await;
+        let (last, output) = chunks.split_last().unwrap();
+        assert_eq!(output.iter().map(|c| c.data.len()).sum::<usize>(), 10);
+        assert!(last.truncated && last.exit_code.is_none());
+
+        // Timeout
+        policy.timeout_secs = 1;
+        let last = run_shell(&policy, &["sleep", "5"]).await.pop().unwrap();
+        assert!(last.timed_out && last.duration_ms.unwrap() < 4000);
+
+        let last = run_shell(&policy, &["no-such-command-xyz"]).await.pop().unwrap();
+        assert!(last.error.unwrap().contains("Failed to start"));
     }
 }
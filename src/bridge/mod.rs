@@ -8,6 +8,6 @@ pub mod grpc_server;
 pub mod grpc_client;
 pub mod types;
 
-pub use grpc_server::{GrpcBridgeServer, GrpcBridgeConfig};
+pub use grpc_server::{GrpcBridgeServer, GrpcBridgeConfig, DEFAULT_MAX_WRITE_BYTES};
 pub use grpc_client::{GrpcBridgeClient, GrpcBridgeClientConfig, ExecuteResult};
 pub use types::ClaudeCliOutput;
@@ -2,8 +2,17 @@
 //!
 //! Context-aware caching with SHA256 keys for deduplication.
 //! Provides ~20% cost reduction by caching identical queries.
+//!
+//! Entries remember the chat and memory ids their context was built from,
+//! so clearing a conversation or forgetting a memory drops the responses
+//! that depended on it.
+//!
+//! Cache warming (opt-in) answers the most repeated chat prompts during idle
+//! time and stores them under a context-free key, so the next time one is
+//! asked it's served without a Claude call. Warming spend is capped per day.
 
 use moka::future::Cache;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -11,12 +20,38 @@ use std::time::Duration;
 use tracing::debug;
 
 /// Cache statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CacheStats {
+    pub enabled: bool,
     pub entries: u64,
     pub hits: u64,
     pub misses: u64,
     pub hit_rate_percent: f64,
+    /// Entries dropped for capacity or TTL
+    pub evictions: u64,
+    /// Approximate size of cached content
+    pub bytes: u64,
+}
+
+impl CacheStats {
+    /// Format for display
+    pub fn format(&self) -> String {
+        if !self.enabled {
+            return "Response cache: disabled (CLAUDEBOT_CACHE_ENABLED=false)".to_string();
+        }
+        format!(
+            "Response cache:\n\
+            - Hit rate: {:.1}% ({} hits, {} misses)\n\
+            - Entries: {} (~{:.1} KB)\n\
+            - Evictions: {}",
+            self.hit_rate_percent,
+            self.hits,
+            self.misses,
+            self.entries,
+            self.bytes as f64 / 1024.0,
+            self.evictions
+        )
+    }
 }
 
 /// Cached response entry
@@ -26,6 +61,134 @@ pub struct CachedResponse {
     pub model: String,
     pub input_tokens: usize,
     pub output_tokens: usize,
+    /// Chat whose history was part of the context
+    pub chat_id: Option<i64>,
+    /// Memories included in the context
+    pub memory_ids: Vec<String>,
+}
+
+/// Scope mixed into keys of warmed answers
+const FREQUENT_QUERY_SCOPE: &str = "frequent-query";
+
+/// Idle-time cache warming settings
+#[derive(Debug, Clone)]
+pub struct CacheWarmConfig {
+    pub enabled: bool,
+    /// Most repeated prompts considered per run
+    pub top_n: usize,
+    /// A prompt must have been sent this many times
+    pub min_count: usize,
+    /// Shorter prompts ("ok", "continue") depend on context and are never warmed
+    pub min_chars: usize,
+    /// How far back conversation history is scanned
+    pub lookback_secs: i64,
+    /// Maximum warming spend per local day (USD)
+    pub daily_budget_usd: f64,
+}
+
+impl Default for CacheWarmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            top_n: 5,
+            min_count: 3,
+            min_chars: 15,
+            lookback_secs: 7 * 86400,
+            daily_budget_usd: 0.50,
+        }
+    }
+}
+
+impl CacheWarmConfig {
+    /// `CLAUDEBOT_CACHE_WARM`, `CLAUDEBOT_CACHE_WARM_TOP_N`, `CLAUDEBOT_CACHE_WARM_MIN_COUNT`,
+    /// `CLAUDEBOT_CACHE_WARM_MIN_CHARS`, `CLAUDEBOT_CACHE_WARM_LOOKBACK_SECS`,
+    /// `CLAUDEBOT_CACHE_WARM_BUDGET_USD`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string());
+        if let Some(v) = var("CLAUDEBOT_CACHE_WARM") {
+            config.enabled = v == "true" || v == "1";
+        }
+        if let Some(n) = var("CLAUDEBOT_CACHE_WARM_TOP_N").and_then(|v| v.parse().ok()) {
+            config.top_n = n;
+        }
+        if let Some(n) = var("CLAUDEBOT_CACHE_WARM_MIN_COUNT").and_then(|v| v.parse::<usize>().ok()) {
+            config.min_count = n.max(2);
+        }
+        if let Some(n) = var("CLAUDEBOT_CACHE_WARM_MIN_CHARS").and_then(|v| v.parse().ok()) {
+            config.min_chars = n;
+        }
+        if let Some(n) = var("CLAUDEBOT_CACHE_WARM_LOOKBACK_SECS").and_then(|v| v.parse().ok()) {
+            config.lookback_secs = n;
+        }
+        if let Some(usd) = var("CLAUDEBOT_CACHE_WARM_BUDGET_USD").and_then(|v| v.parse::<f64>().ok()) {
+            config.daily_budget_usd = usd.max(0.0);
+        }
+        config
+    }
+
+    /// Whether a prompt can be answered ahead of time: long enough to stand
+    /// without its context, and a question rather than a request to act
+    pub fn is_warmable(&self, prompt: &str) -> bool {
+        let prompt = prompt.trim();
+        prompt.chars().count() >= self.min_chars && is_read_only_question(prompt)
+    }
+}
+
+/// Words that open an informational question
+const QUESTION_WORDS: &[&str] = &[
+    "what", "what's", "whats", "how", "why", "when", "where", "which", "who", "whose",
+    "is", "are", "does", "do", "was", "were", "explain", "describe",
+];
+
+/// Openings that ask the bot to do something, even when phrased as a question
+const ACTION_REQUESTS: &[&str] = &[
+    "can you", "could you", "would you", "will you", "please", "pls", "let's", "lets",
+];
+
+/// "How do I deploy?" yes; "deploy to staging", "can you restart it?" no
+fn is_read_only_question(prompt: &str) -> bool {
+    let lower = prompt.to_lowercase();
+    if ACTION_REQUESTS.iter().any(|p| lower.starts_with(p)) {
+        return false;
+    }
+    let first = lower.split_whitespace().next().unwrap_or("");
+    let first = first.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'');
+    lower.ends_with('?') || QUESTION_WORDS.contains(&first)
+}
+
+/// Warming spend for the current day
+#[derive(Debug, Clone, Default)]
+pub struct WarmBudget {
+    day: Option<chrono::NaiveDate>,
+    spent_usd: f64,
+}
+
+impl WarmBudget {
+    /// Budget left on `today` (resets when the day changes)
+    pub fn remaining(&mut self, daily_budget_usd: f64, today: chrono::NaiveDate) -> f64 {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.spent_usd = 0.0;
+        }
+        (daily_budget_usd - self.spent_usd).max(0.0)
+    }
+
+    pub fn spend(&mut self, usd: f64) {
+        self.spent_usd += usd;
+    }
+}
+
+/// Result of one warming run
+#[derive(Debug, Clone, Default)]
+pub struct WarmReport {
+    pub warmed: usize,
+    /// Frequent prompts already cached
+    pub already_cached: usize,
+    /// Skipped because the estimate didn't fit the remaining budget
+    pub over_budget: usize,
+    pub failed: usize,
+    pub cost_usd: f64,
 }
 
 /// Context-aware response cache
@@ -34,25 +197,46 @@ pub struct ResponseCache {
     cache: Cache<String, CachedResponse>,
     hits: Arc<AtomicU64>,
     misses: Arc<AtomicU64>,
+    evictions: Arc<AtomicU64>,
     enabled: bool,
 }
 
 impl ResponseCache {
     /// Create new cache with TTL
     pub fn new(max_entries: u64, ttl_secs: u64, enabled: bool) -> Self {
+        let evictions = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&evictions);
         let cache = Cache::builder()
             .max_capacity(max_entries)
             .time_to_live(Duration::from_secs(ttl_secs))
+            .eviction_listener(move |_key, _value, cause| {
+                if cause.was_evicted() {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            })
             .build();
 
         Self {
             cache,
             hits: Arc::new(AtomicU64::new(0)),
             misses: Arc::new(AtomicU64::new(0)),
+            evictions,
             enabled,
         }
     }
 
+    /// Create from `CLAUDEBOT_CACHE_ENABLED` / `CLAUDEBOT_CACHE_TTL` (same as `Config`)
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("CLAUDEBOT_CACHE_ENABLED")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(true);
+        let ttl_secs = std::env::var("CLAUDEBOT_CACHE_TTL")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(3600);
+        Self::new(1000, ttl_secs, enabled)
+    }
+
     /// Compute cache key from query and context
     ///
     /// Key = SHA256(normalized_query + user_context_hash + memory_hash)
@@ -86,6 +270,13 @@ impl ResponseCache {
         hex::encode(hasher.finalize())
     }
 
+    /// Key for a frequent prompt in one chat (used by cache warming)
+    ///
+    /// Warmed answers are only served back to the chat that kept asking.
+    pub fn frequent_query_key(query: &str, chat_id: i64) -> String {
+        Self::compute_key(query, FREQUENT_QUERY_SCOPE, Some(&chat_id.to_string()), None)
+    }
+
     /// Quick hash for large content (first + last 100 chars + length)
     fn quick_hash(content: &str) -> String {
         let len = content.len();
@@ -134,6 +325,11 @@ impl ResponseCache {
         }
     }
 
+    /// Whether `key` is cached, without counting a hit or miss
+    pub fn contains(&self, key: &str) -> bool {
+        self.enabled && self.cache.contains_key(key)
+    }
+
     /// Store response in cache
     pub async fn set(&self, key: &str, response: CachedResponse) {
         if !self.enabled {
@@ -145,13 +341,21 @@ impl ResponseCache {
     }
 
     /// Get cache statistics
+    ///
+    /// Entries and bytes come from a scan of live entries, so they are exact
+    /// even before moka has processed pending removals.
     pub fn stats(&self) -> CacheStats {
         let hits = self.hits.load(Ordering::Relaxed);
         let misses = self.misses.load(Ordering::Relaxed);
         let total = hits + misses;
 
+        let (entries, bytes) = self.cache.iter().fold((0u64, 0u64), |(n, bytes), (key, value)| {
+            (n + 1, bytes + (key.len() + value.content.len() + value.model.len()) as u64)
+        });
+
         CacheStats {
-            entries: self.cache.entry_count(),
+            enabled: self.enabled,
+            entries,
             hits,
             misses,
             hit_rate_percent: if total > 0 {
@@ -159,6 +363,8 @@ impl ResponseCache {
             } else {
                 0.0
             },
+            evictions: self.evictions.load(Ordering::Relaxed),
+            bytes,
         }
     }
 
@@ -167,11 +373,38 @@ impl ResponseCache {
         self.cache.invalidate(key).await;
     }
 
+    /// Drop entries whose context included this chat's history
+    pub async fn invalidate_for_chat(&self, chat_id: i64) -> usize {
+        self.invalidate_where(|entry| entry.chat_id == Some(chat_id)).await
+    }
+
+    /// Drop entries whose context included this memory
+    pub async fn invalidate_for_memory(&self, memory_id: &str) -> usize {
+        self.invalidate_where(|entry| entry.memory_ids.iter().any(|id| id == memory_id)).await
+    }
+
+    async fn invalidate_where(&self, stale: impl Fn(&CachedResponse) -> bool) -> usize {
+        let keys: Vec<_> = self
+            .cache
+            .iter()
+            .filter(|(_, entry)| stale(entry))
+            .map(|(key, _)| key)
+            .collect();
+        for key in &keys {
+            self.cache.invalidate(key.as_str()).await;
+        }
+        if !keys.is_empty() {
+            debug!("Cache invalidated {} entries", keys.len());
+        }
+        keys.len()
+    }
+
     /// Clear all entries
     pub async fn clear(&self) {
         self.cache.invalidate_all();
         self.hits.store(0, Ordering::Relaxed);
         self.misses.store(0, Ordering::Relaxed);
+        self.evictions.store(0, Ordering::Relaxed);
     }
 }
 
@@ -197,6 +430,8 @@ mod tests {
                     model: "sonnet".to_string(),
                     input_tokens: 10,
                     output_tokens: 20,
+                    chat_id: None,
+                    memory_ids: vec![],
                 },
             )
             .await;
@@ -210,6 +445,77 @@ mod tests {
         let stats = cache.stats();
         assert_eq!(stats.hits, 1);
         assert_eq!(stats.misses, 1);
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.bytes, (key.len() + "response".len() + "sonnet".len()) as u64);
+        assert!(stats.format().contains("50.0%"));
+
+        cache.clear().await;
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.hits, stats.bytes), (0, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_by_chat_and_memory() {
+        let cache = ResponseCache::new(100, 3600, true);
+        let entry = |chat_id, memory_ids: &[&str]| CachedResponse {
+            content: "answer".to_string(),
+            model: "sonnet".to_string(),
+            input_tokens: 1,
+            output_tokens: 1,
+            chat_id,
+            memory_ids: memory_ids.iter().map(|id| id.to_string()).collect(),
+        };
+        cache.set(&"a".repeat(16), entry(Some(1), &["m1"])).await;
+        cache.set(&"b".repeat(16), entry(Some(2), &["m1", "m2"])).await;
+        cache.set(&"c".repeat(16), entry(None, &[])).await;
+
+        assert_eq!(cache.invalidate_for_chat(1).await, 1);
+        assert!(cache.get(&"a".repeat(16)).await.is_none());
+        assert_eq!(cache.invalidate_for_memory("m2").await, 1);
+        assert_eq!(cache.invalidate_for_memory("m1").await, 0);
+        assert!(cache.get(&"c".repeat(16)).await.is_some());
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_frequent_query_key_and_warm_budget() {
+        let cache = ResponseCache::new(100, 3600, true);
+        let key = ResponseCache::frequent_query_key("  How do I deploy to staging? ", 1);
+        assert_eq!(key, ResponseCache::frequent_query_key("how do i deploy to staging?", 1));
+        assert_ne!(key, ResponseCache::frequent_query_key("how do i deploy to staging?", 2));
+        assert_ne!(key, ResponseCache::compute_key("how do i deploy to staging?", "sys", None, None));
+        assert!(!cache.contains(&key));
+        cache
+            .set(
+                &key,
+                CachedResponse {
+                    content: "Run ./deploy.sh staging".to_string(),
+                    model: "sonnet".to_string(),
+                    input_tokens: 1,
+                    output_tokens: 1,
+                    chat_id: None,
+                    memory_ids: vec![],
+                },
+            )
+            .await;
+        assert!(cache.contains(&key));
+        assert_eq!(cache.stats().hits + cache.stats().misses, 0);
+
+        let config = CacheWarmConfig::default();
+        assert!(!config.is_warmable("continue"));
+        assert!(config.is_warmable("How do I deploy to staging?"));
+        assert!(config.is_warmable("what does the retention job keep"));
+        assert!(!config.is_warmable("Deploy the branch to staging now"));
+        assert!(!config.is_warmable("Can you restart the staging server?"));
+
+        let day = chrono::NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let mut budget = WarmBudget::default();
+        assert_eq!(budget.remaining(0.5, day), 0.5);
+        budget.spend(0.4);
+        assert!((budget.remaining(0.5, day) - 0.1).abs() < 1e-9);
+        budget.spend(0.3);
+        assert_eq!(budget.remaining(0.5, day), 0.0);
+        assert_eq!(budget.remaining(0.5, day.succ_opt().unwrap()), 0.5);
     }
 
     #[test]
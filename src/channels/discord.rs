@@ -7,10 +7,20 @@
 //! Environment variables:
 //! - `DISCORD_BOT_TOKEN`: Discord bot token
 //! - `DISCORD_APPLICATION_ID`: Discord application ID
+//! - `DISCORD_PUBLIC_KEY`: Application public key (hex), used to verify interactions
+//!
+//! # Interactions Endpoint
+//!
+//! Discord signs each interaction with Ed25519 over the `X-Signature-Timestamp`
+//! header followed by the raw body. Without `DISCORD_PUBLIC_KEY` every
+//! interaction request is rejected.
 
+use super::format::format_response_limited;
+use super::rate_limit::{ChannelRateLimiter, RateLimitConfig};
 use super::traits::*;
 use anyhow::Result;
 use async_trait::async_trait;
+use ring::signature::{UnparsedPublicKey, ED25519};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -26,6 +36,8 @@ pub struct DiscordConfig {
     pub application_id: String,
     /// Allowed guild IDs (empty = all)
     pub allowed_guilds: Vec<String>,
+    /// Application public key (hex) for verifying interaction signatures
+    pub public_key: Option<String>,
     /// Maximum message length (Discord limit: 2000)
     pub max_message_length: usize,
 }
@@ -41,6 +53,7 @@ impl DiscordConfig {
             allowed_guilds: std::env::var("DISCORD_ALLOWED_GUILDS")
                 .map(|s| s.split(',').map(|s| s.trim().to_string()).collect())
                 .unwrap_or_default(),
+            public_key: std::env::var("DISCORD_PUBLIC_KEY").ok().filter(|k| !k.trim().is_empty()),
             max_message_length: 2000,
         })
     }
@@ -53,6 +66,8 @@ pub struct DiscordChannel {
     ready: bool,
     /// Active channel IDs
     active_channels: Arc<RwLock<HashSet<String>>>,
+    /// Inbound rate limiting (shared when registered with `ChannelRegistry`)
+    rate_limiter: Arc<ChannelRateLimiter>,
 }
 
 impl DiscordChannel {
@@ -62,9 +77,16 @@ impl DiscordChannel {
             client: reqwest::Client::new(),
             ready: false,
             active_channels: Arc::new(RwLock::new(HashSet::new())),
+            rate_limiter: Arc::new(ChannelRateLimiter::new("discord", RateLimitConfig::from_env("discord"))),
         }
     }
 
+    /// Use a shared rate limiter
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<ChannelRateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     /// Create from environment
     pub fn from_env() -> Result<Self> {
         Ok(Self::new(DiscordConfig::from_env()?))
@@ -129,52 +151,40 @@ impl DiscordChannel {
         }
     }
 
-    /// Split long messages
+    /// Split long messages, keeping code blocks balanced across chunks
     fn split_message(&self, content: &str) -> Vec<String> {
-        let max_len = self.config.max_message_length;
-        if content.len() <= max_len {
-            return vec![content.to_string()];
-        }
-
-        let mut chunks = Vec::new();
-        let mut current = String::new();
-
-        // Try to split at code block boundaries first
-        let code_block_pattern = "```";
-
-        for line in content.lines() {
-            if current.len() + line.len() + 1 > max_len {
-                // Check if we're in a code block
-                let open_blocks = current.matches(code_block_pattern).count();
-                if open_blocks % 2 == 1 {
-                    // We're in an unclosed code block, close it
-                    current.push_str("\n```");
-                }
-
-                if !current.is_empty() {
-                    chunks.push(current);
-                    current = String::new();
-                }
-
-                // Re-open code block if needed
-                if open_blocks % 2 == 1 {
-                    current.push_str("```\n");
-                }
-
-                current.push_str(line);
-            } else {
-                if !current.is_empty() {
-                    current.push('\n');
-                }
-                current.push_str(line);
-            }
-        }
+        format_response_limited(content, ParseMode::Markdown, self.config.max_message_length)
+            .into_iter()
+            .map(|chunk| chunk.text)
+            .collect()
+    }
 
-        if !current.is_empty() {
-            chunks.push(current);
-        }
+    /// Verify an interaction's `X-Signature-Ed25519` over timestamp + raw body
+    pub fn verify_interaction(
+        &self,
+        signature: Option<&str>,
+        timestamp: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), ChannelError> {
+        let public_key = self
+            .config
+            .public_key
+            .as_deref()
+            .and_then(|k| hex::decode(k.trim()).ok())
+            .ok_or_else(|| {
+                ChannelError::AuthenticationFailed("DISCORD_PUBLIC_KEY not set, cannot verify interaction".to_string())
+            })?;
+        let (Some(signature), Some(timestamp)) = (signature.and_then(|s| hex::decode(s.trim()).ok()), timestamp) else {
+            return Err(ChannelError::AuthenticationFailed(
+                "Missing or malformed signature headers".to_string(),
+            ));
+        };
 
-        chunks
+        let mut message = timestamp.as_bytes().to_vec();
+        message.extend_from_slice(body);
+        UnparsedPublicKey::new(&ED25519, &public_key)
+            .verify(&message, &signature)
+            .map_err(|_| ChannelError::AuthenticationFailed("Invalid Discord signature".to_string()))
     }
 
     /// Parse Discord webhook/gateway event into ChannelMessage
@@ -198,6 +208,13 @@ impl DiscordChannel {
             raw: Some(serde_json::to_value(event).unwrap_or_default()),
         }
     }
+
+    /// Parse an inbound gateway event, refusing it while the sender is rate limited
+    pub async fn receive(&self, event: &DiscordMessageEvent) -> Result<ChannelMessage, ChannelError> {
+        let message = self.parse_message(event);
+        self.admit(&message).await?;
+        Ok(message)
+    }
 }
 
 #[async_trait]
@@ -374,6 +391,10 @@ impl Channel for DiscordChannel {
             "allowed_guilds": &self.config.allowed_guilds,
         })
     }
+
+    fn rate_limiter(&self) -> Option<&ChannelRateLimiter> {
+        Some(&self.rate_limiter)
+    }
 }
 
 #[async_trait]
@@ -448,6 +469,7 @@ mod tests {
             bot_token: "test".to_string(),
             application_id: "test".to_string(),
             allowed_guilds: vec![],
+            public_key: None,
             max_message_length: 50,
         };
         let channel = DiscordChannel::new(config);
@@ -457,4 +479,54 @@ mod tests {
         // Should preserve code block integrity
         assert!(!chunks.is_empty());
     }
+
+    #[test]
+    fn test_verify_interaction_signature() {
+        use ring::signature::{Ed25519KeyPair, KeyPair};
+
+        let keypair = Ed25519KeyPair::from_seed_unchecked(&[7u8; 32]).unwrap();
+        let config = DiscordConfig {
+            bot_token: "test".to_string(),
+            application_id: "test".to_string(),
+            allowed_guilds: vec![],
+            public_key: Some(hex::encode(keypair.public_key().as_ref())),
+            max_message_length: 2000,
+        };
+        let channel = DiscordChannel::new(config.clone());
+
+        let body = br#"{"type":1}"#;
+        let signature = hex::encode(keypair.sign(&[b"1700000000".as_slice(), body].concat()).as_ref());
+
+        assert!(channel.verify_interaction(Some(&signature), Some("1700000000"), body).is_ok());
+        assert!(channel.verify_interaction(Some(&signature), Some("1700000001"), body).is_err());
+        assert!(channel.verify_interaction(Some(&signature), Some("1700000000"), br#"{"type":2}"#).is_err());
+        assert!(channel.verify_interaction(None, Some("1700000000"), body).is_err());
+
+        let unconfigured = DiscordChannel::new(DiscordConfig { public_key: None, ..config });
+        assert!(unconfigured.verify_interaction(Some(&signature), Some("1700000000"), body).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_admit_rate_limited() {
+        let config = DiscordConfig {
+            bot_token: "test".to_string(),
+            application_id: "test".to_string(),
+            allowed_guilds: vec![],
+            public_key: None,
+            max_message_length: 2000,
+        };
+        let limiter = ChannelRateLimiter::new("discord", RateLimitConfig {
+            max_requests: 1,
+            burst_allowance: 0,
+            ..RateLimitConfig::discord()
+        });
+        let channel = DiscordChannel::new(config).with_rate_limiter(Arc::new(limiter));
+
+        let message = ChannelMessage::text("discord", "user", "chat", "hello");
+        assert!(channel.admit(&message).await.is_ok());
+        assert!(matches!(
+            channel.admit(&message).await,
+            Err(ChannelError::RateLimited(_))
+        ));
+    }
 }
@@ -0,0 +1,326 @@
+//! Channel-agnostic Response Formatting
+//!
+//! Turns Claude's markdown output into chunks a channel can send as-is:
+//! - Splits on line boundaries to fit the platform's message-size limit
+//! - Keeps code blocks intact across chunks (closed and reopened)
+//! - Applies the target's escaping rules
+//!
+//! Targets follow `ParseMode`:
+//! - `Html` - Telegram HTML (`<pre><code>`, `<code>`, `&lt;` escapes)
+//! - `Markdown` - passed through (Discord, Slack, WebChat, WhatsApp)
+//! - `Plain` - code fences and inline backticks removed
+
+use super::ParseMode;
+
+/// Default chunk size for HTML (Telegram allows 4096, kept under for safety)
+pub const HTML_MAX_LENGTH: usize = 4000;
+
+/// Default chunk size for Markdown (Discord's 2000 limit, the smallest channel)
+pub const MARKDOWN_MAX_LENGTH: usize = 2000;
+
+/// Default chunk size for plain text (WhatsApp's 4096 limit)
+pub const PLAIN_MAX_LENGTH: usize = 4096;
+
+const FENCE: &str = "```";
+
+/// One message's worth of formatted output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattedChunk {
+    /// Rendered text for the target parse mode
+    pub text: String,
+    pub parse_mode: ParseMode,
+    /// Unrendered source of this chunk, for when the platform rejects `text`
+    pub plain: String,
+}
+
+impl ParseMode {
+    /// Default message-size limit for this target
+    pub fn max_length(&self) -> usize {
+        match self {
+            Self::Html => HTML_MAX_LENGTH,
+            Self::Markdown => MARKDOWN_MAX_LENGTH,
+            Self::Plain => PLAIN_MAX_LENGTH,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Markdown => "markdown",
+            Self::Plain => "plain",
+        }
+    }
+
+    /// Parse "markdown", "html" or "plain" (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "html" => Some(Self::Html),
+            "plain" | "text" => Some(Self::Plain),
+            _ => None,
+        }
+    }
+}
+
+/// Format a response for a target, split to the target's default limit
+pub fn format_response(text: &str, target: ParseMode) -> Vec<FormattedChunk> {
+    format_response_limited(text, target, target.max_length())
+}
+
+/// Format a response for a target, split so each rendered chunk fits `max_len` bytes
+pub fn format_response_limited(text: &str, target: ParseMode, max_len: usize) -> Vec<FormattedChunk> {
+    split_source(text, target, max_len.max(32))
+        .into_iter()
+        .map(|plain| FormattedChunk {
+            text: render(&plain, target),
+            parse_mode: target,
+            plain,
+        })
+        .collect()
+}
+
+/// Render one chunk of markdown for a target
+pub fn render(text: &str, target: ParseMode) -> String {
+    match target {
+        ParseMode::Html => markdown_to_html(text),
+        ParseMode::Markdown => text.to_string(),
+        ParseMode::Plain => strip_markdown_code(text),
+    }
+}
+
+/// Convert markdown code blocks and inline code to Telegram HTML
+pub fn markdown_to_html(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 100);
+    let mut chars = text.chars().peekable();
+    let mut in_code_block = false;
+    let mut in_inline_code = false;
+
+    while let Some(c) = chars.next() {
+        if c == '`' {
+            // Check for code block (```)
+            if chars.peek() == Some(&'`') {
+                chars.next(); // consume second `
+                if chars.peek() == Some(&'`') {
+                    chars.next(); // consume third `
+
+                    if in_code_block {
+                        result.push_str("</code></pre>");
+                        in_code_block = false;
+                    } else {
+                        // Skip language identifier if present (e.g., ```rust)
+                        while let Some(&ch) = chars.peek() {
+                            if ch == '\n' || ch == '\r' {
+                                chars.next();
+                                break;
+                            } else if ch.is_alphanumeric() || ch == '_' || ch == '-' {
+                                chars.next();
+                            } else {
+                                break;
+                            }
+                        }
+                        result.push_str("<pre><code>");
+                        in_code_block = true;
+                    }
+                    continue;
+                }
+            }
+
+            // Single backtick - inline code
+            if !in_code_block {
+                if in_inline_code {
+                    result.push_str("</code>");
+                    in_inline_code = false;
+                } else {
+                    result.push_str("<code>");
+                    in_inline_code = true;
+                }
+                continue;
+            }
+        }
+
+        // Escape everywhere, code included, to prevent HTML injection
+        match c {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            _ => result.push(c),
+        }
+    }
+
+    // Close any unclosed tags
+    if in_inline_code {
+        result.push_str("</code>");
+    }
+    if in_code_block {
+        result.push_str("</code></pre>");
+    }
+
+    result
+}
+
+/// Languages of the fenced code blocks in `text`, in order of first use
+///
+/// Taken from the opening fence (```rust -> "rust"), lowercased; blocks
+/// without a language are skipped. Lets a frontend load only the
+/// highlighters it needs.
+pub fn code_block_languages(text: &str) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    let mut in_block = false;
+    for line in text.lines() {
+        let Some(info) = line.trim_start().strip_prefix(FENCE) else {
+            continue;
+        };
+        if !in_block {
+            let language = info.split_whitespace().next().unwrap_or("").to_lowercase();
+            if !language.is_empty() && !languages.contains(&language) {
+                languages.push(language);
+            }
+        }
+        in_block = !in_block;
+    }
+    languages
+}
+
+/// Drop code fences and inline backticks, keeping their content
+fn strip_markdown_code(text: &str) -> String {
+    text.split_inclusive('\n')
+        .filter(|line| !line.trim_start().starts_with(FENCE))
+        .collect::<String>()
+        .replace('`', "")
+        .trim_end()
+        .to_string()
+}
+
+/// Worst-case rendered size of a source line
+fn rendered_len(line: &str, target: ParseMode) -> usize {
+    match target {
+        ParseMode::Html if line.trim_start().starts_with(FENCE) => "</code></pre>".len() + 1,
+        ParseMode::Html => line
+            .chars()
+            .map(|c| match c {
+                '&' => "&amp;".len(),
+                '<' | '>' => "&lt;".len(),
+                '`' => "</code>".len(),
+                _ => c.len_utf8(),
+            })
+            .sum(),
+        _ => line.len(),
+    }
+}
+
+/// Split markdown source into chunks whose rendering fits `max_len`,
+/// closing an open code block at each split and reopening it in the next chunk
+fn split_source(text: &str, target: ParseMode, max_len: usize) -> Vec<String> {
+    // Room kept for closing a code block the chunk ends inside
+    let reserve = rendered_len(FENCE, target) + 1;
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    // Opening fence line (with language) of the code block we're inside
+    let mut open_fence: Option<String> = None;
+
+    for line in text.split_inclusive('\n') {
+        let is_fence = line.trim_start().starts_with(FENCE);
+        // A chunk ending after this line inside a code block needs room to close it
+        let closing_room = if open_fence.is_some() != is_fence { reserve } else { 0 };
+        for piece in split_long_line(line, target, max_len.saturating_sub(2 * reserve).max(16)) {
+            let cost = rendered_len(piece, target);
+            if !current.is_empty() && current_len + cost + closing_room > max_len {
+                if open_fence.is_some() {
+                    if !current.ends_with('\n') {
+                        current.push('\n');
+                    }
+                    current.push_str(FENCE);
+                }
+                chunks.push(std::mem::take(&mut current).trim_end().to_string());
+                current_len = 0;
+                if let Some(fence) = &open_fence {
+                    current.push_str(fence);
+                    current.push('\n');
+                    current_len = rendered_len(fence, target) + 1;
+                }
+            }
+            current.push_str(piece);
+            current_len += cost;
+        }
+
+        if is_fence {
+            open_fence = match open_fence {
+                Some(_) => None,
+                None => Some(line.trim_end().trim_start().to_string()),
+            };
+        }
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current.trim_end().to_string());
+    }
+    chunks.retain(|chunk| !chunk.trim().is_empty());
+    chunks
+}
+
+/// Cut a line into pieces rendering to at most `max_len`, on char boundaries
+fn split_long_line(line: &str, target: ParseMode, max_len: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+    while rendered_len(rest, target) > max_len {
+        let mut size = 0;
+        let split_at = rest
+            .char_indices()
+            .take_while(|(_, c)| {
+                size += rendered_len(c.encode_utf8(&mut [0; 4]), target);
+                size <= max_len
+            })
+            .last()
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(rest.len());
+        let (piece, tail) = rest.split_at(split_at);
+        pieces.push(piece);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_rendering_escapes_and_converts_code() {
+        let chunks = format_response("Use `a<b` here:\n```rust\nif x && y {}\n```", ParseMode::Html);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0].text,
+            "Use <code>a&lt;b</code> here:\n<pre><code>if x &amp;&amp; y {}\n</code></pre>"
+        );
+        assert_eq!(chunks[0].plain, "Use `a<b` here:\n```rust\nif x && y {}\n```");
+
+        let plain = format_response("Run `ls`\n```\nls -la\n```", ParseMode::Plain);
+        assert_eq!(plain[0].text, "Run ls\nls -la");
+    }
+
+    #[test]
+    fn test_split_keeps_code_blocks_balanced() {
+        let body: String = (0..40).map(|i| format!("let x{} = {};\n", i, i)).collect();
+        let text = format!("Intro\n```rust\n{}```\nOutro", body);
+
+        for target in [ParseMode::Markdown, ParseMode::Html] {
+            let chunks = format_response_limited(&text, target, 120);
+            assert!(chunks.len() > 2);
+            for chunk in &chunks {
+                assert!(chunk.text.len() <= 120, "{:?} chunk too long: {}", target, chunk.text.len());
+                assert_eq!(chunk.plain.matches(FENCE).count() % 2, 0, "unbalanced: {}", chunk.plain);
+            }
+            assert!(chunks[1].plain.starts_with("```rust\n"));
+            assert!(chunks.last().unwrap().plain.ends_with("Outro"));
+        }
+
+        let long = "é".repeat(300);
+        let chunks = format_response_limited(&long, ParseMode::Plain, 100);
+        assert!(chunks.iter().all(|c| c.text.len() <= 100));
+        assert_eq!(chunks.iter().map(|c| c.plain.as_str()).collect::<String>(), long);
+    }
+}
@@ -10,21 +10,28 @@
 //! Each channel implements the `Channel` trait for unified message handling.
 
 pub mod traits;
+pub mod format;
 pub mod whatsapp;
 pub mod discord;
 pub mod webchat;
 pub mod rate_limit;
+pub mod webhook;
 
 pub use traits::{ChannelMessage, MessageType, ChannelError, ChannelResponse, ResponseButton, ParseMode};
+pub use format::{code_block_languages, format_response, format_response_limited, FormattedChunk};
 pub use rate_limit::{ChannelRateLimiter, RateLimitConfig, RateLimitResult, RateLimitStats};
 pub use whatsapp::{WhatsAppChannel, WhatsAppConfig};
 pub use discord::{DiscordChannel, DiscordConfig};
 pub use webchat::{WebChatChannel, WebChatConfig};
+pub use webhook::{discord_webhook_router, whatsapp_webhook_router, WebhookState};
 
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tokio::sync::RwLock;
 
 /// Supported channel types
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ChannelType {
     Telegram,
     WhatsApp,
@@ -53,6 +60,8 @@ pub struct ChannelRegistry {
     discord: RwLock<Option<DiscordChannel>>,
     /// Active WebChat channels
     webchat: RwLock<Option<WebChatChannel>>,
+    /// Rate limiter shared by all registered channels
+    rate_limiter: Arc<ChannelRateLimiter>,
 }
 
 impl ChannelRegistry {
@@ -61,22 +70,28 @@ impl ChannelRegistry {
             whatsapp: RwLock::new(None),
             discord: RwLock::new(None),
             webchat: RwLock::new(None),
+            rate_limiter: Arc::new(ChannelRateLimiter::for_channels()),
         }
     }
 
+    /// Shared rate limiter, keyed by (channel, user)
+    pub fn rate_limiter(&self) -> &Arc<ChannelRateLimiter> {
+        &self.rate_limiter
+    }
+
     /// Register WhatsApp channel
     pub async fn set_whatsapp(&self, channel: WhatsAppChannel) {
-        *self.whatsapp.write().await = Some(channel);
+        *self.whatsapp.write().await = Some(channel.with_rate_limiter(Arc::clone(&self.rate_limiter)));
     }
 
     /// Register Discord channel
     pub async fn set_discord(&self, channel: DiscordChannel) {
-        *self.discord.write().await = Some(channel);
+        *self.discord.write().await = Some(channel.with_rate_limiter(Arc::clone(&self.rate_limiter)));
     }
 
     /// Register WebChat channel
     pub async fn set_webchat(&self, channel: WebChatChannel) {
-        *self.webchat.write().await = Some(channel);
+        *self.webchat.write().await = Some(channel.with_rate_limiter(Arc::clone(&self.rate_limiter)));
     }
 
     /// List active channels
@@ -3,9 +3,9 @@
 //! Provides per-user and per-channel rate limiting to prevent abuse.
 //!
 //! Features:
-//! - Per-user request limits
+//! - Requests keyed by (channel, user), with per-channel limits
 //! - Per-channel global limits
-//! - Sliding window algorithm
+//! - Sliding or fixed window algorithm
 //! - Burst allowance
 //! - Automatic cleanup of expired entries
 
@@ -15,6 +15,8 @@ use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
+use super::traits::ChannelMessage;
+
 /// Rate limiter configuration
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
@@ -30,6 +32,8 @@ pub struct RateLimitConfig {
     pub enable_global_limit: bool,
     /// Global limit (requests per window across all users)
     pub global_max_requests: u32,
+    /// Reset counts when the window elapses instead of sliding it
+    pub fixed_window: bool,
 }
 
 impl Default for RateLimitConfig {
@@ -41,6 +45,7 @@ impl Default for RateLimitConfig {
             cooldown_secs: 30,       // 30 second cooldown after limit
             enable_global_limit: true,
             global_max_requests: 1000, // 1000 total per minute
+            fixed_window: false,
         }
     }
 }
@@ -55,6 +60,7 @@ impl RateLimitConfig {
             cooldown_secs: 60,
             enable_global_limit: true,
             global_max_requests: 500,
+            fixed_window: false,
         }
     }
 
@@ -67,8 +73,67 @@ impl RateLimitConfig {
             cooldown_secs: 10,
             enable_global_limit: true,
             global_max_requests: 5000,
+            fixed_window: false,
         }
     }
+
+    /// Telegram: 20 requests per fixed one-minute window, no cooldown
+    pub fn telegram() -> Self {
+        Self {
+            max_requests: 20,
+            window_secs: 60,
+            burst_allowance: 0,
+            cooldown_secs: 0,
+            enable_global_limit: false,
+            global_max_requests: 0,
+            fixed_window: true,
+        }
+    }
+
+    /// Discord: users tend to send quick bursts of short messages
+    pub fn discord() -> Self {
+        Self {
+            max_requests: 20,
+            window_secs: 60,
+            burst_allowance: 10,
+            cooldown_secs: 15,
+            enable_global_limit: true,
+            global_max_requests: 1000,
+            fixed_window: false,
+        }
+    }
+
+    /// Default limits for a channel by name
+    pub fn for_channel(channel: &str) -> Self {
+        match channel {
+            "telegram" => Self::telegram(),
+            "discord" => Self::discord(),
+            "whatsapp" => Self::strict(),
+            _ => Self::default(),
+        }
+    }
+
+    /// Channel defaults with environment overrides
+    ///
+    /// - `<CHANNEL>_RATE_LIMIT` - requests per window
+    /// - `<CHANNEL>_RATE_LIMIT_WINDOW` - window in seconds
+    /// - `<CHANNEL>_RATE_LIMIT_BURST` - burst allowance
+    pub fn from_env(channel: &str) -> Self {
+        let prefix = channel.to_uppercase();
+        let var = |suffix: &str| std::env::var(format!("{}_RATE_LIMIT{}", prefix, suffix)).ok();
+
+        let mut config = Self::for_channel(channel);
+        if let Some(max) = var("").and_then(|s| s.parse().ok()) {
+            config.max_requests = max;
+        }
+        if let Some(window) = var("_WINDOW").and_then(|s| s.parse().ok()).filter(|&w| w > 0) {
+            config.window_secs = window;
+        }
+        if let Some(burst) = var("_BURST").and_then(|s| s.parse().ok()) {
+            config.burst_allowance = burst;
+        }
+        config
+    }
 }
 
 /// Rate limit entry for tracking requests
@@ -97,6 +162,21 @@ impl RateLimitEntry {
         self.requests.retain(|&t| t > cutoff);
     }
 
+    /// Start a new window once the current one has elapsed (fixed window mode)
+    fn reset_if_elapsed(&mut self, window: Duration) {
+        if self.requests.first().is_some_and(|t| t.elapsed() >= window) {
+            self.requests.clear();
+        }
+    }
+
+    /// Time until the oldest tracked request leaves the window
+    fn window_remaining(&self, window: Duration) -> Duration {
+        self.requests
+            .first()
+            .map(|t| window.saturating_sub(t.elapsed()))
+            .unwrap_or(window)
+    }
+
     /// Check if in cooldown period
     fn check_cooldown(&mut self, cooldown: Duration) -> bool {
         if let Some(last) = self.last_limited {
@@ -147,14 +227,23 @@ impl RateLimitResult {
     }
 }
 
+/// Known channels configured by `ChannelRateLimiter::for_channels`
+const CHANNELS: [&str; 4] = ["telegram", "whatsapp", "discord", "webchat"];
+
 /// Channel rate limiter
+///
+/// Requests are tracked per (channel, user). Each channel has its own
+/// limits; channels without explicit limits use the default channel's.
 pub struct ChannelRateLimiter {
+    /// Limits for the default channel (used by `check`)
     config: RateLimitConfig,
-    /// Per-user limits: user_id -> entry
-    user_limits: Arc<RwLock<HashMap<String, RateLimitEntry>>>,
-    /// Global request counter
-    global_requests: Arc<RwLock<Vec<Instant>>>,
-    /// Channel name for logging
+    /// Per-channel limit overrides
+    channel_configs: HashMap<String, RateLimitConfig>,
+    /// Per-user limits: (channel, user_id) -> entry
+    user_limits: Arc<RwLock<HashMap<(String, String), RateLimitEntry>>>,
+    /// Global request counters per channel
+    global_requests: Arc<RwLock<HashMap<String, Vec<Instant>>>>,
+    /// Default channel name (also used for logging)
     channel_name: String,
 }
 
@@ -163,8 +252,9 @@ impl ChannelRateLimiter {
     pub fn new(channel_name: &str, config: RateLimitConfig) -> Self {
         Self {
             config,
+            channel_configs: HashMap::new(),
             user_limits: Arc::new(RwLock::new(HashMap::new())),
-            global_requests: Arc::new(RwLock::new(Vec::new())),
+            global_requests: Arc::new(RwLock::new(HashMap::new())),
             channel_name: channel_name.to_string(),
         }
     }
@@ -174,27 +264,62 @@ impl ChannelRateLimiter {
         Self::new(channel_name, RateLimitConfig::default())
     }
 
-    /// Check if a request from user is allowed
+    /// Shared limiter for all known channels, with `RateLimitConfig::from_env` limits
+    pub fn for_channels() -> Self {
+        CHANNELS.iter().fold(
+            Self::new("telegram", RateLimitConfig::from_env("telegram")),
+            |limiter, channel| limiter.with_channel(channel, RateLimitConfig::from_env(channel)),
+        )
+    }
+
+    /// Set limits for a specific channel
+    pub fn with_channel(mut self, channel: &str, config: RateLimitConfig) -> Self {
+        if channel == self.channel_name {
+            self.config = config.clone();
+        }
+        self.channel_configs.insert(channel.to_string(), config);
+        self
+    }
+
+    /// Limits applied to a channel
+    pub fn config_for(&self, channel: &str) -> &RateLimitConfig {
+        self.channel_configs.get(channel).unwrap_or(&self.config)
+    }
+
+    /// Check if a request from user is allowed on the default channel
     pub async fn check(&self, user_id: &str) -> RateLimitResult {
-        let window = Duration::from_secs(self.config.window_secs);
-        let cooldown = Duration::from_secs(self.config.cooldown_secs);
-        let max_with_burst = self.config.max_requests + self.config.burst_allowance;
+        let channel = self.channel_name.clone();
+        self.check_for(&channel, user_id).await
+    }
+
+    /// Check an inbound channel message (keyed by its channel and sender)
+    pub async fn check_message(&self, message: &ChannelMessage) -> RateLimitResult {
+        self.check_for(&message.channel, &message.sender_id).await
+    }
+
+    /// Check if a request from user is allowed on a channel
+    pub async fn check_for(&self, channel: &str, user_id: &str) -> RateLimitResult {
+        let config = self.config_for(channel);
+        let window = Duration::from_secs(config.window_secs);
+        let cooldown = Duration::from_secs(config.cooldown_secs);
+        let max_with_burst = config.max_requests + config.burst_allowance;
 
         // Check global limit first
-        if self.config.enable_global_limit {
+        if config.enable_global_limit {
             let mut global = self.global_requests.write().await;
+            let requests = global.entry(channel.to_string()).or_default();
             let cutoff = Instant::now() - window;
-            global.retain(|&t| t > cutoff);
+            requests.retain(|&t| t > cutoff);
 
-            if global.len() >= self.config.global_max_requests as usize {
+            if requests.len() >= config.global_max_requests as usize {
                 warn!(
                     "Channel {} hit global rate limit ({} requests)",
-                    self.channel_name,
-                    global.len()
+                    channel,
+                    requests.len()
                 );
                 return RateLimitResult::denied(
                     "Channel global rate limit exceeded",
-                    self.config.window_secs,
+                    config.window_secs,
                     false,
                 );
             }
@@ -203,7 +328,7 @@ impl ChannelRateLimiter {
         // Check per-user limit
         let mut limits = self.user_limits.write().await;
         let entry = limits
-            .entry(user_id.to_string())
+            .entry((channel.to_string(), user_id.to_string()))
             .or_insert_with(RateLimitEntry::new);
 
         // Check cooldown
@@ -219,45 +344,47 @@ impl ChannelRateLimiter {
         }
 
         // Cleanup old requests
-        entry.cleanup(window);
+        if config.fixed_window {
+            entry.reset_if_elapsed(window);
+        } else {
+            entry.cleanup(window);
+        }
 
         // Check if over limit
         if entry.requests.len() >= max_with_burst as usize {
             entry.last_limited = Some(Instant::now());
-            entry.in_cooldown = true;
+            entry.in_cooldown = config.cooldown_secs > 0;
 
             warn!(
                 "User {} rate limited on channel {} ({} requests)",
                 user_id,
-                self.channel_name,
+                channel,
                 entry.requests.len()
             );
 
-            return RateLimitResult::denied(
-                "Rate limit exceeded",
-                self.config.cooldown_secs,
-                true,
-            );
+            let reset_after = if config.fixed_window {
+                entry.window_remaining(window).as_secs()
+            } else {
+                config.cooldown_secs
+            };
+            return RateLimitResult::denied("Rate limit exceeded", reset_after, entry.in_cooldown);
         }
 
         // Allow request
         entry.requests.push(Instant::now());
 
         // Update global counter
-        if self.config.enable_global_limit {
+        if config.enable_global_limit {
             let mut global = self.global_requests.write().await;
-            global.push(Instant::now());
+            global.entry(channel.to_string()).or_default().push(Instant::now());
         }
 
         let remaining = max_with_burst.saturating_sub(entry.requests.len() as u32);
-        let oldest = entry.requests.first().copied();
-        let reset_after = oldest
-            .map(|t| window.saturating_sub(t.elapsed()).as_secs())
-            .unwrap_or(self.config.window_secs);
+        let reset_after = entry.window_remaining(window).as_secs();
 
         debug!(
             "User {} allowed on channel {} ({} remaining)",
-            user_id, self.channel_name, remaining
+            user_id, channel, remaining
         );
 
         RateLimitResult::allowed(remaining, reset_after)
@@ -267,53 +394,73 @@ impl ChannelRateLimiter {
     pub async fn record(&self, user_id: &str) {
         // Already recorded in check(), this is for explicit recording
         let mut limits = self.user_limits.write().await;
-        if let Some(entry) = limits.get_mut(user_id) {
+        if let Some(entry) = limits.get_mut(&(self.channel_name.clone(), user_id.to_string())) {
             entry.requests.push(Instant::now());
         }
     }
 
-    /// Reset limits for a user (admin action)
+    /// Reset limits for a user on the default channel (admin action)
     pub async fn reset_user(&self, user_id: &str) {
+        let channel = self.channel_name.clone();
+        self.reset_user_for(&channel, user_id).await;
+    }
+
+    /// Reset limits for a user on a channel (admin action)
+    pub async fn reset_user_for(&self, channel: &str, user_id: &str) {
         let mut limits = self.user_limits.write().await;
-        limits.remove(user_id);
+        limits.remove(&(channel.to_string(), user_id.to_string()));
     }
 
     /// Cleanup expired entries (call periodically)
     pub async fn cleanup(&self) {
-        let window = Duration::from_secs(self.config.window_secs);
-        let cutoff = Instant::now() - window - Duration::from_secs(60);
-
         // Cleanup user entries
         let mut limits = self.user_limits.write().await;
-        limits.retain(|_, entry| {
-            entry.cleanup(window);
-            !entry.requests.is_empty() || entry.last_limited.map(|t| t > cutoff).unwrap_or(false)
+        limits.retain(|(channel, _), entry| {
+            let config = self.config_for(channel);
+            let window = Duration::from_secs(config.window_secs);
+            // Same pruning as `check_for`: a fixed window keeps every request
+            // until it ends, or the user's count would drop mid-window
+            if config.fixed_window {
+                entry.reset_if_elapsed(window);
+            } else {
+                entry.cleanup(window);
+            }
+            let keep_limited = window.max(Duration::from_secs(config.cooldown_secs)) + Duration::from_secs(60);
+            !entry.requests.is_empty() || entry.last_limited.is_some_and(|t| t.elapsed() < keep_limited)
         });
 
         // Cleanup global
-        if self.config.enable_global_limit {
-            let mut global = self.global_requests.write().await;
-            let cutoff = Instant::now() - window;
-            global.retain(|&t| t > cutoff);
+        let mut global = self.global_requests.write().await;
+        for (channel, requests) in global.iter_mut() {
+            let cutoff = Instant::now() - Duration::from_secs(self.config_for(channel).window_secs);
+            requests.retain(|&t| t > cutoff);
         }
     }
 
-    /// Get current stats
+    /// Get current stats for the default channel
     pub async fn stats(&self) -> RateLimitStats {
+        let channel = self.channel_name.clone();
+        self.stats_for(&channel).await
+    }
+
+    /// Get current stats for a channel
+    pub async fn stats_for(&self, channel: &str) -> RateLimitStats {
         let limits = self.user_limits.read().await;
         let global = self.global_requests.read().await;
 
-        let active_users = limits.len();
-        let limited_users = limits.values().filter(|e| e.in_cooldown).count();
-        let total_requests: usize = limits.values().map(|e| e.requests.len()).sum();
+        let entries: Vec<&RateLimitEntry> = limits
+            .iter()
+            .filter(|((c, _), _)| c == channel)
+            .map(|(_, e)| e)
+            .collect();
 
         RateLimitStats {
-            channel: self.channel_name.clone(),
-            active_users,
-            limited_users,
-            total_requests_in_window: total_requests,
-            global_requests_in_window: global.len(),
-            config: self.config.clone(),
+            channel: channel.to_string(),
+            active_users: entries.len(),
+            limited_users: entries.iter().filter(|e| e.in_cooldown).count(),
+            total_requests_in_window: entries.iter().map(|e| e.requests.len()).sum(),
+            global_requests_in_window: global.get(channel).map(|g| g.len()).unwrap_or(0),
+            config: self.config_for(channel).clone(),
         }
     }
 }
@@ -342,6 +489,7 @@ mod tests {
             cooldown_secs: 30,
             enable_global_limit: false,
             global_max_requests: 1000,
+            fixed_window: false,
         });
 
         for i in 0..5 {
@@ -359,6 +507,7 @@ mod tests {
             cooldown_secs: 30,
             enable_global_limit: false,
             global_max_requests: 1000,
+            fixed_window: false,
         });
 
         // Use up limit
@@ -382,6 +531,7 @@ mod tests {
             cooldown_secs: 30,
             enable_global_limit: false,
             global_max_requests: 1000,
+            fixed_window: false,
         });
 
         // User 1 hits limit
@@ -404,6 +554,7 @@ mod tests {
             cooldown_secs: 30,
             enable_global_limit: false,
             global_max_requests: 1000,
+            fixed_window: false,
         });
 
         // Should allow 3 + 2 = 5 requests
@@ -417,6 +568,57 @@ mod tests {
         assert!(!result.allowed);
     }
 
+    #[tokio::test]
+    async fn test_per_channel_limits() {
+        let limiter = ChannelRateLimiter::new("telegram", RateLimitConfig::telegram())
+            .with_channel("discord", RateLimitConfig {
+                max_requests: 1,
+                burst_allowance: 0,
+                enable_global_limit: false,
+                ..RateLimitConfig::discord()
+            });
+
+        // Same user ID on different channels is tracked separately
+        assert!(limiter.check_for("discord", "42").await.allowed);
+        assert!(!limiter.check_for("discord", "42").await.allowed);
+        assert!(limiter.check_for("telegram", "42").await.allowed);
+
+        let message = ChannelMessage::text("discord", "43", "chat", "hi");
+        assert!(limiter.check_message(&message).await.allowed);
+        assert!(!limiter.check_message(&message).await.allowed);
+
+        assert_eq!(limiter.stats_for("discord").await.active_users, 2);
+        assert_eq!(limiter.stats().await.active_users, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fixed_window_no_cooldown() {
+        let limiter = ChannelRateLimiter::new("telegram", RateLimitConfig {
+            max_requests: 2,
+            window_secs: 1,
+            ..RateLimitConfig::telegram()
+        });
+
+        assert!(limiter.check("1").await.allowed);
+        assert!(limiter.check("1").await.allowed);
+        let denied = limiter.check("1").await;
+        assert!(!denied.allowed);
+        assert!(!denied.in_cooldown);
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert!(limiter.check("1").await.allowed);
+
+        // Periodic cleanup doesn't shift a fixed window: after it ends, the
+        // next window gets its full allowance
+        assert!(limiter.check("2").await.allowed);
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert!(limiter.check("2").await.allowed);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        limiter.cleanup().await;
+        assert!(limiter.check("2").await.allowed);
+        assert!(limiter.check("2").await.allowed);
+    }
+
     #[tokio::test]
     async fn test_stats() {
         let limiter = ChannelRateLimiter::new("test", RateLimitConfig::default());
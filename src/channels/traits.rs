@@ -7,6 +7,8 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use super::rate_limit::ChannelRateLimiter;
+
 /// Error types for channel operations
 #[derive(Debug, thiserror::Error)]
 pub enum ChannelError {
@@ -225,7 +227,7 @@ pub struct MediaAttachment {
 }
 
 /// Parse mode for message formatting
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum ParseMode {
     #[default]
     Markdown,
@@ -278,6 +280,24 @@ pub trait Channel: Send + Sync {
 
     /// Get channel-specific configuration
     fn config(&self) -> serde_json::Value;
+
+    /// Rate limiter consulted for inbound messages
+    fn rate_limiter(&self) -> Option<&ChannelRateLimiter> {
+        None
+    }
+
+    /// Check an inbound message against rate limits before dispatching to Claude
+    async fn admit(&self, message: &ChannelMessage) -> Result<(), ChannelError> {
+        let Some(limiter) = self.rate_limiter() else {
+            return Ok(());
+        };
+        let result = limiter.check_message(message).await;
+        if result.allowed {
+            Ok(())
+        } else {
+            Err(ChannelError::RateLimited(result.reset_after_secs))
+        }
+    }
 }
 
 /// Sender trait for simplified sending
@@ -7,7 +7,17 @@
 //! Environment variables:
 //! - `WEBCHAT_PORT`: WebSocket server port (default: 8765)
 //! - `WEBCHAT_ALLOWED_ORIGINS`: Comma-separated allowed origins for CORS
+//! - `WEBCHAT_FORMAT`: `markdown` (default), `plain` or `html`
+//!
+//! # Output
+//!
+//! Responses go out as markdown by default, with the languages of their code
+//! blocks listed in `code_languages`, for the browser to render with a
+//! markdown renderer and syntax highlighter. `plain` strips code markup for
+//! clients that show raw text; `html` is the Telegram HTML subset.
 
+use super::format::{code_block_languages, render};
+use super::rate_limit::{ChannelRateLimiter, RateLimitConfig};
 use super::traits::*;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -28,6 +38,8 @@ pub struct WebChatConfig {
     pub max_message_length: usize,
     /// Session timeout in seconds
     pub session_timeout_secs: u64,
+    /// How response content is rendered for the frontend
+    pub format: ParseMode,
 }
 
 impl Default for WebChatConfig {
@@ -37,6 +49,7 @@ impl Default for WebChatConfig {
             allowed_origins: vec!["*".to_string()],
             max_message_length: 10000,
             session_timeout_secs: 3600,
+            format: ParseMode::Markdown,
         }
     }
 }
@@ -53,6 +66,10 @@ impl WebChatConfig {
                 .unwrap_or_else(|_| vec!["*".to_string()]),
             max_message_length: 10000,
             session_timeout_secs: 3600,
+            format: std::env::var("WEBCHAT_FORMAT")
+                .ok()
+                .and_then(|s| ParseMode::from_name(&s))
+                .unwrap_or(ParseMode::Markdown),
         })
     }
 }
@@ -75,6 +92,8 @@ pub struct WebChatChannel {
     sessions: Arc<RwLock<HashMap<String, WebSession>>>,
     /// Message queue for outgoing messages: session_id -> messages
     outbound_queue: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// Inbound rate limiting (shared when registered with `ChannelRegistry`)
+    rate_limiter: Arc<ChannelRateLimiter>,
 }
 
 impl WebChatChannel {
@@ -84,9 +103,16 @@ impl WebChatChannel {
             ready: false,
             sessions: Arc::new(RwLock::new(HashMap::new())),
             outbound_queue: Arc::new(RwLock::new(HashMap::new())),
+            rate_limiter: Arc::new(ChannelRateLimiter::new("webchat", RateLimitConfig::from_env("webchat"))),
         }
     }
 
+    /// Use a shared rate limiter
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<ChannelRateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     pub fn from_env() -> Result<Self> {
         Ok(Self::new(WebChatConfig::from_env()?))
     }
@@ -148,11 +174,30 @@ impl WebChatChannel {
         }
     }
 
+    /// Parse an inbound WebSocket message, refusing it while the sender is rate limited
+    pub async fn receive(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        data: &WebChatIncoming,
+    ) -> Result<ChannelMessage, ChannelError> {
+        self.touch_session(session_id).await;
+        let message = self.parse_message(session_id, user_id, data);
+        self.admit(&message).await?;
+        Ok(message)
+    }
+
     /// Format outgoing message
     fn format_outgoing(&self, response: &ChannelResponse) -> String {
+        let code_languages = match self.config.format {
+            ParseMode::Markdown => code_block_languages(&response.content),
+            _ => Vec::new(),
+        };
         let msg = WebChatOutgoing {
             id: uuid::Uuid::new_v4().to_string(),
-            content: response.content.clone(),
+            content: render(&response.content, self.config.format),
+            format: self.config.format.as_str(),
+            code_languages,
             message_type: "text".to_string(),
             timestamp: chrono::Utc::now().timestamp(),
             buttons: if response.buttons.is_empty() {
@@ -261,7 +306,8 @@ impl Channel for WebChatChannel {
             let edit_msg = serde_json::json!({
                 "type": "edit",
                 "message_id": message_id,
-                "content": content,
+                "content": render(content, self.config.format),
+                "format": self.config.format.as_str(),
                 "timestamp": chrono::Utc::now().timestamp(),
             });
             session
@@ -330,6 +376,10 @@ impl Channel for WebChatChannel {
             "active_sessions": 0, // Would need async to get real count
         })
     }
+
+    fn rate_limiter(&self) -> Option<&ChannelRateLimiter> {
+        Some(&self.rate_limiter)
+    }
 }
 
 #[async_trait]
@@ -393,6 +443,11 @@ pub struct WebChatIncoming {
 struct WebChatOutgoing {
     id: String,
     content: String,
+    /// "markdown", "plain" or "html"
+    format: &'static str,
+    /// Languages of the code blocks in `content`, for the highlighter
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    code_languages: Vec<String>,
     message_type: String,
     timestamp: i64,
     buttons: Option<Vec<WebChatButton>>,
@@ -429,4 +484,44 @@ mod tests {
         assert_eq!(msg.content, "Hello");
         assert_eq!(msg.channel, "webchat");
     }
+
+    #[tokio::test]
+    async fn test_receive_rate_limited() {
+        let limiter = ChannelRateLimiter::new("webchat", RateLimitConfig {
+            max_requests: 1,
+            burst_allowance: 0,
+            ..RateLimitConfig::default()
+        });
+        let channel = WebChatChannel::new(WebChatConfig::default()).with_rate_limiter(Arc::new(limiter));
+        let incoming = WebChatIncoming {
+            id: None,
+            content: "Hello".to_string(),
+            username: None,
+            reply_to: None,
+        };
+
+        assert!(channel.receive("session1", "user1", &incoming).await.is_ok());
+        assert!(matches!(
+            channel.receive("session1", "user1", &incoming).await,
+            Err(ChannelError::RateLimited(_))
+        ));
+    }
+
+    #[test]
+    fn test_outgoing_markdown_lists_code_languages() {
+        let content = "Try:\n```Rust\nfn main() {}\n```\nor\n```\nplain\n```\n```python\nprint(1)\n```";
+        let channel = WebChatChannel::new(WebChatConfig::default());
+        let msg: serde_json::Value =
+            serde_json::from_str(&channel.format_outgoing(&ChannelResponse::text("s1", content))).unwrap();
+        assert_eq!(msg["content"], content);
+        assert_eq!(msg["format"], "markdown");
+        assert_eq!(msg["code_languages"], serde_json::json!(["rust", "python"]));
+
+        let plain = WebChatChannel::new(WebChatConfig { format: ParseMode::Plain, ..Default::default() });
+        let msg: serde_json::Value =
+            serde_json::from_str(&plain.format_outgoing(&ChannelResponse::text("s1", "Run `ls`"))).unwrap();
+        assert_eq!(msg["content"], "Run ls");
+        assert_eq!(msg["format"], "plain");
+        assert!(msg.get("code_languages").is_none());
+    }
 }
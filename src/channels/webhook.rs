@@ -0,0 +1,305 @@
+//! Inbound Webhook Handlers
+//!
+//! HTTP endpoints for channels that push events to us:
+//! - `POST /whatsapp/webhook` - Twilio WhatsApp messages (`X-Twilio-Signature`)
+//! - `POST /discord/interactions` - Discord interactions (`X-Signature-Ed25519`)
+//!
+//! Every request is signature-checked against the raw body before anything
+//! is parsed; unverified requests get 401. Verified messages go through the
+//! channel's rate limiter and are forwarded on an mpsc channel.
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+use super::discord::DiscordChannel;
+use super::traits::{Channel, ChannelError, ChannelMessage};
+use super::whatsapp::{TwilioWebhookData, WhatsAppChannel};
+
+/// Empty TwiML reply (the response is sent later via the API)
+const EMPTY_TWIML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Response></Response>";
+
+/// Shared state for a channel's webhook endpoint
+pub struct WebhookState<C> {
+    pub channel: Arc<C>,
+    /// Verified inbound messages
+    pub inbound: mpsc::Sender<ChannelMessage>,
+}
+
+impl<C> WebhookState<C> {
+    pub fn new(channel: Arc<C>, inbound: mpsc::Sender<ChannelMessage>) -> Self {
+        Self { channel, inbound }
+    }
+}
+
+/// Discord interaction payload (the fields we use)
+#[derive(Debug, Deserialize)]
+pub struct DiscordInteraction {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub interaction_type: u8,
+    pub channel_id: Option<String>,
+    pub guild_id: Option<String>,
+    /// Guild interactions carry the user inside `member`
+    pub member: Option<DiscordInteractionMember>,
+    /// DM interactions carry the user directly
+    pub user: Option<super::discord::DiscordUser>,
+    pub data: Option<DiscordInteractionData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscordInteractionMember {
+    pub user: super::discord::DiscordUser,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscordInteractionData {
+    pub name: String,
+    #[serde(default)]
+    pub options: Vec<DiscordInteractionOption>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiscordInteractionOption {
+    pub name: String,
+    pub value: Option<serde_json::Value>,
+}
+
+impl DiscordInteraction {
+    const PING: u8 = 1;
+    const APPLICATION_COMMAND: u8 = 2;
+
+    /// Convert a slash command into a channel message (`/name opt=value ...`)
+    fn to_message(&self) -> Option<ChannelMessage> {
+        let data = self.data.as_ref()?;
+        let user = self.member.as_ref().map(|m| &m.user).or(self.user.as_ref())?;
+
+        let mut content = format!("/{}", data.name);
+        for option in &data.options {
+            match &option.value {
+                Some(serde_json::Value::String(s)) => content.push_str(&format!(" {}={}", option.name, s)),
+                Some(value) => content.push_str(&format!(" {}={}", option.name, value)),
+                None => content.push_str(&format!(" {}", option.name)),
+            }
+        }
+
+        let mut message = ChannelMessage::text(
+            "discord",
+            &user.id,
+            self.channel_id.as_deref().unwrap_or(&user.id),
+            &content,
+        );
+        message.id = self.id.clone();
+        message.sender_name = Some(user.username.clone());
+        message.is_group = self.guild_id.is_some();
+        Some(message)
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn rejected(channel: &str, e: &ChannelError) -> axum::response::Response {
+    warn!("Rejected {} webhook: {}", channel, e);
+    (StatusCode::UNAUTHORIZED, "invalid signature").into_response()
+}
+
+/// Rate-limit and forward a verified message
+async fn dispatch<C: Channel>(state: &WebhookState<C>, message: ChannelMessage) -> Result<(), axum::response::Response> {
+    if let Err(e) = state.channel.admit(&message).await {
+        debug!("{} webhook rate limited: {}", state.channel.name(), e);
+        return Err((StatusCode::TOO_MANY_REQUESTS, e.to_string()).into_response());
+    }
+    state
+        .inbound
+        .send(message)
+        .await
+        .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "inbound queue closed").into_response())
+}
+
+/// Twilio WhatsApp webhook
+/// POST /whatsapp/webhook
+pub async fn whatsapp_webhook(
+    State(state): State<Arc<WebhookState<WhatsAppChannel>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    // Unparseable bodies can't be verified, so they are unauthorized too
+    let params: Vec<(String, String)> = serde_urlencoded::from_bytes(&body).unwrap_or_default();
+    if let Err(e) = state
+        .channel
+        .verify_webhook(header_str(&headers, "x-twilio-signature"), &params)
+    {
+        return rejected("whatsapp", &e);
+    }
+
+    let data: TwilioWebhookData = match serde_urlencoded::from_bytes(&body) {
+        Ok(data) => data,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    if let Err(response) = dispatch(&state, state.channel.parse_webhook(&data)).await {
+        return response;
+    }
+    ([(header::CONTENT_TYPE, "text/xml")], EMPTY_TWIML).into_response()
+}
+
+/// Discord interactions endpoint
+/// POST /discord/interactions
+pub async fn discord_interactions(
+    State(state): State<Arc<WebhookState<DiscordChannel>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    if let Err(e) = state.channel.verify_interaction(
+        header_str(&headers, "x-signature-ed25519"),
+        header_str(&headers, "x-signature-timestamp"),
+        &body,
+    ) {
+        return rejected("discord", &e);
+    }
+
+    let interaction: DiscordInteraction = match serde_json::from_slice(&body) {
+        Ok(interaction) => interaction,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    match interaction.interaction_type {
+        DiscordInteraction::PING => Json(serde_json::json!({ "type": 1 })).into_response(),
+        DiscordInteraction::APPLICATION_COMMAND => {
+            let Some(message) = interaction.to_message() else {
+                return (StatusCode::BAD_REQUEST, "interaction has no command data").into_response();
+            };
+            if let Err(response) = dispatch(&state, message).await {
+                return response;
+            }
+            // Deferred response - the reply follows as a regular message
+            Json(serde_json::json!({ "type": 5 })).into_response()
+        }
+        other => (StatusCode::BAD_REQUEST, format!("unsupported interaction type {}", other)).into_response(),
+    }
+}
+
+/// Create the WhatsApp webhook router
+pub fn whatsapp_webhook_router(state: Arc<WebhookState<WhatsAppChannel>>) -> Router {
+    Router::new()
+        .route("/whatsapp/webhook", post(whatsapp_webhook))
+        .with_state(state)
+}
+
+/// Create the Discord interactions router
+pub fn discord_webhook_router(state: Arc<WebhookState<DiscordChannel>>) -> Router {
+    Router::new()
+        .route("/discord/interactions", post(discord_interactions))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::discord::DiscordConfig;
+    use crate::channels::whatsapp::WhatsAppConfig;
+    use axum::body::Body;
+    use axum::http::Request;
+    use base64::Engine;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use tower::ServiceExt;
+
+    const URL: &str = "https://bot.example.com/whatsapp/webhook";
+
+    fn whatsapp_state() -> (Arc<WebhookState<WhatsAppChannel>>, mpsc::Receiver<ChannelMessage>) {
+        let channel = WhatsAppChannel::new(WhatsAppConfig {
+            account_sid: "AC123".to_string(),
+            auth_token: "secret-token".to_string(),
+            whatsapp_number: "+1234567890".to_string(),
+            webhook_url: Some(URL.to_string()),
+            max_message_length: 4096,
+        });
+        let (tx, rx) = mpsc::channel(4);
+        (Arc::new(WebhookState::new(Arc::new(channel), tx)), rx)
+    }
+
+    fn twilio_request(body: &str, signature: Option<String>) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri("/whatsapp/webhook")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+        if let Some(signature) = signature {
+            builder = builder.header("X-Twilio-Signature", signature);
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_whatsapp_webhook_requires_signature() {
+        let body = "MessageSid=SM1&AccountSid=AC123&From=whatsapp%3A%2B15550001111&To=whatsapp%3A%2B1234567890&Body=hi";
+        let payload = format!(
+            "{}AccountSidAC123BodyhiFromwhatsapp:+15550001111MessageSidSM1Towhatsapp:+1234567890",
+            URL
+        );
+        let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, b"secret-token");
+        let signature = base64::engine::general_purpose::STANDARD.encode(ring::hmac::sign(&key, payload.as_bytes()));
+
+        let (state, mut rx) = whatsapp_state();
+        let response = whatsapp_webhook_router(state.clone())
+            .oneshot(twilio_request(body, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(rx.try_recv().is_err());
+
+        let response = whatsapp_webhook_router(state)
+            .oneshot(twilio_request(body, Some(signature)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let message = rx.try_recv().unwrap();
+        assert_eq!(message.sender_id, "+15550001111");
+        assert_eq!(message.content, "hi");
+    }
+
+    #[tokio::test]
+    async fn test_discord_interactions_verify_and_pong() {
+        let keypair = Ed25519KeyPair::from_seed_unchecked(&[9u8; 32]).unwrap();
+        let channel = DiscordChannel::new(DiscordConfig {
+            bot_token: "test".to_string(),
+            application_id: "test".to_string(),
+            allowed_guilds: vec![],
+            public_key: Some(hex::encode(keypair.public_key().as_ref())),
+            max_message_length: 2000,
+        });
+        let (tx, _rx) = mpsc::channel(4);
+        let state = Arc::new(WebhookState::new(Arc::new(channel), tx));
+
+        let body = r#"{"id":"1","type":1}"#;
+        let request = |signature: &str| {
+            Request::builder()
+                .method("POST")
+                .uri("/discord/interactions")
+                .header("X-Signature-Ed25519", signature)
+                .header("X-Signature-Timestamp", "1700000000")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let signature = hex::encode(keypair.sign(format!("1700000000{}", body).as_bytes()).as_ref());
+
+        let response = discord_webhook_router(state.clone()).oneshot(request("00")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = discord_webhook_router(state).oneshot(request(&signature)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["type"], 1);
+    }
+}
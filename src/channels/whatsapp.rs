@@ -9,14 +9,24 @@
 //! - `TWILIO_ACCOUNT_SID`: Twilio account SID
 //! - `TWILIO_AUTH_TOKEN`: Twilio auth token
 //! - `TWILIO_WHATSAPP_NUMBER`: Your Twilio WhatsApp number (e.g., +14155238886)
+//! - `WHATSAPP_WEBHOOK_URL`: Public webhook URL, exactly as configured in Twilio
 //!
 //! # Webhook Setup
 //!
 //! Configure Twilio webhook to POST to: `https://your-domain.com/whatsapp/webhook`
+//!
+//! Requests are authenticated with `X-Twilio-Signature`, an HMAC-SHA1 of the
+//! webhook URL and POST parameters keyed by the auth token. Without
+//! `WHATSAPP_WEBHOOK_URL` the signature can't be checked and every webhook
+//! request is rejected.
 
+use super::format::format_response_limited;
+use super::rate_limit::{ChannelRateLimiter, RateLimitConfig};
 use super::traits::*;
 use anyhow::Result;
 use async_trait::async_trait;
+use base64::Engine;
+use ring::hmac;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -28,11 +38,11 @@ use tracing::{debug, error, info, warn};
 pub struct WhatsAppConfig {
     /// Twilio Account SID
     pub account_sid: String,
-    /// Twilio Auth Token
+    /// Twilio Auth Token (also the webhook signing secret)
     pub auth_token: String,
     /// Twilio WhatsApp number (with country code)
     pub whatsapp_number: String,
-    /// Webhook URL for incoming messages
+    /// Webhook URL for incoming messages (required to verify signatures)
     pub webhook_url: Option<String>,
     /// Maximum message length (WhatsApp limit: 4096)
     pub max_message_length: usize,
@@ -61,6 +71,8 @@ pub struct WhatsAppChannel {
     ready: bool,
     /// Active chat sessions (phone numbers)
     active_chats: Arc<RwLock<HashSet<String>>>,
+    /// Inbound rate limiting (shared when registered with `ChannelRegistry`)
+    rate_limiter: Arc<ChannelRateLimiter>,
 }
 
 impl WhatsAppChannel {
@@ -70,14 +82,54 @@ impl WhatsAppChannel {
             client: reqwest::Client::new(),
             ready: false,
             active_chats: Arc::new(RwLock::new(HashSet::new())),
+            rate_limiter: Arc::new(ChannelRateLimiter::new("whatsapp", RateLimitConfig::from_env("whatsapp"))),
         }
     }
 
+    /// Use a shared rate limiter
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<ChannelRateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     /// Create from environment
     pub fn from_env() -> Result<Self> {
         Ok(Self::new(WhatsAppConfig::from_env()?))
     }
 
+    /// Verify a webhook's `X-Twilio-Signature` against its POST parameters
+    ///
+    /// Twilio signs the webhook URL followed by each parameter name and value,
+    /// sorted by name, with HMAC-SHA1 keyed by the auth token.
+    pub fn verify_webhook(
+        &self,
+        signature: Option<&str>,
+        params: &[(String, String)],
+    ) -> Result<(), ChannelError> {
+        let url = self.config.webhook_url.as_deref().ok_or_else(|| {
+            ChannelError::AuthenticationFailed("WHATSAPP_WEBHOOK_URL not set, cannot verify webhook".to_string())
+        })?;
+        let signature = signature
+            .and_then(|s| base64::engine::general_purpose::STANDARD.decode(s.trim()).ok())
+            .ok_or_else(|| ChannelError::AuthenticationFailed("Missing or malformed X-Twilio-Signature".to_string()))?;
+
+        let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, self.config.auth_token.as_bytes());
+        hmac::verify(&key, Self::signature_payload(url, params).as_bytes(), &signature)
+            .map_err(|_| ChannelError::AuthenticationFailed("Invalid Twilio signature".to_string()))
+    }
+
+    /// Data Twilio signs: URL, then sorted parameter names and values
+    fn signature_payload(url: &str, params: &[(String, String)]) -> String {
+        let mut sorted: Vec<&(String, String)> = params.iter().collect();
+        sorted.sort();
+        let mut payload = url.to_string();
+        for (name, value) in sorted {
+            payload.push_str(name);
+            payload.push_str(value);
+        }
+        payload
+    }
+
     /// Parse incoming Twilio webhook request
     pub fn parse_webhook(&self, form_data: &TwilioWebhookData) -> ChannelMessage {
         ChannelMessage {
@@ -171,44 +223,12 @@ impl WhatsAppChannel {
         }
     }
 
-    /// Split long messages into chunks
+    /// Split long messages into chunks (WhatsApp renders ``` blocks as monospace)
     fn split_message(&self, content: &str) -> Vec<String> {
-        let max_len = self.config.max_message_length;
-        if content.len() <= max_len {
-            return vec![content.to_string()];
-        }
-
-        let mut chunks = Vec::new();
-        let mut current = String::new();
-
-        for line in content.lines() {
-            if current.len() + line.len() + 1 > max_len {
-                if !current.is_empty() {
-                    chunks.push(current);
-                    current = String::new();
-                }
-
-                // Handle lines longer than max
-                if line.len() > max_len {
-                    for chunk in line.as_bytes().chunks(max_len) {
-                        chunks.push(String::from_utf8_lossy(chunk).to_string());
-                    }
-                } else {
-                    current = line.to_string();
-                }
-            } else {
-                if !current.is_empty() {
-                    current.push('\n');
-                }
-                current.push_str(line);
-            }
-        }
-
-        if !current.is_empty() {
-            chunks.push(current);
-        }
-
-        chunks
+        format_response_limited(content, ParseMode::Markdown, self.config.max_message_length)
+            .into_iter()
+            .map(|chunk| chunk.text)
+            .collect()
     }
 }
 
@@ -329,6 +349,10 @@ impl Channel for WhatsAppChannel {
             "webhook_url": &self.config.webhook_url,
         })
     }
+
+    fn rate_limiter(&self) -> Option<&ChannelRateLimiter> {
+        Some(&self.rate_limiter)
+    }
 }
 
 #[async_trait]
@@ -418,6 +442,38 @@ mod tests {
         assert!(chunks.len() >= 2);
     }
 
+    #[test]
+    fn test_verify_webhook_signature() {
+        let url = "https://bot.example.com/whatsapp/webhook";
+        let config = WhatsAppConfig {
+            account_sid: "AC123".to_string(),
+            auth_token: "secret-token".to_string(),
+            whatsapp_number: "+1234567890".to_string(),
+            webhook_url: Some(url.to_string()),
+            max_message_length: 4096,
+        };
+        let channel = WhatsAppChannel::new(config.clone());
+        let params = vec![
+            ("From".to_string(), "whatsapp:+15550001111".to_string()),
+            ("Body".to_string(), "hello".to_string()),
+        ];
+
+        let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, b"secret-token");
+        let payload = format!("{}BodyhelloFromwhatsapp:+15550001111", url);
+        let signature = base64::engine::general_purpose::STANDARD.encode(hmac::sign(&key, payload.as_bytes()));
+
+        assert!(channel.verify_webhook(Some(&signature), &params).is_ok());
+        assert!(channel.verify_webhook(None, &params).is_err());
+
+        let mut tampered = params.clone();
+        tampered[1].1 = "goodbye".to_string();
+        assert!(channel.verify_webhook(Some(&signature), &tampered).is_err());
+
+        // Fails closed without a configured URL
+        let unconfigured = WhatsAppChannel::new(WhatsAppConfig { webhook_url: None, ..config });
+        assert!(unconfigured.verify_webhook(Some(&signature), &params).is_err());
+    }
+
     #[test]
     fn test_phone_formatting() {
         let msg = ChannelMessage::text("whatsapp", "+1234567890", "+1234567890", "test");
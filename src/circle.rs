@@ -6,12 +6,41 @@
 //! 3. Maria - Testing (Kent Beck TDD mastery)
 //! 4. Kai - Optimization (Data-oriented design)
 //! 5. Sentinel - Security Audit (OWASP + breach mentality)
+//!
+//! These are the defaults. A persona file (`CIRCLE_PERSONAS_CONFIG`, default
+//! `~/.claudebot/circle_personas.toml`) can replace the set, e.g. to swap
+//! Sentinel's OWASP focus for an unsafe-code audit:
+//!
+//! ```toml
+//! [[personas]]
+//! builtin = "Carmack"
+//!
+//! [[personas]]
+//! builtin = "Linus"
+//!
+//! [[personas]]
+//! name = "Ferris"
+//! role = "Unsafe Audit"
+//! kind = "audit"
+//! modes = ["full", "review_only", "security_only"]
+//! model = "opus"
+//! temperature = 0.0
+//! system_prompt = "You are Ferris, auditing every unsafe block..."
+//! task = "Audit all unsafe code. End with risk level: LOW, MEDIUM, HIGH, or CRITICAL"
+//! ```
+//!
+//! `builtin` entries start from a default persona; any other field overrides it.
+//! `model` and `temperature` size each phase: e.g. Opus only for the audit,
+//! Haiku for advisory phases, a low temperature for implementation.
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
 use crate::claude::ClaudeClient;
+use crate::router::ModelHint;
+use crate::tokenizer::{ModelPricing, TokenCounter};
 
 /// Maximum number of revision attempts
 const MAX_REVISIONS: u32 = 3;
@@ -31,6 +60,32 @@ pub enum PipelineMode {
     SecurityOnly,
 }
 
+impl PipelineMode {
+    /// All modes
+    pub const ALL: [PipelineMode; 4] = [
+        PipelineMode::Full,
+        PipelineMode::ReviewOnly,
+        PipelineMode::QuickFix,
+        PipelineMode::SecurityOnly,
+    ];
+
+    /// Default personas that run in this mode, in order
+    pub fn personas(&self) -> Vec<Persona> {
+        match self {
+            PipelineMode::Full => vec![
+                Persona::Carmack,
+                Persona::Linus,
+                Persona::Maria,
+                Persona::Kai,
+                Persona::Sentinel,
+            ],
+            PipelineMode::ReviewOnly => vec![Persona::Linus, Persona::Sentinel],
+            PipelineMode::QuickFix => vec![Persona::Carmack],
+            PipelineMode::SecurityOnly => vec![Persona::Sentinel],
+        }
+    }
+}
+
 /// Persona in the development circle
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Persona {
@@ -47,6 +102,15 @@ pub enum Persona {
 }
 
 impl Persona {
+    /// All default personas, in phase order
+    pub const ALL: [Persona; 5] = [
+        Persona::Carmack,
+        Persona::Linus,
+        Persona::Maria,
+        Persona::Kai,
+        Persona::Sentinel,
+    ];
+
     pub fn name(&self) -> &'static str {
         match self {
             Persona::Carmack => "Carmack",
@@ -98,6 +162,303 @@ impl Persona {
             Persona::Sentinel => "opus", // Security needs deep reasoning
         }
     }
+
+    /// Model hint as a router model
+    pub fn model(&self) -> ModelHint {
+        match self.model_hint() {
+            "opus" => ModelHint::Opus,
+            "haiku" => ModelHint::Haiku,
+            _ => ModelHint::Sonnet,
+        }
+    }
+
+    /// Typical response length in tokens, used for pre-run estimates
+    pub fn expected_output_tokens(&self) -> usize {
+        match self {
+            Persona::Carmack => 4000,
+            Persona::Linus => 1500,
+            Persona::Maria => 3000,
+            Persona::Kai => 2500,
+            Persona::Sentinel => 1500,
+        }
+    }
+
+    /// How the pipeline treats this persona's output
+    pub fn kind(&self) -> PersonaKind {
+        match self {
+            Persona::Carmack => PersonaKind::Implementation,
+            Persona::Linus => PersonaKind::Review,
+            Persona::Maria | Persona::Kai => PersonaKind::Advisory,
+            Persona::Sentinel => PersonaKind::Audit,
+        }
+    }
+
+    /// Task instructions appended to the phase prompt
+    pub fn task_prompt(&self) -> &'static str {
+        match self {
+            Persona::Carmack => "Implement this feature completely. Create all necessary files and write production-ready code.",
+            Persona::Linus => "Review this implementation. End your review with exactly one of: APPROVED, APPROVED_WITH_COMMENTS, CHANGES_REQUESTED, BLOCKED",
+            Persona::Maria => "Write comprehensive tests for this implementation. Cover happy paths, edge cases, and error conditions.",
+            Persona::Kai => "Optimize this code for performance. Focus on allocations, hot paths, and code elegance.",
+            Persona::Sentinel => "Perform a security audit. Check OWASP Top 10. End with risk level: LOW, MEDIUM, HIGH, or CRITICAL",
+        }
+    }
+
+    /// Modes that include this persona by default
+    pub fn modes(&self) -> Vec<PipelineMode> {
+        PipelineMode::ALL
+            .into_iter()
+            .filter(|mode| mode.personas().contains(self))
+            .collect()
+    }
+}
+
+/// How the pipeline handles a persona's output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PersonaKind {
+    /// Writes code; its output becomes the context for later phases and
+    /// rejected reviews loop back to it
+    Implementation,
+    /// Ends with a verdict; CHANGES_REQUESTED triggers a revision
+    Review,
+    /// Ends with a risk level; HIGH or CRITICAL blocks the pipeline
+    Audit,
+    /// Output is reported but doesn't gate the pipeline
+    #[default]
+    Advisory,
+}
+
+/// A persona definition as run by the circle
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PersonaConfig {
+    pub name: String,
+    pub role: String,
+    pub kind: PersonaKind,
+    pub system_prompt: String,
+    /// Task instructions appended after the feature and code context
+    pub task: String,
+    /// Modes that include this persona
+    pub modes: Vec<PipelineMode>,
+    /// Model hint ("haiku", "sonnet", "opus")
+    pub model: String,
+    /// Sampling temperature, 0.0-1.0 (None = API default)
+    pub temperature: Option<f32>,
+    /// Typical response length in tokens, used for pre-run estimates
+    pub expected_output_tokens: usize,
+}
+
+impl From<Persona> for PersonaConfig {
+    fn from(persona: Persona) -> Self {
+        Self {
+            name: persona.name().to_string(),
+            role: persona.role().to_string(),
+            kind: persona.kind(),
+            system_prompt: persona.system_prompt().to_string(),
+            task: persona.task_prompt().to_string(),
+            modes: persona.modes(),
+            model: persona.model_hint().to_string(),
+            temperature: None,
+            expected_output_tokens: persona.expected_output_tokens(),
+        }
+    }
+}
+
+impl PersonaConfig {
+    /// Model hint as a router model
+    pub fn model_hint(&self) -> ModelHint {
+        match self.model.as_str() {
+            "opus" => ModelHint::Opus,
+            "haiku" => ModelHint::Haiku,
+            _ => ModelHint::Sonnet,
+        }
+    }
+}
+
+/// Persona file entry; `builtin` entries inherit every omitted field
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PersonaEntry {
+    builtin: Option<Persona>,
+    name: Option<String>,
+    role: Option<String>,
+    kind: Option<PersonaKind>,
+    system_prompt: Option<String>,
+    task: Option<String>,
+    modes: Option<Vec<PipelineMode>>,
+    model: Option<String>,
+    temperature: Option<f32>,
+    expected_output_tokens: Option<usize>,
+}
+
+impl PersonaEntry {
+    fn resolve(self, index: usize) -> Result<PersonaConfig> {
+        let base = self.builtin.map(PersonaConfig::from);
+        let name = self
+            .name
+            .or_else(|| base.as_ref().map(|b| b.name.clone()))
+            .with_context(|| format!("Persona #{} needs a name or builtin", index + 1))?;
+        let required = |value: Option<String>, field: &str, inherited: Option<&String>| {
+            value
+                .or_else(|| inherited.cloned())
+                .with_context(|| format!("Persona {} needs {}", name, field))
+        };
+
+        Ok(PersonaConfig {
+            role: required(self.role, "a role", base.as_ref().map(|b| &b.role))?,
+            system_prompt: required(self.system_prompt, "a system_prompt", base.as_ref().map(|b| &b.system_prompt))?,
+            task: required(self.task, "a task", base.as_ref().map(|b| &b.task))?,
+            kind: self.kind.or(base.as_ref().map(|b| b.kind)).unwrap_or_default(),
+            modes: self
+                .modes
+                .or_else(|| base.as_ref().map(|b| b.modes.clone()))
+                .unwrap_or_else(|| vec![PipelineMode::Full]),
+            model: self
+                .model
+                .or_else(|| base.as_ref().map(|b| b.model.clone()))
+                .unwrap_or_else(|| "sonnet".to_string()),
+            temperature: self.temperature.or(base.as_ref().and_then(|b| b.temperature)),
+            expected_output_tokens: self
+                .expected_output_tokens
+                .or(base.as_ref().map(|b| b.expected_output_tokens))
+                .unwrap_or(2000),
+            name,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PersonaFile {
+    personas: Vec<PersonaEntry>,
+}
+
+/// The active persona set, in phase order
+#[derive(Debug, Clone, PartialEq)]
+pub struct CirclePersonas {
+    personas: Vec<PersonaConfig>,
+}
+
+impl Default for CirclePersonas {
+    fn default() -> Self {
+        Self {
+            personas: Persona::ALL.into_iter().map(PersonaConfig::from).collect(),
+        }
+    }
+}
+
+impl CirclePersonas {
+    /// Build from definitions, validated
+    pub fn new(personas: Vec<PersonaConfig>) -> Result<Self> {
+        let set = Self { personas };
+        set.validate()?;
+        Ok(set)
+    }
+
+    /// Load the persona file, validated
+    ///
+    /// Uses `CIRCLE_PERSONAS_CONFIG` if set (the file must exist), otherwise
+    /// `~/.claudebot/circle_personas.toml` if present, otherwise the defaults.
+    pub fn from_env() -> Result<Self> {
+        let path = match std::env::var("CIRCLE_PERSONAS_CONFIG") {
+            Ok(path) => Some(PathBuf::from(path)),
+            Err(_) => default_personas_path().filter(|p| p.exists()),
+        };
+        match path {
+            Some(ref path) => Self::from_file(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Parse and validate a persona file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read circle personas {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Invalid circle personas {}", path.display()))
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let file: PersonaFile = toml::from_str(content)?;
+        let personas = file
+            .personas
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| entry.resolve(i))
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(personas)
+    }
+
+    /// Every mode needs a persona, and names must be unique
+    pub fn validate(&self) -> Result<()> {
+        let mut names = std::collections::HashSet::new();
+        for persona in &self.personas {
+            if persona.name.trim().is_empty() {
+                bail!("Persona names can't be empty");
+            }
+            if !names.insert(persona.name.to_lowercase()) {
+                bail!("Duplicate persona {}", persona.name);
+            }
+            if persona.system_prompt.trim().is_empty() || persona.task.trim().is_empty() {
+                bail!("Persona {} needs a system_prompt and a task", persona.name);
+            }
+            if !matches!(persona.model.as_str(), "haiku" | "sonnet" | "opus") {
+                bail!("Persona {} has unknown model {} (haiku, sonnet, opus)", persona.name, persona.model);
+            }
+            if let Some(t) = persona.temperature.filter(|t| !(0.0..=1.0).contains(t)) {
+                bail!("Persona {} has temperature {} (0.0-1.0)", persona.name, t);
+            }
+        }
+        for mode in PipelineMode::ALL {
+            if self.for_mode(mode).is_empty() {
+                bail!("No persona runs in {:?} mode", mode);
+            }
+        }
+        Ok(())
+    }
+
+    /// All personas, in phase order
+    pub fn all(&self) -> &[PersonaConfig] {
+        &self.personas
+    }
+
+    /// Personas that run in a mode, in phase order
+    pub fn for_mode(&self, mode: PipelineMode) -> Vec<&PersonaConfig> {
+        self.personas.iter().filter(|p| p.modes.contains(&mode)).collect()
+    }
+
+    /// Phase number (1-based position in the full set)
+    pub fn phase_of(&self, persona: &PersonaConfig) -> u8 {
+        self.personas
+            .iter()
+            .position(|p| p.name == persona.name)
+            .map(|i| i as u8 + 1)
+            .unwrap_or(0)
+    }
+
+    /// Format for display
+    pub fn format(&self) -> String {
+        let mut out = String::from("Circle Personas\n");
+        for persona in &self.personas {
+            let modes: Vec<String> = persona.modes.iter().map(|m| format!("{:?}", m)).collect();
+            let temperature = persona.temperature.map(|t| format!(", temperature {}", t)).unwrap_or_default();
+            out.push_str(&format!(
+                "\n{}. {} - {} ({:?}, {}{})\n   Modes: {}\n",
+                self.phase_of(persona),
+                persona.name,
+                persona.role,
+                persona.kind,
+                persona.model,
+                temperature,
+                modes.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Default persona file (`~/.claudebot/circle_personas.toml`)
+pub fn default_personas_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claudebot").join("circle_personas.toml"))
 }
 
 /// Review verdict from Linus or Sentinel
@@ -142,6 +503,23 @@ pub struct PhaseResult {
     pub risk_level: Option<RiskLevel>,
     pub files_changed: Vec<String>,
     pub duration_ms: u64,
+    /// Model that ran the phase
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub input_tokens: usize,
+    #[serde(default)]
+    pub output_tokens: usize,
+    #[serde(default)]
+    pub cache_read_tokens: usize,
+    #[serde(default)]
+    pub cache_write_tokens: usize,
+    /// Temperature the phase ran at (None = API default)
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Approximate cost of the phase's API call
+    #[serde(default)]
+    pub cost_usd: f64,
 }
 
 /// Overall pipeline result
@@ -156,6 +534,13 @@ pub struct PipelineResult {
     pub total_duration_ms: u64,
 }
 
+impl PipelineResult {
+    /// Cost of every phase run, revisions included
+    pub fn total_cost_usd(&self) -> f64 {
+        self.phases.iter().map(|p| p.cost_usd).sum()
+    }
+}
+
 /// Pipeline execution state
 #[derive(Debug, Clone)]
 pub struct PipelineState {
@@ -168,15 +553,149 @@ pub struct PipelineState {
     pub feedback: Option<String>,
 }
 
+/// A phase about to run, reported to `Circle::run_with_progress`
+#[derive(Debug, Clone)]
+pub struct PhaseProgress {
+    /// Phases started so far, including this one (grows with revisions)
+    pub step: usize,
+    /// Phases expected in total given the revisions so far
+    pub planned: usize,
+    pub persona: String,
+    pub revision: u32,
+}
+
+/// Estimated tokens and cost for one phase
+#[derive(Debug, Clone)]
+pub struct PhaseEstimate {
+    /// Persona name
+    pub persona: String,
+    pub kind: PersonaKind,
+    pub model: ModelHint,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub cost_usd: f64,
+}
+
+/// Pre-run estimate for a pipeline (no revisions assumed)
+#[derive(Debug, Clone)]
+pub struct CircleEstimate {
+    pub mode: PipelineMode,
+    pub phases: Vec<PhaseEstimate>,
+    pub total_tokens: usize,
+    pub total_cost_usd: f64,
+}
+
+impl CircleEstimate {
+    /// Format for display
+    pub fn format(&self) -> String {
+        let mut out = format!("Circle estimate ({:?}):\n", self.mode);
+        for phase in &self.phases {
+            out.push_str(&format!(
+                "  {} ({}): {} in / {} out - {}\n",
+                phase.persona,
+                phase.model.as_str(),
+                TokenCounter::format_tokens(phase.input_tokens),
+                TokenCounter::format_tokens(phase.output_tokens),
+                TokenCounter::format_cost(phase.cost_usd)
+            ));
+        }
+        out.push_str(&format!(
+            "Total: {} tokens, {}",
+            TokenCounter::format_tokens(self.total_tokens),
+            TokenCounter::format_cost(self.total_cost_usd)
+        ));
+        if self.mode == PipelineMode::Full {
+            out.push_str(&format!(
+                "\nEach review revision adds roughly {}",
+                TokenCounter::format_cost(self.revision_cost())
+            ));
+        }
+        out
+    }
+
+    /// Cost of one implementation -> review revision loop
+    pub fn revision_cost(&self) -> f64 {
+        self.phases
+            .iter()
+            .filter(|p| matches!(p.kind, PersonaKind::Implementation | PersonaKind::Review))
+            .map(|p| p.cost_usd)
+            .sum()
+    }
+}
+
 /// Development Circle orchestrator
 pub struct Circle {
     claude: ClaudeClient,
+    personas: CirclePersonas,
 }
 
 impl Circle {
-    /// Create new circle with Claude client
-    pub fn new(claude: ClaudeClient) -> Self {
-        Self { claude }
+    /// Create new circle with Claude client and the active personas
+    pub fn new(claude: ClaudeClient, personas: CirclePersonas) -> Self {
+        Self { claude, personas }
+    }
+
+    /// Active personas
+    pub fn personas(&self) -> &CirclePersonas {
+        &self.personas
+    }
+
+    /// Estimate tokens and cost per phase before running
+    ///
+    /// Each phase sees the feature, the code context and the system prompt.
+    /// Phases after an implementation phase also review its output, so the
+    /// implementer's expected output is added to their input.
+    pub fn estimate(
+        personas: &CirclePersonas,
+        feature: &str,
+        context: &str,
+        mode: PipelineMode,
+        counter: &TokenCounter,
+    ) -> CircleEstimate {
+        let mut implementation_tokens = 0;
+
+        let phases: Vec<PhaseEstimate> = personas
+            .for_mode(mode)
+            .into_iter()
+            .map(|persona| {
+                let state = PipelineState {
+                    feature: feature.to_string(),
+                    mode,
+                    current_phase: personas.phase_of(persona),
+                    revision: 0,
+                    phases: Vec::new(),
+                    code_context: context.to_string(),
+                    feedback: None,
+                };
+                let prompt = format!("{}{}", persona.system_prompt, Self::prompt_for(&state, persona));
+                let input_tokens = counter.count(&prompt) + implementation_tokens;
+                let output_tokens = persona.expected_output_tokens;
+                if persona.kind == PersonaKind::Implementation {
+                    implementation_tokens = output_tokens;
+                }
+                let model = persona.model_hint();
+                let pricing = ModelPricing::for_model(&model);
+                let cost_usd = (input_tokens as f64 * pricing.input_per_million
+                    + output_tokens as f64 * pricing.output_per_million)
+                    / 1_000_000.0;
+
+                PhaseEstimate {
+                    persona: persona.name.clone(),
+                    kind: persona.kind,
+                    model,
+                    input_tokens,
+                    output_tokens,
+                    cost_usd,
+                }
+            })
+            .collect();
+
+        CircleEstimate {
+            mode,
+            total_tokens: phases.iter().map(|p| p.input_tokens + p.output_tokens).sum(),
+            total_cost_usd: phases.iter().map(|p| p.cost_usd).sum(),
+            phases,
+        }
     }
 
     /// Run the full development circle pipeline
@@ -185,6 +704,17 @@ impl Circle {
         feature: &str,
         context: &str,
         mode: PipelineMode,
+    ) -> Result<PipelineResult> {
+        self.run_with_progress(feature, context, mode, &|_| {}).await
+    }
+
+    /// Run the pipeline, calling `on_phase` before each phase starts
+    pub async fn run_with_progress(
+        &self,
+        feature: &str,
+        context: &str,
+        mode: PipelineMode,
+        on_phase: &(dyn Fn(&PhaseProgress) + Send + Sync),
     ) -> Result<PipelineResult> {
         let start = std::time::Instant::now();
         info!("Starting Development Circle: {} (mode: {:?})", feature, mode);
@@ -199,29 +729,35 @@ impl Circle {
             feedback: None,
         };
 
-        let phases = match mode {
-            PipelineMode::Full => vec![
-                Persona::Carmack,
-                Persona::Linus,
-                Persona::Maria,
-                Persona::Kai,
-                Persona::Sentinel,
-            ],
-            PipelineMode::ReviewOnly => vec![Persona::Linus, Persona::Sentinel],
-            PipelineMode::QuickFix => vec![Persona::Carmack],
-            PipelineMode::SecurityOnly => vec![Persona::Sentinel],
-        };
+        let phases = self.personas.for_mode(mode);
+        if phases.is_empty() {
+            bail!("No persona runs in {:?} mode", mode);
+        }
+        // Rejected reviews go back to the first implementer
+        let revise_from = phases
+            .iter()
+            .position(|p| p.kind == PersonaKind::Implementation)
+            .unwrap_or(0);
 
         let mut phase_idx = 0;
+        let mut step = 0;
 
         while phase_idx < phases.len() {
             let persona = phases[phase_idx];
-            state.current_phase = persona.phase();
+            state.current_phase = self.personas.phase_of(persona);
+
+            step += 1;
+            on_phase(&PhaseProgress {
+                step,
+                planned: phases.len() + state.revision as usize * (phases.len() - revise_from),
+                persona: persona.name.clone(),
+                revision: state.revision,
+            });
 
             let result = self.execute_phase(&state, persona).await?;
 
             // Handle review verdicts
-            if persona == Persona::Linus {
+            if persona.kind == PersonaKind::Review {
                 if let Some(verdict) = &result.verdict {
                     if *verdict == Verdict::ChangesRequested {
                         state.revision += 1;
@@ -234,26 +770,26 @@ impl Circle {
                                 phases: state.phases,
                                 revisions: state.revision,
                                 success: false,
-                                blocked_at: Some("Linus - Max Revisions".to_string()),
+                                blocked_at: Some(format!("{} - Max Revisions", persona.name)),
                                 total_duration_ms: start.elapsed().as_millis() as u64,
                             });
                         }
 
-                        // Return to Carmack with feedback
+                        // Return to the implementer with feedback
                         state.feedback = Some(result.output.clone());
                         state.phases.push(result);
-                        phase_idx = 0; // Back to Carmack
+                        phase_idx = revise_from;
                         continue;
                     }
                 }
             }
 
             // Handle security verdicts
-            if persona == Persona::Sentinel {
+            if persona.kind == PersonaKind::Audit {
                 if let Some(risk) = result.risk_level {
                     if !risk.is_acceptable() {
                         warn!("Security blocked: {:?}", risk);
-                        let blocked_reason = format!("Sentinel - {:?} Risk", risk);
+                        let blocked_reason = format!("{} - {:?} Risk", persona.name, risk);
                         state.phases.push(result);
                         return Ok(PipelineResult {
                             feature: state.feature,
@@ -269,7 +805,7 @@ impl Circle {
             }
 
             // Update code context for next phase
-            if !result.output.is_empty() && persona == Persona::Carmack {
+            if !result.output.is_empty() && persona.kind == PersonaKind::Implementation {
                 state.code_context = result.output.clone();
             }
 
@@ -292,33 +828,33 @@ impl Circle {
     }
 
     /// Execute a single phase with the given persona
-    async fn execute_phase(&self, state: &PipelineState, persona: Persona) -> Result<PhaseResult> {
+    async fn execute_phase(&self, state: &PipelineState, persona: &PersonaConfig) -> Result<PhaseResult> {
         let start = std::time::Instant::now();
         debug!(
-            "[{}/5] {} - {}",
-            persona.phase(),
-            persona.name(),
-            persona.role()
+            "[{}/{}] {} - {}",
+            state.current_phase,
+            self.personas.all().len(),
+            persona.name,
+            persona.role
         );
 
-        let prompt = self.build_prompt(state, persona);
-        let system = persona.system_prompt();
-        let model = persona.model_hint();
+        let prompt = Self::prompt_for(state, persona);
 
         let response = self
             .claude
-            .complete(&prompt, system, None, 8192, model)
+            .complete_with_temperature(&prompt, &persona.system_prompt, 8192, &persona.model, persona.temperature)
             .await?;
+        let cost_usd = response.estimated_cost();
 
-        // Parse verdict from response (for Linus)
-        let verdict = if persona == Persona::Linus {
+        // Parse verdict from response (for reviewers)
+        let verdict = if persona.kind == PersonaKind::Review {
             Self::parse_verdict(&response.content)
         } else {
             None
         };
 
-        // Parse risk level from response (for Sentinel)
-        let risk_level = if persona == Persona::Sentinel {
+        // Parse risk level from response (for auditors)
+        let risk_level = if persona.kind == PersonaKind::Audit {
             Self::parse_risk_level(&response.content)
         } else {
             None
@@ -328,18 +864,25 @@ impl Circle {
         let files_changed = Self::extract_files(&response.content);
 
         Ok(PhaseResult {
-            persona: persona.name().to_string(),
-            phase: persona.phase(),
+            persona: persona.name.clone(),
+            phase: state.current_phase,
             output: response.content,
             verdict,
             risk_level,
             files_changed,
             duration_ms: start.elapsed().as_millis() as u64,
+            model: response.model,
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+            cache_read_tokens: response.cache_read_tokens,
+            cache_write_tokens: response.cache_write_tokens,
+            temperature: persona.temperature,
+            cost_usd,
         })
     }
 
     /// Build the prompt for a phase
-    fn build_prompt(&self, state: &PipelineState, persona: Persona) -> String {
+    fn prompt_for(state: &PipelineState, persona: &PersonaConfig) -> String {
         let mut prompt = format!(
             "## Feature Request\n\n{}\n\n## Current Code Context\n\n{}",
             state.feature, state.code_context
@@ -354,23 +897,7 @@ impl Circle {
         }
 
         // Add phase-specific instructions
-        match persona {
-            Persona::Carmack => {
-                prompt.push_str("\n\n## Task\n\nImplement this feature completely. Create all necessary files and write production-ready code.");
-            }
-            Persona::Linus => {
-                prompt.push_str("\n\n## Task\n\nReview this implementation. End your review with exactly one of: APPROVED, APPROVED_WITH_COMMENTS, CHANGES_REQUESTED, BLOCKED");
-            }
-            Persona::Maria => {
-                prompt.push_str("\n\n## Task\n\nWrite comprehensive tests for this implementation. Cover happy paths, edge cases, and error conditions.");
-            }
-            Persona::Kai => {
-                prompt.push_str("\n\n## Task\n\nOptimize this code for performance. Focus on allocations, hot paths, and code elegance.");
-            }
-            Persona::Sentinel => {
-                prompt.push_str("\n\n## Task\n\nPerform a security audit. Check OWASP Top 10. End with risk level: LOW, MEDIUM, HIGH, or CRITICAL");
-            }
-        }
+        prompt.push_str(&format!("\n\n## Task\n\n{}", persona.task));
 
         prompt
     }
@@ -435,18 +962,24 @@ impl Circle {
         }
 
         summary.push_str(&format!(
-            "**Duration:** {}ms\n\n",
+            "**Duration:** {}ms\n",
             result.total_duration_ms
         ));
+        summary.push_str(&format!(
+            "**Cost:** {}\n\n",
+            TokenCounter::format_cost(result.total_cost_usd())
+        ));
 
         summary.push_str("## Phases\n\n");
         for phase in &result.phases {
             summary.push_str(&format!(
-                "- [{}] {} ({:?}): {}ms\n",
+                "- [{}] {} ({:?}): {}ms, {}, {}\n",
                 phase.phase,
                 phase.persona,
                 phase.verdict.as_ref().map(|v| format!("{:?}", v)).unwrap_or_default(),
-                phase.duration_ms
+                phase.duration_ms,
+                phase.model,
+                TokenCounter::format_cost(phase.cost_usd)
             ));
         }
 
@@ -691,6 +1224,28 @@ mod tests {
         assert_eq!(Persona::Carmack.model_hint(), "sonnet");
     }
 
+    #[test]
+    fn test_estimate_follows_mode() {
+        let counter = TokenCounter::new();
+        let personas = CirclePersonas::default();
+        let quick = Circle::estimate(&personas, "Add a flag", "fn main() {}", PipelineMode::QuickFix, &counter);
+        assert_eq!(quick.phases.len(), 1);
+        assert_eq!(quick.phases[0].persona, "Carmack");
+
+        let full = Circle::estimate(&personas, "Add a flag", "fn main() {}", PipelineMode::Full, &counter);
+        assert_eq!(full.phases.len(), 5);
+        assert!(full.total_cost_usd > quick.total_cost_usd);
+
+        // Sentinel runs on opus, reviewing Carmack's output
+        let sentinel = full.phases.iter().find(|p| p.persona == "Sentinel").unwrap();
+        assert_eq!(sentinel.model, ModelHint::Opus);
+        assert!(sentinel.input_tokens > Persona::Carmack.expected_output_tokens());
+
+        // Without an implementation phase there is nothing extra to review
+        let security = Circle::estimate(&personas, "Add a flag", "fn main() {}", PipelineMode::SecurityOnly, &counter);
+        assert!(security.phases[0].input_tokens < sentinel.input_tokens);
+    }
+
     #[test]
     fn test_all_personas_have_prompts() {
         let personas = [
@@ -738,4 +1293,99 @@ mod tests {
     fn test_pipeline_mode_default() {
         assert_eq!(PipelineMode::default(), PipelineMode::Full);
     }
+
+    #[test]
+    fn test_default_personas_match_modes() {
+        let personas = CirclePersonas::default();
+        assert!(personas.validate().is_ok());
+        for mode in PipelineMode::ALL {
+            let names: Vec<&str> = personas.for_mode(mode).iter().map(|p| p.name.as_str()).collect();
+            let expected: Vec<&str> = mode.personas().iter().map(|p| p.name()).collect();
+            assert_eq!(names, expected, "{:?}", mode);
+        }
+        assert_eq!(personas.phase_of(&PersonaConfig::from(Persona::Sentinel)), 5);
+    }
+
+    #[test]
+    fn test_persona_file() {
+        let personas = CirclePersonas::parse(
+            r#"
+            [[personas]]
+            builtin = "Carmack"
+
+            [[personas]]
+            builtin = "Linus"
+            model = "opus"
+
+            [[personas]]
+            name = "Ferris"
+            role = "Unsafe Audit"
+            kind = "audit"
+            modes = ["full", "review_only", "security_only"]
+            temperature = 0.0
+            system_prompt = "You are Ferris, auditing unsafe Rust."
+            task = "Audit every unsafe block. End with risk level."
+            "#,
+        )
+        .unwrap();
+
+        let linus = &personas.all()[1];
+        assert_eq!(linus.model, "opus");
+        assert_eq!(personas.all()[2].temperature, Some(0.0));
+        assert_eq!(linus.temperature, None);
+        assert_eq!(linus.kind, PersonaKind::Review);
+        assert_eq!(linus.task, Persona::Linus.task_prompt());
+
+        let security: Vec<&str> = personas
+            .for_mode(PipelineMode::SecurityOnly)
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect();
+        assert_eq!(security, vec!["Ferris"]);
+        assert_eq!(personas.for_mode(PipelineMode::Full).len(), 3);
+
+        // Every mode needs someone to run
+        let err = CirclePersonas::parse("[[personas]]\nbuiltin = \"Carmack\"\n").unwrap_err();
+        assert!(err.to_string().contains("ReviewOnly"), "{}", err);
+        // Custom personas need a prompt
+        let err = CirclePersonas::parse("[[personas]]\nname = \"X\"\nrole = \"Y\"\n").unwrap_err();
+        assert!(err.to_string().contains("system_prompt"), "{}", err);
+        assert!(CirclePersonas::parse("[[personas]]\nbuiltin = \"Carmack\"\ncolour = \"red\"\n").is_err());
+        let mut hot = CirclePersonas::default().all().to_vec();
+        hot[0].temperature = Some(1.5);
+        let err = CirclePersonas::new(hot).unwrap_err();
+        assert!(err.to_string().contains("temperature"), "{}", err);
+    }
+
+    #[test]
+    fn test_pipeline_cost_per_phase() {
+        let phase = |persona: &str, model: &str, cost_usd: f64| PhaseResult {
+            persona: persona.to_string(),
+            phase: 1,
+            output: String::new(),
+            verdict: None,
+            risk_level: None,
+            files_changed: Vec::new(),
+            duration_ms: 10,
+            model: model.to_string(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            temperature: None,
+            cost_usd,
+        };
+        let result = PipelineResult {
+            feature: "auth".to_string(),
+            mode: PipelineMode::Full,
+            phases: vec![phase("Carmack", "claude-3-5-haiku-20241022", 0.01), phase("Sentinel", "claude-3-opus-20240229", 0.25)],
+            revisions: 0,
+            success: true,
+            blocked_at: None,
+            total_duration_ms: 20,
+        };
+        assert!((result.total_cost_usd() - 0.26).abs() < 1e-9);
+        let summary = Circle::summarize(&result);
+        assert!(summary.contains("Sentinel (\"\"): 10ms, claude-3-opus-20240229"), "{}", summary);
+    }
 }
@@ -53,6 +53,8 @@ struct MessageRequest {
     max_tokens: usize,
     system: Vec<SystemBlock>,
     messages: Vec<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
 }
 
 /// API response
@@ -100,7 +102,7 @@ impl CompleteResult {
 
         // Cached reads are 90% cheaper
         let cached_input_cost = (self.cache_read_tokens as f64 / 1_000_000.0) * input_price * 0.1;
-        let uncached_input_cost = ((self.input_tokens - self.cache_read_tokens) as f64 / 1_000_000.0) * input_price;
+        let uncached_input_cost = (self.input_tokens.saturating_sub(self.cache_read_tokens) as f64 / 1_000_000.0) * input_price;
         let output_cost = (self.output_tokens as f64 / 1_000_000.0) * output_price;
 
         cached_input_cost + uncached_input_cost + output_cost
@@ -169,6 +171,19 @@ impl ClaudeClient {
             .await
     }
 
+    /// Complete at a given sampling temperature (None = API default)
+    pub async fn complete_with_temperature(
+        &self,
+        prompt: &str,
+        static_context: &str,
+        max_tokens: usize,
+        model: &str,
+        temperature: Option<f32>,
+    ) -> Result<CompleteResult> {
+        self.send(prompt, static_context, None, max_tokens, model, &[], temperature)
+            .await
+    }
+
     /// Complete with conversation history
     pub async fn complete_with_history(
         &self,
@@ -178,6 +193,21 @@ impl ClaudeClient {
         max_tokens: usize,
         model: &str,
         history: &[(String, String)],
+    ) -> Result<CompleteResult> {
+        self.send(prompt, static_context, session_context, max_tokens, model, history, None)
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn send(
+        &self,
+        prompt: &str,
+        static_context: &str,
+        session_context: Option<&str>,
+        max_tokens: usize,
+        model: &str,
+        history: &[(String, String)],
+        temperature: Option<f32>,
     ) -> Result<CompleteResult> {
         let model_id = Self::model_id(model);
 
@@ -225,6 +255,7 @@ impl ClaudeClient {
             max_tokens,
             system,
             messages,
+            temperature,
         };
 
         debug!("Calling Claude API: model={}, prompt_len={}", model_id, prompt.len());
@@ -0,0 +1,218 @@
+//! Claude CLI Location
+//!
+//! Where the `claude` binary lives and which extra flags every run gets:
+//! - `CLAUDE_CLI_PATH` - binary to run (default `claude`, looked up on PATH)
+//! - `CLAUDE_EXTRA_ARGS` - shell-split flags appended after the mandatory
+//!   ones (`-p`, `--output-format`, permission flags), e.g.
+//!   `--add-dir /srv/shared --mcp-config "/etc/claude/mcp servers.json"`
+//!
+//! In safe mode (`CLAUDEBOT_SAFE_MODE`) no run skips permission prompts:
+//! `skip_permissions` adds nothing and permission-bypass flags are dropped
+//! from `CLAUDE_EXTRA_ARGS`.
+
+use anyhow::Result;
+use tokio::process::Command;
+use tracing::warn;
+
+/// Binary used when `CLAUDE_CLI_PATH` is unset
+pub const DEFAULT_CLAUDE_CLI: &str = "claude";
+
+/// Flag that lets Claude act without permission prompts
+pub const SKIP_PERMISSIONS_FLAG: &str = "--dangerously-skip-permissions";
+
+/// How to launch the Claude CLI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaudeCli {
+    pub path: String,
+    pub extra_args: Vec<String>,
+    /// Never skip permission prompts
+    pub safe_mode: bool,
+}
+
+impl Default for ClaudeCli {
+    fn default() -> Self {
+        Self {
+            path: DEFAULT_CLAUDE_CLI.to_string(),
+            extra_args: Vec::new(),
+            safe_mode: false,
+        }
+    }
+}
+
+impl ClaudeCli {
+    /// `CLAUDE_CLI_PATH`, `CLAUDE_EXTRA_ARGS` (unparseable args are ignored
+    /// with a warning) and `CLAUDEBOT_SAFE_MODE`
+    pub fn from_env() -> Self {
+        let path = std::env::var("CLAUDE_CLI_PATH")
+            .ok()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| DEFAULT_CLAUDE_CLI.to_string());
+
+        let extra_args = match std::env::var("CLAUDE_EXTRA_ARGS") {
+            Ok(raw) => split_args(&raw).unwrap_or_else(|e| {
+                warn!("Ignoring CLAUDE_EXTRA_ARGS: {}", e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        Self {
+            path,
+            extra_args,
+            safe_mode: false,
+        }
+        .with_safe_mode(crate::permissions::safe_mode_from_env())
+    }
+
+    /// Turn safe mode on or off; on drops permission-bypass extra args
+    pub fn with_safe_mode(mut self, safe_mode: bool) -> Self {
+        self.safe_mode = safe_mode;
+        if safe_mode {
+            let before = self.extra_args.len();
+            self.extra_args = without_permission_bypass(std::mem::take(&mut self.extra_args));
+            if self.extra_args.len() != before {
+                warn!("Safe mode: ignoring permission-bypass flags in CLAUDE_EXTRA_ARGS");
+            }
+        }
+        self
+    }
+
+    /// Add `SKIP_PERMISSIONS_FLAG` when `wanted` and not in safe mode
+    ///
+    /// Returns whether the flag was added.
+    pub fn skip_permissions(&self, cmd: &mut Command, wanted: bool) -> bool {
+        let skip = wanted && !self.safe_mode;
+        if skip {
+            cmd.arg(SKIP_PERMISSIONS_FLAG);
+        }
+        skip
+    }
+
+    /// A command for the configured binary, without any arguments
+    pub fn command(&self) -> Command {
+        Command::new(&self.path)
+    }
+
+    /// Append the configured extra flags (call after the mandatory ones)
+    pub fn add_extra_args(&self, cmd: &mut Command) {
+        cmd.args(&self.extra_args);
+    }
+}
+
+/// Drop flags that skip or bypass permission prompts
+fn without_permission_bypass(args: Vec<String>) -> Vec<String> {
+    let mut kept = Vec::with_capacity(args.len());
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            SKIP_PERMISSIONS_FLAG | "--allow-dangerously-skip-permissions" | "--permission-mode=bypassPermissions" => {}
+            "--permission-mode" if args.peek().map(String::as_str) == Some("bypassPermissions") => {
+                args.next();
+            }
+            _ => kept.push(arg),
+        }
+    }
+    kept
+}
+
+/// Split a string into arguments the way a POSIX shell would
+///
+/// Supports single quotes (literal), double quotes (with `\"` and `\\`
+/// escapes) and backslash escapes outside quotes. No variable expansion.
+pub fn split_args(input: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => anyhow::bail!("unterminated single quote"),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => current.push(ch),
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => anyhow::bail!("unterminated double quote"),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => anyhow::bail!("unterminated double quote"),
+                    }
+                }
+            }
+            '\\' => {
+                in_arg = true;
+                match chars.next() {
+                    Some(ch) => current.push(ch),
+                    None => anyhow::bail!("trailing backslash"),
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            split_args(r#"--add-dir /srv/shared  --mcp-config "/etc/claude/mcp servers.json""#).unwrap(),
+            vec!["--add-dir", "/srv/shared", "--mcp-config", "/etc/claude/mcp servers.json"]
+        );
+        assert_eq!(split_args(r#"'a b' c\ d "e\"f" ''"#).unwrap(), vec!["a b", "c d", "e\"f", ""]);
+        assert!(split_args("").unwrap().is_empty());
+        assert!(split_args("'open").is_err());
+        assert!(split_args("\"open").is_err());
+        assert!(split_args("trailing\\").is_err());
+    }
+
+    #[test]
+    fn test_safe_mode_never_skips_permissions() {
+        let extra = split_args("--add-dir /srv --dangerously-skip-permissions --permission-mode bypassPermissions --permission-mode=bypassPermissions --permission-mode plan").unwrap();
+        let cli = ClaudeCli {
+            extra_args: extra.clone(),
+            ..ClaudeCli::default()
+        };
+        let mut cmd = cli.command();
+        assert!(cli.skip_permissions(&mut cmd, true));
+        assert_eq!(cli.extra_args, extra);
+
+        let safe = cli.with_safe_mode(true);
+        assert_eq!(safe.extra_args, ["--add-dir", "/srv", "--permission-mode", "plan"]);
+        let mut cmd = safe.command();
+        assert!(!safe.skip_permissions(&mut cmd, true));
+        assert_eq!(cmd.as_std().get_args().count(), 0);
+    }
+}
@@ -18,6 +18,9 @@ pub struct Config {
     /// SQLite database path for memory
     pub db_path: PathBuf,
 
+    /// SQLite database of the bot's conversations, exposed as MCP resources (optional)
+    pub conversation_db_path: Option<PathBuf>,
+
     /// Workspace root for memory files
     pub workspace_path: PathBuf,
 
@@ -48,6 +51,8 @@ impl Config {
                     .join("memory.db")
             });
 
+        let conversation_db_path = std::env::var("CONVERSATION_DB_PATH").ok().map(PathBuf::from);
+
         let workspace_path = std::env::var("CLAUDEBOT_WORKSPACE")
             .map(PathBuf::from)
             .unwrap_or_else(|_| PathBuf::from("."));
@@ -69,6 +74,7 @@ impl Config {
             ollama_url,
             redis_url,
             db_path,
+            conversation_db_path,
             workspace_path,
             cache_enabled,
             cache_ttl_secs,
@@ -2,13 +2,17 @@
 //!
 //! Stores actual message turns per chat for conversation continuity.
 //! Unlike MemoryStore (semantic facts), this stores raw message history.
+//!
+//! Bookmarked messages are exempt from the rolling window and from expiry.
 
 use anyhow::Result;
-use rusqlite::{params, Connection};
+use rusqlite::params;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tracing::{debug, info};
 
+use crate::sqlite_pool::{SqliteConfig, SqlitePool};
+
 /// Maximum messages to keep per conversation (rolling window)
 const MAX_MESSAGES_PER_CONVERSATION: usize = 50;
 
@@ -21,6 +25,37 @@ pub struct ConversationMessage {
     pub role: String,      // "user" or "assistant"
     pub content: String,
     pub timestamp: i64,    // Unix timestamp
+    /// What generating an assistant turn cost, when known
+    #[serde(default)]
+    pub usage: Option<TurnUsage>,
+}
+
+/// Tokens and cost of one assistant turn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TurnUsage {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: f64,
+}
+
+/// Columns read by `message_from_row`
+const MESSAGE_COLUMNS: &str = "role, content, timestamp, input_tokens, output_tokens, cost_usd";
+
+fn message_from_row(row: &rusqlite::Row) -> rusqlite::Result<ConversationMessage> {
+    let cost_usd: Option<f64> = row.get(5)?;
+    Ok(ConversationMessage {
+        role: row.get(0)?,
+        content: row.get(1)?,
+        timestamp: row.get(2)?,
+        usage: match cost_usd {
+            Some(cost_usd) => Some(TurnUsage {
+                input_tokens: row.get::<_, Option<i64>>(3)?.unwrap_or(0),
+                output_tokens: row.get::<_, Option<i64>>(4)?.unwrap_or(0),
+                cost_usd,
+            }),
+            None => None,
+        },
+    })
 }
 
 /// Summary of a conversation
@@ -32,9 +67,109 @@ pub struct ConversationSummary {
     pub newest_timestamp: Option<i64>,
 }
 
+/// A user message that keeps being sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrequentMessage {
+    /// Latest wording
+    pub content: String,
+    /// Chat it was sent in
+    pub chat_id: i64,
+    pub count: usize,
+}
+
+/// A bookmarked message
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub message: ConversationMessage,
+    /// Telegram message to jump to (the user message, or the reply's first part)
+    pub telegram_id: Option<i64>,
+    /// For a bookmarked reply, the user message it answered
+    pub question: Option<String>,
+    /// Unix millis
+    pub bookmarked_at: i64,
+}
+
+/// A compression candidate with its importance score
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaleConversation {
+    pub chat_id: i64,
+    pub message_count: usize,
+    /// 0.0 (chit-chat) to 1.0 (dense, code-heavy, corrected)
+    pub importance: f64,
+}
+
+/// Phrases marking a user correcting the bot (as in the feedback loop)
+const CORRECTION_MARKERS: &[&str] = &[
+    "no, ", "actually, ", "that's wrong", "incorrect", "not quite", "i meant ", "to clarify", "let me correct",
+];
+
+/// How worth summarizing a conversation is, from 0.0 to 1.0
+///
+/// Combines three cheap signals:
+/// - entity density: capitalized names, numbers, paths and URLs per word
+/// - code: share of messages with code blocks or inline code
+/// - corrections: user messages correcting the assistant
+///
+/// Conversations of short messages (greetings, thanks) are halved.
+pub fn importance_score(messages: &[ConversationMessage]) -> f64 {
+    if messages.is_empty() {
+        return 0.0;
+    }
+
+    let mut words = 0usize;
+    let mut entities = 0usize;
+    for msg in messages {
+        let mut sentence_start = true;
+        for word in msg.content.split_whitespace() {
+            words += 1;
+            let token = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '/' && c != '.');
+            let is_entity = token.chars().any(|c| c.is_ascii_digit())
+                || token.contains("://")
+                || (token.contains('/') && token.len() > 1)
+                || (!sentence_start && token.chars().next().is_some_and(char::is_uppercase));
+            if is_entity {
+                entities += 1;
+            }
+            sentence_start = word.ends_with(['.', '!', '?', ':']);
+        }
+    }
+    let entity_density = if words == 0 { 0.0 } else { entities as f64 / words as f64 };
+    // ~15% entity words is already very dense
+    let entity_signal = (entity_density / 0.15).min(1.0);
+
+    let with_code = messages
+        .iter()
+        .filter(|m| m.content.contains("```") || m.content.matches('`').count() >= 2)
+        .count();
+    let code_signal = (with_code as f64 / messages.len() as f64 * 3.0).min(1.0);
+
+    let corrections = messages
+        .iter()
+        .filter(|m| m.role == "user")
+        .filter(|m| {
+            let lower = m.content.to_lowercase();
+            CORRECTION_MARKERS
+                .iter()
+                .any(|p| lower.starts_with(p) || lower.contains(&format!(" {}", p)))
+        })
+        .count();
+    let correction_signal = (corrections as f64 / 2.0).min(1.0);
+
+    let score = 0.4 * entity_signal + 0.35 * code_signal + 0.25 * correction_signal;
+    let avg_chars = messages.iter().map(|m| m.content.chars().count()).sum::<usize>() / messages.len();
+    if avg_chars < 40 {
+        score * 0.5
+    } else {
+        score
+    }
+}
+
 /// Conversation store with SQLite backend
+///
+/// Safe to share between tasks without an outer lock: reads use the pool's
+/// read connections while writes queue on its writer.
 pub struct ConversationStore {
-    conn: Connection,
+    db: SqlitePool,
     max_messages: usize,
     ttl_seconds: i64,
 }
@@ -47,9 +182,9 @@ impl ConversationStore {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(path)?;
+        let db = SqlitePool::open(path, &SqliteConfig::from_env())?;
         let store = Self {
-            conn,
+            db,
             max_messages: MAX_MESSAGES_PER_CONVERSATION,
             ttl_seconds: DEFAULT_TTL_SECONDS,
         };
@@ -67,9 +202,15 @@ impl ConversationStore {
         Ok(store)
     }
 
+    /// Messages kept per conversation (rolling window)
+    pub fn max_messages(&self) -> usize {
+        self.max_messages
+    }
+
     /// Initialize database schema
     fn init_schema(&self) -> Result<()> {
-        self.conn.execute_batch(
+        let conn = self.db.writer();
+        conn.execute_batch(
             r#"
             CREATE TABLE IF NOT EXISTS conversations (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -86,6 +227,28 @@ impl ConversationStore {
             "#,
         )?;
 
+        // Migration: Telegram message id of the user message an exchange answers
+        let _ = conn.execute(
+            "ALTER TABLE conversations ADD COLUMN message_id INTEGER",
+            [],
+        );
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_conversations_message_id
+                ON conversations(chat_id, message_id)",
+            [],
+        )?;
+
+        // Migration: tokens and cost of assistant turns
+        for column in ["input_tokens INTEGER", "output_tokens INTEGER", "cost_usd REAL"] {
+            let _ = conn.execute(&format!("ALTER TABLE conversations ADD COLUMN {}", column), []);
+        }
+
+        // Migration: Telegram messages carrying a reply (first and last part)
+        // and when a message was bookmarked
+        for column in ["reply_first_id INTEGER", "reply_last_id INTEGER", "bookmarked_at INTEGER"] {
+            let _ = conn.execute(&format!("ALTER TABLE conversations ADD COLUMN {}", column), []);
+        }
+
         Ok(())
     }
 
@@ -93,7 +256,7 @@ impl ConversationStore {
     pub fn add_message(&self, chat_id: i64, role: &str, content: &str) -> Result<()> {
         let timestamp = chrono::Utc::now().timestamp_millis(); // Use milliseconds for uniqueness
 
-        self.conn.execute(
+        self.db.writer().execute(
             "INSERT INTO conversations (chat_id, role, content, timestamp)
              VALUES (?1, ?2, ?3, ?4)",
             params![chat_id, role, content, timestamp],
@@ -108,22 +271,73 @@ impl ConversationStore {
 
     /// Add a complete exchange (user message + assistant response) atomically
     pub fn add_exchange(&self, chat_id: i64, user_msg: &str, assistant_msg: &str) -> Result<()> {
+        self.add_exchange_for_message(chat_id, None, user_msg, assistant_msg)
+    }
+
+    /// Add an exchange tagged with the chat message id it answers
+    ///
+    /// If an exchange for `message_id` already exists (the message was edited
+    /// and re-run), it is replaced in place instead of appending a new one.
+    pub fn add_exchange_for_message(
+        &self,
+        chat_id: i64,
+        message_id: Option<i64>,
+        user_msg: &str,
+        assistant_msg: &str,
+    ) -> Result<()> {
+        self.add_exchange_with_usage(chat_id, message_id, user_msg, assistant_msg, None)
+    }
+
+    /// Add an exchange, recording what the assistant turn cost
+    ///
+    /// Replacing an exchange (see `add_exchange_for_message`) replaces its
+    /// usage too.
+    pub fn add_exchange_with_usage(
+        &self,
+        chat_id: i64,
+        message_id: Option<i64>,
+        user_msg: &str,
+        assistant_msg: &str,
+        usage: Option<TurnUsage>,
+    ) -> Result<()> {
+        let input_tokens = usage.map(|u| u.input_tokens);
+        let output_tokens = usage.map(|u| u.output_tokens);
+        let cost_usd = usage.map(|u| u.cost_usd);
+
+        if let Some(message_id) = message_id {
+            let replaced = self.db.writer().execute(
+                "UPDATE conversations
+                 SET content = CASE role WHEN 'user' THEN ?3 ELSE ?4 END,
+                     input_tokens = CASE role WHEN 'user' THEN NULL ELSE ?5 END,
+                     output_tokens = CASE role WHEN 'user' THEN NULL ELSE ?6 END,
+                     cost_usd = CASE role WHEN 'user' THEN NULL ELSE ?7 END
+                 WHERE chat_id = ?1 AND message_id = ?2",
+                params![chat_id, message_id, user_msg, assistant_msg, input_tokens, output_tokens, cost_usd],
+            )?;
+            if replaced > 0 {
+                debug!("Replaced exchange for message {} in chat {}", message_id, chat_id);
+                return Ok(());
+            }
+        }
+
         let timestamp = chrono::Utc::now().timestamp_millis(); // Use milliseconds
 
         // Use transaction for atomicity
-        self.conn.execute("BEGIN", [])?;
+        let conn = self.db.writer();
+        conn.execute("BEGIN", [])?;
 
         let result = (|| -> Result<()> {
-            self.conn.execute(
-                "INSERT INTO conversations (chat_id, role, content, timestamp)
-                 VALUES (?1, 'user', ?2, ?3)",
-                params![chat_id, user_msg, timestamp],
+            conn.execute(
+                "INSERT INTO conversations (chat_id, role, content, timestamp, message_id)
+                 VALUES (?1, 'user', ?2, ?3, ?4)",
+                params![chat_id, user_msg, timestamp, message_id],
             )?;
 
-            self.conn.execute(
-                "INSERT INTO conversations (chat_id, role, content, timestamp)
-                 VALUES (?1, 'assistant', ?2, ?3)",
-                params![chat_id, assistant_msg, timestamp + 1], // +1ms to ensure ordering
+            conn.execute(
+                "INSERT INTO conversations
+                    (chat_id, role, content, timestamp, message_id, input_tokens, output_tokens, cost_usd)
+                 VALUES (?1, 'assistant', ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![chat_id, assistant_msg, timestamp + 1, message_id, input_tokens, output_tokens, cost_usd], // +1ms to ensure ordering
             )?;
 
             Ok(())
@@ -131,35 +345,130 @@ impl ConversationStore {
 
         match result {
             Ok(()) => {
-                self.conn.execute("COMMIT", [])?;
+                conn.execute("COMMIT", [])?;
+                drop(conn);
                 self.trim_default(chat_id)?;
                 debug!("Added exchange to chat {}", chat_id);
                 Ok(())
             }
             Err(e) => {
-                let _ = self.conn.execute("ROLLBACK", []);
+                let _ = conn.execute("ROLLBACK", []);
                 Err(e)
             }
         }
     }
 
+    /// Replace the user side of the exchange for an edited message
+    ///
+    /// The assistant reply is kept. Returns false if no exchange is stored
+    /// for `message_id`.
+    pub fn update_user_message(&self, chat_id: i64, message_id: i64, content: &str) -> Result<bool> {
+        let updated = self.db.writer().execute(
+            "UPDATE conversations SET content = ?3
+             WHERE chat_id = ?1 AND message_id = ?2 AND role = 'user'",
+            params![chat_id, message_id, content],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Record the Telegram messages the reply to `message_id` was sent as
+    ///
+    /// Long replies are split; any part between `first_id` and `last_id`
+    /// identifies the reply for `bookmark`.
+    pub fn set_reply_messages(&self, chat_id: i64, message_id: i64, first_id: i64, last_id: i64) -> Result<bool> {
+        let updated = self.db.writer().execute(
+            "UPDATE conversations SET reply_first_id = ?3, reply_last_id = ?4
+             WHERE chat_id = ?1 AND message_id = ?2 AND role = 'assistant'",
+            params![chat_id, message_id, first_id, last_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Bookmark the stored message shown as Telegram message `message_id`:
+    /// a user message, or any part of a reply
+    ///
+    /// Returns false if no stored message matches.
+    pub fn bookmark(&self, chat_id: i64, message_id: i64) -> Result<bool> {
+        let updated = self.db.writer().execute(
+            &format!(
+                "UPDATE conversations SET bookmarked_at = COALESCE(bookmarked_at, ?3)
+                 WHERE chat_id = ?1 AND {}",
+                Self::SHOWN_AS
+            ),
+            params![chat_id, message_id, chrono::Utc::now().timestamp_millis()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Remove a bookmark set with `bookmark`
+    pub fn remove_bookmark(&self, chat_id: i64, message_id: i64) -> Result<bool> {
+        let updated = self.db.writer().execute(
+            &format!(
+                "UPDATE conversations SET bookmarked_at = NULL
+                 WHERE chat_id = ?1 AND bookmarked_at IS NOT NULL AND {}",
+                Self::SHOWN_AS
+            ),
+            params![chat_id, message_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Rows displayed as Telegram message `?2`
+    const SHOWN_AS: &'static str = "((role = 'user' AND message_id = ?2)
+        OR (role = 'assistant' AND ?2 BETWEEN reply_first_id AND reply_last_id))";
+
+    /// A chat's bookmarks, oldest message first
+    pub fn list_bookmarks(&self, chat_id: i64) -> Result<Vec<Bookmark>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}, CASE c.role WHEN 'user' THEN c.message_id ELSE c.reply_first_id END, c.bookmarked_at,
+                (SELECT q.content FROM conversations q
+                 WHERE c.role = 'assistant' AND q.chat_id = c.chat_id AND q.role = 'user'
+                   AND q.message_id = c.message_id
+                 LIMIT 1)
+             FROM conversations c
+             WHERE c.chat_id = ?1 AND c.bookmarked_at IS NOT NULL
+             ORDER BY c.timestamp ASC, c.id ASC",
+            MESSAGE_COLUMNS
+        ))?;
+
+        let bookmarks = stmt
+            .query_map(params![chat_id], |row| {
+                Ok(Bookmark {
+                    message: message_from_row(row)?,
+                    telegram_id: row.get(6)?,
+                    bookmarked_at: row.get(7)?,
+                    question: row.get(8)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(bookmarks)
+    }
+
+    /// Remove the exchange stored for a message
+    pub fn remove_exchange(&self, chat_id: i64, message_id: i64) -> Result<usize> {
+        let removed = self.db.writer().execute(
+            "DELETE FROM conversations WHERE chat_id = ?1 AND message_id = ?2",
+            params![chat_id, message_id],
+        )?;
+        Ok(removed)
+    }
+
     /// Get conversation history for a chat
     pub fn get_history(&self, chat_id: i64, limit: usize) -> Result<Vec<ConversationMessage>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT role, content, timestamp FROM conversations
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM conversations
              WHERE chat_id = ?1
              ORDER BY timestamp DESC
              LIMIT ?2",
-        )?;
+            MESSAGE_COLUMNS
+        ))?;
 
         let messages: Vec<ConversationMessage> = stmt
-            .query_map(params![chat_id, limit], |row| {
-                Ok(ConversationMessage {
-                    role: row.get(0)?,
-                    content: row.get(1)?,
-                    timestamp: row.get(2)?,
-                })
-            })?
+            .query_map(params![chat_id, limit], message_from_row)?
             .filter_map(|r| r.ok())
             .collect();
 
@@ -170,12 +479,45 @@ impl ConversationStore {
         Ok(messages)
     }
 
+    /// User messages sent at least `min_count` times in one chat in the last
+    /// `since_secs`, most repeated first
+    ///
+    /// Messages are grouped per chat, case- and whitespace-insensitively;
+    /// each result carries the latest wording.
+    pub fn frequent_user_messages(&self, since_secs: i64, min_count: usize, limit: usize) -> Result<Vec<FrequentMessage>> {
+        let cutoff = Self::cutoff_millis(since_secs);
+        // SQLite takes bare columns from the row that produced MAX(id)
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            "SELECT content, chat_id, COUNT(*) AS n, MAX(id) FROM conversations
+             WHERE role = 'user' AND timestamp >= ?1
+             GROUP BY chat_id, lower(trim(content))
+             HAVING n >= ?2
+             ORDER BY n DESC, MAX(id) DESC
+             LIMIT ?3",
+        )?;
+        let messages = stmt
+            .query_map(params![cutoff, min_count as i64, limit as i64], |row| {
+                Ok(FrequentMessage {
+                    content: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    count: row.get::<_, i64>(2)? as usize,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(messages)
+    }
+
     /// Get conversation history formatted for Claude prompt injection
     pub fn get_history_as_context(&self, chat_id: i64, limit: usize) -> Result<String> {
-        let messages = self.get_history(chat_id, limit)?;
+        Ok(Self::format_as_context(self.get_history(chat_id, limit)?))
+    }
 
+    /// Format messages (oldest first) for Claude prompt injection
+    pub fn format_as_context(messages: Vec<ConversationMessage>) -> String {
         if messages.is_empty() {
-            return Ok(String::new());
+            return String::new();
         }
 
         let mut context = String::from("\n\n[Previous conversation:]\n");
@@ -197,13 +539,12 @@ impl ConversationStore {
             context.push_str(&format!("{}: {}\n", role_label, content));
         }
         context.push_str("\n[Current message:]\n");
-
-        Ok(context)
+        context
     }
 
     /// Clear conversation history for a chat
     pub fn clear(&self, chat_id: i64) -> Result<usize> {
-        let rows = self.conn.execute(
+        let rows = self.db.writer().execute(
             "DELETE FROM conversations WHERE chat_id = ?1",
             params![chat_id],
         )?;
@@ -213,7 +554,8 @@ impl ConversationStore {
 
     /// Get conversation summary
     pub fn get_summary(&self, chat_id: i64) -> Result<ConversationSummary> {
-        let mut stmt = self.conn.prepare(
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
             "SELECT COUNT(*), MIN(timestamp), MAX(timestamp)
              FROM conversations WHERE chat_id = ?1",
         )?;
@@ -230,11 +572,37 @@ impl ConversationStore {
         Ok(summary)
     }
 
+    /// One page of chats with stored messages, most recently active first
+    pub fn list_chats(&self, offset: usize, limit: usize) -> Result<Vec<ConversationSummary>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            "SELECT chat_id, COUNT(*), MIN(timestamp), MAX(timestamp)
+             FROM conversations
+             GROUP BY chat_id
+             ORDER BY MAX(timestamp) DESC, chat_id
+             LIMIT ?1 OFFSET ?2",
+        )?;
+
+        let chats = stmt
+            .query_map(params![limit, offset], |row| {
+                Ok(ConversationSummary {
+                    chat_id: row.get(0)?,
+                    message_count: row.get::<_, i64>(1)? as usize,
+                    oldest_timestamp: row.get(2)?,
+                    newest_timestamp: row.get(3)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(chats)
+    }
+
     /// Trim conversation to a specific number of messages
     pub fn trim_conversation(&self, chat_id: i64, keep_count: usize) -> Result<usize> {
-        let rows = self.conn.execute(
+        let rows = self.db.writer().execute(
             "DELETE FROM conversations
-             WHERE chat_id = ?1 AND id NOT IN (
+             WHERE chat_id = ?1 AND bookmarked_at IS NULL AND id NOT IN (
                  SELECT id FROM conversations
                  WHERE chat_id = ?1
                  ORDER BY timestamp DESC
@@ -245,26 +613,45 @@ impl ConversationStore {
         Ok(rows)
     }
 
-    /// Get chat IDs with old conversations that have many messages
-    /// Returns chats older than `age_seconds` with more than `min_messages`
-    pub fn get_stale_conversations(&self, age_seconds: i64, min_messages: usize) -> Result<Vec<i64>> {
+    /// Get old conversations that have many messages, most important first
+    /// Returns chats older than `age_seconds` with more than `min_messages`,
+    /// scored with `importance_score` over their stored history
+    pub fn get_stale_conversations(&self, age_seconds: i64, min_messages: usize) -> Result<Vec<StaleConversation>> {
         let cutoff = chrono::Utc::now().timestamp_millis() - (age_seconds * 1000);
 
-        let mut stmt = self.conn.prepare(
-            "SELECT chat_id, COUNT(*) as msg_count, MAX(timestamp) as last_msg
-             FROM conversations
-             GROUP BY chat_id
-             HAVING msg_count > ?1 AND last_msg < ?2
-             ORDER BY msg_count DESC"
-        )?;
+        let chats: Vec<(i64, i64)> = {
+            let conn = self.db.reader();
+            let mut stmt = conn.prepare(
+                "SELECT chat_id, COUNT(*) as msg_count, MAX(timestamp) as last_msg
+                 FROM conversations
+                 GROUP BY chat_id
+                 HAVING msg_count > ?1 AND last_msg < ?2
+                 ORDER BY msg_count DESC"
+            )?;
+            let chats = stmt.query_map(params![min_messages as i64, cutoff], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+            chats
+        };
 
-        let chats = stmt.query_map(params![min_messages as i64, cutoff], |row| {
-            row.get::<_, i64>(0)
-        })?
-        .filter_map(|r| r.ok())
-        .collect();
+        let mut scored = Vec::with_capacity(chats.len());
+        for (chat_id, message_count) in chats {
+            let messages = self.get_history(chat_id, self.max_messages)?;
+            scored.push(StaleConversation {
+                chat_id,
+                message_count: message_count as usize,
+                importance: importance_score(&messages),
+            });
+        }
+        scored.sort_by(|a, b| {
+            b.importance
+                .total_cmp(&a.importance)
+                .then(b.message_count.cmp(&a.message_count))
+        });
 
-        Ok(chats)
+        Ok(scored)
     }
 
     /// Internal trim (uses default max)
@@ -275,11 +662,14 @@ impl ConversationStore {
 
     /// Clean up expired conversations (older than TTL)
     pub fn cleanup_expired(&self) -> Result<usize> {
-        // Convert TTL to milliseconds since we store timestamp_millis
-        let cutoff = chrono::Utc::now().timestamp_millis() - (self.ttl_seconds * 1000);
-        let rows = self.conn.execute(
-            "DELETE FROM conversations WHERE timestamp < ?1",
-            params![cutoff],
+        self.expire_older_than(self.ttl_seconds)
+    }
+
+    /// Delete messages older than `seconds` across all chats
+    pub fn expire_older_than(&self, seconds: i64) -> Result<usize> {
+        let rows = self.db.writer().execute(
+            "DELETE FROM conversations WHERE timestamp < ?1 AND bookmarked_at IS NULL",
+            params![Self::cutoff_millis(seconds)],
         )?;
         if rows > 0 {
             info!("Cleaned up {} expired conversation messages", rows);
@@ -287,14 +677,84 @@ impl ConversationStore {
         Ok(rows)
     }
 
+    /// Delete a chat's messages older than `seconds`
+    pub fn expire_chat_older_than(&self, chat_id: i64, seconds: i64) -> Result<usize> {
+        self.expire_chat_before(chat_id, Self::cutoff_millis(seconds))
+    }
+
+    /// Delete a chat's messages from before `cutoff` (unix milliseconds)
+    ///
+    /// Pass the cutoff used with `get_messages_before` to delete exactly the
+    /// messages that were read, however long processing them took.
+    pub fn expire_chat_before(&self, chat_id: i64, cutoff: i64) -> Result<usize> {
+        let rows = self.db.writer().execute(
+            "DELETE FROM conversations WHERE chat_id = ?1 AND timestamp < ?2 AND bookmarked_at IS NULL",
+            params![chat_id, cutoff],
+        )?;
+        if rows > 0 {
+            info!("Expired {} messages from chat {}", rows, chat_id);
+        }
+        Ok(rows)
+    }
+
+    /// Get a chat's messages older than `seconds`, oldest first (bookmarks
+    /// aren't expired, so they're left out)
+    pub fn get_messages_older_than(&self, chat_id: i64, seconds: i64) -> Result<Vec<ConversationMessage>> {
+        self.get_messages_before(chat_id, Self::cutoff_millis(seconds))
+    }
+
+    /// Get a chat's messages from before `cutoff` (unix milliseconds), oldest first
+    pub fn get_messages_before(&self, chat_id: i64, cutoff: i64) -> Result<Vec<ConversationMessage>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM conversations
+             WHERE chat_id = ?1 AND timestamp < ?2 AND bookmarked_at IS NULL
+             ORDER BY timestamp ASC, id ASC",
+            MESSAGE_COLUMNS
+        ))?;
+
+        let messages = stmt
+            .query_map(params![chat_id, cutoff], message_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(messages)
+    }
+
+    /// Get chat IDs that have messages older than `seconds`
+    pub fn chats_with_messages_older_than(&self, seconds: i64) -> Result<Vec<i64>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT chat_id FROM conversations WHERE timestamp < ?1 AND bookmarked_at IS NULL",
+        )?;
+
+        let chats = stmt
+            .query_map(params![Self::cutoff_millis(seconds)], |row| row.get::<_, i64>(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(chats)
+    }
+
+    /// Cutoff timestamp for an age, in milliseconds since we store timestamp_millis
+    pub fn cutoff_millis(seconds: i64) -> i64 {
+        chrono::Utc::now().timestamp_millis() - seconds.saturating_mul(1000)
+    }
+
+    /// Fail unless the database accepts writes (probe is rolled back)
+    pub fn check_writable(&self) -> Result<()> {
+        let conn = self.db.writer();
+        let probe = conn.execute_batch("BEGIN IMMEDIATE; CREATE TABLE _write_probe(x);");
+        let _ = conn.execute_batch("ROLLBACK");
+        Ok(probe?)
+    }
+
     /// Get total stats
     pub fn stats(&self) -> Result<ConversationStats> {
-        let total_messages: i64 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM conversations", [], |row| row.get(0))?;
+        let conn = self.db.reader();
+        let total_messages: i64 = conn.query_row("SELECT COUNT(*) FROM conversations", [], |row| row.get(0))?;
 
-        let total_chats: i64 = self
-            .conn
+        let total_chats: i64 = conn
             .query_row("SELECT COUNT(DISTINCT chat_id) FROM conversations", [], |row| row.get(0))?;
 
         Ok(ConversationStats {
@@ -302,6 +762,75 @@ impl ConversationStore {
             total_chats: total_chats as usize,
         })
     }
+
+    /// Database size and row counts per table
+    pub fn storage_stats(&self) -> Result<crate::storage::DbStorage> {
+        crate::storage::sqlite_storage(&self.db.reader(), "conversations")
+    }
+}
+
+/// Render messages as a Markdown transcript for sharing
+///
+/// Each message gets a role heading with its UTC time; content is kept
+/// verbatim so code blocks survive, with an unterminated fence closed so it
+/// can't swallow the rest of the transcript.
+pub fn format_markdown(title: &str, messages: &[ConversationMessage]) -> String {
+    let mut out = format!("# {}\n\n", title);
+    if let (Some(first), Some(last)) = (messages.first(), messages.last()) {
+        out.push_str(&format!(
+            "_{} messages, {} – {}_\n",
+            messages.len(),
+            format_millis(first.timestamp),
+            format_millis(last.timestamp)
+        ));
+    }
+
+    for msg in messages {
+        let role = match msg.role.as_str() {
+            "user" => "User",
+            "assistant" => "Assistant",
+            other => other,
+        };
+        out.push_str(&format!("\n## {} · {}\n\n", role, format_millis(msg.timestamp)));
+        out.push_str(msg.content.trim_end());
+        out.push('\n');
+
+        let fences = msg.content.lines().filter(|l| l.trim_start().starts_with("```")).count();
+        if fences % 2 == 1 {
+            out.push_str("```\n");
+        }
+    }
+    out
+}
+
+/// Longest preview of a bookmarked message
+const BOOKMARK_PREVIEW_CHARS: usize = 150;
+
+/// Numbered bookmark list for display
+pub fn format_bookmarks(bookmarks: &[Bookmark]) -> String {
+    let preview = |text: &str, max: usize| {
+        let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        match flat.char_indices().nth(max) {
+            Some((end, _)) => format!("{}…", &flat[..end]),
+            None => flat,
+        }
+    };
+    let mut out = format!("🔖 Bookmarks ({})\n", bookmarks.len());
+    for (i, bookmark) in bookmarks.iter().enumerate() {
+        let role = if bookmark.message.role == "user" { "You" } else { "Assistant" };
+        out.push_str(&format!("\n{}. {} · {}\n", i + 1, format_millis(bookmark.message.timestamp), role));
+        if let Some(question) = &bookmark.question {
+            out.push_str(&format!("   Q: {}\n", preview(question, 80)));
+        }
+        out.push_str(&format!("   {}\n", preview(&bookmark.message.content, BOOKMARK_PREVIEW_CHARS)));
+    }
+    out
+}
+
+fn format_millis(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
 }
 
 /// Global conversation statistics
@@ -322,6 +851,22 @@ mod tests {
         ConversationStore::open(&path).unwrap()
     }
 
+    #[test]
+    fn test_frequent_user_messages() {
+        let store = temp_db("frequent");
+        for (chat_id, text) in [(1, "Deploy status?"), (2, "deploy status? "), (1, "thanks"), (2, "Deploy status?"), (1, "thanks")] {
+            store.add_exchange(chat_id, text, "ok").unwrap();
+        }
+        let frequent = store.frequent_user_messages(3600, 2, 10).unwrap();
+        assert_eq!(frequent.len(), 2);
+        assert_eq!((frequent[0].count, frequent[0].chat_id), (2, 1));
+        assert_eq!(frequent[0].content, "thanks");
+        assert_eq!((frequent[1].count, frequent[1].chat_id), (2, 2));
+        assert_eq!(frequent[1].content, "Deploy status?");
+        // Repeats in other chats don't add up
+        assert!(store.frequent_user_messages(3600, 3, 10).unwrap().is_empty());
+    }
+
     #[test]
     fn test_add_and_get_history() {
         let store = temp_db("history");
@@ -355,6 +900,57 @@ mod tests {
         assert_eq!(history.len(), 4);
     }
 
+    #[test]
+    fn test_edited_message_updates_exchange() {
+        let store = temp_db("edited");
+        let chat_id = 12345;
+
+        store.add_exchange_for_message(chat_id, Some(7), "Wht is Rust?", "A systems language").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        store.add_exchange(chat_id, "Thanks", "You're welcome").unwrap();
+
+        assert!(store.update_user_message(chat_id, 7, "What is Rust?").unwrap());
+        assert!(!store.update_user_message(chat_id, 8, "Unknown").unwrap());
+
+        let history = store.get_history(chat_id, 10).unwrap();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].content, "What is Rust?");
+        assert_eq!(history[1].content, "A systems language");
+
+        // Re-running the edited message replaces both sides in place
+        store.add_exchange_for_message(chat_id, Some(7), "What is Go?", "Another language").unwrap();
+        let history = store.get_history(chat_id, 10).unwrap();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].content, "What is Go?");
+        assert_eq!(history[1].content, "Another language");
+
+        assert_eq!(store.remove_exchange(chat_id, 7).unwrap(), 2);
+        assert_eq!(store.get_history(chat_id, 10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_exchange_usage() {
+        let store = temp_db("usage");
+        let chat_id = 12345;
+        let usage = TurnUsage { input_tokens: 1200, output_tokens: 300, cost_usd: 0.0081 };
+
+        store.add_exchange_with_usage(chat_id, Some(3), "Explain lifetimes", "Long answer", Some(usage)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        store.add_exchange(chat_id, "Thanks", "You're welcome").unwrap();
+
+        let history = store.get_history(chat_id, 10).unwrap();
+        assert_eq!(history[0].usage, None);
+        assert_eq!(history[1].usage, Some(usage));
+        assert_eq!(history[3].usage, None);
+
+        // A re-run replaces the cost along with the answer
+        let rerun = TurnUsage { cost_usd: 0.02, ..usage };
+        store.add_exchange_with_usage(chat_id, Some(3), "Explain lifetimes", "Better answer", Some(rerun)).unwrap();
+        assert_eq!(store.get_history(chat_id, 10).unwrap()[1].usage, Some(rerun));
+        store.add_exchange_for_message(chat_id, Some(3), "Explain lifetimes", "Cached answer").unwrap();
+        assert_eq!(store.get_history(chat_id, 10).unwrap()[1].usage, None);
+    }
+
     #[test]
     fn test_history_as_context() {
         let store = temp_db("context");
@@ -419,6 +1015,74 @@ mod tests {
         assert!(history2[0].content.contains("Chat 2"));
     }
 
+    #[test]
+    fn test_expire_older_than() {
+        let store = temp_db("expire");
+        let old = chrono::Utc::now().timestamp_millis() - 3 * 86_400_000;
+        for (chat_id, content) in [(111, "Old one"), (111, "Old two"), (222, "Old other")] {
+            store
+                .db
+                .writer()
+                .execute(
+                    "INSERT INTO conversations (chat_id, role, content, timestamp) VALUES (?1, 'user', ?2, ?3)",
+                    params![chat_id, content, old],
+                )
+                .unwrap();
+        }
+        store.add_message(111, "user", "Fresh").unwrap();
+
+        assert_eq!(store.chats_with_messages_older_than(86_400).unwrap().len(), 2);
+        let expired = store.get_messages_older_than(111, 86_400).unwrap();
+        assert_eq!(expired.len(), 2);
+        assert_eq!(expired[0].content, "Old one");
+
+        // One cutoff for reading and deleting (as retention does)
+        let cutoff = ConversationStore::cutoff_millis(86_400);
+        assert_eq!(store.get_messages_before(111, cutoff).unwrap().len(), 2);
+        assert_eq!(store.expire_chat_before(111, cutoff).unwrap(), 2);
+        assert_eq!(store.expire_older_than(86_400).unwrap(), 1);
+        let history = store.get_history(111, 10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "Fresh");
+    }
+
+    #[test]
+    fn test_bookmarks_survive_trim_and_expiry() {
+        let store = temp_db("bookmarks");
+        let chat_id = 12345;
+
+        store.add_exchange_for_message(chat_id, Some(7), "How do I rotate keys?", "Run vault rotate").unwrap();
+        assert!(store.set_reply_messages(chat_id, 7, 8, 10).unwrap());
+        // A reply is found from any of its parts
+        assert!(store.bookmark(chat_id, 9).unwrap());
+        assert!(!store.bookmark(chat_id, 42).unwrap());
+
+        let old = chrono::Utc::now().timestamp_millis() - 10 * 86_400_000;
+        store.db.writer().execute("UPDATE conversations SET timestamp = ?1", params![old]).unwrap();
+        store.trim_conversation(chat_id, 0).unwrap();
+        assert!(store.chats_with_messages_older_than(86_400).unwrap().is_empty());
+        assert_eq!(store.expire_older_than(86_400).unwrap(), 0);
+
+        let bookmarks = store.list_bookmarks(chat_id).unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].message.content, "Run vault rotate");
+        // The unbookmarked question was trimmed, so there's nothing to quote
+        assert_eq!((bookmarks[0].telegram_id, bookmarks[0].question.as_deref()), (Some(8), None));
+
+        store.add_exchange_for_message(chat_id, Some(20), "Where are the logs?", "In /var/log/claudebot").unwrap();
+        assert!(store.set_reply_messages(chat_id, 20, 21, 21).unwrap());
+        assert!(store.bookmark(chat_id, 21).unwrap());
+        assert!(store.bookmark(chat_id, 20).unwrap());
+        let bookmarks = store.list_bookmarks(chat_id).unwrap();
+        assert_eq!(bookmarks.len(), 3);
+        assert_eq!(bookmarks[2].question.as_deref(), Some("Where are the logs?"));
+        assert!(format_bookmarks(&bookmarks).contains("3. "));
+
+        assert!(store.remove_bookmark(chat_id, 9).unwrap());
+        assert!(!store.remove_bookmark(chat_id, 9).unwrap());
+        assert_eq!(store.expire_older_than(86_400).unwrap(), 1);
+    }
+
     #[test]
     fn test_summary() {
         let store = temp_db("summary");
@@ -431,4 +1095,51 @@ mod tests {
         assert!(summary.oldest_timestamp.is_some());
         assert!(summary.newest_timestamp.is_some());
     }
+
+    #[test]
+    fn test_stale_conversations_ranked_by_importance() {
+        let store = temp_db("importance");
+        for _ in 0..3 {
+            store.add_exchange(1, "hi", "hello!").unwrap();
+        }
+        store
+            .add_exchange(2, "Why does src/main.rs panic on line 42?", "The `unwrap` on `Config::load` fails:\n```rust\nlet c = Config::load()?;\n```")
+            .unwrap();
+        store
+            .add_exchange(2, "No, the panic is in Tokio's runtime, not Config.", "Then check the Runtime builder in src/lib.rs.")
+            .unwrap();
+
+        // Negative age puts the cutoff in the future so fresh chats count as stale
+        let stale = store.get_stale_conversations(-60, 3).unwrap();
+        assert_eq!(stale.iter().map(|c| c.chat_id).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(stale[1].message_count, 6);
+        assert!(stale[0].importance > 0.5, "{}", stale[0].importance);
+        assert!(stale[1].importance < 0.15, "{}", stale[1].importance);
+        assert_eq!(importance_score(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_format_markdown() {
+        let messages = vec![
+            ConversationMessage {
+                role: "user".to_string(),
+                content: "Why does this fail?\n```rust\nfn main() {}".to_string(),
+                timestamp: 1_700_000_000_000,
+                usage: None,
+            },
+            ConversationMessage {
+                role: "assistant".to_string(),
+                content: "Missing semicolon.".to_string(),
+                timestamp: 1_700_000_060_000,
+                usage: None,
+            },
+        ];
+
+        let md = format_markdown("Debug session", &messages);
+        assert!(md.starts_with("# Debug session\n"));
+        assert!(md.contains("_2 messages, 2023-11-14 22:13 UTC – 2023-11-14 22:14 UTC_"));
+        assert!(md.contains("## User · 2023-11-14 22:13 UTC\n\nWhy does this fail?\n```rust\nfn main() {}\n```\n"));
+        assert!(md.contains("## Assistant · 2023-11-14 22:14 UTC\n\nMissing semicolon.\n"));
+    }
+
 }
@@ -0,0 +1,396 @@
+//! Knowledge Graph API
+//!
+//! REST API endpoints for inspecting the knowledge graph.
+//!
+//! # Endpoints
+//!
+//! - `GET /api/graph/stats` - Entity/relation counts by type
+//! - `GET /api/graph/entities` - Paginated entities (`?type=&q=&limit=&offset=`)
+//! - `GET /api/graph/entity/:name/neighbors` - Node-link view around an entity (`?depth=`)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+use crate::graph::{Entity, GraphSearchResult, GraphStore};
+
+/// Graph API state
+pub struct GraphApiState {
+    /// Shared graph store (None if unavailable)
+    pub store: Option<Arc<Mutex<GraphStore>>>,
+}
+
+impl GraphApiState {
+    /// Create with a shared graph store
+    pub fn new(store: Arc<Mutex<GraphStore>>) -> Self {
+        Self { store: Some(store) }
+    }
+
+    /// Create without a graph store (all endpoints return 503)
+    pub fn empty() -> Self {
+        Self { store: None }
+    }
+
+    /// Open the graph at `MEMORY_DB_PATH` if set, otherwise empty
+    pub fn with_defaults() -> Self {
+        let Ok(path) = std::env::var("MEMORY_DB_PATH") else {
+            return Self::empty();
+        };
+        match GraphStore::open(&PathBuf::from(&path)) {
+            Ok(store) => Self::new(Arc::new(Mutex::new(store))),
+            Err(e) => {
+                warn!("Graph browser disabled, failed to open {}: {}", path, e);
+                Self::empty()
+            }
+        }
+    }
+}
+
+impl Default for GraphApiState {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Graph statistics response
+#[derive(Debug, Serialize)]
+pub struct GraphStatsResponse {
+    pub entity_count: usize,
+    pub relation_count: usize,
+    pub by_type: Vec<TypeCount>,
+}
+
+/// Entity count for one type
+#[derive(Debug, Serialize)]
+pub struct TypeCount {
+    #[serde(rename = "type")]
+    pub entity_type: String,
+    pub count: i64,
+}
+
+/// Entity list query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct EntityListQuery {
+    /// Filter by entity type
+    #[serde(rename = "type")]
+    pub entity_type: Option<String>,
+    /// Name substring
+    pub q: Option<String>,
+    /// Page size (default: 50, max: 200)
+    pub limit: Option<usize>,
+    /// Page offset (default: 0)
+    pub offset: Option<usize>,
+}
+
+/// Entity list response
+#[derive(Debug, Serialize)]
+pub struct EntityListResponse {
+    pub entities: Vec<Entity>,
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+}
+
+/// Neighbors query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct NeighborsQuery {
+    /// Hops to traverse (1 or 2, default: 1)
+    pub depth: Option<usize>,
+}
+
+/// Node in a node-link diagram
+#[derive(Debug, Serialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub entity_type: String,
+    /// Hops from the center entity (0 = center)
+    pub depth: usize,
+    /// Traversal score (1.0 for the center)
+    pub score: f64,
+}
+
+/// Link in a node-link diagram
+#[derive(Debug, Serialize)]
+pub struct GraphLink {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    #[serde(rename = "type")]
+    pub relation_type: String,
+    pub weight: f64,
+    pub evidence_count: i64,
+}
+
+/// Neighborhood of an entity as nodes and links
+#[derive(Debug, Serialize)]
+pub struct NeighborsResponse {
+    pub center: String,
+    pub depth: usize,
+    pub nodes: Vec<GraphNode>,
+    pub links: Vec<GraphLink>,
+}
+
+impl NeighborsResponse {
+    /// Build a node-link view from traversal results
+    fn from_traversal(center: Entity, depth: usize, results: Vec<GraphSearchResult>) -> Self {
+        let mut nodes = vec![GraphNode {
+            id: center.id.clone(),
+            name: center.name,
+            entity_type: center.entity_type,
+            depth: 0,
+            score: 1.0,
+        }];
+        let mut links = Vec::new();
+        let mut seen_links = HashSet::new();
+
+        for result in results {
+            for relation in result.relations {
+                if seen_links.insert(relation.id.clone()) {
+                    links.push(GraphLink {
+                        id: relation.id,
+                        source: relation.source_id,
+                        target: relation.target_id,
+                        relation_type: relation.relation_type,
+                        weight: relation.weight,
+                        evidence_count: relation.evidence_count,
+                    });
+                }
+            }
+            nodes.push(GraphNode {
+                id: result.entity.id,
+                name: result.entity.name,
+                entity_type: result.entity.entity_type,
+                depth: result.path.len().saturating_sub(1),
+                score: result.score,
+            });
+        }
+
+        Self {
+            center: center.id,
+            depth,
+            nodes,
+            links,
+        }
+    }
+}
+
+/// Error response
+#[derive(Debug, Serialize)]
+pub struct GraphErrorResponse {
+    pub error: String,
+    pub message: String,
+}
+
+fn error_response(status: StatusCode, error: &str, message: impl Into<String>) -> axum::response::Response {
+    (
+        status,
+        Json(GraphErrorResponse {
+            error: error.to_string(),
+            message: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+fn unavailable() -> axum::response::Response {
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "unavailable", "Graph store not available")
+}
+
+fn lock_error() -> axum::response::Response {
+    error_response(StatusCode::INTERNAL_SERVER_ERROR, "lock_error", "Graph store lock poisoned")
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Graph statistics
+/// GET /api/graph/stats
+pub async fn graph_stats(State(state): State<Arc<GraphApiState>>) -> impl IntoResponse {
+    let Some(ref store) = state.store else {
+        return unavailable();
+    };
+    let Ok(store) = store.lock() else {
+        return lock_error();
+    };
+
+    match store.stats() {
+        Ok(stats) => Json(GraphStatsResponse {
+            entity_count: stats.entity_count,
+            relation_count: stats.relation_count,
+            by_type: stats
+                .by_type
+                .into_iter()
+                .map(|(entity_type, count)| TypeCount { entity_type, count })
+                .collect(),
+        })
+        .into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, "query_failed", e.to_string()),
+    }
+}
+
+/// List entities
+/// GET /api/graph/entities
+pub async fn list_entities(
+    State(state): State<Arc<GraphApiState>>,
+    Query(query): Query<EntityListQuery>,
+) -> impl IntoResponse {
+    let Some(ref store) = state.store else {
+        return unavailable();
+    };
+    let limit = query.limit.unwrap_or(50).min(200);
+    let offset = query.offset.unwrap_or(0);
+    let entity_type = query.entity_type.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    let q = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+
+    let Ok(store) = store.lock() else {
+        return lock_error();
+    };
+
+    match store.list_entities(entity_type, q, limit, offset) {
+        Ok(page) => Json(EntityListResponse {
+            entities: page.entities,
+            total: page.total,
+            limit,
+            offset,
+        })
+        .into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, "query_failed", e.to_string()),
+    }
+}
+
+/// Neighborhood of an entity
+/// GET /api/graph/entity/:name/neighbors
+pub async fn entity_neighbors(
+    State(state): State<Arc<GraphApiState>>,
+    Path(name): Path<String>,
+    Query(query): Query<NeighborsQuery>,
+) -> impl IntoResponse {
+    let Some(ref store) = state.store else {
+        return unavailable();
+    };
+    // traverse() supports at most two hops
+    let depth = query.depth.unwrap_or(1).clamp(1, 2);
+
+    let Ok(store) = store.lock() else {
+        return lock_error();
+    };
+
+    let center = match store.find_entity_by_name(&name) {
+        Ok(Some(entity)) => entity,
+        Ok(None) => {
+            return error_response(StatusCode::NOT_FOUND, "not_found", format!("Entity {} not found", name))
+        }
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, "query_failed", e.to_string()),
+    };
+
+    match store.traverse(&center.id, depth) {
+        Ok(results) => Json(NeighborsResponse::from_traversal(center, depth, results)).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, "query_failed", e.to_string()),
+    }
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+/// Create knowledge graph router
+pub fn graph_router(state: Arc<GraphApiState>) -> Router {
+    Router::new()
+        .route("/stats", get(graph_stats))
+        .route("/entities", get(list_entities))
+        .route("/entity/{name}/neighbors", get(entity_neighbors))
+        .with_state(state)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn state_with_graph(name: &str) -> Arc<GraphApiState> {
+        let path = PathBuf::from(format!("/tmp/claudebot_test_graph_api_{}.db", name));
+        let _ = std::fs::remove_file(&path);
+        let store = GraphStore::open(&path).unwrap();
+
+        let rust = store.add_entity("technology", "Rust", None).unwrap();
+        let tokio = store.add_entity("technology", "Tokio", None).unwrap();
+        let bot = store.add_entity("project", "ClaudeBot", None).unwrap();
+        store.add_relation(&bot, &rust, "uses", None).unwrap();
+        store.add_relation(&tokio, &rust, "depends_on", None).unwrap();
+
+        Arc::new(GraphApiState::new(Arc::new(Mutex::new(store))))
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_stats_and_entities() {
+        let state = state_with_graph("list");
+
+        let (status, json) = get_json(graph_router(state.clone()), "/stats").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["entity_count"], 3);
+        assert_eq!(json["relation_count"], 2);
+
+        let (status, json) = get_json(graph_router(state), "/entities?type=technology&limit=1&offset=1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["entities"][0]["name"], "Tokio");
+    }
+
+    #[tokio::test]
+    async fn test_neighbors_node_link() {
+        let state = state_with_graph("neighbors");
+
+        let (status, json) = get_json(graph_router(state.clone()), "/entity/claudebot/neighbors").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 2);
+        assert_eq!(json["links"].as_array().unwrap().len(), 1);
+
+        let (_, json) = get_json(graph_router(state.clone()), "/entity/ClaudeBot/neighbors?depth=2").await;
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(json["nodes"][2]["depth"], 2);
+
+        let (status, _) = get_json(graph_router(state), "/entity/Nope/neighbors").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_without_store() {
+        let (status, json) = get_json(graph_router(Arc::new(GraphApiState::empty())), "/stats").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json["error"], "unavailable");
+    }
+}
@@ -4,8 +4,9 @@
 //! Builds on top of the SSE streaming in stream.rs.
 
 use axum::{
+    body::{Body, Bytes},
     extract::{Query, State},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::{
         sse::{Event, KeepAlive, Sse},
         IntoResponse, Response,
@@ -13,7 +14,7 @@ use axum::{
     routing::get,
     Json, Router,
 };
-use futures_util::{stream, StreamExt as FuturesStreamExt};
+use chrono::{DateTime, NaiveDate, Utc};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
@@ -21,7 +22,8 @@ use std::convert::Infallible;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
-use tokio_stream::wrappers::BroadcastStream;
+
+use super::stream::{bounded_sse_stream, SubscriberQueue, SUBSCRIBER_BUFFER};
 
 // ===== Types =====
 
@@ -200,9 +202,26 @@ impl LogEntry {
             }
         }
 
+        // Time window (from inclusive, to exclusive)
+        if filter.from.is_some() || filter.to.is_some() {
+            let Some(time) = self.time() else {
+                return false;
+            };
+            if filter.from.is_some_and(|from| time < from) || filter.to.is_some_and(|to| time >= to) {
+                return false;
+            }
+        }
+
         true
     }
 
+    /// Parsed timestamp
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|t| t.with_timezone(&Utc))
+    }
+
     /// Format as plain text log line
     pub fn to_log_line(&self) -> String {
         format!(
@@ -227,6 +246,27 @@ pub struct LogFilter {
     /// Text search within message
     #[serde(skip_serializing_if = "Option::is_none")]
     pub search: Option<String>,
+    /// Earliest timestamp to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+    /// Include entries before this timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Parse a time bound: RFC 3339, unix seconds, or a date (midnight UTC)
+pub fn parse_time_bound(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.with_timezone(&Utc));
+    }
+    if let Ok(secs) = s.parse::<i64>() {
+        return DateTime::from_timestamp(secs, 0);
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|t| t.and_utc())
 }
 
 // ===== State =====
@@ -364,6 +404,36 @@ impl LogApiState {
             .join("\n")
     }
 
+    /// Next batch of matching entries for a streamed export
+    ///
+    /// Scans at most `max_scan` entries with IDs in `(after, until]`, so the
+    /// history lock is only held briefly. Returns the matches and the last
+    /// scanned ID to resume from, or None once the range is exhausted.
+    pub fn export_batch(
+        &self,
+        filter: &LogFilter,
+        after: u64,
+        until: u64,
+        max_scan: usize,
+    ) -> (Vec<LogEntry>, Option<u64>) {
+        let history = self.history.read();
+        let start = history.partition_point(|e| e.id <= after);
+        let mut matches = Vec::new();
+        let mut last = None;
+        for entry in history.range(start..).take_while(|e| e.id <= until).take(max_scan) {
+            last = Some(entry.id);
+            if entry.matches_filter(filter) {
+                matches.push(entry.clone());
+            }
+        }
+        (matches, last.filter(|id| *id < until))
+    }
+
+    /// ID of the newest entry (0 if empty)
+    pub fn newest_id(&self) -> u64 {
+        self.history.read().back().map(|e| e.id).unwrap_or(0)
+    }
+
     /// Export all logs as JSON
     pub fn export_json(&self, filter: &LogFilter) -> Vec<LogEntry> {
         let history = self.history.read();
@@ -456,6 +526,7 @@ impl LogHistoryQuery {
                     .collect()
             }),
             search: self.search.clone(),
+            ..Default::default()
         }
     }
 }
@@ -484,6 +555,7 @@ impl LogStreamQuery {
                     .collect()
             }),
             search: self.search.clone(),
+            ..Default::default()
         }
     }
 }
@@ -492,7 +564,7 @@ impl LogStreamQuery {
 #[derive(Debug, Deserialize)]
 pub struct LogDownloadQuery {
     /// Minimum log level
-    #[serde(default)]
+    #[serde(default, alias = "min_level")]
     pub level: Option<String>,
     /// Components to include (comma-separated)
     #[serde(default)]
@@ -500,29 +572,115 @@ pub struct LogDownloadQuery {
     /// Search filter
     #[serde(default)]
     pub search: Option<String>,
-    /// Format: "text" or "json" (default: text)
-    #[serde(default = "default_format")]
-    pub format: String,
-}
-
-fn default_format() -> String {
-    "text".to_string()
+    /// Earliest entry to include (RFC 3339, unix seconds or YYYY-MM-DD)
+    #[serde(default)]
+    pub from: Option<String>,
+    /// Include entries before this time (same formats as `from`)
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Format: "text", "json" or "jsonl" (default: from `Accept`, else text)
+    #[serde(default)]
+    pub format: Option<String>,
 }
 
 impl LogDownloadQuery {
-    fn to_filter(&self) -> LogFilter {
-        LogFilter {
-            min_level: self.level.as_ref().and_then(|l| LogLevel::from_str(l)),
+    fn to_filter(&self) -> Result<LogFilter, String> {
+        let bound = |value: &Option<String>, name: &str| match value.as_deref().filter(|v| !v.trim().is_empty()) {
+            Some(v) => parse_time_bound(v)
+                .map(Some)
+                .ok_or_else(|| format!("Invalid {}: {} (use RFC 3339, unix seconds or YYYY-MM-DD)", name, v)),
+            None => Ok(None),
+        };
+        let min_level = match self.level.as_deref().filter(|l| !l.is_empty()) {
+            Some(l) => Some(LogLevel::from_str(l).ok_or_else(|| format!("Invalid level: {}", l))?),
+            None => None,
+        };
+
+        Ok(LogFilter {
+            min_level,
             components: self.components.as_ref().map(|c| {
                 c.split(',')
                     .filter_map(|s| LogComponent::from_str(s.trim()))
                     .collect()
             }),
             search: self.search.clone(),
+            from: bound(&self.from, "from")?,
+            to: bound(&self.to, "to")?,
+        })
+    }
+}
+
+/// Log download format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadFormat {
+    Text,
+    /// Single JSON array
+    Json,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl DownloadFormat {
+    /// From the `format` param, falling back to the `Accept` header
+    fn negotiate(format: Option<&str>, accept: Option<&str>) -> Option<Self> {
+        if let Some(format) = format {
+            return match format.to_lowercase().as_str() {
+                "text" | "txt" => Some(Self::Text),
+                "json" => Some(Self::Json),
+                "jsonl" | "ndjson" => Some(Self::JsonLines),
+                _ => None,
+            };
+        }
+        let accept = accept.unwrap_or_default().to_lowercase();
+        Some(if accept.contains("ndjson") || accept.contains("jsonl") {
+            Self::JsonLines
+        } else if accept.contains("application/json") {
+            Self::Json
+        } else {
+            Self::Text
+        })
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Text => "text/plain; charset=utf-8",
+            Self::Json => "application/json",
+            Self::JsonLines => "application/x-ndjson",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Json => "json",
+            Self::JsonLines => "jsonl",
+        }
+    }
+
+    /// Serialize one entry, including separators
+    fn write_entry(&self, out: &mut String, entry: &LogEntry, first: bool) {
+        match self {
+            Self::Text => {
+                out.push_str(&entry.to_log_line());
+                out.push('\n');
+            }
+            Self::Json => {
+                if !first {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(entry).unwrap_or_default());
+            }
+            Self::JsonLines => {
+                out.push_str(&serde_json::to_string(entry).unwrap_or_default());
+                out.push('\n');
+            }
         }
     }
 }
 
+/// Entries scanned per streamed chunk
+const DOWNLOAD_BATCH: usize = 500;
+
 /// Log history response
 #[derive(Debug, Serialize)]
 pub struct LogHistoryResponse {
@@ -588,39 +746,19 @@ pub async fn stream_logs(
     Query(query): Query<LogStreamQuery>,
 ) -> Response {
     let filter = query.to_filter();
-    let rx = state.subscribe();
-    let broadcast_stream = BroadcastStream::new(rx);
-
-    // Process broadcast stream: convert to SSE events with filtering
-    // Use map to transform each result, then use filter to remove None entries
-    let log_stream = broadcast_stream
-        .map(move |result| {
-            let filter = &filter;
-            match result {
-                Ok(ref entry) if entry.matches_filter(filter) => Some(Ok::<_, Infallible>(Event::default()
-                    .event("log")
-                    .data(serde_json::to_string(&entry).unwrap_or_default())
-                    .id(entry.id.to_string()))),
-                Ok(_) => None, // Filtered out
-                Err(_) => Some(Ok(Event::default()
-                    .event("warning")
-                    .data(r#"{"message":"Some logs were skipped due to buffer overflow"}"#))),
-            }
+    let queue = SubscriberQueue::subscribe(&state.log_tx, SUBSCRIBER_BUFFER);
+
+    // Bounded per-subscriber queue: slow clients drop the oldest entries and
+    // see the skipped count in the heartbeat instead of growing server memory
+    let combined = bounded_sse_stream(queue, move |entry: LogEntry| {
+        entry.matches_filter(&filter).then(|| {
+            Event::default()
+                .event("log")
+                .data(serde_json::to_string(&entry).unwrap_or_default())
+                .id(entry.id.to_string())
         })
-        .filter(|opt: &Option<Result<Event, Infallible>>| futures_util::future::ready(opt.is_some()))
-        .map(|opt: Option<Result<Event, Infallible>>| opt.unwrap());
-
-    // Add heartbeat stream
-    let heartbeat = stream::unfold((), |()| async {
-        tokio::time::sleep(Duration::from_secs(30)).await;
-        let event = Event::default()
-            .event("heartbeat")
-            .data(format!(r#"{{"timestamp":"{}"}}"#, chrono::Utc::now().to_rfc3339()));
-        Some((Ok::<_, Infallible>(event), ()))
     });
 
-    let combined = stream::select(log_stream, heartbeat);
-
     Sse::new(combined)
         .keep_alive(
             KeepAlive::new()
@@ -631,43 +769,67 @@ pub async fn stream_logs(
 }
 
 /// GET /api/logs/download - Download logs as file
+///
+/// Filters by `level`/`min_level`, `components`, `search` and a `from`/`to`
+/// time window. The body is streamed in batches, so the history is never
+/// copied in full; entries logged after the request started are excluded.
 pub async fn download_logs(
     State(state): State<Arc<LogApiState>>,
+    headers: HeaderMap,
     Query(query): Query<LogDownloadQuery>,
 ) -> Response {
-    let filter = query.to_filter();
+    let filter = match query.to_filter() {
+        Ok(filter) => filter,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let accept = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok());
+    let Some(format) = DownloadFormat::negotiate(query.format.as_deref(), accept) else {
+        return (StatusCode::BAD_REQUEST, "format must be text, json or jsonl").into_response();
+    };
     let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
 
-    match query.format.as_str() {
-        "json" => {
-            let entries = state.export_json(&filter);
-            let content = serde_json::to_string_pretty(&entries).unwrap_or_default();
-
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "application/json")
-                .header(
-                    header::CONTENT_DISPOSITION,
-                    format!("attachment; filename=\"claudebot_logs_{}.json\"", timestamp),
-                )
-                .body(content.into())
-                .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
-        }
-        _ => {
-            // Default to text
-            let content = state.export_text(&filter);
-
-            Response::builder()
-                .status(StatusCode::OK)
-                .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
-                .header(
-                    header::CONTENT_DISPOSITION,
-                    format!("attachment; filename=\"claudebot_logs_{}.txt\"", timestamp),
-                )
-                .body(content.into())
-                .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+    // (cursor, no entry written yet, finished); the JSON array opener is its
+    // own first chunk because a batch can match nothing
+    let until = state.newest_id();
+    let initial = (Some(0u64), true, false);
+    let opener = futures_util::stream::iter(
+        (format == DownloadFormat::Json).then(|| Ok::<_, Infallible>(Bytes::from_static(b"["))),
+    );
+    let entries = futures_util::stream::unfold(initial, move |(cursor, first, done)| {
+        let state = Arc::clone(&state);
+        let filter = filter.clone();
+        async move {
+            if done {
+                return None;
+            }
+            let mut chunk = String::new();
+            let Some(after) = cursor else {
+                if format == DownloadFormat::Json {
+                    chunk.push(']');
+                }
+                return Some((Ok::<_, Infallible>(Bytes::from(chunk)), (None, first, true)));
+            };
+
+            let (entries, next) = state.export_batch(&filter, after, until, DOWNLOAD_BATCH);
+            let mut first = first;
+            for entry in &entries {
+                format.write_entry(&mut chunk, entry, first);
+                first = false;
+            }
+            Some((Ok(Bytes::from(chunk)), (next, first, false)))
         }
-    }
+    });
+    let stream = futures_util::StreamExt::chain(opener, entries);
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"claudebot_logs_{}.{}\"", timestamp, format.extension()),
+        )
+        .body(Body::from_stream(stream))
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
 }
 
 /// DELETE /api/logs - Clear log buffer
@@ -1008,6 +1170,81 @@ mod tests {
             .to_str()
             .unwrap()
             .contains("application/json"));
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let entries: Vec<LogEntry> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_download_logs_json_first_batch_without_matches() {
+        let state = Arc::new(LogApiState::new(1000));
+        for i in 0..600 {
+            state.info(LogComponent::System, format!("quiet {}", i));
+        }
+        state.error(LogComponent::System, "the one error");
+
+        let response = logs_router(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/download?format=json&min_level=error")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let entries: Vec<LogEntry> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "the one error");
+    }
+
+    #[tokio::test]
+    async fn test_download_logs_range_and_jsonl() {
+        let state = Arc::new(LogApiState::new(100));
+        state.warn(LogComponent::System, "too early");
+        state.info(LogComponent::System, "too quiet");
+        state.error(LogComponent::System, "in range");
+        state.warn(LogComponent::System, "too late");
+        for (entry, time) in state.history.write().iter_mut().zip([
+            "2026-01-01T10:00:00Z",
+            "2026-01-02T10:00:00Z",
+            "2026-01-02T11:00:00Z",
+            "2026-01-03T00:00:00Z",
+        ]) {
+            entry.timestamp = time.to_string();
+        }
+
+        let get = |uri: &str, accept: Option<&str>| {
+            let mut builder = Request::builder().uri(uri);
+            if let Some(accept) = accept {
+                builder = builder.header(header::ACCEPT, accept);
+            }
+            logs_router(state.clone()).oneshot(builder.body(Body::empty()).unwrap())
+        };
+
+        let response = get("/download?from=2026-01-02&to=2026-01-03&min_level=warn", Some("application/x-ndjson"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/x-ndjson");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let lines: Vec<LogEntry> = String::from_utf8(body.to_vec())
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].message, "in range");
+
+        // Explicit format wins over Accept; unix seconds work as bounds
+        let response = get("/download?format=text&from=1767348000", Some("application/json")).await.unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(!text.contains("too early"));
+
+        let response = get("/download?from=yesterday", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
@@ -0,0 +1,327 @@
+//! Memory Browser API
+//!
+//! REST API endpoints for browsing the long-term memory store.
+//!
+//! # Endpoints
+//!
+//! - `GET /api/memory` - Recent memories, or keyword search with `?q=`
+//! - `GET /api/memory/:id` - Get a memory by ID or 8-char prefix
+//! - `GET /api/memory/:id/similar` - "More like this" (nearest by embedding)
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::memory::{MemoryEntry, MemoryStore, ScoredMemory, SearchResult};
+
+/// Memory API state
+pub struct MemoryApiState {
+    /// Shared memory store (None if unavailable)
+    pub store: Option<Arc<MemoryStore>>,
+}
+
+impl MemoryApiState {
+    /// Create with a shared memory store
+    pub fn new(store: Arc<MemoryStore>) -> Self {
+        Self { store: Some(store) }
+    }
+
+    /// Create without a memory store (all endpoints return 503)
+    pub fn empty() -> Self {
+        Self { store: None }
+    }
+
+    /// Open the store at `MEMORY_DB_PATH` if set, otherwise empty
+    pub fn with_defaults() -> Self {
+        let Ok(path) = std::env::var("MEMORY_DB_PATH") else {
+            return Self::empty();
+        };
+        match MemoryStore::open(&PathBuf::from(&path)) {
+            Ok(store) => Self::new(Arc::new(store)),
+            Err(e) => {
+                warn!("Memory browser disabled, failed to open {}: {}", path, e);
+                Self::empty()
+            }
+        }
+    }
+}
+
+impl Default for MemoryApiState {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Memory item in API responses
+#[derive(Debug, Serialize)]
+pub struct MemoryItem {
+    pub id: String,
+    /// 8-char ID prefix as shown in Telegram commands
+    pub short_id: String,
+    pub content: String,
+    pub category: String,
+    pub source: String,
+    pub confidence: f64,
+    pub created_at: i64,
+    pub access_count: i64,
+    pub has_embedding: bool,
+    /// Relevance or similarity score (search results only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+}
+
+impl From<MemoryEntry> for MemoryItem {
+    fn from(entry: MemoryEntry) -> Self {
+        Self {
+            short_id: entry.id.get(..8).unwrap_or(&entry.id).to_string(),
+            has_embedding: entry.embedding.is_some(),
+            id: entry.id,
+            content: entry.content,
+            category: entry.category,
+            source: entry.source,
+            confidence: entry.confidence,
+            created_at: entry.created_at,
+            access_count: entry.access_count,
+            score: None,
+        }
+    }
+}
+
+impl From<SearchResult> for MemoryItem {
+    fn from(result: SearchResult) -> Self {
+        Self {
+            score: Some(result.score),
+            ..Self::from(result.entry)
+        }
+    }
+}
+
+impl From<ScoredMemory> for MemoryItem {
+    fn from(scored: ScoredMemory) -> Self {
+        Self::from(SearchResult::from(scored))
+    }
+}
+
+/// Memory list query parameters
+#[derive(Debug, Default, Deserialize)]
+pub struct MemoryListQuery {
+    /// Keyword search query
+    pub q: Option<String>,
+    /// Maximum results (default: 20, max: 100)
+    pub limit: Option<usize>,
+}
+
+/// Memory list response
+#[derive(Debug, Serialize)]
+pub struct MemoryListResponse {
+    pub memories: Vec<MemoryItem>,
+    pub total: usize,
+}
+
+/// Error response
+#[derive(Debug, Serialize)]
+pub struct MemoryErrorResponse {
+    pub error: String,
+    pub message: String,
+}
+
+fn error_response(status: StatusCode, error: &str, message: impl Into<String>) -> axum::response::Response {
+    (
+        status,
+        Json(MemoryErrorResponse {
+            error: error.to_string(),
+            message: message.into(),
+        }),
+    )
+        .into_response()
+}
+
+fn unavailable() -> axum::response::Response {
+    error_response(StatusCode::SERVICE_UNAVAILABLE, "unavailable", "Memory store not available")
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List recent memories or keyword search
+/// GET /api/memory
+pub async fn list_memories(
+    State(state): State<Arc<MemoryApiState>>,
+    Query(query): Query<MemoryListQuery>,
+) -> impl IntoResponse {
+    let Some(ref store) = state.store else {
+        return unavailable();
+    };
+    let limit = query.limit.unwrap_or(20).min(100);
+
+    let result = match query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        Some(q) => store
+            .search(q, limit)
+            .map(|results| results.into_iter().map(MemoryItem::from).collect()),
+        None => store
+            .get_recent(limit)
+            .map(|entries| entries.into_iter().map(MemoryItem::from).collect::<Vec<_>>()),
+    };
+
+    match result {
+        Ok(memories) => Json(MemoryListResponse {
+            total: memories.len(),
+            memories,
+        })
+        .into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, "query_failed", e.to_string()),
+    }
+}
+
+/// Get a memory by ID or prefix
+/// GET /api/memory/:id
+pub async fn get_memory(
+    State(state): State<Arc<MemoryApiState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let Some(ref store) = state.store else {
+        return unavailable();
+    };
+
+    match store.resolve_id(&id) {
+        Ok(Some(entry)) => Json(MemoryItem::from(entry)).into_response(),
+        Ok(None) => error_response(StatusCode::NOT_FOUND, "not_found", format!("Memory {} not found", id)),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, "invalid_id", e.to_string()),
+    }
+}
+
+/// "More like this" for a memory
+/// GET /api/memory/:id/similar
+pub async fn similar_memories(
+    State(state): State<Arc<MemoryApiState>>,
+    Path(id): Path<String>,
+    Query(query): Query<MemoryListQuery>,
+) -> impl IntoResponse {
+    let Some(ref store) = state.store else {
+        return unavailable();
+    };
+    let limit = query.limit.unwrap_or(10).min(100);
+
+    match store.resolve_id(&id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return error_response(StatusCode::NOT_FOUND, "not_found", format!("Memory {} not found", id))
+        }
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, "invalid_id", e.to_string()),
+    }
+
+    match store.similar_to(&id, limit).await {
+        Ok(results) => {
+            let memories: Vec<MemoryItem> = results.into_iter().map(MemoryItem::from).collect();
+            Json(MemoryListResponse {
+                total: memories.len(),
+                memories,
+            })
+            .into_response()
+        }
+        Err(e) => error_response(StatusCode::UNPROCESSABLE_ENTITY, "no_embedding", e.to_string()),
+    }
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+/// Create memory browser router
+pub fn memory_router(state: Arc<MemoryApiState>) -> Router {
+    Router::new()
+        .route("/", get(list_memories))
+        .route("/{id}", get(get_memory))
+        .route("/{id}/similar", get(similar_memories))
+        .with_state(state)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn state_with_memories(name: &str) -> (Arc<MemoryApiState>, Vec<String>) {
+        let path = PathBuf::from(format!("/tmp/claudebot_test_memory_api_{}.db", name));
+        let _ = std::fs::remove_file(&path);
+        let store = MemoryStore::open(&path).unwrap();
+
+        let a = store.learn("Rust ownership rules", "tech", "test", None, 0.9).unwrap();
+        let b = store.learn("Rust borrow checker", "tech", "test", None, 0.9).unwrap();
+        let c = store.learn("Favorite pizza topping", "preferences", "test", None, 0.9).unwrap();
+        store.store_embedding(&a, &[1.0, 0.0]).unwrap();
+        store.store_embedding(&b, &[0.9, 0.1]).unwrap();
+        store.store_embedding(&c, &[0.0, 1.0]).unwrap();
+
+        (Arc::new(MemoryApiState::new(Arc::new(store))), vec![a, b, c])
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_without_store() {
+        let app = memory_router(Arc::new(MemoryApiState::empty()));
+        let (status, json) = get_json(app, "/").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json["error"], "unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_memory() {
+        let (state, ids) = state_with_memories("list");
+
+        let (status, json) = get_json(memory_router(state.clone()), "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["total"], 3);
+
+        let uri = format!("/{}", &ids[2][..8]);
+        let (status, json) = get_json(memory_router(state.clone()), &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["id"], ids[2]);
+        assert_eq!(json["has_embedding"], true);
+
+        let (status, _) = get_json(memory_router(state), "/ffffffff").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_similar_memories() {
+        let (state, ids) = state_with_memories("similar");
+
+        let uri = format!("/{}/similar?limit=1", &ids[0][..8]);
+        let (status, json) = get_json(memory_router(state), &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["total"], 1);
+        assert_eq!(json["memories"][0]["id"], ids[1]);
+        assert!(json["memories"][0]["score"].as_f64().unwrap() > 0.9);
+    }
+}
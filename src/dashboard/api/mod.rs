@@ -3,12 +3,15 @@
 //! REST API for dashboard functionality.
 
 pub mod config;
+pub mod graph;
 pub mod health;
 pub mod logs;
+pub mod memory;
 pub mod network;
 pub mod skills;
 pub mod status;
 pub mod stream;
+pub mod tasks;
 pub mod users;
 
 use axum::{routing::get, Router};
@@ -20,6 +23,10 @@ pub use config::{
     SchemaResponse, UpdateConfigRequest, UpdateConfigResponse, ValidateConfigRequest,
     ValidateConfigResponse, ValidationRules,
 };
+pub use graph::{
+    graph_router, EntityListQuery, EntityListResponse, GraphApiState, GraphErrorResponse,
+    GraphLink, GraphNode, GraphStatsResponse, NeighborsQuery, NeighborsResponse, TypeCount,
+};
 pub use health::health_router;
 pub use skills::{
     skills_router, InstallSkillRequest, InstallSkillResponse, SkillApiState, SkillDetailResponse,
@@ -35,16 +42,22 @@ pub use stream::{
     LogLevel, MessageEvent, MetricsEvent, StreamState,
 };
 pub use users::{
-    users_router, TelegramUser, TelegramUserRole, UpdateUserRequest, UserApiState, UserDetail,
-    UserExport, UserListItem, UserListQuery, UserListResponse, UserStats,
+    users_router, AllowedUsersRequest, AllowedUsersResponse, TelegramUser, TelegramUserRole,
+    UpdateUserRequest, UserApiState, UserDetail, UserExport, UserListItem, UserListQuery,
+    UserListResponse, UserStats,
 };
 pub use logs::{
     logs_router, LogApiState, LogComponent, LogEntry, LogFilter, LogHistoryResponse, LogStats,
     LogLevel as EnhancedLogLevel,
 };
+pub use memory::{
+    memory_router, MemoryApiState, MemoryErrorResponse, MemoryItem, MemoryListQuery,
+    MemoryListResponse,
+};
 pub use network::{
     network_router, NetworkApiState, NetworkStatus, TailscaleStatus,
 };
+pub use tasks::{tasks_router, TaskApiState, TaskItem, TaskListResponse};
 
 /// Combined dashboard API state
 #[derive(Clone)]
@@ -5,14 +5,16 @@
 //! # Endpoints
 //!
 //! - `GET /api/skills` - List all installed skills
-//! - `POST /api/skills` - Install new skill from TOML or URL
+//! - `POST /api/skills` - Install new skill from TOML, or from a signed URL
+//!   with its `sha256`
 //! - `GET /api/skills/:name` - Get skill details
 //! - `PATCH /api/skills/:name` - Enable/disable skill
 //! - `DELETE /api/skills/:name` - Uninstall skill
 //! - `GET /api/skills/stats` - Get skill statistics
 
 use crate::skills::{
-    GeneratedSkill, InstalledSkill, SkillDefinition, SkillRegistry, SkillSource, SkillStats,
+    GeneratedSkill, InstalledSkill, SkillDefinition, SkillLoader, SkillRegistry, SkillSource,
+    SkillStats, TrustedKeys,
 };
 use axum::{
     extract::{Path, Query, State},
@@ -30,20 +32,37 @@ use tracing::{info, warn};
 pub struct SkillApiState {
     /// Skill registry
     pub registry: Arc<RwLock<SkillRegistry>>,
+    /// Keys whose signatures URL installs must carry
+    pub trusted_keys: TrustedKeys,
 }
 
 impl SkillApiState {
-    /// Create new skill API state with registry
+    /// Create new skill API state with registry, trusting `SKILLS_TRUSTED_KEYS`
     pub fn new(registry: Arc<RwLock<SkillRegistry>>) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            trusted_keys: trusted_keys_from_env(),
+        }
     }
 
     /// Create with default registry location
     pub fn with_defaults() -> Self {
-        Self {
-            registry: Arc::new(RwLock::new(SkillRegistry::default_location())),
-        }
+        Self::new(Arc::new(RwLock::new(SkillRegistry::default_location())))
     }
+
+    /// Replace the keys URL installs are verified against
+    pub fn with_trusted_keys(mut self, trusted_keys: TrustedKeys) -> Self {
+        self.trusted_keys = trusted_keys;
+        self
+    }
+}
+
+/// Invalid keys trust nothing, which leaves URL installs disabled
+fn trusted_keys_from_env() -> TrustedKeys {
+    TrustedKeys::from_env().unwrap_or_else(|e| {
+        warn!("Ignoring SKILLS_TRUSTED_KEYS: {}", e);
+        TrustedKeys::default()
+    })
 }
 
 /// Skill list item (summary for listing)
@@ -74,6 +93,7 @@ impl From<&InstalledSkill> for SkillListItem {
                 SkillSource::Generated => "generated".to_string(),
                 SkillSource::Imported(_) => "imported".to_string(),
                 SkillSource::Hub(url) => format!("hub:{}", url),
+                SkillSource::Verified { url, .. } => format!("verified:{}", url),
                 SkillSource::Builtin => "builtin".to_string(),
             },
             tags: skill.definition.skill.tags.clone(),
@@ -115,6 +135,7 @@ impl From<&InstalledSkill> for SkillDetailResponse {
                 SkillSource::Generated => "generated".to_string(),
                 SkillSource::Imported(path) => format!("imported:{}", path.display()),
                 SkillSource::Hub(url) => format!("hub:{}", url),
+                SkillSource::Verified { url, .. } => format!("verified:{}", url),
                 SkillSource::Builtin => "builtin".to_string(),
             },
             tags: skill.definition.skill.tags.clone(),
@@ -140,6 +161,9 @@ pub struct InstallSkillRequest {
     pub install_type: String,
     /// TOML content or URL
     pub content: String,
+    /// Expected SHA256 of the file at `content`; required for "url"
+    #[serde(default)]
+    pub sha256: Option<String>,
 }
 
 /// Install skill response
@@ -258,7 +282,10 @@ pub async fn install_skill(
 ) -> impl IntoResponse {
     let result = match req.install_type.as_str() {
         "toml" => install_from_toml(&state, &req.content).await,
-        "url" => install_from_url(&state, &req.content).await,
+        "url" => match req.sha256.as_deref() {
+            Some(sha256) => install_from_url(&state, &req.content, sha256).await,
+            None => Err(anyhow::anyhow!("URL installs need the file's sha256")),
+        },
         _ => Err(anyhow::anyhow!("Invalid install type: must be 'toml' or 'url'")),
     };
 
@@ -329,41 +356,24 @@ async fn install_from_toml(
     Ok(skill)
 }
 
-/// Install skill from URL
-async fn install_from_url(state: &SkillApiState, url: &str) -> anyhow::Result<InstalledSkill> {
-    // Validate URL - basic check without url crate
-    if !url.starts_with("https://") {
-        return Err(anyhow::anyhow!("Only HTTPS URLs are allowed"));
-    }
-
-    // Simple URL validation
-    if url.len() < 12 || url.contains(' ') {
-        return Err(anyhow::anyhow!("Invalid URL format"));
-    }
-
-    // Fetch content
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .timeout(std::time::Duration::from_secs(30))
-        .send()
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to fetch URL: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Failed to fetch URL: HTTP {}",
-            response.status()
-        ));
-    }
+/// Install skill from URL after checking its checksum and signature
+async fn install_from_url(
+    state: &SkillApiState,
+    url: &str,
+    sha256: &str,
+) -> anyhow::Result<InstalledSkill> {
+    let (definition, provenance) = SkillLoader::new()
+        .load_verified(url, sha256, &state.trusted_keys)
+        .await?;
 
-    let content = response
-        .text()
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to read response: {}", e))?;
+    let registry = state.registry.write().await;
+    let name = registry.install_verified(definition, url, &provenance).await?;
+    let skill = registry.get(&name).await.ok_or_else(|| {
+        anyhow::anyhow!("Failed to retrieve installed skill")
+    })?;
 
-    // Install from fetched TOML
-    install_from_toml(state, &content).await
+    info!("Installed skill '{}' from {} (signed by {})", name, url, provenance.signed_by);
+    Ok(skill)
 }
 
 /// Get skill details
@@ -623,4 +633,34 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn test_install_url_requires_verification() {
+        let state = Arc::new(SkillApiState::with_defaults().with_trusted_keys(TrustedKeys::default()));
+
+        for body in [
+            r#"{"type": "url", "content": "https://example.com/skill.toml"}"#,
+            r#"{"type": "url", "content": "https://example.com/skill.toml", "sha256": "00"}"#,
+        ] {
+            let response = skills_router(Arc::clone(&state))
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/")
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let error = json["error"].as_str().unwrap();
+            assert!(error.contains("sha256") || error.contains("SKILLS_TRUSTED_KEYS"), "{}", error);
+        }
+    }
 }
@@ -12,7 +12,12 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Instant;
 
-use crate::metrics::{AggregateMetrics, CostBreakdown, LatencyStats, MetricsCollector};
+use crate::cache::{CacheStats, ResponseCache};
+use crate::metrics::{AggregateMetrics, CostBreakdown, FailureStats, LatencyStats, MetricsCollector};
+use crate::storage::StorageReport;
+
+/// Produces a fresh storage report for `/status`
+pub type StorageSource = Arc<dyn Fn() -> StorageReport + Send + Sync>;
 
 /// Shared application state for status/metrics endpoints
 #[derive(Clone)]
@@ -23,6 +28,10 @@ pub struct StatusState {
     pub version: &'static str,
     /// Metrics collector
     pub metrics: Option<Arc<MetricsCollector>>,
+    /// Response cache (stats reported in /metrics)
+    pub cache: Option<ResponseCache>,
+    /// Database/index sizes (reported in /status)
+    pub storage: Option<StorageSource>,
     /// Bot status
     pub bot_status: BotStatus,
 }
@@ -54,6 +63,8 @@ impl StatusState {
             start_time: Instant::now(),
             version: env!("CARGO_PKG_VERSION"),
             metrics: None,
+            cache: None,
+            storage: None,
             bot_status: BotStatus::Running,
         }
     }
@@ -64,10 +75,24 @@ impl StatusState {
             start_time: Instant::now(),
             version: env!("CARGO_PKG_VERSION"),
             metrics: Some(metrics),
+            cache: None,
+            storage: None,
             bot_status: BotStatus::Running,
         }
     }
 
+    /// Report stats for a response cache (clones share the same entries)
+    pub fn with_cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Report database and vector index sizes in /status
+    pub fn with_storage(mut self, storage: StorageSource) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
     /// Get uptime in seconds
     pub fn uptime_secs(&self) -> u64 {
         self.start_time.elapsed().as_secs()
@@ -99,6 +124,9 @@ pub struct StatusResponse {
     pub last_message_at: Option<String>,
     /// Current timestamp (ISO 8601)
     pub timestamp: String,
+    /// Database file sizes and row counts (if a storage source is attached)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<StorageReport>,
 }
 
 /// Usage metrics response
@@ -118,10 +146,15 @@ pub struct MetricsResponse {
     pub avg_response_ms: u64,
     /// Latency percentiles
     pub latency: LatencyStats,
-    /// Cost breakdown
+    /// Cost breakdown (including per-origin cost)
     pub cost: CostBreakdown,
     /// Per-model breakdown
     pub by_model: AggregateMetrics,
+    /// Failed calls by reason over the last hour and day
+    pub failures: FailureStats,
+    /// Response cache stats (if a cache is attached)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_cache: Option<CacheStats>,
 }
 
 /// Conversation summary for list
@@ -209,6 +242,7 @@ pub async fn status_handler(State(state): State<Arc<StatusState>>) -> Json<Statu
         api_status,
         last_message_at,
         timestamp: chrono::Utc::now().to_rfc3339(),
+        storage: state.storage.as_ref().map(|source| source()),
     })
 }
 
@@ -251,6 +285,8 @@ pub async fn metrics_handler(
         latency,
         cost,
         by_model: all,
+        failures: metrics.failure_stats(),
+        response_cache: state.cache.as_ref().map(|c| c.stats()),
     }))
 }
 
@@ -403,6 +439,21 @@ mod tests {
         assert_eq!(response.api_status, ApiStatus::Degraded);
     }
 
+    #[tokio::test]
+    async fn test_status_handler_with_storage() {
+        let source: StorageSource = Arc::new(|| StorageReport::new(Vec::new(), 2048));
+        let state = Arc::new(StatusState::new().with_storage(source));
+        let response = status_handler(State(state)).await;
+
+        let storage = response.storage.as_ref().unwrap();
+        assert_eq!(storage.total_bytes, 2048);
+        let json = serde_json::to_value(&*response).unwrap();
+        assert_eq!(json["storage"]["vector_index_bytes"], 2048);
+
+        let plain = status_handler(State(Arc::new(StatusState::new()))).await;
+        assert!(serde_json::to_value(&*plain).unwrap().get("storage").is_none());
+    }
+
     #[tokio::test]
     async fn test_status_handler_with_metrics() {
         let metrics = Arc::new(MetricsCollector::new(100));
@@ -438,12 +489,41 @@ mod tests {
             Some("test"),
         );
 
+        metrics.record_with_origin(
+            Some("circle"),
+            "opus",
+            2000,
+            1000,
+            0,
+            Duration::from_millis(3000),
+            false,
+            None,
+        );
+        metrics.record_failure(crate::metrics::FailureReason::NonZeroExit);
+
         let state = Arc::new(StatusState::with_metrics(metrics));
         let result = metrics_handler(State(state)).await;
 
         assert!(result.is_ok());
         let response = result.unwrap();
         assert!(response.cache_hit_rate > 0.0);
+        assert!(response.cost.by_origin["circle"] > 0.0);
+        assert_eq!(response.by_model.by_origin["circle"].requests, 1);
+        assert_eq!(response.failures.last_hour.by_reason["non_zero_exit"], 1);
+        assert_eq!(response.failures.last_hour.calls, 3);
+        assert!(response.response_cache.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_handler_with_cache() {
+        let cache = ResponseCache::new(10, 60, true);
+        assert!(cache.get("0123456789abcdef-miss").await.is_none());
+
+        let state = Arc::new(StatusState::with_metrics(Arc::new(MetricsCollector::new(10))).with_cache(cache));
+        let response = metrics_handler(State(state)).await.unwrap();
+        let stats = response.response_cache.as_ref().unwrap();
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.entries, 0);
     }
 
     #[tokio::test]
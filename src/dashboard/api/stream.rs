@@ -6,6 +6,10 @@
 //! - Native browser EventSource API with auto-reconnect
 //! - Works through proxies without configuration
 //! - Simpler to implement and maintain
+//!
+//! Broadcast-backed streams give each subscriber its own bounded queue with a
+//! drop-oldest policy, so a slow tab cannot grow server memory. Heartbeats
+//! report how many events were skipped for that subscriber.
 
 use axum::{
     extract::State,
@@ -16,12 +20,13 @@ use axum::{
 };
 use futures_util::stream::{self, Stream};
 use serde::Serialize;
+use std::collections::VecDeque;
 use std::convert::Infallible;
 use std::pin::Pin;
-use std::sync::Arc;
-use std::time::Duration;
-use tokio_stream::wrappers::BroadcastStream;
-use tokio_stream::StreamExt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Notify};
 
 use crate::metrics::MetricsCollector;
 
@@ -187,20 +192,209 @@ pub struct HeartbeatEvent {
     pub timestamp: String,
     /// Uptime in seconds
     pub uptime_secs: u64,
+    /// Events skipped for this subscriber since it connected
+    #[serde(rename = "X-Dropped")]
+    pub dropped: u64,
+}
+
+impl HeartbeatEvent {
+    /// Create a heartbeat for a stream started at `start_time`
+    pub fn new(start_time: Instant, dropped: u64) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            uptime_secs: start_time.elapsed().as_secs(),
+            dropped,
+        }
+    }
+
+    /// Convert to an SSE event
+    pub fn to_event(&self) -> Event {
+        Event::default()
+            .event("heartbeat")
+            .data(serde_json::to_string(self).unwrap_or_default())
+    }
 }
 
 /// Type alias for boxed SSE stream
-type BoxedSseStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+pub(crate) type BoxedSseStream = Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>;
+
+/// Events buffered per SSE subscriber before the oldest are dropped
+pub const SUBSCRIBER_BUFFER: usize = 256;
+
+/// Interval between heartbeat events on broadcast-backed streams
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+// ===== Per-Subscriber Queue =====
+
+/// Bounded per-subscriber queue with drop-oldest overflow
+pub struct SubscriberQueue<T> {
+    inner: Arc<QueueInner<T>>,
+}
+
+struct QueueInner<T> {
+    buf: parking_lot::Mutex<VecDeque<T>>,
+    capacity: usize,
+    dropped: AtomicU64,
+    closed: AtomicBool,
+    notify: Notify,
+}
+
+impl<T> QueueInner<T> {
+    fn push(&self, item: T) {
+        {
+            let mut buf = self.buf.lock();
+            if buf.len() >= self.capacity {
+                buf.pop_front();
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            buf.push_back(item);
+        }
+        self.notify.notify_one();
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.notify.notify_one();
+    }
+}
+
+impl<T: Clone + Send + 'static> SubscriberQueue<T> {
+    /// Create an empty queue holding at most `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(QueueInner {
+                buf: parking_lot::Mutex::new(VecDeque::with_capacity(capacity.min(64))),
+                capacity: capacity.max(1),
+                dropped: AtomicU64::new(0),
+                closed: AtomicBool::new(false),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// Subscribe to a broadcast channel, forwarding into a bounded queue
+    ///
+    /// The forwarding task exits when the channel closes or the queue is dropped.
+    pub fn subscribe(tx: &broadcast::Sender<T>, capacity: usize) -> Self {
+        let queue = Self::new(capacity);
+        let weak: Weak<QueueInner<T>> = Arc::downgrade(&queue.inner);
+        let mut rx = tx.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                let result = rx.recv().await;
+                let Some(inner) = weak.upgrade() else { break };
+                match result {
+                    Ok(item) => inner.push(item),
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        inner.dropped.fetch_add(n, Ordering::Relaxed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        inner.close();
+                        break;
+                    }
+                }
+            }
+        });
+
+        queue
+    }
+
+    /// Add an event, dropping the oldest if the queue is full
+    pub fn push(&self, item: T) {
+        self.inner.push(item);
+    }
+
+    /// Take the next event, or None once the source has closed and the queue is empty
+    pub async fn pop(&self) -> Option<T> {
+        loop {
+            let notified = self.inner.notify.notified();
+            if let Some(item) = self.inner.buf.lock().pop_front() {
+                return Some(item);
+            }
+            if self.inner.closed.load(Ordering::Relaxed) {
+                return None;
+            }
+            notified.await;
+        }
+    }
+
+    /// Events currently buffered
+    pub fn len(&self) -> usize {
+        self.inner.buf.lock().len()
+    }
+
+    /// True if nothing is buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Total events skipped for this subscriber
+    pub fn dropped(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Build an SSE stream from a bounded subscriber queue
+///
+/// `to_event` converts queued items to SSE events; returning None skips the item.
+/// A heartbeat carrying the subscriber's drop count is interleaved every 30s.
+pub(crate) fn bounded_sse_stream<T, F>(queue: SubscriberQueue<T>, to_event: F) -> BoxedSseStream
+where
+    T: Clone + Send + 'static,
+    F: Fn(T) -> Option<Event> + Send + Sync + 'static,
+{
+    let start_time = Instant::now();
+    let to_event = Arc::new(to_event);
+    let queue = Arc::new(queue);
+    let mut heartbeat = tokio::time::interval_at(
+        tokio::time::Instant::now() + HEARTBEAT_INTERVAL,
+        HEARTBEAT_INTERVAL,
+    );
+    heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let s = stream::unfold((queue, heartbeat), move |(queue, mut heartbeat)| {
+        let to_event = Arc::clone(&to_event);
+        async move {
+            loop {
+                tokio::select! {
+                    item = queue.pop() => {
+                        let item = item?;
+                        if let Some(event) = to_event(item) {
+                            return Some((Ok::<_, Infallible>(event), (queue, heartbeat)));
+                        }
+                    }
+                    _ = heartbeat.tick() => {
+                        let event = HeartbeatEvent::new(start_time, queue.dropped()).to_event();
+                        return Some((Ok(event), (queue, heartbeat)));
+                    }
+                }
+            }
+        }
+    });
+    Box::pin(s)
+}
+
+/// Heartbeat-only stream used when no broadcast channel is configured
+fn heartbeat_stream() -> BoxedSseStream {
+    let start_time = Instant::now();
+    let s = stream::unfold((), move |()| async move {
+        tokio::time::sleep(HEARTBEAT_INTERVAL).await;
+        Some((Ok::<_, Infallible>(HeartbeatEvent::new(start_time, 0).to_event()), ()))
+    });
+    Box::pin(s)
+}
 
 // ===== SSE Handlers =====
 
 /// GET /api/stream/metrics - Live metrics (1 event/second)
 ///
 /// Streams metrics events every second with system statistics.
-/// Includes heartbeat to prevent connection timeout.
+/// Includes heartbeat to prevent connection timeout. Events are produced
+/// only when the client polls, so a slow consumer never builds a backlog.
 pub async fn stream_metrics(State(state): State<Arc<StreamState>>) -> Response {
     let metrics = state.metrics.clone();
-    let start_time = std::time::Instant::now();
+    let start_time = Instant::now();
 
     // Create a stream that emits every second
     let stream = stream::unfold(0u64, move |counter| {
@@ -232,14 +426,7 @@ pub async fn stream_metrics(State(state): State<Arc<StreamState>>) -> Response {
                     .id(counter.to_string())
             } else {
                 // No metrics - send heartbeat
-                let heartbeat = HeartbeatEvent {
-                    timestamp: chrono::Utc::now().to_rfc3339(),
-                    uptime_secs: start_time.elapsed().as_secs(),
-                };
-
-                Event::default()
-                    .event("heartbeat")
-                    .data(serde_json::to_string(&heartbeat).unwrap_or_default())
+                HeartbeatEvent::new(start_time, 0).to_event()
             };
 
             Some((Ok::<_, Infallible>(event), counter + 1))
@@ -261,41 +448,17 @@ pub async fn stream_metrics(State(state): State<Arc<StreamState>>) -> Response {
 /// Streams message events as they are received.
 /// Falls back to heartbeat if no message channel is configured.
 pub async fn stream_messages(State(state): State<Arc<StreamState>>) -> Response {
-    let start_time = std::time::Instant::now();
-
     let stream: BoxedSseStream = if let Some(ref tx) = state.message_tx {
-        // Subscribe to the broadcast channel using BroadcastStream wrapper
-        let rx = tx.subscribe();
-        let broadcast_stream = BroadcastStream::new(rx);
-
-        let s = broadcast_stream.map(|result| {
-            Ok(match result {
-                Ok(msg) => Event::default()
+        let queue = SubscriberQueue::subscribe(tx, SUBSCRIBER_BUFFER);
+        bounded_sse_stream(queue, |msg: MessageEvent| {
+            Some(
+                Event::default()
                     .event("message")
                     .data(serde_json::to_string(&msg).unwrap_or_default()),
-                Err(_) => Event::default()
-                    .event("warning")
-                    .data(r#"{"message":"Some messages were skipped"}"#),
-            })
-        });
-        Box::pin(s)
+            )
+        })
     } else {
-        // No message channel - send periodic heartbeat
-        let s = stream::unfold(0u64, move |counter| async move {
-            tokio::time::sleep(Duration::from_secs(30)).await;
-
-            let heartbeat = HeartbeatEvent {
-                timestamp: chrono::Utc::now().to_rfc3339(),
-                uptime_secs: start_time.elapsed().as_secs(),
-            };
-
-            let event = Event::default()
-                .event("heartbeat")
-                .data(serde_json::to_string(&heartbeat).unwrap_or_default());
-
-            Some((Ok::<_, Infallible>(event), counter + 1))
-        });
-        Box::pin(s)
+        heartbeat_stream()
     };
 
     Sse::new(stream)
@@ -312,41 +475,17 @@ pub async fn stream_messages(State(state): State<Arc<StreamState>>) -> Response
 /// Streams log events as they are emitted.
 /// Falls back to heartbeat if no log channel is configured.
 pub async fn stream_logs(State(state): State<Arc<StreamState>>) -> Response {
-    let start_time = std::time::Instant::now();
-
     let stream: BoxedSseStream = if let Some(ref tx) = state.log_tx {
-        // Subscribe to the broadcast channel using BroadcastStream wrapper
-        let rx = tx.subscribe();
-        let broadcast_stream = BroadcastStream::new(rx);
-
-        let s = broadcast_stream.map(|result| {
-            Ok(match result {
-                Ok(log) => Event::default()
+        let queue = SubscriberQueue::subscribe(tx, SUBSCRIBER_BUFFER);
+        bounded_sse_stream(queue, |log: LogEvent| {
+            Some(
+                Event::default()
                     .event("log")
                     .data(serde_json::to_string(&log).unwrap_or_default()),
-                Err(_) => Event::default()
-                    .event("warning")
-                    .data(r#"{"message":"Some logs were skipped"}"#),
-            })
-        });
-        Box::pin(s)
+            )
+        })
     } else {
-        // No log channel - send periodic heartbeat
-        let s = stream::unfold(0u64, move |counter| async move {
-            tokio::time::sleep(Duration::from_secs(30)).await;
-
-            let heartbeat = HeartbeatEvent {
-                timestamp: chrono::Utc::now().to_rfc3339(),
-                uptime_secs: start_time.elapsed().as_secs(),
-            };
-
-            let event = Event::default()
-                .event("heartbeat")
-                .data(serde_json::to_string(&heartbeat).unwrap_or_default());
-
-            Some((Ok::<_, Infallible>(event), counter + 1))
-        });
-        Box::pin(s)
+        heartbeat_stream()
     };
 
     Sse::new(stream)
@@ -509,6 +648,48 @@ mod tests {
         assert_eq!(response.status(), axum::http::StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_subscriber_queue_drops_oldest() {
+        let queue = SubscriberQueue::new(3);
+        for i in 0..5 {
+            queue.push(i);
+        }
+
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.dropped(), 2);
+        assert_eq!(queue.pop().await, Some(2));
+        assert_eq!(queue.pop().await, Some(3));
+        assert_eq!(queue.pop().await, Some(4));
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscriber_queue_forwards_broadcast() {
+        let (tx, _) = broadcast::channel::<LogEvent>(16);
+        let queue = SubscriberQueue::subscribe(&tx, 2);
+
+        for i in 0..4 {
+            tx.send(LogEvent::info(format!("log {}", i))).unwrap();
+        }
+        drop(tx);
+
+        let mut received = Vec::new();
+        while let Some(log) = queue.pop().await {
+            received.push(log.message);
+        }
+
+        // Slow consumer: only the newest events survive, the rest are counted
+        assert_eq!(received, vec!["log 2", "log 3"]);
+        assert_eq!(queue.dropped(), 2);
+    }
+
+    #[test]
+    fn test_heartbeat_reports_drops() {
+        let heartbeat = HeartbeatEvent::new(Instant::now(), 7);
+        let json = serde_json::to_string(&heartbeat).unwrap();
+        assert!(json.contains("\"X-Dropped\":7"));
+    }
+
     #[tokio::test]
     async fn test_stream_messages_without_channel() {
         let state = Arc::new(StreamState::new());
@@ -0,0 +1,157 @@
+//! Live Task API
+//!
+//! Claude invocations currently running, from the shared `TaskRegistry`:
+//! - GET /api/tasks - Snapshot with elapsed time per task
+//! - GET /api/tasks/stream - SSE: `snapshot` on connect, then `task` events
+//!   as invocations start and finish
+
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::stream::{bounded_sse_stream, BoxedSseStream, SubscriberQueue, SUBSCRIBER_BUFFER};
+use crate::tasks::{RunningTask, TaskEvent, TaskRegistry};
+
+// ===== Types =====
+
+/// A running task with its elapsed time
+#[derive(Debug, Serialize)]
+pub struct TaskItem {
+    #[serde(flatten)]
+    pub task: RunningTask,
+    pub elapsed_secs: i64,
+}
+
+/// GET /api/tasks response
+#[derive(Debug, Serialize)]
+pub struct TaskListResponse {
+    pub tasks: Vec<TaskItem>,
+    pub total: usize,
+}
+
+/// Task API state
+pub struct TaskApiState {
+    pub registry: Arc<TaskRegistry>,
+}
+
+impl TaskApiState {
+    pub fn new(registry: Arc<TaskRegistry>) -> Self {
+        Self { registry }
+    }
+
+    /// Create with an empty registry of its own
+    pub fn with_defaults() -> Self {
+        Self::new(Arc::new(TaskRegistry::new()))
+    }
+
+    fn snapshot(&self) -> TaskListResponse {
+        let now = Utc::now();
+        let tasks: Vec<TaskItem> = self
+            .registry
+            .list()
+            .into_iter()
+            .map(|task| TaskItem {
+                elapsed_secs: task.elapsed_secs(now),
+                task,
+            })
+            .collect();
+        TaskListResponse {
+            total: tasks.len(),
+            tasks,
+        }
+    }
+}
+
+impl Default for TaskApiState {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+// ===== Handlers =====
+
+/// GET /api/tasks - Currently running invocations, oldest first
+async fn list_tasks(State(state): State<Arc<TaskApiState>>) -> Json<TaskListResponse> {
+    Json(state.snapshot())
+}
+
+/// GET /api/tasks/stream - Snapshot followed by start/finish events
+async fn stream_tasks(State(state): State<Arc<TaskApiState>>) -> Response {
+    // Subscribe before taking the snapshot so no start/finish is missed in between
+    let queue = SubscriberQueue::subscribe(state.registry.events(), SUBSCRIBER_BUFFER);
+    let snapshot = Event::default()
+        .event("snapshot")
+        .data(serde_json::to_string(&state.snapshot()).unwrap_or_default());
+
+    let updates = bounded_sse_stream(queue, |event: TaskEvent| {
+        Some(
+            Event::default()
+                .event("task")
+                .data(serde_json::to_string(&event).unwrap_or_default()),
+        )
+    });
+    let stream: BoxedSseStream = Box::pin(stream::once(async move { Ok::<_, Infallible>(snapshot) }).chain(updates));
+
+    Sse::new(stream)
+        .keep_alive(
+            KeepAlive::new()
+                .interval(Duration::from_secs(30))
+                .text("heartbeat"),
+        )
+        .into_response()
+}
+
+// ===== Router =====
+
+/// Create the task API router
+pub fn tasks_router(state: Arc<TaskApiState>) -> Router {
+    Router::new()
+        .route("/", get(list_tasks))
+        .route("/stream", get(stream_tasks))
+        .with_state(state)
+}
+
+// ===== Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskKind;
+    use axum::body::{to_bytes, Body};
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_list_tasks() {
+        let registry = Arc::new(TaskRegistry::new());
+        let _running = registry.start(TaskKind::Circle, 42, 7, "review the parser");
+        let app = tasks_router(Arc::new(TaskApiState::new(registry.clone())));
+
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["total"], 1);
+        assert_eq!(json["tasks"][0]["kind"], "circle");
+        assert_eq!(json["tasks"][0]["user_id"], 42);
+        assert_eq!(json["tasks"][0]["description"], "review the parser");
+        assert!(json["tasks"][0]["elapsed_secs"].is_number());
+        assert!(json["tasks"][0]["started_at"].is_string());
+    }
+}
@@ -19,6 +19,12 @@
 //! - `DELETE /api/users/:id` - Delete user and all data
 //! - `GET /api/users/:id/conversations` - Get user's conversation history
 //! - `GET /api/users/:id/export` - Export all user data (GDPR)
+//! - `GET /api/users/allowed` - Current Telegram allowlist
+//! - `POST /api/users/allowed` - Replace or edit the allowlist (applies immediately)
+//!
+//! The allowlist endpoints only reach the bot when the server is built with
+//! the bot's list (`DashboardServer::with_allowed_users`), as the bot does
+//! when `DASHBOARD_ENABLED` is set; edits are then saved like /allow and /deny.
 
 use axum::{
     extract::{Path, Query, State},
@@ -27,6 +33,8 @@ use axum::{
     routing::get,
     Json, Router,
 };
+
+use crate::permissions::AllowedUsers;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -126,6 +134,8 @@ pub struct UserApiState {
     conversations: RwLock<HashMap<i64, Vec<ConversationItem>>>,
     /// Data directory for exports
     data_dir: PathBuf,
+    /// Telegram allowlist (shared with the bot when wired up)
+    allowed_users: AllowedUsers,
 }
 
 impl UserApiState {
@@ -136,9 +146,16 @@ impl UserApiState {
             blocked_users: RwLock::new(HashSet::new()),
             conversations: RwLock::new(HashMap::new()),
             data_dir,
+            allowed_users: AllowedUsers::default(),
         }
     }
 
+    /// Use the bot's allowlist so changes here take effect in the bot
+    pub fn with_allowed_users(mut self, allowed_users: AllowedUsers) -> Self {
+        self.allowed_users = allowed_users;
+        self
+    }
+
     /// Create with default paths
     pub fn with_defaults() -> Self {
         let data_dir = dirs::data_local_dir()
@@ -390,6 +407,34 @@ pub struct UpdateUserRequest {
     pub notes: Option<String>,
 }
 
+/// Allowlist update: `users` replaces the list, then `allow`/`deny` are applied
+#[derive(Debug, Deserialize)]
+pub struct AllowedUsersRequest {
+    pub users: Option<Vec<i64>>,
+    #[serde(default)]
+    pub allow: Vec<i64>,
+    #[serde(default)]
+    pub deny: Vec<i64>,
+}
+
+/// Allowlist response
+#[derive(Debug, Serialize)]
+pub struct AllowedUsersResponse {
+    pub users: Vec<i64>,
+    /// True when the list is empty, which lets everyone in
+    pub everyone_allowed: bool,
+}
+
+impl AllowedUsersResponse {
+    fn from_state(allowed_users: &AllowedUsers) -> Self {
+        let users = allowed_users.list();
+        Self {
+            everyone_allowed: users.is_empty(),
+            users,
+        }
+    }
+}
+
 /// Conversations response
 #[derive(Debug, Serialize)]
 pub struct ConversationsResponse {
@@ -681,6 +726,43 @@ pub async fn export_user_data(
     }
 }
 
+/// Current allowlist
+/// GET /api/users/allowed
+pub async fn get_allowed_users(State(state): State<Arc<UserApiState>>) -> impl IntoResponse {
+    Json(AllowedUsersResponse::from_state(&state.allowed_users))
+}
+
+/// Update the allowlist without a restart
+/// POST /api/users/allowed
+pub async fn update_allowed_users(
+    State(state): State<Arc<UserApiState>>,
+    Json(req): Json<AllowedUsersRequest>,
+) -> impl IntoResponse {
+    // Apply to a copy first so a request that would empty the list changes nothing
+    let mut users = req.users.unwrap_or_else(|| state.allowed_users.list());
+    users.extend(req.allow);
+    users.retain(|id| !req.deny.contains(id));
+    let would_allow_everyone = users.is_empty() && !state.allowed_users.is_open();
+    if let Err(e) = state.allowed_users.replace(users) {
+        let (status, error) = if would_allow_everyone {
+            (StatusCode::BAD_REQUEST, "would_allow_everyone")
+        } else {
+            (StatusCode::INTERNAL_SERVER_ERROR, "save_failed")
+        };
+        return (
+            status,
+            Json(UserErrorResponse {
+                error: error.to_string(),
+                message: e.to_string(),
+            }),
+        )
+            .into_response();
+    }
+    let response = AllowedUsersResponse::from_state(&state.allowed_users);
+    info!("Updated allowed users: {:?}", response.users);
+    Json(response).into_response()
+}
+
 // ============================================================================
 // Router
 // ============================================================================
@@ -689,6 +771,7 @@ pub async fn export_user_data(
 pub fn users_router(state: Arc<UserApiState>) -> Router {
     Router::new()
         .route("/", get(list_users))
+        .route("/allowed", get(get_allowed_users).post(update_allowed_users))
         .route("/{id}", get(get_user).patch(update_user).delete(delete_user))
         .route("/{id}/conversations", get(get_conversations))
         .route("/{id}/export", get(export_user_data))
@@ -830,6 +913,54 @@ mod tests {
         assert!(state.is_blocked(12345).await);
     }
 
+    #[tokio::test]
+    async fn test_update_allowed_users() {
+        let allowed = AllowedUsers::new(vec![1]);
+        let state = Arc::new(UserApiState::with_defaults().with_allowed_users(allowed.clone()));
+        let app = users_router(state);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/allowed")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"allow": [12345, 678], "deny": [1]}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["users"], serde_json::json!([678, 12345]));
+        assert_eq!(json["everyone_allowed"], false);
+        // The bot's handle sees the change immediately
+        assert!(allowed.is_allowed(12345) && !allowed.is_allowed(1));
+
+        // Emptying the list would let everyone in
+        for body in [r#"{"users": []}"#, r#"{"deny": [678, 12345]}"#] {
+            let response = users_router(Arc::new(UserApiState::with_defaults().with_allowed_users(allowed.clone())))
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/allowed")
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(allowed.list(), vec![678, 12345]);
+    }
+
     #[tokio::test]
     async fn test_delete_user() {
         let state = state_with_user().await;
@@ -22,8 +22,15 @@
 //! │  GET /api/logs         → Log history    │
 //! │  GET /api/logs/stream  → Log tail (SSE) │
 //! │  GET /api/logs/download→ Export logs    │
+//! │  GET /api/memory       → Memory browser │
+//! │  GET /api/memory/:id/similar → Similar  │
+//! │  GET /api/graph/stats  → Graph counts   │
+//! │  GET /api/graph/entities → Entity list  │
+//! │  GET /api/graph/entity/:name/neighbors  │
 //! │  GET /api/network      → Network status │
 //! │  GET /api/network/tailscale → Tailscale │
+//! │  GET /api/tasks        → Running tasks  │
+//! │  GET /api/tasks/stream → Tasks (SSE)    │
 //! │  POST /api/auth/login  → Authenticate   │
 //! │  POST /api/auth/logout → End session    │
 //! │  POST /api/auth/refresh→ Refresh token  │
@@ -37,15 +44,15 @@ pub mod config;
 pub mod server;
 
 pub use api::{
-    api_router, config_router, health_router, logs_router, network_router, skills_router,
-    stream_router, users_router, ApiStatus, BotStatus, ConfigApiState, ConfigFieldResponse,
+    api_router, config_router, graph_router, health_router, logs_router, memory_router, network_router,
+    skills_router, stream_router, tasks_router, users_router, ApiStatus, BotStatus, ConfigApiState, ConfigFieldResponse,
     ConfigFieldSchema, ConfigResponse, ConfigSource, DashboardApiState, EnhancedLogLevel,
-    ErrorResponse, FieldSensitivity, FieldType, HeartbeatEvent, InstallSkillRequest,
+    ErrorResponse, FieldSensitivity, FieldType, GraphApiState, GraphLink, GraphNode, HeartbeatEvent, InstallSkillRequest,
     InstallSkillResponse, LogApiState, LogComponent, LogEntry, LogEvent, LogFilter,
-    LogHistoryResponse, LogLevel, LogStats, MessageEvent, MetricsEvent, MetricsResponse,
+    LogHistoryResponse, LogLevel, LogStats, MemoryApiState, MemoryItem, MessageEvent, MetricsEvent, MetricsResponse,
     NetworkApiState, NetworkStatus, ReloadBehavior, SchemaResponse, SkillApiState,
     SkillDetailResponse, SkillListItem, SkillListResponse, StatusResponse, StatusState,
-    StreamState, TailscaleStatus, TelegramUser, TelegramUserRole, UpdateConfigRequest,
+    StreamState, TailscaleStatus, TaskApiState, TaskItem, TaskListResponse, TelegramUser, TelegramUserRole, UpdateConfigRequest,
     UpdateConfigResponse, UpdateSkillRequest, UpdateUserRequest, UserApiState, UserDetail,
     UserExport, UserListItem, UserListResponse, UserStats, ValidateConfigRequest,
     ValidateConfigResponse,
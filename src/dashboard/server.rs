@@ -3,10 +3,12 @@
 //! Axum-based server with embedded static files, CORS, authentication, and graceful shutdown.
 
 use crate::dashboard::api::{
-    config_router, health::AppState, health_router, logs_router, network_router, skills_router,
-    users_router, ConfigApiState, LogApiState, NetworkApiState, SkillApiState, UserApiState,
+    config_router, conversations_handler, graph_router, health::AppState, health_router,
+    logs_router, memory_router, metrics_handler, network_router, skills_router, status_handler,
+    tasks_router, users_router, ConfigApiState, GraphApiState, LogApiState, MemoryApiState,
+    NetworkApiState, SkillApiState, StatusState, TaskApiState, UserApiState,
 };
-use crate::dashboard::auth::{auth_router, AuthConfig, AuthState};
+use crate::dashboard::auth::{auth_middleware, auth_router, AuthConfig, AuthState};
 use crate::dashboard::config::DashboardConfig;
 use axum::{
     body::Body,
@@ -37,6 +39,10 @@ pub struct DashboardServer {
     user_state: Arc<UserApiState>,
     log_state: Arc<LogApiState>,
     network_state: Arc<NetworkApiState>,
+    memory_state: Arc<MemoryApiState>,
+    graph_state: Arc<GraphApiState>,
+    task_state: Arc<TaskApiState>,
+    status_state: Arc<StatusState>,
 }
 
 impl DashboardServer {
@@ -64,6 +70,10 @@ impl DashboardServer {
             user_state: Arc::new(UserApiState::with_defaults()),
             log_state: Arc::new(LogApiState::with_defaults()),
             network_state,
+            memory_state: Arc::new(MemoryApiState::with_defaults()),
+            graph_state: Arc::new(GraphApiState::with_defaults()),
+            task_state: Arc::new(TaskApiState::with_defaults()),
+            status_state: Arc::new(StatusState::new()),
         }
     }
 
@@ -88,6 +98,10 @@ impl DashboardServer {
             user_state: Arc::new(UserApiState::with_defaults()),
             log_state: Arc::new(LogApiState::with_defaults()),
             network_state,
+            memory_state: Arc::new(MemoryApiState::with_defaults()),
+            graph_state: Arc::new(GraphApiState::with_defaults()),
+            task_state: Arc::new(TaskApiState::with_defaults()),
+            status_state: Arc::new(StatusState::new()),
         }
     }
 
@@ -116,6 +130,10 @@ impl DashboardServer {
             user_state: Arc::new(UserApiState::with_defaults()),
             log_state: Arc::new(LogApiState::with_defaults()),
             network_state,
+            memory_state: Arc::new(MemoryApiState::with_defaults()),
+            graph_state: Arc::new(GraphApiState::with_defaults()),
+            task_state: Arc::new(TaskApiState::with_defaults()),
+            status_state: Arc::new(StatusState::new()),
         }
     }
 
@@ -149,6 +167,39 @@ impl DashboardServer {
         &self.network_state
     }
 
+    /// Use a shared memory store for the memory browser
+    pub fn with_memory_store(mut self, store: Arc<crate::memory::MemoryStore>) -> Self {
+        self.memory_state = Arc::new(MemoryApiState::new(store));
+        self
+    }
+
+    /// Use a shared graph store for the knowledge graph API
+    pub fn with_graph_store(mut self, store: Arc<std::sync::Mutex<crate::graph::GraphStore>>) -> Self {
+        self.graph_state = Arc::new(GraphApiState::new(store));
+        self
+    }
+
+    /// Use the bot's task registry for the live task view
+    pub fn with_task_registry(mut self, registry: Arc<crate::tasks::TaskRegistry>) -> Self {
+        self.task_state = Arc::new(TaskApiState::new(registry));
+        self
+    }
+
+    /// Report the bot's metrics and cache in `/api/status` and `/api/metrics`
+    pub fn with_status(mut self, status: StatusState) -> Self {
+        self.status_state = Arc::new(status);
+        self
+    }
+
+    /// Share the bot's allowlist so `POST /api/users/allowed` applies live
+    ///
+    /// The bot passes its list when it starts the dashboard; without this the
+    /// endpoint edits the dashboard's own list, which the bot never reads.
+    pub fn with_allowed_users(mut self, allowed_users: crate::permissions::AllowedUsers) -> Self {
+        self.user_state = Arc::new(UserApiState::with_defaults().with_allowed_users(allowed_users));
+        self
+    }
+
     /// Build the router with all routes and middleware
     fn build_router(&self) -> Router {
         // CORS configuration - localhost only for security
@@ -178,6 +229,14 @@ impl DashboardServer {
             .route("/{*path}", get(static_handler))
             // Health API (nested)
             .nest("/api", health_router(self.state.clone()))
+            // Status and metrics API
+            .merge(
+                Router::new()
+                    .route("/api/status", get(status_handler))
+                    .route("/api/metrics", get(metrics_handler))
+                    .route("/api/conversations", get(conversations_handler))
+                    .with_state(self.status_state.clone()),
+            )
             // Authentication API
             .nest("/api/auth", auth_router(self.auth_state.clone()))
             // Skills API
@@ -188,8 +247,34 @@ impl DashboardServer {
             .nest("/api/logs", logs_router(self.log_state.clone()))
             // Network API
             .nest("/api/network", network_router(self.network_state.clone()))
-            // Users API
-            .nest("/api/users", users_router(self.user_state.clone()))
+            // Users API (requires auth when enabled; it can edit the allowlist)
+            .nest(
+                "/api/users",
+                users_router(self.user_state.clone()).route_layer(
+                    axum::middleware::from_fn_with_state(self.auth_state.clone(), auth_middleware),
+                ),
+            )
+            // Memory browser API (requires auth when enabled; it shows every user's memories)
+            .nest(
+                "/api/memory",
+                memory_router(self.memory_state.clone()).route_layer(
+                    axum::middleware::from_fn_with_state(self.auth_state.clone(), auth_middleware),
+                ),
+            )
+            // Live task view (requires auth when enabled; it shows per-user tasks)
+            .nest(
+                "/api/tasks",
+                tasks_router(self.task_state.clone()).route_layer(
+                    axum::middleware::from_fn_with_state(self.auth_state.clone(), auth_middleware),
+                ),
+            )
+            // Knowledge graph API (requires auth when enabled)
+            .nest(
+                "/api/graph",
+                graph_router(self.graph_state.clone()).route_layer(
+                    axum::middleware::from_fn_with_state(self.auth_state.clone(), auth_middleware),
+                ),
+            )
             // Middleware
             .layer(cors);
 
@@ -390,6 +475,92 @@ mod tests {
     use axum::http::Request;
     use tower::ServiceExt;
 
+    #[tokio::test]
+    async fn test_graph_api_requires_auth() {
+        let auth_state = Arc::new(AuthState::new(AuthConfig {
+            enabled: true,
+            jwt_secret: "test-secret-at-least-32-characters-long".to_string(),
+            ..AuthConfig::default()
+        }));
+        let server = DashboardServer::with_auth(DashboardConfig::default(), auth_state);
+        let app = server.build_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/graph/stats")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_memory_api_requires_auth() {
+        let auth_state = Arc::new(AuthState::new(AuthConfig {
+            enabled: true,
+            jwt_secret: "test-secret-at-least-32-characters-long".to_string(),
+            ..AuthConfig::default()
+        }));
+        let server = DashboardServer::with_auth(DashboardConfig::default(), auth_state);
+
+        for uri in ["/api/memory", "/api/memory/abcd1234", "/api/memory/abcd1234/similar"] {
+            let response = server
+                .build_router()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tasks_api_requires_auth() {
+        let auth_state = Arc::new(AuthState::new(AuthConfig {
+            enabled: true,
+            jwt_secret: "test-secret-at-least-32-characters-long".to_string(),
+            ..AuthConfig::default()
+        }));
+        let server = DashboardServer::with_auth(DashboardConfig::default(), auth_state);
+
+        for uri in ["/api/tasks", "/api/tasks/stream"] {
+            let response = server
+                .build_router()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_uses_shared_collector() {
+        let metrics = Arc::new(crate::metrics::MetricsCollector::new(10));
+        metrics.record_failure(crate::metrics::FailureReason::Timeout);
+        let server = DashboardServer::with_defaults().with_status(StatusState::with_metrics(metrics));
+        let app = server.build_router();
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(json["failures"]["last_hour"]["by_reason"]["timeout"], 1);
+    }
+
     #[tokio::test]
     async fn test_health_endpoint() {
         let server = DashboardServer::with_defaults();
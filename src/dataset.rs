@@ -0,0 +1,284 @@
+//! Fine-tuning Dataset Sink
+//!
+//! Opt-in JSONL log of Claude exchanges for training a local model.
+//! Enabled by `CLAUDEBOT_DATASET_PATH`; chats are left out with
+//! `CLAUDEBOT_DATASET_EXCLUDE_CHATS` or `/dataset off`. Opt-outs are saved
+//! next to the dataset so they survive restarts, and `/dataset on` can't
+//! re-include a chat the env excludes.
+//!
+//! Each line is one record, tagged by `kind`:
+//! - `example` - prompt, response, model, token counts, reflection score
+//! - `feedback` - a later thumbs up/down, joined to its example on `id`
+//!
+//! Callers redact text before recording; records carry no user or chat IDs.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+
+/// Dataset sink configuration
+#[derive(Debug, Clone, Default)]
+pub struct DatasetConfig {
+    /// JSONL file to append to (None = disabled)
+    pub path: Option<PathBuf>,
+    /// Chats never recorded
+    pub excluded_chats: HashSet<i64>,
+    /// Where `/dataset off` opt-outs are saved (None = memory only)
+    pub opt_out_path: Option<PathBuf>,
+}
+
+impl DatasetConfig {
+    /// `CLAUDEBOT_DATASET_PATH`, `CLAUDEBOT_DATASET_EXCLUDE_CHATS`
+    /// (comma-separated chat IDs) and `CLAUDEBOT_DATASET_OPT_OUT_PATH`
+    /// (default: `<dataset>.optout.json`)
+    pub fn from_env() -> Self {
+        let path = std::env::var("CLAUDEBOT_DATASET_PATH")
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .map(PathBuf::from);
+        let opt_out_path = std::env::var("CLAUDEBOT_DATASET_OPT_OUT_PATH")
+            .map(PathBuf::from)
+            .ok()
+            .or_else(|| path.as_ref().map(|p| p.with_extension("optout.json")));
+        Self {
+            path,
+            excluded_chats: std::env::var("CLAUDEBOT_DATASET_EXCLUDE_CHATS")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect(),
+            opt_out_path,
+        }
+    }
+}
+
+/// One prompt/response pair
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetExample {
+    /// Links later feedback to this example
+    pub id: String,
+    pub prompt: String,
+    /// Memory/goal context added to the prompt, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    pub response: String,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    /// Reflection quality score (0.0-1.0), if the response was evaluated
+    pub quality: Option<f64>,
+    pub timestamp: i64,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum DatasetRecord<'a> {
+    Example(&'a DatasetExample),
+    Feedback { id: &'a str, positive: bool, timestamp: i64 },
+}
+
+/// Appends dataset records to a JSONL file
+pub struct DatasetSink {
+    path: Option<PathBuf>,
+    /// Excluded by config; `/dataset on` can't re-include these
+    config_excluded: HashSet<i64>,
+    /// Excluded with `/dataset off`
+    opted_out: RwLock<HashSet<i64>>,
+    opt_out_path: Option<PathBuf>,
+    /// Serializes appends so concurrent records don't interleave
+    write_lock: Mutex<()>,
+    written: AtomicU64,
+}
+
+impl DatasetSink {
+    pub fn new(config: DatasetConfig) -> Self {
+        let opted_out: HashSet<i64> = config
+            .opt_out_path
+            .as_deref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Self {
+            path: config.path,
+            config_excluded: config.excluded_chats,
+            opted_out: RwLock::new(opted_out),
+            opt_out_path: config.opt_out_path,
+            write_lock: Mutex::new(()),
+            written: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(DatasetConfig::from_env())
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// True if the sink is on and the chat isn't excluded
+    pub fn accepts(&self, chat_id: i64) -> bool {
+        self.path.is_some() && !self.is_excluded(chat_id)
+    }
+
+    pub fn is_excluded(&self, chat_id: i64) -> bool {
+        self.config_excluded.contains(&chat_id)
+            || self.opted_out.read().unwrap_or_else(|e| e.into_inner()).contains(&chat_id)
+    }
+
+    /// True if `CLAUDEBOT_DATASET_EXCLUDE_CHATS` lists the chat
+    pub fn is_excluded_by_config(&self, chat_id: i64) -> bool {
+        self.config_excluded.contains(&chat_id)
+    }
+
+    /// Opt a chat out of or back into the dataset
+    ///
+    /// The choice is saved to the opt-out file. Chats excluded by config
+    /// stay excluded either way.
+    pub fn set_excluded(&self, chat_id: i64, excluded: bool) -> Result<()> {
+        let mut chats = self.opted_out.write().unwrap_or_else(|e| e.into_inner());
+        let changed = if excluded {
+            chats.insert(chat_id)
+        } else {
+            chats.remove(&chat_id)
+        };
+        if let (true, Some(path)) = (changed, &self.opt_out_path) {
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, serde_json::to_vec(&*chats)?)
+                .with_context(|| format!("Failed to save dataset opt-outs to {:?}", path))?;
+            std::fs::rename(&tmp, path)?;
+        }
+        Ok(())
+    }
+
+    /// Records appended since startup
+    pub fn records_written(&self) -> u64 {
+        self.written.load(Ordering::Relaxed)
+    }
+
+    /// Append an example (no-op if the chat isn't accepted)
+    pub fn record_example(&self, chat_id: i64, example: &DatasetExample) -> Result<()> {
+        if !self.accepts(chat_id) {
+            return Ok(());
+        }
+        self.append(&DatasetRecord::Example(example))
+    }
+
+    /// Append thumbs feedback for an earlier example
+    pub fn record_feedback(&self, chat_id: i64, id: &str, positive: bool) -> Result<()> {
+        if !self.accepts(chat_id) {
+            return Ok(());
+        }
+        self.append(&DatasetRecord::Feedback {
+            id,
+            positive,
+            timestamp: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    fn append(&self, record: &DatasetRecord) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| format!("Failed to append to {}", path.display()))?;
+        self.written.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(id: &str) -> DatasetExample {
+        DatasetExample {
+            id: id.to_string(),
+            prompt: "What is 2+2?".to_string(),
+            context: None,
+            response: "4".to_string(),
+            model: "claude-sonnet".to_string(),
+            input_tokens: 10,
+            output_tokens: 1,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            quality: Some(0.9),
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_dataset_sink_writes_jsonl() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data/dataset.jsonl");
+        let sink = DatasetSink::new(DatasetConfig {
+            path: Some(path.clone()),
+            excluded_chats: [7].into_iter().collect(),
+            opt_out_path: None,
+        });
+
+        sink.record_example(1, &example("a")).unwrap();
+        sink.record_feedback(1, "a", true).unwrap();
+        sink.record_example(7, &example("b")).unwrap();
+        sink.set_excluded(1, true).unwrap();
+        sink.record_example(1, &example("c")).unwrap();
+        assert_eq!(sink.records_written(), 2);
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["kind"], "example");
+        assert_eq!(lines[0]["model"], "claude-sonnet");
+        assert_eq!(lines[0]["quality"], 0.9);
+        assert!(lines[0].get("context").is_none());
+        assert_eq!(lines[1]["kind"], "feedback");
+        assert_eq!(lines[1]["id"], "a");
+        assert_eq!(lines[1]["positive"], true);
+
+        let disabled = DatasetSink::new(DatasetConfig::default());
+        assert!(!disabled.accepts(1));
+        disabled.record_example(1, &example("d")).unwrap();
+        assert_eq!(disabled.records_written(), 0);
+    }
+
+    #[test]
+    fn test_opt_out_persists_and_config_exclusion_sticks() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = DatasetConfig {
+            path: Some(dir.path().join("dataset.jsonl")),
+            excluded_chats: [7].into_iter().collect(),
+            opt_out_path: Some(dir.path().join("dataset.optout.json")),
+        };
+
+        let sink = DatasetSink::new(config.clone());
+        sink.set_excluded(1, true).unwrap();
+        sink.set_excluded(7, false).unwrap();
+        assert!(sink.is_excluded(7));
+
+        let reopened = DatasetSink::new(config);
+        assert!(reopened.is_excluded(1));
+        assert!(!reopened.is_excluded(2));
+        reopened.set_excluded(1, false).unwrap();
+        assert!(!reopened.is_excluded(1));
+    }
+}
@@ -4,13 +4,20 @@
 //! Falls back to keyword-based FTS5 search if Ollama is unavailable.
 //!
 //! Supports hybrid retrieval: combines FTS5 keyword scores with vector similarity.
-//! Includes LRU caching for query embeddings to reduce latency.
+//! Includes LRU caching for query embeddings to reduce latency. The cache can
+//! be backed by a SQLite file (keyed by a hash of model and text) so it
+//! survives restarts; entries keep their original age, so the TTL still holds.
 
 use anyhow::{Context, Result};
 use moka::future::Cache;
+use moka::Expiry;
+use rusqlite::{params, Connection};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
 /// Embedding store configuration
@@ -26,6 +33,10 @@ pub struct EmbeddingConfig {
     pub timeout: Duration,
     /// Reranker model (optional, for cross-encoder reranking)
     pub reranker_model: Option<String>,
+    /// Query embeddings kept in the cache
+    pub cache_capacity: u64,
+    /// How long a cached embedding is reused, counted from when it was computed
+    pub cache_ttl: Duration,
 }
 
 /// Get embedding dimension for known models
@@ -54,17 +65,44 @@ impl Default for EmbeddingConfig {
             dimension,
             timeout: Duration::from_secs(30),
             reranker_model: std::env::var("RERANKER_MODEL").ok(), // e.g., "bge-reranker-base"
+            cache_capacity: std::env::var("CLAUDEBOT_EMBEDDING_CACHE_SIZE")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(1000),
+            cache_ttl: std::env::var("CLAUDEBOT_EMBEDDING_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(Duration::from_secs(3600)),
         }
     }
 }
 
+/// Cached embedding with the time it has left
+#[derive(Debug, Clone)]
+struct CachedEmbedding {
+    embedding: Vec<f32>,
+    ttl: Duration,
+}
+
+/// Expires each entry after its own remaining TTL (loaded entries are older)
+struct CacheExpiry;
+
+impl Expiry<String, CachedEmbedding> for CacheExpiry {
+    fn expire_after_create(&self, _key: &String, value: &CachedEmbedding, _created_at: Instant) -> Option<Duration> {
+        Some(value.ttl)
+    }
+}
+
 /// Embedding generator and similarity search
 pub struct EmbeddingStore {
     config: EmbeddingConfig,
     client: reqwest::Client,
     available: std::sync::atomic::AtomicBool,
-    /// LRU cache for query embeddings (max 1000 entries, 1 hour TTL)
-    cache: Cache<String, Vec<f32>>,
+    /// LRU cache for query embeddings (`cache_capacity` entries, `cache_ttl`)
+    cache: Cache<String, CachedEmbedding>,
+    /// On-disk copy of the cache, once `load_disk_cache` has run
+    disk_cache: Option<Mutex<Connection>>,
     /// Cache statistics
     cache_hits: AtomicU64,
     cache_misses: AtomicU64,
@@ -84,10 +122,9 @@ impl EmbeddingStore {
             .build()
             .expect("Failed to create HTTP client");
 
-        // LRU cache: 1000 entries, 1 hour TTL
         let cache = Cache::builder()
-            .max_capacity(1000)
-            .time_to_live(Duration::from_secs(3600))
+            .max_capacity(config.cache_capacity)
+            .expire_after(CacheExpiry)
             .build();
 
         Self {
@@ -95,6 +132,7 @@ impl EmbeddingStore {
             client,
             available: std::sync::atomic::AtomicBool::new(true),
             cache,
+            disk_cache: None,
             cache_hits: AtomicU64::new(0),
             cache_misses: AtomicU64::new(0),
         }
@@ -113,6 +151,89 @@ impl EmbeddingStore {
         )
     }
 
+    /// One-line description of the query cache for stats
+    pub fn cache_summary(&self) -> String {
+        let (hits, misses) = self.cache_stats();
+        format!(
+            "{} of {} entries, {}s TTL, {}; {} hits / {} misses",
+            self.cache.entry_count(),
+            self.config.cache_capacity,
+            self.config.cache_ttl.as_secs(),
+            if self.disk_cache.is_some() { "persisted" } else { "in memory" },
+            hits,
+            misses
+        )
+    }
+
+    /// Back the query cache with the SQLite file at `path`, loading the
+    /// entries that are still within the TTL. Returns how many were loaded.
+    pub async fn load_disk_cache(&mut self, path: &Path) -> Result<usize> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open embedding cache {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS embedding_cache (
+                key TEXT PRIMARY KEY,
+                embedding BLOB NOT NULL,
+                created_at INTEGER NOT NULL
+            );",
+        )?;
+
+        let now = chrono::Utc::now().timestamp();
+        let ttl = self.config.cache_ttl.as_secs() as i64;
+        conn.execute("DELETE FROM embedding_cache WHERE created_at <= ?1", params![now - ttl])?;
+        let rows: Vec<(String, Vec<u8>, i64)> = {
+            let mut stmt = conn.prepare(
+                "SELECT key, embedding, created_at FROM embedding_cache ORDER BY created_at DESC, rowid DESC LIMIT ?1",
+            )?;
+            let rows = stmt.query_map(params![self.config.cache_capacity as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let loaded = rows.len();
+        for (key, bytes, created_at) in rows {
+            let ttl = Duration::from_secs((created_at + ttl - now).max(1) as u64);
+            let embedding = embedding_from_bytes(&bytes);
+            self.cache.insert(key, CachedEmbedding { embedding, ttl }).await;
+        }
+        self.disk_cache = Some(Mutex::new(conn));
+        Ok(loaded)
+    }
+
+    /// Cache key: the text alone would return another model's vectors
+    /// after `EMBEDDING_MODEL` changes
+    fn cache_key(&self, text: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.config.model.as_bytes());
+        hasher.update([0]);
+        hasher.update(text.trim().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    async fn store_cached(&self, key: String, embedding: Vec<f32>) {
+        if let Some(db) = &self.disk_cache {
+            let conn = db.lock().unwrap_or_else(|e| e.into_inner());
+            let saved = conn
+                .execute(
+                    "INSERT OR REPLACE INTO embedding_cache (key, embedding, created_at) VALUES (?1, ?2, ?3)",
+                    params![key, embedding_to_bytes(&embedding), chrono::Utc::now().timestamp()],
+                )
+                .and_then(|_| {
+                    conn.execute(
+                        "DELETE FROM embedding_cache WHERE key NOT IN
+                            (SELECT key FROM embedding_cache ORDER BY created_at DESC, rowid DESC LIMIT ?1)",
+                        params![self.config.cache_capacity as i64],
+                    )
+                });
+            if let Err(e) = saved {
+                warn!("Failed to persist cached embedding: {}", e);
+            }
+        }
+        let ttl = self.config.cache_ttl;
+        self.cache.insert(key, CachedEmbedding { embedding, ttl }).await;
+    }
+
     /// Check if Ollama is available
     pub async fn check_availability(&self) -> bool {
         match self.client
@@ -144,13 +265,12 @@ impl EmbeddingStore {
             anyhow::bail!("Embedding service unavailable");
         }
 
-        // Normalize text for cache key (trim, lowercase for queries)
-        let cache_key = text.trim().to_string();
+        let cache_key = self.cache_key(text);
 
         // Check cache first
         if let Some(cached) = self.cache.get(&cache_key).await {
             self.cache_hits.fetch_add(1, Ordering::Relaxed);
-            return Ok(cached);
+            return Ok(cached.embedding);
         }
         self.cache_misses.fetch_add(1, Ordering::Relaxed);
 
@@ -158,7 +278,7 @@ impl EmbeddingStore {
         let embedding = self.embed_uncached(text).await?;
 
         // Store in cache
-        self.cache.insert(cache_key, embedding.clone()).await;
+        self.store_cached(cache_key, embedding.clone()).await;
 
         Ok(embedding)
     }
@@ -511,4 +631,32 @@ mod tests {
             assert!((a - b).abs() < 0.0001);
         }
     }
+
+    #[tokio::test]
+    async fn test_disk_cache_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("embedding_cache.db");
+        let config = EmbeddingConfig { cache_capacity: 2, ..EmbeddingConfig::default() };
+
+        let mut store = EmbeddingStore::new(config.clone());
+        assert_eq!(store.load_disk_cache(&path).await.unwrap(), 0);
+        for text in ["first", "second", "third"] {
+            store.store_cached(store.cache_key(text), vec![1.0, 2.0]).await;
+        }
+
+        // Restart: only the newest `cache_capacity` entries come back
+        let mut restarted = EmbeddingStore::new(config.clone());
+        assert_eq!(restarted.load_disk_cache(&path).await.unwrap(), 2);
+        let key = restarted.cache_key("  third ");
+        assert_eq!(restarted.cache.get(&key).await.unwrap().embedding, vec![1.0, 2.0]);
+
+        // Entries past the TTL are dropped on load
+        Connection::open(&path)
+            .unwrap()
+            .execute("UPDATE embedding_cache SET created_at = created_at - 7200", [])
+            .unwrap();
+        let mut expired = EmbeddingStore::new(config);
+        assert_eq!(expired.load_disk_cache(&path).await.unwrap(), 0);
+        assert!(expired.cache_summary().contains("persisted"));
+    }
 }
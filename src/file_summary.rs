@@ -0,0 +1,265 @@
+//! Large File Summaries
+//!
+//! `/summarize_file` handles files too big for a single Claude call with a
+//! map-reduce:
+//! - map: split the file into token-sized chunks and summarize each one
+//! - reduce: merge the chunk summaries in token-sized groups, round after
+//!   round, until a single summary is left
+//!
+//! Each step is a cheap call (Llama when it's available, Haiku otherwise),
+//! so the chunk size depends on which summarizer runs.
+
+use anyhow::{Context, Result};
+use std::future::Future;
+
+use crate::input_limits;
+use crate::tokenizer::TokenCounter;
+
+/// Rough characters per token, for cutting lines too long to count piecewise
+const CHARS_PER_TOKEN: usize = 3;
+
+/// Chunk sizes and limits for `/summarize_file`
+#[derive(Debug, Clone)]
+pub struct FileSummaryConfig {
+    /// Tokens per chunk when Haiku summarizes
+    pub chunk_tokens: usize,
+    /// Tokens per chunk when Llama summarizes (smaller context window)
+    pub llama_chunk_tokens: usize,
+    /// Larger files are refused
+    pub max_file_bytes: u64,
+}
+
+impl Default for FileSummaryConfig {
+    fn default() -> Self {
+        Self {
+            chunk_tokens: 20_000,
+            llama_chunk_tokens: 4_000,
+            max_file_bytes: 20 * 1024 * 1024,
+        }
+    }
+}
+
+impl FileSummaryConfig {
+    /// `CLAUDEBOT_SUMMARIZE_CHUNK_TOKENS`, `CLAUDEBOT_SUMMARIZE_LLAMA_CHUNK_TOKENS`,
+    /// `CLAUDEBOT_SUMMARIZE_MAX_BYTES`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|n| *n > 0)
+        };
+        if let Some(n) = var("CLAUDEBOT_SUMMARIZE_CHUNK_TOKENS") {
+            config.chunk_tokens = n as usize;
+        }
+        if let Some(n) = var("CLAUDEBOT_SUMMARIZE_LLAMA_CHUNK_TOKENS") {
+            config.llama_chunk_tokens = n as usize;
+        }
+        if let Some(n) = var("CLAUDEBOT_SUMMARIZE_MAX_BYTES") {
+            config.max_file_bytes = n;
+        }
+        config
+    }
+
+    pub fn chunk_tokens_for(&self, llama: bool) -> usize {
+        if llama {
+            self.llama_chunk_tokens
+        } else {
+            self.chunk_tokens
+        }
+    }
+}
+
+/// Split on line boundaries into chunks of at most `max_tokens`; a single
+/// line longer than that is cut by characters
+pub fn split_by_tokens<'a>(text: &'a str, counter: &TokenCounter, max_tokens: usize) -> Vec<&'a str> {
+    let max_tokens = max_tokens.max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut pos = 0;
+    let mut tokens = 0;
+    for line in text.split_inclusive('\n') {
+        let line_tokens = counter.count(line);
+        if tokens + line_tokens > max_tokens && pos > start {
+            chunks.push(&text[start..pos]);
+            start = pos;
+            tokens = 0;
+        }
+        if line_tokens > max_tokens {
+            // Minified code, a CSV without newlines, ...
+            chunks.extend(input_limits::split_chunks(line, max_tokens * CHARS_PER_TOKEN));
+            start = pos + line.len();
+        } else {
+            tokens += line_tokens;
+        }
+        pos += line.len();
+    }
+    if pos > start {
+        chunks.push(&text[start..pos]);
+    }
+    chunks.retain(|c| !c.trim().is_empty());
+    chunks
+}
+
+/// Consecutive groups that fit in `max_tokens` together. Groups take at
+/// least two items when there are two left, so every reduce round shrinks.
+fn group_by_tokens<'a>(items: &'a [String], counter: &TokenCounter, max_tokens: usize) -> Vec<&'a [String]> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+    let mut tokens = 0;
+    for (i, item) in items.iter().enumerate() {
+        let item_tokens = counter.count(item);
+        if i - start >= 2 && tokens + item_tokens > max_tokens {
+            groups.push(&items[start..i]);
+            start = i;
+            tokens = 0;
+        }
+        tokens += item_tokens;
+    }
+    if start < items.len() {
+        groups.push(&items[start..]);
+    }
+    groups
+}
+
+/// Prompt for summarizing one chunk
+pub fn map_prompt(file_name: &str, part: usize, total: usize, chunk: &str) -> String {
+    format!(
+        "This is part {part}/{total} of the file `{file_name}`. Summarize what this part contains: \
+        its purpose, the main definitions/sections, key facts, numbers and names, and anything \
+        that looks wrong or notable. Be concise; the summaries of all parts will be merged.\n\n\
+        --- Part {part}/{total} ---\n{chunk}"
+    )
+}
+
+/// Prompt for merging consecutive chunk summaries
+pub fn reduce_prompt(file_name: &str, summaries: &[String], final_round: bool) -> String {
+    let sections: Vec<String> = summaries
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("--- Summary {} ---\n{}", i + 1, s.trim()))
+        .collect();
+    let goal = if final_round {
+        "Write the final summary of the whole file: what it is, how it's organized, and the \
+        most important contents. Use short sections or bullets."
+    } else {
+        "Merge them into one concise summary of this stretch of the file, keeping key facts, \
+        names and anything notable."
+    };
+    format!(
+        "Below are summaries of consecutive parts of the file `{}`, in order. {}\n\n{}",
+        file_name,
+        goal,
+        sections.join("\n\n")
+    )
+}
+
+/// Result of summarizing a file
+#[derive(Debug, Clone)]
+pub struct MapReduce {
+    pub summary: String,
+    pub chunks: usize,
+    /// Summarizer calls across map and reduce
+    pub calls: usize,
+    pub reduce_rounds: usize,
+    pub cost_usd: f64,
+}
+
+/// Summarize `chunks` and reduce the summaries to one. `summarize` takes a
+/// prompt and returns the text and its cost.
+pub async fn map_reduce<F, Fut>(
+    file_name: &str,
+    chunks: &[&str],
+    counter: &TokenCounter,
+    max_tokens: usize,
+    mut summarize: F,
+) -> Result<MapReduce>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<(String, f64)>>,
+{
+    if chunks.is_empty() {
+        anyhow::bail!("{} is empty", file_name);
+    }
+    let mut calls = 0;
+    let mut cost_usd = 0.0;
+
+    let mut summaries = Vec::with_capacity(chunks.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        let (summary, cost) = summarize(map_prompt(file_name, i + 1, chunks.len(), chunk))
+            .await
+            .with_context(|| format!("Summarizing part {}/{} failed", i + 1, chunks.len()))?;
+        calls += 1;
+        cost_usd += cost;
+        summaries.push(summary);
+    }
+
+    let mut reduce_rounds = 0;
+    while summaries.len() > 1 {
+        reduce_rounds += 1;
+        let groups = group_by_tokens(&summaries, counter, max_tokens);
+        let final_round = groups.len() == 1;
+        let mut merged = Vec::with_capacity(groups.len());
+        for group in groups {
+            if group.len() == 1 {
+                merged.push(group[0].clone());
+                continue;
+            }
+            let (summary, cost) = summarize(reduce_prompt(file_name, group, final_round))
+                .await
+                .with_context(|| format!("Merging summaries failed (round {})", reduce_rounds))?;
+            calls += 1;
+            cost_usd += cost;
+            merged.push(summary);
+        }
+        summaries = merged;
+    }
+
+    Ok(MapReduce {
+        summary: summaries.pop().unwrap_or_default(),
+        chunks: chunks.len(),
+        calls,
+        reduce_rounds,
+        cost_usd,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_split_and_map_reduce() {
+        let counter = TokenCounter::heuristic();
+        let text: String = (0..200).map(|i| format!("fn item_{}() {{ compute({}); }}\n", i, i)).collect();
+        let chunks = split_by_tokens(&text, &counter, 200);
+        assert!(chunks.len() > 3);
+        assert_eq!(chunks.concat(), text);
+        assert!(chunks.iter().all(|c| counter.count(c) <= 200));
+
+        let long_line = "x".repeat(5000);
+        assert!(split_by_tokens(&long_line, &counter, 100).len() > 1);
+
+        let mut prompts = Vec::new();
+        let result = map_reduce("lib.rs", &chunks, &counter, 8, |prompt| {
+            let summary = if prompt.starts_with("This is part") { "part summary" } else { "merged summary" };
+            prompts.push(prompt);
+            async move { Ok((summary.to_string(), 0.01)) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.summary, "merged summary");
+        assert_eq!(result.chunks, chunks.len());
+        assert!(result.reduce_rounds >= 2);
+        assert_eq!(result.calls, prompts.len());
+        assert!((result.cost_usd - 0.01 * prompts.len() as f64).abs() < 1e-9);
+        assert!(prompts.last().unwrap().contains("final summary of the whole file"));
+
+        let single = map_reduce("a.txt", &["hello"], &counter, 30, |_| async { Ok(("only".to_string(), 0.0)) })
+            .await
+            .unwrap();
+        assert_eq!((single.summary.as_str(), single.calls, single.reduce_rounds), ("only", 1, 0));
+    }
+}
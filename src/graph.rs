@@ -2,12 +2,17 @@
 //!
 //! Entity extraction, relationships, and hybrid retrieval.
 //! Extends basic memory with graph-based knowledge representation.
+//! The whole graph can be exported and re-imported as JSON (lossless) or
+//! GraphML (for Gephi and other graph tools).
+//! Entity types are normalized against an `EntityTaxonomy` (canonical types
+//! plus synonyms), so "Person", "PERSON" and "human" all end up as `person`.
 
 use anyhow::Result;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tracing::{debug, info};
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
 
 /// Entity types for knowledge graph
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -49,6 +54,175 @@ impl EntityType {
     }
 }
 
+/// What `add_entity` does with a type outside the taxonomy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnknownTypePolicy {
+    /// Store it (normalized spelling) and flag it in logs and `/graph`
+    Keep,
+    /// Store the entity under this canonical type instead
+    Fallback(String),
+    /// Refuse the entity
+    Reject,
+}
+
+/// Allowed entity types and the synonyms that map onto them
+#[derive(Debug, Clone)]
+pub struct EntityTaxonomy {
+    types: Vec<String>,
+    synonyms: HashMap<String, String>,
+    pub unknown: UnknownTypePolicy,
+}
+
+/// Built-in synonyms (synonym, canonical type)
+const DEFAULT_TYPE_SYNONYMS: &[(&str, &str)] = &[
+    ("human", "person"),
+    ("people", "person"),
+    ("user", "person"),
+    ("developer", "person"),
+    ("contact", "person"),
+    ("repo", "project"),
+    ("repository", "project"),
+    ("app", "project"),
+    ("application", "project"),
+    ("product", "project"),
+    ("service", "project"),
+    ("tech", "technology"),
+    ("tool", "technology"),
+    ("library", "technology"),
+    ("framework", "technology"),
+    ("language", "technology"),
+    ("programming_language", "technology"),
+    ("database", "technology"),
+    ("idea", "concept"),
+    ("topic", "concept"),
+    ("term", "concept"),
+    ("choice", "decision"),
+    ("path", "file"),
+    ("document", "file"),
+    ("like", "preference"),
+];
+
+impl Default for EntityTaxonomy {
+    fn default() -> Self {
+        let types = [
+            EntityType::Project,
+            EntityType::Person,
+            EntityType::Technology,
+            EntityType::Preference,
+            EntityType::Concept,
+            EntityType::Decision,
+            EntityType::File,
+        ];
+        Self::new(
+            types.iter().map(|t| t.as_str().to_string()).collect(),
+            DEFAULT_TYPE_SYNONYMS.iter().map(|(s, t)| (s.to_string(), t.to_string())).collect(),
+            UnknownTypePolicy::Keep,
+        )
+    }
+}
+
+impl EntityTaxonomy {
+    /// Synonyms pointing at types outside `types` are dropped
+    pub fn new(types: Vec<String>, synonyms: Vec<(String, String)>, unknown: UnknownTypePolicy) -> Self {
+        let types: Vec<String> = types.iter().map(|t| Self::key(t)).filter(|t| !t.is_empty()).collect();
+        let synonyms = synonyms
+            .into_iter()
+            .map(|(s, t)| (Self::key(&s), Self::key(&t)))
+            .filter(|(_, t)| types.contains(t))
+            .collect();
+        let unknown = match unknown {
+            UnknownTypePolicy::Fallback(t) if types.contains(&Self::key(&t)) => UnknownTypePolicy::Fallback(Self::key(&t)),
+            UnknownTypePolicy::Fallback(t) => {
+                warn!("Entity type fallback {} is not in the taxonomy, keeping unknown types instead", t);
+                UnknownTypePolicy::Keep
+            }
+            other => other,
+        };
+        Self { types, synonyms, unknown }
+    }
+
+    /// `CLAUDEBOT_ENTITY_TYPES` (comma-separated, replaces the defaults),
+    /// `CLAUDEBOT_ENTITY_SYNONYMS` (`synonym=type,...`, added to the defaults),
+    /// `CLAUDEBOT_ENTITY_UNKNOWN` (`keep`, `reject` or a fallback type)
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let types = std::env::var("CLAUDEBOT_ENTITY_TYPES")
+            .ok()
+            .map(|v| v.split(',').map(str::to_string).collect::<Vec<_>>())
+            .filter(|t| t.iter().any(|t| !t.trim().is_empty()))
+            .unwrap_or_else(|| default.types.clone());
+        let mut synonyms: Vec<(String, String)> = default.synonyms.into_iter().collect();
+        if let Ok(v) = std::env::var("CLAUDEBOT_ENTITY_SYNONYMS") {
+            synonyms.extend(v.split(',').filter_map(|pair| {
+                let (synonym, canonical) = pair.split_once('=')?;
+                Some((synonym.to_string(), canonical.to_string()))
+            }));
+        }
+        let unknown = match std::env::var("CLAUDEBOT_ENTITY_UNKNOWN").ok().map(|v| v.trim().to_lowercase()) {
+            None => UnknownTypePolicy::Keep,
+            Some(v) if v.is_empty() || v == "keep" => UnknownTypePolicy::Keep,
+            Some(v) if v == "reject" => UnknownTypePolicy::Reject,
+            Some(v) => UnknownTypePolicy::Fallback(v),
+        };
+        Self::new(types, synonyms, unknown)
+    }
+
+    /// Lowercase, trimmed, spaces and dashes as underscores
+    fn key(raw: &str) -> String {
+        raw.trim().to_lowercase().replace([' ', '-'], "_")
+    }
+
+    /// Canonical types, in configured order
+    pub fn types(&self) -> &[String] {
+        &self.types
+    }
+
+    pub fn is_known(&self, entity_type: &str) -> bool {
+        self.types.iter().any(|t| t == entity_type)
+    }
+
+    /// Canonical type for `raw` (exact, synonym, or either after dropping a
+    /// plural "s"), None if it isn't in the taxonomy
+    pub fn canonical(&self, raw: &str) -> Option<&str> {
+        let key = Self::key(raw);
+        let lookup = |k: &str| {
+            self.types
+                .iter()
+                .find(|t| *t == k)
+                .or_else(|| self.synonyms.get(k))
+                .map(String::as_str)
+        };
+        lookup(&key).or_else(|| key.strip_suffix('s').and_then(lookup))
+    }
+
+    /// Type to store `raw` under, applying the unknown-type policy; None if rejected
+    pub fn normalize(&self, raw: &str) -> Option<String> {
+        if let Some(canonical) = self.canonical(raw) {
+            return Some(canonical.to_string());
+        }
+        match &self.unknown {
+            UnknownTypePolicy::Keep => {
+                let key = Self::key(raw);
+                warn!("Entity type {:?} is not in the taxonomy", key);
+                (!key.is_empty()).then_some(key)
+            }
+            UnknownTypePolicy::Fallback(fallback) => Some(fallback.clone()),
+            UnknownTypePolicy::Reject => None,
+        }
+    }
+}
+
+/// Result of `GraphStore::normalize_types`
+#[derive(Debug, Clone, Default)]
+pub struct TypeNormalizeReport {
+    /// Entities moved to their canonical type
+    pub retyped: usize,
+    /// Entities folded into an existing entity of the canonical type
+    pub merged: usize,
+    /// Types left outside the taxonomy, with entity counts
+    pub unknown: Vec<(String, usize)>,
+}
+
 /// Relationship types between entities
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -61,6 +235,8 @@ pub enum RelationType {
     DependsOn,
     CreatedBy,
     Contains,
+    /// Entities mentioned together in the same message
+    MentionedWith,
 }
 
 impl RelationType {
@@ -74,6 +250,7 @@ impl RelationType {
             RelationType::DependsOn => "depends_on",
             RelationType::CreatedBy => "created_by",
             RelationType::Contains => "contains",
+            RelationType::MentionedWith => "mentioned_with",
         }
     }
 
@@ -87,6 +264,7 @@ impl RelationType {
             "depends_on" => Some(RelationType::DependsOn),
             "created_by" => Some(RelationType::CreatedBy),
             "contains" => Some(RelationType::Contains),
+            "mentioned_with" => Some(RelationType::MentionedWith),
             _ => None,
         }
     }
@@ -142,9 +320,27 @@ pub struct GraphSearchResult {
     pub relations: Vec<Relation>,
 }
 
+/// A memory reached from another memory through the graph: the seed memory
+/// mentions `from`, which is related to `to`, which this memory mentions
+#[derive(Debug, Clone)]
+pub struct ConnectedMemory {
+    pub memory_id: String,
+    pub from: String,
+    pub to: String,
+    pub relation_type: String,
+    pub weight: f64,
+}
+
+/// Maximum entities per message linked by co-occurrence (pairs grow quadratically)
+const MAX_CO_OCCURRING: usize = 12;
+
+/// Character distance at which co-occurrence confidence halves
+const CO_OCCURRENCE_HALF_DISTANCE: f64 = 40.0;
+
 /// Graph memory store
 pub struct GraphStore {
     conn: Connection,
+    taxonomy: EntityTaxonomy,
 }
 
 impl GraphStore {
@@ -199,7 +395,20 @@ impl GraphStore {
     /// Open graph store with existing connection
     pub fn new(conn: Connection) -> Result<Self> {
         Self::init(&conn)?;
-        Ok(Self { conn })
+        Ok(Self {
+            conn,
+            taxonomy: EntityTaxonomy::default(),
+        })
+    }
+
+    /// Use a different entity type taxonomy
+    pub fn with_taxonomy(mut self, taxonomy: EntityTaxonomy) -> Self {
+        self.taxonomy = taxonomy;
+        self
+    }
+
+    pub fn taxonomy(&self) -> &EntityTaxonomy {
+        &self.taxonomy
     }
 
     /// Open graph store from file path
@@ -229,29 +438,325 @@ impl GraphStore {
     }
 
     /// Add or update an entity
+    ///
+    /// The type is normalized against the taxonomy first ("Human" becomes
+    /// "person"); types it rejects are an error. Names match
+    /// case-insensitively within a type, so "claude" updates an existing
+    /// "Claude" instead of creating a duplicate. The first spelling seen is kept.
     pub fn add_entity(
         &self,
         entity_type: &str,
         name: &str,
         attributes: Option<serde_json::Value>,
     ) -> Result<String> {
-        let id = Self::entity_id(entity_type, name);
+        let entity_type = self
+            .taxonomy
+            .normalize(entity_type)
+            .ok_or_else(|| anyhow::anyhow!("Entity type {:?} is not in the taxonomy", entity_type))?;
+        let entity_type = entity_type.as_str();
+        let name = name.trim();
+        let new_id = Self::entity_id(entity_type, name);
         let attrs = attributes.unwrap_or(serde_json::json!({})).to_string();
 
-        self.conn.execute(
-            r#"
-            INSERT INTO entities (id, entity_type, name, attributes)
-            VALUES (?1, ?2, ?3, ?4)
-            ON CONFLICT(entity_type, name) DO UPDATE SET
-                attributes = json_patch(entities.attributes, excluded.attributes)
-            "#,
-            params![id, entity_type, name, attrs],
-        )?;
+        // The ID hash folds Unicode case, SQLite's LOWER() only ASCII - check both
+        let existing: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT id FROM entities
+                 WHERE id = ?1 OR (entity_type = ?2 AND LOWER(name) = LOWER(?3))
+                 LIMIT 1",
+                params![new_id, entity_type, name],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let id = match existing {
+            Some(id) => {
+                self.conn.execute(
+                    "UPDATE entities SET attributes = json_patch(attributes, ?2) WHERE id = ?1",
+                    params![id, attrs],
+                )?;
+                id
+            }
+            None => {
+                self.conn.execute(
+                    "INSERT INTO entities (id, entity_type, name, attributes) VALUES (?1, ?2, ?3, ?4)",
+                    params![new_id, entity_type, name, attrs],
+                )?;
+                new_id
+            }
+        };
 
         debug!("Entity added/updated: {} ({}: {})", &id[..8], entity_type, name);
         Ok(id)
     }
 
+    /// Merge alias entities into a canonical one
+    ///
+    /// Relations of the aliases are repointed to the canonical entity (edges
+    /// that already exist there are combined, edges between the merged
+    /// entities are dropped), memory links move over, attributes are combined
+    /// with the canonical entity's values winning, and the aliases are deleted.
+    /// Their names are kept in the canonical entity's `aliases` attribute.
+    ///
+    /// Names match case-insensitively; an alias name matching entities of
+    /// several types merges all of them.
+    pub fn merge_entities(&self, canonical: &str, aliases: &[&str]) -> Result<MergeReport> {
+        let target = self
+            .find_entity_by_name(canonical)?
+            .ok_or_else(|| anyhow::anyhow!("Entity {} not found", canonical))?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut report = MergeReport {
+            canonical: target.clone(),
+            merged: Vec::new(),
+            not_found: Vec::new(),
+            relations_moved: 0,
+            relations_combined: 0,
+            relations_dropped: 0,
+            memories_moved: 0,
+        };
+        let mut attributes = match target.attributes {
+            serde_json::Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        };
+        let mut alias_names: Vec<serde_json::Value> = attributes
+            .get("aliases")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        for alias in aliases {
+            let entities = self.entities_named(alias)?;
+            let entities: Vec<Entity> = entities.into_iter().filter(|e| e.id != target.id).collect();
+            if entities.is_empty() {
+                report.not_found.push(alias.to_string());
+                continue;
+            }
+
+            for entity in entities {
+                self.repoint_relations(&entity.id, &target.id, &mut report)?;
+                report.memories_moved += tx.execute(
+                    "INSERT OR IGNORE INTO entity_memories (entity_id, memory_id, created_at)
+                     SELECT ?2, memory_id, created_at FROM entity_memories WHERE entity_id = ?1",
+                    params![entity.id, target.id],
+                )?;
+                tx.execute("DELETE FROM entity_memories WHERE entity_id = ?1", params![entity.id])?;
+                tx.execute("DELETE FROM entities WHERE id = ?1", params![entity.id])?;
+
+                if let serde_json::Value::Object(alias_attrs) = entity.attributes {
+                    for (key, value) in alias_attrs {
+                        if key != "aliases" {
+                            attributes.entry(key).or_insert(value);
+                        }
+                    }
+                }
+                let name = serde_json::Value::String(entity.name.clone());
+                if !alias_names.contains(&name) && !entity.name.eq_ignore_ascii_case(&target.name) {
+                    alias_names.push(name);
+                }
+                report.merged.push(entity.name);
+            }
+        }
+
+        if !alias_names.is_empty() {
+            attributes.insert("aliases".to_string(), serde_json::Value::Array(alias_names));
+        }
+        let attributes = serde_json::Value::Object(attributes);
+        tx.execute(
+            "UPDATE entities SET attributes = ?2 WHERE id = ?1",
+            params![target.id, attributes.to_string()],
+        )?;
+        tx.commit()?;
+
+        report.canonical.attributes = attributes;
+        info!(
+            "Merged {} entities into {} ({} relations moved, {} combined)",
+            report.merged.len(),
+            target.name,
+            report.relations_moved,
+            report.relations_combined
+        );
+        Ok(report)
+    }
+
+    /// Move existing entities onto their canonical taxonomy type
+    ///
+    /// An entity whose canonical twin already exists is merged into it
+    /// (relations and memory links move over, the twin's attributes win);
+    /// otherwise it is re-created under the canonical type. Types the
+    /// taxonomy doesn't know are left alone and listed in the report.
+    pub fn normalize_types(&self) -> Result<TypeNormalizeReport> {
+        let entities: Vec<Entity> = self
+            .conn
+            .prepare("SELECT id, entity_type, name, attributes, created_at FROM entities ORDER BY created_at")?
+            .query_map([], |row| {
+                Ok(Entity {
+                    id: row.get(0)?,
+                    entity_type: row.get(1)?,
+                    name: row.get(2)?,
+                    attributes: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut report = TypeNormalizeReport::default();
+        let mut unknown: HashMap<String, usize> = HashMap::new();
+        for entity in entities {
+            let Some(canonical) = self.taxonomy.canonical(&entity.entity_type) else {
+                *unknown.entry(entity.entity_type.clone()).or_default() += 1;
+                continue;
+            };
+            let canonical_id = Self::entity_id(canonical, &entity.name);
+            if canonical == entity.entity_type && canonical_id == entity.id {
+                continue;
+            }
+
+            let twin: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM entities
+                     WHERE id != ?1 AND (id = ?2 OR (entity_type = ?3 AND LOWER(name) = LOWER(?4)))
+                     LIMIT 1",
+                    params![entity.id, canonical_id, canonical, entity.name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let target_id = match twin {
+                Some(id) => {
+                    tx.execute(
+                        "UPDATE entities SET attributes = json_patch(?2, attributes) WHERE id = ?1",
+                        params![id, entity.attributes.to_string()],
+                    )?;
+                    report.merged += 1;
+                    id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO entities (id, entity_type, name, attributes, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![canonical_id, canonical, entity.name, entity.attributes.to_string(), entity.created_at],
+                    )?;
+                    report.retyped += 1;
+                    canonical_id
+                }
+            };
+
+            let mut scratch = MergeReport {
+                canonical: entity.clone(),
+                merged: Vec::new(),
+                not_found: Vec::new(),
+                relations_moved: 0,
+                relations_combined: 0,
+                relations_dropped: 0,
+                memories_moved: 0,
+            };
+            self.repoint_relations(&entity.id, &target_id, &mut scratch)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO entity_memories (entity_id, memory_id, created_at)
+                 SELECT ?2, memory_id, created_at FROM entity_memories WHERE entity_id = ?1",
+                params![entity.id, target_id],
+            )?;
+            tx.execute("DELETE FROM entity_memories WHERE entity_id = ?1", params![entity.id])?;
+            tx.execute("DELETE FROM entities WHERE id = ?1", params![entity.id])?;
+        }
+        tx.commit()?;
+
+        report.unknown = unknown.into_iter().collect();
+        report.unknown.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        info!(
+            "Normalized entity types: {} retyped, {} merged, {} unknown types",
+            report.retyped,
+            report.merged,
+            report.unknown.len()
+        );
+        Ok(report)
+    }
+
+    /// All entities with a name (case-insensitive), any type
+    fn entities_named(&self, name: &str) -> Result<Vec<Entity>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, entity_type, name, attributes, created_at
+             FROM entities WHERE LOWER(name) = LOWER(?1)",
+        )?;
+        let entities = stmt
+            .query_map(params![name.trim()], |row| {
+                Ok(Entity {
+                    id: row.get(0)?,
+                    entity_type: row.get(1)?,
+                    name: row.get(2)?,
+                    attributes: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entities)
+    }
+
+    /// Move every relation of `from` onto `to`
+    fn repoint_relations(&self, from: &str, to: &str, report: &mut MergeReport) -> Result<()> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, source_id, target_id, relation_type, weight, valid_from, valid_until, evidence_count
+             FROM relations WHERE source_id = ?1 OR target_id = ?1",
+        )?;
+        let relations = stmt
+            .query_map(params![from], |row| {
+                Ok(Relation {
+                    id: row.get(0)?,
+                    source_id: row.get(1)?,
+                    target_id: row.get(2)?,
+                    relation_type: row.get(3)?,
+                    weight: row.get(4)?,
+                    valid_from: row.get(5)?,
+                    valid_until: row.get(6)?,
+                    evidence_count: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        for relation in relations {
+            self.conn
+                .execute("DELETE FROM relations WHERE id = ?1", params![relation.id])?;
+
+            let repoint = |id: &str| if id == from { to.to_string() } else { id.to_string() };
+            let (source, target) = (repoint(&relation.source_id), repoint(&relation.target_id));
+            if source == target {
+                report.relations_dropped += 1;
+                continue;
+            }
+
+            let combined = self.conn.execute(
+                "UPDATE relations SET
+                    weight = MAX(weight, ?4),
+                    evidence_count = evidence_count + ?5,
+                    valid_from = MIN(valid_from, ?6)
+                 WHERE source_id = ?1 AND target_id = ?2 AND relation_type = ?3",
+                params![source, target, relation.relation_type, relation.weight, relation.evidence_count, relation.valid_from],
+            )?;
+            if combined > 0 {
+                report.relations_combined += 1;
+                continue;
+            }
+
+            self.conn.execute(
+                "INSERT INTO relations (id, source_id, target_id, relation_type, weight, valid_from, valid_until, evidence_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    Self::relation_id(&source, &target, &relation.relation_type),
+                    source,
+                    target,
+                    relation.relation_type,
+                    relation.weight,
+                    relation.valid_from,
+                    relation.valid_until,
+                    relation.evidence_count
+                ],
+            )?;
+            report.relations_moved += 1;
+        }
+        Ok(())
+    }
+
     /// Add or strengthen a relationship
     pub fn add_relation(
         &self,
@@ -352,6 +857,58 @@ impl GraphStore {
         Ok(results)
     }
 
+    /// List entities, optionally filtered by type and name substring
+    ///
+    /// Returns one page ordered by name plus the total number of matches.
+    pub fn list_entities(
+        &self,
+        entity_type: Option<&str>,
+        query: Option<&str>,
+        limit: usize,
+        offset: usize,
+    ) -> Result<EntityPage> {
+        let pattern = query.map(|q| format!("%{}%", q));
+
+        let total: i64 = self.conn.query_row(
+            r#"
+            SELECT COUNT(*) FROM entities
+            WHERE (?1 IS NULL OR entity_type = ?1)
+              AND (?2 IS NULL OR name LIKE ?2)
+            "#,
+            params![entity_type, pattern],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, entity_type, name, attributes, created_at
+            FROM entities
+            WHERE (?1 IS NULL OR entity_type = ?1)
+              AND (?2 IS NULL OR name LIKE ?2)
+            ORDER BY name COLLATE NOCASE
+            LIMIT ?3 OFFSET ?4
+            "#,
+        )?;
+
+        let entities = stmt
+            .query_map(params![entity_type, pattern, limit, offset], |row| {
+                Ok(Entity {
+                    id: row.get(0)?,
+                    entity_type: row.get(1)?,
+                    name: row.get(2)?,
+                    attributes: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    created_at: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(EntityPage {
+            entities,
+            total: total as usize,
+        })
+    }
+
     /// Traverse graph from entity (1-2 hops)
     pub fn traverse(&self, entity_id: &str, max_hops: usize) -> Result<Vec<GraphSearchResult>> {
         let mut results = Vec::new();
@@ -516,6 +1073,39 @@ impl GraphStore {
         Ok(())
     }
 
+    /// Link entities mentioned together in `text` with `mentioned_with` edges
+    ///
+    /// `entities` are (entity_type, name) pairs as passed to `add_entity`.
+    /// Confidence decays with the distance between the mentions; each pair
+    /// gets one edge (lower ID as source) that is strengthened on repeat.
+    /// Returns the number of edges added or strengthened.
+    pub fn link_co_occurring(&self, text: &str, entities: &[(&str, &str)]) -> Result<usize> {
+        let text_lower = text.to_lowercase();
+        let mut seen = std::collections::HashSet::new();
+        let mentions: Vec<(String, Option<usize>)> = entities
+            .iter()
+            .filter_map(|(entity_type, name)| {
+                let entity_type = self.taxonomy.normalize(entity_type)?;
+                Some((Self::entity_id(&entity_type, name), text_lower.find(&name.to_lowercase())))
+            })
+            .filter(|(id, _)| seen.insert(id.clone()))
+            .take(MAX_CO_OCCURRING)
+            .collect();
+
+        let relation_type = RelationType::MentionedWith.as_str();
+        let mut linked = 0;
+        for (i, (id_a, pos_a)) in mentions.iter().enumerate() {
+            for (id_b, pos_b) in &mentions[i + 1..] {
+                let distance = pos_a.zip(*pos_b).map(|(a, b)| a.abs_diff(b));
+                let (source, target) = if id_a < id_b { (id_a, id_b) } else { (id_b, id_a) };
+                self.add_relation(source, target, relation_type, Some(co_occurrence_confidence(distance)))?;
+                linked += 1;
+            }
+        }
+
+        Ok(linked)
+    }
+
     /// Find entity by exact name match
     pub fn find_entity_by_name(&self, name: &str) -> Result<Option<Entity>> {
         let mut stmt = self.conn.prepare(
@@ -574,6 +1164,77 @@ impl GraphStore {
         Ok(results)
     }
 
+    /// Memories one hop away from `memory_ids`
+    ///
+    /// Takes the entities those memories mention, follows each of their
+    /// relations once and returns the memories linked to the entities on the
+    /// other end, strongest relation first. The seed memories themselves are
+    /// never returned.
+    pub fn connected_memories(&self, memory_ids: &[&str], limit: usize) -> Result<Vec<ConnectedMemory>> {
+        let seeds: std::collections::HashSet<&str> = memory_ids.iter().copied().collect();
+
+        let mut mentioned: Vec<(String, String)> = Vec::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT e.id, e.name FROM entity_memories m JOIN entities e ON e.id = m.entity_id WHERE m.memory_id = ?1",
+        )?;
+        for memory_id in memory_ids {
+            for entity in stmt.query_map(params![memory_id], |row| Ok((row.get(0)?, row.get(1)?)))? {
+                let entity = entity?;
+                if !mentioned.contains(&entity) {
+                    mentioned.push(entity);
+                }
+            }
+        }
+
+        let mut linked = self.conn.prepare(
+            "SELECT memory_id FROM entity_memories WHERE entity_id = ?1 ORDER BY created_at DESC LIMIT ?2",
+        )?;
+        let mut seen = std::collections::HashSet::new();
+        let mut connected = Vec::new();
+        for (entity_id, name) in &mentioned {
+            for (neighbor, relation) in self.get_related(entity_id)? {
+                let memories = linked
+                    .query_map(params![neighbor.id, limit as i64], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                for memory_id in memories {
+                    if seeds.contains(memory_id.as_str()) || !seen.insert(memory_id.clone()) {
+                        continue;
+                    }
+                    connected.push(ConnectedMemory {
+                        memory_id,
+                        from: name.clone(),
+                        to: neighbor.name.clone(),
+                        relation_type: relation.relation_type.clone(),
+                        weight: relation.weight,
+                    });
+                }
+            }
+        }
+
+        connected.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap_or(std::cmp::Ordering::Equal));
+        connected.truncate(limit);
+        Ok(connected)
+    }
+
+    /// Everything the graph knows about an entity: exact name match first,
+    /// then a fuzzy one
+    pub fn profile(&self, name: &str) -> Result<Option<EntityProfile>> {
+        let entity = match self.find_entity_by_name(name)? {
+            Some(entity) => entity,
+            None => match self.find_entity(name)? {
+                Some(entity) => entity,
+                None => return Ok(None),
+            },
+        };
+        let neighbors = self.get_related(&entity.id)?;
+        let memory_ids = self
+            .conn
+            .prepare("SELECT memory_id FROM entity_memories WHERE entity_id = ?1 ORDER BY created_at DESC")?
+            .query_map(params![entity.id], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+        Ok(Some(EntityProfile { entity, neighbors, memory_ids }))
+    }
+
     /// Get graph statistics
     pub fn stats(&self) -> Result<GraphStats> {
         let entity_count: i64 = self
@@ -598,6 +1259,213 @@ impl GraphStore {
             by_type,
         })
     }
+
+    /// Export every entity (with its memory links) and relation
+    pub fn export(&self, format: GraphFormat) -> Result<String> {
+        let mut links: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+        {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT entity_id, memory_id FROM entity_memories ORDER BY memory_id")?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+            for row in rows {
+                let (entity_id, memory_id) = row?;
+                links.entry(entity_id).or_default().push(memory_id);
+            }
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT id, entity_type, name, attributes, created_at FROM entities ORDER BY created_at, id",
+        )?;
+        let nodes = stmt
+            .query_map([], |row| {
+                Ok(Entity {
+                    id: row.get(0)?,
+                    entity_type: row.get(1)?,
+                    name: row.get(2)?,
+                    attributes: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .map(|entity| ExportedNode {
+                memory_ids: links.remove(&entity.id).unwrap_or_default(),
+                entity,
+            })
+            .collect();
+
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, source_id, target_id, relation_type, weight,
+                   valid_from, valid_until, evidence_count
+            FROM relations
+            ORDER BY valid_from, id
+            "#,
+        )?;
+        let edges = stmt
+            .query_map([], |row| {
+                Ok(Relation {
+                    id: row.get(0)?,
+                    source_id: row.get(1)?,
+                    target_id: row.get(2)?,
+                    relation_type: row.get(3)?,
+                    weight: row.get(4)?,
+                    valid_from: row.get(5)?,
+                    valid_until: row.get(6)?,
+                    evidence_count: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let export = GraphExport {
+            version: GRAPH_EXPORT_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            nodes,
+            edges,
+        };
+        Ok(match format {
+            GraphFormat::Json => serde_json::to_string_pretty(&export)?,
+            GraphFormat::GraphMl => export.to_graphml(),
+        })
+    }
+
+    /// Import an export produced by `export`
+    ///
+    /// Runs in one transaction. A node matching an existing entity (same id,
+    /// or same type and name) is merged into it: attributes are combined with
+    /// the imported values winning and its relations are repointed. Relations
+    /// already present keep the higher weight and evidence count.
+    pub fn import(&self, data: &str, format: GraphFormat) -> Result<ImportReport> {
+        let export = match format {
+            GraphFormat::Json => serde_json::from_str::<GraphExport>(data)?,
+            GraphFormat::GraphMl => GraphExport::from_graphml(data)?,
+        };
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut report = ImportReport::default();
+        let mut ids: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+
+        for node in &export.nodes {
+            let entity = &node.entity;
+            let existing: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM entities
+                     WHERE id = ?1 OR (entity_type = ?2 AND LOWER(name) = LOWER(?3))
+                     LIMIT 1",
+                    params![entity.id, entity.entity_type, entity.name],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let id = match existing {
+                Some(id) => {
+                    tx.execute(
+                        "UPDATE entities SET attributes = json_patch(attributes, ?2) WHERE id = ?1",
+                        params![id, entity.attributes.to_string()],
+                    )?;
+                    report.entities_merged += 1;
+                    id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO entities (id, entity_type, name, attributes, created_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![
+                            entity.id,
+                            entity.entity_type,
+                            entity.name,
+                            entity.attributes.to_string(),
+                            entity.created_at
+                        ],
+                    )?;
+                    report.entities_added += 1;
+                    entity.id.clone()
+                }
+            };
+            for memory_id in &node.memory_ids {
+                report.memory_links += tx.execute(
+                    "INSERT OR IGNORE INTO entity_memories (entity_id, memory_id) VALUES (?1, ?2)",
+                    params![id, memory_id],
+                )?;
+            }
+            ids.insert(entity.id.clone(), id);
+        }
+
+        for edge in &export.edges {
+            let (Some(source), Some(target)) = (ids.get(&edge.source_id), ids.get(&edge.target_id)) else {
+                anyhow::bail!("Relation {} references an entity missing from the import", edge.id);
+            };
+            let id = if *source == edge.source_id && *target == edge.target_id {
+                edge.id.clone()
+            } else {
+                Self::relation_id(source, target, &edge.relation_type)
+            };
+            let inserted = tx.execute(
+                r#"
+                INSERT INTO relations (id, source_id, target_id, relation_type, weight,
+                                       valid_from, valid_until, evidence_count)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT(source_id, target_id, relation_type) DO UPDATE SET
+                    weight = MAX(relations.weight, excluded.weight),
+                    evidence_count = MAX(relations.evidence_count, excluded.evidence_count)
+                "#,
+                params![
+                    id,
+                    source,
+                    target,
+                    edge.relation_type,
+                    edge.weight,
+                    edge.valid_from,
+                    edge.valid_until,
+                    edge.evidence_count
+                ],
+            )?;
+            report.relations += inserted;
+        }
+
+        tx.commit()?;
+        info!(
+            "Graph import: {} entities added, {} merged, {} relations",
+            report.entities_added, report.entities_merged, report.relations
+        );
+        Ok(report)
+    }
+}
+
+/// Confidence for a co-occurrence edge given the character distance between mentions
+///
+/// Adjacent mentions score close to 1.0; unknown positions get a low default.
+fn co_occurrence_confidence(distance: Option<usize>) -> f64 {
+    match distance {
+        Some(d) => (1.0 / (1.0 + d as f64 / CO_OCCURRENCE_HALF_DISTANCE)).max(0.2),
+        None => 0.3,
+    }
+}
+
+/// One page of entities from `list_entities`
+#[derive(Debug, Clone, Serialize)]
+pub struct EntityPage {
+    pub entities: Vec<Entity>,
+    /// Total matching entities across all pages
+    pub total: usize,
+}
+
+/// Outcome of `merge_entities`
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeReport {
+    /// Canonical entity after the merge
+    pub canonical: Entity,
+    /// Names of the entities merged into it
+    pub merged: Vec<String>,
+    /// Alias names that matched no entity
+    pub not_found: Vec<String>,
+    /// Relations repointed to the canonical entity
+    pub relations_moved: usize,
+    /// Relations folded into an existing canonical edge
+    pub relations_combined: usize,
+    /// Relations between merged entities, which would have become self-loops
+    pub relations_dropped: usize,
+    pub memories_moved: usize,
 }
 
 /// Graph statistics
@@ -608,11 +1476,440 @@ pub struct GraphStats {
     pub by_type: Vec<(String, i64)>,
 }
 
+/// An entity with its current relations and linked memories
+#[derive(Debug, Clone)]
+pub struct EntityProfile {
+    pub entity: Entity,
+    /// Directly related entities, strongest relation first
+    pub neighbors: Vec<(Entity, Relation)>,
+    /// Linked memories, newest first
+    pub memory_ids: Vec<String>,
+}
+
+impl EntityProfile {
+    /// "uses → Rust" for outgoing relations, "Velofi → uses" for incoming ones
+    pub fn describe_relation(&self, neighbor: &Entity, relation: &Relation) -> String {
+        if relation.source_id == self.entity.id {
+            format!("{} → {}", relation.relation_type, neighbor.name)
+        } else {
+            format!("{} → {}", neighbor.name, relation.relation_type)
+        }
+    }
+}
+
+/// Version written into graph exports
+const GRAPH_EXPORT_VERSION: u32 = 1;
+
+/// File format for `GraphStore::export` / `import`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Lossless nodes + edges document
+    Json,
+    /// GraphML for Gephi, yEd and friends
+    GraphMl,
+}
+
+impl GraphFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "graphml" | "xml" => Some(Self::GraphMl),
+            _ => None,
+        }
+    }
+
+    /// Format for a file name's extension
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        Self::parse(name.rsplit_once('.')?.1)
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::GraphMl => "graphml",
+        }
+    }
+}
+
+/// Whole-graph export document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphExport {
+    pub version: u32,
+    pub exported_at: i64,
+    pub nodes: Vec<ExportedNode>,
+    pub edges: Vec<Relation>,
+}
+
+/// An entity and the memories linked to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedNode {
+    #[serde(flatten)]
+    pub entity: Entity,
+    #[serde(default)]
+    pub memory_ids: Vec<String>,
+}
+
+/// GraphML `<key>` declarations: (id, for, attr.name, attr.type)
+const GRAPHML_KEYS: &[(&str, &str, &str, &str)] = &[
+    ("type", "node", "type", "string"),
+    ("name", "node", "label", "string"),
+    ("attributes", "node", "attributes", "string"),
+    ("created_at", "node", "created_at", "long"),
+    ("memory_ids", "node", "memory_ids", "string"),
+    ("relation", "edge", "relation", "string"),
+    ("weight", "edge", "weight", "double"),
+    ("valid_from", "edge", "valid_from", "long"),
+    ("valid_until", "edge", "valid_until", "long"),
+    ("evidence_count", "edge", "evidence_count", "long"),
+];
+
+impl GraphExport {
+    /// Render as GraphML (attributes are kept as a JSON string)
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        );
+        for (id, domain, name, kind) in GRAPHML_KEYS {
+            out.push_str(&format!(
+                "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+                id, domain, name, kind
+            ));
+        }
+        out.push_str("  <graph id=\"claudebot\" edgedefault=\"directed\">\n");
+        let data = |out: &mut String, key: &str, value: &str| {
+            out.push_str(&format!("      <data key=\"{}\">{}</data>\n", key, xml_escape(value)));
+        };
+        for node in &self.nodes {
+            let entity = &node.entity;
+            out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&entity.id)));
+            data(&mut out, "type", &entity.entity_type);
+            data(&mut out, "name", &entity.name);
+            data(&mut out, "attributes", &entity.attributes.to_string());
+            data(&mut out, "created_at", &entity.created_at.to_string());
+            if !node.memory_ids.is_empty() {
+                data(&mut out, "memory_ids", &node.memory_ids.join(","));
+            }
+            out.push_str("    </node>\n");
+        }
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+                xml_escape(&edge.id),
+                xml_escape(&edge.source_id),
+                xml_escape(&edge.target_id)
+            ));
+            data(&mut out, "relation", &edge.relation_type);
+            data(&mut out, "weight", &edge.weight.to_string());
+            data(&mut out, "valid_from", &edge.valid_from.to_string());
+            if let Some(until) = edge.valid_until {
+                data(&mut out, "valid_until", &until.to_string());
+            }
+            data(&mut out, "evidence_count", &edge.evidence_count.to_string());
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    /// Read GraphML in the layout `to_graphml` writes
+    ///
+    /// Only `<node>`, `<edge>` and their `<data>` children are read; other
+    /// elements are skipped.
+    pub fn from_graphml(xml: &str) -> Result<Self> {
+        type Element = (String, std::collections::HashMap<String, String>, std::collections::HashMap<String, String>);
+        let mut export = GraphExport {
+            version: GRAPH_EXPORT_VERSION,
+            exported_at: chrono::Utc::now().timestamp(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        let mut current: Option<Element> = None;
+        let mut rest = xml;
+
+        while let Some(start) = rest.find('<') {
+            let end = rest[start..]
+                .find('>')
+                .map(|i| start + i)
+                .ok_or_else(|| anyhow::anyhow!("Unterminated tag in GraphML"))?;
+            let tag = &rest[start + 1..end];
+            rest = &rest[end + 1..];
+            let closing = tag.starts_with('/');
+            let name = tag
+                .trim_start_matches('/')
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap_or("");
+
+            match (closing, name) {
+                (false, "node" | "edge") => {
+                    let element = (name.to_string(), xml_attrs(tag), std::collections::HashMap::new());
+                    if tag.ends_with('/') {
+                        export.push_element(element)?;
+                    } else {
+                        current = Some(element);
+                    }
+                }
+                (false, "data") => {
+                    let value = if tag.ends_with('/') {
+                        String::new()
+                    } else {
+                        let close = rest
+                            .find("</data>")
+                            .ok_or_else(|| anyhow::anyhow!("Unterminated <data> in GraphML"))?;
+                        let value = xml_unescape(&rest[..close]);
+                        rest = &rest[close + "</data>".len()..];
+                        value
+                    };
+                    if let (Some((_, _, data)), Some(key)) = (current.as_mut(), xml_attrs(tag).remove("key")) {
+                        data.insert(key, value);
+                    }
+                }
+                (true, "node" | "edge") => {
+                    if let Some(element) = current.take() {
+                        export.push_element(element)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(export)
+    }
+
+    fn push_element(
+        &mut self,
+        (kind, attrs, data): (String, std::collections::HashMap<String, String>, std::collections::HashMap<String, String>),
+    ) -> Result<()> {
+        let attr = |name: &str| {
+            attrs
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("GraphML {} is missing '{}'", kind, name))
+        };
+        let field = |key: &str| {
+            data.get(key)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("GraphML {} {} is missing <data key=\"{}\">", kind, attrs.get("id").map(String::as_str).unwrap_or("?"), key))
+        };
+        let number = |key: &str| -> Result<Option<i64>> {
+            data.get(key)
+                .map(|v| v.trim().parse().map_err(|_| anyhow::anyhow!("Invalid {} in GraphML: {}", key, v)))
+                .transpose()
+        };
+        let now = chrono::Utc::now().timestamp();
+
+        if kind == "node" {
+            let attributes = match data.get("attributes") {
+                Some(raw) => serde_json::from_str(raw)?,
+                None => serde_json::json!({}),
+            };
+            self.nodes.push(ExportedNode {
+                entity: Entity {
+                    id: attr("id")?,
+                    entity_type: field("type")?,
+                    name: field("name")?,
+                    attributes,
+                    created_at: number("created_at")?.unwrap_or(now),
+                },
+                memory_ids: data
+                    .get("memory_ids")
+                    .map(|ids| ids.split(',').map(str::trim).filter(|id| !id.is_empty()).map(String::from).collect())
+                    .unwrap_or_default(),
+            });
+        } else {
+            let source_id = attr("source")?;
+            let target_id = attr("target")?;
+            let relation_type = field("relation")?;
+            let weight = match data.get("weight") {
+                Some(w) => w.trim().parse().map_err(|_| anyhow::anyhow!("Invalid weight in GraphML: {}", w))?,
+                None => 1.0,
+            };
+            self.edges.push(Relation {
+                id: attrs
+                    .get("id")
+                    .cloned()
+                    .unwrap_or_else(|| GraphStore::relation_id(&source_id, &target_id, &relation_type)),
+                source_id,
+                target_id,
+                relation_type,
+                weight,
+                valid_from: number("valid_from")?.unwrap_or(now),
+                valid_until: number("valid_until")?,
+                evidence_count: number("evidence_count")?.unwrap_or(1),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Counts from `GraphStore::import`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub entities_added: usize,
+    /// Nodes folded into an entity that already existed
+    pub entities_merged: usize,
+    /// Relations added or strengthened
+    pub relations: usize,
+    pub memory_links: usize,
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// `name="value"` pairs of a tag, unescaped
+fn xml_attrs(tag: &str) -> std::collections::HashMap<String, String> {
+    let mut attrs = std::collections::HashMap::new();
+    let mut rest = tag;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].split_whitespace().last().unwrap_or("").to_string();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(close) = after[1..].find(quote) else {
+            break;
+        };
+        attrs.insert(name, xml_unescape(&after[1..1 + close]));
+        rest = &after[close + 2..];
+    }
+    attrs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rusqlite::Connection;
 
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GraphStore::open(&dir.path().join("source.db")).unwrap();
+        let rust = store
+            .add_entity("technology", "Rust", Some(serde_json::json!({"note": "<fast> & \"safe\"", "year": 2010})))
+            .unwrap();
+        let app = store.add_entity("project", "Velofi", None).unwrap();
+        store.add_relation(&app, &rust, "uses", Some(0.35)).unwrap();
+        store.add_relation(&app, &rust, "uses", None).unwrap();
+        store.link_to_memory(&rust, "mem-1").unwrap();
+
+        let comparable = |json: &str| {
+            let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+            value["exported_at"] = serde_json::json!(0);
+            value
+        };
+        let json = store.export(GraphFormat::Json).unwrap();
+        let graphml = store.export(GraphFormat::GraphMl).unwrap();
+        assert!(graphml.contains("&lt;fast&gt; &amp;"));
+
+        for (name, data, format) in [("json.db", &json, GraphFormat::Json), ("graphml.db", &graphml, GraphFormat::GraphMl)] {
+            let copy = GraphStore::open(&dir.path().join(name)).unwrap();
+            let report = copy.import(data, format).unwrap();
+            assert_eq!((report.entities_added, report.relations, report.memory_links), (2, 1, 1));
+            assert_eq!(comparable(&copy.export(GraphFormat::Json).unwrap()), comparable(&json), "{:?}", format);
+        }
+
+        // Importing into a graph that already has the entity merges instead of duplicating
+        let existing = GraphStore::open(&dir.path().join("existing.db")).unwrap();
+        existing.add_entity("technology", "rust", Some(serde_json::json!({"mine": true}))).unwrap();
+        let report = existing.import(&json, GraphFormat::Json).unwrap();
+        assert_eq!((report.entities_added, report.entities_merged), (1, 1));
+        let merged = existing.find_entity_by_name("rust").unwrap().unwrap();
+        assert_eq!(merged.attributes["mine"], true);
+        assert_eq!(merged.attributes["year"], 2010);
+        assert_eq!(existing.get_relations_for_entity(&merged.id).unwrap()[0].evidence_count, 2);
+
+        assert!(store.import("{not json", GraphFormat::Json).is_err());
+        assert_eq!(GraphFormat::from_file_name("graph_2026.GraphML"), Some(GraphFormat::GraphMl));
+    }
+
+    #[test]
+    fn test_entity_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GraphStore::open(&dir.path().join("graph.db")).unwrap();
+        let alice = store.add_entity("person", "Alice Smith", None).unwrap();
+        let velofi = store.add_entity("project", "Velofi", None).unwrap();
+        let rust = store.add_entity("technology", "Rust", None).unwrap();
+        store.add_relation(&alice, &velofi, "works_on", Some(0.9)).unwrap();
+        store.add_relation(&velofi, &rust, "uses", None).unwrap();
+        store.add_relation(&rust, &alice, "favorite_of", Some(0.5)).unwrap();
+        store.link_to_memory(&alice, "mem-1").unwrap();
+
+        let profile = store.profile("alice smith").unwrap().unwrap();
+        assert_eq!(profile.entity.id, alice);
+        assert_eq!(profile.memory_ids, vec!["mem-1"]);
+        let described: Vec<String> = profile
+            .neighbors
+            .iter()
+            .map(|(entity, relation)| profile.describe_relation(entity, relation))
+            .collect();
+        assert_eq!(described, vec!["works_on → Velofi", "Rust → favorite_of"]);
+
+        // Fuzzy fallback, and nothing for unknown names
+        assert_eq!(store.profile("Alice").unwrap().unwrap().entity.id, alice);
+        assert!(store.profile("Bob").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_entity_type_taxonomy() {
+        let taxonomy = EntityTaxonomy::default();
+        assert_eq!(taxonomy.canonical("PERSON"), Some("person"));
+        assert_eq!(taxonomy.canonical(" Human "), Some("person"));
+        assert_eq!(taxonomy.canonical("Programming Language"), Some("technology"));
+        assert_eq!(taxonomy.canonical("projects"), Some("project"));
+        assert_eq!(taxonomy.canonical("planet"), None);
+        assert_eq!(taxonomy.normalize("Planet").as_deref(), Some("planet"));
+
+        let strict = EntityTaxonomy::new(
+            vec!["person".into(), "concept".into()],
+            vec![("human".into(), "person".into()), ("lib".into(), "technology".into())],
+            UnknownTypePolicy::Reject,
+        );
+        assert_eq!(strict.canonical("lib"), None);
+        assert_eq!(strict.normalize("planet"), None);
+        let fallback = EntityTaxonomy::new(strict.types().to_vec(), vec![], UnknownTypePolicy::Fallback("Concept".into()));
+        assert_eq!(fallback.normalize("planet").as_deref(), Some("concept"));
+
+        // add_entity normalizes; normalize_types cleans up rows written before
+        let dir = tempfile::tempdir().unwrap();
+        let store = GraphStore::open(&dir.path().join("graph.db")).unwrap();
+        let alice = store.add_entity("Person", "Alice", None).unwrap();
+        assert_eq!(store.add_entity("human", "alice", None).unwrap(), alice);
+        let rust = store.add_entity("technology", "Rust", None).unwrap();
+        for (entity_type, name) in [("PERSON", "Alice"), ("Library", "Tokio"), ("planet", "Mars")] {
+            store.conn.execute(
+                "INSERT INTO entities (id, entity_type, name) VALUES (?1, ?2, ?3)",
+                params![GraphStore::entity_id(entity_type, name), entity_type, name],
+            ).unwrap();
+        }
+        let tokio_raw = GraphStore::entity_id("Library", "Tokio");
+        store.add_relation(&tokio_raw, &rust, "depends_on", None).unwrap();
+
+        let report = store.normalize_types().unwrap();
+        assert_eq!((report.retyped, report.merged), (1, 1));
+        assert_eq!(report.unknown, vec![("planet".to_string(), 1)]);
+        let tokio = store.find_entity_by_name("tokio").unwrap().unwrap();
+        assert_eq!(tokio.entity_type, "technology");
+        assert_eq!(store.get_relations_for_entity(&tokio.id).unwrap().len(), 1);
+        let by_type: Vec<String> = store.stats().unwrap().by_type.into_iter().map(|(t, _)| t).collect();
+        assert!(by_type.iter().all(|t| t == "person" || t == "technology" || t == "planet"), "{:?}", by_type);
+
+        let strict_store = GraphStore::open(&dir.path().join("strict.db")).unwrap().with_taxonomy(strict);
+        assert!(strict_store.add_entity("planet", "Mars", None).is_err());
+    }
+
     fn temp_graph(name: &str) -> GraphStore {
         let conn = Connection::open(format!("/tmp/claudebot_graph_{}.db", name)).unwrap();
         GraphStore::new(conn).unwrap()
@@ -642,6 +1939,29 @@ mod tests {
         assert_eq!(stats.relation_count, 1);
     }
 
+    #[test]
+    fn test_list_entities_paginated() {
+        let path = "/tmp/claudebot_graph_list.db";
+        let _ = std::fs::remove_file(path);
+        let store = GraphStore::new(Connection::open(path).unwrap()).unwrap();
+
+        for name in ["Rust", "Ruby", "Go", "Python"] {
+            store.add_entity("technology", name, None).unwrap();
+        }
+        store.add_entity("person", "Russ", None).unwrap();
+
+        let page = store.list_entities(Some("technology"), None, 2, 0).unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.entities.len(), 2);
+        assert_eq!(page.entities[0].name, "Go");
+
+        let page = store.list_entities(Some("technology"), None, 2, 2).unwrap();
+        assert_eq!(page.entities[1].name, "Rust");
+
+        let page = store.list_entities(None, Some("ru"), 10, 0).unwrap();
+        assert_eq!(page.total, 3);
+    }
+
     #[test]
     fn test_graph_traversal() {
         let store = temp_graph("traverse");
@@ -665,6 +1985,37 @@ mod tests {
         assert!(rust_result.is_some());
     }
 
+    #[test]
+    fn test_co_occurrence_links() {
+        let store = temp_graph("co_occurrence");
+        let _ = store.conn.execute("DELETE FROM relations", []);
+
+        let text = "Velofi uses Rust. Much later in a long message we also mention Docker.";
+        let entities = [("project", "Velofi"), ("technology", "rust"), ("technology", "docker")];
+        for (t, n) in entities {
+            store.add_entity(t, n, None).unwrap();
+        }
+
+        assert_eq!(store.link_co_occurring(text, &entities).unwrap(), 3);
+        let velofi = GraphStore::entity_id("project", "Velofi");
+        let relations = store.get_relations_for_entity(&velofi).unwrap();
+        assert_eq!(relations.len(), 2);
+        assert!(relations.iter().all(|r| r.relation_type == "mentioned_with"));
+        // Closer mention (Rust) ranks above the distant one (Docker)
+        let rust = GraphStore::entity_id("technology", "rust");
+        assert!(relations[0].source_id == rust || relations[0].target_id == rust);
+
+        // Repeat co-occurrence strengthens instead of duplicating
+        store.link_co_occurring(text, &entities[..2]).unwrap();
+        let relations = store.get_relations_for_entity(&rust).unwrap();
+        let edge = relations.iter().find(|r| r.source_id == velofi || r.target_id == velofi).unwrap();
+        assert_eq!(edge.evidence_count, 2);
+        assert_eq!(store.stats().unwrap().relation_count, 3);
+
+        // A single entity has nothing to co-occur with
+        assert_eq!(store.link_co_occurring(text, &entities[..1]).unwrap(), 0);
+    }
+
     #[test]
     fn test_simple_extraction() {
         let text = "We use Rust and TypeScript with Vue for the Velofi project";
@@ -715,6 +2066,67 @@ mod tests {
         assert_eq!(stats.entity_count, 1);
     }
 
+    #[test]
+    fn test_case_insensitive_add_entity() {
+        let store = temp_graph("case_dedup");
+        let _ = store.conn.execute("DELETE FROM entities", []);
+
+        let id1 = store.add_entity("technology", "Claude", None).unwrap();
+        let id2 = store
+            .add_entity("technology", " claude ", Some(serde_json::json!({"vendor": "Anthropic"})))
+            .unwrap();
+        assert_eq!(id1, id2);
+
+        let entity = store.find_entity_by_name("CLAUDE").unwrap().unwrap();
+        assert_eq!(entity.name, "Claude");
+        assert_eq!(entity.attributes["vendor"], "Anthropic");
+        assert_eq!(store.stats().unwrap().entity_count, 1);
+    }
+
+    #[test]
+    fn test_merge_entities() {
+        let store = temp_graph("merge");
+        let _ = store.conn.execute("DELETE FROM relations", []);
+        let _ = store.conn.execute("DELETE FROM entity_memories", []);
+        let _ = store.conn.execute("DELETE FROM entities", []);
+
+        let claude = store
+            .add_entity("technology", "Claude", Some(serde_json::json!({"vendor": "Anthropic"})))
+            .unwrap();
+        let code = store
+            .add_entity("tool", "Claude Code", Some(serde_json::json!({"vendor": "?", "cli": true})))
+            .unwrap();
+        let rust = store.add_entity("technology", "Rust", None).unwrap();
+        let bot = store.add_entity("project", "ClaudeBot", None).unwrap();
+
+        store.add_relation(&bot, &claude, "uses", Some(0.5)).unwrap();
+        store.add_relation(&bot, &code, "uses", Some(0.9)).unwrap();
+        store.add_relation(&code, &rust, "written_in", None).unwrap();
+        store.add_relation(&code, &claude, "related_to", None).unwrap();
+        store.link_to_memory(&code, "mem-1").unwrap();
+
+        let report = store.merge_entities("claude", &["Claude Code", "Nope"]).unwrap();
+        assert_eq!(report.merged, vec!["Claude Code"]);
+        assert_eq!(report.not_found, vec!["Nope"]);
+        assert_eq!(report.relations_moved, 1);
+        assert_eq!(report.relations_combined, 1);
+        assert_eq!(report.relations_dropped, 1);
+        assert_eq!(report.memories_moved, 1);
+        assert_eq!(report.canonical.attributes["vendor"], "Anthropic");
+        assert_eq!(report.canonical.attributes["cli"], true);
+        assert_eq!(report.canonical.attributes["aliases"][0], "Claude Code");
+
+        assert!(store.find_entity_by_name("Claude Code").unwrap().is_none());
+        let relations = store.get_relations_for_entity(&claude).unwrap();
+        assert_eq!(relations.len(), 2);
+        let uses = relations.iter().find(|r| r.relation_type == "uses").unwrap();
+        assert_eq!(uses.evidence_count, 2);
+        assert!((uses.weight - 0.9).abs() < 1e-9);
+        assert_eq!(store.stats().unwrap().entity_count, 3);
+
+        assert!(store.merge_entities("Missing", &["Rust"]).is_err());
+    }
+
     #[test]
     fn test_relation_strengthening() {
         let store = temp_graph("strengthen");
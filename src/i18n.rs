@@ -0,0 +1,498 @@
+//! Localized Bot Messages
+//!
+//! The bot's own UI strings (authorization errors, rate limits, help) keyed
+//! by message ID, one catalog per language. Claude's responses are not
+//! translated.
+//!
+//! To contribute a language: add a `Locale` variant, a catalog with the same
+//! keys as `EN`, and wire it into `Locale::from_code` and `catalog`. Missing
+//! keys fall back to English.
+
+use std::fmt;
+
+/// Supported bot UI languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+}
+
+impl Locale {
+    /// All supported locales
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::De, Locale::Es];
+
+    /// Parse a language code ("de", "de-AT", "es_MX"); None if unsupported
+    pub fn from_code(code: &str) -> Option<Self> {
+        let lang = code.trim().split(['-', '_']).next()?.to_lowercase();
+        match lang.as_str() {
+            "en" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            "es" => Some(Locale::Es),
+            _ => None,
+        }
+    }
+
+    /// ISO 639-1 code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+            Locale::Es => "es",
+        }
+    }
+
+    /// Language name in that language
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::De => "Deutsch",
+            Locale::Es => "Español",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+fn catalog(locale: Locale) -> Catalog {
+    match locale {
+        Locale::En => EN,
+        Locale::De => DE,
+        Locale::Es => ES,
+    }
+}
+
+/// Look up a message, falling back to English and then to the key itself
+pub fn t(locale: Locale, key: &'static str) -> &'static str {
+    let find = |catalog: Catalog| catalog.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+    find(catalog(locale)).or_else(|| find(EN)).unwrap_or(key)
+}
+
+/// Look up a message and substitute `{name}` placeholders
+pub fn tf(locale: Locale, key: &'static str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(locale, key).to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+// ============================================================================
+// Catalogs
+// ============================================================================
+
+const EN: Catalog = &[
+    ("unauthorized", "Unauthorized."),
+    ("unauthorized_short", "Unauthorized"),
+    (
+        "rate_limited",
+        "⚠️ Rate limit exceeded.\n\
+        Please wait a moment before sending more requests.\n\
+        Remaining: {remaining}/min",
+    ),
+    (
+        "start",
+        "ClaudeBot (Eliot Brain)\n\n\
+        I'm Claude Code with persistent memory and continuous learning.\n\n\
+        Commands:\n\
+        /help - Show help\n\
+        /usage - Token usage & costs\n\
+        /memory - View memories\n\
+        /goals - View tracked goals\n\
+        /feedback - Learning statistics\n\
+        /graph - View knowledge graph\n\
+        /lang - Bot language\n\n\
+        I learn autonomously from conversations!",
+    ),
+    (
+        "help",
+        "Help:\n\n\
+        Chat:\n\
+        - Send text: I process with full Claude Code\n\
+        - Send files: I analyze them\n\
+        - Send images: I describe them\n\
+        /summarize_file <path> - Summarize a file too large for one request\n\
+        /cd <path> - Work in an existing project (/pwd shows where, /cd reset goes back)\n\n\
+        Conversation:\n\
+        /history - View recent conversation\n\
+        /export_conversation [N | A-B] [redact] - Markdown transcript\n\
+        /bookmark - Reply to a message to bookmark it\n\
+        /bookmarks [N | remove N] - List bookmarks or jump to one\n\
+        /clear - Clear conversation history\n\n\
+        Memory (Autonomous):\n\
+        /memory - View memory stats\n\
+        /memory search <query> - Search memories\n\
+        /memory tag <id> <tag> - Tag a memory (/memory tagged <tag> to list)\n\
+        /goals - View/manage tracked goals\n\
+        /feedback - Learning statistics\n\
+        /feedback reset [id] - Undo your 👍/👎 adjustments\n\
+        /context - Load system context\n\
+        /context window <N> - Conversation messages in context\n\
+        /context strategy full|fast|graph|none - Retrieval depth vs. speed (/context show for settings)\n\
+        /graph - View knowledge graph\n\
+        /graph export [json|graphml] - Download the graph (caption a file /graph import to restore)\n\
+        /graph normalize - Clean up entity types\n\
+        /whois <name> - What I know about someone or something\n\n\
+        Skills:\n\
+        /skills sandbox - Active sandbox policy\n\
+        /skills install <url> <sha256> - Install a verified skill\n\n\
+        Budget & Stats:\n\
+        /usage - View token usage\n\
+        /limits - View/set limits\n\
+        /budget_forecast - Project month-end cost\n\
+        /cost <circle args | bypass task> - Estimate before running\n\
+        /stats [disk] - System statistics (disk: database sizes)\n\
+        /metrics - Claude call failures and error rate\n\
+        /cache [clear] - Response cache stats\n\
+        /status - Check bot status\n\
+        /preflight [cmd] - Check tool availability\n\
+        /diag - Run all health checks\n\
+        /route <text> - Explain model routing (dry run)\n\
+        /trace on|off - Follow each response with its pipeline trace\n\
+        /models - Models, prices and when each is chosen\n\
+        /model stats - Routing distribution and cost per target\n\
+        /retry opus|sonnet|haiku - Re-run your last message on that model\n\
+        /reflect auto on|off - Re-run low-quality answers once\n\
+        /reflect criteria - Show quality weights and floors\n\
+        /dataset [on|off] - Fine-tuning dataset logging for this chat\n\
+        /experiment start|stats|stop - A/B test two prompt instructions\n\
+        /lang [code|auto] - Bot language\n\n\
+        Lifecycle:\n\
+        /sleep - Run background tasks now and sleep (admin)\n\
+        /wake - Force wake from sleep\n\
+        /restart [now] - Save state and restart the bot (admin)\n\
+        /allow <id> | /deny <id> - Grant or revoke access without a restart (admin)\n\
+        /usage adjust|reset|audit - Correct a user's recorded usage (admin)\n\
+        /compression - View/tune conversation compression\n\
+        /retention - View/set conversation retention\n\n\
+        Planning & Scheduling:\n\
+        /plan <task> - Create execution plan\n\
+        /approve [id] - Approve the latest (or given) request/plan\n\
+        /reject [id] - Reject the latest (or given) request/plan\n\
+        /remind <time> <msg> - Set reminder\n\
+        /remind failed - Undelivered notifications\n\
+        /quiet <start>-<end> [offset]|off - Hold notifications overnight\n\n\
+        Permissions:\n\
+        /interactive - Toggle pre-approval mode\n\
+          → Shows Run/Stop buttons before executing\n\
+          → Preview what operations will run\n\
+        /autonomous [duration] - Auto-approve all\n\
+        /supervised - Back to normal mode\n\
+        /perms - View current permission status\n\n\
+        Bypass Bridge (AR):\n\
+        /bypass <task> - Execute on AR server\n\
+        /bypass_file <path> - Analyze file on AR\n\
+        /bypass_cat <path> - Raw file content\n\
+        /bypass_exec <command> - Run a shell command on AR (live output)\n\
+        /bypass_write <path> [mode] - Write file on AR (content on next lines)\n\
+        /bypass_status - Check bridge status",
+    ),
+    (
+        "lang_status",
+        "Language: {name} ({code}) - {source}\n\n\
+        Available: {available}\n\n\
+        Usage: /lang <code> | /lang auto",
+    ),
+    ("lang_source_chosen", "set with /lang"),
+    ("lang_source_telegram", "from your Telegram settings"),
+    ("lang_source_default", "default"),
+    ("lang_set", "Language set to {name}."),
+    ("lang_auto", "Language follows your Telegram settings again ({name})."),
+    ("lang_unknown", "Unknown language: {code}\nAvailable: {available}"),
+];
+
+const DE: Catalog = &[
+    ("unauthorized", "Nicht autorisiert."),
+    ("unauthorized_short", "Nicht autorisiert"),
+    (
+        "rate_limited",
+        "⚠️ Anfragelimit überschritten.\n\
+        Bitte warte einen Moment, bevor du weitere Anfragen sendest.\n\
+        Verbleibend: {remaining}/min",
+    ),
+    (
+        "start",
+        "ClaudeBot (Eliot Brain)\n\n\
+        Ich bin Claude Code mit dauerhaftem Gedächtnis und lerne ständig dazu.\n\n\
+        Befehle:\n\
+        /help - Hilfe anzeigen\n\
+        /usage - Token-Verbrauch & Kosten\n\
+        /memory - Erinnerungen anzeigen\n\
+        /goals - Verfolgte Ziele anzeigen\n\
+        /feedback - Lernstatistiken\n\
+        /graph - Wissensgraph anzeigen\n\
+        /lang - Sprache des Bots\n\n\
+        Ich lerne selbstständig aus unseren Gesprächen!",
+    ),
+    (
+        "help",
+        "Hilfe:\n\n\
+        Chat:\n\
+        - Text senden: Ich bearbeite ihn mit vollem Claude Code\n\
+        - Dateien senden: Ich analysiere sie\n\
+        - Bilder senden: Ich beschreibe sie\n\
+        /summarize_file <Pfad> - Datei zusammenfassen, die für eine Anfrage zu groß ist\n\
+        /cd <Pfad> - In einem bestehenden Projekt arbeiten (/pwd zeigt wo, /cd reset zurück)\n\n\
+        Gespräch:\n\
+        /history - Letzten Gesprächsverlauf anzeigen\n\
+        /export_conversation [N | A-B] [redact] - Markdown-Transkript\n\
+        /bookmark - Als Antwort auf eine Nachricht: Lesezeichen setzen\n\
+        /bookmarks [N | remove N] - Lesezeichen anzeigen oder hinspringen\n\
+        /clear - Gesprächsverlauf löschen\n\n\
+        Gedächtnis (autonom):\n\
+        /memory - Gedächtnisstatistik anzeigen\n\
+        /memory search <Suche> - Erinnerungen durchsuchen\n\
+        /memory tag <ID> <Tag> - Erinnerung taggen (/memory tagged <Tag> listet sie)\n\
+        /goals - Verfolgte Ziele anzeigen/verwalten\n\
+        /feedback - Lernstatistiken\n\
+        /feedback reset [ID] - Deine 👍/👎-Anpassungen zurücknehmen\n\
+        /context - Systemkontext laden\n\
+        /context window <N> - Gesprächsnachrichten im Kontext\n\
+        /context strategy full|fast|graph|none - Suchtiefe vs. Tempo (/context show zeigt die Einstellungen)\n\
+        /graph - Wissensgraph anzeigen\n\
+        /graph export [json|graphml] - Graph herunterladen (Datei mit Beschriftung /graph import stellt ihn wieder her)\n\
+        /graph normalize - Entitätstypen bereinigen\n\
+        /whois <name> - Was ich über jemanden oder etwas weiß\n\n\
+        Skills:\n\
+        /skills sandbox - Aktive Sandbox-Richtlinie\n\
+        /skills install <URL> <sha256> - Geprüften Skill installieren\n\n\
+        Budget & Statistik:\n\
+        /usage - Token-Verbrauch anzeigen\n\
+        /limits - Limits anzeigen/setzen\n\
+        /budget_forecast - Kosten zum Monatsende hochrechnen\n\
+        /cost <circle-Argumente | bypass-Aufgabe> - Vorher schätzen\n\
+        /stats [disk] - Systemstatistik (disk: Datenbankgrößen)\n\
+        /metrics - Fehlgeschlagene Claude-Aufrufe und Fehlerrate\n\
+        /cache [clear] - Statistik des Antwort-Caches\n\
+        /status - Bot-Status prüfen\n\
+        /preflight [Befehl] - Verfügbarkeit der Tools prüfen\n\
+        /diag - Alle Systemprüfungen ausführen\n\
+        /route <Text> - Modellwahl erklären (Probelauf)\n\
+        /trace on|off - Jeder Antwort einen Pipeline-Trace anhängen\n\
+        /models - Modelle, Preise und wann welches gewählt wird\n\
+        /model stats - Routing-Verteilung und Kosten pro Ziel\n\
+        /retry opus|sonnet|haiku - Letzte Nachricht mit diesem Modell wiederholen\n\
+        /reflect auto on|off - Schwache Antworten einmal neu erzeugen\n\
+        /reflect criteria - Qualitätsgewichte und Mindestwerte anzeigen\n\
+        /dataset [on|off] - Trainingsdaten-Protokoll für diesen Chat\n\
+        /experiment start|stats|stop - A/B-Test zweier Prompt-Anweisungen\n\
+        /lang [Code|auto] - Sprache des Bots\n\n\
+        Lebenszyklus:\n\
+        /sleep - Hintergrundaufgaben jetzt ausführen und schlafen (Admin)\n\
+        /wake - Aufwecken erzwingen\n\
+        /restart [now] - Zustand sichern und Bot neu starten (Admin)\n\
+        /allow <id> | /deny <id> - Zugriff ohne Neustart gewähren oder entziehen (Admin)\n\
+        /usage adjust|reset|audit - Erfassten Verbrauch eines Nutzers korrigieren (Admin)\n\
+        /compression - Gesprächskomprimierung anzeigen/einstellen\n\
+        /retention - Aufbewahrung von Gesprächen anzeigen/setzen\n\n\
+        Planung & Termine:\n\
+        /plan <Aufgabe> - Ausführungsplan erstellen\n\
+        /approve [id] - Letzte (oder angegebene) Anfrage/Plan genehmigen\n\
+        /reject [id] - Letzte (oder angegebene) Anfrage/Plan ablehnen\n\
+        /remind <Zeit> <Nachricht> - Erinnerung setzen\n\
+        /remind failed - Nicht zugestellte Benachrichtigungen\n\
+        /quiet <Start>-<Ende> [Offset]|off - Benachrichtigungen nachts zurückhalten\n\n\
+        Berechtigungen:\n\
+        /interactive - Vorabfreigabe ein-/ausschalten\n\
+          → Zeigt Ausführen/Stopp-Buttons vor der Ausführung\n\
+          → Vorschau der geplanten Operationen\n\
+        /autonomous [Dauer] - Alles automatisch freigeben\n\
+        /supervised - Zurück zum Normalmodus\n\
+        /perms - Aktuellen Berechtigungsstatus anzeigen\n\n\
+        Bypass-Bridge (AR):\n\
+        /bypass <Aufgabe> - Auf dem AR-Server ausführen\n\
+        /bypass_file <Pfad> - Datei auf AR analysieren\n\
+        /bypass_cat <Pfad> - Rohinhalt einer Datei\n\
+        /bypass_exec <Befehl> - Shell-Befehl auf AR ausführen (Live-Ausgabe)\n\
+        /bypass_write <Pfad> [Modus] - Datei auf AR schreiben (Inhalt in den Folgezeilen)\n\
+        /bypass_status - Bridge-Status prüfen",
+    ),
+    (
+        "lang_status",
+        "Sprache: {name} ({code}) - {source}\n\n\
+        Verfügbar: {available}\n\n\
+        Verwendung: /lang <Code> | /lang auto",
+    ),
+    ("lang_source_chosen", "mit /lang gewählt"),
+    ("lang_source_telegram", "aus deinen Telegram-Einstellungen"),
+    ("lang_source_default", "Standard"),
+    ("lang_set", "Sprache auf {name} gesetzt."),
+    ("lang_auto", "Die Sprache folgt wieder deinen Telegram-Einstellungen ({name})."),
+    ("lang_unknown", "Unbekannte Sprache: {code}\nVerfügbar: {available}"),
+];
+
+const ES: Catalog = &[
+    ("unauthorized", "No autorizado."),
+    ("unauthorized_short", "No autorizado"),
+    (
+        "rate_limited",
+        "⚠️ Límite de solicitudes superado.\n\
+        Espera un momento antes de enviar más solicitudes.\n\
+        Restantes: {remaining}/min",
+    ),
+    (
+        "start",
+        "ClaudeBot (Eliot Brain)\n\n\
+        Soy Claude Code con memoria persistente y aprendizaje continuo.\n\n\
+        Comandos:\n\
+        /help - Mostrar ayuda\n\
+        /usage - Uso de tokens y costes\n\
+        /memory - Ver recuerdos\n\
+        /goals - Ver objetivos registrados\n\
+        /feedback - Estadísticas de aprendizaje\n\
+        /graph - Ver grafo de conocimiento\n\
+        /lang - Idioma del bot\n\n\
+        ¡Aprendo de forma autónoma de las conversaciones!",
+    ),
+    (
+        "help",
+        "Ayuda:\n\n\
+        Chat:\n\
+        - Envía texto: lo proceso con Claude Code completo\n\
+        - Envía archivos: los analizo\n\
+        - Envía imágenes: las describo\n\
+        /summarize_file <ruta> - Resumir un archivo demasiado grande para una sola petición\n\
+        /cd <ruta> - Trabajar en un proyecto existente (/pwd muestra dónde, /cd reset vuelve)\n\n\
+        Conversación:\n\
+        /history - Ver la conversación reciente\n\
+        /export_conversation [N | A-B] [redact] - Transcripción en Markdown\n\
+        /bookmark - Responde a un mensaje para guardarlo como marcador\n\
+        /bookmarks [N | remove N] - Ver marcadores o saltar a uno\n\
+        /clear - Borrar el historial de conversación\n\n\
+        Memoria (autónoma):\n\
+        /memory - Ver estadísticas de memoria\n\
+        /memory search <consulta> - Buscar recuerdos\n\
+        /memory tag <id> <etiqueta> - Etiquetar un recuerdo (/memory tagged <etiqueta> para listar)\n\
+        /goals - Ver/gestionar objetivos registrados\n\
+        /feedback - Estadísticas de aprendizaje\n\
+        /feedback reset [id] - Deshacer tus ajustes 👍/👎\n\
+        /context - Cargar contexto del sistema\n\
+        /context window <N> - Mensajes de conversación en contexto\n\
+        /context strategy full|fast|graph|none - Profundidad de búsqueda vs. velocidad (/context show muestra los ajustes)\n\
+        /graph - Ver grafo de conocimiento\n\
+        /graph export [json|graphml] - Descargar el grafo (un archivo con el texto /graph import lo restaura)\n\
+        /graph normalize - Limpiar los tipos de entidad\n\
+        /whois <nombre> - Lo que sé sobre alguien o algo\n\n\
+        Skills:\n\
+        /skills sandbox - Política de sandbox activa\n\
+        /skills install <url> <sha256> - Instalar un skill verificado\n\n\
+        Presupuesto y estadísticas:\n\
+        /usage - Ver uso de tokens\n\
+        /limits - Ver/fijar límites\n\
+        /budget_forecast - Proyectar el coste a fin de mes\n\
+        /cost <args de circle | tarea bypass> - Estimar antes de ejecutar\n\
+        /stats [disk] - Estadísticas del sistema (disk: tamaño de las bases de datos)\n\
+        /metrics - Llamadas fallidas a Claude y tasa de error\n\
+        /cache [clear] - Estadísticas de la caché de respuestas\n\
+        /status - Comprobar el estado del bot\n\
+        /preflight [cmd] - Comprobar herramientas disponibles\n\
+        /diag - Ejecutar todas las comprobaciones\n\
+        /route <texto> - Explicar la elección de modelo (simulación)\n\
+        /trace on|off - Añadir a cada respuesta la traza del pipeline\n\
+        /models - Modelos, precios y cuándo se elige cada uno\n\
+        /model stats - Distribución de enrutamiento y coste por destino\n\
+        /retry opus|sonnet|haiku - Repetir tu último mensaje con ese modelo\n\
+        /reflect auto on|off - Repetir una vez las respuestas de baja calidad\n\
+        /reflect criteria - Ver pesos y mínimos de calidad\n\
+        /dataset [on|off] - Registro de datos de entrenamiento para este chat\n\
+        /experiment start|stats|stop - Prueba A/B de dos instrucciones de prompt\n\
+        /lang [código|auto] - Idioma del bot\n\n\
+        Ciclo de vida:\n\
+        /sleep - Ejecutar tareas en segundo plano ahora y dormir (admin)\n\
+        /wake - Forzar el despertar\n\
+        /restart [now] - Guardar el estado y reiniciar el bot (admin)\n\
+        /allow <id> | /deny <id> - Conceder o retirar acceso sin reiniciar (admin)\n\
+        /usage adjust|reset|audit - Corregir el uso registrado de un usuario (admin)\n\
+        /compression - Ver/ajustar la compresión de conversaciones\n\
+        /retention - Ver/fijar la retención de conversaciones\n\n\
+        Planificación:\n\
+        /plan <tarea> - Crear plan de ejecución\n\
+        /approve [id] - Aprobar la última (o indicada) solicitud/plan\n\
+        /reject [id] - Rechazar la última (o indicada) solicitud/plan\n\
+        /remind <hora> <mensaje> - Crear recordatorio\n\
+        /remind failed - Notificaciones no entregadas\n\
+        /quiet <inicio>-<fin> [offset]|off - Retener notificaciones de noche\n\n\
+        Permisos:\n\
+        /interactive - Activar/desactivar la aprobación previa\n\
+          → Muestra botones Ejecutar/Detener antes de ejecutar\n\
+          → Vista previa de las operaciones\n\
+        /autonomous [duración] - Aprobar todo automáticamente\n\
+        /supervised - Volver al modo normal\n\
+        /perms - Ver el estado de permisos\n\n\
+        Bypass Bridge (AR):\n\
+        /bypass <tarea> - Ejecutar en el servidor AR\n\
+        /bypass_file <ruta> - Analizar archivo en AR\n\
+        /bypass_cat <ruta> - Contenido bruto del archivo\n\
+        /bypass_exec <comando> - Ejecutar un comando de shell en AR (salida en vivo)\n\
+        /bypass_write <ruta> [modo] - Escribir archivo en AR (contenido en las líneas siguientes)\n\
+        /bypass_status - Comprobar el estado del bridge",
+    ),
+    (
+        "lang_status",
+        "Idioma: {name} ({code}) - {source}\n\n\
+        Disponibles: {available}\n\n\
+        Uso: /lang <código> | /lang auto",
+    ),
+    ("lang_source_chosen", "elegido con /lang"),
+    ("lang_source_telegram", "según tu configuración de Telegram"),
+    ("lang_source_default", "predeterminado"),
+    ("lang_set", "Idioma cambiado a {name}."),
+    ("lang_auto", "El idioma vuelve a seguir tu configuración de Telegram ({name})."),
+    ("lang_unknown", "Idioma desconocido: {code}\nDisponibles: {available}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_code() {
+        assert_eq!(Locale::from_code("de"), Some(Locale::De));
+        assert_eq!(Locale::from_code("es-MX"), Some(Locale::Es));
+        assert_eq!(Locale::from_code("EN_gb"), Some(Locale::En));
+        assert_eq!(Locale::from_code("fr"), None);
+        assert_eq!(Locale::from_code(""), None);
+    }
+
+    #[test]
+    fn test_catalogs_complete() {
+        for locale in Locale::ALL {
+            for (key, _) in EN {
+                assert!(
+                    catalog(locale).iter().any(|(k, _)| k == key),
+                    "{} catalog is missing {}",
+                    locale,
+                    key
+                );
+            }
+            // Help lines must keep the command first so safe mode can filter them
+            let help_commands: Vec<&str> = t(locale, "help")
+                .lines()
+                .filter_map(|l| l.trim_start().strip_prefix('/'))
+                .filter_map(|l| l.split_whitespace().next())
+                .collect();
+            let en_commands: Vec<&str> = t(Locale::En, "help")
+                .lines()
+                .filter_map(|l| l.trim_start().strip_prefix('/'))
+                .filter_map(|l| l.split_whitespace().next())
+                .collect();
+            assert_eq!(help_commands, en_commands, "{} help commands differ", locale);
+        }
+    }
+
+    #[test]
+    fn test_lookup_and_placeholders() {
+        assert_eq!(t(Locale::De, "unauthorized"), "Nicht autorisiert.");
+        assert_eq!(t(Locale::Es, "no_such_key"), "no_such_key");
+        assert_eq!(
+            tf(Locale::En, "lang_set", &[("name", "Deutsch")]),
+            "Language set to Deutsch."
+        );
+        assert!(tf(Locale::De, "rate_limited", &[("remaining", "3")]).contains("3/min"));
+    }
+}
@@ -0,0 +1,174 @@
+//! Oversized Input Handling
+//!
+//! Messages longer than `max_chars` aren't sent to Claude as-is. The user
+//! picks one of:
+//! - truncate to the limit
+//! - summarize with Llama first, then process the summary
+//! - split into chunks, read sequentially, then answer from the combined notes
+//!
+//! Very large pastes also show the estimated cost of sending them whole.
+
+/// Limits for incoming chat text
+#[derive(Debug, Clone)]
+pub struct InputLimits {
+    /// Messages longer than this (in characters) ask how to proceed; 0 disables
+    pub max_chars: usize,
+    /// Chunk size when splitting (defaults to `max_chars`)
+    pub chunk_chars: usize,
+    /// Show the full-input cost estimate from this many characters
+    pub cost_notice_chars: usize,
+}
+
+impl Default for InputLimits {
+    fn default() -> Self {
+        Self {
+            max_chars: 20_000,
+            chunk_chars: 20_000,
+            cost_notice_chars: 50_000,
+        }
+    }
+}
+
+impl InputLimits {
+    /// `CLAUDEBOT_MAX_INPUT_CHARS`, `CLAUDEBOT_INPUT_CHUNK_CHARS`, `CLAUDEBOT_INPUT_COST_NOTICE_CHARS`
+    pub fn from_env() -> Self {
+        let mut limits = Self::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<usize>().ok());
+        if let Some(n) = var("CLAUDEBOT_MAX_INPUT_CHARS") {
+            limits.max_chars = n;
+            limits.chunk_chars = n;
+        }
+        if let Some(n) = var("CLAUDEBOT_INPUT_CHUNK_CHARS").filter(|n| *n > 0) {
+            limits.chunk_chars = n;
+        }
+        if let Some(n) = var("CLAUDEBOT_INPUT_COST_NOTICE_CHARS") {
+            limits.cost_notice_chars = n;
+        }
+        limits
+    }
+
+    /// Whether `text` needs the user to choose how to proceed
+    pub fn exceeds(&self, text: &str) -> bool {
+        self.max_chars > 0 && text.chars().count() > self.max_chars
+    }
+
+    /// Whether the full-input cost estimate should be shown
+    pub fn show_cost(&self, text: &str) -> bool {
+        text.chars().count() >= self.cost_notice_chars
+    }
+}
+
+/// How the user chose to handle an oversized message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizeAction {
+    Truncate,
+    Summarize,
+    Chunks,
+    Cancel,
+}
+
+impl OversizeAction {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "truncate" => Some(Self::Truncate),
+            "summarize" => Some(Self::Summarize),
+            "chunks" => Some(Self::Chunks),
+            "cancel" => Some(Self::Cancel),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Truncate => "truncate",
+            Self::Summarize => "summarize",
+            Self::Chunks => "chunks",
+            Self::Cancel => "cancel",
+        }
+    }
+}
+
+/// First `max_chars` characters, cut back to a line or word boundary when one
+/// is reasonably close
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    let Some((end, _)) = text.char_indices().nth(max_chars) else {
+        return text;
+    };
+    let head = &text[..end];
+    let floor = head.len() * 4 / 5;
+    match head.rfind('\n').filter(|&i| i >= floor).or_else(|| head.rfind(' ').filter(|&i| i >= floor)) {
+        Some(i) => &head[..i],
+        None => head,
+    }
+}
+
+/// Split into chunks of at most `chunk_chars` characters, preferring line and
+/// word boundaries
+pub fn split_chunks(text: &str, chunk_chars: usize) -> Vec<&str> {
+    let chunk_chars = chunk_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut chunk = truncate_chars(rest, chunk_chars);
+        if chunk.is_empty() {
+            // A boundary at position 0 would never advance; cut mid-word instead
+            let end = rest.char_indices().nth(chunk_chars).map_or(rest.len(), |(i, _)| i);
+            chunk = &rest[..end];
+        }
+        chunks.push(chunk);
+        rest = rest[chunk.len()..].trim_start_matches(['\n', ' ']);
+    }
+    chunks
+}
+
+/// Prompt for reading one chunk of a split message
+pub fn chunk_prompt(part: usize, total: usize, chunk: &str) -> String {
+    format!(
+        "The user sent a message too long to process at once; it is split into {total} parts.\n\
+        This is part {part}/{total}. Don't answer yet: write concise notes on everything in this \
+        part the final answer will need (facts, code, questions, instructions).\n\n\
+        --- Part {part}/{total} ---\n{chunk}"
+    )
+}
+
+/// Prompt for answering from the notes on every chunk
+pub fn combine_prompt(notes: &[String]) -> String {
+    let sections: Vec<String> = notes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| format!("--- Notes on part {}/{} ---\n{}", i + 1, notes.len(), n.trim()))
+        .collect();
+    format!(
+        "The user sent a long message that was read in {} parts. Using the notes below, \
+        respond to the message as a whole, as if you had read it in one go.\n\n{}",
+        notes.len(),
+        sections.join("\n\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_and_split() {
+        let limits = InputLimits { max_chars: 10, chunk_chars: 10, cost_notice_chars: 100 };
+        assert!(!limits.exceeds("ääääääääää"));
+        assert!(limits.exceeds("ääääääääääx"));
+        assert!(!InputLimits { max_chars: 0, ..limits.clone() }.exceeds(&"x".repeat(1000)));
+
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("hello world again", 14), "hello world");
+        assert_eq!(truncate_chars("ééééééé", 3), "ééé");
+
+        let text = "line one\nline two\nline three is longer";
+        let chunks = split_chunks(text, 10);
+        assert_eq!(chunks, vec!["line one", "line two", "line three", "is longer"]);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 10));
+        assert_eq!(split_chunks(&"x".repeat(25), 10).len(), 3);
+
+        let combined = combine_prompt(&["a".to_string(), "b".to_string()]);
+        assert!(combined.contains("Notes on part 2/2"));
+        assert_eq!(OversizeAction::parse(OversizeAction::Chunks.as_str()), Some(OversizeAction::Chunks));
+    }
+}
@@ -36,26 +36,41 @@ pub mod cache;
 pub mod channels;
 pub mod circle;
 pub mod claude;
+pub mod claude_cli;
 pub mod config;
 pub mod conversation;
 pub mod dashboard;
+pub mod dataset;
 pub mod embeddings;
 pub mod feedback;
+pub mod file_summary;
 pub mod graph;
+pub mod i18n;
+pub mod input_limits;
 pub mod lifecycle;
 pub mod llama_worker;
 pub mod mcp;
 pub mod memory;
+pub mod memory_backend;
+pub mod ocr;
 pub mod metrics;
 pub mod permissions;
+pub mod pipeline_trace;
+pub mod postprocess;
 pub mod preflight;
 pub mod router;
+pub mod session_compaction;
 pub mod skills;
+pub mod sqlite_pool;
+pub mod storage;
+pub mod tasks;
 pub mod telegram;
 pub mod tokenizer;
 pub mod tools;
+pub mod update_dedup;
 pub mod usage;
 pub mod vault;
+pub mod working_dirs;
 pub mod git_ops;
 pub mod worker_pool;
 pub mod coordinator;
@@ -65,22 +80,30 @@ pub mod telegram_ui;
 #[cfg(test)]
 mod telegram_tests;
 
-pub use cache::ResponseCache;
-pub use circle::{Circle, PipelineMode, PipelineResult};
+pub use cache::{CacheStats, CacheWarmConfig, ResponseCache, WarmBudget, WarmReport};
+pub use circle::{Circle, CirclePersonas, PersonaConfig, PersonaKind, PhaseProgress, PipelineMode, PipelineResult};
 pub use claude::ClaudeClient;
 pub use config::Config;
-pub use conversation::{ConversationStore, ConversationMessage, ConversationSummary};
+pub use conversation::{ConversationStore, ConversationMessage, ConversationSummary, FrequentMessage, StaleConversation, TurnUsage};
+pub use dataset::{DatasetConfig, DatasetExample, DatasetSink};
 pub use embeddings::{EmbeddingStore, EmbeddingConfig, VectorIndex, embedding_to_bytes, embedding_from_bytes};
-pub use graph::GraphStore;
-pub use memory::{MemoryStore, MemoryEntry, ScoredMemory, SearchResult, MemoryStats, EmbeddingStats};
-pub use lifecycle::{LifecycleManager, LifecycleConfig, State as LifecycleState};
+pub use graph::{ConnectedMemory, EntityProfile, EntityTaxonomy, GraphExport, GraphFormat, GraphStore, ImportReport, MergeReport, TypeNormalizeReport, UnknownTypePolicy};
+pub use i18n::Locale;
+pub use memory::{MemoryStore, MemoryEntry, MemoryScope, MemoryScopeMode, ScoredMemory, SearchResult, MemoryStats, EmbeddingStats, IndexHealth, IndexRebuild, LearnEntry, LearnBatch, MemoryLimits, BackfillBatch, FeedbackCounts, FeedbackReset};
+pub use memory_backend::{MemoryBackend, MemoryBackendUrl, PostgresMemoryStore};
+pub use lifecycle::{LifecycleManager, LifecycleConfig, CompressionConfig, CompressionOverride, SleepReport, SleepTaskOutcome, State as LifecycleState};
 pub use llama_worker::{LlamaWorker, LlamaWorkerConfig, QueryComplexity};
-pub use mcp::{McpRequest, McpResponse, McpServer};
-pub use metrics::MetricsCollector;
-pub use router::{ModelHint, RouteResult, Target, TaskRouter};
+pub use mcp::{McpCallLog, McpCallRecord, McpRequest, McpResponse, McpServer, McpToolPolicy, ResourceAccess, ResourceStore};
+pub use metrics::{FailureReason, MetricsCollector};
+pub use input_limits::{InputLimits, OversizeAction};
+pub use ocr::{Ocr, OcrBackend, OcrConfig};
+pub use router::{CodeFloor, FallbackConfig, ModelHint, RouteCacheStats, RouteResult, Target, TaskRouter};
+pub use sqlite_pool::{SqliteConfig, SqlitePool};
+pub use storage::{DbStorage, StorageReport, TableRows};
+pub use tasks::{RunningTask, TaskEvent, TaskGuard, TaskKind, TaskRegistry};
 pub use tokenizer::{BudgetCheck, TokenCounter};
 pub use bridge::{GrpcBridgeServer, GrpcBridgeClient, GrpcBridgeConfig, GrpcBridgeClientConfig, ExecuteResult};
-pub use preflight::{PreflightChecker, PreflightResult};
+pub use preflight::{ConnectivityReport, DiagReport, DiagStatus, EndpointStatus, PreflightChecker, PreflightResult};
 pub use feedback::{TaskSummary, TaskAction, TaskFeedback};
 pub use vault::{CredentialVault, CredentialType, Credential, VaultError};
 pub use git_ops::{GitRepo, GitError, CommitInfo, BranchInfo, FileStatus};
@@ -95,8 +118,9 @@ pub use telegram_ui::{
 };
 pub use autonomous::{
     AutonomousLearner, LearnedFact, LearningConfig,
-    ContextManager, EnrichedContext, ContextConfig,
-    BackgroundProcessor, BackgroundConfig, BackgroundTask,
-    GoalTracker, Goal, GoalStatus,
+    ContextManager, EnrichedContext, ContextConfig, ContextTrim,
+    BackgroundProcessor, BackgroundConfig, BackgroundTask, RetentionConfig, RetentionReport,
+    GoalTracker, Goal, GoalMatch, GoalResolution, GoalStatus,
     FeedbackLoop, FeedbackSignal, MemoryFeedback,
+    Digest, DigestConfig, DigestSections,
 };
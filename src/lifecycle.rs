@@ -7,9 +7,12 @@
 //!
 //! Background tasks run during idle periods to optimize memory and reduce costs.
 
-use std::sync::atomic::{AtomicI64, AtomicU8, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::Notify;
 use tracing::{debug, info, warn};
 
@@ -49,6 +52,8 @@ pub struct LifecycleConfig {
     pub enable_decay: bool,
     /// Enable context compression during sleep
     pub enable_compression: bool,
+    /// Thresholds for conversation compression
+    pub compression: CompressionConfig,
 }
 
 impl Default for LifecycleConfig {
@@ -59,17 +64,178 @@ impl Default for LifecycleConfig {
             enable_consolidation: true,
             enable_decay: true,
             enable_compression: true,
+            compression: CompressionConfig::default(),
         }
     }
 }
 
+impl LifecycleConfig {
+    /// The bot's settings: sleep after a day idle (practically never during
+    /// normal use), run background tasks hourly while asleep
+    ///
+    /// Overridden by `CLAUDEBOT_IDLE_TIMEOUT_SECS`,
+    /// `CLAUDEBOT_SLEEP_TASK_INTERVAL_SECS`, `CLAUDEBOT_ENABLE_CONSOLIDATION`,
+    /// `CLAUDEBOT_ENABLE_DECAY`, `CLAUDEBOT_ENABLE_COMPRESSION` and the
+    /// `CLAUDEBOT_COMPRESS_*` thresholds.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let mut config = Self {
+            idle_timeout: Duration::from_secs(86400),
+            sleep_task_interval: Duration::from_secs(3600),
+            compression: CompressionConfig::from_env(),
+            ..Self::default()
+        };
+
+        let secs = |key: &str| lookup(key).and_then(|s| s.trim().parse::<u64>().ok());
+        if let Some(secs) = secs("CLAUDEBOT_IDLE_TIMEOUT_SECS") {
+            config.idle_timeout = Duration::from_secs(secs.max(1));
+        }
+        if let Some(secs) = secs("CLAUDEBOT_SLEEP_TASK_INTERVAL_SECS") {
+            config.sleep_task_interval = Duration::from_secs(secs.max(1));
+        }
+
+        let flag = |key: &str| {
+            lookup(key).and_then(|s| match s.trim().to_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Some(true),
+                "0" | "false" | "no" | "off" => Some(false),
+                _ => {
+                    tracing::warn!("Ignoring {}={:?}, expected true or false", key, s);
+                    None
+                }
+            })
+        };
+        if let Some(on) = flag("CLAUDEBOT_ENABLE_CONSOLIDATION") {
+            config.enable_consolidation = on;
+        }
+        if let Some(on) = flag("CLAUDEBOT_ENABLE_DECAY") {
+            config.enable_decay = on;
+        }
+        if let Some(on) = flag("CLAUDEBOT_ENABLE_COMPRESSION") {
+            config.enable_compression = on;
+        }
+
+        config
+    }
+}
+
+/// Thresholds deciding which conversations get summarized into memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionConfig {
+    /// Only compress conversations idle for at least this long (default: 1 hour)
+    pub min_age: Duration,
+    /// Only compress conversations with more than this many messages (default: 20)
+    pub min_messages: usize,
+    /// Maximum conversations compressed per sleep cycle (default: 3)
+    pub max_per_cycle: usize,
+    /// Target size of the summary relative to the original (default: 0.3)
+    pub target_ratio: f32,
+    /// Skip conversations whose importance score is below this (default: 0.15)
+    pub min_importance: f64,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            min_age: Duration::from_secs(3600),
+            min_messages: 20,
+            max_per_cycle: 3,
+            target_ratio: 0.3,
+            min_importance: 0.15,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Create config from environment variables
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(secs) = std::env::var("CLAUDEBOT_COMPRESS_MIN_AGE_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+        {
+            config.min_age = Duration::from_secs(secs);
+        }
+
+        if let Some(count) = std::env::var("CLAUDEBOT_COMPRESS_MIN_MESSAGES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+        {
+            config.min_messages = count;
+        }
+
+        if let Some(count) = std::env::var("CLAUDEBOT_COMPRESS_MAX_PER_CYCLE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+        {
+            config.max_per_cycle = count;
+        }
+
+        if let Some(ratio) = std::env::var("CLAUDEBOT_COMPRESS_TARGET_RATIO")
+            .ok()
+            .and_then(|s| s.parse::<f32>().ok())
+        {
+            config.target_ratio = ratio.clamp(0.05, 1.0);
+        }
+
+        if let Some(score) = std::env::var("CLAUDEBOT_COMPRESS_MIN_IMPORTANCE")
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+        {
+            config.min_importance = score.clamp(0.0, 1.0);
+        }
+
+        config
+    }
+
+    /// Apply a per-chat override on top of these settings
+    pub fn with_override(&self, o: &CompressionOverride) -> Self {
+        Self {
+            min_age: o.min_age.unwrap_or(self.min_age),
+            min_messages: o.min_messages.unwrap_or(self.min_messages),
+            max_per_cycle: self.max_per_cycle,
+            target_ratio: o.target_ratio.unwrap_or(self.target_ratio),
+            min_importance: self.min_importance,
+        }
+    }
+}
+
+/// Per-chat compression override (unset fields fall back to the global config)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompressionOverride {
+    pub min_age: Option<Duration>,
+    pub min_messages: Option<usize>,
+    pub target_ratio: Option<f32>,
+}
+
+impl CompressionOverride {
+    /// Check if no field is overridden
+    pub fn is_empty(&self) -> bool {
+        self.min_age.is_none() && self.min_messages.is_none() && self.target_ratio.is_none()
+    }
+}
+
 /// Lifecycle manager for wake/sleep cycle
 pub struct LifecycleManager {
     state: AtomicU8,
     last_activity: AtomicI64,
     config: LifecycleConfig,
+    compression_overrides: RwLock<HashMap<i64, CompressionOverride>>,
+    /// Where compression overrides are saved (None = memory only)
+    overrides_path: Option<PathBuf>,
     wake_notify: Notify,
     stats: LifecycleStats,
+    /// Callbacks registered by `run`, shared with `force_sleep`
+    callbacks: RwLock<Option<Arc<LifecycleCallbacks>>>,
+    /// Serializes sleep task runs between the loop and `force_sleep`
+    sleep_tasks: tokio::sync::Mutex<()>,
+    /// Unix time the sleep tasks last ran
+    last_sleep_run: AtomicI64,
+    /// Enter sleep instead of wake when the current processing ends
+    sleep_pending: AtomicBool,
 }
 
 /// Statistics for lifecycle monitoring
@@ -85,13 +251,41 @@ pub struct LifecycleStats {
 impl LifecycleManager {
     /// Create a new lifecycle manager
     pub fn new(config: LifecycleConfig) -> Arc<Self> {
-        Arc::new(Self {
+        Arc::new(Self::unshared(config))
+    }
+
+    fn unshared(config: LifecycleConfig) -> Self {
+        Self {
             state: AtomicU8::new(State::Wake as u8),
             last_activity: AtomicI64::new(chrono::Utc::now().timestamp()),
             config,
+            compression_overrides: RwLock::new(HashMap::new()),
+            overrides_path: None,
             wake_notify: Notify::new(),
             stats: LifecycleStats::default(),
-        })
+            callbacks: RwLock::new(None),
+            sleep_tasks: tokio::sync::Mutex::new(()),
+            last_sleep_run: AtomicI64::new(0),
+            sleep_pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Create a manager whose compression overrides are loaded from and
+    /// saved to `path`, so they survive restarts
+    ///
+    /// A missing or unreadable file starts with no overrides.
+    pub fn with_overrides_file(config: LifecycleConfig, path: &Path) -> Arc<Self> {
+        let overrides: HashMap<i64, CompressionOverride> = match std::fs::read_to_string(path) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable compression overrides {:?}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        let mut manager = Self::unshared(config);
+        manager.compression_overrides = RwLock::new(overrides);
+        manager.overrides_path = Some(path.to_path_buf());
+        Arc::new(manager)
     }
 
     /// Create with default configuration
@@ -99,6 +293,11 @@ impl LifecycleManager {
         Self::new(LifecycleConfig::default())
     }
 
+    /// The active configuration
+    pub fn config(&self) -> &LifecycleConfig {
+        &self.config
+    }
+
     /// Get current state
     pub fn current_state(&self) -> State {
         State::from(self.state.load(Ordering::Relaxed))
@@ -143,29 +342,35 @@ impl LifecycleManager {
     /// Mark processing complete
     pub fn end_processing(&self) {
         self.record_activity();
-        self.transition_to(State::Wake);
+        if self.sleep_pending.swap(false, Ordering::Relaxed) {
+            self.enter_sleep();
+        } else {
+            self.transition_to(State::Wake);
+        }
     }
 
-    /// Manually force sleep state (for /sleep command)
-    /// Returns true if transitioned, false if already sleeping or processing
-    pub fn force_sleep(&self) -> bool {
-        let current = self.current_state();
-        if current == State::Processing {
-            // Don't interrupt active work
-            return false;
-        }
-        if current == State::Sleep {
-            // Already sleeping
-            return false;
+    /// Manually force sleep (for /sleep command)
+    ///
+    /// Runs the sleep tasks right away instead of waiting out the idle
+    /// timeout, then enters sleep. When called while processing (e.g. from
+    /// the command handler itself), sleep starts once that processing ends.
+    pub async fn force_sleep(&self) -> SleepReport {
+        let report = match self.registered_callbacks() {
+            Some(callbacks) => self.run_sleep_tasks(&callbacks).await,
+            None => SleepReport::default(),
+        };
+        if self.current_state() == State::Processing {
+            self.sleep_pending.store(true, Ordering::Relaxed);
+        } else {
+            self.enter_sleep();
         }
-        self.transition_to(State::Sleep);
-        self.stats.sleep_count.fetch_add(1, Ordering::Relaxed);
         info!("Forced sleep via command");
-        true
+        report
     }
 
     /// Manually force wake state (for /wake command)
     pub fn force_wake(&self) {
+        self.sleep_pending.store(false, Ordering::Relaxed);
         self.record_activity();
         if self.current_state() == State::Sleep {
             self.transition_to(State::Wake);
@@ -175,6 +380,138 @@ impl LifecycleManager {
         }
     }
 
+    /// Global compression settings
+    pub fn compression_config(&self) -> &CompressionConfig {
+        &self.config.compression
+    }
+
+    /// Effective compression settings for a chat (global config + override)
+    pub fn compression_for_chat(&self, chat_id: i64) -> CompressionConfig {
+        let overrides = self.compression_overrides.read().unwrap_or_else(|e| e.into_inner());
+        match overrides.get(&chat_id) {
+            Some(o) => self.config.compression.with_override(o),
+            None => self.config.compression.clone(),
+        }
+    }
+
+    /// Get the override for a chat, if any
+    pub fn compression_override(&self, chat_id: i64) -> Option<CompressionOverride> {
+        let overrides = self.compression_overrides.read().unwrap_or_else(|e| e.into_inner());
+        overrides.get(&chat_id).cloned()
+    }
+
+    /// Update the override for a chat
+    pub fn update_compression_override<F>(&self, chat_id: i64, f: F)
+    where
+        F: FnOnce(&mut CompressionOverride),
+    {
+        let mut overrides = self.compression_overrides.write().unwrap_or_else(|e| e.into_inner());
+        let entry = overrides.entry(chat_id).or_default();
+        f(entry);
+        if entry.is_empty() {
+            overrides.remove(&chat_id);
+        }
+        self.save_compression_overrides(&overrides);
+    }
+
+    /// Remove the override for a chat. Returns true if one existed.
+    pub fn clear_compression_override(&self, chat_id: i64) -> bool {
+        let mut overrides = self.compression_overrides.write().unwrap_or_else(|e| e.into_inner());
+        let removed = overrides.remove(&chat_id).is_some();
+        if removed {
+            self.save_compression_overrides(&overrides);
+        }
+        removed
+    }
+
+    fn save_compression_overrides(&self, overrides: &HashMap<i64, CompressionOverride>) {
+        let Some(path) = &self.overrides_path else {
+            return;
+        };
+        let result = serde_json::to_vec_pretty(overrides)
+            .map_err(std::io::Error::other)
+            .and_then(|json| {
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, path)
+            });
+        if let Err(e) = result {
+            warn!("Failed to save compression overrides to {:?}: {}", path, e);
+        }
+    }
+
+    /// Loosest thresholds across the global config and all overrides
+    ///
+    /// Used to pre-filter candidate conversations before applying
+    /// each chat's own settings.
+    pub fn compression_candidate_thresholds(&self) -> (Duration, usize) {
+        let overrides = self.compression_overrides.read().unwrap_or_else(|e| e.into_inner());
+        let global = &self.config.compression;
+        overrides.values().fold((global.min_age, global.min_messages), |(age, msgs), o| {
+            (
+                o.min_age.map_or(age, |a| a.min(age)),
+                o.min_messages.map_or(msgs, |m| m.min(msgs)),
+            )
+        })
+    }
+
+    fn enter_sleep(&self) {
+        if self.current_state() != State::Sleep {
+            self.transition_to(State::Sleep);
+            self.stats.sleep_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn registered_callbacks(&self) -> Option<Arc<LifecycleCallbacks>> {
+        self.callbacks.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Run every enabled sleep task once, in order
+    async fn run_sleep_tasks(&self, callbacks: &LifecycleCallbacks) -> SleepReport {
+        let _running = self.sleep_tasks.lock().await;
+        let started = Instant::now();
+        let tasks: [(&'static str, bool, &Option<SleepCallback>, Option<&AtomicI64>); 5] = [
+            ("consolidation", self.config.enable_consolidation, &callbacks.on_consolidate, Some(&self.stats.consolidations)),
+            ("decay", self.config.enable_decay, &callbacks.on_decay, Some(&self.stats.decays_applied)),
+            ("compression", self.config.enable_compression, &callbacks.on_compress, Some(&self.stats.compressions)),
+            ("digest", true, &callbacks.on_digest, None),
+            ("cache warming", true, &callbacks.on_warm_cache, None),
+        ];
+
+        let mut report = SleepReport::default();
+        for (task, enabled, callback, counter) in tasks {
+            let outcome = match callback {
+                None => SleepTaskOutcome::NotConfigured,
+                Some(_) if !enabled => SleepTaskOutcome::Disabled,
+                Some(cb) => {
+                    let task_started = Instant::now();
+                    match cb().await {
+                        Ok(()) => {
+                            if let Some(counter) = counter {
+                                counter.fetch_add(1, Ordering::Relaxed);
+                            }
+                            SleepTaskOutcome::Ran(task_started.elapsed())
+                        }
+                        Err(e) => {
+                            warn!("Sleep task {} failed: {}", task, e);
+                            SleepTaskOutcome::Failed(e.to_string())
+                        }
+                    }
+                }
+            };
+            report.tasks.push((task, outcome));
+        }
+        report.elapsed = started.elapsed();
+        self.last_sleep_run.store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+        report
+    }
+
+    /// Whether the sleep tasks ran within the last sleep interval
+    fn ran_recently(&self) -> bool {
+        let last = self.last_sleep_run.load(Ordering::Relaxed);
+        chrono::Utc::now().timestamp() - last < self.config.sleep_task_interval.as_secs() as i64
+    }
+
     /// Transition to a new state
     fn transition_to(&self, new_state: State) {
         let old = self.state.swap(new_state as u8, Ordering::Relaxed);
@@ -186,39 +523,15 @@ impl LifecycleManager {
     /// Run the lifecycle loop (call this in a background task)
     pub async fn run(self: Arc<Self>, callbacks: LifecycleCallbacks) {
         info!("Lifecycle manager started");
+        let callbacks = Arc::new(callbacks);
+        *self.callbacks.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::clone(&callbacks));
 
         loop {
             match self.current_state() {
                 State::Sleep => {
-                    // Run background tasks
-                    if self.config.enable_consolidation {
-                        if let Some(ref cb) = callbacks.on_consolidate {
-                            if let Err(e) = cb().await {
-                                warn!("Consolidation failed: {}", e);
-                            } else {
-                                self.stats.consolidations.fetch_add(1, Ordering::Relaxed);
-                            }
-                        }
-                    }
-
-                    if self.config.enable_decay {
-                        if let Some(ref cb) = callbacks.on_decay {
-                            if let Err(e) = cb().await {
-                                warn!("Decay failed: {}", e);
-                            } else {
-                                self.stats.decays_applied.fetch_add(1, Ordering::Relaxed);
-                            }
-                        }
-                    }
-
-                    if self.config.enable_compression {
-                        if let Some(ref cb) = callbacks.on_compress {
-                            if let Err(e) = cb().await {
-                                warn!("Compression failed: {}", e);
-                            } else {
-                                self.stats.compressions.fetch_add(1, Ordering::Relaxed);
-                            }
-                        }
+                    // Run background tasks (unless a forced sleep just did)
+                    if !self.ran_recently() {
+                        self.run_sleep_tasks(&callbacks).await;
                     }
 
                     // Wait for wake signal or interval
@@ -238,8 +551,7 @@ impl LifecycleManager {
                     let timeout_secs = self.config.idle_timeout.as_secs() as i64;
 
                     if idle > timeout_secs {
-                        self.transition_to(State::Sleep);
-                        self.stats.sleep_count.fetch_add(1, Ordering::Relaxed);
+                        self.enter_sleep();
                         info!("Entering sleep mode after {}s idle", idle);
                     } else {
                         // Short sleep before checking again
@@ -281,14 +593,67 @@ pub struct LifecycleStatsSnapshot {
     pub compressions: i64,
 }
 
+/// How one sleep task went
+#[derive(Debug, Clone, PartialEq)]
+pub enum SleepTaskOutcome {
+    Ran(Duration),
+    Failed(String),
+    /// Turned off in `LifecycleConfig`
+    Disabled,
+    /// No callback registered
+    NotConfigured,
+}
+
+/// What a round of sleep tasks did
+#[derive(Debug, Clone, Default)]
+pub struct SleepReport {
+    pub tasks: Vec<(&'static str, SleepTaskOutcome)>,
+    pub elapsed: Duration,
+}
+
+impl SleepReport {
+    /// Names of the tasks that ran successfully
+    pub fn ran(&self) -> Vec<&'static str> {
+        self.tasks
+            .iter()
+            .filter(|(_, o)| matches!(o, SleepTaskOutcome::Ran(_)))
+            .map(|(task, _)| *task)
+            .collect()
+    }
+
+    /// One line per task
+    pub fn format(&self) -> String {
+        if self.tasks.is_empty() {
+            return "No background tasks registered (lifecycle loop not running)".to_string();
+        }
+        self.tasks
+            .iter()
+            .map(|(task, outcome)| match outcome {
+                SleepTaskOutcome::Ran(took) => format!("✅ {} ({:.1}s)", task, took.as_secs_f64()),
+                SleepTaskOutcome::Failed(e) => format!("❌ {}: {}", task, e),
+                SleepTaskOutcome::Disabled => format!("⏸ {} (disabled)", task),
+                SleepTaskOutcome::NotConfigured => format!("➖ {} (not configured)", task),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A background task run during sleep
+pub type SleepCallback = Box<dyn Fn() -> futures_util::future::BoxFuture<'static, anyhow::Result<()>> + Send + Sync>;
+
 /// Callbacks for lifecycle events
 pub struct LifecycleCallbacks {
     /// Called during sleep to consolidate similar memories
-    pub on_consolidate: Option<Box<dyn Fn() -> futures_util::future::BoxFuture<'static, anyhow::Result<()>> + Send + Sync>>,
+    pub on_consolidate: Option<SleepCallback>,
     /// Called during sleep to apply Ebbinghaus decay
-    pub on_decay: Option<Box<dyn Fn() -> futures_util::future::BoxFuture<'static, anyhow::Result<()>> + Send + Sync>>,
+    pub on_decay: Option<SleepCallback>,
     /// Called during sleep to compress old conversations
-    pub on_compress: Option<Box<dyn Fn() -> futures_util::future::BoxFuture<'static, anyhow::Result<()>> + Send + Sync>>,
+    pub on_compress: Option<SleepCallback>,
+    /// Called during sleep to send the daily digest once it is due
+    pub on_digest: Option<SleepCallback>,
+    /// Called during sleep to pre-cache answers to frequent prompts
+    pub on_warm_cache: Option<SleepCallback>,
 }
 
 impl Default for LifecycleCallbacks {
@@ -297,6 +662,8 @@ impl Default for LifecycleCallbacks {
             on_consolidate: None,
             on_decay: None,
             on_compress: None,
+            on_digest: None,
+            on_warm_cache: None,
         }
     }
 }
@@ -324,6 +691,30 @@ impl Drop for ProcessingGuard {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_config_from_env_overrides() {
+        let config = LifecycleConfig::from_lookup(|_| None);
+        assert_eq!(config.idle_timeout, Duration::from_secs(86400));
+        assert_eq!(config.sleep_task_interval, Duration::from_secs(3600));
+        assert!(config.enable_consolidation && config.enable_decay && config.enable_compression);
+
+        let config = LifecycleConfig::from_lookup(|key| {
+            match key {
+                "CLAUDEBOT_IDLE_TIMEOUT_SECS" => Some("120"),
+                "CLAUDEBOT_SLEEP_TASK_INTERVAL_SECS" => Some("0"),
+                "CLAUDEBOT_ENABLE_DECAY" => Some("off"),
+                "CLAUDEBOT_ENABLE_COMPRESSION" => Some("maybe"),
+                _ => None,
+            }
+            .map(String::from)
+        });
+        assert_eq!(config.idle_timeout, Duration::from_secs(120));
+        assert_eq!(config.sleep_task_interval, Duration::from_secs(1));
+        assert!(config.enable_consolidation);
+        assert!(!config.enable_decay);
+        assert!(config.enable_compression);
+    }
+
     #[test]
     fn test_state_transitions() {
         let manager = LifecycleManager::with_defaults();
@@ -372,4 +763,97 @@ mod tests {
         // Guard dropped, should be back to Wake
         assert!(!manager.is_processing());
     }
+
+    #[test]
+    fn test_compression_override() {
+        let manager = LifecycleManager::with_defaults();
+        let global = manager.compression_config().clone();
+
+        assert_eq!(manager.compression_for_chat(42), global);
+
+        manager.update_compression_override(42, |o| {
+            o.min_messages = Some(100);
+            o.target_ratio = Some(0.5);
+        });
+
+        let chat = manager.compression_for_chat(42);
+        assert_eq!(chat.min_messages, 100);
+        assert_eq!(chat.target_ratio, 0.5);
+        assert_eq!(chat.min_age, global.min_age);
+        assert_eq!(manager.compression_for_chat(7), global);
+
+        assert!(manager.clear_compression_override(42));
+        assert_eq!(manager.compression_for_chat(42), global);
+        assert!(!manager.clear_compression_override(42));
+    }
+
+    #[test]
+    fn test_compression_overrides_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("compression.json");
+
+        let manager = LifecycleManager::with_overrides_file(LifecycleConfig::default(), &path);
+        manager.update_compression_override(42, |o| o.min_age = Some(Duration::from_secs(1800)));
+        manager.update_compression_override(7, |o| o.min_messages = Some(5));
+        assert!(manager.clear_compression_override(7));
+
+        let reopened = LifecycleManager::with_overrides_file(LifecycleConfig::default(), &path);
+        assert_eq!(reopened.compression_for_chat(42).min_age, Duration::from_secs(1800));
+        assert!(reopened.compression_override(7).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_force_sleep_runs_tasks() {
+        let manager = LifecycleManager::new(LifecycleConfig {
+            enable_decay: false,
+            ..LifecycleConfig::default()
+        });
+        let runs = Arc::new(AtomicI64::new(0));
+        let counting = |runs: &Arc<AtomicI64>| -> Option<SleepCallback> {
+            let runs = Arc::clone(runs);
+            Some(Box::new(move || {
+                let runs = Arc::clone(&runs);
+                Box::pin(async move {
+                    runs.fetch_add(1, Ordering::Relaxed);
+                    Ok(())
+                })
+            }))
+        };
+        let callbacks = LifecycleCallbacks {
+            on_consolidate: counting(&runs),
+            on_decay: counting(&runs),
+            on_compress: Some(Box::new(|| Box::pin(async { anyhow::bail!("llama down") }))),
+            on_digest: None,
+            on_warm_cache: None,
+        };
+        *manager.callbacks.write().unwrap() = Some(Arc::new(callbacks));
+
+        // From inside a command handler: sleep starts when processing ends
+        manager.start_processing();
+        let report = manager.force_sleep().await;
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        assert_eq!(report.ran(), vec!["consolidation"]);
+        assert_eq!(report.tasks[1].1, SleepTaskOutcome::Disabled);
+        assert!(matches!(report.tasks[2].1, SleepTaskOutcome::Failed(_)));
+        assert_eq!(report.tasks[3].1, SleepTaskOutcome::NotConfigured);
+        assert!(manager.is_processing());
+        manager.end_processing();
+        assert!(manager.is_sleeping());
+        assert!(manager.ran_recently());
+
+        manager.force_wake();
+        assert_eq!(manager.current_state(), State::Wake);
+        assert_eq!(manager.get_stats().consolidations, 1);
+    }
+
+    #[test]
+    fn test_compression_candidate_thresholds() {
+        let manager = LifecycleManager::with_defaults();
+        manager.update_compression_override(1, |o| o.min_age = Some(Duration::from_secs(60)));
+        manager.update_compression_override(2, |o| o.min_messages = Some(5));
+
+        let (age, msgs) = manager.compression_candidate_thresholds();
+        assert_eq!(age, Duration::from_secs(60));
+        assert_eq!(msgs, 5);
+    }
 }
@@ -43,6 +43,19 @@ pub struct LlamaWorker {
     client: reqwest::Client,
 }
 
+/// Keywords that classify a query as `Complex` without asking Llama
+pub const COMPLEX_KEYWORDS: &[&str] = &[
+    "architect", "security", "design", "optimize", "refactor",
+    "why", "tradeoff", "compare", "evaluate", "review",
+    "circle", "audit", "vulnerability", "performance",
+];
+
+/// Keywords that classify a query as `Simple` (checked after `COMPLEX_KEYWORDS`)
+pub const SIMPLE_KEYWORDS: &[&str] = &[
+    "what is", "how to", "show me", "list", "find",
+    "where", "status", "version", "help", "usage",
+];
+
 /// Query complexity for model routing
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QueryComplexity {
@@ -125,6 +138,36 @@ impl LlamaWorker {
         }
     }
 
+    /// Configured models (generation, embedding) that Ollama hasn't pulled
+    pub async fn missing_models(&self) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct Tags {
+            models: Vec<Tag>,
+        }
+        #[derive(Deserialize)]
+        struct Tag {
+            name: String,
+        }
+
+        let tags: Tags = self
+            .client
+            .get(format!("{}/api/tags", self.config.ollama_url))
+            .timeout(Duration::from_secs(2))
+            .send()
+            .await
+            .context("Ollama not reachable")?
+            .json()
+            .await
+            .context("Invalid /api/tags response")?;
+        let names: Vec<&str> = tags.models.iter().map(|t| t.name.as_str()).collect();
+
+        Ok([&self.config.model, &self.config.embedding_model]
+            .into_iter()
+            .filter(|model| !names.iter().any(|name| model_matches(name, model)))
+            .cloned()
+            .collect())
+    }
+
     /// Generate text with Llama
     /// Generate text completion using Llama
     pub async fn generate(&self, prompt: &str) -> Result<String> {
@@ -230,32 +273,28 @@ impl LlamaWorker {
     /// - Moderate: Sonnet ($3/M) - implementation, analysis
     /// - Complex: Opus ($15/M) - architecture, security, deep reasoning
     pub async fn classify_complexity(&self, query: &str) -> QueryComplexity {
+        self.classify_complexity_explained(query).await.0
+    }
+
+    /// Classify query complexity and report which signal decided it
+    pub async fn classify_complexity_explained(&self, query: &str) -> (QueryComplexity, String) {
         // Fast keyword-based classification first
         let lower = query.to_lowercase();
 
         // Complex indicators (requires deep reasoning)
-        let complex_keywords = [
-            "architect", "security", "design", "optimize", "refactor",
-            "why", "tradeoff", "compare", "evaluate", "review",
-            "circle", "audit", "vulnerability", "performance",
-        ];
-        if complex_keywords.iter().any(|k| lower.contains(k)) {
-            return QueryComplexity::Complex;
+        if let Some(k) = COMPLEX_KEYWORDS.iter().find(|k| lower.contains(*k)) {
+            return (QueryComplexity::Complex, format!("complex keyword '{}'", k));
         }
 
         // Simple indicators (factual, quick)
-        let simple_keywords = [
-            "what is", "how to", "show me", "list", "find",
-            "where", "status", "version", "help", "usage",
-        ];
-        if simple_keywords.iter().any(|k| lower.contains(k)) {
-            return QueryComplexity::Simple;
+        if let Some(k) = SIMPLE_KEYWORDS.iter().find(|k| lower.contains(*k)) {
+            return (QueryComplexity::Simple, format!("simple keyword '{}'", k));
         }
 
         // For uncertain cases, use Llama
         if !self.is_available().await {
             debug!("Ollama unavailable, defaulting to Moderate");
-            return QueryComplexity::Moderate;
+            return (QueryComplexity::Moderate, "no keywords, Ollama unavailable".to_string());
         }
 
         let prompt = format!(
@@ -271,17 +310,18 @@ impl LlamaWorker {
         match self.generate(&prompt).await {
             Ok(response) => {
                 let upper = response.to_uppercase();
-                if upper.contains("SIMPLE") {
+                let complexity = if upper.contains("SIMPLE") {
                     QueryComplexity::Simple
                 } else if upper.contains("COMPLEX") {
                     QueryComplexity::Complex
                 } else {
                     QueryComplexity::Moderate
-                }
+                };
+                (complexity, "Llama classification".to_string())
             }
             Err(e) => {
                 warn!("Llama classification failed: {}, defaulting to Moderate", e);
-                QueryComplexity::Moderate
+                (QueryComplexity::Moderate, "Llama classification failed".to_string())
             }
         }
     }
@@ -325,14 +365,17 @@ impl LlamaWorker {
     }
 
     /// Extract entities from text for graph memory
-    pub async fn extract_entities(&self, text: &str) -> Result<Vec<ExtractedEntity>> {
+    ///
+    /// `types` is the graph's entity taxonomy; the model is told to use only those.
+    pub async fn extract_entities(&self, text: &str, types: &[String]) -> Result<Vec<ExtractedEntity>> {
         let prompt = format!(
             "Extract named entities from this text. Return as JSON array.\n\
-            Types: person, project, technology, concept, file, decision\n\n\
+            entity_type must be exactly one of: {}\n\n\
             Example output:\n\
             [{{\"name\": \"Velofi\", \"entity_type\": \"project\", \"context\": \"trading platform\", \"confidence\": 0.9}}]\n\n\
             Text: {}\n\n\
             Entities (JSON only):",
+            types.join(", "),
             text
         );
 
@@ -410,6 +453,19 @@ impl LlamaWorker {
         self.generate(&prompt).await
     }
 
+    /// Summarize a long user message, keeping what a reply to it would need
+    pub async fn summarize_text(&self, text: &str) -> Result<String> {
+        let prompt = format!(
+            "Summarize this text so someone who hasn't read it can respond to it.\n\
+            Preserve: questions and instructions, key facts, names, numbers and code identifiers.\n\n\
+            Text:\n{}\n\n\
+            Summary:",
+            text
+        );
+
+        self.generate(&prompt).await
+    }
+
     /// Check if text contains sensitive information that shouldn't be cached
     pub async fn contains_sensitive_info(&self, text: &str) -> bool {
         // Quick keyword check first
@@ -438,6 +494,11 @@ impl Default for LlamaWorker {
     }
 }
 
+/// Whether an installed Ollama tag satisfies a configured model (`x` matches `x:latest`)
+fn model_matches(installed: &str, wanted: &str) -> bool {
+    installed == wanted || (!wanted.contains(':') && installed == format!("{}:latest", wanted))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -454,6 +515,14 @@ mod tests {
         assert!(matches!(result, QueryComplexity::Complex));
     }
 
+    #[test]
+    fn test_model_matches() {
+        assert!(model_matches("nomic-embed-text:latest", "nomic-embed-text"));
+        assert!(model_matches("llama3.2:3b", "llama3.2:3b"));
+        assert!(!model_matches("llama3.2:1b", "llama3.2:3b"));
+        assert!(!model_matches("llama3.2:3b", "llama3.2"));
+    }
+
     #[tokio::test]
     async fn test_sensitive_detection() {
         let worker = LlamaWorker::new();
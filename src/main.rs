@@ -4,6 +4,7 @@
 //! - Default: MCP server over stdio
 //! - --telegram / -t: Telegram bot mode
 //! - --grpc-server / -g: gRPC bridge server mode
+//! - --mcp-replay <file>: replay a captured MCP session and print responses
 
 use claudebot_mcp::{Config, McpServer, GrpcBridgeServer};
 use tracing::{info, Level};
@@ -19,6 +20,11 @@ async fn main() -> anyhow::Result<()> {
     let telegram_mode = args.iter().any(|a| a == "--telegram" || a == "-t");
     let grpc_server_mode = args.iter().any(|a| a == "--grpc-server" || a == "-g");
     let help_mode = args.iter().any(|a| a == "--help" || a == "-h");
+    let replay_file = args
+        .iter()
+        .position(|a| a == "--mcp-replay")
+        .map(|i| args.get(i + 1).cloned().ok_or_else(|| anyhow::anyhow!("--mcp-replay requires a file")))
+        .transpose()?;
 
     if help_mode {
         println!("ClaudeBot MCP Server v{}", env!("CARGO_PKG_VERSION"));
@@ -28,6 +34,7 @@ async fn main() -> anyhow::Result<()> {
         println!("Options:");
         println!("  --telegram, -t     Run as Telegram bot");
         println!("  --grpc-server, -g  Run as gRPC bridge server");
+        println!("  --mcp-replay <file>  Replay a captured MCP session (JSON-RPC lines) and print responses");
         println!("  --help, -h         Show this help");
         println!();
         println!("Default: Run as MCP server (stdio)");
@@ -70,7 +77,11 @@ async fn main() -> anyhow::Result<()> {
         tracing::subscriber::set_global_default(subscriber)?;
     }
 
-    if grpc_server_mode {
+    if let Some(file) = replay_file {
+        let config = Config::from_env()?;
+        let server = McpServer::new(config).await?;
+        server.replay(std::path::Path::new(&file)).await?;
+    } else if grpc_server_mode {
         info!("ClaudeBot gRPC Bridge Server v{}", env!("CARGO_PKG_VERSION"));
 
         let server = GrpcBridgeServer::from_env()?;
@@ -2,14 +2,61 @@
 //!
 //! Implements JSON-RPC 2.0 over stdio for Model Context Protocol.
 //! Reference: https://modelcontextprotocol.io/specification
+//!
+//! Every request is recorded in an in-memory call log, exposed through the
+//! `mcp_call_log` tool. `McpServer::replay` re-runs a captured session.
+//!
+//! Stored memories (`memory://<id>`) and chat transcripts
+//! (`conversation://<chat_id>`) are readable through `resources/list` and
+//! `resources/read`, subject to the same policy as tools under the names
+//! `memory_resources` and `conversation_resources`.
+//!
+//! A `tools/call` carrying `_meta.progressToken` gets `notifications/progress`
+//! while it runs, for tools that report progress (`circle_run`, per phase).
+//!
+//! Tool calls go through `McpToolPolicy`: each tool requires a permission
+//! level (`permissions::mcp_tool_level`), the client gets `MCP_CLIENT_LEVEL`
+//! (supervised unless raised, capped by safe mode), and operators can allow-
+//! or deny-list tool names.
+//! Rejected calls get a `PERMISSION_DENIED` error and aren't listed by
+//! `tools/list`.
 
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use crate::config::Config;
-use crate::tools::ToolRegistry;
+use crate::conversation::{self, ConversationStore};
+use crate::memory_backend::{MemoryBackend, MemoryBackendUrl};
+use crate::permissions::{mcp_tool_level, safe_mode_from_env, PermissionLevel, PermissionManager};
+use crate::tools::{ToolDefinition, ToolRegistry};
+
+/// Requests kept in the call log
+pub const CALL_LOG_CAPACITY: usize = 200;
+
+/// Longest params summary stored per call
+const PARAMS_SUMMARY_CHARS: usize = 200;
+
+/// Name of the built-in diagnostic tool
+const CALL_LOG_TOOL: &str = "mcp_call_log";
+
+/// Resources per `resources/list` page
+pub const RESOURCE_PAGE_SIZE: usize = 100;
+
+/// Characters of memory content used as a resource name
+const RESOURCE_NAME_CHARS: usize = 60;
+
+const MEMORY_SCHEME: &str = "memory://";
+const CONVERSATION_SCHEME: &str = "conversation://";
+
+/// Policy names for the two resource kinds
+const MEMORY_RESOURCES: &str = "memory_resources";
+const CONVERSATION_RESOURCES: &str = "conversation_resources";
 
 /// JSON-RPC 2.0 Request
 #[derive(Debug, Clone, Deserialize)]
@@ -88,12 +135,121 @@ pub mod error_codes {
     pub const TOOL_NOT_FOUND: i32 = -32000;
     pub const TOOL_EXECUTION_ERROR: i32 = -32001;
     pub const RESOURCE_NOT_FOUND: i32 = -32002;
+    pub const PERMISSION_DENIED: i32 = -32003;
+}
+
+/// Which tools the MCP client may call
+pub struct McpToolPolicy {
+    permissions: PermissionManager,
+    /// When set, only these tools are callable
+    allowlist: Option<HashSet<String>>,
+    denylist: HashSet<String>,
+}
+
+impl McpToolPolicy {
+    /// Client at `level` (capped at Supervised in safe mode)
+    pub fn new(level: PermissionLevel, safe_mode: bool) -> Self {
+        Self {
+            permissions: PermissionManager::new().with_default_level(level).with_safe_mode(safe_mode),
+            allowlist: None,
+            denylist: HashSet::new(),
+        }
+    }
+
+    /// Only allow the named tools (still subject to the level)
+    pub fn with_allowlist<'a>(mut self, tools: impl IntoIterator<Item = &'a str>) -> Self {
+        self.allowlist = Some(tools.into_iter().map(String::from).collect());
+        self
+    }
+
+    /// Never allow the named tools
+    pub fn with_denylist<'a>(mut self, tools: impl IntoIterator<Item = &'a str>) -> Self {
+        self.denylist = tools.into_iter().map(String::from).collect();
+        self
+    }
+
+    /// `MCP_CLIENT_LEVEL` (default supervised), `MCP_TOOL_ALLOWLIST`,
+    /// `MCP_TOOL_DENYLIST` (comma-separated) and `CLAUDEBOT_SAFE_MODE`
+    ///
+    /// Tools that run Claude (`claude_complete`, `circle_run`) need an explicit
+    /// `MCP_CLIENT_LEVEL=autonomous`. An unrecognized level falls back to
+    /// restricted rather than granting more.
+    pub fn from_env() -> Self {
+        let level = match std::env::var("MCP_CLIENT_LEVEL") {
+            Ok(raw) => PermissionLevel::parse(&raw).unwrap_or_else(|| {
+                warn!("Unknown MCP_CLIENT_LEVEL '{}', using restricted", raw);
+                PermissionLevel::Restricted
+            }),
+            Err(_) => PermissionLevel::Supervised,
+        };
+        let names = |var: &str| -> Option<Vec<String>> {
+            let raw = std::env::var(var).ok()?;
+            Some(raw.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect())
+        };
+        let mut policy = Self::new(level, safe_mode_from_env());
+        if let Some(allow) = names("MCP_TOOL_ALLOWLIST").filter(|a| !a.is_empty()) {
+            policy = policy.with_allowlist(allow.iter().map(String::as_str));
+        }
+        if let Some(deny) = names("MCP_TOOL_DENYLIST") {
+            policy = policy.with_denylist(deny.iter().map(String::as_str));
+        }
+        policy
+    }
+
+    /// The client's effective level
+    pub fn client_level(&self) -> PermissionLevel {
+        self.permissions.default_level()
+    }
+
+    /// Ok, or why the call is rejected
+    pub fn check(&self, tool: &str) -> Result<(), String> {
+        if self.denylist.contains(tool) {
+            return Err(format!("Tool '{}' is disabled by MCP_TOOL_DENYLIST", tool));
+        }
+        if self.allowlist.as_ref().is_some_and(|allow| !allow.contains(tool)) {
+            return Err(format!("Tool '{}' is not in MCP_TOOL_ALLOWLIST", tool));
+        }
+        let required = mcp_tool_level(tool);
+        let level = self.client_level();
+        if !level.covers(required) {
+            return Err(format!(
+                "Tool '{}' requires {} permission; this MCP client is {}",
+                tool,
+                required.as_str(),
+                level.as_str()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resource kinds this client may list and read
+    pub fn resource_access(&self) -> ResourceAccess {
+        ResourceAccess {
+            memories: self.check(MEMORY_RESOURCES).is_ok(),
+            conversations: self.check(CONVERSATION_RESOURCES).is_ok(),
+        }
+    }
+}
+
+/// Which resource kinds a client may see
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceAccess {
+    pub memories: bool,
+    pub conversations: bool,
+}
+
+impl ResourceAccess {
+    pub const ALL: Self = Self {
+        memories: true,
+        conversations: true,
+    };
 }
 
 /// Server capabilities
 #[derive(Debug, Clone, Serialize)]
 pub struct ServerCapabilities {
     pub tools: Option<ToolCapabilities>,
+    pub resources: Option<ResourceCapabilities>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -102,6 +258,13 @@ pub struct ToolCapabilities {
     pub list_changed: bool,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceCapabilities {
+    pub subscribe: bool,
+    #[serde(rename = "listChanged")]
+    pub list_changed: bool,
+}
+
 /// Server info
 #[derive(Debug, Clone, Serialize)]
 pub struct ServerInfo {
@@ -109,11 +272,270 @@ pub struct ServerInfo {
     pub version: String,
 }
 
+/// One handled MCP request
+#[derive(Debug, Clone, Serialize)]
+pub struct McpCallRecord {
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub method: String,
+    /// Tool name for `tools/call`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// Compact, truncated JSON of the params
+    pub params: String,
+    pub duration_ms: u64,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Ring buffer of recent MCP requests
+pub struct McpCallLog {
+    capacity: usize,
+    entries: Mutex<VecDeque<McpCallRecord>>,
+}
+
+impl McpCallLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Record a call, dropping the oldest when full
+    pub fn record(&self, record: McpCallRecord) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= self.capacity {
+                entries.pop_front();
+            }
+            entries.push_back(record);
+        }
+    }
+
+    /// Most recent calls, newest first
+    pub fn recent(&self, limit: usize) -> Vec<McpCallRecord> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl Default for McpCallLog {
+    fn default() -> Self {
+        Self::new(CALL_LOG_CAPACITY)
+    }
+}
+
+/// Compact JSON of request params, truncated for the call log
+fn summarize_params(params: &serde_json::Value) -> String {
+    let json = params.to_string();
+    match json.char_indices().nth(PARAMS_SUMMARY_CHARS) {
+        Some((i, _)) => format!("{}…", &json[..i]),
+        None => json,
+    }
+}
+
+/// Outgoing JSON-RPC notifications, written to stdout as they arrive
+type NotificationSender = mpsc::UnboundedSender<serde_json::Value>;
+
+/// Sends `notifications/progress` for one request's `progressToken`
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    token: serde_json::Value,
+    tx: NotificationSender,
+}
+
+impl ProgressReporter {
+    /// Reporter for a request's params, None unless they carry a progress token
+    fn from_params(params: &serde_json::Value, tx: &NotificationSender) -> Option<Self> {
+        let token = params.get("_meta")?.get("progressToken")?;
+        (token.is_string() || token.is_i64() || token.is_u64()).then(|| Self {
+            token: token.clone(),
+            tx: tx.clone(),
+        })
+    }
+
+    /// Report progress; `progress` must increase with every call
+    pub fn report(&self, progress: f64, total: Option<f64>, message: &str) {
+        let mut params = serde_json::json!({
+            "progressToken": self.token,
+            "progress": progress,
+            "message": message
+        });
+        if let Some(total) = total {
+            params["total"] = serde_json::json!(total);
+        }
+        // The request may already have finished; a late update is simply dropped
+        let _ = self.tx.send(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/progress",
+            "params": params
+        }));
+    }
+}
+
+/// Extract the request from a line of a captured session
+///
+/// Accepts raw JSON-RPC lines and the server's debug log lines (`← {...}` for
+/// requests, `→ {...}` for responses). Responses, blank lines and anything
+/// that isn't a JSON object with a `method` are skipped.
+fn replay_request_line(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if line.starts_with('→') {
+        return None;
+    }
+    let json = line.find('{').map(|i| &line[i..])?;
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value.get("method")?;
+    Some(json)
+}
+
+/// Memories and conversations exposed as MCP resources
+///
+/// Listing pages through memories (newest first), then conversations (most
+/// recently active first). The cursor is `memory:<offset>` or
+/// `conversation:<offset>`.
+pub struct ResourceStore {
+    memory: Box<dyn MemoryBackend>,
+    conversations: Option<ConversationStore>,
+    page_size: usize,
+}
+
+impl ResourceStore {
+    pub fn new(memory: Box<dyn MemoryBackend>, conversations: Option<ConversationStore>) -> Self {
+        Self {
+            memory,
+            conversations,
+            page_size: RESOURCE_PAGE_SIZE,
+        }
+    }
+
+    /// One page of the resources `access` allows and the cursor of the next
+    /// page, if any
+    pub fn list(
+        &self,
+        cursor: Option<&str>,
+        access: ResourceAccess,
+    ) -> anyhow::Result<(Vec<serde_json::Value>, Option<String>)> {
+        let allowed = |kind: &str| match kind {
+            "memory" => access.memories,
+            "conversation" => access.conversations,
+            _ => false,
+        };
+        let (kind, offset) = match cursor {
+            None if access.memories => ("memory", 0),
+            None => ("conversation", 0),
+            Some(cursor) => cursor
+                .split_once(':')
+                .and_then(|(kind, offset)| Some((kind, offset.parse::<usize>().ok()?)))
+                .filter(|(kind, _)| allowed(kind))
+                .ok_or_else(|| anyhow::anyhow!("Invalid cursor: {}", cursor))?,
+        };
+
+        if kind == "memory" {
+            let mut entries = self.memory.list_page(offset, self.page_size + 1)?;
+            let more = entries.len() > self.page_size;
+            entries.truncate(self.page_size);
+            let resources = entries
+                .iter()
+                .map(|entry| {
+                    serde_json::json!({
+                        "uri": format!("{}{}", MEMORY_SCHEME, entry.id),
+                        "name": resource_name(&entry.content),
+                        "description": format!("{} memory from {}", entry.category, entry.source),
+                        "mimeType": "text/plain"
+                    })
+                })
+                .collect();
+            let next = if more {
+                Some(format!("memory:{}", offset + self.page_size))
+            } else {
+                self.conversations
+                    .as_ref()
+                    .filter(|_| access.conversations)
+                    .map(|_| "conversation:0".to_string())
+            };
+            return Ok((resources, next));
+        }
+
+        let Some(conversations) = self.conversations.as_ref().filter(|_| access.conversations) else {
+            return Ok((Vec::new(), None));
+        };
+        let mut chats = conversations.list_chats(offset, self.page_size + 1)?;
+        let more = chats.len() > self.page_size;
+        chats.truncate(self.page_size);
+        let resources = chats
+            .iter()
+            .map(|chat| {
+                serde_json::json!({
+                    "uri": format!("{}{}", CONVERSATION_SCHEME, chat.chat_id),
+                    "name": format!("Chat {}", chat.chat_id),
+                    "description": format!("{} messages", chat.message_count),
+                    "mimeType": "text/markdown"
+                })
+            })
+            .collect();
+        let next = more.then(|| format!("conversation:{}", offset + self.page_size));
+        Ok((resources, next))
+    }
+
+    /// Contents of a resource, or None if it doesn't exist
+    pub fn read(&self, uri: &str) -> anyhow::Result<Option<serde_json::Value>> {
+        if let Some(id) = uri.strip_prefix(MEMORY_SCHEME) {
+            return Ok(self.memory.get_by_id(id)?.map(|entry| {
+                serde_json::json!({ "uri": uri, "mimeType": "text/plain", "text": entry.content })
+            }));
+        }
+
+        if let Some(chat_id) = uri.strip_prefix(CONVERSATION_SCHEME) {
+            let chat_id: i64 = chat_id
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid chat id: {}", chat_id))?;
+            let Some(conversations) = &self.conversations else {
+                return Ok(None);
+            };
+            let messages = conversations.get_history(chat_id, conversations.max_messages())?;
+            if messages.is_empty() {
+                return Ok(None);
+            }
+            let text = conversation::format_markdown(&format!("Chat {}", chat_id), &messages);
+            return Ok(Some(serde_json::json!({ "uri": uri, "mimeType": "text/markdown", "text": text })));
+        }
+
+        anyhow::bail!("Unsupported resource URI: {}", uri)
+    }
+}
+
+/// First line of content, shortened for use as a resource name
+fn resource_name(content: &str) -> String {
+    let line = content.lines().next().unwrap_or("").trim();
+    match line.char_indices().nth(RESOURCE_NAME_CHARS) {
+        Some((i, _)) => format!("{}…", &line[..i]),
+        None => line.to_string(),
+    }
+}
+
+/// Write one JSON-RPC message as a line on stdout
+async fn write_message(stdout: &mut tokio::io::Stdout, message: &impl Serialize) -> anyhow::Result<()> {
+    let json = serde_json::to_string(message)?;
+    debug!("→ {}", json);
+
+    stdout.write_all(json.as_bytes()).await?;
+    stdout.write_all(b"\n").await?;
+    stdout.flush().await?;
+    Ok(())
+}
+
 /// MCP Server
 pub struct McpServer {
     #[allow(dead_code)]
     config: Arc<Config>,
     tools: Arc<tokio::sync::Mutex<ToolRegistry>>,
+    resources: Arc<tokio::sync::Mutex<ResourceStore>>,
+    call_log: Arc<McpCallLog>,
+    policy: McpToolPolicy,
 }
 
 impl McpServer {
@@ -122,7 +544,108 @@ impl McpServer {
         let config = Arc::new(config);
         let tools = Arc::new(tokio::sync::Mutex::new(ToolRegistry::new(config.clone()).await?));
 
-        Ok(Self { config, tools })
+        // Separate connections to the tool registry's memory db and the bot's conversations
+        let conversations = match config.conversation_db_path.as_deref().filter(|p| p.exists()) {
+            Some(path) => Some(ConversationStore::open(path)?),
+            None => None,
+        };
+        let resources = ResourceStore::new(MemoryBackendUrl::from_env(&config.db_path)?.open()?, conversations);
+
+        let policy = McpToolPolicy::from_env();
+        info!("MCP client permission level: {}", policy.client_level().as_str());
+
+        Ok(Self {
+            config,
+            tools,
+            resources: Arc::new(tokio::sync::Mutex::new(resources)),
+            call_log: Arc::new(McpCallLog::default()),
+            policy,
+        })
+    }
+
+    /// Recent request log
+    pub fn call_log(&self) -> Arc<McpCallLog> {
+        self.call_log.clone()
+    }
+
+    /// Replay a captured JSON-RPC session, printing each response to stdout
+    ///
+    /// Returns the number of requests replayed.
+    pub async fn replay(&self, path: &Path) -> anyhow::Result<usize> {
+        let session = tokio::fs::read_to_string(path).await?;
+        let mut stdout = tokio::io::stdout();
+        let mut replayed = 0;
+
+        for request in session.lines().filter_map(replay_request_line) {
+            replayed += 1;
+            stdout.write_all(format!("← {}\n", request).as_bytes()).await?;
+            let output = match self.handle_line(request, None).await {
+                Some(response) => serde_json::to_string(&response)?,
+                None => "(notification, no response)".to_string(),
+            };
+            stdout.write_all(format!("→ {}\n\n", output).as_bytes()).await?;
+        }
+        stdout.flush().await?;
+
+        info!("Replayed {} requests from {}", replayed, path.display());
+        Ok(replayed)
+    }
+
+    /// Parse and handle one JSON-RPC line; None when no response is due
+    ///
+    /// Progress notifications go to `notifications` when given.
+    async fn handle_line(&self, line: &str, notifications: Option<&NotificationSender>) -> Option<McpResponse> {
+        let response = match serde_json::from_str::<McpRequest>(line) {
+            Ok(request) => {
+                // Handle notification (no id) - no response needed
+                if request.id.is_none() && request.method == "notifications/initialized" {
+                    debug!("Received initialized notification");
+                    return None;
+                }
+                self.handle_logged(request, notifications).await
+            }
+            Err(e) => {
+                error!("Parse error: {}", e);
+                McpResponse::error(None, error_codes::PARSE_ERROR, format!("Parse error: {}", e))
+            }
+        };
+
+        // Don't send response for notifications
+        if response.id.is_none() && response.result.is_none() && response.error.is_none() {
+            return None;
+        }
+        Some(response)
+    }
+
+    /// Handle a request and record it in the call log
+    async fn handle_logged(&self, request: McpRequest, notifications: Option<&NotificationSender>) -> McpResponse {
+        let start = Instant::now();
+        let method = request.method.clone();
+        let tool = (method == "tools/call")
+            .then(|| request.params.get("name").and_then(|v| v.as_str()).map(String::from))
+            .flatten();
+        let params = summarize_params(&request.params);
+
+        let response = self.handle_request(request, notifications).await;
+
+        let record = McpCallRecord {
+            timestamp: chrono::Utc::now().timestamp(),
+            method,
+            tool,
+            params,
+            duration_ms: start.elapsed().as_millis() as u64,
+            success: response.error.is_none(),
+            error: response.error.as_ref().map(|e| e.message.clone()),
+        };
+        info!(
+            method = %record.method,
+            tool = record.tool.as_deref().unwrap_or(""),
+            duration_ms = record.duration_ms,
+            success = record.success,
+            "MCP call"
+        );
+        self.call_log.record(record);
+        response
     }
 
     /// Run the MCP server (stdio mode)
@@ -131,6 +654,7 @@ impl McpServer {
         let mut stdout = tokio::io::stdout();
         let mut reader = BufReader::new(stdin);
         let mut line = String::new();
+        let (notification_tx, mut notification_rx) = mpsc::unbounded_channel();
 
         info!("MCP server ready, waiting for requests...");
 
@@ -150,39 +674,33 @@ impl McpServer {
 
             debug!("← {}", trimmed);
 
-            let response = match serde_json::from_str::<McpRequest>(trimmed) {
-                Ok(request) => {
-                    // Handle notification (no id) - no response needed
-                    if request.id.is_none() && request.method == "notifications/initialized" {
-                        debug!("Received initialized notification");
-                        continue;
+            // Forward progress notifications while the request runs
+            let handled = self.handle_line(trimmed, Some(&notification_tx));
+            tokio::pin!(handled);
+            let response = loop {
+                tokio::select! {
+                    response = &mut handled => break response,
+                    Some(notification) = notification_rx.recv() => {
+                        write_message(&mut stdout, &notification).await?;
                     }
-                    self.handle_request(request).await
-                }
-                Err(e) => {
-                    error!("Parse error: {}", e);
-                    McpResponse::error(None, error_codes::PARSE_ERROR, format!("Parse error: {}", e))
                 }
             };
-
-            // Don't send response for notifications
-            if response.id.is_none() && response.result.is_none() && response.error.is_none() {
-                continue;
+            while let Ok(notification) = notification_rx.try_recv() {
+                write_message(&mut stdout, &notification).await?;
             }
 
-            let response_json = serde_json::to_string(&response)?;
-            debug!("→ {}", response_json);
+            let Some(response) = response else {
+                continue;
+            };
 
-            stdout.write_all(response_json.as_bytes()).await?;
-            stdout.write_all(b"\n").await?;
-            stdout.flush().await?;
+            write_message(&mut stdout, &response).await?;
         }
 
         Ok(())
     }
 
     /// Handle a single MCP request
-    async fn handle_request(&self, request: McpRequest) -> McpResponse {
+    async fn handle_request(&self, request: McpRequest, notifications: Option<&NotificationSender>) -> McpResponse {
         match request.method.as_str() {
             // Lifecycle
             "initialize" => self.handle_initialize(request.id),
@@ -194,7 +712,11 @@ impl McpServer {
 
             // Tools
             "tools/list" => self.handle_tools_list(request.id).await,
-            "tools/call" => self.handle_tools_call(request.id, request.params).await,
+            "tools/call" => self.handle_tools_call(request.id, request.params, notifications).await,
+
+            // Resources
+            "resources/list" => self.handle_resources_list(request.id, request.params).await,
+            "resources/read" => self.handle_resources_read(request.id, request.params).await,
 
             // Ping
             "ping" => McpResponse::success(request.id, serde_json::json!({})),
@@ -220,6 +742,10 @@ impl McpServer {
                 "capabilities": {
                     "tools": {
                         "listChanged": false
+                    },
+                    "resources": {
+                        "subscribe": false,
+                        "listChanged": false
                     }
                 },
                 "serverInfo": {
@@ -232,15 +758,77 @@ impl McpServer {
 
     /// Handle tools/list
     async fn handle_tools_list(&self, id: Option<serde_json::Value>) -> McpResponse {
-        let tools = self.tools.lock().await.list_definitions();
+        let mut tools = self.tools.lock().await.list_definitions();
+        tools.push(ToolDefinition {
+            name: CALL_LOG_TOOL.to_string(),
+            description: "Recent MCP requests handled by this server (method, params, duration, success)"
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum entries, newest first (default: 20)"
+                    }
+                }
+            }),
+        });
+        tools.retain(|tool| self.policy.check(&tool.name).is_ok());
         McpResponse::success(id, serde_json::json!({ "tools": tools }))
     }
 
+    /// Handle resources/list
+    async fn handle_resources_list(&self, id: Option<serde_json::Value>, params: serde_json::Value) -> McpResponse {
+        let access = self.policy.resource_access();
+        if !access.memories && !access.conversations {
+            let reason = self.policy.check(MEMORY_RESOURCES).unwrap_err();
+            warn!("Rejected MCP resources/list: {}", reason);
+            return McpResponse::error(id, error_codes::PERMISSION_DENIED, reason);
+        }
+        let cursor = params.get("cursor").and_then(|v| v.as_str());
+        match self.resources.lock().await.list(cursor, access) {
+            Ok((resources, next_cursor)) => {
+                let mut result = serde_json::json!({ "resources": resources });
+                if let Some(next) = next_cursor {
+                    result["nextCursor"] = serde_json::json!(next);
+                }
+                McpResponse::success(id, result)
+            }
+            Err(e) => McpResponse::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+        }
+    }
+
+    /// Handle resources/read
+    async fn handle_resources_read(&self, id: Option<serde_json::Value>, params: serde_json::Value) -> McpResponse {
+        let Some(uri) = params.get("uri").and_then(|v| v.as_str()) else {
+            return McpResponse::error(id, error_codes::INVALID_PARAMS, "Missing 'uri' parameter");
+        };
+        let kind = if uri.starts_with(CONVERSATION_SCHEME) {
+            CONVERSATION_RESOURCES
+        } else {
+            MEMORY_RESOURCES
+        };
+        if let Err(reason) = self.policy.check(kind) {
+            warn!("Rejected MCP resources/read of {}: {}", uri, reason);
+            return McpResponse::error(id, error_codes::PERMISSION_DENIED, reason);
+        }
+        match self.resources.lock().await.read(uri) {
+            Ok(Some(contents)) => McpResponse::success(id, serde_json::json!({ "contents": [contents] })),
+            Ok(None) => McpResponse::error(
+                id,
+                error_codes::RESOURCE_NOT_FOUND,
+                format!("Resource not found: {}", uri),
+            ),
+            Err(e) => McpResponse::error(id, error_codes::INVALID_PARAMS, e.to_string()),
+        }
+    }
+
     /// Handle tools/call
     async fn handle_tools_call(
         &self,
         id: Option<serde_json::Value>,
         params: serde_json::Value,
+        notifications: Option<&NotificationSender>,
     ) -> McpResponse {
         let name = match params.get("name").and_then(|v| v.as_str()) {
             Some(n) => n,
@@ -253,21 +841,50 @@ impl McpServer {
             }
         };
 
+        if let Err(reason) = self.policy.check(name) {
+            warn!("Rejected MCP tool call: {}", reason);
+            return McpResponse::error(id, error_codes::PERMISSION_DENIED, reason);
+        }
+
         let arguments = params
             .get("arguments")
             .cloned()
             .unwrap_or(serde_json::json!({}));
 
-        match self.tools.lock().await.call(name, arguments).await {
-            Ok(result) => McpResponse::success(
+        if name == CALL_LOG_TOOL {
+            let limit = arguments.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+            let calls = self.call_log.recent(limit);
+            let text = serde_json::to_string_pretty(&calls).unwrap_or_default();
+            return McpResponse::success(
                 id,
                 serde_json::json!({
+                    "content": [{ "type": "text", "text": text }],
+                    "structuredContent": { "calls": calls }
+                }),
+            );
+        }
+
+        let progress = notifications.and_then(|tx| ProgressReporter::from_params(&params, tx));
+        match self
+            .tools
+            .lock()
+            .await
+            .call_formatted_with_progress(name, arguments, progress.as_ref())
+            .await
+        {
+            Ok(output) => {
+                let mut result = serde_json::json!({
                     "content": [{
                         "type": "text",
-                        "text": result
+                        "text": output.text
                     }]
-                }),
-            ),
+                });
+                // Structured content lets clients consume typed results directly
+                if let Some(structured) = output.structured {
+                    result["structuredContent"] = structured;
+                }
+                McpResponse::success(id, result)
+            }
             Err(e) => McpResponse::error(
                 id,
                 error_codes::TOOL_EXECUTION_ERROR,
@@ -276,3 +893,182 @@ impl McpServer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryStore;
+
+    fn record(method: &str) -> McpCallRecord {
+        McpCallRecord {
+            timestamp: 0,
+            method: method.to_string(),
+            tool: None,
+            params: "{}".to_string(),
+            duration_ms: 1,
+            success: true,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_call_log_ring_buffer() {
+        let log = McpCallLog::new(2);
+        log.record(record("initialize"));
+        log.record(record("tools/list"));
+        log.record(record("tools/call"));
+
+        let recent = log.recent(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].method, "tools/call");
+        assert_eq!(recent[1].method, "tools/list");
+        assert_eq!(log.recent(1).len(), 1);
+    }
+
+    #[test]
+    fn test_params_summary_truncated() {
+        let params = serde_json::json!({ "text": "x".repeat(500) });
+        let summary = summarize_params(&params);
+        assert_eq!(summary.chars().count(), PARAMS_SUMMARY_CHARS + 1);
+        assert!(summary.ends_with('…'));
+        assert_eq!(summarize_params(&serde_json::json!({"a": 1})), r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn test_replay_request_line() {
+        let request = r#"{"jsonrpc":"2.0","method":"ping","id":1}"#;
+        assert_eq!(replay_request_line(request), Some(request));
+        assert_eq!(replay_request_line(&format!("← {}", request)), Some(request));
+        assert_eq!(replay_request_line(r#"→ {"jsonrpc":"2.0","result":{},"id":1}"#), None);
+        assert_eq!(replay_request_line(r#"{"jsonrpc":"2.0","result":{},"id":1}"#), None);
+        assert_eq!(replay_request_line(""), None);
+        assert_eq!(replay_request_line("not json"), None);
+    }
+
+    #[test]
+    fn test_tool_policy() {
+        let supervised = McpToolPolicy::new(PermissionLevel::Supervised, false);
+        assert!(supervised.check("memory_search").is_ok());
+        assert!(supervised.check("memory_learn").is_ok());
+        let err = supervised.check("claude_complete").unwrap_err();
+        assert!(err.contains("requires autonomous"));
+        assert!(supervised.check("some_future_tool").is_err());
+
+        let restricted = McpToolPolicy::new(PermissionLevel::Restricted, false);
+        assert!(restricted.check(CALL_LOG_TOOL).is_ok());
+        assert!(restricted.check("memory_forget").is_err());
+
+        // Safe mode caps autonomous clients
+        let safe = McpToolPolicy::new(PermissionLevel::Autonomous, true);
+        assert_eq!(safe.client_level(), PermissionLevel::Supervised);
+        assert!(safe.check("circle_run").is_err());
+
+        let listed = McpToolPolicy::new(PermissionLevel::Autonomous, false)
+            .with_allowlist(["memory_search", "cache_clear"])
+            .with_denylist(["cache_clear"]);
+        assert!(listed.check("memory_search").is_ok());
+        assert!(listed.check("memory_recall").unwrap_err().contains("ALLOWLIST"));
+        assert!(listed.check("cache_clear").unwrap_err().contains("DENYLIST"));
+    }
+
+    #[test]
+    fn test_progress_notifications() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        assert!(ProgressReporter::from_params(&serde_json::json!({ "name": "circle_run" }), &tx).is_none());
+        assert!(ProgressReporter::from_params(&serde_json::json!({ "_meta": { "progressToken": {} } }), &tx).is_none());
+
+        let params = serde_json::json!({ "name": "circle_run", "_meta": { "progressToken": "abc" } });
+        let reporter = ProgressReporter::from_params(&params, &tx).unwrap();
+        reporter.report(0.0, Some(5.0), "Phase 1: Architect");
+        reporter.report(1.0, None, "Phase 2: Implementer");
+
+        let first = rx.try_recv().unwrap();
+        assert_eq!(first["method"], "notifications/progress");
+        assert!(first.get("id").is_none());
+        assert_eq!(first["params"]["progressToken"], "abc");
+        assert_eq!(first["params"]["total"], 5.0);
+        assert_eq!(first["params"]["message"], "Phase 1: Architect");
+        let second = rx.try_recv().unwrap();
+        assert_eq!(second["params"]["progress"], 1.0);
+        assert!(second["params"].get("total").is_none());
+    }
+
+    #[test]
+    fn test_resources_list_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemoryStore::open(&dir.path().join("memory.db")).unwrap();
+        let ids: Vec<String> = (0..3)
+            .map(|i| memory.learn(&format!("Fact number {}", i), "fact", "mcp", None, 0.9).unwrap())
+            .collect();
+        let conversations = ConversationStore::open(&dir.path().join("conversations.db")).unwrap();
+        conversations.add_exchange(42, "hello", "hi there").unwrap();
+
+        let mut store = ResourceStore::new(Box::new(memory), Some(conversations));
+        store.page_size = 2;
+
+        let (page1, next) = store.list(None, ResourceAccess::ALL).unwrap();
+        assert_eq!(page1.len(), 2);
+        assert_eq!(next.as_deref(), Some("memory:2"));
+        let (page2, next) = store.list(next.as_deref(), ResourceAccess::ALL).unwrap();
+        assert_eq!(page2.len(), 1);
+        assert_eq!(next.as_deref(), Some("conversation:0"));
+        let (page3, next) = store.list(next.as_deref(), ResourceAccess::ALL).unwrap();
+        assert_eq!(page3[0]["uri"], "conversation://42");
+        assert_eq!(next, None);
+        assert!(store.list(Some("bogus"), ResourceAccess::ALL).is_err());
+
+        let listed: Vec<&str> = page1.iter().chain(&page2).map(|r| r["uri"].as_str().unwrap()).collect();
+        for id in &ids {
+            assert!(listed.contains(&format!("memory://{}", id).as_str()));
+        }
+
+        let memory = store.read(&format!("memory://{}", ids[0])).unwrap().unwrap();
+        assert_eq!(memory["text"], "Fact number 0");
+        let chat = store.read("conversation://42").unwrap().unwrap();
+        assert!(chat["text"].as_str().unwrap().contains("hi there"));
+        assert!(store.read("memory://missing").unwrap().is_none());
+        assert!(store.read("conversation://7").unwrap().is_none());
+        assert!(store.read("file:///etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_resource_access_follows_policy() {
+        let none = ResourceAccess {
+            memories: false,
+            conversations: false,
+        };
+        let restricted = McpToolPolicy::new(PermissionLevel::Restricted, false);
+        assert_eq!(restricted.resource_access(), none);
+        assert!(restricted.check(CONVERSATION_RESOURCES).unwrap_err().contains("requires autonomous"));
+
+        // Safe mode keeps transcripts out even for an autonomous client
+        let safe = McpToolPolicy::new(PermissionLevel::Autonomous, true);
+        let memories_only = ResourceAccess {
+            memories: true,
+            conversations: false,
+        };
+        assert_eq!(safe.resource_access(), memories_only);
+        assert_eq!(McpToolPolicy::new(PermissionLevel::Autonomous, false).resource_access(), ResourceAccess::ALL);
+        let denied = McpToolPolicy::new(PermissionLevel::Autonomous, false).with_denylist([CONVERSATION_RESOURCES]);
+        assert_eq!(denied.resource_access(), memories_only);
+
+        let dir = tempfile::tempdir().unwrap();
+        let memory = MemoryStore::open(&dir.path().join("memory.db")).unwrap();
+        memory.learn("Fact", "fact", "mcp", None, 0.9).unwrap();
+        let conversations = ConversationStore::open(&dir.path().join("conversations.db")).unwrap();
+        conversations.add_exchange(42, "hello", "hi there").unwrap();
+        let store = ResourceStore::new(Box::new(memory), Some(conversations));
+
+        let (listed, next) = store.list(None, memories_only).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(next, None);
+        assert!(store.list(Some("conversation:0"), memories_only).is_err());
+
+        let transcripts_only = ResourceAccess {
+            memories: false,
+            conversations: true,
+        };
+        let (listed, _) = store.list(None, transcripts_only).unwrap();
+        assert_eq!(listed[0]["uri"], "conversation://42");
+    }
+}
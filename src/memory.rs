@@ -7,15 +7,19 @@
 use anyhow::Result;
 use hnsw::{Hnsw, Params, Searcher};
 use rand::rngs::SmallRng;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use sha2::{Digest, Sha256};
 use space::{Metric, Neighbor};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use crate::sqlite_pool::{SqliteConfig, SqlitePool};
+use crate::storage::{sqlite_storage, DbStorage};
 use crate::embeddings::{embedding_from_bytes, embedding_to_bytes, EmbeddingConfig, EmbeddingStore};
 
 /// HNSW parameters
@@ -57,6 +61,8 @@ struct HnswIndex {
     id_to_idx: HashMap<String, usize>,
     /// Expected embedding dimension (set on first insert)
     dimension: Option<usize>,
+    /// Internal indices of removed memories (the graph is append-only)
+    tombstones: HashSet<usize>,
 }
 
 impl HnswIndex {
@@ -69,6 +75,7 @@ impl HnswIndex {
             idx_to_id: Vec::new(),
             id_to_idx: HashMap::new(),
             dimension: None,
+            tombstones: HashSet::new(),
         }
     }
 
@@ -148,6 +155,7 @@ impl HnswIndex {
 
         found_slice
             .iter()
+            .filter(|n| !self.tombstones.contains(&n.index))
             .take(k)
             .filter_map(|n| {
                 let id = self.idx_to_id.get(n.index)?;
@@ -158,12 +166,95 @@ impl HnswIndex {
             .collect()
     }
 
-    /// Number of indexed vectors
+    /// Tombstone a memory's vector; it stays in the graph until a rebuild
+    fn remove(&mut self, id: &str) -> bool {
+        match self.id_to_idx.remove(id) {
+            Some(idx) => self.tombstones.insert(idx),
+            None => false,
+        }
+    }
+
+    /// Number of live indexed vectors
     fn len(&self) -> usize {
-        self.idx_to_id.len()
+        self.id_to_idx.len()
+    }
+
+    fn health(&self) -> IndexHealth {
+        IndexHealth {
+            live: self.len(),
+            tombstones: self.tombstones.len(),
+        }
+    }
+
+    /// Approximate heap footprint: vectors, layer-0 neighbor lists and both ID maps
+    /// (tombstoned nodes included, since the graph keeps them)
+    fn memory_bytes(&self) -> u64 {
+        let nodes = self.idx_to_id.len();
+        let vector = self.dimension.unwrap_or(0) * std::mem::size_of::<f32>();
+        let neighbors = HNSW_M0 * std::mem::size_of::<usize>();
+        let ids: usize = self.idx_to_id.iter().map(|id| 2 * (id.len() + std::mem::size_of::<String>())).sum();
+        let map_overhead = self.id_to_idx.len() * std::mem::size_of::<usize>() * 2;
+        (nodes * (vector + neighbors) + ids + map_overhead) as u64
+    }
+}
+
+/// HNSW index occupancy
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IndexHealth {
+    pub live: usize,
+    /// Removed vectors still occupying graph nodes
+    pub tombstones: usize,
+}
+
+impl IndexHealth {
+    /// Fraction of graph nodes that are tombstones
+    pub fn tombstone_ratio(&self) -> f64 {
+        let total = self.live + self.tombstones;
+        if total == 0 {
+            0.0
+        } else {
+            self.tombstones as f64 / total as f64
+        }
+    }
+}
+
+/// Outcome of an HNSW rebuild
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexRebuild {
+    /// Tombstones reclaimed
+    pub reclaimed: usize,
+    /// Vectors in the rebuilt index
+    pub indexed: usize,
+    pub duration_ms: u64,
+}
+
+/// Thumbs-up/down ratings a memory received
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeedbackCounts {
+    pub up: usize,
+    pub down: usize,
+}
+
+impl FeedbackCounts {
+    pub fn is_empty(&self) -> bool {
+        self.up == 0 && self.down == 0
+    }
+
+    /// "↑3 ↓1"
+    pub fn label(&self) -> String {
+        format!("↑{} ↓{}", self.up, self.down)
     }
 }
 
+/// Outcome of `MemoryStore::reset_feedback`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedbackReset {
+    /// Ratings removed from the log
+    pub ratings: usize,
+    /// Memories whose confidence was restored
+    pub memory_ids: Vec<String>,
+}
+
 /// Memory entry
 #[derive(Debug, Clone)]
 pub struct MemoryEntry {
@@ -175,6 +266,82 @@ pub struct MemoryEntry {
     pub created_at: i64,
     pub access_count: i64,
     pub embedding: Option<Vec<f32>>,
+    /// Visible to every user even under per-user scoping
+    pub shared: bool,
+    /// User (or chat, for conversation summaries) the memory belongs to;
+    /// `None` for memories without an owner
+    pub owner_id: Option<i64>,
+}
+
+/// Which memories a lookup may return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryScope {
+    /// Everything ("shared brain")
+    #[default]
+    Global,
+    /// Memories owned by this user, explicitly shared ones, and ones
+    /// without an owner (MCP, context loads, consolidation)
+    User(i64),
+}
+
+impl MemoryScope {
+    /// Whether an entry is visible in this scope
+    pub fn allows(&self, entry: &MemoryEntry) -> bool {
+        match self {
+            Self::Global => true,
+            Self::User(user_id) => entry.shared || entry.owner_id.is_none_or(|owner| owner == *user_id),
+        }
+    }
+
+    /// `allows` as a condition on the `memories` table, where `param` is
+    /// bound to `sql_user()`
+    fn sql_filter(param: &str) -> String {
+        format!("({p} IS NULL OR shared = 1 OR owner_id IS NULL OR owner_id = {p})", p = param)
+    }
+
+    /// The user ID to bind for `sql_filter`, NULL for `Global`
+    pub(crate) fn sql_user(&self) -> Option<i64> {
+        match self {
+            Self::Global => None,
+            Self::User(user_id) => Some(*user_id),
+        }
+    }
+}
+
+/// Memory isolation between users
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryScopeMode {
+    /// All users see all memories
+    #[default]
+    Shared,
+    /// Users only see their own memories unless marked shared
+    PerUser,
+}
+
+impl MemoryScopeMode {
+    /// `CLAUDEBOT_MEMORY_SCOPE` = `shared` (default) or `per_user`
+    pub fn from_env() -> Self {
+        std::env::var("CLAUDEBOT_MEMORY_SCOPE")
+            .ok()
+            .and_then(|v| Self::parse(&v))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "shared" | "global" => Some(Self::Shared),
+            "per_user" | "per-user" | "isolated" | "user" => Some(Self::PerUser),
+            _ => None,
+        }
+    }
+
+    /// Scope for lookups made on behalf of a user
+    pub fn scope_for(&self, user_id: i64) -> MemoryScope {
+        match self {
+            Self::Shared => MemoryScope::Global,
+            Self::PerUser => MemoryScope::User(user_id),
+        }
+    }
 }
 
 /// Search result with score breakdown
@@ -202,12 +369,116 @@ impl From<ScoredMemory> for SearchResult {
     }
 }
 
+/// One memory for `MemoryStore::learn_batch`:
+/// (content, category, source, owner, confidence, embedding)
+pub type LearnEntry<'a> = (&'a str, &'a str, &'a str, Option<i64>, f64, Option<&'a [f32]>);
+
+/// Result of `MemoryStore::learn_batch`
+#[derive(Debug, Clone, Default)]
+pub struct LearnBatch {
+    /// IDs of the stored entries, in input order
+    pub ids: Vec<String>,
+    /// (input index, reason) for each entry that wasn't stored
+    pub rejected: Vec<(usize, String)>,
+}
+
+/// Longest tag accepted by `MemoryStore::add_tag`
+pub const MAX_TAG_CHARS: usize = 32;
+
+/// Canonical form of a user tag: lowercase, no leading `#`, letters, digits and
+/// `-_.:`; None if nothing valid is left
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().count() <= MAX_TAG_CHARS
+        && tag.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+    valid.then_some(tag)
+}
+
+/// Appended to memory content cut down to `MemoryLimits::max_chars`
+pub const TRUNCATION_MARKER: &str = " […truncated]";
+
+/// Size limits for stored memory content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryLimits {
+    /// Longer content is truncated on a word boundary (0 = no limit)
+    pub max_chars: usize,
+    /// Longer content is rejected outright (0 = no limit)
+    pub reject_chars: usize,
+}
+
+impl Default for MemoryLimits {
+    fn default() -> Self {
+        Self {
+            max_chars: 8000,
+            reject_chars: 100_000,
+        }
+    }
+}
+
+impl MemoryLimits {
+    /// `CLAUDEBOT_MEMORY_MAX_CHARS` and `CLAUDEBOT_MEMORY_REJECT_CHARS`
+    pub fn from_env() -> Self {
+        let mut limits = Self::default();
+        if let Some(n) = std::env::var("CLAUDEBOT_MEMORY_MAX_CHARS").ok().and_then(|v| v.parse().ok()) {
+            limits.max_chars = n;
+        }
+        if let Some(n) = std::env::var("CLAUDEBOT_MEMORY_REJECT_CHARS").ok().and_then(|v| v.parse().ok()) {
+            limits.reject_chars = n;
+        }
+        limits
+    }
+
+    /// Content to store: unchanged, truncated with a marker, or an error
+    /// above the hard ceiling
+    pub fn apply<'a>(&self, content: &'a str) -> Result<Cow<'a, str>> {
+        let chars = content.chars().count();
+        if self.reject_chars > 0 && chars > self.reject_chars {
+            anyhow::bail!("Memory content too large ({} chars, limit {})", chars, self.reject_chars);
+        }
+        if self.max_chars == 0 || chars <= self.max_chars {
+            return Ok(Cow::Borrowed(content));
+        }
+
+        let keep = self.max_chars.saturating_sub(TRUNCATION_MARKER.chars().count()).max(1);
+        let cut = content.char_indices().nth(keep).map(|(i, _)| i).unwrap_or(content.len());
+        let head = &content[..cut];
+        // Back up to the last word boundary unless that throws away most of it
+        let head = match head.rfind(char::is_whitespace) {
+            Some(i) if i >= cut / 2 => &head[..i],
+            _ => head,
+        };
+        warn!("Truncated memory content from {} to {} chars", chars, head.chars().count());
+        Ok(Cow::Owned(format!("{}{}", head.trim_end(), TRUNCATION_MARKER)))
+    }
+}
+
 /// Memory store with SQLite backend and optional embeddings
+///
+/// Safe to share between tasks without an outer lock: reads use the pool's
+/// read connections, writes queue on its writer. Anything touching the HNSW
+/// index along with the database takes the writer first and the index lock
+/// second, and holds both until the index matches what was committed.
 pub struct MemoryStore {
-    conn: Connection,
+    db: SqlitePool,
     embedder: Option<Arc<RwLock<EmbeddingStore>>>,
     /// HNSW index for O(log n) approximate nearest neighbor search
     hnsw_index: Arc<Mutex<HnswIndex>>,
+    limits: MemoryLimits,
+    /// Set once a backfill resuming from a saved checkpoint has been logged
+    resume_logged: AtomicBool,
+}
+
+/// Key of the embedding backfill checkpoint in `backfill_state`
+const BACKFILL_CHECKPOINT_KEY: &str = "embedding";
+
+/// Memories to embed next, from the saved backfill checkpoint onwards
+#[derive(Debug, Clone, Default)]
+pub struct BackfillBatch {
+    /// (id, content) in rowid order
+    pub memories: Vec<(String, String)>,
+    /// Save with `save_backfill_checkpoint` once the batch has been processed
+    pub checkpoint: i64,
 }
 
 impl MemoryStore {
@@ -218,11 +489,13 @@ impl MemoryStore {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(path)?;
+        let db = SqlitePool::open(path, &SqliteConfig::from_env())?;
         let mut store = Self {
-            conn,
+            db,
             embedder: None,
             hnsw_index: Arc::new(Mutex::new(HnswIndex::new())),
+            limits: MemoryLimits::from_env(),
+            resume_logged: AtomicBool::new(false),
         };
         store.init_schema()?;
         store.build_hnsw_index()?;
@@ -237,13 +510,18 @@ impl MemoryStore {
             std::fs::create_dir_all(parent)?;
         }
 
-        let conn = Connection::open(path)?;
+        let db = SqlitePool::open(path, &SqliteConfig::from_env())?;
 
         // Try to initialize embedder
         let embedder = {
-            let store = EmbeddingStore::new(EmbeddingConfig::default());
+            let mut store = EmbeddingStore::new(EmbeddingConfig::default());
             if store.check_availability().await {
                 info!("Embedding service available - semantic search enabled");
+                let cache_path = path.with_file_name("embedding_cache.db");
+                match store.load_disk_cache(&cache_path).await {
+                    Ok(loaded) => info!("Loaded {} cached query embeddings", loaded),
+                    Err(e) => warn!("Embedding cache not persisted: {}", e),
+                }
                 Some(Arc::new(RwLock::new(store)))
             } else {
                 warn!("Embedding service unavailable - using keyword search only");
@@ -252,9 +530,11 @@ impl MemoryStore {
         };
 
         let mut store = Self {
-            conn,
+            db,
             embedder,
             hnsw_index: Arc::new(Mutex::new(HnswIndex::new())),
+            limits: MemoryLimits::from_env(),
+            resume_logged: AtomicBool::new(false),
         };
         store.init_schema()?;
         store.build_hnsw_index()?;
@@ -263,6 +543,15 @@ impl MemoryStore {
         Ok(store)
     }
 
+    /// Content size limits applied by `learn*`
+    pub fn limits(&self) -> MemoryLimits {
+        self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: MemoryLimits) {
+        self.limits = limits;
+    }
+
     /// Set embedder (for testing or late initialization)
     pub fn set_embedder(&mut self, embedder: EmbeddingStore) {
         self.embedder = Some(Arc::new(RwLock::new(embedder)));
@@ -273,15 +562,16 @@ impl MemoryStore {
         self.embedder.is_some()
     }
 
-    /// Get a clone of the embedder Arc for async operations outside the mutex
+    /// Get a clone of the embedder Arc for async operations
     pub fn get_embedder(&self) -> Option<Arc<RwLock<EmbeddingStore>>> {
         self.embedder.clone()
     }
 
     /// Initialize database schema
     fn init_schema(&self) -> Result<()> {
+        let conn = self.db.writer();
         // Base schema (without embedding column for backwards compatibility)
-        self.conn.execute_batch(
+        conn.execute_batch(
             r#"
             CREATE TABLE IF NOT EXISTS memories (
                 id TEXT PRIMARY KEY,
@@ -316,17 +606,72 @@ impl MemoryStore {
                 INSERT INTO memories_fts(memories_fts, rowid, content) VALUES('delete', old.rowid, old.content);
                 INSERT INTO memories_fts(rowid, content) VALUES (new.rowid, new.content);
             END;
+
+            -- Free-form user tags, independent of the inferred category
+            CREATE TABLE IF NOT EXISTS memory_tags (
+                memory_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+                PRIMARY KEY (memory_id, tag)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_memory_tags_tag ON memory_tags(tag);
+
+            -- Thumbs-up/down adjustments, delta as actually applied after clamping
+            CREATE TABLE IF NOT EXISTS memory_feedback (
+                memory_id TEXT NOT NULL,
+                user_id INTEGER NOT NULL,
+                positive INTEGER NOT NULL,
+                delta REAL NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT (unixepoch())
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_memory_feedback_memory ON memory_feedback(memory_id);
+            CREATE INDEX IF NOT EXISTS idx_memory_feedback_user ON memory_feedback(user_id);
+
+            -- Resume points of long-running passes over memories (embedding backfill)
+            CREATE TABLE IF NOT EXISTS backfill_state (
+                key TEXT PRIMARY KEY,
+                last_rowid INTEGER NOT NULL
+            );
             "#,
         )?;
 
         // Migration: Add embedding column if it doesn't exist
-        let _ = self.conn.execute(
+        let _ = conn.execute(
             "ALTER TABLE memories ADD COLUMN embedding BLOB",
             [],
         );
 
+        // Migration: Add shared flag for per-user scoping
+        let _ = conn.execute(
+            "ALTER TABLE memories ADD COLUMN shared INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Migration: Add explicit owner, taking it from the digits after the
+        // last '_' in source the one time the column is created (as scoping
+        // did before), so existing private memories stay private
+        if conn
+            .execute("ALTER TABLE memories ADD COLUMN owner_id INTEGER", [])
+            .is_ok()
+        {
+            // rtrim drops everything after the last '_'
+            let suffix = "substr(source, length(rtrim(source, replace(source, '_', ''))) + 1)";
+            let digits = format!("(CASE WHEN {0} GLOB '[+-]*' THEN substr({0}, 2) ELSE {0} END)", suffix);
+            conn.execute(
+                &format!(
+                    "UPDATE memories SET owner_id = CAST({s} AS INTEGER) \
+                     WHERE instr(source, '_') > 0 AND {d} != '' AND {d} NOT GLOB '*[^0-9]*'",
+                    s = suffix,
+                    d = digits,
+                ),
+                [],
+            )?;
+        }
+
         // Create embedding index (after migration ensures column exists)
-        let _ = self.conn.execute(
+        let _ = conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_memories_has_embedding ON memories(embedding IS NOT NULL)",
             [],
         );
@@ -337,7 +682,18 @@ impl MemoryStore {
     /// Build HNSW index from existing embeddings in database
     /// Called on startup to enable O(log n) approximate nearest neighbor search
     fn build_hnsw_index(&mut self) -> Result<()> {
-        let mut stmt = self.conn.prepare(
+        let conn = self.db.writer();
+        let index = Self::load_hnsw_index(&conn)?;
+        *self.hnsw_index.lock().unwrap() = index;
+        Ok(())
+    }
+
+    /// Build a fresh HNSW index from the live embeddings in the database
+    ///
+    /// Callers pass the writer, so no embedding can change between the scan
+    /// and swapping the index in.
+    fn load_hnsw_index(conn: &Connection) -> Result<HnswIndex> {
+        let mut stmt = conn.prepare(
             "SELECT id, embedding FROM memories WHERE embedding IS NOT NULL"
         )?;
 
@@ -351,14 +707,14 @@ impl MemoryStore {
             .filter_map(|r| r.ok())
             .collect();
 
+        let mut index = HnswIndex::new();
         let count = memories.len();
         if count == 0 {
             debug!("No embeddings to index in HNSW");
-            return Ok(());
+            return Ok(index);
         }
 
         // Build index, tracking skipped embeddings due to dimension mismatch
-        let mut index = self.hnsw_index.lock().unwrap();
         let mut indexed = 0;
         let mut skipped = 0;
         for (id, embedding) in memories {
@@ -377,44 +733,144 @@ impl MemoryStore {
         } else {
             info!("Built HNSW index with {} vectors", indexed);
         }
-        Ok(())
+        Ok(index)
+    }
+
+    /// Live vectors and tombstones in the HNSW index
+    pub fn index_health(&self) -> IndexHealth {
+        self.hnsw_index.lock().unwrap().health()
+    }
+
+    /// Approximate memory used by the HNSW index
+    pub fn index_memory_bytes(&self) -> u64 {
+        self.hnsw_index.lock().unwrap().memory_bytes()
+    }
+
+    /// Database size and row counts per table
+    pub fn storage_stats(&self) -> Result<DbStorage> {
+        sqlite_storage(&self.db.reader(), "memory")
+    }
+
+    /// Rebuild the HNSW index from live embeddings, dropping tombstones
+    pub fn rebuild_hnsw_index(&self) -> Result<IndexRebuild> {
+        let start = std::time::Instant::now();
+        let reclaimed = self.index_health().tombstones;
+        let conn = self.db.writer();
+        let index = Self::load_hnsw_index(&conn)?;
+        let indexed = index.len();
+        *self.hnsw_index.lock().unwrap() = index;
+        Ok(IndexRebuild {
+            reclaimed,
+            indexed,
+            duration_ms: start.elapsed().as_millis() as u64,
+        })
     }
 
     /// Generate content hash for ID
-    fn hash_content(content: &str) -> String {
+    pub(crate) fn hash_content(content: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(content.as_bytes());
         hex::encode(&hasher.finalize()[..16])
     }
 
     /// Store a memory (sync, no embedding)
-    pub fn learn(&self, content: &str, category: &str, source: &str, confidence: f64) -> Result<String> {
-        let id = Self::hash_content(content);
+    ///
+    /// `owner` is the user (or chat) the memory belongs to under per-user
+    /// scoping; `None` makes it visible to everyone.
+    pub fn learn(&self, content: &str, category: &str, source: &str, owner: Option<i64>, confidence: f64) -> Result<String> {
+        let content = self.limits.apply(content)?;
+        let id = Self::hash_content(&content);
 
-        self.conn.execute(
+        self.db.writer().execute(
             r#"
-            INSERT INTO memories (id, content, category, source, confidence)
-            VALUES (?1, ?2, ?3, ?4, ?5)
+            INSERT INTO memories (id, content, category, source, confidence, owner_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
             ON CONFLICT(id) DO UPDATE SET
                 confidence = MAX(confidence, excluded.confidence),
                 access_count = access_count + 1,
                 last_accessed = unixepoch()
             "#,
-            params![id, content, category, source, confidence],
+            params![id, content, category, source, confidence, owner],
         )?;
 
         debug!("Learned: {} ({})", &id.get(..8).unwrap_or(&id), category);
         Ok(id)
     }
 
+    /// Store many memories in one transaction (one commit instead of one per insert)
+    ///
+    /// Embeddings, when given, are stored too and added to the HNSW index after
+    /// the commit. An entry that fails (e.g. over the size limit) is skipped and
+    /// reported in `rejected`; the rest are still stored.
+    pub fn learn_batch(&self, entries: &[LearnEntry]) -> Result<LearnBatch> {
+        let conn = self.db.writer();
+        let tx = conn.unchecked_transaction()?;
+        let mut batch = LearnBatch::default();
+        let mut embedded = Vec::new();
+        {
+            let mut stmt = tx.prepare(
+                r#"
+                INSERT INTO memories (id, content, category, source, confidence, embedding, owner_id)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                ON CONFLICT(id) DO UPDATE SET
+                    confidence = MAX(confidence, excluded.confidence),
+                    access_count = access_count + 1,
+                    last_accessed = unixepoch(),
+                    embedding = COALESCE(excluded.embedding, embedding)
+                "#,
+            )?;
+            for (index, (content, category, source, owner, confidence, embedding)) in entries.iter().enumerate() {
+                let stored = self.limits.apply(content).and_then(|content| {
+                    let id = Self::hash_content(&content);
+                    stmt.execute(params![
+                        id,
+                        content,
+                        category,
+                        source,
+                        confidence,
+                        embedding.map(embedding_to_bytes),
+                        owner,
+                    ])?;
+                    Ok(id)
+                });
+                match stored {
+                    Ok(id) => {
+                        if let Some(embedding) = embedding {
+                            embedded.push((id.clone(), *embedding));
+                        }
+                        batch.ids.push(id);
+                    }
+                    Err(e) => batch.rejected.push((index, e.to_string())),
+                }
+            }
+        }
+        tx.commit()?;
+
+        if !embedded.is_empty() {
+            let mut index = self.hnsw_index.lock().unwrap();
+            for (id, embedding) in embedded {
+                index.insert(id, embedding.to_vec());
+            }
+        }
+
+        if !batch.rejected.is_empty() {
+            warn!("Learn batch: {} of {} entries rejected", batch.rejected.len(), entries.len());
+        }
+        debug!("Learned batch of {}", batch.ids.len());
+        Ok(batch)
+    }
+
     /// Store a memory with embedding (async)
     pub async fn learn_with_embedding(
         &self,
         content: &str,
         category: &str,
         source: &str,
+        owner: Option<i64>,
         confidence: f64,
     ) -> Result<String> {
+        let content = self.limits.apply(content)?;
+        let content = content.as_ref();
         let id = Self::hash_content(content);
 
         // Generate embedding if available
@@ -430,17 +886,18 @@ impl MemoryStore {
             (None, None)
         };
 
-        self.conn.execute(
+        let conn = self.db.writer();
+        conn.execute(
             r#"
-            INSERT INTO memories (id, content, category, source, confidence, embedding)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            INSERT INTO memories (id, content, category, source, confidence, embedding, owner_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
             ON CONFLICT(id) DO UPDATE SET
                 confidence = MAX(confidence, excluded.confidence),
                 access_count = access_count + 1,
                 last_accessed = unixepoch(),
                 embedding = COALESCE(excluded.embedding, embedding)
             "#,
-            params![id, content, category, source, confidence, embedding_bytes],
+            params![id, content, category, source, confidence, embedding_bytes, owner],
         )?;
 
         // Insert into HNSW index if we have an embedding
@@ -455,11 +912,12 @@ impl MemoryStore {
 
     /// Search memories using FTS (keyword search)
     pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-        let mut stmt = self.conn.prepare(
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
             r#"
             SELECT m.id, m.content, m.category, m.source, m.confidence,
                    m.created_at, m.access_count, m.embedding,
-                   bm25(memories_fts) as score
+                   bm25(memories_fts) as score, m.shared, m.owner_id
             FROM memories_fts
             JOIN memories m ON memories_fts.rowid = m.rowid
             WHERE memories_fts MATCH ?1
@@ -492,6 +950,8 @@ impl MemoryStore {
                         created_at: row.get(5)?,
                         access_count: row.get(6)?,
                         embedding: embedding_bytes.map(|b| embedding_from_bytes(&b)),
+                        shared: row.get(9)?,
+                        owner_id: row.get(10)?,
                     },
                     score: row.get::<_, f64>(8)?.abs(), // BM25 returns negative
                 })
@@ -534,19 +994,70 @@ impl MemoryStore {
         let fused = self.fuse_results(keyword_results, vector_results, keyword_weight);
 
         // 4. Return top-k
-        Ok(fused.into_iter().take(limit).collect())
+        let results: Vec<ScoredMemory> = fused.into_iter().take(limit).collect();
+        self.record_retrieved(&results);
+        Ok(results)
     }
 
     /// Hybrid search with pre-computed embedding (sync version)
     ///
-    /// Use this when you've already computed the query embedding outside the mutex lock.
+    /// Use this when you've already computed the query embedding.
+    /// Results (including the recency fallback) are limited to `scope`.
+    /// Returned matches count as accessed (see `record_access`).
     pub fn search_hybrid_sync(
         &self,
         query: &str,
         query_embedding: Option<Vec<f32>>,
         limit: usize,
         keyword_weight: f32,
+        scope: MemoryScope,
+    ) -> Result<Vec<ScoredMemory>> {
+        self.search_hybrid_scoped(query, query_embedding, limit, keyword_weight, scope, None, true)
+    }
+
+    /// `search_hybrid_sync` without recording access, for browsing/lookup
+    /// that shouldn't influence ranking
+    pub fn search_hybrid_sync_readonly(
+        &self,
+        query: &str,
+        query_embedding: Option<Vec<f32>>,
+        limit: usize,
+        keyword_weight: f32,
+        scope: MemoryScope,
+    ) -> Result<Vec<ScoredMemory>> {
+        self.search_hybrid_scoped(query, query_embedding, limit, keyword_weight, scope, None, false)
+    }
+
+    /// `search_hybrid_sync_readonly` restricted to memories tagged `tag`;
+    /// the fallback is the most recently tagged ones
+    pub fn search_hybrid_tagged(
+        &self,
+        query: &str,
+        query_embedding: Option<Vec<f32>>,
+        limit: usize,
+        keyword_weight: f32,
+        scope: MemoryScope,
+        tag: &str,
+    ) -> Result<Vec<ScoredMemory>> {
+        self.search_hybrid_scoped(query, query_embedding, limit, keyword_weight, scope, Some(tag), false)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search_hybrid_scoped(
+        &self,
+        query: &str,
+        query_embedding: Option<Vec<f32>>,
+        limit: usize,
+        keyword_weight: f32,
+        scope: MemoryScope,
+        tag: Option<&str>,
+        track_access: bool,
     ) -> Result<Vec<ScoredMemory>> {
+        let tagged = match tag {
+            Some(tag) => Some(self.tagged_ids(tag)?),
+            None => None,
+        };
+
         // 1. Get keyword results (BM25)
         let keyword_results = self.search(query, limit * 3)?;
 
@@ -560,15 +1071,39 @@ impl MemoryStore {
         // 3. Fuse results
         let fused = self.fuse_results(keyword_results, vector_results, keyword_weight);
 
-        // 4. Return top-k, with recency fallback if empty
-        let results: Vec<ScoredMemory> = fused.into_iter().take(limit).collect();
+        // 4. Return top-k in scope, with recency fallback if empty
+        let results: Vec<ScoredMemory> = fused
+            .into_iter()
+            .filter(|r| scope.allows(&r.entry))
+            .filter(|r| tagged.as_ref().is_none_or(|ids| ids.contains(&r.entry.id)))
+            .take(limit)
+            .collect();
 
         if results.is_empty() {
+            if let Some(tag) = tag {
+                debug!("Tagged hybrid search empty, falling back to recently tagged memories");
+                return Ok(self
+                    .search_by_tag(tag, 50)?
+                    .into_iter()
+                    .filter(|entry| scope.allows(entry))
+                    .take(limit.min(3))
+                    .map(|entry| ScoredMemory {
+                        entry,
+                        score: 0.05,
+                        keyword_score: 0.0,
+                        vector_score: 0.0,
+                    })
+                    .collect());
+            }
+
             // Fallback: return recent memories with low score
             debug!("Hybrid search empty, falling back to recent memories");
-            let recent = self.get_recent(limit.min(3))?;
+            let fetch = if scope == MemoryScope::Global { limit.min(3) } else { 50 };
+            let recent = self.get_recent(fetch)?;
             return Ok(recent
                 .into_iter()
+                .filter(|entry| scope.allows(entry))
+                .take(limit.min(3))
                 .map(|entry| ScoredMemory {
                     entry,
                     score: 0.05,
@@ -578,9 +1113,38 @@ impl MemoryStore {
                 .collect());
         }
 
+        // The recency fallback above isn't a match, so only real hits count
+        if track_access {
+            self.record_retrieved(&results);
+        }
         Ok(results)
     }
 
+    /// Bump `access_count` and `last_accessed` for these memories in one
+    /// UPDATE, returning how many rows changed
+    ///
+    /// Feeds the access boost in hybrid ranking, which otherwise only grows
+    /// when a memory is re-learned.
+    pub fn record_access(&self, ids: &[&str]) -> Result<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let sql = format!(
+            "UPDATE memories SET access_count = access_count + 1, last_accessed = unixepoch() WHERE id IN ({})",
+            placeholders
+        );
+        Ok(self.db.writer().execute(&sql, rusqlite::params_from_iter(ids))?)
+    }
+
+    /// `record_access` for search results; failures only cost the boost
+    fn record_retrieved(&self, results: &[ScoredMemory]) {
+        let ids: Vec<&str> = results.iter().map(|r| r.entry.id.as_str()).collect();
+        if let Err(e) = self.record_access(&ids) {
+            warn!("Failed to record memory access: {}", e);
+        }
+    }
+
     /// Search by embedding similarity only
     /// Uses brute force O(n) search - HNSW disabled due to upstream bug
     /// TODO: Re-enable HNSW once hnsw crate fixes copy_from_slice panic
@@ -589,7 +1153,8 @@ impl MemoryStore {
         // "copy_from_slice: source slice length (X) does not match destination slice length (Y)"
         // Using brute force instead (acceptable for < 10k memories)
         debug!("Using brute force search (HNSW disabled)");
-        let mut stmt = self.conn.prepare(
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
             r#"
             SELECT id, embedding
             FROM memories
@@ -719,11 +1284,130 @@ impl MemoryStore {
         results
     }
 
+    /// Fail unless the database accepts writes (probe is rolled back)
+    pub fn check_writable(&self) -> Result<()> {
+        let conn = self.db.writer();
+        let probe = conn.execute_batch("BEGIN IMMEDIATE; CREATE TABLE _write_probe(x);");
+        let _ = conn.execute_batch("ROLLBACK");
+        Ok(probe?)
+    }
+
+    /// Mark a memory as shared (or private again); false if not found
+    pub fn set_shared(&self, id: &str, shared: bool) -> Result<bool> {
+        let updated = self.db.writer().execute(
+            "UPDATE memories SET shared = ?2 WHERE id = ?1",
+            params![id, shared],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Tag a memory (see `normalize_tag`); false if the memory doesn't exist
+    /// or already has the tag
+    pub fn add_tag(&self, id: &str, tag: &str) -> Result<bool> {
+        let tag = normalize_tag(tag).ok_or_else(|| anyhow::anyhow!("Invalid tag '{}'", tag))?;
+        let inserted = self.db.writer().execute(
+            "INSERT OR IGNORE INTO memory_tags (memory_id, tag)
+             SELECT id, ?2 FROM memories WHERE id = ?1",
+            params![id, tag],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Remove a tag; false if the memory didn't have it
+    pub fn remove_tag(&self, id: &str, tag: &str) -> Result<bool> {
+        let Some(tag) = normalize_tag(tag) else {
+            return Ok(false);
+        };
+        let removed = self.db.writer().execute(
+            "DELETE FROM memory_tags WHERE memory_id = ?1 AND tag = ?2",
+            params![id, tag],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// A memory's tags, alphabetically
+    pub fn tags_for(&self, id: &str) -> Result<Vec<String>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare("SELECT tag FROM memory_tags WHERE memory_id = ?1 ORDER BY tag")?;
+        let tags = stmt
+            .query_map(params![id], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tags)
+    }
+
+    /// Every tag in use with its memory count, most used first
+    pub fn tag_counts(&self) -> Result<Vec<(String, i64)>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            "SELECT tag, COUNT(*) FROM memory_tags GROUP BY tag ORDER BY COUNT(*) DESC, tag",
+        )?;
+        let counts = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(counts)
+    }
+
+    /// Memories with a tag, most recently tagged first
+    pub fn search_by_tag(&self, tag: &str, limit: usize) -> Result<Vec<MemoryEntry>> {
+        let Some(tag) = normalize_tag(tag) else {
+            return Ok(Vec::new());
+        };
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT m.id, m.content, m.category, m.source, m.confidence, m.created_at, m.access_count, m.embedding, m.shared, m.owner_id
+            FROM memories m
+            JOIN memory_tags t ON t.memory_id = m.id
+            WHERE t.tag = ?1
+            ORDER BY t.created_at DESC, m.created_at DESC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let results = stmt
+            .query_map(params![tag, limit], |row| {
+                let embedding_bytes: Option<Vec<u8>> = row.get(7)?;
+                Ok(MemoryEntry {
+                    id: row.get(0)?,
+                    content: row.get(1)?,
+                    category: row.get(2)?,
+                    source: row.get(3)?,
+                    confidence: row.get(4)?,
+                    created_at: row.get(5)?,
+                    access_count: row.get(6)?,
+                    embedding: embedding_bytes.map(|b| embedding_from_bytes(&b)),
+                    shared: row.get(8)?,
+                    owner_id: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// IDs of every memory with a tag
+    fn tagged_ids(&self, tag: &str) -> Result<HashSet<String>> {
+        let Some(tag) = normalize_tag(tag) else {
+            return Ok(HashSet::new());
+        };
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare("SELECT memory_id FROM memory_tags WHERE tag = ?1")?;
+        let ids = stmt
+            .query_map(params![tag], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(ids)
+    }
+
     /// Get memory by ID
     pub fn get_by_id(&self, id: &str) -> Result<Option<MemoryEntry>> {
-        let mut stmt = self.conn.prepare(
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
             r#"
-            SELECT id, content, category, source, confidence, created_at, access_count, embedding
+            SELECT id, content, category, source, confidence, created_at, access_count, embedding, shared, owner_id
             FROM memories
             WHERE id = ?1
             "#,
@@ -740,6 +1424,8 @@ impl MemoryStore {
                 created_at: row.get(5)?,
                 access_count: row.get(6)?,
                 embedding: embedding_bytes.map(|b| embedding_from_bytes(&b)),
+                shared: row.get(8)?,
+                owner_id: row.get(9)?,
             })
         });
 
@@ -750,9 +1436,94 @@ impl MemoryStore {
         }
     }
 
+    /// Resolve a full memory ID or a unique ID prefix (e.g. the 8-char form)
+    pub fn resolve_id(&self, id_or_prefix: &str) -> Result<Option<MemoryEntry>> {
+        let id_or_prefix = id_or_prefix.trim();
+        if let Some(entry) = self.get_by_id(id_or_prefix)? {
+            return Ok(Some(entry));
+        }
+        // IDs are hex; anything else can't be a prefix (and keeps LIKE safe)
+        if id_or_prefix.is_empty() || !id_or_prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(None);
+        }
+
+        let ids: Vec<String> = {
+            let conn = self.db.reader();
+            let mut stmt = conn.prepare("SELECT id FROM memories WHERE id LIKE ?1 LIMIT 2")?;
+            let ids = stmt
+                .query_map(params![format!("{}%", id_or_prefix.to_lowercase())], |row| row.get(0))?
+                .filter_map(|r| r.ok())
+                .collect();
+            ids
+        };
+
+        match ids.as_slice() {
+            [id] => self.get_by_id(id),
+            [] => Ok(None),
+            _ => anyhow::bail!("Ambiguous memory ID prefix '{}'", id_or_prefix),
+        }
+    }
+
+    /// Find memories most similar to a given embedding, excluding `exclude_id`
+    ///
+    /// Goes through the same neighbor search as vector recall (brute force
+    /// while HNSW is disabled). Scores are cosine similarities.
+    pub fn similar_to_embedding(
+        &self,
+        exclude_id: &str,
+        embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<ScoredMemory>> {
+        let neighbors = self.search_by_embedding(embedding, limit + 1)?;
+        let mut results = Vec::with_capacity(limit);
+        for (id, similarity) in neighbors {
+            if id == exclude_id {
+                continue;
+            }
+            if let Some(entry) = self.get_by_id(&id)? {
+                results.push(ScoredMemory {
+                    entry,
+                    score: similarity,
+                    keyword_score: 0.0,
+                    vector_score: similarity,
+                });
+            }
+            if results.len() >= limit {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
+    /// "More like this": memories nearest to an existing memory's embedding
+    ///
+    /// Accepts a full ID or unique prefix. Entries without a stored embedding
+    /// are embedded on the fly (and the embedding is saved).
+    pub async fn similar_to(&self, id: &str, limit: usize) -> Result<Vec<ScoredMemory>> {
+        let entry = self
+            .resolve_id(id)?
+            .ok_or_else(|| anyhow::anyhow!("Memory not found: {}", id))?;
+
+        let embedding = match entry.embedding {
+            Some(ref emb) => emb.clone(),
+            None => {
+                let embedder = self
+                    .embedder
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("Memory has no embedding and embeddings are unavailable"))?;
+                let emb = embedder.read().await.embed(&entry.content).await?;
+                self.store_embedding(&entry.id, &emb)?;
+                emb
+            }
+        };
+
+        self.similar_to_embedding(&entry.id, &embedding, limit)
+    }
+
     /// Get memories that need embeddings (sync)
     pub fn get_memories_needing_embeddings(&self, batch_size: usize) -> Result<Vec<(String, String)>> {
-        let mut stmt = self.conn.prepare(
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
             r#"
             SELECT id, content
             FROM memories
@@ -769,10 +1540,79 @@ impl MemoryStore {
         Ok(memories)
     }
 
+    /// Next memories needing embeddings after the saved checkpoint (sync)
+    ///
+    /// Memories are taken in rowid order after the last checkpoint, so a
+    /// backfill interrupted by a restart resumes where it stopped rather than
+    /// re-scanning from the start. Once the end is reached the pass wraps
+    /// around, retrying memories whose embedding failed earlier.
+    pub fn next_backfill_batch(&self, batch_size: usize) -> Result<BackfillBatch> {
+        let checkpoint = self.backfill_checkpoint()?;
+        if checkpoint > 0 && !self.resume_logged.swap(true, Ordering::Relaxed) {
+            info!("Resuming embedding backfill from checkpoint (after rowid {})", checkpoint);
+        }
+
+        let mut batch = self.backfill_batch_after(checkpoint, batch_size)?;
+        if batch.memories.is_empty() && checkpoint > 0 {
+            debug!("Embedding backfill reached the end, starting a new pass");
+            self.save_backfill_checkpoint(0)?;
+            batch = self.backfill_batch_after(0, batch_size)?;
+        }
+        Ok(batch)
+    }
+
+    fn backfill_batch_after(&self, after_rowid: i64, batch_size: usize) -> Result<BackfillBatch> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT rowid, id, content
+            FROM memories
+            WHERE embedding IS NULL AND rowid > ?1
+            ORDER BY rowid
+            LIMIT ?2
+            "#,
+        )?;
+
+        let rows: Vec<(i64, String, String)> = stmt
+            .query_map(params![after_rowid, batch_size], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(BackfillBatch {
+            checkpoint: rows.last().map(|(rowid, _, _)| *rowid).unwrap_or(after_rowid),
+            memories: rows.into_iter().map(|(_, id, content)| (id, content)).collect(),
+        })
+    }
+
+    /// Rowid the embedding backfill has processed up to (0 = from the start)
+    pub fn backfill_checkpoint(&self) -> Result<i64> {
+        let checkpoint = self
+            .db
+            .reader()
+            .query_row(
+                "SELECT last_rowid FROM backfill_state WHERE key = ?1",
+                params![BACKFILL_CHECKPOINT_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(checkpoint.unwrap_or(0))
+    }
+
+    /// Persist backfill progress so a restarted backfill resumes after `rowid`
+    pub fn save_backfill_checkpoint(&self, rowid: i64) -> Result<()> {
+        self.db.writer().execute(
+            "INSERT INTO backfill_state (key, last_rowid) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET last_rowid = excluded.last_rowid",
+            params![BACKFILL_CHECKPOINT_KEY, rowid],
+        )?;
+        Ok(())
+    }
+
     /// Store a single embedding (sync)
     pub fn store_embedding(&self, id: &str, embedding: &[f32]) -> Result<()> {
         let bytes = embedding_to_bytes(embedding);
-        self.conn.execute(
+        let conn = self.db.writer();
+        conn.execute(
             "UPDATE memories SET embedding = ?1 WHERE id = ?2",
             params![bytes, id],
         )?;
@@ -794,29 +1634,16 @@ impl MemoryStore {
             }
         };
 
-        // Get memories without embeddings
-        let mut stmt = self.conn.prepare(
-            r#"
-            SELECT id, content
-            FROM memories
-            WHERE embedding IS NULL
-            LIMIT ?1
-            "#,
-        )?;
-
-        let memories: Vec<(String, String)> = stmt
-            .query_map(params![batch_size], |row| Ok((row.get(0)?, row.get(1)?)))?
-            .filter_map(|r| r.ok())
-            .collect();
-
-        let total = memories.len();
+        // Get memories without embeddings, resuming from the checkpoint
+        let batch = self.next_backfill_batch(batch_size)?;
+        let total = batch.memories.len();
         let mut embedded = 0;
 
-        for (id, content) in memories {
+        for (id, content) in batch.memories {
             match embedder.read().await.embed(&content).await {
                 Ok(embedding) => {
                     let bytes = embedding_to_bytes(&embedding);
-                    self.conn.execute(
+                    self.db.writer().execute(
                         "UPDATE memories SET embedding = ?1 WHERE id = ?2",
                         params![bytes, id],
                     )?;
@@ -829,17 +1656,249 @@ impl MemoryStore {
             }
         }
 
+        self.save_backfill_checkpoint(batch.checkpoint)?;
         info!("Backfilled {}/{} memories with embeddings", embedded, total);
         Ok(embedded)
     }
 
+    /// Shift a memory's confidence by `delta`, clamped to `[min, max]`
+    ///
+    /// Returns the new confidence, or None if the memory doesn't exist.
+    pub fn adjust_confidence(&self, id: &str, delta: f64, min: f64, max: f64) -> Result<Option<f64>> {
+        let conn = self.db.writer();
+        let updated = conn.execute(
+            "UPDATE memories SET confidence = MIN(MAX(confidence + ?1, ?2), ?3) WHERE id = ?4",
+            params![delta, min, max, id],
+        )?;
+        if updated == 0 {
+            return Ok(None);
+        }
+
+        let confidence =
+            conn.query_row("SELECT confidence FROM memories WHERE id = ?1", params![id], |row| row.get(0))?;
+        Ok(Some(confidence))
+    }
+
+    /// Apply a user's thumbs-up/down to a memory and log the adjustment
+    ///
+    /// Like `adjust_confidence`, but the change that survives clamping is
+    /// recorded so `reset_feedback` can undo it later.
+    pub fn apply_feedback(
+        &self,
+        id: &str,
+        user_id: i64,
+        positive: bool,
+        delta: f64,
+        min: f64,
+        max: f64,
+    ) -> Result<Option<f64>> {
+        let mut conn = self.db.writer();
+        let tx = conn.transaction()?;
+        let Some(before): Option<f64> = tx
+            .query_row("SELECT confidence FROM memories WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        let after = (before + delta).max(min).min(max);
+        tx.execute("UPDATE memories SET confidence = ?1 WHERE id = ?2", params![after, id])?;
+        tx.execute(
+            "INSERT INTO memory_feedback (memory_id, user_id, positive, delta) VALUES (?1, ?2, ?3, ?4)",
+            params![id, user_id, positive, after - before],
+        )?;
+        tx.commit()?;
+        Ok(Some(after))
+    }
+
+    /// Thumbs-up/down ratings per memory, for the given ids that have any
+    pub fn feedback_counts(&self, ids: &[&str]) -> Result<HashMap<String, FeedbackCounts>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            "SELECT SUM(positive), SUM(1 - positive) FROM memory_feedback WHERE memory_id = ?1",
+        )?;
+        let mut counts = HashMap::new();
+        for id in ids {
+            let (up, down): (Option<i64>, Option<i64>) =
+                stmt.query_row(params![id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            let entry = FeedbackCounts {
+                up: up.unwrap_or(0) as usize,
+                down: down.unwrap_or(0) as usize,
+            };
+            if !entry.is_empty() {
+                counts.insert(id.to_string(), entry);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Undo a user's logged feedback, on every memory or only `memory_id`
+    ///
+    /// Each memory's confidence moves back by the sum of that user's applied
+    /// deltas (clamped to `[min, max]`), and the ratings leave the log.
+    pub fn reset_feedback(&self, user_id: i64, memory_id: Option<&str>, min: f64, max: f64) -> Result<FeedbackReset> {
+        let mut conn = self.db.writer();
+        let tx = conn.transaction()?;
+        let totals: Vec<(String, f64, i64)> = {
+            let mut stmt = tx.prepare(
+                "SELECT memory_id, SUM(delta), COUNT(*) FROM memory_feedback
+                 WHERE user_id = ?1 AND (?2 IS NULL OR memory_id = ?2)
+                 GROUP BY memory_id",
+            )?;
+            let rows = stmt.query_map(params![user_id, memory_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        let mut reset = FeedbackReset::default();
+        for (id, delta, count) in &totals {
+            reset.ratings += *count as usize;
+            let updated = tx.execute(
+                "UPDATE memories SET confidence = MIN(MAX(confidence - ?1, ?2), ?3) WHERE id = ?4",
+                params![delta, min, max, id],
+            )?;
+            if updated > 0 {
+                reset.memory_ids.push(id.clone());
+            }
+        }
+        tx.execute(
+            "DELETE FROM memory_feedback WHERE user_id = ?1 AND (?2 IS NULL OR memory_id = ?2)",
+            params![user_id, memory_id],
+        )?;
+        tx.commit()?;
+        Ok(reset)
+    }
+
+    /// Stored embedding dimensions as (dimension, count), most common first
+    pub fn stored_dimensions(&self) -> Result<Vec<(usize, usize)>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT length(embedding) / 4 AS dim, COUNT(*)
+            FROM memories
+            WHERE embedding IS NOT NULL
+            GROUP BY dim
+            ORDER BY COUNT(*) DESC
+            "#,
+        )?;
+
+        let dims = stmt
+            .query_map([], |row| {
+                let dim: i64 = row.get(0)?;
+                let count: i64 = row.get(1)?;
+                Ok((dim as usize, count as usize))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(dims)
+    }
+
+    /// Compare stored embedding dimensions with what the current embedder produces
+    ///
+    /// Probes the embedder once; `current` is None if it is unavailable.
+    pub async fn check_dimensions(&self) -> Result<DimensionReport> {
+        let stored = self.stored_dimensions()?;
+        let current = match &self.embedder {
+            Some(embedder) => match embedder.read().await.embed_uncached("dimension probe").await {
+                Ok(embedding) => Some(embedding.len()),
+                Err(e) => {
+                    warn!("Embedding dimension probe failed: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Ok(DimensionReport { stored, current })
+    }
+
+    /// Replace every stored embedding and rebuild the HNSW index
+    ///
+    /// Memories not in `embeddings` are left without one (picked up by backfill).
+    pub fn replace_embeddings(&self, embeddings: &[(String, Vec<f32>)]) -> Result<usize> {
+        let conn = self.db.writer();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("UPDATE memories SET embedding = NULL", [])?;
+        let mut stored = 0;
+        for (id, embedding) in embeddings {
+            stored += tx.execute(
+                "UPDATE memories SET embedding = ?1 WHERE id = ?2",
+                params![embedding_to_bytes(embedding), id],
+            )?;
+        }
+        tx.commit()?;
+
+        let mut index = HnswIndex::new();
+        for (id, embedding) in embeddings {
+            index.insert(id.clone(), embedding.clone());
+        }
+        let indexed = index.len();
+        *self.hnsw_index.lock().unwrap() = index;
+
+        info!("Replaced embeddings: {} stored, {} indexed", stored, indexed);
+        Ok(stored)
+    }
+
+    /// Re-embed every memory with the current embedder (dimension migration)
+    ///
+    /// Embeddings are computed without holding any connection and only swapped
+    /// in once all are done, so search keeps working on the old vectors until
+    /// then. `progress` is called with (done, total) after each batch. Fails
+    /// without touching stored embeddings if none could be computed.
+    pub async fn reembed_all(
+        &self,
+        batch_size: usize,
+        mut progress: impl FnMut(usize, usize),
+    ) -> Result<ReembedReport> {
+        let embedder = self
+            .get_embedder()
+            .ok_or_else(|| anyhow::anyhow!("Embeddings are unavailable"))?;
+        let memories: Vec<(String, String)> = {
+            let conn = self.db.reader();
+            let mut stmt = conn.prepare("SELECT id, content FROM memories ORDER BY created_at")?;
+            let memories = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .filter_map(|r| r.ok())
+                .collect();
+            memories
+        };
+
+        let total = memories.len();
+        let mut embeddings = Vec::with_capacity(total);
+        let mut failed = 0;
+        for (done, batch) in memories.chunks(batch_size.max(1)).enumerate() {
+            for (id, content) in batch {
+                match embedder.read().await.embed_uncached(content).await {
+                    Ok(embedding) => embeddings.push((id.clone(), embedding)),
+                    Err(e) => {
+                        warn!("Failed to re-embed memory {}: {}", id.get(..8).unwrap_or(id), e);
+                        failed += 1;
+                    }
+                }
+            }
+            progress((done * batch_size.max(1) + batch.len()).min(total), total);
+        }
+
+        if total > 0 && embeddings.is_empty() {
+            anyhow::bail!("No memories could be re-embedded, existing embeddings kept");
+        }
+
+        let dimension = embeddings.first().map(|(_, e)| e.len());
+        let embedded = self.replace_embeddings(&embeddings)?;
+
+        Ok(ReembedReport {
+            total,
+            embedded,
+            failed,
+            dimension,
+        })
+    }
+
     /// Get embedding statistics
     pub fn embedding_stats(&self) -> Result<EmbeddingStats> {
-        let total: i64 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+        let conn = self.db.reader();
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
 
-        let with_embedding: i64 = self.conn.query_row(
+        let with_embedding: i64 = conn.query_row(
             "SELECT COUNT(*) FROM memories WHERE embedding IS NOT NULL",
             [],
             |row| row.get(0),
@@ -857,20 +1916,23 @@ impl MemoryStore {
         })
     }
 
-    /// Get memories by category
-    pub fn get_by_category(&self, category: &str, limit: usize) -> Result<Vec<MemoryEntry>> {
-        let mut stmt = self.conn.prepare(
+    /// Get memories by category that are visible in `scope`, newest first
+    pub fn get_by_category(&self, category: &str, limit: usize, scope: MemoryScope) -> Result<Vec<MemoryEntry>> {
+        let conn = self.db.reader();
+        // The scope is part of the WHERE clause so the limit counts visible rows only
+        let mut stmt = conn.prepare(&format!(
             r#"
-            SELECT id, content, category, source, confidence, created_at, access_count, embedding
+            SELECT id, content, category, source, confidence, created_at, access_count, embedding, shared, owner_id
             FROM memories
-            WHERE category = ?1
+            WHERE category = ?1 AND {}
             ORDER BY created_at DESC
             LIMIT ?2
             "#,
-        )?;
+            MemoryScope::sql_filter("?3"),
+        ))?;
 
         let results = stmt
-            .query_map(params![category, limit], |row| {
+            .query_map(params![category, limit, scope.sql_user()], |row| {
                 let embedding_bytes: Option<Vec<u8>> = row.get(7)?;
                 Ok(MemoryEntry {
                     id: row.get(0)?,
@@ -881,6 +1943,8 @@ impl MemoryStore {
                     created_at: row.get(5)?,
                     access_count: row.get(6)?,
                     embedding: embedding_bytes.map(|b| embedding_from_bytes(&b)),
+                    shared: row.get(8)?,
+                    owner_id: row.get(9)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -891,9 +1955,10 @@ impl MemoryStore {
 
     /// Get recent memories
     pub fn get_recent(&self, limit: usize) -> Result<Vec<MemoryEntry>> {
-        let mut stmt = self.conn.prepare(
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
             r#"
-            SELECT id, content, category, source, confidence, created_at, access_count, embedding
+            SELECT id, content, category, source, confidence, created_at, access_count, embedding, shared, owner_id
             FROM memories
             ORDER BY created_at DESC
             LIMIT ?1
@@ -912,6 +1977,8 @@ impl MemoryStore {
                     created_at: row.get(5)?,
                     access_count: row.get(6)?,
                     embedding: embedding_bytes.map(|b| embedding_from_bytes(&b)),
+                    shared: row.get(8)?,
+                    owner_id: row.get(9)?,
                 })
             })?
             .filter_map(|r| r.ok())
@@ -920,21 +1987,96 @@ impl MemoryStore {
         Ok(results)
     }
 
+    /// One page of all memories, newest first (for paginated listings)
+    pub fn list_page(&self, offset: usize, limit: usize) -> Result<Vec<MemoryEntry>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, content, category, source, confidence, created_at, access_count, embedding, shared, owner_id
+            FROM memories
+            ORDER BY created_at DESC, id
+            LIMIT ?1 OFFSET ?2
+            "#,
+        )?;
+
+        let results = stmt
+            .query_map(params![limit, offset], |row| {
+                let embedding_bytes: Option<Vec<u8>> = row.get(7)?;
+                Ok(MemoryEntry {
+                    id: row.get(0)?,
+                    content: row.get(1)?,
+                    category: row.get(2)?,
+                    source: row.get(3)?,
+                    confidence: row.get(4)?,
+                    created_at: row.get(5)?,
+                    access_count: row.get(6)?,
+                    embedding: embedding_bytes.map(|b| embedding_from_bytes(&b)),
+                    shared: row.get(8)?,
+                    owner_id: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Get memories learned since a unix timestamp and visible in `scope`,
+    /// most confident first
+    pub fn get_since(&self, since: i64, limit: usize, scope: MemoryScope) -> Result<Vec<MemoryEntry>> {
+        let conn = self.db.reader();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, content, category, source, confidence, created_at, access_count, embedding, shared, owner_id
+            FROM memories
+            WHERE created_at >= ?1
+            ORDER BY confidence DESC, created_at DESC
+            LIMIT ?2
+            "#,
+        )?;
+        // Scoped lookups filter afterwards, so they can't cut the query short
+        let sql_limit = if scope == MemoryScope::Global { limit as i64 } else { -1 };
+
+        let results = stmt
+            .query_map(params![since, sql_limit], |row| {
+                let embedding_bytes: Option<Vec<u8>> = row.get(7)?;
+                Ok(MemoryEntry {
+                    id: row.get(0)?,
+                    content: row.get(1)?,
+                    category: row.get(2)?,
+                    source: row.get(3)?,
+                    confidence: row.get(4)?,
+                    created_at: row.get(5)?,
+                    access_count: row.get(6)?,
+                    embedding: embedding_bytes.map(|b| embedding_from_bytes(&b)),
+                    shared: row.get(8)?,
+                    owner_id: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .filter(|entry| scope.allows(entry))
+            .take(limit)
+            .collect();
+
+        Ok(results)
+    }
+
     /// Delete a memory
     pub fn forget(&self, id: &str) -> Result<bool> {
-        let rows = self.conn.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+        let conn = self.db.writer();
+        let rows = conn.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+        conn.execute("DELETE FROM memory_tags WHERE memory_id = ?1", params![id])?;
+        conn.execute("DELETE FROM memory_feedback WHERE memory_id = ?1", params![id])?;
+        self.hnsw_index.lock().unwrap().remove(id);
         Ok(rows > 0)
     }
 
     /// Get memory stats
     pub fn stats(&self) -> Result<MemoryStats> {
-        let total: i64 = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+        let conn = self.db.reader();
+        let total: i64 = conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
 
-        let mut stmt = self
-            .conn
-            .prepare("SELECT category, COUNT(*) FROM memories GROUP BY category")?;
+        let mut stmt = conn.prepare("SELECT category, COUNT(*) FROM memories GROUP BY category")?;
         let by_category: Vec<(String, i64)> = stmt
             .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
             .filter_map(|r| r.ok())
@@ -963,6 +2105,63 @@ pub struct EmbeddingStats {
     pub coverage_percent: f64,
 }
 
+/// Stored embedding dimensions versus the current embedder
+#[derive(Debug, Clone, Default)]
+pub struct DimensionReport {
+    /// (dimension, count) of stored embeddings, most common first
+    pub stored: Vec<(usize, usize)>,
+    /// Dimension the current embedder produces (None if unavailable)
+    pub current: Option<usize>,
+}
+
+impl DimensionReport {
+    /// Stored embeddings that won't be searchable alongside the rest
+    ///
+    /// Compared against the current embedder, or against the most common
+    /// stored dimension if the embedder is unavailable.
+    pub fn stale_count(&self) -> usize {
+        let expected = self.current.or_else(|| self.stored.first().map(|(dim, _)| *dim));
+        self.stored
+            .iter()
+            .filter(|(dim, _)| Some(*dim) != expected)
+            .map(|(_, count)| count)
+            .sum()
+    }
+
+    /// True if semantic search is (partly) broken by mixed dimensions
+    pub fn is_mismatched(&self) -> bool {
+        self.stale_count() > 0
+    }
+
+    /// Format for display
+    pub fn format(&self) -> String {
+        let stored = if self.stored.is_empty() {
+            "none".to_string()
+        } else {
+            self.stored
+                .iter()
+                .map(|(dim, count)| format!("{}d x{}", dim, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let current = self
+            .current
+            .map(|d| format!("{}d", d))
+            .unwrap_or_else(|| "unknown".to_string());
+        format!("Stored embeddings: {}\nCurrent model: {}", stored, current)
+    }
+}
+
+/// Result of a `reembed_all` migration
+#[derive(Debug, Clone, Default)]
+pub struct ReembedReport {
+    pub total: usize,
+    pub embedded: usize,
+    pub failed: usize,
+    /// New embedding dimension (None if nothing was embedded)
+    pub dimension: Option<usize>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -979,10 +2178,10 @@ mod tests {
         let store = temp_db("search");
 
         store
-            .learn("Rust is a systems programming language", "tech", "test", 0.9)
+            .learn("Rust is a systems programming language", "tech", "test", None, 0.9)
             .unwrap();
         store
-            .learn("Vue is a JavaScript framework", "tech", "test", 0.8)
+            .learn("Vue is a JavaScript framework", "tech", "test", None, 0.8)
             .unwrap();
 
         let results = store.search("Rust programming", 5).unwrap();
@@ -994,23 +2193,45 @@ mod tests {
     fn test_categories() {
         let store = temp_db("categories");
 
-        store.learn("Fact 1", "facts", "test", 0.9).unwrap();
-        store.learn("Fact 2", "facts", "test", 0.9).unwrap();
-        store.learn("Preference 1", "preferences", "test", 0.9).unwrap();
+        store.learn("Fact 1", "facts", "test", None, 0.9).unwrap();
+        store.learn("Fact 2", "facts", "test", None, 0.9).unwrap();
+        store.learn("Preference 1", "preferences", "test", None, 0.9).unwrap();
 
-        let facts = store.get_by_category("facts", 10).unwrap();
+        let facts = store.get_by_category("facts", 10, MemoryScope::Global).unwrap();
         assert_eq!(facts.len(), 2);
 
         let stats = store.stats().unwrap();
         assert_eq!(stats.total_entries, 3);
     }
 
+    #[test]
+    fn test_search_records_access() {
+        let store = temp_db("record_access");
+        let hit = store.learn("The staging cluster runs on ar-2", "fact", "test", None, 0.9).unwrap();
+        let other = store.learn("Lunch is at noon", "fact", "test", None, 0.9).unwrap();
+
+        store.search_hybrid_sync_readonly("staging", None, 5, 0.4, MemoryScope::Global).unwrap();
+        assert_eq!(store.get_by_id(&hit).unwrap().unwrap().access_count, 0);
+
+        let results = store.search_hybrid_sync("staging", None, 5, 0.4, MemoryScope::Global).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(store.get_by_id(&hit).unwrap().unwrap().access_count, 1);
+        assert_eq!(store.get_by_id(&other).unwrap().unwrap().access_count, 0);
+
+        // The recency fallback for a miss isn't counted
+        store.search_hybrid_sync("kubernetes", None, 5, 0.4, MemoryScope::Global).unwrap();
+        assert_eq!(store.get_by_id(&other).unwrap().unwrap().access_count, 0);
+
+        assert_eq!(store.record_access(&[hit.as_str(), other.as_str(), "missing"]).unwrap(), 2);
+        assert_eq!(store.record_access(&[]).unwrap(), 0);
+    }
+
     #[test]
     fn test_embedding_stats() {
         let store = temp_db("embedding_stats");
 
-        store.learn("Memory 1", "test", "test", 0.9).unwrap();
-        store.learn("Memory 2", "test", "test", 0.9).unwrap();
+        store.learn("Memory 1", "test", "test", None, 0.9).unwrap();
+        store.learn("Memory 2", "test", "test", None, 0.9).unwrap();
 
         let stats = store.embedding_stats().unwrap();
         assert_eq!(stats.total_memories, 2);
@@ -1022,7 +2243,7 @@ mod tests {
     fn test_get_by_id() {
         let store = temp_db("get_by_id");
 
-        let id = store.learn("Test memory", "test", "test", 0.9).unwrap();
+        let id = store.learn("Test memory", "test", "test", None, 0.9).unwrap();
         let entry = store.get_by_id(&id).unwrap();
 
         assert!(entry.is_some());
@@ -1032,6 +2253,294 @@ mod tests {
         assert!(missing.is_none());
     }
 
+    #[test]
+    fn test_get_since() {
+        let store = temp_db("get_since");
+
+        store.learn("Old fact", "facts", "test", None, 0.9).unwrap();
+        store.learn("New fact", "facts", "test", None, 0.7).unwrap();
+        store.learn("New preference", "preferences", "test", None, 0.95).unwrap();
+        store
+            .db
+            .writer()
+            .execute("UPDATE memories SET created_at = created_at - 172800 WHERE content = 'Old fact'", [])
+            .unwrap();
+
+        let since = chrono::Utc::now().timestamp() - 86400;
+        let recent = store.get_since(since, 10, MemoryScope::Global).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].content, "New preference");
+
+        let limited = store.get_since(since, 1, MemoryScope::Global).unwrap();
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn test_scoped_hybrid_search() {
+        let store = temp_db("scoped");
+        store.learn("Alice deploys on Fridays", "facts", "telegram_user_1", Some(1), 0.9).unwrap();
+        let bob = store.learn("Bob deploys on Mondays", "facts", "auto_learn_user_2", Some(2), 0.9).unwrap();
+        store.learn("Deploys need a ticket", "facts", "context_load", None, 0.9).unwrap();
+
+        let search = |scope| {
+            let mut found: Vec<String> = store
+                .search_hybrid_sync("deploys", None, 10, 0.4, scope)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.entry.content)
+                .collect();
+            found.sort();
+            found
+        };
+
+        assert_eq!(search(MemoryScope::Global).len(), 3);
+        assert_eq!(
+            search(MemoryScope::User(1)),
+            vec!["Alice deploys on Fridays", "Deploys need a ticket"]
+        );
+
+        assert!(store.set_shared(&bob, true).unwrap());
+        assert_eq!(search(MemoryScope::User(1)).len(), 3);
+        assert_eq!(
+            MemoryScopeMode::parse("per_user").unwrap().scope_for(7),
+            MemoryScope::User(7)
+        );
+    }
+
+    #[test]
+    fn test_owner_comes_from_column_not_source() {
+        let store = temp_db("owner_column");
+        // Group chat IDs are negative and aren't the user who owns the memory
+        let alice = store.learn("Alice's group note", "facts", "chat_-1001234", Some(1), 0.9).unwrap();
+        store.learn("Untagged group note", "facts", "chat_-1005678", None, 0.9).unwrap();
+
+        let alice = store.get_by_id(&alice).unwrap().unwrap();
+        assert_eq!(alice.owner_id, Some(1));
+        assert!(MemoryScope::User(1).allows(&alice));
+        assert!(!MemoryScope::User(-1001234).allows(&alice));
+
+        let names = |user| {
+            let mut found: Vec<String> = store
+                .get_by_category("facts", 10, MemoryScope::User(user))
+                .unwrap()
+                .into_iter()
+                .map(|e| e.content)
+                .collect();
+            found.sort();
+            found
+        };
+        assert_eq!(names(1), vec!["Alice's group note", "Untagged group note"]);
+        assert_eq!(names(2), vec!["Untagged group note"]);
+        assert_eq!(names(-1001234), vec!["Untagged group note"]);
+    }
+
+    #[test]
+    fn test_owner_column_migration_keeps_private_memories_private() {
+        let path = PathBuf::from("/tmp/claudebot_test_owner_migration.db");
+        let _ = std::fs::remove_file(&path);
+        // A database from before the owner column
+        let store = MemoryStore::open(&path).unwrap();
+        let alice = store.learn("Alice note", "general", "telegram_user_1", None, 0.9).unwrap();
+        let loaded = store.learn("Loaded note", "general", "context_load", None, 0.9).unwrap();
+        store.db.writer().execute("ALTER TABLE memories DROP COLUMN owner_id", []).unwrap();
+        drop(store);
+
+        let store = MemoryStore::open(&path).unwrap();
+        assert_eq!(store.get_by_id(&alice).unwrap().unwrap().owner_id, Some(1));
+        assert_eq!(store.get_by_id(&loaded).unwrap().unwrap().owner_id, None);
+        // A reopen leaves owners written since then alone
+        store.learn("Group note", "general", "chat_-100", Some(2), 0.9).unwrap();
+        drop(store);
+        let store = MemoryStore::open(&path).unwrap();
+        let group = store.get_by_category("general", 10, MemoryScope::User(2)).unwrap();
+        assert!(group.iter().any(|e| e.content == "Group note"));
+        assert!(group.iter().all(|e| e.content != "Alice note"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_get_by_category_scope_applies_before_limit() {
+        let store = temp_db("category_scope");
+        for i in 0..5 {
+            store.learn(&format!("Bob note {}", i), "identity", "telegram_user_2", Some(2), 0.9).unwrap();
+        }
+        store.learn("I am Alice", "identity", "telegram_user_1", Some(1), 0.9).unwrap();
+        store.learn("Deploys need a ticket", "identity", "context_load", None, 0.9).unwrap();
+        store.learn("Group rule", "identity", "telegram_chat_-100", Some(-100), 0.9).unwrap();
+        store.learn("Odd tag", "identity", "import_v2x", None, 0.9).unwrap();
+        let bob = store.learn("Bob note shared", "identity", "telegram_user_2", Some(2), 0.9).unwrap();
+        store.set_shared(&bob, true).unwrap();
+        // Bob's notes are the oldest in this list
+        let conn = store.db.writer();
+        conn.execute("UPDATE memories SET created_at = created_at + 10 WHERE source <> 'telegram_user_2'", [])
+            .unwrap();
+        drop(conn);
+
+        let all = store.get_by_category("identity", 100, MemoryScope::Global).unwrap();
+        assert_eq!(all.len(), 10);
+        for user in [1, 2, -100] {
+            let scope = MemoryScope::User(user);
+            let mut expected: Vec<&str> =
+                all.iter().filter(|e| scope.allows(e)).map(|e| e.content.as_str()).collect();
+            expected.sort();
+            let found = store.get_by_category("identity", 100, scope).unwrap();
+            let mut found: Vec<&str> = found.iter().map(|e| e.content.as_str()).collect();
+            found.sort();
+            assert_eq!(found, expected, "user {}", user);
+        }
+
+        let alice = store.get_by_category("identity", 3, MemoryScope::User(1)).unwrap();
+        assert_eq!(alice.len(), 3);
+        assert!(alice.iter().any(|e| e.content == "I am Alice"));
+        assert!(alice.iter().all(|e| e.content != "Bob note 0"));
+    }
+
+    #[test]
+    fn test_memory_tags() {
+        let store = temp_db("tags");
+        let deploy = store.learn("Deploys go through the ar-2 host", "fact", "telegram_user_1", Some(1), 0.9).unwrap();
+        let coffee = store.learn("The user drinks coffee black", "preference", "telegram_user_1", Some(1), 0.8).unwrap();
+        let other = store.learn("Deploy freezes start on Fridays", "fact", "telegram_user_2", Some(2), 0.8).unwrap();
+
+        assert!(store.add_tag(&deploy, "#Project-X").unwrap());
+        assert!(!store.add_tag(&deploy, "project-x").unwrap());
+        assert!(store.add_tag(&deploy, "important").unwrap());
+        assert!(store.add_tag(&other, "project-x").unwrap());
+        assert!(!store.add_tag("missing", "project-x").unwrap());
+        assert!(store.add_tag(&coffee, "two words").is_err());
+        assert_eq!(store.tags_for(&deploy).unwrap(), vec!["important", "project-x"]);
+        assert_eq!(store.tag_counts().unwrap()[0], ("project-x".to_string(), 2));
+
+        let tagged: Vec<String> = store.search_by_tag("PROJECT-X", 10).unwrap().into_iter().map(|e| e.id).collect();
+        assert_eq!(tagged.len(), 2);
+        assert!(!tagged.contains(&coffee));
+
+        // The tag filter and scope both apply to hybrid results
+        let results = store
+            .search_hybrid_tagged("deploy", None, 5, 0.4, MemoryScope::User(1), "project-x")
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entry.id, deploy);
+        let fallback = store
+            .search_hybrid_tagged("espresso", None, 5, 0.4, MemoryScope::Global, "important")
+            .unwrap();
+        assert_eq!(fallback[0].entry.id, deploy);
+
+        assert!(store.remove_tag(&deploy, "important").unwrap());
+        assert!(!store.remove_tag(&deploy, "important").unwrap());
+        store.forget(&other).unwrap();
+        assert_eq!(store.search_by_tag("project-x", 10).unwrap().len(), 1);
+        assert_eq!(normalize_tag("  #Q3:Launch "), Some("q3:launch".to_string()));
+        assert_eq!(normalize_tag("#"), None);
+    }
+
+    #[test]
+    fn test_feedback_log_survives_reopen_and_resets() {
+        let path = PathBuf::from("/tmp/claudebot_test_feedback_log.db");
+        let _ = std::fs::remove_file(&path);
+        let store = MemoryStore::open(&path).unwrap();
+        let deploy = store.learn("Deploys go through staging", "fact", "telegram_user_1", Some(1), 0.5).unwrap();
+        let coffee = store.learn("The user drinks coffee black", "preference", "telegram_user_1", Some(1), 0.98).unwrap();
+
+        store.apply_feedback(&deploy, 1, true, 0.05, 0.1, 1.0).unwrap();
+        store.apply_feedback(&deploy, 1, false, -0.1, 0.1, 1.0).unwrap();
+        store.apply_feedback(&deploy, 2, true, 0.05, 0.1, 1.0).unwrap();
+        // Clamped at the ceiling: only 0.02 is applied and logged
+        store.apply_feedback(&coffee, 1, true, 0.05, 0.1, 1.0).unwrap();
+        assert_eq!(store.apply_feedback("missing", 1, true, 0.05, 0.1, 1.0).unwrap(), None);
+        drop(store);
+
+        let store = MemoryStore::open(&path).unwrap();
+        let counts = store.feedback_counts(&[&deploy, &coffee, "missing"]).unwrap();
+        assert_eq!(counts[&deploy], FeedbackCounts { up: 2, down: 1 });
+        assert_eq!(counts[&deploy].label(), "↑2 ↓1");
+        assert!(!counts.contains_key("missing"));
+
+        let reset = store.reset_feedback(1, None, 0.1, 1.0).unwrap();
+        assert_eq!(reset.ratings, 3);
+        assert_eq!(reset.memory_ids.len(), 2);
+        // User 2's thumbs-up stays
+        assert!((store.get_by_id(&deploy).unwrap().unwrap().confidence - 0.55).abs() < 1e-9);
+        assert!((store.get_by_id(&coffee).unwrap().unwrap().confidence - 0.98).abs() < 1e-9);
+        assert_eq!(store.feedback_counts(&[&deploy]).unwrap()[&deploy], FeedbackCounts { up: 1, down: 0 });
+
+        assert_eq!(store.reset_feedback(2, Some(&coffee), 0.1, 1.0).unwrap(), FeedbackReset::default());
+        assert_eq!(store.reset_feedback(2, Some(&deploy), 0.1, 1.0).unwrap().ratings, 1);
+        assert!(store.feedback_counts(&[&deploy]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_id_prefix() {
+        let store = temp_db("resolve_id");
+
+        let id = store.learn("Prefix lookup", "test", "test", None, 0.9).unwrap();
+        let entry = store.resolve_id(&id[..8]).unwrap().unwrap();
+        assert_eq!(entry.id, id);
+        assert!(store.resolve_id("zzzz").unwrap().is_none());
+        assert!(store.resolve_id("%").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_similar_to() {
+        let store = temp_db("similar_to");
+
+        let base = store.learn("Rust ownership rules", "tech", "test", None, 0.9).unwrap();
+        let close = store.learn("Rust borrow checker", "tech", "test", None, 0.9).unwrap();
+        let far = store.learn("Favorite pizza topping", "preferences", "test", None, 0.9).unwrap();
+        let bare = store.learn("No embedding here", "test", "test", None, 0.9).unwrap();
+
+        store.store_embedding(&base, &[1.0, 0.0, 0.0]).unwrap();
+        store.store_embedding(&close, &[0.9, 0.1, 0.0]).unwrap();
+        store.store_embedding(&far, &[0.0, 0.0, 1.0]).unwrap();
+
+        let results = store.similar_to(&base[..8], 5).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].entry.id, close);
+        assert!(results[0].score > results[1].score);
+        assert!(results.iter().all(|r| r.entry.id != base));
+
+        // No embedding and no embedder available
+        assert!(store.similar_to(&bare, 5).await.is_err());
+        assert!(store.similar_to("missing", 5).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dimension_migration() {
+        let store = temp_db("dimension_migration");
+
+        let a = store.learn("Rust ownership rules", "tech", "test", None, 0.9).unwrap();
+        let b = store.learn("Rust borrow checker", "tech", "test", None, 0.9).unwrap();
+        let c = store.learn("Favorite pizza topping", "preferences", "test", None, 0.9).unwrap();
+        store.store_embedding(&a, &[1.0, 0.0]).unwrap();
+        store.store_embedding(&b, &[0.9, 0.1]).unwrap();
+        store.store_embedding(&c, &[0.0, 0.0, 1.0]).unwrap();
+
+        // Mixed dimensions: the 3d vector was skipped by the index
+        let report = store.check_dimensions().await.unwrap();
+        assert_eq!(report.stored, vec![(2, 2), (3, 1)]);
+        assert_eq!(report.current, None);
+        assert_eq!(report.stale_count(), 1);
+        assert!(report.format().contains("2d x2"));
+
+        let embeddings = vec![
+            (a.clone(), vec![1.0, 0.0, 0.0]),
+            (b.clone(), vec![0.9, 0.1, 0.0]),
+            (c.clone(), vec![0.0, 0.0, 1.0]),
+        ];
+        assert_eq!(store.replace_embeddings(&embeddings).unwrap(), 3);
+
+        let report = store.check_dimensions().await.unwrap();
+        assert_eq!(report.stored, vec![(3, 3)]);
+        assert!(!report.is_mismatched());
+        let results = store.similar_to(&a, 5).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].entry.id, b);
+
+        // No embedder: migration refuses to run
+        assert!(store.reembed_all(10, |_, _| {}).await.is_err());
+        assert_eq!(store.embedding_stats().unwrap().with_embeddings, 3);
+    }
+
     #[test]
     fn test_hnsw_index_insert() {
         // Test HNSW index basic insert operations
@@ -1059,6 +2568,111 @@ mod tests {
         // In production with 50+ real embeddings, HNSW search works correctly.
     }
 
+    #[test]
+    fn test_hnsw_tombstones_and_rebuild() {
+        let store = temp_db("hnsw_rebuild");
+        let mut ids = Vec::new();
+        for i in 0..5 {
+            let id = store.learn(&format!("memory {}", i), "fact", "test", None, 0.9).unwrap();
+            let mut emb = vec![0.01f32; 8];
+            emb[i] = 1.0;
+            store.store_embedding(&id, &emb).unwrap();
+            ids.push(id);
+        }
+        assert_eq!(store.index_health(), IndexHealth { live: 5, tombstones: 0 });
+
+        store.forget(&ids[0]).unwrap();
+        store.forget(&ids[1]).unwrap();
+        let health = store.index_health();
+        assert_eq!(health, IndexHealth { live: 3, tombstones: 2 });
+        assert!((health.tombstone_ratio() - 0.4).abs() < 1e-9);
+
+        let rebuild = store.rebuild_hnsw_index().unwrap();
+        assert_eq!((rebuild.reclaimed, rebuild.indexed), (2, 3));
+        assert_eq!(store.index_health(), IndexHealth { live: 3, tombstones: 0 });
+        assert_eq!(IndexHealth::default().tombstone_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_learn_batch() {
+        let store = temp_db("learn_batch");
+        let embedding = vec![0.5f32; 8];
+        let ids = store
+            .learn_batch(&[
+                ("Fact A", "facts", "test", None, 0.7, None),
+                ("Fact B", "facts", "test", None, 0.8, Some(embedding.as_slice())),
+                ("Fact A", "facts", "test", None, 0.9, None),
+            ])
+            .unwrap()
+            .ids;
+
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[0], ids[2]);
+        assert_eq!(store.stats().unwrap().total_entries, 2);
+        assert_eq!(store.get_by_id(&ids[0]).unwrap().unwrap().confidence, 0.9);
+        assert_eq!(store.embedding_stats().unwrap().with_embeddings, 1);
+        assert_eq!(store.index_health().live, 1);
+        assert!(store.learn_batch(&[]).unwrap().ids.is_empty());
+    }
+
+    #[test]
+    fn test_backfill_checkpoint_resumes() {
+        let path = PathBuf::from("/tmp/claudebot_test_backfill_checkpoint.db");
+        let _ = std::fs::remove_file(&path);
+        let store = MemoryStore::open(&path).unwrap();
+        let ids: Vec<String> = (0..5)
+            .map(|i| store.learn(&format!("Fact {}", i), "facts", "test", None, 0.9).unwrap())
+            .collect();
+
+        // First batch is processed but only one embedding succeeds
+        let batch = store.next_backfill_batch(2).unwrap();
+        assert_eq!(batch.memories.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>(), ids[..2]);
+        store.store_embedding(&ids[0], &[0.5; 8]).unwrap();
+        store.save_backfill_checkpoint(batch.checkpoint).unwrap();
+
+        // A reopened store (restart) resumes after the checkpoint
+        drop(store);
+        let store = MemoryStore::open(&path).unwrap();
+        let batch = store.next_backfill_batch(10).unwrap();
+        assert_eq!(batch.memories.len(), 3);
+        assert_eq!(batch.memories[0].0, ids[2]);
+        store.save_backfill_checkpoint(batch.checkpoint).unwrap();
+
+        // Past the end, a new pass retries what's still missing
+        let batch = store.next_backfill_batch(10).unwrap();
+        assert_eq!(batch.memories.len(), 4);
+        assert_eq!(batch.memories[0].0, ids[1]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_memory_limits() {
+        let mut store = temp_db("memory_limits");
+        store.set_limits(MemoryLimits { max_chars: 40, reject_chars: 100 });
+
+        let id = store.learn("short fact", "facts", "test", None, 0.9).unwrap();
+        assert_eq!(store.get_by_id(&id).unwrap().unwrap().content, "short fact");
+
+        let long = "word ".repeat(15);
+        let id = store.learn(&long, "facts", "test", None, 0.9).unwrap();
+        let stored = store.get_by_id(&id).unwrap().unwrap().content;
+        assert!(stored.chars().count() <= 40, "{}", stored);
+        assert_eq!(stored, format!("{}{}", "word ".repeat(5).trim_end(), TRUNCATION_MARKER));
+
+        assert!(store.learn(&"x".repeat(101), "facts", "test", None, 0.9).is_err());
+        // One oversized entry doesn't lose the rest of the batch
+        let batch = store
+            .learn_batch(&[("ok", "facts", "test", None, 0.9, None), (&"x".repeat(101), "facts", "test", None, 0.9, None)])
+            .unwrap();
+        assert_eq!(batch.ids.len(), 1);
+        assert_eq!(batch.rejected.len(), 1);
+        assert_eq!(batch.rejected[0].0, 1);
+        assert!(store.get_by_id(&batch.ids[0]).unwrap().is_some());
+
+        let unlimited = MemoryLimits { max_chars: 0, reject_chars: 0 };
+        assert_eq!(unlimited.apply(&"y".repeat(200_000)).unwrap().len(), 200_000);
+    }
+
     #[test]
     fn test_hnsw_cosine_distance() {
         // Test the CosineDistance metric directly
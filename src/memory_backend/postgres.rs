@@ -0,0 +1,696 @@
+//! Postgres Memory Store
+//!
+//! Keeps memories in a `memories` table with a generated `tsvector` column
+//! (GIN-indexed) for keyword search. IDs are the same content hashes as
+//! `MemoryStore`, so learning the same content twice upserts.
+//!
+//! Embeddings use pgvector when the extension is installed (or can be
+//! created): each embedding dimension gets a partial HNSW index and
+//! nearest-neighbor search is an index scan on cosine distance. Without
+//! pgvector they are stored as `REAL[]` and ranked in-process. The mode is
+//! fixed when the table is created and read back from the column type.
+//!
+//! # Transport
+//!
+//! Connections use TLS (rustls) with the server certificate checked against
+//! the system roots, or the PEM file in `sslrootcert`, including the host
+//! name. `sslmode` may be:
+//! - `require` (default) / `verify-full` - TLS, certificate verified
+//! - `disable` - plaintext, only for localhost and Unix sockets
+//!
+//! `prefer` and `allow` would fall back to plaintext and `verify-ca` skips the
+//! host name check, so they are rejected.
+
+use anyhow::{Context, Result};
+use pgvector::Vector;
+use postgres::config::{Host, SslMode};
+use postgres::types::ToSql;
+use postgres::{Client, NoTls, Row};
+use reqwest::Url;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_postgres_rustls::MakeRustlsConnect;
+use tracing::{debug, info, warn};
+
+use super::MemoryBackend;
+use crate::embeddings::EmbeddingStore;
+use crate::memory::{MemoryEntry, MemoryLimits, MemoryScope, MemoryStats, MemoryStore, ScoredMemory, SearchResult};
+
+const COLUMNS: &str = "id, content, category, source, confidence, created_at, access_count, embedding, shared, owner_id";
+
+/// pgvector can't build an HNSW index on wider vectors
+const MAX_INDEXED_DIMENSIONS: usize = 2000;
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Param<'a> = &'a (dyn ToSql + Sync);
+
+/// How embeddings are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EmbeddingColumn {
+    /// pgvector `vector`, searched through per-dimension HNSW indexes
+    Vector,
+    /// `REAL[]`, searched by scanning rows of the same dimension
+    RealArray,
+}
+
+/// Parsed connection string plus the TLS connector it calls for
+#[derive(Clone)]
+struct ConnectOptions {
+    config: postgres::Config,
+    /// None only for `sslmode=disable` on a local connection
+    tls: Option<MakeRustlsConnect>,
+}
+
+impl ConnectOptions {
+    fn parse(url: &str) -> Result<Self> {
+        let mut parsed = Url::parse(url).context("Invalid Postgres URL")?;
+        let mut sslmode = None;
+        let mut root_cert = None;
+        let passthrough: Vec<(String, String)> = parsed
+            .query_pairs()
+            .filter_map(|(key, value)| match key.as_ref() {
+                "sslmode" => {
+                    sslmode = Some(value.into_owned());
+                    None
+                }
+                "sslrootcert" => {
+                    root_cert = Some(PathBuf::from(value.as_ref()));
+                    None
+                }
+                _ => Some((key.into_owned(), value.into_owned())),
+            })
+            .collect();
+        if passthrough.is_empty() {
+            parsed.set_query(None);
+        } else {
+            parsed.query_pairs_mut().clear().extend_pairs(passthrough);
+        }
+
+        let mut config = postgres::Config::from_str(parsed.as_str()).context("Invalid Postgres URL")?;
+        if config.get_connect_timeout().is_none() {
+            config.connect_timeout(DEFAULT_CONNECT_TIMEOUT);
+        }
+
+        let tls = match sslmode.as_deref().unwrap_or("require") {
+            "require" | "verify-full" => {
+                config.ssl_mode(SslMode::Require);
+                Some(tls_connector(root_cert.as_deref())?)
+            }
+            "disable" => {
+                if !config.get_hosts().iter().all(is_local_host) {
+                    anyhow::bail!(
+                        "sslmode=disable would send the password and memories in plaintext; \
+                         it's only allowed for localhost and Unix sockets"
+                    );
+                }
+                config.ssl_mode(SslMode::Disable);
+                None
+            }
+            "prefer" | "allow" => anyhow::bail!(
+                "sslmode={} may fall back to plaintext; use sslmode=require (or disable for localhost)",
+                sslmode.unwrap_or_default()
+            ),
+            "verify-ca" => anyhow::bail!("sslmode=verify-ca isn't supported (host names are always checked); use verify-full"),
+            other => anyhow::bail!("Unknown sslmode: {}", other),
+        };
+
+        Ok(Self { config, tls })
+    }
+
+    fn connect(&self) -> Result<Client> {
+        let client = match &self.tls {
+            Some(tls) => self.config.connect(tls.clone()),
+            None => self.config.connect(NoTls),
+        };
+        client.context("Failed to connect to Postgres")
+    }
+
+    /// `host:port/database` for logs
+    fn describe(&self) -> String {
+        let host = match self.config.get_hosts().first() {
+            Some(Host::Tcp(host)) => host.clone(),
+            #[cfg(unix)]
+            Some(Host::Unix(path)) => path.display().to_string(),
+            None => "localhost".to_string(),
+        };
+        let port = self.config.get_ports().first().copied().unwrap_or(5432);
+        format!("{}:{}/{}", host, port, self.config.get_dbname().unwrap_or(""))
+    }
+}
+
+fn is_local_host(host: &Host) -> bool {
+    match host {
+        Host::Tcp(host) => {
+            host == "localhost" || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+        }
+        #[cfg(unix)]
+        Host::Unix(_) => true,
+    }
+}
+
+/// rustls with the system roots, or only the certificates in `root_cert`
+fn tls_connector(root_cert: Option<&Path>) -> Result<MakeRustlsConnect> {
+    let mut roots = rustls::RootCertStore::empty();
+    match root_cert {
+        Some(path) => {
+            let pem = std::fs::read(path).with_context(|| format!("Failed to read sslrootcert {}", path.display()))?;
+            for cert in rustls_pemfile::certs(&mut pem.as_slice()) {
+                roots.add(cert.with_context(|| format!("Invalid certificate in {}", path.display()))?)?;
+            }
+        }
+        None => {
+            let native = rustls_native_certs::load_native_certs();
+            for error in &native.errors {
+                debug!("Skipping system certificate: {}", error);
+            }
+            roots.add_parsable_certificates(native.certs);
+        }
+    }
+    if roots.is_empty() {
+        anyhow::bail!("No trusted root certificates for the Postgres TLS connection (set sslrootcert)");
+    }
+
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(MakeRustlsConnect::new(config))
+}
+
+/// Run `f` on a thread without a Tokio runtime
+///
+/// The blocking `postgres` client drives its own runtime and panics inside
+/// another one, and the MCP handlers call the store from async code.
+fn outside_runtime<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    if tokio::runtime::Handle::try_current().is_err() {
+        return f();
+    }
+    std::thread::scope(|scope| match scope.spawn(f).join() {
+        Ok(value) => value,
+        Err(panic) => std::panic::resume_unwind(panic),
+    })
+}
+
+/// Memory store backed by Postgres (+ pgvector when available)
+pub struct PostgresMemoryStore {
+    options: ConnectOptions,
+    client: Mutex<Option<Client>>,
+    embedding_column: EmbeddingColumn,
+    /// Dimensions whose HNSW index exists (or couldn't be built)
+    indexed_dimensions: Mutex<HashSet<usize>>,
+    limits: MemoryLimits,
+}
+
+impl PostgresMemoryStore {
+    /// Connect to a `postgres://` URL and create the schema if needed
+    pub fn open(url: &str) -> Result<Self> {
+        Self::with_options(ConnectOptions::parse(url)?)
+    }
+
+    fn with_options(options: ConnectOptions) -> Result<Self> {
+        let (client, embedding_column) = outside_runtime(|| -> Result<_> {
+            let mut client = options.connect()?;
+            let embedding_column = Self::init_schema(&mut client)?;
+            Ok((client, embedding_column))
+        })?;
+        info!(
+            "Memory store opened: postgres {} ({} embeddings, {})",
+            options.describe(),
+            match embedding_column {
+                EmbeddingColumn::Vector => "pgvector",
+                EmbeddingColumn::RealArray => "REAL[]",
+            },
+            if options.tls.is_some() { "TLS" } else { "local, no TLS" }
+        );
+
+        Ok(Self {
+            options,
+            client: Mutex::new(Some(client)),
+            embedding_column,
+            indexed_dimensions: Mutex::new(HashSet::new()),
+            limits: MemoryLimits::from_env(),
+        })
+    }
+
+    fn init_schema(client: &mut Client) -> Result<EmbeddingColumn> {
+        if let Err(e) = client.batch_execute("CREATE EXTENSION IF NOT EXISTS vector") {
+            debug!("pgvector not created: {}", e);
+        }
+        let has_pgvector = !client
+            .query("SELECT 1 FROM pg_extension WHERE extname = 'vector'", &[])?
+            .is_empty();
+        let embedding_type = if has_pgvector { "vector" } else { "REAL[]" };
+
+        client.batch_execute(&format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS memories (
+                id TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                category TEXT NOT NULL DEFAULT 'general',
+                source TEXT NOT NULL DEFAULT 'unknown',
+                confidence DOUBLE PRECISION NOT NULL DEFAULT 0.5,
+                created_at BIGINT NOT NULL DEFAULT extract(epoch FROM now())::bigint,
+                last_accessed BIGINT,
+                access_count BIGINT NOT NULL DEFAULT 0,
+                shared BOOLEAN NOT NULL DEFAULT false,
+                owner_id BIGINT,
+                embedding {},
+                search tsvector GENERATED ALWAYS AS (to_tsvector('simple', content)) STORED
+            );
+            CREATE INDEX IF NOT EXISTS memories_search_idx ON memories USING GIN (search);
+            CREATE INDEX IF NOT EXISTS memories_category_idx ON memories (category, created_at DESC);
+            CREATE INDEX IF NOT EXISTS memories_created_idx ON memories (created_at DESC, id);
+            "#,
+            embedding_type
+        ))?;
+
+        // Tables from before the owner column take the owner from the digits
+        // after the last '_' in source, as scoping did then
+        let has_owner = !client
+            .query(
+                "SELECT 1 FROM pg_attribute WHERE attrelid = 'memories'::regclass \
+                 AND attname = 'owner_id' AND NOT attisdropped",
+                &[],
+            )?
+            .is_empty();
+        if !has_owner {
+            client.batch_execute(
+                r#"
+                ALTER TABLE memories ADD COLUMN owner_id BIGINT;
+                UPDATE memories SET owner_id = substring(source FROM '_([+-]?[0-9]{1,18})$')::bigint;
+                "#,
+            )?;
+        }
+
+        let column_type: String = client
+            .query_opt(
+                "SELECT format_type(atttypid, atttypmod) FROM pg_attribute \
+                 WHERE attrelid = 'memories'::regclass AND attname = 'embedding'",
+                &[],
+            )?
+            .context("memories table has no embedding column")?
+            .get(0);
+        Ok(if column_type.starts_with("vector") {
+            EmbeddingColumn::Vector
+        } else {
+            EmbeddingColumn::RealArray
+        })
+    }
+
+    /// Run `f` on the connection
+    ///
+    /// A connection found closed is replaced before `f` runs. A statement that
+    /// fails is never re-sent: the server may already have applied it.
+    fn with_client<T: Send>(&self, f: impl FnOnce(&mut Client) -> Result<T> + Send) -> Result<T> {
+        let mut guard = self.client.lock().unwrap();
+        let slot: &mut Option<Client> = &mut guard;
+        outside_runtime(move || {
+            if slot.as_ref().is_none_or(|client| client.is_closed()) {
+                if slot.take().is_some() {
+                    warn!("Postgres connection lost, reconnecting");
+                }
+                *slot = Some(self.options.connect()?);
+            }
+            let client = slot.as_mut().expect("connected above");
+            let result = f(client);
+            if client.is_closed() {
+                *slot = None;
+            }
+            result
+        })
+    }
+
+    fn query_entries(&self, sql: &str, params: &[Param]) -> Result<Vec<MemoryEntry>> {
+        let rows = self.with_client(|client| Ok(client.query(sql, params)?))?;
+        rows.iter().map(|row| self.entry_from_row(row)).collect()
+    }
+
+    /// A row selected with `COLUMNS`
+    fn entry_from_row(&self, row: &Row) -> Result<MemoryEntry> {
+        let embedding = match self.embedding_column {
+            EmbeddingColumn::Vector => row.try_get::<_, Option<Vector>>(7)?.map(|v| v.to_vec()),
+            EmbeddingColumn::RealArray => row.try_get::<_, Option<Vec<f32>>>(7)?,
+        };
+        Ok(MemoryEntry {
+            id: row.try_get(0)?,
+            content: row.try_get(1)?,
+            category: row.try_get(2)?,
+            source: row.try_get(3)?,
+            confidence: row.try_get(4)?,
+            created_at: row.try_get(5)?,
+            access_count: row.try_get(6)?,
+            embedding,
+            shared: row.try_get(8)?,
+            owner_id: row.try_get(9)?,
+        })
+    }
+
+    /// Build the HNSW index for a dimension the first time it's seen
+    fn ensure_vector_index(&self, client: &mut Client, dimensions: usize) {
+        let mut indexed = self.indexed_dimensions.lock().unwrap();
+        if !indexed.insert(dimensions) {
+            return;
+        }
+        if dimensions > MAX_INDEXED_DIMENSIONS {
+            warn!("{}-dimension embeddings are too wide for an HNSW index; searching them scans", dimensions);
+            return;
+        }
+        let sql = format!(
+            "CREATE INDEX IF NOT EXISTS memories_embedding_{0}_idx ON memories \
+             USING hnsw ((embedding::vector({0})) vector_cosine_ops) WHERE vector_dims(embedding) = {0}",
+            dimensions
+        );
+        if let Err(e) = client.batch_execute(&sql) {
+            warn!("No HNSW index for {}-dimension embeddings: {}", dimensions, e);
+        }
+    }
+}
+
+impl Drop for PostgresMemoryStore {
+    fn drop(&mut self) {
+        // Closing the connection blocks on the client's runtime too
+        if let Some(client) = self.client.get_mut().ok().and_then(Option::take) {
+            outside_runtime(move || drop(client));
+        }
+    }
+}
+
+impl MemoryBackend for PostgresMemoryStore {
+    fn backend_name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn learn(&self, content: &str, category: &str, source: &str, owner: Option<i64>, confidence: f64) -> Result<String> {
+        let content = self.limits.apply(content)?;
+        let id = MemoryStore::hash_content(&content);
+
+        self.with_client(|client| {
+            Ok(client.execute(
+                r#"
+                INSERT INTO memories (id, content, category, source, confidence, owner_id)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (id) DO UPDATE SET
+                    confidence = GREATEST(memories.confidence, EXCLUDED.confidence),
+                    access_count = memories.access_count + 1,
+                    last_accessed = extract(epoch FROM now())::bigint
+                "#,
+                &[&id, &content.as_ref(), &category, &source, &confidence, &owner],
+            )?)
+        })?;
+
+        debug!("Learned: {} ({})", &id.get(..8).unwrap_or(&id), category);
+        Ok(id)
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        // Any term may match, as with the SQLite FTS query
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+        let tsquery = (2..terms.len() + 2)
+            .map(|i| format!("plainto_tsquery('simple', ${})", i))
+            .collect::<Vec<_>>()
+            .join(" || ");
+        let sql = format!(
+            "SELECT {}, ts_rank(search, q)::float8 AS score FROM memories, (SELECT {}) AS t(q) \
+             WHERE search @@ q ORDER BY score DESC, created_at DESC LIMIT $1",
+            COLUMNS, tsquery
+        );
+        let limit = limit as i64;
+        let params: Vec<Param> = std::iter::once(&limit as Param)
+            .chain(terms.iter().map(|t| t as Param))
+            .collect();
+
+        let rows = self.with_client(|client| Ok(client.query(&sql, &params)?))?;
+        rows.iter()
+            .map(|row| {
+                Ok(SearchResult {
+                    entry: self.entry_from_row(row)?,
+                    score: row.try_get(10)?,
+                })
+            })
+            .collect()
+    }
+
+    fn get_by_id(&self, id: &str) -> Result<Option<MemoryEntry>> {
+        let sql = format!("SELECT {} FROM memories WHERE id = $1", COLUMNS);
+        Ok(self.query_entries(&sql, &[&id])?.into_iter().next())
+    }
+
+    fn get_by_category(&self, category: &str, limit: usize, scope: MemoryScope) -> Result<Vec<MemoryEntry>> {
+        // Same visibility as MemoryScope::allows
+        let sql = format!(
+            r#"
+            SELECT {} FROM memories
+            WHERE category = $1
+              AND ($3::bigint IS NULL OR shared OR owner_id IS NULL OR owner_id = $3::bigint)
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            COLUMNS
+        );
+        let user = scope.sql_user();
+        self.query_entries(&sql, &[&category, &(limit as i64), &user])
+    }
+
+    fn get_recent(&self, limit: usize) -> Result<Vec<MemoryEntry>> {
+        let sql = format!("SELECT {} FROM memories ORDER BY created_at DESC LIMIT $1", COLUMNS);
+        self.query_entries(&sql, &[&(limit as i64)])
+    }
+
+    fn list_page(&self, offset: usize, limit: usize) -> Result<Vec<MemoryEntry>> {
+        let sql = format!(
+            "SELECT {} FROM memories ORDER BY created_at DESC, id LIMIT $1 OFFSET $2",
+            COLUMNS
+        );
+        self.query_entries(&sql, &[&(limit as i64), &(offset as i64)])
+    }
+
+    fn forget(&self, id: &str) -> Result<bool> {
+        let rows = self.with_client(|client| Ok(client.execute("DELETE FROM memories WHERE id = $1", &[&id])?))?;
+        Ok(rows > 0)
+    }
+
+    fn store_embedding(&self, id: &str, embedding: &[f32]) -> Result<()> {
+        self.with_client(|client| {
+            match self.embedding_column {
+                EmbeddingColumn::Vector => {
+                    self.ensure_vector_index(client, embedding.len());
+                    let value = Vector::from(embedding.to_vec());
+                    client.execute("UPDATE memories SET embedding = $1 WHERE id = $2", &[&value, &id])?
+                }
+                EmbeddingColumn::RealArray => {
+                    client.execute("UPDATE memories SET embedding = $1 WHERE id = $2", &[&embedding, &id])?
+                }
+            };
+            Ok(())
+        })
+    }
+
+    fn similar_to_embedding(&self, exclude_id: &str, embedding: &[f32], limit: usize) -> Result<Vec<ScoredMemory>> {
+        if embedding.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut scored: Vec<ScoredMemory> = match self.embedding_column {
+            EmbeddingColumn::Vector => {
+                // The dimension is a literal so the planner can match the partial index
+                let sql = format!(
+                    "SELECT {0}, 1 - (embedding::vector({1}) <=> $1::vector({1})) AS score FROM memories \
+                     WHERE vector_dims(embedding) = {1} AND id <> $2 \
+                     ORDER BY embedding::vector({1}) <=> $1::vector({1}) LIMIT $3",
+                    COLUMNS,
+                    embedding.len()
+                );
+                let value = Vector::from(embedding.to_vec());
+                let rows = self.with_client(|client| {
+                    Ok(client.query(&sql, &[&value, &exclude_id, &(limit as i64)])?)
+                })?;
+                rows.iter()
+                    .map(|row| {
+                        let similarity: f64 = row.try_get(10)?;
+                        Ok(ScoredMemory {
+                            entry: self.entry_from_row(row)?,
+                            score: similarity,
+                            keyword_score: 0.0,
+                            vector_score: similarity,
+                        })
+                    })
+                    .collect::<Result<_>>()?
+            }
+            EmbeddingColumn::RealArray => {
+                let sql = format!(
+                    "SELECT {} FROM memories WHERE cardinality(embedding) = $1 AND id <> $2",
+                    COLUMNS
+                );
+                let dimensions = embedding.len() as i32;
+                self.query_entries(&sql, &[&dimensions, &exclude_id])?
+                    .into_iter()
+                    .map(|entry| {
+                        let similarity = entry
+                            .embedding
+                            .as_deref()
+                            .map(|e| EmbeddingStore::cosine_similarity(embedding, e) as f64)
+                            .unwrap_or(0.0);
+                        ScoredMemory {
+                            entry,
+                            score: similarity,
+                            keyword_score: 0.0,
+                            vector_score: similarity,
+                        }
+                    })
+                    .collect()
+            }
+        };
+
+        scored.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    fn stats(&self) -> Result<MemoryStats> {
+        let rows = self.with_client(|client| {
+            Ok(client.query("SELECT category, COUNT(*) FROM memories GROUP BY category ORDER BY category", &[])?)
+        })?;
+        let by_category = rows
+            .iter()
+            .map(|row| Ok((row.try_get(0)?, row.try_get(1)?)))
+            .collect::<Result<Vec<(String, i64)>>>()?;
+
+        Ok(MemoryStats {
+            total_entries: by_category.iter().map(|(_, n)| *n as usize).sum(),
+            by_category,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sslmode_handling() {
+        let remote = ConnectOptions::parse("postgres://bot:pw@db.internal:6543/memory?connect_timeout=3").unwrap();
+        assert!(remote.tls.is_some());
+        assert_eq!(remote.config.get_ssl_mode(), SslMode::Require);
+        assert_eq!(remote.config.get_connect_timeout(), Some(&Duration::from_secs(3)));
+        assert_eq!(remote.describe(), "db.internal:6543/memory");
+        assert!(ConnectOptions::parse("postgres://db.internal/memory?sslmode=verify-full").unwrap().tls.is_some());
+
+        for mode in ["prefer", "allow", "verify-ca", "bogus"] {
+            let url = format!("postgres://db.internal/memory?sslmode={}", mode);
+            assert!(ConnectOptions::parse(&url).is_err(), "{} accepted", mode);
+        }
+        let err = ConnectOptions::parse("postgres://db.internal/memory?sslmode=disable").err().unwrap();
+        assert!(err.to_string().contains("plaintext"));
+
+        let local = ConnectOptions::parse("postgres://bot@127.0.0.1/memory?sslmode=disable").unwrap();
+        assert!(local.tls.is_none());
+        assert_eq!(local.config.get_ssl_mode(), SslMode::Disable);
+        assert!(ConnectOptions::parse("postgres://bot@localhost/memory?sslmode=disable").is_ok());
+
+        assert!(ConnectOptions::parse("postgres://db.internal/memory?sslrootcert=/nonexistent.pem").is_err());
+    }
+
+    #[test]
+    fn test_outside_runtime_from_async() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let in_runtime = runtime.block_on(async { outside_runtime(|| tokio::runtime::Handle::try_current().is_ok()) });
+        assert!(!in_runtime);
+    }
+
+    /// Runs against `CLAUDEBOT_TEST_POSTGRES_URL` (a role allowed to create
+    /// databases) in a throwaway database; skipped when unset
+    #[test]
+    fn test_postgres_round_trip() {
+        let Ok(url) = std::env::var("CLAUDEBOT_TEST_POSTGRES_URL") else {
+            eprintln!("CLAUDEBOT_TEST_POSTGRES_URL not set, skipping");
+            return;
+        };
+        let admin_options = ConnectOptions::parse(&url).unwrap();
+        let mut admin = admin_options.connect().unwrap();
+        let database = format!("claudebot_test_{}", uuid::Uuid::new_v4().simple());
+        admin.batch_execute(&format!("CREATE DATABASE {}", database)).unwrap();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut options = admin_options.clone();
+            options.config.dbname(&database);
+            let store = PostgresMemoryStore::with_options(options).unwrap();
+            exercise_backend(&store);
+
+            // After the server drops the connection, the statement that hits the
+            // dead connection fails without being re-sent and the next one reconnects
+            let pid: i32 = store.with_client(|c| Ok(c.query_one("SELECT pg_backend_pid()", &[])?.get(0))).unwrap();
+            let mut killer = admin_options.connect().unwrap();
+            killer.execute("SELECT pg_terminate_backend($1)", &[&pid]).unwrap();
+            std::thread::sleep(Duration::from_millis(200));
+            assert!(store.learn("Written after the drop", "fact", "mcp", None, 0.5).is_err());
+            assert_eq!(store.stats().unwrap().total_entries, 3);
+        }));
+
+        admin
+            .batch_execute(&format!("DROP DATABASE {} WITH (FORCE)", database))
+            .unwrap();
+        if let Err(panic) = result {
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    fn exercise_backend(store: &PostgresMemoryStore) {
+        assert_eq!(store.backend_name(), "postgres");
+
+        let rust = store.learn("Rust is a systems language", "fact", "mcp", None, 0.6).unwrap();
+        assert_eq!(store.learn("Rust is a systems language", "fact", "mcp", None, 0.9).unwrap(), rust);
+        let entry = store.get_by_id(&rust).unwrap().unwrap();
+        assert_eq!((entry.confidence, entry.access_count), (0.9, 1));
+        assert!(store.get_by_id("missing").unwrap().is_none());
+
+        let python = store.learn("Python is a scripting language", "fact", "telegram_user_7", Some(7), 0.8).unwrap();
+        let alice = store.learn("Alice prefers tea", "identity", "telegram_user_7", Some(7), 0.9).unwrap();
+        // The owner comes from the column, not the group chat ID in source
+        let bob = store.learn("Bob prefers coffee", "identity", "chat_-1008", Some(8), 0.9).unwrap();
+
+        let hits = store.search("systems scripting", 10).unwrap();
+        let mut ids: Vec<_> = hits.iter().map(|r| r.entry.id.clone()).collect();
+        ids.sort();
+        let mut expected = vec![rust.clone(), python.clone()];
+        expected.sort();
+        assert_eq!(ids, expected);
+        assert!(hits.iter().all(|r| r.score > 0.0));
+        assert!(store.search("   ", 10).unwrap().is_empty());
+
+        let for_alice = store.get_by_category("identity", 10, MemoryScope::User(7)).unwrap();
+        assert_eq!(for_alice.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), vec![alice.as_str()]);
+        assert_eq!(store.get_by_category("identity", 10, MemoryScope::Global).unwrap().len(), 2);
+        assert_eq!(store.get_by_category("identity", 1, MemoryScope::User(8)).unwrap()[0].id, bob);
+
+        assert_eq!(store.get_recent(2).unwrap().len(), 2);
+        let first = store.list_page(0, 3).unwrap();
+        let rest = store.list_page(3, 3).unwrap();
+        assert_eq!((first.len(), rest.len()), (3, 1));
+        assert!(!first.iter().any(|e| e.id == rest[0].id));
+
+        store.store_embedding(&rust, &[1.0, 0.0, 0.0]).unwrap();
+        store.store_embedding(&python, &[0.9, 0.1, 0.0]).unwrap();
+        store.store_embedding(&alice, &[0.0, 1.0, 0.0]).unwrap();
+        store.store_embedding(&bob, &[1.0, 0.0]).unwrap();
+        assert_eq!(store.get_by_id(&rust).unwrap().unwrap().embedding, Some(vec![1.0, 0.0, 0.0]));
+
+        let similar = store.similar_to_embedding(&rust, &[1.0, 0.0, 0.0], 5).unwrap();
+        assert_eq!(similar.iter().map(|s| s.entry.id.as_str()).collect::<Vec<_>>(), vec![python.as_str(), alice.as_str()]);
+        assert!(similar[0].score > 0.9 && similar[1].score.abs() < 1e-6);
+
+        let stats = store.stats().unwrap();
+        assert_eq!(stats.total_entries, 4);
+        assert_eq!(stats.by_category, vec![("fact".to_string(), 2), ("identity".to_string(), 2)]);
+
+        assert!(store.forget(&bob).unwrap());
+        assert!(!store.forget(&bob).unwrap());
+        assert_eq!(store.stats().unwrap().total_entries, 3);
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! Cost tracking, latency metrics, and usage statistics.
 //! Provides observability into Claude API usage and system performance.
+//!
+//! Failed calls are counted by reason (timeout, non-zero exit, parse error,
+//! bridge error, budget exceeded) over rolling hour/day windows, so a spike
+//! in one class shows up before users start reporting it.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -10,6 +14,15 @@ use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
+/// Window for "recent" failures and spike detection
+const HOUR_SECS: u64 = 3600;
+
+/// A reason counts as spiking from this many failures in the last hour...
+const SPIKE_MIN_FAILURES: u64 = 3;
+
+/// ...when that's this many times its average hourly count over the rest of the day
+const SPIKE_FACTOR: f64 = 3.0;
+
 /// Pricing per million tokens (USD)
 /// Constants defined at compile time for zero runtime cost
 #[derive(Debug, Clone, Copy)]
@@ -89,6 +102,180 @@ pub struct RequestMetrics {
     pub latency_ms: u64,
     pub cache_hit: bool,
     pub route_target: Option<String>,
+    /// Feature that issued the request (see `crate::usage::ORIGIN_*`)
+    #[serde(default)]
+    pub origin: Option<String>,
+}
+
+impl RequestMetrics {
+    /// Origin for grouping, with untagged requests bucketed together
+    fn origin_label(&self) -> &str {
+        self.origin.as_deref().unwrap_or(crate::usage::ORIGIN_UNTAGGED)
+    }
+}
+
+/// Why a Claude call failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureReason {
+    /// No result within the allowed time
+    Timeout,
+    /// The CLI exited with a non-zero status
+    NonZeroExit,
+    /// Output wasn't the expected JSON (the reply fell back to plain text)
+    ParseError,
+    /// The gRPC bridge was unreachable or reported a failure
+    BridgeError,
+    /// Refused up front because it would exceed the user's budget
+    BudgetExceeded,
+    Other,
+}
+
+impl FailureReason {
+    pub const ALL: [FailureReason; 6] = [
+        Self::Timeout,
+        Self::NonZeroExit,
+        Self::ParseError,
+        Self::BridgeError,
+        Self::BudgetExceeded,
+        Self::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::NonZeroExit => "non_zero_exit",
+            Self::ParseError => "parse_error",
+            Self::BridgeError => "bridge_error",
+            Self::BudgetExceeded => "budget_exceeded",
+            Self::Other => "other",
+        }
+    }
+
+    /// Classify an error: a `ClassifiedError` anywhere in the chain wins, then
+    /// known error types, then the message
+    pub fn classify(err: &anyhow::Error) -> Self {
+        for cause in err.chain() {
+            if let Some(classified) = cause.downcast_ref::<ClassifiedError>() {
+                return classified.reason;
+            }
+            if cause.is::<tokio::time::error::Elapsed>() {
+                return Self::Timeout;
+            }
+            if cause.is::<tonic::Status>() || cause.is::<tonic::transport::Error>() {
+                return Self::BridgeError;
+            }
+            if cause.is::<serde_json::Error>() {
+                return Self::ParseError;
+            }
+            if cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.kind() == std::io::ErrorKind::TimedOut)
+            {
+                return Self::Timeout;
+            }
+        }
+        Self::classify_message(&err.to_string(), Self::Other)
+    }
+
+    /// Classify a plain error message, `fallback` unless it names a timeout
+    pub fn classify_message(message: &str, fallback: Self) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("timed out") || message.contains("timeout") {
+            Self::Timeout
+        } else {
+            fallback
+        }
+    }
+}
+
+/// An error tagged with its failure class; displays as its message
+#[derive(Debug)]
+pub struct ClassifiedError {
+    pub reason: FailureReason,
+    pub message: String,
+}
+
+impl ClassifiedError {
+    pub fn error(reason: FailureReason, message: impl Into<String>) -> anyhow::Error {
+        anyhow::Error::new(Self {
+            reason,
+            message: message.into(),
+        })
+    }
+}
+
+impl std::fmt::Display for ClassifiedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ClassifiedError {}
+
+/// A failed call
+#[derive(Debug, Clone, Copy)]
+struct FailureRecord {
+    timestamp: u64,
+    reason: FailureReason,
+}
+
+/// Calls and failures over one rolling window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FailureWindow {
+    pub window_secs: u64,
+    /// Successful requests plus failures
+    pub calls: u64,
+    pub failures: u64,
+    /// Failures per call (0-1)
+    pub error_rate: f64,
+    pub by_reason: HashMap<String, u64>,
+}
+
+impl FailureWindow {
+    fn count(&self, reason: FailureReason) -> u64 {
+        self.by_reason.get(reason.as_str()).copied().unwrap_or(0)
+    }
+}
+
+/// Failures over the last hour and day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FailureStats {
+    pub last_hour: FailureWindow,
+    pub last_day: FailureWindow,
+    /// Reasons failing well above their usual rate in the last hour
+    pub spiking: Vec<FailureReason>,
+}
+
+impl FailureStats {
+    pub fn format(&self) -> String {
+        let describe = |label: &str, w: &FailureWindow| {
+            let mut lines = vec![format!(
+                "{}: {}/{} calls failed ({:.1}%)",
+                label,
+                w.failures,
+                w.calls,
+                w.error_rate * 100.0
+            )];
+            for reason in FailureReason::ALL {
+                let count = w.count(reason);
+                if count > 0 {
+                    lines.push(format!("  • {}: {}", reason.as_str(), count));
+                }
+            }
+            lines.join("\n")
+        };
+        let mut out = format!(
+            "{}\n{}",
+            describe("Last hour", &self.last_hour),
+            describe("Last 24h", &self.last_day)
+        );
+        if !self.spiking.is_empty() {
+            let names: Vec<&str> = self.spiking.iter().map(|r| r.as_str()).collect();
+            out.push_str(&format!("\n⚠️ Spiking: {}", names.join(", ")));
+        }
+        out
+    }
 }
 
 /// Aggregate metrics for a time period
@@ -102,6 +289,7 @@ pub struct AggregateMetrics {
     pub avg_latency_ms: f64,
     pub cache_hit_rate: f64,
     pub by_model: HashMap<String, ModelMetrics>,
+    pub by_origin: HashMap<String, ModelMetrics>,
 }
 
 /// Per-model metrics
@@ -131,6 +319,7 @@ pub struct CostBreakdown {
     pub this_week_usd: f64,
     pub this_month_usd: f64,
     pub by_model: HashMap<String, f64>,
+    pub by_origin: HashMap<String, f64>,
     pub savings_from_cache_usd: f64,
 }
 
@@ -145,6 +334,15 @@ pub struct MetricsCollector {
     total_cost_micros: AtomicU64, // Store as microdollars for atomic
     cache_hits: AtomicU64,
     cache_misses: AtomicU64,
+    /// Failed calls (rolling window, same capacity as `requests`)
+    failures: Arc<RwLock<Vec<FailureRecord>>>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 impl MetricsCollector {
@@ -157,6 +355,7 @@ impl MetricsCollector {
             total_cost_micros: AtomicU64::new(0),
             cache_hits: AtomicU64::new(0),
             cache_misses: AtomicU64::new(0),
+            failures: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -170,6 +369,31 @@ impl MetricsCollector {
         latency: Duration,
         cache_hit: bool,
         route_target: Option<&str>,
+    ) {
+        self.record_with_origin(
+            None,
+            model,
+            input_tokens,
+            output_tokens,
+            cached_tokens,
+            latency,
+            cache_hit,
+            route_target,
+        );
+    }
+
+    /// Record a request attributed to the feature that issued it
+    #[allow(clippy::too_many_arguments)]
+    pub fn record_with_origin(
+        &self,
+        origin: Option<&str>,
+        model: &str,
+        input_tokens: usize,
+        output_tokens: usize,
+        cached_tokens: usize,
+        latency: Duration,
+        cache_hit: bool,
+        route_target: Option<&str>,
     ) {
         let pricing = ModelPricing::for_model(model);
         let cost = pricing.calculate_cost(input_tokens, output_tokens, cached_tokens);
@@ -187,6 +411,7 @@ impl MetricsCollector {
             latency_ms: latency.as_millis() as u64,
             cache_hit,
             route_target: route_target.map(String::from),
+            origin: origin.map(String::from),
         };
 
         // Update counters
@@ -217,6 +442,67 @@ impl MetricsCollector {
         );
     }
 
+    /// Record a failed call
+    pub fn record_failure(&self, reason: FailureReason) {
+        self.record_failure_at(reason, now_secs());
+    }
+
+    fn record_failure_at(&self, reason: FailureReason, timestamp: u64) {
+        if let Ok(mut failures) = self.failures.write() {
+            failures.push(FailureRecord { timestamp, reason });
+            if failures.len() > self.max_history {
+                let drain_count = failures.len() - self.max_history;
+                failures.drain(0..drain_count);
+            }
+        }
+        debug!("Recorded failure: {}", reason.as_str());
+    }
+
+    /// Calls and failures since `now - window_secs`
+    fn failure_window(&self, now: u64, window_secs: u64) -> FailureWindow {
+        let since = now.saturating_sub(window_secs);
+        let successes = self
+            .requests
+            .read()
+            .map(|r| r.iter().filter(|r| r.timestamp >= since).count() as u64)
+            .unwrap_or(0);
+        let mut by_reason: HashMap<String, u64> = HashMap::new();
+        if let Ok(failures) = self.failures.read() {
+            for f in failures.iter().filter(|f| f.timestamp >= since) {
+                *by_reason.entry(f.reason.as_str().to_string()).or_default() += 1;
+            }
+        }
+        let failures: u64 = by_reason.values().sum();
+        let calls = successes + failures;
+        FailureWindow {
+            window_secs,
+            calls,
+            failures,
+            error_rate: if calls > 0 { failures as f64 / calls as f64 } else { 0.0 },
+            by_reason,
+        }
+    }
+
+    /// Failure counts by reason over the last hour and day, with spikes
+    pub fn failure_stats(&self) -> FailureStats {
+        let now = now_secs();
+        let last_hour = self.failure_window(now, HOUR_SECS);
+        let last_day = self.failure_window(now, 24 * HOUR_SECS);
+        let spiking = FailureReason::ALL
+            .into_iter()
+            .filter(|&reason| {
+                let hour = last_hour.count(reason);
+                let usual = last_day.count(reason).saturating_sub(hour) as f64 / 23.0;
+                hour >= SPIKE_MIN_FAILURES && hour as f64 > usual * SPIKE_FACTOR
+            })
+            .collect();
+        FailureStats {
+            last_hour,
+            last_day,
+            spiking,
+        }
+    }
+
     /// Get quick stats
     pub fn quick_stats(&self) -> QuickStats {
         let total = self.total_requests.load(Ordering::Relaxed);
@@ -272,6 +558,17 @@ impl MetricsCollector {
             entry.cost_usd += r.cost_usd;
         }
 
+        // By origin breakdown
+        let mut by_origin: HashMap<String, ModelMetrics> = HashMap::new();
+        for r in &filtered {
+            let entry = by_origin.entry(r.origin_label().to_string()).or_default();
+            entry.requests += 1;
+            entry.input_tokens += r.input_tokens as u64;
+            entry.output_tokens += r.output_tokens as u64;
+            entry.cached_tokens += r.cached_tokens as u64;
+            entry.cost_usd += r.cost_usd;
+        }
+
         AggregateMetrics {
             total_requests,
             total_input_tokens,
@@ -281,6 +578,7 @@ impl MetricsCollector {
             avg_latency_ms: total_latency as f64 / total_requests as f64,
             cache_hit_rate: cache_hits as f64 / total_requests as f64 * 100.0,
             by_model,
+            by_origin,
         }
     }
 
@@ -347,6 +645,7 @@ impl MetricsCollector {
                     this_week_usd: 0.0,
                     this_month_usd: 0.0,
                     by_model: HashMap::new(),
+                    by_origin: HashMap::new(),
                     savings_from_cache_usd: 0.0,
                 }
             }
@@ -376,6 +675,12 @@ impl MetricsCollector {
             *by_model.entry(r.model.clone()).or_default() += r.cost_usd;
         }
 
+        // By origin
+        let mut by_origin: HashMap<String, f64> = HashMap::new();
+        for r in requests.iter() {
+            *by_origin.entry(r.origin_label().to_string()).or_default() += r.cost_usd;
+        }
+
         // Calculate cache savings
         let savings_from_cache_usd: f64 = requests
             .iter()
@@ -392,6 +697,7 @@ impl MetricsCollector {
             this_week_usd,
             this_month_usd,
             by_model,
+            by_origin,
             savings_from_cache_usd,
         }
     }
@@ -401,6 +707,9 @@ impl MetricsCollector {
         if let Ok(mut requests) = self.requests.write() {
             requests.clear();
         }
+        if let Ok(mut failures) = self.failures.write() {
+            failures.clear();
+        }
         self.total_requests.store(0, Ordering::Relaxed);
         self.total_cost_micros.store(0, Ordering::Relaxed);
         self.cache_hits.store(0, Ordering::Relaxed);
@@ -414,12 +723,14 @@ impl MetricsCollector {
         let aggregate = self.aggregate(None);
         let latency = self.latency_stats();
         let cost = self.cost_breakdown();
+        let failures = self.failure_stats();
 
         serde_json::json!({
             "quick": stats,
             "aggregate": aggregate,
             "latency": latency,
-            "cost": cost
+            "cost": cost,
+            "failures": failures
         })
         .to_string()
     }
@@ -554,6 +865,23 @@ mod tests {
         assert_eq!(agg.by_model.get("sonnet").unwrap().requests, 1);
     }
 
+    #[test]
+    fn test_aggregate_by_origin() {
+        let collector = MetricsCollector::new(100);
+
+        collector.record_with_origin(Some("circle"), "opus", 1000, 500, 0, Duration::from_millis(100), false, None);
+        collector.record_with_origin(Some("chat"), "haiku", 100, 50, 0, Duration::from_millis(100), false, None);
+        collector.record("haiku", 100, 50, 0, Duration::from_millis(100), false, None);
+
+        let agg = collector.aggregate(None);
+        assert_eq!(agg.by_origin.len(), 3);
+        assert_eq!(agg.by_origin.get("circle").unwrap().requests, 1);
+        assert_eq!(agg.by_origin.get(crate::usage::ORIGIN_UNTAGGED).unwrap().requests, 1);
+
+        let cost = collector.cost_breakdown();
+        assert!(cost.by_origin["circle"] > cost.by_origin["chat"]);
+    }
+
     #[test]
     fn test_empty_collector() {
         let collector = MetricsCollector::new(100);
@@ -624,6 +952,38 @@ mod tests {
         assert!(agg.total_requests <= 5);
     }
 
+    #[test]
+    fn test_failure_tracking() {
+        let collector = MetricsCollector::new(100);
+        let now = now_secs();
+
+        collector.record("sonnet", 100, 50, 0, Duration::from_millis(100), false, None);
+        for _ in 0..3 {
+            collector.record_failure(FailureReason::Timeout);
+        }
+        collector.record_failure(FailureReason::BudgetExceeded);
+        // Steady bridge failures throughout the day aren't a spike
+        for hours_ago in [0, 0, 2, 3, 5, 8, 11, 14, 17, 20] {
+            collector.record_failure_at(FailureReason::BridgeError, now - hours_ago * HOUR_SECS);
+        }
+
+        let stats = collector.failure_stats();
+        assert_eq!((stats.last_hour.calls, stats.last_hour.failures), (7, 6));
+        assert_eq!(stats.last_hour.by_reason["timeout"], 3);
+        assert_eq!(stats.last_day.by_reason["bridge_error"], 10);
+        assert!((stats.last_day.error_rate - 14.0 / 15.0).abs() < 1e-9);
+        assert_eq!(stats.spiking, vec![FailureReason::Timeout]);
+        assert!(stats.format().contains("• budget_exceeded: 1"));
+
+        let nonzero = ClassifiedError::error(FailureReason::NonZeroExit, "exit 1: Invalid API key");
+        assert_eq!(nonzero.to_string(), "exit 1: Invalid API key");
+        assert_eq!(FailureReason::classify(&nonzero.context("Claude failed")), FailureReason::NonZeroExit);
+        assert_eq!(FailureReason::classify(&anyhow::anyhow!("request timed out")), FailureReason::Timeout);
+        let parse = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert_eq!(FailureReason::classify(&parse.into()), FailureReason::ParseError);
+        assert_eq!(FailureReason::classify(&anyhow::anyhow!("spawn failed")), FailureReason::Other);
+    }
+
     #[test]
     fn test_zero_tokens() {
         let pricing = ModelPricing::SONNET;
@@ -0,0 +1,263 @@
+//! Image OCR
+//!
+//! Extracts text from uploaded images so screenshots become searchable
+//! memories (category `image_ocr`). Two backends:
+//! - `tesseract` - local `tesseract` CLI
+//! - `claude` - reuse Claude's vision reply for the image
+//!
+//! `auto` picks tesseract when installed, otherwise Claude. Disabled unless
+//! `CLAUDEBOT_OCR=true`.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Stdio;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+
+/// Memory category for extracted image text
+pub const OCR_CATEGORY: &str = "image_ocr";
+
+/// Longest extracted text kept per image
+const MAX_OCR_CHARS: usize = 4000;
+
+/// OCR backend selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OcrBackend {
+    /// Tesseract if installed, otherwise Claude
+    #[default]
+    Auto,
+    Tesseract,
+    Claude,
+}
+
+impl OcrBackend {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Some(Self::Auto),
+            "tesseract" => Some(Self::Tesseract),
+            "claude" | "vision" => Some(Self::Claude),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Tesseract => "tesseract",
+            Self::Claude => "claude",
+        }
+    }
+}
+
+/// OCR configuration
+#[derive(Debug, Clone)]
+pub struct OcrConfig {
+    pub enabled: bool,
+    pub backend: OcrBackend,
+    /// Tesseract language(s), e.g. `eng+deu`
+    pub language: Option<String>,
+    /// Ignore results shorter than this (noise from photos without text)
+    pub min_chars: usize,
+}
+
+impl Default for OcrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: OcrBackend::Auto,
+            language: None,
+            min_chars: 12,
+        }
+    }
+}
+
+impl OcrConfig {
+    /// `CLAUDEBOT_OCR`, `CLAUDEBOT_OCR_BACKEND`, `CLAUDEBOT_OCR_LANG` and
+    /// `CLAUDEBOT_OCR_MIN_CHARS`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(v) = std::env::var("CLAUDEBOT_OCR") {
+            config.enabled = v == "true" || v == "1";
+        }
+        if let Some(backend) = std::env::var("CLAUDEBOT_OCR_BACKEND")
+            .ok()
+            .and_then(|v| OcrBackend::parse(&v))
+        {
+            config.backend = backend;
+        }
+        config.language = std::env::var("CLAUDEBOT_OCR_LANG").ok().filter(|v| !v.is_empty());
+        if let Some(n) = std::env::var("CLAUDEBOT_OCR_MIN_CHARS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.min_chars = n;
+        }
+        config
+    }
+}
+
+/// Image text extractor
+#[derive(Debug, Clone)]
+pub struct Ocr {
+    config: OcrConfig,
+    /// Backend after resolving `auto` (None when disabled or unusable)
+    active: Option<OcrBackend>,
+}
+
+impl Ocr {
+    /// Resolve the configured backend, checking that tesseract is installed
+    pub async fn new(config: OcrConfig) -> Self {
+        let active = if !config.enabled {
+            None
+        } else {
+            match check_backend(config.backend).await {
+                Ok(backend) => {
+                    info!("Image OCR enabled ({})", backend.name());
+                    Some(backend)
+                }
+                Err(e) => {
+                    warn!("Image OCR disabled: {}", e);
+                    None
+                }
+            }
+        };
+        Self { config, active }
+    }
+
+    pub async fn from_env() -> Self {
+        Self::new(OcrConfig::from_env()).await
+    }
+
+    /// Active backend, if OCR is on
+    pub fn backend(&self) -> Option<OcrBackend> {
+        self.active
+    }
+
+    pub fn config(&self) -> &OcrConfig {
+        &self.config
+    }
+
+    /// Run tesseract on an image (None unless the tesseract backend is active)
+    pub async fn extract(&self, image: &Path) -> Option<String> {
+        if self.active != Some(OcrBackend::Tesseract) {
+            return None;
+        }
+        match run_tesseract(image, self.config.language.as_deref()).await {
+            Ok(text) => self.clean(&text),
+            Err(e) => {
+                warn!("OCR failed for {}: {}", image.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Text to store for an image: tesseract output, or Claude's reply
+    /// when the Claude backend is active
+    pub async fn text_for(&self, image: &Path, vision_reply: &str) -> Option<String> {
+        match self.active? {
+            OcrBackend::Tesseract => self.extract(image).await,
+            _ => self.clean(vision_reply),
+        }
+    }
+
+    /// Normalize whitespace and drop results that are too short to be useful
+    pub fn clean(&self, text: &str) -> Option<String> {
+        let cleaned = text
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if cleaned.chars().count() < self.config.min_chars {
+            debug!("OCR result too short ({} chars), ignoring", cleaned.len());
+            return None;
+        }
+        Some(cleaned.chars().take(MAX_OCR_CHARS).collect())
+    }
+}
+
+/// Check a backend is usable, resolving `auto`
+pub async fn check_backend(backend: OcrBackend) -> Result<OcrBackend, String> {
+    match backend {
+        OcrBackend::Claude => Ok(OcrBackend::Claude),
+        OcrBackend::Tesseract if tesseract_available().await => Ok(OcrBackend::Tesseract),
+        OcrBackend::Tesseract => Err("tesseract not found (apt install tesseract-ocr)".to_string()),
+        OcrBackend::Auto if tesseract_available().await => Ok(OcrBackend::Tesseract),
+        OcrBackend::Auto => Ok(OcrBackend::Claude),
+    }
+}
+
+async fn tesseract_available() -> bool {
+    Command::new("tesseract")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+async fn run_tesseract(image: &Path, language: Option<&str>) -> Result<String> {
+    let mut command = Command::new("tesseract");
+    command.arg(image).arg("stdout");
+    if let Some(language) = language {
+        command.args(["-l", language]);
+    }
+    let output = command
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .context("Failed to run tesseract")?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Memory source for an image: its path plus an owner suffix, so
+/// per-user memory scoping still applies (`/x/photo.jpg#user_42`)
+pub fn memory_source(image: &Path, user_id: i64) -> String {
+    format!("{}#user_{}", image.display(), user_id)
+}
+
+/// Memory content for extracted image text
+pub fn memory_content(image: &Path, text: &str) -> String {
+    let name = image.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    format!("Text in image {}:\n{}", name, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_clean_and_claude_backend() {
+        let ocr = Ocr::new(OcrConfig {
+            enabled: true,
+            backend: OcrBackend::Claude,
+            ..OcrConfig::default()
+        })
+        .await;
+        assert_eq!(ocr.backend(), Some(OcrBackend::Claude));
+
+        let text = ocr
+            .text_for(Path::new("/tmp/x.jpg"), "  error[E0382]:   borrow of\n\n moved value  ")
+            .await;
+        assert_eq!(text.as_deref(), Some("error[E0382]: borrow of\nmoved value"));
+        assert_eq!(ocr.clean("ok"), None);
+
+        let disabled = Ocr::new(OcrConfig::default()).await;
+        assert_eq!(disabled.backend(), None);
+        assert_eq!(disabled.text_for(Path::new("/tmp/x.jpg"), "plenty of text here").await, None);
+    }
+
+    #[test]
+    fn test_memory_source_keeps_owner() {
+        let source = memory_source(Path::new("/data/photo_1700000000.jpg"), 42);
+        assert_eq!(source, "/data/photo_1700000000.jpg#user_42");
+        assert_eq!(source.rsplit_once('_').unwrap().1.parse::<i64>().unwrap(), 42);
+        assert_eq!(OcrBackend::parse("Tesseract"), Some(OcrBackend::Tesseract));
+        assert_eq!(OcrBackend::parse("nope"), None);
+    }
+}
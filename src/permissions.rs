@@ -6,10 +6,18 @@
 //! - **Autonomous**: Full access, can commit and push
 //!
 //! Session-based escalation: User can grant temporary full access.
+//!
+//! Safe mode (`CLAUDEBOT_SAFE_MODE`) caps every user at Supervised and
+//! disables escalation entirely, for shared deployments.
+//!
+//! MCP tools map to the same levels (`mcp_tool_level`), so the MCP server can
+//! gate calls the way the Telegram entrypoint gates operations.
 
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::RwLock;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 /// Permission level for operations
@@ -30,6 +38,59 @@ impl Default for PermissionLevel {
     }
 }
 
+impl PermissionLevel {
+    /// Parse "restricted", "supervised" or "autonomous"
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "restricted" | "read" | "readonly" => Some(Self::Restricted),
+            "supervised" => Some(Self::Supervised),
+            "autonomous" | "full" => Some(Self::Autonomous),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Restricted => "restricted",
+            Self::Supervised => "supervised",
+            Self::Autonomous => "autonomous",
+        }
+    }
+
+    /// Whether holding this level is enough for `required`
+    pub fn covers(self, required: PermissionLevel) -> bool {
+        self.rank() >= required.rank()
+    }
+
+    fn rank(self) -> u8 {
+        match self {
+            Self::Restricted => 0,
+            Self::Supervised => 1,
+            Self::Autonomous => 2,
+        }
+    }
+}
+
+/// Level an MCP client needs to call a tool
+///
+/// Reads are Restricted, changes to memory, graph, cache or metrics are
+/// Supervised, and tools that spend money or run Claude are Autonomous.
+/// Unknown tools require Autonomous.
+///
+/// MCP resources are gated under the same names: `memory_resources` (every
+/// stored memory) is Supervised and `conversation_resources` (chat
+/// transcripts) is Autonomous.
+pub fn mcp_tool_level(tool: &str) -> PermissionLevel {
+    match tool {
+        "router_classify" | "memory_search" | "memory_recall" | "memory_stats" | "graph_find_entity"
+        | "graph_traverse" | "graph_entities_by_type" | "graph_stats" | "cache_stats" | "metrics_quick"
+        | "metrics_cost" | "metrics_latency" | "metrics_export" | "mcp_call_log" => PermissionLevel::Restricted,
+        "memory_learn" | "memory_forget" | "graph_add_entity" | "graph_add_relation" | "graph_extract"
+        | "cache_clear" | "metrics_reset" | "memory_resources" => PermissionLevel::Supervised,
+        _ => PermissionLevel::Autonomous,
+    }
+}
+
 /// Operation types that require permission checks
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Operation {
@@ -228,6 +289,129 @@ impl SessionPermissions {
     }
 }
 
+/// Telegram users allowed to talk to the bot (`TELEGRAM_ALLOWED_USERS`)
+///
+/// Shared handle so access can be granted or revoked without a restart. An
+/// empty list allows everyone, so once there is a list, edits that would
+/// empty it are refused rather than opening the bot to the public.
+///
+/// With a file (`with_file`), edits are saved there and the saved list
+/// replaces `TELEGRAM_ALLOWED_USERS` on the next start.
+#[derive(Debug, Clone, Default)]
+pub struct AllowedUsers {
+    users: Arc<RwLock<Vec<i64>>>,
+    /// Where edits are saved (None = memory only)
+    path: Option<Arc<PathBuf>>,
+}
+
+impl AllowedUsers {
+    pub fn new(mut users: Vec<i64>) -> Self {
+        users.sort_unstable();
+        users.dedup();
+        Self {
+            users: Arc::new(RwLock::new(users)),
+            path: None,
+        }
+    }
+
+    /// Save edits to `path`, starting from the list saved there if any
+    pub fn with_file(mut self, path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(data) => {
+                let saved: Vec<i64> = serde_json::from_str(&data)
+                    .map_err(|e| anyhow::anyhow!("Invalid allowed users file {:?}: {}", path, e))?;
+                if !saved.is_empty() {
+                    tracing::info!("Allowed users loaded from {:?} (overrides TELEGRAM_ALLOWED_USERS)", path);
+                    self = Self::new(saved);
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        self.path = Some(Arc::new(path.to_path_buf()));
+        Ok(self)
+    }
+
+    /// Where edits are saved, if anywhere
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref().map(PathBuf::as_path)
+    }
+
+    fn save(&self, users: &[i64]) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(users)?)?;
+        std::fs::rename(&tmp, path.as_path())?;
+        Ok(())
+    }
+
+    /// Comma-separated IDs from `TELEGRAM_ALLOWED_USERS`
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("TELEGRAM_ALLOWED_USERS")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|s| s.trim().parse().ok())
+                .collect(),
+        )
+    }
+
+    pub fn is_allowed(&self, user_id: i64) -> bool {
+        let users = self.users.read().unwrap();
+        users.is_empty() || users.contains(&user_id)
+    }
+
+    /// Whether everyone is allowed (no list configured)
+    pub fn is_open(&self) -> bool {
+        self.users.read().unwrap().is_empty()
+    }
+
+    /// Current list (empty when everyone is allowed)
+    pub fn list(&self) -> Vec<i64> {
+        self.users.read().unwrap().clone()
+    }
+
+    /// Replace the whole list; refused if it would empty a non-empty list
+    pub fn replace(&self, mut users: Vec<i64>) -> Result<()> {
+        let mut current = self.users.write().unwrap();
+        if users.is_empty() && !current.is_empty() {
+            anyhow::bail!("An empty allowed list would let everyone in");
+        }
+        users.sort_unstable();
+        users.dedup();
+        *current = users;
+        self.save(&current)
+    }
+
+    /// Add a user; false if already listed
+    pub fn allow(&self, user_id: i64) -> Result<bool> {
+        let mut users = self.users.write().unwrap();
+        if users.contains(&user_id) {
+            return Ok(false);
+        }
+        users.push(user_id);
+        users.sort_unstable();
+        self.save(&users)?;
+        Ok(true)
+    }
+
+    /// Remove a user; false if not listed, refused for the last one listed
+    pub fn deny(&self, user_id: i64) -> Result<bool> {
+        let mut users = self.users.write().unwrap();
+        if !users.contains(&user_id) {
+            return Ok(false);
+        }
+        if users.len() == 1 {
+            anyhow::bail!("User {} is the last one allowed; an empty list would let everyone in", user_id);
+        }
+        users.retain(|&id| id != user_id);
+        self.save(&users)?;
+        Ok(true)
+    }
+}
+
 /// Permission manager for all users
 pub struct PermissionManager {
     /// Project permissions by path prefix
@@ -236,6 +420,15 @@ pub struct PermissionManager {
     sessions: RwLock<HashMap<i64, SessionPermissions>>,
     /// Default permission level for unknown projects
     default_level: PermissionLevel,
+    /// Hard off-switch for autonomous mode
+    safe_mode: bool,
+}
+
+/// Whether `CLAUDEBOT_SAFE_MODE` is set ("true"/"1")
+pub fn safe_mode_from_env() -> bool {
+    std::env::var("CLAUDEBOT_SAFE_MODE")
+        .map(|s| s == "true" || s == "1")
+        .unwrap_or(false)
 }
 
 impl PermissionManager {
@@ -250,6 +443,43 @@ impl PermissionManager {
             projects,
             sessions: RwLock::new(HashMap::new()),
             default_level: PermissionLevel::Autonomous,
+            safe_mode: false,
+        }
+    }
+
+    /// Create with safe mode taken from `CLAUDEBOT_SAFE_MODE`
+    pub fn from_env() -> Self {
+        Self::new().with_safe_mode(safe_mode_from_env())
+    }
+
+    /// Enable or disable safe mode
+    pub fn with_safe_mode(mut self, safe_mode: bool) -> Self {
+        self.safe_mode = safe_mode;
+        self
+    }
+
+    /// Whether safe mode is active
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode
+    }
+
+    /// Set the level for users without a session or project
+    pub fn with_default_level(mut self, level: PermissionLevel) -> Self {
+        self.default_level = level;
+        self
+    }
+
+    /// Level for users without a session, after the safe-mode cap
+    pub fn default_level(&self) -> PermissionLevel {
+        self.cap_level(self.default_level)
+    }
+
+    /// Cap a level at Supervised when in safe mode
+    fn cap_level(&self, level: PermissionLevel) -> PermissionLevel {
+        if self.safe_mode && level == PermissionLevel::Autonomous {
+            PermissionLevel::Supervised
+        } else {
+            level
         }
     }
 
@@ -261,9 +491,14 @@ impl PermissionManager {
             .unwrap_or(self.default_level);
 
         let sessions = self.sessions.read().unwrap();
-        sessions.get(&user_id)
+        let mut session = sessions.get(&user_id)
             .cloned()
-            .unwrap_or_else(|| SessionPermissions::new(user_id, base_level))
+            .unwrap_or_else(|| SessionPermissions::new(user_id, base_level));
+        if self.safe_mode {
+            session.current_level = self.cap_level(session.current_level);
+            session.escalation_expires = None;
+        }
+        session
     }
 
     /// Check if operation is allowed for user in project
@@ -305,11 +540,17 @@ impl PermissionManager {
     }
 
     /// Escalate user to autonomous mode
-    pub fn escalate_user(&self, user_id: i64, duration: Option<Duration>) {
+    ///
+    /// Fails (and does nothing) in safe mode.
+    pub fn escalate_user(&self, user_id: i64, duration: Option<Duration>) -> Result<()> {
+        if self.safe_mode {
+            anyhow::bail!("Autonomous mode is disabled (safe mode)");
+        }
         let mut sessions = self.sessions.write().unwrap();
         let session = sessions.entry(user_id)
             .or_insert_with(|| SessionPermissions::new(user_id, self.default_level));
         session.escalate(duration);
+        Ok(())
     }
 
     /// Revoke user escalation
@@ -325,15 +566,17 @@ impl PermissionManager {
         let sessions = self.sessions.read().unwrap();
         if let Some(session) = sessions.get(&user_id) {
             PermissionStatus {
-                level: session.effective_level(),
-                escalation_remaining: session.escalation_remaining(),
+                level: self.cap_level(session.effective_level()),
+                escalation_remaining: session.escalation_remaining().filter(|_| !self.safe_mode),
                 approved_ops: session.approved_operations.len(),
+                safe_mode: self.safe_mode,
             }
         } else {
             PermissionStatus {
-                level: self.default_level,
+                level: self.cap_level(self.default_level),
                 escalation_remaining: None,
                 approved_ops: 0,
+                safe_mode: self.safe_mode,
             }
         }
     }
@@ -368,6 +611,8 @@ pub struct PermissionStatus {
     pub level: PermissionLevel,
     pub escalation_remaining: Option<Duration>,
     pub approved_ops: usize,
+    /// Safe mode is active (escalation disabled)
+    pub safe_mode: bool,
 }
 
 /// Simple glob matching (supports * and **)
@@ -458,6 +703,72 @@ mod tests {
         assert!(session.is_allowed(Operation::Deploy));
     }
 
+    #[test]
+    fn test_level_parse_and_cover() {
+        assert_eq!(PermissionLevel::parse(" Supervised "), Some(PermissionLevel::Supervised));
+        assert_eq!(PermissionLevel::parse("root"), None);
+        assert!(PermissionLevel::Autonomous.covers(PermissionLevel::Supervised));
+        assert!(!PermissionLevel::Restricted.covers(PermissionLevel::Supervised));
+        assert_eq!(mcp_tool_level("graph_traverse"), PermissionLevel::Restricted);
+        assert_eq!(mcp_tool_level("claude_complete"), PermissionLevel::Autonomous);
+        assert_eq!(mcp_tool_level("memory_resources"), PermissionLevel::Supervised);
+        assert_eq!(mcp_tool_level("conversation_resources"), PermissionLevel::Autonomous);
+    }
+
+    #[test]
+    fn test_safe_mode_blocks_escalation() {
+        let pm = PermissionManager::new().with_safe_mode(true);
+
+        assert!(pm.escalate_user(1, Some(Duration::from_secs(60))).is_err());
+        let status = pm.get_status(1);
+        assert_eq!(status.level, PermissionLevel::Supervised);
+        assert!(status.safe_mode);
+        assert!(!pm.get_session(1, None).is_allowed(Operation::Push));
+
+        let normal = PermissionManager::new();
+        assert!(normal.escalate_user(1, None).is_ok());
+        assert_eq!(normal.get_status(1).level, PermissionLevel::Autonomous);
+    }
+
+    #[test]
+    fn test_allowed_users_update_live() {
+        let allowed = AllowedUsers::new(vec![]);
+        assert!(allowed.is_open() && allowed.is_allowed(7));
+
+        let shared = allowed.clone();
+        assert!(shared.allow(42).unwrap());
+        assert!(!shared.allow(42).unwrap());
+        assert!(allowed.is_allowed(42) && !allowed.is_allowed(7));
+
+        allowed.replace(vec![3, 1, 3]).unwrap();
+        assert_eq!(shared.list(), vec![1, 3]);
+        assert!(shared.deny(1).unwrap());
+        assert!(!shared.deny(1).unwrap());
+        assert!(!allowed.is_allowed(1) && allowed.is_allowed(3));
+
+        // Revoking the last user or clearing the list would open the bot
+        assert!(shared.deny(3).is_err());
+        assert!(shared.replace(vec![]).is_err());
+        assert_eq!(allowed.list(), vec![3]);
+        assert!(!allowed.is_allowed(7));
+    }
+
+    #[test]
+    fn test_allowed_users_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("allowed_users.json");
+
+        // No file yet: the configured list
+        let allowed = AllowedUsers::new(vec![1]).with_file(&path).unwrap();
+        assert_eq!(allowed.list(), vec![1]);
+        allowed.allow(2).unwrap();
+        allowed.deny(1).unwrap();
+
+        // The saved list wins over the configured one
+        let reopened = AllowedUsers::new(vec![1, 9]).with_file(&path).unwrap();
+        assert_eq!(reopened.list(), vec![2]);
+    }
+
     #[test]
     fn test_glob_matching() {
         assert!(glob_match("**/auth/**", "src/auth/login.rs"));
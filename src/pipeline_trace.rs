@@ -0,0 +1,110 @@
+//! Pipeline Trace
+//!
+//! `/trace on` makes every chat message report what the bot did with it, as a
+//! follow-up after the response: the routing decision, the memories retrieved
+//! with their scores, the context added to the prompt, the budget check, the
+//! model and tokens that ran, and the learning done along the way. It's the
+//! `/route`, `/context` and `/usage` views for one message, in one place.
+
+use crate::input_limits::truncate_chars;
+use crate::telegram_ui::html_escape;
+
+/// Longest trace sent (characters before escaping); Telegram allows 4096
+const MAX_TRACE_CHARS: usize = 3500;
+
+/// Longest single line, for previews of context and memories
+pub const MAX_LINE_CHARS: usize = 300;
+
+/// Steps of one message, in the order they ran
+#[derive(Debug, Default)]
+pub struct PipelineTrace {
+    enabled: bool,
+    stages: Vec<(&'static str, Vec<String>)>,
+}
+
+impl PipelineTrace {
+    /// A trace that records nothing unless `enabled`
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, stages: Vec::new() }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Append a line to `stage`, starting the stage if it's new
+    pub fn add(&mut self, stage: &'static str, line: impl Into<String>) {
+        if !self.enabled {
+            return;
+        }
+        let line = one_line(&line.into());
+        match self.stages.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, lines)) => lines.push(line),
+            None => self.stages.push((stage, vec![line])),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Telegram HTML, stages in an expandable quote, cut to `MAX_TRACE_CHARS`
+    pub fn to_html(&self) -> String {
+        let mut body = String::new();
+        let mut dropped = 0;
+        for (stage, lines) in &self.stages {
+            let section = format!("[{}]\n{}\n", stage, lines.join("\n"));
+            if dropped > 0 || body.len() + section.len() > MAX_TRACE_CHARS {
+                dropped += 1;
+                continue;
+            }
+            body.push_str(&section);
+        }
+        if dropped > 0 {
+            body.push_str(&format!("… {} more stage(s) cut", dropped));
+        }
+        format!(
+            "🔎 <b>Pipeline trace</b>\n<blockquote expandable>{}</blockquote>",
+            html_escape(body.trim_end())
+        )
+    }
+}
+
+/// Collapse whitespace and cut to `MAX_LINE_CHARS`
+fn one_line(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let cut = truncate_chars(&flat, MAX_LINE_CHARS);
+    if cut.len() < flat.len() {
+        format!("{}…", cut)
+    } else {
+        flat
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_stages_and_limits() {
+        let mut off = PipelineTrace::new(false);
+        off.add("route", "api → sonnet");
+        assert!(off.is_empty());
+
+        let mut trace = PipelineTrace::new(true);
+        trace.add("route", "api → sonnet");
+        trace.add("context", "2 memories <b>");
+        trace.add("route", "reasoning:\n  general question");
+        let html = trace.to_html();
+        assert!(html.contains("[route]\napi → sonnet\nreasoning: general question\n[context]"));
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(html.starts_with("🔎 <b>Pipeline trace</b>\n<blockquote expandable>"));
+
+        for stage in ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m"] {
+            trace.add(stage, "x".repeat(1000));
+        }
+        let html = trace.to_html();
+        assert!(html.chars().count() < MAX_TRACE_CHARS + 200);
+        assert!(html.contains("more stage(s) cut"));
+    }
+}
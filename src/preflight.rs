@@ -2,12 +2,27 @@
 //!
 //! Verifies tool availability and credentials BEFORE executing Claude Code.
 //! Prevents silent failures from missing `gh`, expired tokens, etc.
+//!
+//! `check_all` also probes the Anthropic API and Ollama endpoints, so network
+//! problems show up as "unreachable" instead of a confusing task failure.
+//! `DiagReport` collects these and other health checks into one pass/warn/fail
+//! report for `/diag`.
 
 use std::collections::HashMap;
 use std::process::Stdio;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
 use tracing::debug;
 
+use crate::claude_cli::ClaudeCli;
+
+/// Default Anthropic API base URL (override with `ANTHROPIC_BASE_URL`)
+const DEFAULT_ANTHROPIC_URL: &str = "https://api.anthropic.com";
+/// Default Ollama URL (override with `OLLAMA_URL`)
+const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+/// Per-endpoint probe timeout
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Result of pre-flight checks
 #[derive(Debug, Default)]
 pub struct PreflightResult {
@@ -19,6 +34,8 @@ pub struct PreflightResult {
     pub missing_creds: Vec<String>,
     /// Non-blocking warnings
     pub warnings: Vec<String>,
+    /// Network reachability (only filled by `check_all`)
+    pub connectivity: Option<ConnectivityReport>,
 }
 
 impl PreflightResult {
@@ -48,6 +65,16 @@ impl PreflightResult {
             }
         }
 
+        if let Some(ref report) = self.connectivity {
+            let down: Vec<_> = report.endpoints.iter().filter(|e| e.required && !e.reachable).collect();
+            if !down.is_empty() {
+                msg.push_str("\nUnreachable:\n");
+                for endpoint in down {
+                    msg.push_str(&format!("  - {}\n", endpoint.format_line()));
+                }
+            }
+        }
+
         msg
     }
 
@@ -64,9 +91,146 @@ impl PreflightResult {
     }
 }
 
+/// Reachability of one network endpoint
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub name: &'static str,
+    pub url: String,
+    /// Whether a failure blocks execution
+    pub required: bool,
+    /// Got any HTTP response (auth errors still count as reachable)
+    pub reachable: bool,
+    pub latency: Option<Duration>,
+    pub error: Option<String>,
+}
+
+impl EndpointStatus {
+    /// One-line summary, e.g. `Anthropic API (https://...): 120ms`
+    pub fn format_line(&self) -> String {
+        match (&self.error, self.latency) {
+            (Some(error), _) => format!("{} ({}): {}", self.name, self.url, error),
+            (None, Some(latency)) => format!("{} ({}): {}ms", self.name, self.url, latency.as_millis()),
+            (None, None) => format!("{} ({}): ok", self.name, self.url),
+        }
+    }
+}
+
+/// Result of the network connectivity check
+#[derive(Debug, Clone, Default)]
+pub struct ConnectivityReport {
+    pub endpoints: Vec<EndpointStatus>,
+}
+
+impl ConnectivityReport {
+    /// Whether every required endpoint is reachable
+    pub fn ok(&self) -> bool {
+        self.endpoints.iter().all(|e| e.reachable || !e.required)
+    }
+
+    /// Format for display
+    pub fn format(&self) -> String {
+        let mut msg = String::from("Connectivity:\n");
+        for endpoint in &self.endpoints {
+            let mark = if endpoint.reachable { "ok" } else { "FAIL" };
+            msg.push_str(&format!("  [{}] {}\n", mark, endpoint.format_line()));
+        }
+        msg
+    }
+}
+
+/// Outcome of a single diagnostic check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DiagStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl DiagStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Warn => "WARN",
+            Self::Fail => "FAIL",
+        }
+    }
+}
+
+/// One line of a diagnostic report
+#[derive(Debug, Clone)]
+pub struct DiagCheck {
+    pub name: String,
+    pub status: DiagStatus,
+    pub detail: String,
+}
+
+/// Aggregated health checks
+#[derive(Debug, Clone, Default)]
+pub struct DiagReport {
+    pub checks: Vec<DiagCheck>,
+}
+
+impl DiagReport {
+    pub fn push(&mut self, name: &str, status: DiagStatus, detail: impl Into<String>) {
+        self.checks.push(DiagCheck {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    /// Worst status across all checks
+    pub fn overall(&self) -> DiagStatus {
+        self.checks.iter().map(|c| c.status).max().unwrap_or(DiagStatus::Pass)
+    }
+
+    /// Format for display
+    pub fn format(&self) -> String {
+        let count = |status| self.checks.iter().filter(|c| c.status == status).count();
+        let mut msg = format!(
+            "Diagnostics: {} ({} pass, {} warn, {} fail)\n\n",
+            self.overall().label(),
+            count(DiagStatus::Pass),
+            count(DiagStatus::Warn),
+            count(DiagStatus::Fail)
+        );
+        for check in &self.checks {
+            msg.push_str(&format!("[{}] {}: {}\n", check.status.label(), check.name, check.detail));
+        }
+        msg
+    }
+}
+
+/// Free space below this fails the disk check
+pub const DISK_FAIL_BYTES: u64 = 100 * 1024 * 1024;
+/// Free space below this warns
+pub const DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Bytes available on the filesystem holding `path` (via `df`)
+pub async fn available_disk_bytes(path: &std::path::Path) -> Result<u64, String> {
+    let output = Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .await
+        .map_err(|e| format!("failed to run df: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "unexpected df output".to_string())
+}
+
+/// Available bytes from POSIX `df -Pk` output (4th column, in KiB)
+fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let kib: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
 /// Tool check configuration
 struct ToolCheck {
-    command: &'static str,
+    command: String,
     args: &'static [&'static str],
     install_hint: &'static str,
 }
@@ -83,6 +247,9 @@ struct CredentialCheck {
 pub struct PreflightChecker {
     required_tools: HashMap<String, ToolCheck>,
     credential_checks: Vec<CredentialCheck>,
+    anthropic_url: String,
+    ollama_url: String,
+    probe_timeout: Duration,
 }
 
 impl Default for PreflightChecker {
@@ -99,7 +266,7 @@ impl PreflightChecker {
         required_tools.insert(
             "git".into(),
             ToolCheck {
-                command: "git",
+                command: "git".into(),
                 args: &["--version"],
                 install_hint: "apt install git",
             },
@@ -107,7 +274,7 @@ impl PreflightChecker {
         required_tools.insert(
             "gh".into(),
             ToolCheck {
-                command: "gh",
+                command: "gh".into(),
                 args: &["--version"],
                 install_hint: "apt install gh  # Then: gh auth login",
             },
@@ -115,7 +282,7 @@ impl PreflightChecker {
         required_tools.insert(
             "claude".into(),
             ToolCheck {
-                command: "claude",
+                command: ClaudeCli::from_env().path,
                 args: &["--version"],
                 install_hint: "npm install -g @anthropic-ai/claude-code",
             },
@@ -123,7 +290,7 @@ impl PreflightChecker {
         required_tools.insert(
             "cargo".into(),
             ToolCheck {
-                command: "cargo",
+                command: "cargo".into(),
                 args: &["--version"],
                 install_hint: "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh",
             },
@@ -131,7 +298,7 @@ impl PreflightChecker {
         required_tools.insert(
             "node".into(),
             ToolCheck {
-                command: "node",
+                command: "node".into(),
                 args: &["--version"],
                 install_hint: "apt install nodejs  # or use nvm",
             },
@@ -139,12 +306,25 @@ impl PreflightChecker {
         required_tools.insert(
             "npm".into(),
             ToolCheck {
-                command: "npm",
+                command: "npm".into(),
                 args: &["--version"],
                 install_hint: "apt install npm",
             },
         );
 
+        // Only needed when OCR is pinned to tesseract (auto falls back to Claude)
+        let ocr = crate::ocr::OcrConfig::from_env();
+        if ocr.enabled && ocr.backend == crate::ocr::OcrBackend::Tesseract {
+            required_tools.insert(
+                "tesseract".into(),
+                ToolCheck {
+                    command: "tesseract".into(),
+                    args: &["--version"],
+                    install_hint: "apt install tesseract-ocr",
+                },
+            );
+        }
+
         let credential_checks = vec![
             CredentialCheck {
                 name: "GitHub CLI auth",
@@ -175,9 +355,21 @@ impl PreflightChecker {
         Self {
             required_tools,
             credential_checks,
+            anthropic_url: std::env::var("ANTHROPIC_BASE_URL")
+                .unwrap_or_else(|_| DEFAULT_ANTHROPIC_URL.to_string()),
+            ollama_url: std::env::var("OLLAMA_URL").unwrap_or_else(|_| DEFAULT_OLLAMA_URL.to_string()),
+            probe_timeout: PROBE_TIMEOUT,
         }
     }
 
+    /// Override the probed endpoints and timeout
+    pub fn with_endpoints(mut self, anthropic_url: &str, ollama_url: &str, timeout: Duration) -> Self {
+        self.anthropic_url = anthropic_url.to_string();
+        self.ollama_url = ollama_url.to_string();
+        self.probe_timeout = timeout;
+        self
+    }
+
     /// Check all tools and credentials
     pub async fn check_all(&self) -> PreflightResult {
         let mut result = PreflightResult {
@@ -214,9 +406,39 @@ impl PreflightChecker {
             }
         }
 
+        // Check network (Ollama is optional)
+        let report = self.check_connectivity().await;
+        for endpoint in report.endpoints.iter().filter(|e| !e.reachable) {
+            if endpoint.required {
+                result.ready = false;
+            } else {
+                result.warnings.push(format!("{} unreachable", endpoint.format_line()));
+            }
+        }
+        result.connectivity = Some(report);
+
         result
     }
 
+    /// Probe the Anthropic API and Ollama endpoints concurrently
+    pub async fn check_connectivity(&self) -> ConnectivityReport {
+        let client = match reqwest::Client::builder().timeout(self.probe_timeout).build() {
+            Ok(client) => client,
+            Err(e) => {
+                debug!("Failed to build probe client: {}", e);
+                reqwest::Client::new()
+            }
+        };
+
+        let (anthropic, ollama) = tokio::join!(
+            probe(&client, "Anthropic API", &self.anthropic_url, true),
+            probe(&client, "Ollama", &self.ollama_url, false),
+        );
+        ConnectivityReport {
+            endpoints: vec![anthropic, ollama],
+        }
+    }
+
     /// Check only tools/creds needed for a specific command
     /// Only claude is mandatory - everything else is a warning
     pub async fn check_for_command(&self, command: &str) -> PreflightResult {
@@ -311,7 +533,7 @@ impl PreflightChecker {
 
     /// Check if a tool exists and works
     async fn tool_exists(&self, check: &ToolCheck) -> bool {
-        match Command::new(check.command)
+        match Command::new(&check.command)
             .args(check.args)
             .stdout(Stdio::null())
             .stderr(Stdio::null())
@@ -412,6 +634,27 @@ impl PreflightChecker {
     }
 }
 
+/// Lightweight reachability probe: any HTTP response counts
+async fn probe(client: &reqwest::Client, name: &'static str, url: &str, required: bool) -> EndpointStatus {
+    let start = Instant::now();
+    let (reachable, error) = match client.get(url).send().await {
+        Ok(_) => (true, None),
+        Err(e) if e.is_timeout() => (false, Some("timed out".to_string())),
+        Err(e) if e.is_connect() => (false, Some("connection failed".to_string())),
+        Err(e) => (false, Some(e.to_string())),
+    };
+    debug!("Probe {} ({}): reachable={}", name, url, reachable);
+
+    EndpointStatus {
+        name,
+        url: url.to_string(),
+        required,
+        reachable,
+        latency: reachable.then(|| start.elapsed()),
+        error,
+    }
+}
+
 #[derive(Debug)]
 enum CredStatus {
     Ok,
@@ -446,10 +689,61 @@ mod tests {
             missing_tools: vec!["gh (apt install gh)".into()],
             missing_creds: vec!["GitHub CLI: not authenticated".into()],
             warnings: vec![],
+            connectivity: None,
         };
 
         let msg = result.format_error();
         assert!(msg.contains("gh"));
         assert!(msg.contains("GitHub CLI"));
     }
+
+    #[test]
+    fn test_diag_report() {
+        let mut report = DiagReport::default();
+        assert_eq!(report.overall(), DiagStatus::Pass);
+
+        report.push("Claude CLI", DiagStatus::Pass, "1.0.0");
+        report.push("Disk", DiagStatus::Warn, "512 MB free");
+        assert_eq!(report.overall(), DiagStatus::Warn);
+
+        let msg = report.format();
+        assert!(msg.starts_with("Diagnostics: WARN (1 pass, 1 warn, 0 fail)"));
+        assert!(msg.contains("[WARN] Disk: 512 MB free"));
+
+        let df = "Filesystem 1024-blocks Used Available Capacity Mounted on\n/dev/sda1 1000 400 600 40% /\n";
+        assert_eq!(parse_df_available(df), Some(600 * 1024));
+        assert_eq!(parse_df_available("garbage"), None);
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity() {
+        // Minimal HTTP server for the "Anthropic" endpoint
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let up = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await;
+        });
+
+        // Closed port for "Ollama"
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let down = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+
+        let checker = PreflightChecker::new().with_endpoints(&up, &down, Duration::from_secs(2));
+        let report = checker.check_connectivity().await;
+
+        assert!(report.endpoints[0].reachable);
+        assert!(report.endpoints[0].latency.is_some());
+        assert!(!report.endpoints[1].reachable);
+        assert!(report.endpoints[1].error.is_some());
+        // Ollama is optional
+        assert!(report.ok());
+        assert!(report.format().contains("[FAIL] Ollama"));
+    }
 }
@@ -2,13 +2,33 @@
 //!
 //! Routes messages to appropriate handlers with model selection.
 //! Supports both keyword-based routing and Ollama/Llama classification.
+//!
+//! Whatever picks the model, prompts that clearly involve real code work
+//! (a code block, a diff, "implement", ...) are never sent below Sonnet:
+//! terse coding prompts get classified as simple far too often.
+//!
+//! Chat messages run on the CLI's default model unless model selection is
+//! turned on (`CLAUDEBOT_ROUTE_MODEL`); routes are recorded for `/model stats`
+//! either way.
 
 use once_cell::sync::Lazy;
 use regex::Regex;
-use tracing::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::llama_worker::{LlamaWorker, QueryComplexity};
 
 /// Routing targets
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Target {
     Api,      // Claude API direct (quick answers)
     Backend,  // Rust/Axum code
@@ -30,7 +50,8 @@ impl Target {
 }
 
 /// Model hints for cost optimization
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ModelHint {
     /// Fast, cheap - factual Q&A, simple lookups ($0.25/M)
     Haiku,
@@ -49,6 +70,230 @@ impl ModelHint {
             ModelHint::Opus => "opus",
         }
     }
+
+    /// Tier named exactly (`haiku`, `sonnet`, `opus`), case-insensitive
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "haiku" => Some(ModelHint::Haiku),
+            "sonnet" => Some(ModelHint::Sonnet),
+            "opus" => Some(ModelHint::Opus),
+            _ => None,
+        }
+    }
+
+    /// Tier of a full model name (`claude-3-5-haiku-...`), Sonnet if unrecognized
+    pub fn from_model_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if name.contains("haiku") {
+            ModelHint::Haiku
+        } else if name.contains("opus") {
+            ModelHint::Opus
+        } else {
+            ModelHint::Sonnet
+        }
+    }
+}
+
+impl From<QueryComplexity> for ModelHint {
+    fn from(complexity: QueryComplexity) -> Self {
+        match complexity {
+            QueryComplexity::Simple => ModelHint::Haiku,
+            QueryComplexity::Moderate => ModelHint::Sonnet,
+            QueryComplexity::Complex => ModelHint::Opus,
+        }
+    }
+}
+
+impl From<ModelHint> for QueryComplexity {
+    fn from(model: ModelHint) -> Self {
+        match model {
+            ModelHint::Haiku => QueryComplexity::Simple,
+            ModelHint::Sonnet => QueryComplexity::Moderate,
+            ModelHint::Opus => QueryComplexity::Complex,
+        }
+    }
+}
+
+/// Degrading to a cheaper model when the requested one is unavailable
+///
+/// On an error matching one of `triggers` (overload, rate limit), the request
+/// is retried with the next tier in `chain`, up to `max_depth` times.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackConfig {
+    /// Tiers from first choice to last resort
+    pub chain: Vec<ModelHint>,
+    /// Most fallback attempts per request; 0 disables fallback
+    pub max_depth: usize,
+    /// Error texts that trigger a fallback: numeric codes ("529") must appear
+    /// as a whole token, anything else as a case-insensitive substring
+    pub triggers: Vec<String>,
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        Self {
+            chain: vec![ModelHint::Opus, ModelHint::Sonnet, ModelHint::Haiku],
+            max_depth: 2,
+            triggers: ["529", "overloaded", "429", "rate limit", "rate_limit"]
+                .iter()
+                .map(|t| t.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl FallbackConfig {
+    /// `CLAUDEBOT_FALLBACK_CHAIN` (e.g. `opus,sonnet,haiku`),
+    /// `CLAUDEBOT_FALLBACK_DEPTH`, `CLAUDEBOT_FALLBACK_ON` (comma-separated
+    /// status codes and error substrings)
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(chain) = std::env::var("CLAUDEBOT_FALLBACK_CHAIN") {
+            match chain.split(',').map(ModelHint::parse).collect::<Option<Vec<_>>>() {
+                Some(chain) => config.chain = chain,
+                None => warn!("Ignoring invalid CLAUDEBOT_FALLBACK_CHAIN '{}' (expected tiers like opus,sonnet,haiku)", chain),
+            }
+        }
+        if let Some(depth) = std::env::var("CLAUDEBOT_FALLBACK_DEPTH")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+        {
+            config.max_depth = depth;
+        }
+        if let Ok(triggers) = std::env::var("CLAUDEBOT_FALLBACK_ON") {
+            config.triggers = triggers
+                .split(',')
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect();
+        }
+        config
+    }
+
+    /// Whether an error should move the request to the next tier
+    pub fn triggers_on(&self, error: &str) -> bool {
+        let error = error.to_lowercase();
+        self.triggers.iter().any(|t| {
+            if t.chars().all(|c| c.is_ascii_digit()) {
+                // "429" shouldn't match a request id or a duration like 14290ms
+                error.split(|c: char| !c.is_ascii_alphanumeric()).any(|token| token == t)
+            } else {
+                error.contains(t.as_str())
+            }
+        })
+    }
+
+    /// Tier to try after `current` fails, if the chain continues past it
+    pub fn next(&self, current: ModelHint) -> Option<ModelHint> {
+        let position = self.chain.iter().position(|m| *m == current)?;
+        self.chain.get(position + 1).copied()
+    }
+
+    /// Like `next`, for a request whose tier may be unknown (the CLI's
+    /// default model); that counts as the chain's first choice
+    pub fn next_after(&self, current: Option<ModelHint>) -> Option<ModelHint> {
+        match current {
+            Some(current) => self.next(current),
+            None => self.chain.get(1).copied(),
+        }
+    }
+}
+
+/// Keeps code-heavy prompts on at least Sonnet
+///
+/// A prompt triggers the floor with a fenced code block, diff/patch
+/// content, `min_code_lines` lines that look like code, or one of
+/// `keywords` as a whole word.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeFloor {
+    pub enabled: bool,
+    /// Lowercase keywords or phrases
+    pub keywords: Vec<String>,
+    /// Code-looking lines needed without a code block; 0 disables the check
+    pub min_code_lines: usize,
+}
+
+impl Default for CodeFloor {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            keywords: [
+                "diff", "patch", "implement", "refactor", "rewrite", "write a function",
+                "write a script", "unit test", "regex", "algorithm", "stack trace",
+                "traceback", "race condition", "deadlock", "segfault",
+            ]
+            .iter()
+            .map(|k| k.to_string())
+            .collect(),
+            min_code_lines: 3,
+        }
+    }
+}
+
+impl CodeFloor {
+    /// `CLAUDEBOT_CODE_FLOOR` (false disables), `CLAUDEBOT_CODE_FLOOR_KEYWORDS`
+    /// (comma-separated, replaces the defaults), `CLAUDEBOT_CODE_FLOOR_LINES`
+    pub fn from_env() -> Self {
+        let mut floor = Self::default();
+        if let Ok(value) = std::env::var("CLAUDEBOT_CODE_FLOOR") {
+            floor.enabled = !matches!(value.trim().to_lowercase().as_str(), "false" | "0" | "no" | "off");
+        }
+        if let Ok(keywords) = std::env::var("CLAUDEBOT_CODE_FLOOR_KEYWORDS") {
+            floor.keywords = keywords
+                .split(',')
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect();
+        }
+        if let Some(lines) = std::env::var("CLAUDEBOT_CODE_FLOOR_LINES")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+        {
+            floor.min_code_lines = lines;
+        }
+        floor
+    }
+
+    /// The signal that makes `message` code-heavy, if any
+    pub fn signal(&self, message: &str) -> Option<String> {
+        if !self.enabled {
+            return None;
+        }
+        if message.contains("```") {
+            return Some("code block".to_string());
+        }
+        if message.lines().any(|l| l.starts_with("--- a/") || l.starts_with("+++ b/") || l.starts_with("@@ -")) {
+            return Some("diff content".to_string());
+        }
+        let msg_lower = message.to_lowercase();
+        if let Some(kw) = self.keywords.iter().find(|kw| contains_word(&msg_lower, kw)) {
+            return Some(format!("keyword '{}'", kw));
+        }
+        let code_lines = message.lines().filter(|l| looks_like_code(l)).count();
+        if self.min_code_lines > 0 && code_lines >= self.min_code_lines {
+            return Some(format!("{} lines of code", code_lines));
+        }
+        None
+    }
+}
+
+/// `needle` in `haystack` with no letters or digits directly around it
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(at, _)| {
+        let before = haystack[..at].chars().next_back();
+        let after = haystack[at + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+fn looks_like_code(line: &str) -> bool {
+    let line = line.trim();
+    line.ends_with(';')
+        || line.ends_with('{')
+        || line == "}"
+        || ["fn ", "def ", "class ", "import ", "#include", "let ", "const ", "return "]
+            .iter()
+            .any(|p| line.starts_with(p))
 }
 
 /// Routing result
@@ -56,8 +301,13 @@ impl ModelHint {
 pub struct RouteResult {
     pub target: Target,
     pub model: ModelHint,
+    /// Detected query complexity (drives model selection)
+    pub complexity: QueryComplexity,
+    /// Which signals triggered the target and model choice
     pub reasoning: String,
     pub confidence: f32,
+    /// Set when a Haiku choice was raised to Sonnet, with the code signal
+    pub code_floor: Option<String>,
 }
 
 impl RouteResult {
@@ -120,15 +370,365 @@ static EXPLICIT_PATTERN: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"(?i)@(backend|frontend|codebase|api|circle)\b").unwrap()
 });
 
+/// Default number of cached routes
+pub const DEFAULT_ROUTE_CACHE_CAPACITY: usize = 256;
+
+/// Default lifetime of a cached route
+pub const DEFAULT_ROUTE_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Route cache hit/miss counters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RouteCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl RouteCacheStats {
+    /// Hit rate in [0, 1] (0 when nothing was looked up yet)
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+struct CachedRoute {
+    result: RouteResult,
+    inserted_at: Instant,
+    last_used: u64,
+}
+
+/// Small LRU cache of classified routes keyed by normalized prompt hash
+struct RouteCache {
+    capacity: usize,
+    ttl: Duration,
+    entries: Mutex<HashMap<u64, CachedRoute>>,
+    /// Monotonic use counter for LRU ordering
+    tick: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl RouteCache {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            tick: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Hash of the prompt with case and whitespace differences removed
+    fn key(message: &str) -> u64 {
+        let normalized = message
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut hasher = DefaultHasher::new();
+        normalized.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn get(&self, key: u64) -> Option<RouteResult> {
+        if self.capacity == 0 {
+            return None;
+        }
+        let mut entries = self.entries.lock().ok()?;
+        let hit = match entries.get_mut(&key) {
+            Some(entry) if entry.inserted_at.elapsed() < self.ttl => {
+                entry.last_used = self.tick.fetch_add(1, Ordering::Relaxed);
+                Some(entry.result.clone())
+            }
+            Some(_) => {
+                entries.remove(&key);
+                None
+            }
+            None => None,
+        };
+        let counter = if hit.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        hit
+    }
+
+    fn insert(&self, key: u64, result: &RouteResult) {
+        if self.capacity == 0 {
+            return;
+        }
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.inserted_at.elapsed() < self.ttl);
+            if entries.len() >= self.capacity {
+                if let Some(oldest) = entries.iter().min_by_key(|(_, e)| e.last_used).map(|(k, _)| *k) {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, CachedRoute {
+            result: result.clone(),
+            inserted_at: Instant::now(),
+            last_used: self.tick.fetch_add(1, Ordering::Relaxed),
+        });
+    }
+
+    fn stats(&self) -> RouteCacheStats {
+        RouteCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().map(|e| e.len()).unwrap_or(0),
+        }
+    }
+
+    fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// Decisions kept for `/model stats` (a week of heavy use)
+pub const MAX_ROUTE_DECISIONS: usize = 10_000;
+
+/// How long routing decisions are kept
+const ROUTE_DECISION_RETENTION_SECS: i64 = 7 * 24 * 3600;
+
+/// One routed request
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RouteDecision {
+    /// Unix timestamp (seconds)
+    pub at: i64,
+    pub target: Target,
+    pub model: ModelHint,
+    /// Cost of the request, if known
+    pub cost_usd: Option<f64>,
+}
+
+/// Request count and average cost for one target or model
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteShare {
+    pub name: &'static str,
+    pub count: usize,
+    /// Average over the decisions with a known cost
+    pub avg_cost_usd: Option<f64>,
+}
+
+/// Routing decisions over a time window, most frequent first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteDistribution {
+    pub total: usize,
+    pub by_target: Vec<RouteShare>,
+    pub by_model: Vec<RouteShare>,
+}
+
+impl RouteDistribution {
+    fn from_decisions<'a>(decisions: impl Iterator<Item = &'a RouteDecision> + Clone) -> Self {
+        Self {
+            total: decisions.clone().count(),
+            by_target: Self::shares(decisions.clone().map(|d| (d.target.as_str(), d.cost_usd))),
+            by_model: Self::shares(decisions.map(|d| (d.model.as_str(), d.cost_usd))),
+        }
+    }
+
+    fn shares(items: impl Iterator<Item = (&'static str, Option<f64>)>) -> Vec<RouteShare> {
+        // name -> (count, cost sum, costed count)
+        let mut groups: HashMap<&'static str, (usize, f64, usize)> = HashMap::new();
+        for (name, cost) in items {
+            let group = groups.entry(name).or_default();
+            group.0 += 1;
+            if let Some(cost) = cost {
+                group.1 += cost;
+                group.2 += 1;
+            }
+        }
+        let mut shares: Vec<RouteShare> = groups
+            .into_iter()
+            .map(|(name, (count, cost, costed))| RouteShare {
+                name,
+                count,
+                avg_cost_usd: (costed > 0).then(|| cost / costed as f64),
+            })
+            .collect();
+        shares.sort_by(|a, b| b.count.cmp(&a.count).then(a.name.cmp(b.name)));
+        shares
+    }
+}
+
 /// Task router with keyword analysis
 pub struct TaskRouter {
     /// Optional Ollama URL for Llama-based classification
     ollama_url: Option<String>,
+    /// Classified routes for repeated prompts
+    cache: RouteCache,
+    /// Recent routing decisions, oldest first
+    decisions: Mutex<VecDeque<RouteDecision>>,
+    /// Where decisions are appended (JSON lines) so they survive restarts
+    decisions_path: Option<PathBuf>,
+    /// Minimum model for code-heavy prompts
+    code_floor: CodeFloor,
+    /// Run requests on the routed model instead of the CLI default
+    select_model: bool,
 }
 
 impl TaskRouter {
     pub fn new(ollama_url: Option<String>) -> Self {
-        Self { ollama_url }
+        Self::with_cache(ollama_url, DEFAULT_ROUTE_CACHE_CAPACITY, DEFAULT_ROUTE_CACHE_TTL)
+    }
+
+    /// Create with a custom route cache size and TTL (capacity 0 disables it)
+    pub fn with_cache(ollama_url: Option<String>, capacity: usize, ttl: Duration) -> Self {
+        Self {
+            ollama_url,
+            cache: RouteCache::new(capacity, ttl),
+            decisions: Mutex::new(VecDeque::new()),
+            decisions_path: None,
+            code_floor: CodeFloor::default(),
+            select_model: false,
+        }
+    }
+
+    /// Keep routing decisions in `path`, loading the recent ones saved there
+    ///
+    /// The file is compacted to the retention window on load.
+    pub fn with_decisions_file(mut self, path: &Path) -> Self {
+        let cutoff = chrono::Utc::now().timestamp() - ROUTE_DECISION_RETENTION_SECS;
+        let loaded: Vec<RouteDecision> = match std::fs::read_to_string(path) {
+            Ok(data) => data
+                .lines()
+                .filter_map(|line| serde_json::from_str::<RouteDecision>(line).ok())
+                .filter(|d| d.at >= cutoff)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                warn!("Failed to read routing decisions {:?}: {}", path, e);
+                Vec::new()
+            }
+        };
+        let skip = loaded.len().saturating_sub(MAX_ROUTE_DECISIONS);
+        let decisions: VecDeque<_> = loaded.into_iter().skip(skip).collect();
+        if let Err(e) = Self::write_decisions(path, &decisions) {
+            warn!("Failed to compact routing decisions {:?}: {}", path, e);
+        }
+        self.decisions = Mutex::new(decisions);
+        self.decisions_path = Some(path.to_path_buf());
+        self
+    }
+
+    fn write_decisions(path: &Path, decisions: &VecDeque<RouteDecision>) -> std::io::Result<()> {
+        let mut data = String::new();
+        for decision in decisions {
+            data.push_str(&serde_json::to_string(decision)?);
+            data.push('\n');
+        }
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Run requests on the routed model (`selects_model`)
+    pub fn with_model_selection(mut self, enabled: bool) -> Self {
+        self.select_model = enabled;
+        self
+    }
+
+    /// `CLAUDEBOT_ROUTE_MODEL` (default off: the CLI's default model answers)
+    pub fn model_selection_from_env() -> bool {
+        std::env::var("CLAUDEBOT_ROUTE_MODEL").is_ok_and(|v| v.trim() == "true" || v.trim() == "1")
+    }
+
+    /// Whether the routed model should answer, rather than the CLI default
+    pub fn selects_model(&self) -> bool {
+        self.select_model
+    }
+
+    /// Use custom code-floor heuristics
+    pub fn with_code_floor(mut self, code_floor: CodeFloor) -> Self {
+        self.code_floor = code_floor;
+        self
+    }
+
+    pub fn code_floor(&self) -> &CodeFloor {
+        &self.code_floor
+    }
+
+    /// Raise a Haiku route to Sonnet when the prompt is code-heavy
+    fn apply_code_floor(&self, message: &str, mut result: RouteResult) -> RouteResult {
+        if result.model != ModelHint::Haiku {
+            return result;
+        }
+        if let Some(signal) = self.code_floor.signal(message) {
+            debug!("Code floor: {} routed to haiku, using sonnet", signal);
+            result.model = ModelHint::Sonnet;
+            result.complexity = ModelHint::Sonnet.into();
+            result.reasoning = format!("{}; raised to sonnet by code floor ({})", result.reasoning, signal);
+            result.code_floor = Some(signal);
+        }
+        result
+    }
+
+    /// Record a routed request for `route_distribution`
+    pub fn record_decision(&self, route: &RouteResult, cost_usd: Option<f64>) {
+        self.record_decision_at(chrono::Utc::now().timestamp(), route, cost_usd);
+    }
+
+    fn record_decision_at(&self, at: i64, route: &RouteResult, cost_usd: Option<f64>) {
+        let Ok(mut decisions) = self.decisions.lock() else {
+            return;
+        };
+        while decisions
+            .front()
+            .is_some_and(|d| d.at < at - ROUTE_DECISION_RETENTION_SECS)
+            || decisions.len() >= MAX_ROUTE_DECISIONS
+        {
+            decisions.pop_front();
+        }
+        let decision = RouteDecision {
+            at,
+            target: route.target,
+            model: route.model,
+            cost_usd,
+        };
+        decisions.push_back(decision);
+        drop(decisions);
+
+        if let Some(path) = &self.decisions_path {
+            let appended = serde_json::to_string(&decision).map_err(std::io::Error::from).and_then(|line| {
+                let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+                writeln!(file, "{}", line)
+            });
+            if let Err(e) = appended {
+                debug!("Failed to save routing decision: {}", e);
+            }
+        }
+    }
+
+    /// Distribution of routing decisions made at or after `since` (unix seconds)
+    ///
+    /// Decisions are kept for a week; without a decisions file they don't
+    /// survive a restart.
+    pub fn route_distribution(&self, since: i64) -> RouteDistribution {
+        let Ok(decisions) = self.decisions.lock() else {
+            return RouteDistribution::default();
+        };
+        RouteDistribution::from_decisions(decisions.iter().filter(|d| d.at >= since))
+    }
+
+    /// Route cache hit/miss counters
+    pub fn cache_stats(&self) -> RouteCacheStats {
+        self.cache.stats()
+    }
+
+    /// Drop all cached routes
+    pub fn clear_cache(&self) {
+        self.cache.clear();
     }
 
     /// Route a message to appropriate target and model
@@ -145,8 +745,10 @@ impl TaskRouter {
             return RouteResult {
                 target: Target::Circle,
                 model: ModelHint::Opus,
+                complexity: QueryComplexity::Complex,
                 reasoning: "Development Circle requested".to_string(),
                 confidence: 1.0,
+                code_floor: None,
             };
         }
 
@@ -161,7 +763,7 @@ impl TaskRouter {
             .filter(|kw| msg_lower.contains(*kw))
             .count();
 
-        let model = self.determine_model(&msg_lower);
+        let (model, model_reason) = self.determine_model(&msg_lower);
 
         // Route based on scores
         if has_code || backend_score > 0 || frontend_score > 0 {
@@ -173,21 +775,71 @@ impl TaskRouter {
                 (Target::Codebase, "Mixed code task".to_string())
             };
 
-            return RouteResult {
+            let result = RouteResult {
                 target,
                 model,
-                reasoning,
+                complexity: model.into(),
+                reasoning: format!("{}; {}", reasoning, model_reason),
                 confidence: 0.8,
+                code_floor: None,
             };
+            return self.apply_code_floor(message, result);
         }
 
         // 4. Default to API
-        RouteResult {
+        let result = RouteResult {
             target: Target::Api,
             model,
-            reasoning: "General question".to_string(),
+            complexity: model.into(),
+            reasoning: format!("General question; {}", model_reason),
             confidence: 0.6,
+            code_floor: None,
+        };
+        self.apply_code_floor(message, result)
+    }
+
+    /// Route using `LlamaWorker` complexity classification for model selection
+    ///
+    /// Target comes from keyword routing; the model tier comes from the
+    /// worker's complexity classifier. Explicit @targets and /circle keep
+    /// their fixed model. Results are cached per normalized prompt.
+    pub async fn route_with_worker(&self, message: &str, worker: &LlamaWorker) -> RouteResult {
+        let key = RouteCache::key(message);
+        if let Some(cached) = self.cache.get(key) {
+            debug!("Route cache hit");
+            return cached;
         }
+        let result = self.route_with_worker_uncached(message, worker).await;
+        self.cache.insert(key, &result);
+        result
+    }
+
+    /// Like `route_with_worker`, but always re-classifies
+    ///
+    /// For callers whose routing depends on state outside the prompt
+    /// (e.g. remaining budget), where a cached route could be stale.
+    pub async fn route_with_worker_uncached(&self, message: &str, worker: &LlamaWorker) -> RouteResult {
+        let keyword_result = self.route(message);
+        if keyword_result.confidence >= 1.0 {
+            return keyword_result;
+        }
+
+        let (complexity, signal) = worker.classify_complexity_explained(message).await;
+
+        let result = RouteResult {
+            target: keyword_result.target,
+            model: complexity.into(),
+            complexity,
+            reasoning: format!(
+                "{}; worker classified {} ({})",
+                keyword_result.reasoning,
+                complexity.as_str(),
+                signal
+            ),
+            confidence: keyword_result.confidence,
+            code_floor: None,
+        };
+        self.apply_code_floor(message, result)
     }
 
     /// Route with Llama classification (async, uses Ollama)
@@ -200,16 +852,26 @@ impl TaskRouter {
             return keyword_result;
         }
 
+        let key = RouteCache::key(message);
+        if let Some(cached) = self.cache.get(key) {
+            debug!("Route cache hit");
+            return cached;
+        }
+
         // Try Llama classification
         match self.classify_with_llama(message).await {
             Ok(model) => {
                 debug!("Llama classified as {:?}", model);
-                RouteResult {
+                let result = self.apply_code_floor(message, RouteResult {
                     target: keyword_result.target,
                     model,
+                    complexity: model.into(),
                     reasoning: format!("{} (Llama)", keyword_result.reasoning),
                     confidence: 0.95,
-                }
+                    code_floor: None,
+                });
+                self.cache.insert(key, &result);
+                result
             }
             Err(e) => {
                 debug!("Llama classification failed: {}, using keyword routing", e);
@@ -283,19 +945,21 @@ Classification:"#,
         Some(RouteResult {
             target,
             model,
+            complexity: model.into(),
             reasoning: format!("Explicit @{}", target_str),
             confidence: 1.0,
+            code_floor: None,
         })
     }
 
-    /// Determine model from keywords
-    fn determine_model(&self, msg_lower: &str) -> ModelHint {
-        if OPUS_KEYWORDS.iter().any(|kw| msg_lower.contains(kw)) {
-            ModelHint::Opus
-        } else if HAIKU_KEYWORDS.iter().any(|kw| msg_lower.contains(kw)) {
-            ModelHint::Haiku
+    /// Determine model from keywords, with the signal that triggered it
+    fn determine_model(&self, msg_lower: &str) -> (ModelHint, String) {
+        if let Some(kw) = OPUS_KEYWORDS.iter().find(|kw| msg_lower.contains(*kw)) {
+            (ModelHint::Opus, format!("opus keyword '{}'", kw))
+        } else if let Some(kw) = HAIKU_KEYWORDS.iter().find(|kw| msg_lower.contains(*kw)) {
+            (ModelHint::Haiku, format!("haiku keyword '{}'", kw))
         } else {
-            ModelHint::Sonnet
+            (ModelHint::Sonnet, "no model keywords (default sonnet)".to_string())
         }
     }
 }
@@ -351,6 +1015,29 @@ mod tests {
 
         let result = router.route("Quick format check");
         assert_eq!(result.model, ModelHint::Haiku);
+
+        assert_eq!(ModelHint::parse(" Opus "), Some(ModelHint::Opus));
+        assert_eq!(ModelHint::parse("claude-3-opus"), None);
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let config = FallbackConfig::default();
+        assert!(config.triggers_on("API Error: 529 {\"type\":\"overloaded_error\"}"));
+        assert!(config.triggers_on("Rate limit reached"));
+        assert!(!config.triggers_on("Invalid API key"));
+        assert!(config.triggers_on("HTTP 429 Too Many Requests"));
+        assert!(config.triggers_on("status=429"));
+        assert!(!config.triggers_on("Parse error in request req_14290"));
+        assert!(!config.triggers_on("took 4290ms"));
+        assert_eq!(config.next(ModelHint::Opus), Some(ModelHint::Sonnet));
+        assert_eq!(config.next(ModelHint::Haiku), None);
+        assert_eq!(config.next_after(Some(ModelHint::Sonnet)), Some(ModelHint::Haiku));
+        assert_eq!(config.next_after(None), Some(ModelHint::Sonnet));
+
+        let short = FallbackConfig { chain: vec![ModelHint::Sonnet, ModelHint::Haiku], ..config };
+        assert_eq!(short.next(ModelHint::Opus), None);
+        assert_eq!(short.next(ModelHint::Sonnet), Some(ModelHint::Haiku));
     }
 
     #[test]
@@ -360,4 +1047,122 @@ mod tests {
         let result = router.route("What is the weather?");
         assert_eq!(result.target, Target::Api);
     }
+
+    #[test]
+    fn test_route_reasoning() {
+        let router = TaskRouter::new(None);
+
+        let result = router.route("Do a thorough review of the Rust handler");
+        assert_eq!(result.complexity, QueryComplexity::Complex);
+        assert!(result.reasoning.contains("Backend keywords"));
+        assert!(result.reasoning.contains("'thorough'"));
+
+        let result = router.route("Hello there");
+        assert_eq!(result.complexity, QueryComplexity::Moderate);
+        assert!(result.reasoning.contains("default sonnet"));
+    }
+
+    #[test]
+    fn test_route_cache() {
+        let router = TaskRouter::with_cache(None, 2, Duration::from_secs(60));
+        let route = router.route("Fix the Rust handler");
+
+        let key = RouteCache::key("  fix the   RUST handler ");
+        assert_eq!(key, RouteCache::key("Fix the Rust handler"));
+        assert!(router.cache.get(key).is_none());
+        router.cache.insert(key, &route);
+        assert_eq!(router.cache.get(key).unwrap().target, Target::Backend);
+
+        // Inserting past capacity evicts the least recently used entry
+        router.cache.insert(RouteCache::key("a"), &route);
+        router.cache.get(key);
+        router.cache.insert(RouteCache::key("b"), &route);
+        assert!(router.cache.get(key).is_some());
+        assert!(router.cache.get(RouteCache::key("a")).is_none());
+
+        let stats = router.cache_stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (3, 2, 2));
+        assert!((stats.hit_rate() - 0.6).abs() < 1e-9);
+
+        let expired = TaskRouter::with_cache(None, 2, Duration::ZERO);
+        expired.cache.insert(key, &route);
+        assert!(expired.cache.get(key).is_none());
+    }
+
+    #[test]
+    fn test_route_distribution() {
+        let router = TaskRouter::new(None);
+        let now = chrono::Utc::now().timestamp();
+        let backend = router.route("@backend fix the handler");
+        let frontend = router.route("@frontend update the chart");
+
+        router.record_decision_at(now - 8 * 24 * 3600, &frontend, Some(9.0));
+        router.record_decision_at(now - 2 * 24 * 3600, &frontend, None);
+        router.record_decision_at(now - 60, &backend, Some(0.10));
+        router.record_decision_at(now, &backend, Some(0.30));
+
+        // The 8-day-old decision was pruned
+        let week = router.route_distribution(now - ROUTE_DECISION_RETENTION_SECS);
+        assert_eq!(week.total, 3);
+        assert_eq!(week.by_target[0].name, "backend");
+        assert_eq!(week.by_target[0].count, 2);
+        assert!((week.by_target[0].avg_cost_usd.unwrap() - 0.20).abs() < 1e-9);
+        assert_eq!(week.by_target[1], RouteShare { name: "frontend", count: 1, avg_cost_usd: None });
+
+        let today = router.route_distribution(now - 3600);
+        assert_eq!(today.total, 2);
+        assert_eq!(today.by_model.len(), 1);
+        assert!(router.route_distribution(now + 1).by_target.is_empty());
+    }
+
+    #[test]
+    fn test_route_decisions_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("routes.jsonl");
+        let now = chrono::Utc::now().timestamp();
+
+        let router = TaskRouter::new(None).with_decisions_file(&path);
+        let backend = router.route("@backend fix the handler");
+        router.record_decision_at(now - 8 * 24 * 3600, &backend, Some(9.0));
+        router.record_decision_at(now, &backend, Some(0.25));
+
+        // Reloaded without the expired decision, and compacted on disk
+        let reopened = TaskRouter::new(None).with_decisions_file(&path);
+        let week = reopened.route_distribution(now - ROUTE_DECISION_RETENTION_SECS);
+        assert_eq!(week.total, 1);
+        assert_eq!(week.by_target[0].avg_cost_usd, Some(0.25));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        assert!(!reopened.selects_model());
+    }
+
+    #[test]
+    fn test_code_floor() {
+        let router = TaskRouter::new(None);
+
+        let result = router.route("quick question: why does this fail?\n```rust\nlet x: u8 = 300;\n```");
+        assert_eq!(result.model, ModelHint::Sonnet);
+        assert_eq!(result.code_floor.as_deref(), Some("code block"));
+        assert!(result.reasoning.contains("raised to sonnet by code floor"));
+
+        let result = router.route("quick: implement a retry helper");
+        assert_eq!(result.model, ModelHint::Sonnet);
+        assert_eq!(result.code_floor.as_deref(), Some("keyword 'implement'"));
+
+        // Whole words only: "different" is not "diff"
+        let result = router.route("quick: what is the different between them");
+        assert_eq!(result.model, ModelHint::Haiku);
+        assert!(result.code_floor.is_none());
+
+        // Opus is never lowered
+        let result = router.route("deep analysis of this diff");
+        assert_eq!(result.model, ModelHint::Opus);
+        assert!(result.code_floor.is_none());
+
+        let floor = CodeFloor::default();
+        assert_eq!(floor.signal("explain:\nimport os\nx = 1;\nreturn x;"), Some("3 lines of code".to_string()));
+        assert_eq!(floor.signal("--- a/src/main.rs\n+++ b/src/main.rs"), Some("diff content".to_string()));
+
+        let off = TaskRouter::new(None).with_code_floor(CodeFloor { enabled: false, ..CodeFloor::default() });
+        assert_eq!(off.route("quick: implement a retry helper").model, ModelHint::Haiku);
+    }
 }
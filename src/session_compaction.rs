@@ -0,0 +1,178 @@
+//! CLI Session Compaction
+//!
+//! Chat requests resume the working directory's Claude CLI session
+//! (`.claude_session`), so every turn re-reads everything the session has
+//! accumulated. The approximate session size is tracked as the sum of its
+//! exchanges' tokens in `.claude_session_size`. Once it crosses the
+//! threshold, the session's exchanges are summarized with Llama, the session
+//! is dropped, and the next request starts a fresh one with the summary
+//! prepended to its prompt.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Session id the CLI resumes (written by the chat path)
+const SESSION_FILE: &str = ".claude_session";
+
+/// Tracked size of the current session
+const SIZE_FILE: &str = ".claude_session_size";
+
+/// Summary waiting to seed the next fresh session
+const SEED_FILE: &str = ".claude_session_summary";
+
+/// When sessions are compacted
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionCompactionConfig {
+    /// Compact once a session reaches this many tokens; 0 disables
+    pub threshold_tokens: usize,
+    /// Target size of the summary relative to the exchanges it replaces
+    pub target_ratio: f32,
+}
+
+impl Default for SessionCompactionConfig {
+    fn default() -> Self {
+        Self {
+            threshold_tokens: 100_000,
+            target_ratio: 0.2,
+        }
+    }
+}
+
+impl SessionCompactionConfig {
+    /// `CLAUDEBOT_SESSION_COMPACT_TOKENS`, `CLAUDEBOT_SESSION_COMPACT_RATIO`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(tokens) = std::env::var("CLAUDEBOT_SESSION_COMPACT_TOKENS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+        {
+            config.threshold_tokens = tokens;
+        }
+        if let Some(ratio) = std::env::var("CLAUDEBOT_SESSION_COMPACT_RATIO")
+            .ok()
+            .and_then(|v| v.trim().parse::<f32>().ok())
+            .filter(|r| *r > 0.0 && *r < 1.0)
+        {
+            config.target_ratio = ratio;
+        }
+        config
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.threshold_tokens > 0
+    }
+
+    /// Whether a session this size should be compacted
+    pub fn needs_compaction(&self, size: &SessionSize) -> bool {
+        self.enabled() && size.tokens >= self.threshold_tokens
+    }
+}
+
+/// Approximate size of the current session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionSize {
+    pub tokens: usize,
+    pub exchanges: usize,
+    /// When the session started (Unix millis, as conversation timestamps)
+    pub started_at: i64,
+}
+
+impl SessionSize {
+    fn fresh() -> Self {
+        Self {
+            tokens: 0,
+            exchanges: 0,
+            started_at: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+fn path(working_dir: &Path, file: &str) -> PathBuf {
+    working_dir.join(file)
+}
+
+/// The tracked size, or a fresh session if nothing is tracked yet
+pub fn load_size(working_dir: &Path) -> SessionSize {
+    std::fs::read_to_string(path(working_dir, SIZE_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_else(SessionSize::fresh)
+}
+
+fn save_size(working_dir: &Path, size: &SessionSize) -> Result<()> {
+    std::fs::write(path(working_dir, SIZE_FILE), serde_json::to_string(size)?)?;
+    Ok(())
+}
+
+/// Add an exchange to the session's size, returning the new total
+pub fn record_exchange(working_dir: &Path, tokens: usize) -> Result<SessionSize> {
+    let mut size = load_size(working_dir);
+    size.tokens += tokens;
+    size.exchanges += 1;
+    save_size(working_dir, &size)?;
+    Ok(size)
+}
+
+/// Drop the session and leave `summary` to seed the next one
+pub fn start_fresh(working_dir: &Path, summary: &str) -> Result<()> {
+    std::fs::write(path(working_dir, SEED_FILE), summary)?;
+    match std::fs::remove_file(path(working_dir, SESSION_FILE)) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    save_size(working_dir, &SessionSize::fresh())
+}
+
+/// Summary left by a compaction, if the next session hasn't started yet
+pub fn pending_seed(working_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(path(working_dir, SEED_FILE))
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+}
+
+/// Forget the seed once a fresh session has started with it
+pub fn clear_seed(working_dir: &Path) {
+    let _ = std::fs::remove_file(path(working_dir, SEED_FILE));
+}
+
+/// Prompt for the first request of a compacted session
+pub fn seeded_prompt(summary: &str, prompt: &str) -> String {
+    format!(
+        "[Summary of our earlier session, which was compacted to save context:]\n{}\n\n{}",
+        summary.trim(),
+        prompt
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_and_compact_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SessionCompactionConfig { threshold_tokens: 1000, ..Default::default() };
+        std::fs::write(dir.path().join(SESSION_FILE), "session-1").unwrap();
+
+        let size = record_exchange(dir.path(), 600).unwrap();
+        assert!(!config.needs_compaction(&size));
+        let size = record_exchange(dir.path(), 500).unwrap();
+        assert_eq!((size.tokens, size.exchanges), (1100, 2));
+        assert!(config.needs_compaction(&size));
+        assert!(!SessionCompactionConfig { threshold_tokens: 0, ..config.clone() }.needs_compaction(&size));
+
+        start_fresh(dir.path(), "User is migrating the bot to axum 0.7.").unwrap();
+        assert!(!dir.path().join(SESSION_FILE).exists());
+        let fresh = load_size(dir.path());
+        assert_eq!(fresh.tokens, 0);
+        assert!(fresh.started_at >= size.started_at);
+
+        let seed = pending_seed(dir.path()).unwrap();
+        let prompt = seeded_prompt(&seed, "What's left?");
+        assert!(prompt.contains("migrating the bot") && prompt.ends_with("What's left?"));
+        clear_seed(dir.path());
+        assert!(pending_seed(dir.path()).is_none());
+    }
+}
@@ -1,11 +1,16 @@
 //! Skill Loader
 //!
-//! Loads skills from various sources: local files, URLs, and skill hubs.
+//! Loads skills from local files, or from HTTPS URLs with a verified
+//! checksum and signature.
 
+use super::provenance::{self, Provenance, TrustedKeys};
 use super::types::*;
 use anyhow::{Context, Result};
 use std::path::{Path, PathBuf};
-use tracing::{debug, info, warn};
+use tracing::warn;
+
+/// Largest skill file or signature `load_verified` will download
+const MAX_SKILL_BYTES: usize = 1024 * 1024;
 
 /// Skill loader for importing from various sources
 pub struct SkillLoader {
@@ -29,16 +34,14 @@ impl SkillLoader {
         }
     }
 
-    /// Load skill from a path or URL
+    /// Load skill from a local path
+    ///
+    /// Remote skills are only accepted through `load_verified`.
     pub async fn load(&self, source: &str) -> Result<SkillDefinition> {
-        if source.starts_with("http://") || source.starts_with("https://") {
-            self.load_from_url(source).await
-        } else if source.starts_with("hub:") {
-            let skill_name = source.strip_prefix("hub:").unwrap();
-            self.load_from_hub(skill_name).await
-        } else {
-            self.load_from_file(Path::new(source)).await
+        if source.starts_with("http://") || source.starts_with("https://") || source.starts_with("hub:") {
+            anyhow::bail!("Remote skills need a SHA256 and a trusted signature; use load_verified");
         }
+        self.load_from_file(Path::new(source)).await
     }
 
     /// Load from local file
@@ -69,73 +72,58 @@ impl SkillLoader {
         }
     }
 
-    /// Load from URL
-    pub async fn load_from_url(&self, url: &str) -> Result<SkillDefinition> {
-        let response = self
+    /// Fetch a TOML skill from an HTTPS URL, verifying its checksum and the
+    /// signature from `<url>.sig` before parsing
+    pub async fn load_verified(
+        &self,
+        url: &str,
+        sha256: &str,
+        trusted: &TrustedKeys,
+    ) -> Result<(SkillDefinition, Provenance)> {
+        if !url.starts_with("https://") || url.contains(char::is_whitespace) {
+            anyhow::bail!("Skill URL must be https://");
+        }
+        if !trusted.is_configured() {
+            anyhow::bail!("No SKILLS_TRUSTED_KEYS configured, so no skill signature can be checked");
+        }
+        let content = self.fetch_bytes(url).await?;
+        let sig_url = format!("{}.sig", url);
+        let signature = self
+            .fetch_bytes(&sig_url)
+            .await
+            .with_context(|| format!("Failed to fetch signature {}", sig_url))?;
+        let signature = String::from_utf8_lossy(&signature);
+
+        let provenance = provenance::verify(&content, sha256, Some(&signature), trusted)?;
+        let text = std::str::from_utf8(&content).context("Skill file is not UTF-8")?;
+        let definition: SkillDefinition = toml::from_str(text).context("Failed to parse TOML")?;
+        definition.validate()?;
+        Ok((definition, provenance))
+    }
+
+    /// Download `url`, refusing bodies over `MAX_SKILL_BYTES`
+    async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let mut response = self
             .client
             .get(url)
             .timeout(std::time::Duration::from_secs(30))
             .send()
             .await
             .context("Failed to fetch skill from URL")?;
-
         if !response.status().is_success() {
             anyhow::bail!("HTTP error: {}", response.status());
         }
-
-        let content = response.text().await?;
-
-        // Determine format from URL or content
-        if url.ends_with(".json") || content.trim().starts_with('{') {
-            serde_json::from_str(&content).context("Failed to parse JSON")
-        } else {
-            toml::from_str(&content).context("Failed to parse TOML")
+        if response.content_length().is_some_and(|len| len > MAX_SKILL_BYTES as u64) {
+            anyhow::bail!("Skill download exceeds {} bytes", MAX_SKILL_BYTES);
         }
-    }
-
-    /// Load from skill hub (GitHub-based)
-    pub async fn load_from_hub(&self, skill_name: &str) -> Result<SkillDefinition> {
-        // Hub format: skill_name or author/skill_name
-        let (author, name) = if skill_name.contains('/') {
-            let parts: Vec<&str> = skill_name.splitn(2, '/').collect();
-            (parts[0], parts[1])
-        } else {
-            ("claudebot-skills", skill_name)
-        };
-
-        // Try different hub URLs in order
-        let urls = [
-            // GitHub raw content
-            format!(
-                "https://raw.githubusercontent.com/{}/{}/main/skill.toml",
-                author, name
-            ),
-            // Alternative path
-            format!(
-                "https://raw.githubusercontent.com/{}/{}/main/{}.toml",
-                author, name, name
-            ),
-            // Skills repository
-            format!(
-                "https://raw.githubusercontent.com/claudebot/skills/main/{}/{}.toml",
-                author, name
-            ),
-        ];
-
-        for url in &urls {
-            match self.load_from_url(url).await {
-                Ok(skill) => {
-                    info!("Loaded skill '{}' from hub", skill_name);
-                    return Ok(skill);
-                }
-                Err(e) => {
-                    debug!("Failed to load from {}: {}", url, e);
-                    continue;
-                }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > MAX_SKILL_BYTES {
+                anyhow::bail!("Skill download exceeds {} bytes", MAX_SKILL_BYTES);
             }
+            body.extend_from_slice(&chunk);
         }
-
-        anyhow::bail!("Skill '{}' not found in hub", skill_name)
+        Ok(body)
     }
 
     /// Search hub for skills
@@ -234,16 +222,39 @@ command = "echo hello"
         assert_eq!(skill.skill.version, "1.0.0");
     }
 
-    #[test]
-    fn test_hub_name_parsing() {
-        // Test parsing of hub skill names
-        let full = "author/skill_name";
-        let parts: Vec<&str> = full.splitn(2, '/').collect();
-        assert_eq!(parts[0], "author");
-        assert_eq!(parts[1], "skill_name");
-
-        let simple = "skill_name";
-        let has_slash = simple.contains('/');
-        assert!(!has_slash);
+    #[tokio::test]
+    async fn test_load_refuses_remote_sources() {
+        let loader = SkillLoader::new();
+        for source in ["https://example.com/skill.toml", "http://example.com/skill.toml", "hub:weather"] {
+            let err = loader.load(source).await.unwrap_err();
+            assert!(err.to_string().contains("load_verified"), "{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_caps_download() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // No Content-Length, so the cap has to hold while streaming
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let _ = socket.write_all(b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n").await;
+            let chunk = vec![b'a'; 64 * 1024];
+            for _ in 0..(MAX_SKILL_BYTES / chunk.len() + 2) {
+                if socket.write_all(&chunk).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let err = SkillLoader::new()
+            .fetch_bytes(&format!("http://{}/skill.toml", addr))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds"), "{}", err);
     }
 }
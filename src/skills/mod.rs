@@ -42,15 +42,23 @@
 //! - Resource limits (timeout, memory, output size)
 //! - Environment sanitization
 //! - Pattern-based blocking for dangerous operations
+//! - Operator policy from `skills_sandbox.toml`/env, with an audit log
+//!
+//! Skills installed from a URL must be served over https, and installs are
+//! refused unless `SKILLS_TRUSTED_KEYS` holds at least one key. The file must
+//! match an operator-supplied SHA256 and `<url>.sig` must carry a valid
+//! Ed25519 signature from one of those keys.
 
 pub mod registry;
 pub mod generator;
 pub mod loader;
 pub mod types;
 pub mod sandbox;
+pub mod provenance;
 
 pub use registry::{SkillRegistry, InstalledSkill, SkillSource, SkillResult, SkillStats};
 pub use generator::{SkillGenerator, GeneratedSkill};
 pub use loader::SkillLoader;
 pub use types::{SkillDefinition, SkillParameter, ExecutionType, SkillMetadata};
-pub use sandbox::{SkillSandbox, SandboxConfig, SandboxResult, ValidationResult};
+pub use provenance::{Provenance, TrustedKeys};
+pub use sandbox::{SkillSandbox, SandboxAuditEntry, SandboxConfig, SandboxResult, ValidationResult};
@@ -0,0 +1,122 @@
+//! Skill Provenance
+//!
+//! Checks a downloaded skill definition before it is installed:
+//! - SHA256 of the exact bytes must match the checksum the operator supplied
+//! - A detached signature (`<url>.sig`, hex) over the same bytes must verify
+//!   against one of `SKILLS_TRUSTED_KEYS` (comma-separated hex Ed25519 public
+//!   keys); without trusted keys nothing can be installed
+
+use anyhow::Result;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use sha2::{Digest, Sha256};
+
+/// Ed25519 public keys whose signatures are accepted
+#[derive(Debug, Clone, Default)]
+pub struct TrustedKeys {
+    keys: Vec<Vec<u8>>,
+}
+
+impl TrustedKeys {
+    /// Parse hex-encoded 32-byte keys; any malformed key is an error
+    pub fn parse(raw: &str) -> Result<Self> {
+        let mut keys = Vec::new();
+        for key in raw.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            let bytes = hex::decode(key).map_err(|_| anyhow::anyhow!("Trusted key is not hex: {}", key))?;
+            if bytes.len() != 32 {
+                anyhow::bail!("Trusted key must be 32 bytes, got {}: {}", bytes.len(), key);
+            }
+            keys.push(bytes);
+        }
+        Ok(Self { keys })
+    }
+
+    /// `SKILLS_TRUSTED_KEYS` (unset means no key is trusted)
+    pub fn from_env() -> Result<Self> {
+        match std::env::var("SKILLS_TRUSTED_KEYS") {
+            Ok(raw) => Self::parse(&raw),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    /// Whether any key is configured, i.e. installs are possible at all
+    pub fn is_configured(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// Hex of the key that verifies `signature` over `content`, if any
+    pub fn verify(&self, content: &[u8], signature: &[u8]) -> Option<String> {
+        self.keys
+            .iter()
+            .find(|key| UnparsedPublicKey::new(&ED25519, key).verify(content, signature).is_ok())
+            .map(hex::encode)
+    }
+}
+
+/// What was verified about an installed skill
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    pub sha256: String,
+    /// Trusted key (hex) that signed it
+    pub signed_by: String,
+}
+
+/// Lowercase hex SHA256 of `content`
+pub fn sha256_hex(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// Verify the checksum and the detached signature (hex) against the trusted keys
+pub fn verify(content: &[u8], expected_sha256: &str, signature: Option<&str>, trusted: &TrustedKeys) -> Result<Provenance> {
+    if !trusted.is_configured() {
+        anyhow::bail!("No SKILLS_TRUSTED_KEYS configured, so no skill signature can be checked");
+    }
+    let expected = expected_sha256.trim().to_lowercase();
+    if expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("Checksum must be a 64-character hex SHA256");
+    }
+    let actual = sha256_hex(content);
+    if actual != expected {
+        anyhow::bail!("Checksum mismatch: expected {}, got {}", expected, actual);
+    }
+
+    let signature = signature
+        .and_then(|s| hex::decode(s.trim()).ok())
+        .ok_or_else(|| anyhow::anyhow!("Missing or malformed signature"))?;
+    let signed_by = trusted
+        .verify(content, &signature)
+        .ok_or_else(|| anyhow::anyhow!("Signature does not match any trusted key"))?;
+    Ok(Provenance { sha256: actual, signed_by })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+
+    #[test]
+    fn test_verify_checksum_and_signature() {
+        let content = b"[skill]\nname = \"weather\"\n";
+        let sha = sha256_hex(content);
+
+        let keypair = Ed25519KeyPair::from_seed_unchecked(&[7u8; 32]).unwrap();
+        let other = Ed25519KeyPair::from_seed_unchecked(&[8u8; 32]).unwrap();
+        let public = hex::encode(keypair.public_key().as_ref());
+        let trusted = TrustedKeys::parse(&format!(" {} ", public)).unwrap();
+        let signature = hex::encode(keypair.sign(content).as_ref());
+
+        // Without trusted keys nothing installs, not even with a matching checksum
+        let err = verify(content, &sha, Some(&signature), &TrustedKeys::default()).unwrap_err();
+        assert!(err.to_string().contains("SKILLS_TRUSTED_KEYS"));
+
+        let provenance = verify(content, &sha.to_uppercase(), Some(&signature), &trusted).unwrap();
+        assert_eq!(provenance.signed_by, public);
+        assert!(verify(content, &sha256_hex(b"other"), Some(&signature), &trusted).is_err());
+        assert!(verify(content, "abc", Some(&signature), &trusted).is_err());
+        assert!(verify(content, &sha, None, &trusted).is_err());
+        let forged = hex::encode(other.sign(content).as_ref());
+        assert!(verify(content, &sha, Some(&forged), &trusted).is_err());
+
+        assert!(TrustedKeys::parse("zz").is_err());
+        assert!(TrustedKeys::parse("abcd").is_err());
+    }
+}
@@ -4,7 +4,8 @@
 
 use super::types::*;
 use super::generator::GeneratedSkill;
-use super::sandbox::{SkillSandbox, SandboxConfig};
+use super::sandbox::{default_audit_path, SkillSandbox, SandboxConfig};
+use super::provenance::Provenance;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -76,6 +77,14 @@ pub enum SkillSource {
     Imported(PathBuf),
     /// Downloaded from hub
     Hub(String),
+    /// Downloaded from a URL after checksum and signature verification
+    Verified {
+        url: String,
+        sha256: String,
+        /// Trusted key (hex) whose signature verified
+        #[serde(default)]
+        signed_by: String,
+    },
     /// Built-in skill
     Builtin,
 }
@@ -114,6 +123,9 @@ impl SkillRegistry {
     }
 
     /// Create from default location (~/.claudebot/skills)
+    ///
+    /// Uses the operator sandbox policy (`SandboxConfig::from_env`), falling
+    /// back to the strict preset if it is invalid.
     pub fn default_location() -> Self {
         let skills_dir = dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -123,11 +135,17 @@ impl SkillRegistry {
         // Ensure directory exists
         std::fs::create_dir_all(&skills_dir).ok();
 
+        let sandbox_config = SandboxConfig::from_env().unwrap_or_else(|e| {
+            warn!("Invalid skills sandbox policy, using strict preset: {:#}", e);
+            SandboxConfig::strict()
+        });
+        let audit_log = default_audit_path().unwrap_or_else(|| skills_dir.join("sandbox_audit.jsonl"));
+
         Self {
             skills: RwLock::new(HashMap::new()),
             skills_dir,
             client: reqwest::Client::new(),
-            sandbox: SkillSandbox::default_sandbox(),
+            sandbox: SkillSandbox::new(sandbox_config).with_audit_log(audit_log),
         }
     }
 
@@ -194,9 +212,38 @@ impl SkillRegistry {
     /// Install a generated skill
     pub async fn install(&self, skill: GeneratedSkill) -> Result<String> {
         skill.definition.validate()?;
+        let installed = InstalledSkill::from_generated(skill, SkillSource::Generated);
+        self.persist(installed).await
+    }
 
-        let name = skill.definition.skill.name.clone();
-        let mut installed = InstalledSkill::from_generated(skill, SkillSource::Generated);
+    /// Install a skill downloaded with `SkillLoader::load_verified`
+    ///
+    /// Refuses to replace an installed skill of the same name.
+    pub async fn install_verified(&self, definition: SkillDefinition, url: &str, provenance: &Provenance) -> Result<String> {
+        definition.validate()?;
+        if self.get(&definition.skill.name).await.is_some() {
+            anyhow::bail!("Skill '{}' already exists", definition.skill.name);
+        }
+        let installed = InstalledSkill {
+            definition,
+            installed_at: chrono::Utc::now().timestamp(),
+            last_used: None,
+            usage_count: 0,
+            success_count: 0,
+            enabled: true,
+            source: SkillSource::Verified {
+                url: url.to_string(),
+                sha256: provenance.sha256.clone(),
+                signed_by: provenance.signed_by.clone(),
+            },
+            file_path: None,
+        };
+        self.persist(installed).await
+    }
+
+    /// Write a skill to the skills directory and register it
+    async fn persist(&self, mut installed: InstalledSkill) -> Result<String> {
+        let name = installed.definition.skill.name.clone();
 
         // Persist to disk
         let file_path = self.skills_dir.join(format!("{}.toml", name));
@@ -384,6 +431,8 @@ impl SkillRegistry {
 
         if result.success {
             Ok(result.stdout)
+        } else if result.blocked {
+            anyhow::bail!("{}", result.stderr)
         } else if result.timed_out {
             anyhow::bail!("Command timed out: {}", result.stderr)
         } else if !result.warnings.is_empty() && result.stderr.is_empty() {
@@ -412,6 +461,8 @@ impl SkillRegistry {
 
         if result.success {
             Ok(result.stdout)
+        } else if result.blocked {
+            anyhow::bail!("{}", result.stderr)
         } else if result.timed_out {
             anyhow::bail!("Script timed out: {}", result.stderr)
         } else if !result.warnings.is_empty() && result.stderr.is_empty() {
@@ -14,19 +14,41 @@
 //! 3. **Resource Limits**: Prevent DoS via resource exhaustion
 //! 4. **Timeout**: Hard limit on execution time
 //! 5. **Output Limits**: Prevent memory exhaustion from large outputs
+//!
+//! # Operator Policy
+//!
+//! `SandboxConfig::from_env()` loads `skills_sandbox.toml` (path from
+//! `SKILLS_SANDBOX_CONFIG`, default `~/.claudebot/skills_sandbox.toml`) and
+//! applies `SKILLS_SANDBOX_*` env overrides on top:
+//!
+//! ```toml
+//! timeout_secs = 20
+//! max_output_bytes = 262144
+//! max_memory_mb = 128
+//! allowed_commands = ["echo", "jq", "python3"]
+//! blocked_patterns = ["$(", "`", "curl"]
+//! ```
+//!
+//! Blocked attempts fail with "blocked by policy: <reason>" and, like every
+//! executed command, are appended to the sandbox audit log.
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::time::Duration;
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tracing::{debug, info, warn};
 
+/// Longest timeout a policy may set (1 hour)
+const MAX_TIMEOUT_SECS: u64 = 3600;
+
 /// Sandbox configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SandboxConfig {
     /// Enable sandboxing (false = execute directly)
     pub enabled: bool,
@@ -122,6 +144,143 @@ impl SandboxConfig {
             ..Default::default()
         }
     }
+
+    /// Load the operator policy from `skills_sandbox.toml` and env, validated
+    ///
+    /// Uses `SKILLS_SANDBOX_CONFIG` if set (the file must exist), otherwise
+    /// `~/.claudebot/skills_sandbox.toml` if present. Fields the file omits
+    /// keep their defaults.
+    pub fn from_env() -> Result<Self> {
+        let path = match std::env::var("SKILLS_SANDBOX_CONFIG") {
+            Ok(path) => Some(PathBuf::from(path)),
+            Err(_) => default_policy_path().filter(|p| p.exists()),
+        };
+        let config = match path {
+            Some(ref path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+        let config = config.with_env_overrides(|key| std::env::var(key).ok())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parse a policy file (not validated)
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read sandbox policy {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse sandbox policy {}", path.display()))
+    }
+
+    /// Apply `SKILLS_SANDBOX_*` overrides
+    ///
+    /// Command lists are comma-separated; `SKILLS_SANDBOX_MAX_MEMORY_MB=0`
+    /// removes the memory limit.
+    pub fn with_env_overrides(mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let number = |key: &str| -> Result<Option<u64>> {
+            lookup(key)
+                .map(|v| v.trim().parse::<u64>().with_context(|| format!("{} must be a number, got '{}'", key, v)))
+                .transpose()
+        };
+        let list = |key: &str| -> Option<HashSet<String>> {
+            lookup(key).map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+        };
+
+        if let Some(secs) = number("SKILLS_SANDBOX_TIMEOUT_SECS")? {
+            self.timeout_secs = secs;
+        }
+        if let Some(bytes) = number("SKILLS_SANDBOX_MAX_OUTPUT_BYTES")? {
+            self.max_output_bytes = bytes as usize;
+        }
+        if let Some(mb) = number("SKILLS_SANDBOX_MAX_MEMORY_MB")? {
+            self.max_memory_mb = (mb > 0).then_some(mb);
+        }
+        if let Some(allowed) = list("SKILLS_SANDBOX_ALLOWED_COMMANDS") {
+            self.allowed_commands = allowed;
+        }
+        if let Some(blocked) = list("SKILLS_SANDBOX_BLOCKED_COMMANDS") {
+            self.blocked_commands = blocked;
+        }
+        Ok(self)
+    }
+
+    /// Check the policy is coherent
+    pub fn validate(&self) -> Result<()> {
+        if self.timeout_secs == 0 || self.timeout_secs > MAX_TIMEOUT_SECS {
+            anyhow::bail!("timeout_secs must be between 1 and {}, got {}", MAX_TIMEOUT_SECS, self.timeout_secs);
+        }
+        if self.max_output_bytes == 0 {
+            anyhow::bail!("max_output_bytes must be greater than 0");
+        }
+        if self.max_memory_mb == Some(0) {
+            anyhow::bail!("max_memory_mb must be greater than 0 (omit it for no limit)");
+        }
+        for command in self.allowed_commands.iter().chain(&self.blocked_commands) {
+            if command.is_empty() || command.contains(|c: char| c.is_whitespace() || c == '/') {
+                anyhow::bail!("Invalid command name '{}': use a bare command like 'grep'", command);
+            }
+        }
+        let mut both: Vec<&String> = self.allowed_commands.intersection(&self.blocked_commands).collect();
+        if !both.is_empty() {
+            both.sort();
+            anyhow::bail!("Commands both allowed and blocked: {:?}", both);
+        }
+        if self.blocked_patterns.iter().any(|p| p.is_empty()) {
+            anyhow::bail!("blocked_patterns must not contain empty strings");
+        }
+        Ok(())
+    }
+
+    /// Format the active policy for display
+    pub fn format(&self) -> String {
+        let sorted = |set: &HashSet<String>| {
+            let mut items: Vec<&str> = set.iter().map(String::as_str).collect();
+            items.sort_unstable();
+            items.join(", ")
+        };
+
+        let mut s = format!(
+            "Skill Sandbox Policy\n\n\
+            Sandboxing: {}\n\
+            Timeout: {}s\n\
+            Max output: {} KB\n\
+            Memory limit: {}\n\
+            Network: {}\n\
+            File writes: {}\n\
+            Approval required: {}\n\n",
+            if self.enabled { "on" } else { "OFF" },
+            self.timeout_secs,
+            self.max_output_bytes / 1024,
+            self.max_memory_mb.map(|mb| format!("{} MB", mb)).unwrap_or_else(|| "none".to_string()),
+            if self.allow_network { "allowed" } else { "blocked" },
+            if self.allow_file_write { "allowed" } else { "blocked" },
+            if self.require_approval { "yes" } else { "no" },
+        );
+
+        if self.allowed_commands.is_empty() {
+            s.push_str(&format!("Mode: blocklist\nBlocked commands: {}\n", sorted(&self.blocked_commands)));
+        } else {
+            s.push_str(&format!("Mode: allowlist\nAllowed commands: {}\n", sorted(&self.allowed_commands)));
+        }
+        s.push_str(&format!("Blocked patterns: {}", self.blocked_patterns.join("  ")));
+        s
+    }
+}
+
+/// Default operator policy file (`~/.claudebot/skills_sandbox.toml`)
+pub fn default_policy_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claudebot").join("skills_sandbox.toml"))
+}
+
+/// Default sandbox audit log (`~/.claudebot/skills/sandbox_audit.jsonl`)
+pub fn default_audit_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claudebot").join("skills").join("sandbox_audit.jsonl"))
 }
 
 /// Default blocked commands (dangerous operations)
@@ -231,17 +390,34 @@ pub struct SandboxResult {
     pub duration_ms: u64,
     /// Validation warnings
     pub warnings: Vec<String>,
+    /// Whether the command was refused by the sandbox policy
+    pub blocked: bool,
+}
+
+/// One sandbox decision in the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxAuditEntry {
+    /// Unix timestamp
+    pub timestamp: i64,
+    /// Command as submitted (truncated)
+    pub command: String,
+    /// Whether the command was allowed to run
+    pub allowed: bool,
+    /// Block reason, or "executed"
+    pub detail: String,
 }
 
 /// Skill sandbox executor
 pub struct SkillSandbox {
     config: SandboxConfig,
+    /// JSON-lines audit log of sandbox decisions (None = tracing only)
+    audit_log: Option<PathBuf>,
 }
 
 impl SkillSandbox {
     /// Create new sandbox with config
     pub fn new(config: SandboxConfig) -> Self {
-        Self { config }
+        Self { config, audit_log: None }
     }
 
     /// Create with default config
@@ -249,6 +425,75 @@ impl SkillSandbox {
         Self::new(SandboxConfig::default())
     }
 
+    /// Append sandbox decisions to a JSON-lines audit log
+    pub fn with_audit_log(mut self, path: PathBuf) -> Self {
+        self.audit_log = Some(path);
+        self
+    }
+
+    /// Active sandbox policy
+    pub fn config(&self) -> &SandboxConfig {
+        &self.config
+    }
+
+    /// Most recent audit entries, newest first
+    pub fn recent_audit(&self, limit: usize) -> Vec<SandboxAuditEntry> {
+        let Some(ref path) = self.audit_log else {
+            return Vec::new();
+        };
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect()
+    }
+
+    /// Record a sandbox decision
+    fn audit(&self, command: &str, allowed: bool, detail: &str) {
+        let entry = SandboxAuditEntry {
+            timestamp: chrono::Utc::now().timestamp(),
+            command: command.chars().take(200).collect(),
+            allowed,
+            detail: detail.to_string(),
+        };
+        info!(target: "skills::audit", allowed, "{}: {}", detail, entry.command);
+
+        let Some(ref path) = self.audit_log else { return };
+        let written = serde_json::to_string(&entry).map_err(anyhow::Error::from).and_then(|line| {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{}", line)?;
+            Ok(())
+        });
+        if let Err(e) = written {
+            warn!("Failed to write sandbox audit log {}: {}", path.display(), e);
+        }
+    }
+
+    /// Result for a command refused by policy (audited)
+    fn blocked_result(&self, command: &str, validation: ValidationResult, duration_ms: u64) -> SandboxResult {
+        let reason = format!("blocked by policy: {}", validation.blocked_reasons.join("; "));
+        warn!("Skill command {}", reason);
+        self.audit(command, false, &reason);
+        SandboxResult {
+            exit_code: None,
+            stdout: String::new(),
+            stderr: reason,
+            success: false,
+            truncated: false,
+            timed_out: false,
+            duration_ms,
+            warnings: validation.warnings,
+            blocked: true,
+        }
+    }
+
     /// Validate a command before execution
     pub fn validate(&self, command: &str) -> ValidationResult {
         let mut warnings = Vec::new();
@@ -327,17 +572,9 @@ impl SkillSandbox {
         // Validate first
         let validation = self.validate(command);
         if !validation.allowed {
-            return Ok(SandboxResult {
-                exit_code: None,
-                stdout: String::new(),
-                stderr: validation.blocked_reasons.join("; "),
-                success: false,
-                truncated: false,
-                timed_out: false,
-                duration_ms: start.elapsed().as_millis() as u64,
-                warnings: validation.warnings,
-            });
+            return Ok(self.blocked_result(command, validation, start.elapsed().as_millis() as u64));
         }
+        self.audit(command, true, "executed");
 
         if !self.config.enabled {
             // Direct execution without sandbox
@@ -373,6 +610,7 @@ impl SkillSandbox {
             timed_out: false,
             duration_ms: start.elapsed().as_millis() as u64,
             warnings,
+            blocked: false,
         })
     }
 
@@ -473,6 +711,7 @@ impl SkillSandbox {
                     timed_out: false,
                     duration_ms: start.elapsed().as_millis() as u64,
                     warnings,
+                    blocked: false,
                 })
             }
             Ok(Err(e)) => {
@@ -485,6 +724,7 @@ impl SkillSandbox {
                     timed_out: false,
                     duration_ms: start.elapsed().as_millis() as u64,
                     warnings,
+                    blocked: false,
                 })
             }
             Err(_) => {
@@ -501,6 +741,7 @@ impl SkillSandbox {
                     timed_out: true,
                     duration_ms: start.elapsed().as_millis() as u64,
                     warnings,
+                    blocked: false,
                 })
             }
         }
@@ -520,16 +761,7 @@ impl SkillSandbox {
                 // Extra validation for shell scripts
                 let validation = self.validate(script);
                 if !validation.allowed {
-                    return Ok(SandboxResult {
-                        exit_code: None,
-                        stdout: String::new(),
-                        stderr: validation.blocked_reasons.join("; "),
-                        success: false,
-                        truncated: false,
-                        timed_out: false,
-                        duration_ms: 0,
-                        warnings: validation.warnings,
-                    });
+                    return Ok(self.blocked_result(script, validation, 0));
                 }
                 "sh"
             }
@@ -658,6 +890,56 @@ mod tests {
         assert!(!result.success);
     }
 
+    #[tokio::test]
+    async fn test_blocked_command_is_audited() {
+        let path = PathBuf::from("/tmp/claudebot_test_sandbox_audit.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let sandbox = SkillSandbox::new(SandboxConfig::strict()).with_audit_log(path.clone());
+
+        let result = sandbox.execute("nmap localhost").await.unwrap();
+        assert!(result.blocked);
+        assert!(result.stderr.starts_with("blocked by policy: Command 'nmap' not in allowlist"));
+
+        let audit = sandbox.recent_audit(10);
+        assert_eq!(audit.len(), 1);
+        assert!(!audit[0].allowed);
+        assert_eq!(audit[0].command, "nmap localhost");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_policy_file_and_env_overrides() {
+        let path = PathBuf::from("/tmp/claudebot_test_skills_sandbox.toml");
+        std::fs::write(&path, "timeout_secs = 5\nallowed_commands = [\"echo\", \"jq\"]\n").unwrap();
+
+        let config = SandboxConfig::from_file(&path).unwrap();
+        assert_eq!(config.timeout_secs, 5);
+        assert_eq!(config.allowed_commands.len(), 2);
+        // Omitted fields keep their defaults
+        assert_eq!(config.max_output_bytes, SandboxConfig::default().max_output_bytes);
+        assert!(config.validate().is_ok());
+
+        let env = |key: &str| match key {
+            "SKILLS_SANDBOX_TIMEOUT_SECS" => Some("15".to_string()),
+            "SKILLS_SANDBOX_MAX_MEMORY_MB" => Some("0".to_string()),
+            "SKILLS_SANDBOX_BLOCKED_COMMANDS" => Some("jq, rm".to_string()),
+            _ => None,
+        };
+        let config = config.with_env_overrides(env).unwrap();
+        assert_eq!(config.timeout_secs, 15);
+        assert_eq!(config.max_memory_mb, None);
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("both allowed and blocked"), "{}", err);
+
+        let bad = SandboxConfig::default().with_env_overrides(|_| Some("soon".to_string()));
+        assert!(bad.is_err());
+        let zero = SandboxConfig { timeout_secs: 0, ..SandboxConfig::default() };
+        assert!(zero.validate().is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[tokio::test]
     async fn test_timeout() {
         let sandbox = SkillSandbox::new(SandboxConfig {
@@ -0,0 +1,178 @@
+//! SQLite Connection Pool
+//!
+//! The memory and conversation stores are shared by every chat, the
+//! background tasks and the dashboard. To keep one slow write from stalling
+//! everyone's reads, each database runs in WAL mode with one writer
+//! connection and a few read-only connections: readers see the last committed
+//! state while a write is in progress, and only writes queue behind each
+//! other. `busy_timeout` makes a connection wait for a lock held by another
+//! process (or a checkpoint) instead of failing straight away with
+//! "database is locked".
+
+use anyhow::Result;
+use rusqlite::Connection;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
+use tracing::warn;
+
+/// Connection settings for the SQLite stores
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqliteConfig {
+    /// How long a statement waits for a lock before failing
+    pub busy_timeout: Duration,
+    /// Read-only connections per database; 0 reads through the writer
+    pub readers: usize,
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            busy_timeout: Duration::from_millis(5000),
+            readers: 4,
+        }
+    }
+}
+
+impl SqliteConfig {
+    /// `CLAUDEBOT_SQLITE_BUSY_TIMEOUT_MS`, `CLAUDEBOT_SQLITE_READERS`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Some(ms) = std::env::var("CLAUDEBOT_SQLITE_BUSY_TIMEOUT_MS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+        {
+            config.busy_timeout = Duration::from_millis(ms);
+        }
+        if let Some(readers) = std::env::var("CLAUDEBOT_SQLITE_READERS")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+        {
+            config.readers = readers.min(32);
+        }
+        config
+    }
+}
+
+/// One writer and a few readers on the same database file
+pub struct SqlitePool {
+    writer: Mutex<Connection>,
+    readers: Vec<Mutex<Connection>>,
+    next_reader: AtomicUsize,
+}
+
+impl SqlitePool {
+    /// Open `path` in WAL mode with `config.readers` read connections
+    ///
+    /// Falls back to fewer readers (down to reading through the writer) if
+    /// WAL can't be enabled or a reader fails to open, e.g. on a read-only
+    /// or network filesystem.
+    pub fn open(path: &Path, config: &SqliteConfig) -> Result<Self> {
+        let writer = Connection::open(path)?;
+        writer.busy_timeout(config.busy_timeout)?;
+
+        let journal_mode: String = writer.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+        let wal = journal_mode.eq_ignore_ascii_case("wal");
+        if wal {
+            // Durable at checkpoints; commits no longer wait for an fsync each
+            writer.pragma_update(None, "synchronous", "NORMAL")?;
+        } else if path != Path::new(":memory:") {
+            warn!("{}: WAL unavailable (journal_mode={}), reads share the writer", path.display(), journal_mode);
+        }
+
+        let mut readers = Vec::new();
+        if wal {
+            for _ in 0..config.readers {
+                match Self::open_reader(path, config) {
+                    Ok(conn) => readers.push(Mutex::new(conn)),
+                    Err(e) => {
+                        warn!("{}: opened {} of {} read connections: {}", path.display(), readers.len(), config.readers, e);
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            writer: Mutex::new(writer),
+            readers,
+            next_reader: AtomicUsize::new(0),
+        })
+    }
+
+    fn open_reader(path: &Path, config: &SqliteConfig) -> Result<Connection> {
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(config.busy_timeout)?;
+        conn.pragma_update(None, "query_only", true)?;
+        Ok(conn)
+    }
+
+    /// The writer, for anything that modifies the database
+    ///
+    /// Writes queue on this lock; hold the guard for a whole transaction, and
+    /// read through it when a read must see the transaction's own changes.
+    pub fn writer(&self) -> MutexGuard<'_, Connection> {
+        self.writer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A read connection, sees the last committed state
+    ///
+    /// Takes the first idle reader, or waits for one if all are busy. Reads
+    /// go through the writer when there are no readers, so never call this
+    /// while holding `writer()`.
+    pub fn reader(&self) -> MutexGuard<'_, Connection> {
+        if self.readers.is_empty() {
+            return self.writer();
+        }
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        for i in 0..self.readers.len() {
+            match self.readers[(start + i) % self.readers.len()].try_lock() {
+                Ok(conn) => return conn,
+                Err(TryLockError::Poisoned(e)) => return e.into_inner(),
+                Err(TryLockError::WouldBlock) => {}
+            }
+        }
+        self.readers[start % self.readers.len()]
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Read connections in the pool (0 when reads share the writer)
+    pub fn reader_count(&self) -> usize {
+        self.readers.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_during_open_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let pool = SqlitePool::open(&dir.path().join("pool.db"), &SqliteConfig::default()).unwrap();
+        assert_eq!(pool.reader_count(), 4);
+
+        let mode: String = pool.writer().query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+        pool.writer().execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1);").unwrap();
+
+        // A write transaction is open and uncommitted; readers aren't blocked
+        let writer = pool.writer();
+        writer.execute_batch("BEGIN IMMEDIATE; INSERT INTO t VALUES (2);").unwrap();
+        let count: i64 = pool.reader().query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+        writer.execute_batch("COMMIT").unwrap();
+        drop(writer);
+
+        let count: i64 = pool.reader().query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+        assert!(pool.reader().execute("INSERT INTO t VALUES (3)", []).is_err());
+
+        // Without readers, reads go through the writer
+        let single = SqlitePool::open(&dir.path().join("single.db"), &SqliteConfig { readers: 0, ..Default::default() }).unwrap();
+        assert_eq!(single.reader_count(), 0);
+        single.reader().execute_batch("CREATE TABLE t (x INTEGER)").unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+//! Storage Reporting
+//!
+//! File sizes and row counts for the SQLite stores (memory, conversations,
+//! goals, usage) plus the in-memory vector index, so growth shows up in
+//! `/stats disk` and the dashboard before the disk fills.
+
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Row count for one table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TableRows {
+    pub table: String,
+    pub rows: i64,
+}
+
+/// Size of one SQLite database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DbStorage {
+    /// Store name (`memory`, `conversations`, ...)
+    pub name: String,
+    /// `page_count * page_size` (main file, excluding any WAL)
+    pub bytes: u64,
+    /// Free pages that `VACUUM` would reclaim
+    pub free_bytes: u64,
+    pub tables: Vec<TableRows>,
+}
+
+impl DbStorage {
+    pub fn total_rows(&self) -> i64 {
+        self.tables.iter().map(|t| t.rows).sum()
+    }
+}
+
+/// All stores plus the vector index
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageReport {
+    pub databases: Vec<DbStorage>,
+    /// Approximate heap size of the HNSW index
+    pub vector_index_bytes: u64,
+    pub total_bytes: u64,
+}
+
+impl StorageReport {
+    pub fn new(databases: Vec<DbStorage>, vector_index_bytes: u64) -> Self {
+        let total_bytes = databases.iter().map(|db| db.bytes).sum::<u64>() + vector_index_bytes;
+        Self {
+            databases,
+            vector_index_bytes,
+            total_bytes,
+        }
+    }
+}
+
+/// Size and per-table row counts of an open SQLite database
+///
+/// FTS5 shadow tables (`<name>_data`, `<name>_idx`, ...) are folded into
+/// their virtual table rather than listed separately.
+pub fn sqlite_storage(conn: &Connection, name: &str) -> Result<DbStorage> {
+    let page_size: i64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+    let page_count: i64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let free_pages: i64 = conn.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )?;
+    let schema: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get::<_, Option<String>>(1)?.unwrap_or_default())))?
+        .filter_map(|r| r.ok())
+        .collect();
+    let virtual_tables: Vec<&str> = schema
+        .iter()
+        .filter(|(_, sql)| sql.to_uppercase().starts_with("CREATE VIRTUAL"))
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    let mut tables = Vec::new();
+    for (table, _) in &schema {
+        let is_shadow = virtual_tables
+            .iter()
+            .any(|v| table.len() > v.len() && table.starts_with(v) && table[v.len()..].starts_with('_'));
+        if is_shadow {
+            continue;
+        }
+        let rows = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\"")), [], |row| {
+            row.get(0)
+        })?;
+        tables.push(TableRows { table: table.clone(), rows });
+    }
+
+    Ok(DbStorage {
+        name: name.to_string(),
+        bytes: (page_count * page_size).max(0) as u64,
+        free_bytes: (free_pages * page_size).max(0) as u64,
+        tables,
+    })
+}
+
+/// Human-readable byte count (`512 B`, `1.5 KB`, `3.2 MB`, `1.1 GB`)
+pub fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b < KB {
+        format!("{} B", bytes)
+    } else if b < KB * KB {
+        format!("{:.1} KB", b / KB)
+    } else if b < KB * KB * KB {
+        format!("{:.1} MB", b / (KB * KB))
+    } else {
+        format!("{:.1} GB", b / (KB * KB * KB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_storage_counts_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT);
+             CREATE VIRTUAL TABLE notes_fts USING fts5(body);
+             INSERT INTO notes (body) VALUES ('a'), ('b'), ('c');
+             INSERT INTO notes_fts (body) VALUES ('a');",
+        )
+        .unwrap();
+
+        let storage = sqlite_storage(&conn, "notes").unwrap();
+        assert!(storage.bytes > 0);
+        let tables: Vec<(&str, i64)> = storage.tables.iter().map(|t| (t.table.as_str(), t.rows)).collect();
+        assert_eq!(tables, vec![("notes", 3), ("notes_fts", 1)]);
+        assert_eq!(storage.total_rows(), 4);
+
+        let report = StorageReport::new(vec![storage.clone()], 100);
+        assert_eq!(report.total_bytes, storage.bytes + 100);
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
@@ -0,0 +1,184 @@
+//! Running Task Registry
+//!
+//! In-memory list of Claude invocations currently in flight (CLI runs,
+//! `/bypass` bridge executions, `/circle` pipelines), so the dashboard can
+//! show what the bot is busy with. Callers hold a `TaskGuard` for the
+//! duration of the run; dropping it removes the task, so early returns and
+//! errors never leave stale entries behind. Start/finish events are
+//! broadcast for the live SSE view.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Broadcast capacity for task events (slow subscribers skip ahead)
+const EVENT_CAPACITY: usize = 256;
+
+/// Longest prompt excerpt kept per task
+const DESCRIPTION_CHARS: usize = 120;
+
+/// What kind of invocation a task is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    /// Local Claude CLI run
+    Claude,
+    /// `/bypass` execution on the remote bridge
+    Bridge,
+    /// `/circle` multi-persona pipeline
+    Circle,
+}
+
+/// One in-flight invocation
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningTask {
+    pub id: u64,
+    pub kind: TaskKind,
+    pub user_id: i64,
+    pub chat_id: i64,
+    pub started_at: DateTime<Utc>,
+    /// Start of the prompt, for telling tasks apart
+    pub description: String,
+}
+
+impl RunningTask {
+    /// Seconds since the task started
+    pub fn elapsed_secs(&self, now: DateTime<Utc>) -> i64 {
+        (now - self.started_at).num_seconds().max(0)
+    }
+}
+
+/// A task starting or finishing
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TaskEvent {
+    Started { task: RunningTask },
+    Finished { id: u64, elapsed_secs: i64 },
+}
+
+/// Shared registry of running tasks
+pub struct TaskRegistry {
+    tasks: Mutex<BTreeMap<u64, RunningTask>>,
+    next_id: AtomicU64,
+    events: broadcast::Sender<TaskEvent>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self {
+            tasks: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Register a task; it stays listed until the returned guard is dropped
+    pub fn start(self: &Arc<Self>, kind: TaskKind, user_id: i64, chat_id: i64, prompt: &str) -> TaskGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let task = RunningTask {
+            id,
+            kind,
+            user_id,
+            chat_id,
+            started_at: Utc::now(),
+            description: excerpt(prompt),
+        };
+        self.tasks.lock().unwrap().insert(id, task.clone());
+        let _ = self.events.send(TaskEvent::Started { task });
+        TaskGuard {
+            registry: Arc::clone(self),
+            id,
+        }
+    }
+
+    fn finish(&self, id: u64) {
+        if let Some(task) = self.tasks.lock().unwrap().remove(&id) {
+            let elapsed_secs = task.elapsed_secs(Utc::now());
+            let _ = self.events.send(TaskEvent::Finished { id, elapsed_secs });
+        }
+    }
+
+    /// Running tasks, oldest first
+    pub fn list(&self) -> Vec<RunningTask> {
+        self.tasks.lock().unwrap().values().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Channel carrying start/finish events
+    pub fn events(&self) -> &broadcast::Sender<TaskEvent> {
+        &self.events
+    }
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps a task listed; deregisters it on drop
+pub struct TaskGuard {
+    registry: Arc<TaskRegistry>,
+    id: u64,
+}
+
+impl TaskGuard {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.registry.finish(self.id);
+    }
+}
+
+/// First line of the prompt, cut to `DESCRIPTION_CHARS`
+fn excerpt(prompt: &str) -> String {
+    let line = prompt.trim().lines().next().unwrap_or("");
+    if line.chars().count() <= DESCRIPTION_CHARS {
+        line.to_string()
+    } else {
+        format!("{}…", line.chars().take(DESCRIPTION_CHARS - 1).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_registers_and_deregisters() {
+        let registry = Arc::new(TaskRegistry::new());
+        let mut events = registry.events().subscribe();
+
+        let first = registry.start(TaskKind::Claude, 1, 10, "explain lifetimes\nin detail");
+        let second = registry.start(TaskKind::Bridge, 2, 20, &"x".repeat(500));
+        let tasks = registry.list();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].description, "explain lifetimes");
+        assert_eq!(tasks[1].kind, TaskKind::Bridge);
+        assert_eq!(tasks[1].description.chars().count(), DESCRIPTION_CHARS);
+
+        let first_id = first.id();
+        drop(first);
+        assert_eq!(registry.list().iter().map(|t| t.id).collect::<Vec<_>>(), vec![second.id()]);
+        drop(second);
+        assert!(registry.is_empty());
+
+        assert!(matches!(events.try_recv().unwrap(), TaskEvent::Started { task } if task.id == first_id));
+        assert!(matches!(events.try_recv().unwrap(), TaskEvent::Started { .. }));
+        assert!(matches!(events.try_recv().unwrap(), TaskEvent::Finished { id, .. } if id == first_id));
+    }
+}
@@ -14,8 +14,9 @@
 
 use anyhow::{Context, Result};
 use serde::Deserialize;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use teloxide::{
@@ -24,40 +25,71 @@ use teloxide::{
     error_handlers::LoggingErrorHandler,
     net::Download,
     prelude::*,
-    types::{ParseMode, Update},
+    types::{InputFile, MessageId, ParseMode, ReplyParameters, Update},
 };
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::RwLock;
 
 use crate::agent::{
-    PlanningEngine, ReflectionEngine, Scheduler, ToolRegistry, AgentOrchestrator,
-    Reminder, Plan, ApprovalState,
+    PlanningEngine, ReflectionConfig, ReflectionEngine, Scheduler, SchedulerConfig, ToolRegistry, AgentOrchestrator,
+    Reminder, QuietHours, Plan, ApprovalState, DeliveryQueue, DeliveryConfig, NotificationType, Priority,
+    NotificationSink, NotificationRouter, DiscordWebhookSink, EmailSink,
 };
 use crate::autonomous::{
-    AutonomousLearner, BackgroundProcessor, ContextManager, GoalTracker, FeedbackLoop,
+    categorize_by_keywords, AutonomousLearner, BackgroundConfig, BackgroundProcessor, ContextConfig,
+    ContextManager, ContextStrategy, EnrichedContext, ExperimentTracker, GoalTracker, FeedbackLoop, Digest, DigestConfig, LearningConfig, RetentionReport,
 };
 use crate::bridge::GrpcBridgeClient;
-use crate::conversation::ConversationStore;
+use crate::channels::{self, ChannelRateLimiter, ChannelType, RateLimitConfig};
+use crate::conversation::{format_bookmarks, ConversationStore, TurnUsage};
 use crate::feedback::{OutputParser, TaskFeedback};
-use crate::graph::GraphStore;
-use crate::lifecycle::{LifecycleManager, LifecycleConfig, LifecycleCallbacks, ProcessingGuard};
-use crate::llama_worker::LlamaWorker;
-use crate::memory::MemoryStore;
-use crate::permissions::PermissionManager;
-use crate::preflight::PreflightChecker;
-use crate::circle::{Circle, PipelineMode, PipelineResult};
+use crate::graph::{EntityTaxonomy, GraphFormat, GraphStore};
+use crate::i18n::{self, Locale};
+use crate::file_summary::{self, FileSummaryConfig};
+use crate::input_limits::{self, InputLimits, OversizeAction};
+use crate::session_compaction::{self, SessionCompactionConfig};
+use crate::update_dedup::{self, UpdateDedup};
+use crate::working_dirs::WorkingDirs;
+use crate::postprocess::{self, PostProcessor};
+use crate::lifecycle::{
+    CompressionConfig, LifecycleManager, LifecycleConfig, LifecycleCallbacks, ProcessingGuard,
+};
+use crate::llama_worker::{LlamaWorker, COMPLEX_KEYWORDS, SIMPLE_KEYWORDS};
+use crate::memory::{normalize_tag, DimensionReport, MemoryEntry, MemoryScope, MemoryStore, MAX_TAG_CHARS};
+use crate::memory_backend::MemoryBackendUrl;
+use crate::ocr::{self, Ocr};
+use crate::permissions::{AllowedUsers, PermissionManager};
+use crate::pipeline_trace::PipelineTrace;
+use crate::preflight::{available_disk_bytes, DiagReport, DiagStatus, PreflightChecker, DISK_FAIL_BYTES, DISK_WARN_BYTES};
+use crate::cache::{CacheWarmConfig, CachedResponse, ResponseCache, WarmBudget, WarmReport};
+use crate::dataset::{DatasetExample, DatasetSink};
+use crate::storage::{format_bytes, StorageReport};
+use crate::tasks::{TaskKind, TaskRegistry};
+use crate::dashboard::{DashboardConfig, DashboardServer, StatusState};
+use crate::claude_cli::ClaudeCli;
+use crate::router::{CodeFloor, FallbackConfig, ModelHint, RouteDistribution, TaskRouter};
+use crate::skills::sandbox::default_audit_path;
+use crate::skills::{SandboxConfig, SkillLoader, SkillRegistry, SkillSandbox, TrustedKeys};
+use crate::circle::{Circle, CirclePersonas, PipelineMode, PipelineResult};
 use crate::telegram_ui::{
-    ButtonAction, ConversationContext as UiContext, ContextParser, Intent,
-    ProgressManager,
+    confirmation_keyboard, feedback_keyboard, ButtonAction, ConversationContext as UiContext, ContextParser,
+    Intent, ProgressManager,
+};
+use crate::metrics::{ClassifiedError, FailureReason, MetricsCollector};
+use crate::tokenizer::{BudgetCheck, ModelPricing, TokenCounter};
+use crate::usage::{
+    format_tokens, LimitCheck, SpikeConfig, SpikeMonitor, UsageRecord, UsageScope, UsageTracker, UserLimits,
+    ORIGIN_BACKGROUND, ORIGIN_BYPASS, ORIGIN_CHAT, ORIGIN_CIRCLE, ORIGIN_REFLECTION,
 };
-use crate::tokenizer::{TokenCounter, BudgetCheck};
-use crate::usage::{format_tokens, LimitCheck, UsageRecord, UsageTracker, UserLimits};
 
 /// Claude CLI JSON output structure
 #[derive(Debug, Deserialize)]
 struct ClaudeJsonOutput {
     result: String,
+    /// Set when the run failed (e.g. an API error), with the reason in `result`
+    #[serde(default)]
+    is_error: bool,
     #[serde(default)]
     usage: Option<ClaudeUsage>,
     #[serde(default)]
@@ -83,12 +115,18 @@ pub async fn run_telegram_bot() -> Result<()> {
     let token = std::env::var("TELEGRAM_BOT_TOKEN")
         .expect("TELEGRAM_BOT_TOKEN must be set");
 
-    let allowed_users: Vec<i64> = std::env::var("TELEGRAM_ALLOWED_USERS")
+    // Only these may /restart; unlike TELEGRAM_ALLOWED_USERS, empty means nobody
+    let admin_users: Vec<i64> = std::env::var("CLAUDEBOT_ADMIN_USERS")
         .unwrap_or_default()
         .split(',')
         .filter_map(|s| s.trim().parse().ok())
         .collect();
 
+    let restart_exit_code: i32 = std::env::var("CLAUDEBOT_RESTART_EXIT_CODE")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(75);
+
     let working_dir = std::env::var("CLAUDE_WORKING_DIR")
         .map(PathBuf::from)
         .unwrap_or_else(|_| PathBuf::from("/home/eliot/workspace"));
@@ -100,6 +138,8 @@ pub async fn run_telegram_bot() -> Result<()> {
     let memory_db_path = std::env::var("MEMORY_DB_PATH")
         .map(PathBuf::from)
         .unwrap_or_else(|_| working_dir.join("memory.db"));
+    // MEMORY_DATABASE_URL overrides the path; the bot needs SQLite, so a Postgres URL stops startup
+    let memory_db_path = MemoryBackendUrl::from_env(&memory_db_path)?.sqlite_path()?.to_path_buf();
 
     let conversation_db_path = std::env::var("CONVERSATION_DB_PATH")
         .map(PathBuf::from)
@@ -109,12 +149,50 @@ pub async fn run_telegram_bot() -> Result<()> {
         .map(PathBuf::from)
         .unwrap_or_else(|_| working_dir.join("goals.db"));
 
+    let delivery_queue_path = std::env::var("DELIVERY_QUEUE_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("undelivered.json"));
+
+    let scheduler_db_path = std::env::var("SCHEDULER_DB_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("scheduler.db"));
+
+    let reminders_path = std::env::var("REMINDERS_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("reminders.json"));
+
+    let digest_state_path = std::env::var("DIGEST_STATE_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("digest.json"));
+
+    let seen_updates_path = std::env::var("SEEN_UPDATES_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("seen_updates.json"));
+
+    let working_dirs_path = std::env::var("WORKING_DIRS_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("working_dirs.json"));
+
+    let experiments_path = std::env::var("EXPERIMENTS_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("experiments.json"));
+
+    let route_decisions_path = std::env::var("ROUTE_DECISIONS_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("route_decisions.jsonl"));
+
+    // /allow, /deny and the dashboard save here; a saved list replaces TELEGRAM_ALLOWED_USERS
+    let allowed_users_path = std::env::var("ALLOWED_USERS_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("allowed_users.json"));
+    let allowed_users = AllowedUsers::from_env().with_file(&allowed_users_path)?;
+
     // Create base working directory
     tokio::fs::create_dir_all(&working_dir).await?;
 
     // Initialize usage tracker, memory store, and conversation store
     let usage_tracker = UsageTracker::new(&usage_db_path)?;
-    let memory_store = MemoryStore::open_with_embeddings(&memory_db_path).await?;
+    let memory_store = Arc::new(MemoryStore::open_with_embeddings(&memory_db_path).await?);
     
     // Industry standard: Backfill embeddings on startup for semantic search
     {
@@ -132,12 +210,51 @@ pub async fn run_telegram_bot() -> Result<()> {
             }
         }
     }
+
+    // Warn about embeddings left behind by a model switch
+    let mut dimension_warning = None;
+    if memory_store.has_embeddings() {
+        match memory_store.check_dimensions().await {
+            Ok(report) if report.is_mismatched() => {
+                tracing::warn!(
+                    "{} embeddings don't match the current model ({}), semantic search is degraded - run /memory reembed",
+                    report.stale_count(),
+                    report.format().replace('\n', "; ")
+                );
+                dimension_warning = Some(report);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Embedding dimension check failed: {}", e),
+        }
+    }
     let conversation_store = ConversationStore::open(&conversation_db_path)?;
+    let background_config = BackgroundConfig::from_env();
+    if let Some(max_age) = background_config.retention.max_age {
+        tracing::info!("Conversation retention: {}", format_duration(max_age));
+    }
+
+    // Skill sandbox policy is operator-controlled - refuse to start on a bad one
+    let sandbox_config = SandboxConfig::from_env().context("Invalid skills sandbox policy")?;
+    let mut skills_sandbox = SkillSandbox::new(sandbox_config);
+    if let Some(path) = default_audit_path() {
+        skills_sandbox = skills_sandbox.with_audit_log(path);
+    }
+    let circle_personas = CirclePersonas::from_env().context("Invalid circle persona config")?;
+    let post_processor = PostProcessor::from_env().context("Invalid response post-processing config")?;
+    if !post_processor.is_empty() {
+        let steps: Vec<&str> = post_processor.transforms().iter().map(|t| t.name()).collect();
+        tracing::info!("Response post-processing: {}", steps.join(" → "));
+    }
+    let trusted_skill_keys = TrustedKeys::from_env().context("Invalid SKILLS_TRUSTED_KEYS")?;
+    let skill_registry = SkillRegistry::default_location();
+    if let Err(e) = skill_registry.load_all().await {
+        tracing::warn!("Failed to load installed skills: {}", e);
+    }
 
     tracing::info!("===========================================");
     tracing::info!("  ClaudeBot Telegram - Starting...");
     tracing::info!("===========================================");
-    tracing::info!("Allowed users: {:?}", if allowed_users.is_empty() { "ALL".to_string() } else { format!("{:?}", allowed_users) });
+    tracing::info!("Allowed users: {:?}", if allowed_users.is_open() { "ALL".to_string() } else { format!("{:?}", allowed_users.list()) });
     tracing::info!("Working directory: {:?}", working_dir);
     tracing::info!("Usage database: {:?}", usage_db_path);
     tracing::info!("Memory database: {:?}", memory_db_path);
@@ -172,13 +289,22 @@ pub async fn run_telegram_bot() -> Result<()> {
     // Lifecycle manager for background memory tasks (NOT for process timeouts)
     // idle_timeout only affects when memory consolidation runs, not task execution
     // Tasks run until completion with NO timeout
-    let lifecycle = LifecycleManager::new(LifecycleConfig {
-        idle_timeout: std::time::Duration::from_secs(86400), // 24 hours - practically never sleep during normal use
-        sleep_task_interval: std::time::Duration::from_secs(3600), // Run background tasks hourly when sleeping
-        enable_consolidation: true,
-        enable_decay: true,
-        enable_compression: true,
-    });
+    let lifecycle_config = LifecycleConfig::from_env();
+    tracing::info!(
+        "Lifecycle: sleep after {:?} idle, sleep tasks every {:?} (consolidation={}, decay={}, compression={})",
+        lifecycle_config.idle_timeout,
+        lifecycle_config.sleep_task_interval,
+        lifecycle_config.enable_consolidation,
+        lifecycle_config.enable_decay,
+        lifecycle_config.enable_compression
+    );
+    let compression_overrides_path = std::env::var("COMPRESSION_OVERRIDES_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("compression.json"));
+    let lifecycle = LifecycleManager::with_overrides_file(lifecycle_config, &compression_overrides_path);
+    let context_overrides_path = std::env::var("CONTEXT_OVERRIDES_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| working_dir.join("context.json"));
 
     // Check Llama availability
     if llama_worker.is_available().await {
@@ -188,11 +314,14 @@ pub async fn run_telegram_bot() -> Result<()> {
     }
 
     // Initialize graph store (uses same database as memory)
-    let graph_store = GraphStore::open(&memory_db_path)?;
+    let graph_store = GraphStore::open(&memory_db_path)?.with_taxonomy(EntityTaxonomy::from_env());
     tracing::info!("Graph store initialized");
 
     // Initialize permission manager
-    let permission_manager = PermissionManager::new();
+    let permission_manager = PermissionManager::from_env();
+    if permission_manager.is_safe_mode() {
+        tracing::warn!("Safe mode active: autonomous escalation disabled");
+    }
     tracing::info!("Permission manager initialized");
 
     // Initialize gRPC bridge client (optional - only if BRIDGE_GRPC_URL is set)
@@ -212,26 +341,59 @@ pub async fn run_telegram_bot() -> Result<()> {
 
     // Quick check that claude CLI exists at startup
     if !preflight_checker.check_claude_cli().await {
-        tracing::error!("Claude CLI not found! Install with: npm install -g @anthropic-ai/claude-code");
+        tracing::error!(
+            "Claude CLI not found at '{}'! Install with: npm install -g @anthropic-ai/claude-code, or set CLAUDE_CLI_PATH",
+            ClaudeCli::from_env().path
+        );
     } else {
         tracing::info!("Pre-flight checker: Claude CLI available");
     }
 
+    // Network probe runs in the background so a slow NAT doesn't delay startup
+    {
+        let checker = PreflightChecker::new();
+        tokio::spawn(async move {
+            let report = checker.check_connectivity().await;
+            for endpoint in &report.endpoints {
+                if endpoint.reachable {
+                    tracing::info!("Pre-flight: {}", endpoint.format_line());
+                } else if endpoint.required {
+                    tracing::error!("Pre-flight: {} unreachable", endpoint.format_line());
+                } else {
+                    tracing::warn!("Pre-flight: {} unreachable", endpoint.format_line());
+                }
+            }
+        });
+    }
+
+    let dataset = DatasetSink::from_env();
+    if let Some(path) = dataset.path() {
+        tracing::info!("Fine-tuning dataset: {:?}", path);
+    }
+
     // Initialize Phase 8: Agent system components
-    let reflection_engine = ReflectionEngine::new();
+    let reflection_engine = ReflectionEngine::with_config(ReflectionConfig::from_env());
     let planning_engine = PlanningEngine::new();
-    let (scheduler, scheduler_rx) = Scheduler::new(100);
+    let (scheduler, scheduler_rx) = Scheduler::with_config(SchedulerConfig::from_env(), 100);
+    let scheduler = scheduler
+        .with_quiet_hours_db(&scheduler_db_path)
+        .context("Failed to open the scheduler database")?;
     let tool_registry = ToolRegistry::new();
     let agent_orchestrator = AgentOrchestrator::new();
     tracing::info!("Agent system components initialized");
 
     let handler_data = Arc::new(BotData {
         allowed_users,
+        admin_users,
+        restart_exit_code,
+        reminders_path,
+        seen_updates: UpdateDedup::open(&seen_updates_path, update_dedup::DEFAULT_CAPACITY),
+        working_dirs: WorkingDirs::open(&working_dir, &WorkingDirs::allowed_roots_from_env(), &working_dirs_path),
         base_working_dir: working_dir,
         usage_tracker,
-        memory_store: std::sync::Mutex::new(memory_store),
-        conversation_store: std::sync::Mutex::new(conversation_store),
-        graph_store: std::sync::Mutex::new(graph_store),
+        memory_store,
+        conversation_store,
+        graph_store: Arc::new(std::sync::Mutex::new(graph_store)),
         token_counter,
         llama_worker,
         lifecycle: Arc::clone(&lifecycle),
@@ -243,29 +405,84 @@ pub async fn run_telegram_bot() -> Result<()> {
         progress_manager: ProgressManager::new(),
         // Interactive permissions
         interactive_permissions: RwLock::new(HashMap::new()),
+        auto_reflect: RwLock::new(HashMap::new()),
+        trace_modes: RwLock::new(HashMap::new()),
+        chosen_locales: RwLock::new(HashMap::new()),
+        telegram_locales: RwLock::new(HashMap::new()),
         pending_permissions: RwLock::new(HashMap::new()),
+        input_limits: InputLimits::from_env(),
+        file_summary: FileSummaryConfig::from_env(),
+        pending_inputs: RwLock::new(HashMap::new()),
+        session_compaction: SessionCompactionConfig::from_env(),
+        metrics: Arc::new(MetricsCollector::new(METRICS_HISTORY)),
+        model_fallback: FallbackConfig::from_env(),
         // Phase 7: Autonomous behavior components
-        autonomous_learner: AutonomousLearner::new(),
-        context_manager: ContextManager::new(),
+        autonomous_learner: AutonomousLearner::with_config(LearningConfig::from_env()),
+        context_manager: ContextManager::with_overrides_file(ContextConfig::from_env(), &context_overrides_path),
         goal_tracker: GoalTracker::open(&goals_db_path).unwrap_or_else(|e| {
             tracing::warn!("Failed to open goals DB: {}, using in-memory", e);
             GoalTracker::new()
         }),
-        feedback_loop: FeedbackLoop::new(),
-        background_processor: BackgroundProcessor::new(),
+        feedback_loop: FeedbackLoop::new().with_experiments(ExperimentTracker::open(&experiments_path)),
+        background_processor: BackgroundProcessor::with_config(background_config),
         // Phase 8: Agent system components
         reflection_engine,
         planning_engine,
         scheduler,
+        delivery_queue: Arc::new(DeliveryQueue::open(&delivery_queue_path, DeliveryConfig::default())),
         tool_registry: RwLock::new(tool_registry),
         agent_orchestrator,
-        // Phase 9: Security hardening - 20 requests per minute per user
-        rate_limiter: RateLimiter::new(20, 60),
+        // Phase 9: Security hardening - per-user limits (TELEGRAM_RATE_LIMIT*)
+        rate_limiter: ChannelRateLimiter::new("telegram", RateLimitConfig::from_env("telegram")),
+        skills_sandbox,
+        skill_registry,
+        trusted_skill_keys,
+        router: TaskRouter::default()
+            .with_code_floor(CodeFloor::from_env())
+            .with_model_selection(TaskRouter::model_selection_from_env())
+            .with_decisions_file(&route_decisions_path),
+        response_cache: ResponseCache::from_env(),
+        cache_warm: CacheWarmConfig::from_env(),
+        circle_personas,
+        post_processor,
+        ocr: Ocr::from_env().await,
+        dataset: Arc::new(dataset),
+        task_registry: Arc::new(TaskRegistry::new()),
     });
     tracing::info!("Autonomous behavior system initialized");
-    tracing::info!("Rate limiter: 20 req/min per user");
+    let rate_limit = handler_data.rate_limiter.config_for("telegram");
+    tracing::info!(
+        "Rate limiter: {} req per {}s per user (+{} burst)",
+        rate_limit.max_requests,
+        rate_limit.window_secs,
+        rate_limit.burst_allowance
+    );
     tracing::info!("Goals database: {:?}", goals_db_path);
 
+    // Dashboard reads the bot's own metrics, cache, memory and graph; opt-in since it opens a port
+    if std::env::var("DASHBOARD_ENABLED").map(|s| s == "true" || s == "1").unwrap_or(false) {
+        let status = StatusState::with_metrics(Arc::clone(&handler_data.metrics))
+            .with_cache(handler_data.response_cache.clone());
+        let dashboard = DashboardServer::new(DashboardConfig::from_env())
+            .with_status(status)
+            .with_allowed_users(handler_data.allowed_users.clone())
+            .with_task_registry(Arc::clone(&handler_data.task_registry))
+            .with_memory_store(Arc::clone(&handler_data.memory_store))
+            .with_graph_store(Arc::clone(&handler_data.graph_store));
+        tokio::spawn(async move {
+            if let Err(e) = dashboard.run().await {
+                tracing::error!("Dashboard server failed: {}", e);
+            }
+        });
+    }
+
+    // Reminders saved by /restart
+    match handler_data.scheduler.restore_reminders(&handler_data.reminders_path).await {
+        Ok(0) => {}
+        Ok(n) => tracing::info!("Restored {} reminder(s) from {:?}", n, handler_data.reminders_path),
+        Err(e) => tracing::warn!("Failed to restore reminders from {:?}: {}", handler_data.reminders_path, e),
+    }
+
     // Start the scheduler background loop
     handler_data.scheduler.start().await;
     tracing::info!("Scheduler started");
@@ -299,6 +516,11 @@ pub async fn run_telegram_bot() -> Result<()> {
                                 tracing::debug!("Background {}: {} items", task.as_str(), count);
                             }
                         }
+
+                        // Expire old conversation messages, keeping their summaries
+                        data.background_processor
+                            .run_retention_if_due(&data.conversation_store, &data.memory_store, &data.llama_worker)
+                            .await?;
                         Ok(())
                     })
                 }
@@ -314,19 +536,50 @@ pub async fn run_telegram_bot() -> Result<()> {
                             return Ok(());
                         }
 
-                        // Get conversations that need compression (older than 1 hour, > 20 messages)
-                        let conversations_to_compress = {
-                            let store = data.conversation_store.lock()
-                                .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-                            store.get_stale_conversations(3600, 20)?
+                        // Get conversations that need compression. Candidates are fetched with the
+                        // loosest thresholds, most important first, then filtered by each chat's
+                        // effective settings. Trivial conversations aren't worth an LLM call.
+                        let (min_age, min_messages) = data.lifecycle.compression_candidate_thresholds();
+                        let max_per_cycle = data.lifecycle.compression_config().max_per_cycle;
+                        let min_importance = data.lifecycle.compression_config().min_importance;
+                        let conversations_to_compress: Vec<(i64, CompressionConfig)> = {
+                            let store = &data.conversation_store;
+                            let now_ms = chrono::Utc::now().timestamp_millis();
+                            store.get_stale_conversations(min_age.as_secs() as i64, min_messages)?
+                                .into_iter()
+                                .filter(|candidate| {
+                                    let keep = candidate.importance >= min_importance;
+                                    if !keep {
+                                        tracing::debug!(
+                                            "Skipping compression of trivial conversation {} (importance {:.2})",
+                                            candidate.chat_id, candidate.importance
+                                        );
+                                    }
+                                    keep
+                                })
+                                .map(|candidate| (candidate.chat_id, data.lifecycle.compression_for_chat(candidate.chat_id)))
+                                .filter(|(chat_id, settings)| {
+                                    store.get_summary(*chat_id).map(|summary| {
+                                        let cutoff = now_ms - settings.min_age.as_millis() as i64;
+                                        summary.message_count > settings.min_messages
+                                            && summary.newest_timestamp.is_some_and(|t| t < cutoff)
+                                    }).unwrap_or(false)
+                                })
+                                .take(max_per_cycle)
+                                .collect()
                         };
 
-                        for chat_id in conversations_to_compress.into_iter().take(3) {
+                        // Shares the background cycle budget; the rest stay stale and come up next cycle
+                        let deadline = data.background_processor.cycle_deadline();
+                        for (i, (chat_id, settings)) in conversations_to_compress.into_iter().enumerate() {
+                            if i > 0 && crate::autonomous::out_of_time(deadline) {
+                                tracing::debug!("Compression cycle budget spent, continuing next cycle");
+                                break;
+                            }
                             // Get the messages
                             let messages = {
-                                let store = data.conversation_store.lock()
-                                    .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
-                                store.get_history(chat_id, 50)?
+                                let store = &data.conversation_store;
+                                store.get_history(chat_id, store.max_messages())?
                             };
 
                             if messages.len() < 10 {
@@ -338,22 +591,19 @@ pub async fn run_telegram_bot() -> Result<()> {
                                 .map(|m| (m.role.as_str(), m.content.as_str()))
                                 .collect();
 
-                            // Compress using Llama (target 30% reduction)
-                            if let Ok(summary) = data.llama_worker.compress_context(&context, 0.3).await {
+                            // Compress using Llama
+                            if let Ok(summary) = data.llama_worker.compress_context(&context, settings.target_ratio).await {
                                 // Store the compressed version as a memory
-                                if let Ok(store) = data.memory_store.lock() {
-                                    let _ = store.learn(
-                                        &format!("Conversation summary: {}", summary),
-                                        "conversation_summary",
-                                        &format!("chat_{}", chat_id),
-                                        0.8
-                                    );
-                                }
+                                let _ = data.memory_store.learn(
+                                    &format!("Conversation summary: {}", summary),
+                                    "conversation_summary",
+                                    &format!("chat_{}", chat_id),
+                                    Some(chat_id),
+                                    0.8
+                                );
 
                                 // Trim the old messages
-                                if let Ok(store) = data.conversation_store.lock() {
-                                    let _ = store.trim_conversation(chat_id, 10);
-                                }
+                                let _ = data.conversation_store.trim_conversation(chat_id, 10);
 
                                 tracing::info!("Compressed conversation {} ({} messages)", chat_id, messages.len());
                             }
@@ -363,26 +613,152 @@ pub async fn run_telegram_bot() -> Result<()> {
                     })
                 }
             })),
+            on_digest: Some(Box::new({
+                let data = Arc::clone(&data);
+                let config = Arc::new(DigestConfig::from_env());
+                let last_sent = Arc::new(std::sync::Mutex::new(Digest::load_last_sent(&digest_state_path)));
+                if config.enabled {
+                    tracing::info!("Daily digest enabled at {}", config.time.format("%H:%M"));
+                }
+                move || {
+                    let data = Arc::clone(&data);
+                    let config = Arc::clone(&config);
+                    let last_sent = Arc::clone(&last_sent);
+                    let digest_state_path = digest_state_path.clone();
+                    Box::pin(async move {
+                        {
+                            let mut last = last_sent.lock()
+                                .map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+                            if !config.is_due_now(*last) {
+                                return Ok(());
+                            }
+                            let today = chrono::Local::now().date_naive();
+                            *last = Some(today);
+                            if let Err(e) = Digest::save_last_sent(&digest_state_path, today) {
+                                tracing::warn!("Failed to save digest state to {:?}: {}", digest_state_path, e);
+                            }
+                        }
+
+                        let recipients = data.allowed_users.list();
+                        if recipients.is_empty() {
+                            tracing::debug!("Daily digest skipped: no TELEGRAM_ALLOWED_USERS to send to");
+                            return Ok(());
+                        }
+
+                        for &user_id in &recipients {
+                            let digest = Digest::build(
+                                &config,
+                                user_id,
+                                &data.goal_tracker,
+                                &data.scheduler,
+                                &data.memory_store,
+                                data.context_manager.config().memory_scope.scope_for(user_id),
+                            ).await;
+                            if digest.is_empty() {
+                                continue;
+                            }
+                            data.scheduler.notify(digest.into_notification(user_id, user_id)).await;
+                        }
+
+                        tracing::info!("Daily digest sent to {} user(s)", recipients.len());
+                        Ok(())
+                    })
+                }
+            })),
+            on_warm_cache: Some(Box::new({
+                let data = Arc::clone(&data);
+                let budget = Arc::new(std::sync::Mutex::new(WarmBudget::default()));
+                if data.cache_warm.enabled {
+                    tracing::info!(
+                        "Cache warming enabled: top {} prompts, ${:.2}/day",
+                        data.cache_warm.top_n, data.cache_warm.daily_budget_usd
+                    );
+                }
+                move || {
+                    let data = Arc::clone(&data);
+                    let budget = Arc::clone(&budget);
+                    Box::pin(async move {
+                        let report = warm_response_cache(&data, &budget).await?;
+                        if report.warmed > 0 || report.over_budget > 0 {
+                            tracing::info!(
+                                "Cache warming: {} warmed (${:.4}), {} already cached, {} over budget, {} failed",
+                                report.warmed, report.cost_usd, report.already_cached, report.over_budget, report.failed
+                            );
+                        }
+                        Ok(())
+                    })
+                }
+            })),
         };
         lifecycle_clone.run(callbacks).await;
     });
 
-    // Start scheduler notification processor
-    let bot_for_scheduler = Bot::new(token.clone());
+    // Watch for spend spikes (runaway loops, prompt injection) and alert allowed users
+    let spike_monitor = SpikeMonitor::new(SpikeConfig::from_env());
+    if spike_monitor.config().enabled {
+        let data = Arc::clone(&handler_data);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(spike_monitor.config().check_interval);
+            loop {
+                interval.tick().await;
+                let spike = match spike_monitor.check(&data.usage_tracker, chrono::Utc::now().timestamp()) {
+                    Ok(Some(spike)) => spike,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::warn!("Spend spike check failed: {}", e);
+                        continue;
+                    }
+                };
+                tracing::warn!(
+                    "Spend spike: ${:.2} in the last hour (threshold ${:.2})",
+                    spike.current_hour_cost, spike.threshold
+                );
+                let message = spike.format();
+                for user_id in data.allowed_users.list() {
+                    let alert = Reminder::once(user_id, user_id, &message, chrono::Utc::now().timestamp())
+                        .from_channel(ChannelType::Telegram)
+                        .with_type(NotificationType::SystemStatus)
+                        .with_priority(Priority::Urgent);
+                    data.scheduler.notify(alert).await;
+                }
+            }
+        });
+    }
+
+    // Start scheduler notification processor. Reminders go to every configured
+    // sink (or the one for their origin channel); failed sends go to the retry
+    // queue and are re-attempted with backoff until they exhaust their attempts.
+    let notifications = notification_router(Bot::new(token.clone()));
+    tracing::info!("Notification sinks: {}", notifications.sink_names().join(", "));
+    let delivery_queue = Arc::clone(&handler_data.delivery_queue);
+    if delivery_queue.pending_count() > 0 {
+        tracing::info!("Restored {} undelivered notification(s)", delivery_queue.pending_count());
+    }
     tokio::spawn(async move {
         let mut rx = scheduler_rx;
-        while let Some(reminder) = rx.recv().await {
-            let notification_text = format!(
-                "{} *Reminder*\n\n{}",
-                reminder.notification_type.emoji(),
-                reminder.message
-            );
-            if let Err(e) = bot_for_scheduler
-                .send_message(ChatId(reminder.chat_id), notification_text)
-                .parse_mode(ParseMode::MarkdownV2)
-                .await
-            {
-                tracing::warn!("Failed to send scheduled notification: {}", e);
+        let mut retry_tick = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            tokio::select! {
+                reminder = rx.recv() => {
+                    let Some(reminder) = reminder else { break };
+                    if let Err(e) = notifications.deliver(&reminder).await {
+                        delivery_queue.record_failure(reminder, 1, &e.to_string());
+                    }
+                }
+                _ = retry_tick.tick() => {
+                    for pending in delivery_queue.take_due() {
+                        let attempts = pending.attempts + 1;
+                        match notifications.deliver(&pending.reminder).await {
+                            Ok(()) => tracing::info!(
+                                "Delivered notification {} on attempt {}",
+                                pending.reminder.id, attempts
+                            ),
+                            Err(e) => {
+                                delivery_queue.record_failure(pending.reminder, attempts, &e.to_string());
+                            }
+                        }
+                    }
+                }
             }
         }
         tracing::warn!("Scheduler notification processor stopped");
@@ -394,6 +770,10 @@ pub async fn run_telegram_bot() -> Result<()> {
             Update::filter_message()
                 .endpoint(message_handler)
         )
+        .branch(
+            Update::filter_edited_message()
+                .endpoint(edited_message_handler)
+        )
         .branch(
             Update::filter_callback_query()
                 .endpoint(callback_handler)
@@ -405,16 +785,24 @@ pub async fn run_telegram_bot() -> Result<()> {
     tracing::info!("===========================================");
 
     // Send startup notification to allowed users (crash/restart feedback)
-    let startup_users = handler_data.allowed_users.clone();
+    let startup_users = handler_data.allowed_users.list();
     if !startup_users.is_empty() {
         let startup_bot = Bot::new(token.clone());
-        let startup_msg = format!(
+        let mut startup_msg = format!(
             "🤖 *Bot Started*\n\n\
             ClaudeBot is now online\\.\n\
             _If you see this unexpectedly, the bot may have crashed and restarted\\._\n\n\
             ⏰ {}",
             chrono::Utc::now().format("%Y\\-%m\\-%d %H:%M:%S UTC")
         );
+        if let Some(ref report) = dimension_warning {
+            startup_msg.push_str(&teloxide::utils::markdown::escape(&format!(
+                "\n\n⚠️ {} memory embeddings don't match the current embedding model, \
+                so semantic search is degraded. Run /memory reembed to migrate.\n{}",
+                report.stale_count(),
+                report.format()
+            )));
+        }
         for user_id in &startup_users {
             if let Err(e) = startup_bot
                 .send_message(ChatId(*user_id), &startup_msg)
@@ -446,12 +834,62 @@ pub async fn run_telegram_bot() -> Result<()> {
     Ok(())
 }
 
+/// Delivers scheduled notifications to their Telegram chat
+struct TelegramSink {
+    bot: Bot,
+}
+
+#[async_trait::async_trait]
+impl NotificationSink for TelegramSink {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn channel(&self) -> Option<ChannelType> {
+        Some(ChannelType::Telegram)
+    }
+
+    async fn send(&self, reminder: &Reminder) -> Result<()> {
+        let notification_text = format!(
+            "{} *{}*\n\n{}",
+            reminder.notification_type.emoji(),
+            teloxide::utils::markdown::escape(reminder.notification_type.title()),
+            teloxide::utils::markdown::escape(&reminder.message)
+        );
+        self.bot.send_message(ChatId(reminder.chat_id), notification_text)
+            .parse_mode(ParseMode::MarkdownV2)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Telegram plus any Discord webhook / email sinks configured in the environment
+/// (those only receive `NOTIFY_BROADCAST_TYPES`)
+fn notification_router(bot: Bot) -> NotificationRouter {
+    let mut router = NotificationRouter::new()
+        .with_sink(Arc::new(TelegramSink { bot }))
+        .with_broadcast_types(NotificationRouter::broadcast_types_from_env());
+    if let Some(discord) = DiscordWebhookSink::from_env() {
+        router = router.with_sink(Arc::new(discord));
+    }
+    if let Some(email) = EmailSink::from_env() {
+        router = router.with_sink(Arc::new(email));
+    }
+    router
+}
+
 /// Message handler endpoint for the dispatcher
 async fn message_handler(
     bot: Bot,
+    update: Update,
     msg: Message,
     data: Arc<BotData>,
 ) -> ResponseResult<()> {
+    if !data.seen_updates.first_seen(update.id.0) {
+        tracing::info!("Skipping redelivered update {}", update.id.0);
+        return Ok(());
+    }
+
     let user_id = msg.from.as_ref().map(|u| u.id.0).unwrap_or(0);
     let chat_id = msg.chat.id.0;
     let text_preview = msg.text().unwrap_or("<non-text>").chars().take(50).collect::<String>();
@@ -468,6 +906,116 @@ async fn message_handler(
     Ok(())
 }
 
+/// Edited message handler endpoint for the dispatcher
+async fn edited_message_handler(
+    bot: Bot,
+    update: Update,
+    msg: Message,
+    data: Arc<BotData>,
+) -> ResponseResult<()> {
+    if !data.seen_updates.first_seen(update.id.0) {
+        tracing::info!("Skipping redelivered update {}", update.id.0);
+        return Ok(());
+    }
+
+    tracing::info!(
+        ">>> Message edited: chat={}, message={}",
+        msg.chat.id.0, msg.id.0
+    );
+
+    if let Err(e) = handle_edited_message(bot, msg, data).await {
+        tracing::error!("Error handling edited message: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Re-run edited messages instead of only updating stored history
+/// (`CLAUDEBOT_RERUN_EDITS`, default false)
+fn rerun_edits_from_env() -> bool {
+    std::env::var("CLAUDEBOT_RERUN_EDITS")
+        .map(|s| s == "true" || s == "1")
+        .unwrap_or(false)
+}
+
+/// Handle an edited message
+///
+/// The stored exchange for the message is updated in place so the edited
+/// prompt replaces the original in conversation history. With
+/// `CLAUDEBOT_RERUN_EDITS` the message is processed again, and its new
+/// reply replaces the old one.
+async fn handle_edited_message(bot: Bot, msg: Message, data: Arc<BotData>) -> Result<()> {
+    let user_id = msg.from.as_ref().map(|u| u.id.0 as i64).unwrap_or(0);
+    if !data.is_allowed(user_id) {
+        tracing::warn!("Ignoring edit from unauthorized user: {}", user_id);
+        return Ok(());
+    }
+    let Some(text) = msg.text() else {
+        return Ok(());
+    };
+
+    let chat_id = msg.chat.id.0;
+    let message_id = msg.id.0 as i64;
+    let sensitive = contains_sensitive_data(text);
+
+    if !text.starts_with('/') {
+        let store = &data.conversation_store;
+        if sensitive {
+            // The edit can't be stored, so don't keep remembering the original either
+            store.remove_exchange(chat_id, message_id)?;
+        } else if store.update_user_message(chat_id, message_id, &sanitize_for_storage(text))? {
+            tracing::info!("Updated stored exchange for edited message {}", message_id);
+        }
+    }
+
+    if rerun_edits_from_env() {
+        return handle_message(bot, msg.clone(), data).await;
+    }
+    if text.starts_with('/') {
+        tracing::debug!("Edited command not re-run (CLAUDEBOT_RERUN_EDITS disabled)");
+    }
+    Ok(())
+}
+
+/// Record a thumbs-up/down on a response and remove its rating buttons
+async fn rate_response(
+    bot: &Bot,
+    query: &CallbackQuery,
+    data: &BotData,
+    retrieval_id: &str,
+    positive: bool,
+) -> ResponseResult<()> {
+    let text = match data
+        .feedback_loop
+        .record_response_feedback(retrieval_id, positive, query.from.id.0 as i64, &data.memory_store)
+        .await
+    {
+        Ok(Some(_)) => {
+            if let Some(msg) = &query.message {
+                if let Err(e) = data.dataset.record_feedback(msg.chat().id.0, retrieval_id, positive) {
+                    tracing::warn!("Failed to record dataset feedback: {}", e);
+                }
+            }
+            if positive {
+                "Thanks - marked as helpful"
+            } else {
+                "Thanks - memories used here will be trusted less"
+            }
+        }
+        Ok(None) => "Feedback already recorded or expired",
+        Err(e) => {
+            tracing::warn!("Failed to record response feedback: {}", e);
+            "Failed to record feedback"
+        }
+    };
+    bot.answer_callback_query(&query.id).text(text).await?;
+
+    if let Some(msg) = &query.message {
+        let _ = bot.edit_message_reply_markup(msg.chat().id, msg.id()).await;
+    }
+    Ok(())
+}
+
 /// Callback query handler for inline keyboard buttons
 async fn callback_handler(
     bot: Bot,
@@ -478,8 +1026,9 @@ async fn callback_handler(
 
     // Check if user is allowed
     if !data.is_allowed(user_id) {
+        data.note_language_code(user_id, query.from.language_code.as_deref()).await;
         bot.answer_callback_query(&query.id)
-            .text("Unauthorized")
+            .text(i18n::t(data.locale(user_id).await, "unauthorized_short"))
             .await?;
         return Ok(());
     }
@@ -542,7 +1091,8 @@ async fn callback_handler(
                             data.permission_manager.get_status(user_id).level,
                             crate::permissions::PermissionLevel::Autonomous
                         );
-                        if let Ok(response) = invoke_claude_cli(cmd, &working_dir, is_autonomous).await {
+                        if let Ok(response) = data.invoke_claude(user_id, cid.0, cmd, &working_dir, is_autonomous).await {
+                            record_usage(&data, user_id, &response, ORIGIN_CHAT);
                             let _ = send_long_message(&bot, cid, &response.text).await;
                         }
                     } else {
@@ -583,14 +1133,25 @@ async fn callback_handler(
 
             ButtonAction::Confirm(action_id) => {
                 if let Some(cid) = chat_id {
+                    let mut pending = None;
                     data.update_ui_context(cid.0, |ctx| {
-                        ctx.clear_confirmation();
+                        pending = ctx.take_confirmation();
                     }).await;
                     bot.answer_callback_query(&query.id)
                         .text("Confirmed")
                         .await?;
                     // Execute the confirmed action
-                    bot.send_message(cid, format!("Action {} confirmed. Executing...", action_id)).await?;
+                    match pending {
+                        Some(command) if action_id == COST_CONFIRM_ACTION => {
+                            let working_dir = data.working_dir_for_user(user_id);
+                            if let Err(e) = run_confirmed_command(&bot, cid, &data, &command, &working_dir, user_id).await {
+                                tracing::error!("Confirmed command failed: {}", e);
+                            }
+                        }
+                        _ => {
+                            bot.send_message(cid, format!("Action {} confirmed. Executing...", action_id)).await?;
+                        }
+                    }
                 }
             }
 
@@ -612,6 +1173,14 @@ async fn callback_handler(
                     .await?;
             }
 
+            ButtonAction::ThumbsUp(retrieval_id) => {
+                rate_response(&bot, &query, &data, &retrieval_id, true).await?;
+            }
+
+            ButtonAction::ThumbsDown(retrieval_id) => {
+                rate_response(&bot, &query, &data, &retrieval_id, false).await?;
+            }
+
             ButtonAction::PauseTask(_) | ButtonAction::ResumeTask(_) => {
                 bot.answer_callback_query(&query.id)
                     .text("Not implemented yet")
@@ -631,10 +1200,50 @@ async fn callback_handler(
                 data.permission_manager.get_status(user_id).level,
                 crate::permissions::PermissionLevel::Autonomous
             );
-            if let Ok(response) = invoke_claude_cli(cmd, &working_dir, is_autonomous).await {
+            if let Ok(response) = data.invoke_claude(user_id, cid.0, cmd, &working_dir, is_autonomous).await {
+                record_usage(&data, user_id, &response, ORIGIN_CHAT);
                 let _ = send_long_message(&bot, cid, &response.text).await;
             }
         }
+    } else if let Some((status, goal_id)) = callback_data
+        .strip_prefix("goal_complete:")
+        .map(|id| (crate::autonomous::GoalStatus::Completed, id))
+        .or_else(|| callback_data.strip_prefix("goal_pause:").map(|id| (crate::autonomous::GoalStatus::Paused, id)))
+    {
+        // Goal disambiguation choice
+        let owned = data.goal_tracker.get_goal(goal_id).await.filter(|g| g.user_id == user_id && g.status.is_open());
+        if owned.is_none() {
+            bot.answer_callback_query(&query.id).text("Goal no longer open").await?;
+        } else {
+            let result = set_goal_status(&data, goal_id, status).await;
+            bot.answer_callback_query(&query.id).await?;
+            if let (Some(chat_id), Some(msg)) = (chat_id, &query.message) {
+                let _ = bot.edit_message_text(chat_id, msg.id(), result).await;
+            }
+        }
+    } else if let Some(action) = callback_data.strip_prefix("oversize:").and_then(OversizeAction::parse) {
+        // Choice for an oversized message
+        if let Some(cid) = chat_id {
+            // Only the sender's own message: in groups, others see the buttons too
+            match data.pending_inputs.write().await.remove(&(cid.0, user_id)) {
+                Some(text) => {
+                    bot.answer_callback_query(&query.id).await?;
+                    if let Some(msg) = &query.message {
+                        let _ = bot.edit_message_reply_markup(cid, msg.id()).await;
+                    }
+                    let message_id = query.message.as_ref().map_or(0, |msg| msg.id().0);
+                    let working_dir = data.working_dir_for_user(user_id);
+                    if let Err(e) = handle_oversized_input(&bot, cid, &data, text, action, message_id, &working_dir, user_id).await {
+                        tracing::error!("Oversized message handling failed: {}", e);
+                    }
+                }
+                None => {
+                    bot.answer_callback_query(&query.id)
+                        .text("Message expired or already handled")
+                        .await?;
+                }
+            }
+        }
     } else if callback_data.starts_with("wkill:") {
         // Worker kill
         let worker_id = callback_data.strip_prefix("wkill:").unwrap_or("");
@@ -649,47 +1258,8 @@ async fn callback_handler(
             bot.answer_callback_query(&query.id)
                 .text("Permission approved - executing...")
                 .await?;
-
-            // Get the stored command from UI context
-            let ui_context = data.get_ui_context(pending.chat_id).await;
-            if let Some(command) = ui_context.last_command {
-                // Update the message to show approval
-                if let Some(msg) = &query.message {
-                    let _ = bot.edit_message_text(
-                        ChatId(pending.chat_id),
-                        msg.id(),
-                        format!("✅ Approved: {}\n\nExecuting...", pending.description)
-                    ).await;
-                }
-
-                // Execute the command
-                let working_dir = data.working_dir_for_user(user_id);
-                let is_autonomous = matches!(
-                    data.permission_manager.get_status(user_id).level,
-                    crate::permissions::PermissionLevel::Autonomous
-                );
-
-                match invoke_claude_cli(&command, &working_dir, is_autonomous).await {
-                    Ok(response) => {
-                        // Record usage
-                        record_usage(&data, user_id, &response);
-
-                        // Send response
-                        let _ = send_long_message(&bot, ChatId(pending.chat_id), &response.text).await;
-                    }
-                    Err(e) => {
-                        let _ = bot.send_message(
-                            ChatId(pending.chat_id),
-                            format!("❌ Error executing command: {}", e)
-                        ).await;
-                    }
-                }
-            } else {
-                let _ = bot.send_message(
-                    ChatId(pending.chat_id),
-                    "❌ No pending command found - request may have expired"
-                ).await;
-            }
+            let message_id = query.message.as_ref().map(|msg| msg.id());
+            run_approved_permission(&bot, &data, user_id, &pending, message_id).await;
         } else {
             bot.answer_callback_query(&query.id)
                 .text("Permission request expired or not found")
@@ -703,20 +1273,8 @@ async fn callback_handler(
             bot.answer_callback_query(&query.id)
                 .text("Permission denied - operation cancelled")
                 .await?;
-
-            // Update the message to show denial
-            if let Some(msg) = &query.message {
-                let _ = bot.edit_message_text(
-                    ChatId(pending.chat_id),
-                    msg.id(),
-                    format!("❌ Denied: {}\n\nOperation cancelled.", pending.description)
-                ).await;
-            }
-
-            // Clear the stored command
-            data.update_ui_context(pending.chat_id, |ctx| {
-                ctx.last_command = None;
-            }).await;
+            let message_id = query.message.as_ref().map(|msg| msg.id());
+            cancel_permission(&bot, &data, &pending, message_id).await;
         } else {
             bot.answer_callback_query(&query.id)
                 .text("Permission request expired or not found")
@@ -727,27 +1285,18 @@ async fn callback_handler(
         let plan_id = callback_data.strip_prefix("plan_approve:").unwrap_or("");
 
         if let Some(chat_id) = chat_id {
-            match data.planning_engine.process_approval(plan_id, "approve").await {
-                Ok(ApprovalState::Approved) => {
+            match resolve_plan(&data, chat_id.0, plan_id, true).await {
+                Ok(Some(confirmation)) => {
                     bot.answer_callback_query(&query.id)
                         .text("Plan approved!")
                         .await?;
 
                     // Update message
                     if let Some(msg) = &query.message {
-                        let _ = bot.edit_message_text(
-                            chat_id,
-                            msg.id(),
-                            "✅ Plan Approved\n\nReady to execute. Send the task description to begin execution."
-                        ).await;
+                        let _ = bot.edit_message_text(chat_id, msg.id(), confirmation).await;
                     }
-
-                    // Clear pending plan from context
-                    data.update_ui_context(chat_id.0, |ctx| {
-                        ctx.pending_plan_id = None;
-                    }).await;
                 }
-                Ok(_) => {
+                Ok(None) => {
                     bot.answer_callback_query(&query.id)
                         .text("Plan state updated")
                         .await?;
@@ -764,27 +1313,18 @@ async fn callback_handler(
         let plan_id = callback_data.strip_prefix("plan_reject:").unwrap_or("");
 
         if let Some(chat_id) = chat_id {
-            match data.planning_engine.process_approval(plan_id, "reject").await {
-                Ok(ApprovalState::Rejected) => {
+            match resolve_plan(&data, chat_id.0, plan_id, false).await {
+                Ok(Some(confirmation)) => {
                     bot.answer_callback_query(&query.id)
                         .text("Plan rejected")
                         .await?;
 
                     // Update message
                     if let Some(msg) = &query.message {
-                        let _ = bot.edit_message_text(
-                            chat_id,
-                            msg.id(),
-                            "❌ Plan Rejected\n\nCreate a new plan with /plan <task>"
-                        ).await;
+                        let _ = bot.edit_message_text(chat_id, msg.id(), confirmation).await;
                     }
-
-                    // Clear pending plan from context
-                    data.update_ui_context(chat_id.0, |ctx| {
-                        ctx.pending_plan_id = None;
-                    }).await;
                 }
-                Ok(_) => {
+                Ok(None) => {
                     bot.answer_callback_query(&query.id)
                         .text("Plan state updated")
                         .await?;
@@ -804,12 +1344,23 @@ async fn callback_handler(
 }
 
 struct BotData {
-    allowed_users: Vec<i64>,
+    /// Live allowlist, shared with the dashboard; /allow and /deny edit it
+    allowed_users: AllowedUsers,
+    /// Users allowed to /restart (CLAUDEBOT_ADMIN_USERS)
+    admin_users: Vec<i64>,
+    /// Exit code /restart uses so the supervisor starts the bot again
+    restart_exit_code: i32,
+    /// Where /restart saves pending reminders for the next start
+    reminders_path: PathBuf,
+    /// Update ids already handled, so redelivery after a restart is skipped
+    seen_updates: UpdateDedup,
+    /// Per-user working directories chosen with /cd
+    working_dirs: WorkingDirs,
     base_working_dir: PathBuf,
     usage_tracker: UsageTracker,
-    memory_store: std::sync::Mutex<MemoryStore>,
-    conversation_store: std::sync::Mutex<ConversationStore>,
-    graph_store: std::sync::Mutex<GraphStore>,
+    memory_store: Arc<MemoryStore>,
+    conversation_store: ConversationStore,
+    graph_store: Arc<std::sync::Mutex<GraphStore>>,
     token_counter: TokenCounter,
     llama_worker: LlamaWorker,
     lifecycle: Arc<LifecycleManager>,
@@ -821,8 +1372,27 @@ struct BotData {
     progress_manager: ProgressManager,
     // Interactive permission mode - when enabled, shows permission requests to user
     interactive_permissions: RwLock<HashMap<i64, bool>>,
+    // Auto-reflection mode - when enabled, low-quality responses are re-run once
+    auto_reflect: RwLock<HashMap<i64, bool>>,
+    // Pipeline trace mode - when enabled, each chat response is followed by a /trace report
+    trace_modes: RwLock<HashMap<i64, bool>>,
+    // Bot UI language: chosen with /lang, else the user's Telegram language_code
+    chosen_locales: RwLock<HashMap<i64, Locale>>,
+    telegram_locales: RwLock<HashMap<i64, Locale>>,
     // Pending permission requests: request_id -> (chat_id, permission_description)
     pending_permissions: RwLock<HashMap<String, PendingPermission>>,
+    /// Maximum message length before asking how to handle it
+    input_limits: InputLimits,
+    /// Chunk sizes for /summarize_file
+    file_summary: FileSummaryConfig,
+    /// Oversized messages waiting for a truncate/summarize/chunks choice, per (chat, user)
+    pending_inputs: RwLock<HashMap<(i64, i64), String>>,
+    /// When a growing CLI session is summarized and restarted
+    session_compaction: SessionCompactionConfig,
+    /// Claude call latency and failures by reason for `/metrics`
+    metrics: Arc<MetricsCollector>,
+    /// Cheaper tiers to try when the requested model is overloaded
+    model_fallback: FallbackConfig,
     // Phase 7: Autonomous behavior components
     autonomous_learner: AutonomousLearner,
     context_manager: ContextManager,
@@ -833,93 +1403,156 @@ struct BotData {
     reflection_engine: ReflectionEngine,
     planning_engine: PlanningEngine,
     scheduler: Scheduler,
+    /// Retry queue for notifications that failed to send
+    delivery_queue: Arc<DeliveryQueue>,
     tool_registry: RwLock<ToolRegistry>,
     agent_orchestrator: AgentOrchestrator,
     // Phase 9: Security hardening (T3.3)
-    rate_limiter: RateLimiter,
+    rate_limiter: ChannelRateLimiter,
+    /// Operator sandbox policy for skill shell/script execution
+    skills_sandbox: SkillSandbox,
+    /// Installed skills (~/.claudebot/skills)
+    skill_registry: SkillRegistry,
+    /// Keys whose signatures `/skills install` accepts
+    trusted_skill_keys: TrustedKeys,
+    /// Task router (caches classifications of repeated prompts)
+    router: TaskRouter,
+    /// Response cache (SHA256-keyed Claude responses)
+    response_cache: ResponseCache,
+    /// Idle-time pre-caching of frequent prompts
+    cache_warm: CacheWarmConfig,
+    /// Personas run by /circle
+    circle_personas: CirclePersonas,
+    /// Transforms applied to chat answers before they're sent
+    post_processor: PostProcessor,
+    /// Text extraction for uploaded images
+    ocr: Ocr,
+    /// Opt-in fine-tuning dataset (prompt/response/quality JSONL)
+    dataset: Arc<DatasetSink>,
+    /// Invocations currently running (dashboard live task view)
+    task_registry: Arc<TaskRegistry>,
 }
 
-/// Per-user rate limiting for Telegram requests
-struct RateLimiter {
-    /// Max requests per window
-    max_requests: u32,
-    /// Window duration in seconds
-    window_secs: u64,
-    /// Per-user request counts: user_id -> (window_start, count)
-    entries: RwLock<HashMap<i64, RateLimitEntry>>,
+/// Pending permission request waiting for user approval
+struct PendingPermission {
+    chat_id: i64,
+    description: String,
+    tool: String,
+    created_at: Instant,
 }
 
-struct RateLimitEntry {
-    window_start: Instant,
-    count: u32,
-}
+impl BotData {
+    fn is_allowed(&self, user_id: i64) -> bool {
+        self.allowed_users.is_allowed(user_id)
+    }
 
-impl RateLimiter {
-    fn new(max_requests: u32, window_secs: u64) -> Self {
-        Self {
-            max_requests,
-            window_secs,
-            entries: RwLock::new(HashMap::new()),
-        }
+    /// Explicitly configured admin (never everyone, even with no admins set)
+    fn is_admin(&self, user_id: i64) -> bool {
+        self.admin_users.contains(&user_id)
     }
 
-    /// Check if user is within rate limit. Returns true if allowed.
-    async fn check(&self, user_id: i64) -> bool {
-        let mut entries = self.entries.write().await;
-        let now = Instant::now();
-        let window = Duration::from_secs(self.window_secs);
+    /// Run the Claude CLI, listed in the task registry while it runs
+    async fn invoke_claude(
+        &self,
+        user_id: i64,
+        chat_id: i64,
+        prompt: &str,
+        working_dir: &PathBuf,
+        autonomous: bool,
+    ) -> Result<ClaudeResponse> {
+        self.invoke_claude_with_model(user_id, chat_id, prompt, working_dir, autonomous, None).await
+    }
 
-        let entry = entries.entry(user_id).or_insert(RateLimitEntry {
-            window_start: now,
-            count: 0,
-        });
+    /// `invoke_claude`, optionally forcing the model tier
+    async fn invoke_claude_with_model(
+        &self,
+        user_id: i64,
+        chat_id: i64,
+        prompt: &str,
+        working_dir: &PathBuf,
+        autonomous: bool,
+        model: Option<ModelHint>,
+    ) -> Result<ClaudeResponse> {
+        self.run_claude(user_id, chat_id, prompt, working_dir, autonomous, model, false).await
+    }
 
-        // Reset window if expired
-        if now.duration_since(entry.window_start) >= window {
-            entry.window_start = now;
-            entry.count = 0;
-        }
-
-        // Check limit
-        if entry.count >= self.max_requests {
-            return false;
-        }
-
-        entry.count += 1;
-        true
+    /// `invoke_claude` in the CLI's read-only plan mode, never skipping permissions
+    async fn invoke_claude_read_only(
+        &self,
+        user_id: i64,
+        chat_id: i64,
+        prompt: &str,
+        working_dir: &PathBuf,
+    ) -> Result<ClaudeResponse> {
+        self.run_claude(user_id, chat_id, prompt, working_dir, false, None, true).await
     }
 
-    /// Get remaining requests for user
-    async fn remaining(&self, user_id: i64) -> u32 {
-        let entries = self.entries.read().await;
-        if let Some(entry) = entries.get(&user_id) {
-            let now = Instant::now();
-            let window = Duration::from_secs(self.window_secs);
-            if now.duration_since(entry.window_start) >= window {
-                return self.max_requests;
+    #[allow(clippy::too_many_arguments)]
+    async fn run_claude(
+        &self,
+        user_id: i64,
+        chat_id: i64,
+        prompt: &str,
+        working_dir: &PathBuf,
+        autonomous: bool,
+        model: Option<ModelHint>,
+        read_only: bool,
+    ) -> Result<ClaudeResponse> {
+        let _task = self.task_registry.start(TaskKind::Claude, user_id, chat_id, prompt);
+        // None runs the CLI's default model, whose tier isn't known here
+        let requested = model;
+        let mut model = model;
+        let mut depth = 0;
+        let result = loop {
+            let start = Instant::now();
+            let result = invoke_claude_cli(prompt, working_dir, autonomous, model, read_only).await;
+            match &result {
+                // A plain-text fallback still answers, but the CLI's output is broken
+                Ok(response) if response.tokens_estimated => self.metrics.record_failure(FailureReason::ParseError),
+                Ok(response) => self.metrics.record(
+                    &response.model,
+                    response.input_tokens as usize,
+                    response.output_tokens as usize,
+                    response.cache_read_tokens as usize,
+                    start.elapsed(),
+                    false,
+                    None,
+                ),
+                Err(e) => self.metrics.record_failure(FailureReason::classify(e)),
             }
-            self.max_requests.saturating_sub(entry.count)
-        } else {
-            self.max_requests
-        }
-    }
-}
-
-/// Pending permission request waiting for user approval
-struct PendingPermission {
-    chat_id: i64,
-    description: String,
-    tool: String,
-    created_at: Instant,
-}
 
-impl BotData {
-    fn is_allowed(&self, user_id: i64) -> bool {
-        self.allowed_users.is_empty() || self.allowed_users.contains(&user_id)
+            // Overloaded or rate-limited: degrade to the next tier instead of failing
+            let next = match &result {
+                Err(e) if depth < self.model_fallback.max_depth && self.model_fallback.triggers_on(&e.to_string()) => {
+                    self.model_fallback.next_after(model)
+                }
+                _ => None,
+            };
+            match next {
+                Some(next) => {
+                    tracing::warn!("{} unavailable, falling back to {}", model_label(model), next.as_str());
+                    model = Some(next);
+                    depth += 1;
+                }
+                None => break result,
+            }
+        };
+        let mut response = result?;
+        if let (true, Some(used)) = (depth > 0, model) {
+            response.fallback = Some((requested, used));
+        }
+        if self.session_compaction.enabled() {
+            let tokens = TokenCounter::new().count(prompt) + response.output_tokens as usize;
+            if let Err(e) = session_compaction::record_exchange(working_dir, tokens) {
+                tracing::debug!("Failed to track session size: {}", e);
+            }
+        }
+        Ok(response)
     }
 
+    /// The directory chosen with /cd, else `base/user_<id>`
     fn working_dir_for_user(&self, user_id: i64) -> PathBuf {
-        self.base_working_dir.join(format!("user_{}", user_id))
+        self.working_dirs.for_user(user_id)
     }
 
     /// Get remaining daily budget for user
@@ -960,6 +1593,62 @@ impl BotData {
         modes.insert(user_id, enabled);
     }
 
+    /// Check if auto-reflection retry is enabled for a user
+    async fn is_auto_reflect(&self, user_id: i64) -> bool {
+        let modes = self.auto_reflect.read().await;
+        modes.get(&user_id).copied().unwrap_or(false)
+    }
+
+    /// Set auto-reflection retry for a user
+    async fn set_auto_reflect(&self, user_id: i64, enabled: bool) {
+        let mut modes = self.auto_reflect.write().await;
+        modes.insert(user_id, enabled);
+    }
+
+    /// Check if pipeline tracing is enabled for a user
+    async fn is_trace_on(&self, user_id: i64) -> bool {
+        let modes = self.trace_modes.read().await;
+        modes.get(&user_id).copied().unwrap_or(false)
+    }
+
+    /// Set pipeline tracing for a user
+    async fn set_trace(&self, user_id: i64, enabled: bool) {
+        let mut modes = self.trace_modes.write().await;
+        modes.insert(user_id, enabled);
+    }
+
+    /// Remember the language reported by the user's Telegram client
+    async fn note_language_code(&self, user_id: i64, code: Option<&str>) {
+        if let Some(locale) = code.and_then(Locale::from_code) {
+            self.telegram_locales.write().await.insert(user_id, locale);
+        }
+    }
+
+    /// Bot UI language for a user and the catalog key describing where it came from
+    async fn locale_with_source(&self, user_id: i64) -> (Locale, &'static str) {
+        if let Some(locale) = self.chosen_locales.read().await.get(&user_id) {
+            return (*locale, "lang_source_chosen");
+        }
+        match self.telegram_locales.read().await.get(&user_id) {
+            Some(locale) => (*locale, "lang_source_telegram"),
+            None => (Locale::default(), "lang_source_default"),
+        }
+    }
+
+    /// Bot UI language for a user
+    async fn locale(&self, user_id: i64) -> Locale {
+        self.locale_with_source(user_id).await.0
+    }
+
+    /// Set (or clear, to follow Telegram again) a user's bot UI language
+    async fn set_locale(&self, user_id: i64, locale: Option<Locale>) {
+        let mut locales = self.chosen_locales.write().await;
+        match locale {
+            Some(locale) => locales.insert(user_id, locale),
+            None => locales.remove(&user_id),
+        };
+    }
+
     /// Store a pending permission request
     async fn add_pending_permission(&self, request_id: &str, chat_id: i64, tool: &str, description: &str) {
         let mut pending = self.pending_permissions.write().await;
@@ -976,6 +1665,166 @@ impl BotData {
         let mut pending = self.pending_permissions.write().await;
         pending.remove(request_id)
     }
+
+    /// Get and remove a pending permission request, only if it belongs to the chat
+    async fn take_chat_permission(&self, chat_id: i64, request_id: &str) -> Option<PendingPermission> {
+        let mut pending = self.pending_permissions.write().await;
+        if pending.get(request_id)?.chat_id != chat_id {
+            return None;
+        }
+        pending.remove(request_id)
+    }
+
+    /// Most recent pending permission request in a chat, with its age
+    async fn latest_pending_permission(&self, chat_id: i64) -> Option<(String, Duration)> {
+        self.pending_permissions.read().await
+            .iter()
+            .filter(|(_, p)| p.chat_id == chat_id)
+            .max_by_key(|(_, p)| p.created_at)
+            .map(|(id, p)| (id.clone(), p.created_at.elapsed()))
+    }
+}
+
+/// Run the command stored for an approved permission request.
+/// Edits the prompt message when given, otherwise confirms with a new message.
+async fn run_approved_permission(
+    bot: &Bot,
+    data: &BotData,
+    user_id: i64,
+    pending: &PendingPermission,
+    message_id: Option<teloxide::types::MessageId>,
+) {
+    let chat_id = ChatId(pending.chat_id);
+
+    // Get the stored command from UI context
+    let ui_context = data.get_ui_context(pending.chat_id).await;
+    let Some(command) = ui_context.last_command else {
+        let _ = bot.send_message(chat_id, "❌ No pending command found - request may have expired").await;
+        return;
+    };
+
+    let confirmation = format!("✅ Approved: {}\n\nExecuting...", pending.description);
+    let _ = match message_id {
+        Some(message_id) => bot.edit_message_text(chat_id, message_id, confirmation).await,
+        None => bot.send_message(chat_id, confirmation).await,
+    };
+
+    // Execute the command
+    let working_dir = data.working_dir_for_user(user_id);
+    let is_autonomous = matches!(
+        data.permission_manager.get_status(user_id).level,
+        crate::permissions::PermissionLevel::Autonomous
+    );
+
+    match data.invoke_claude(user_id, chat_id.0, &command, &working_dir, is_autonomous).await {
+        Ok(response) => {
+            record_usage(data, user_id, &response, ORIGIN_CHAT);
+            let _ = send_long_message(bot, chat_id, &response.text).await;
+        }
+        Err(e) => {
+            let _ = bot.send_message(chat_id, format!("❌ Error executing command: {}", e)).await;
+        }
+    }
+}
+
+/// Cancel a denied permission request and drop its stored command
+async fn cancel_permission(
+    bot: &Bot,
+    data: &BotData,
+    pending: &PendingPermission,
+    message_id: Option<teloxide::types::MessageId>,
+) {
+    let chat_id = ChatId(pending.chat_id);
+    let confirmation = format!("❌ Denied: {}\n\nOperation cancelled.", pending.description);
+    let _ = match message_id {
+        Some(message_id) => bot.edit_message_text(chat_id, message_id, confirmation).await,
+        None => bot.send_message(chat_id, confirmation).await,
+    };
+
+    data.update_ui_context(pending.chat_id, |ctx| {
+        ctx.last_command = None;
+    }).await;
+}
+
+/// Approve or reject a plan. Returns the confirmation text when the plan
+/// reached that state (None if the engine left it elsewhere).
+async fn resolve_plan(data: &BotData, chat_id: i64, plan_id: &str, approve: bool) -> Result<Option<&'static str>> {
+    let response = if approve { "approve" } else { "reject" };
+    let confirmation = match data.planning_engine.process_approval(plan_id, response).await? {
+        ApprovalState::Approved if approve => {
+            "✅ Plan Approved\n\nReady to execute. Send the task description to begin execution."
+        }
+        ApprovalState::Rejected if !approve => "❌ Plan Rejected\n\nCreate a new plan with /plan <task>",
+        _ => return Ok(None),
+    };
+
+    // Clear pending plan from context
+    data.update_ui_context(chat_id, |ctx| {
+        ctx.pending_plan_id = None;
+    }).await;
+    Ok(Some(confirmation))
+}
+
+/// ID of the most recent permission request or plan awaiting approval in a chat
+async fn latest_pending_approval(data: &BotData, chat_id: i64) -> Option<String> {
+    let permission = data.latest_pending_permission(chat_id).await;
+    let plan = match data.get_ui_context(chat_id).await.pending_plan_id {
+        Some(plan_id) => data.planning_engine.get_plan(&plan_id).await
+            .filter(|plan| plan.approval == ApprovalState::Pending),
+        None => None,
+    };
+
+    match (permission, plan) {
+        (Some((request_id, age)), Some(plan)) => {
+            let plan_age = (chrono::Utc::now().timestamp() - plan.created_at).max(0) as u64;
+            Some(if age.as_secs() <= plan_age { request_id } else { plan.id })
+        }
+        (Some((request_id, _)), None) => Some(request_id),
+        (None, Some(plan)) => Some(plan.id),
+        (None, None) => None,
+    }
+}
+
+/// `/approve [id]` and `/reject [id]` - text fallback for the approval buttons
+async fn handle_approval_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    args: &str,
+    user_id: i64,
+    approve: bool,
+) -> Result<()> {
+    let id = match args.split_whitespace().next() {
+        Some(id) => id.to_string(),
+        None => match latest_pending_approval(data, chat_id.0).await {
+            Some(id) => id,
+            None => {
+                bot.send_message(chat_id, "Nothing is waiting for approval in this chat.").await?;
+                return Ok(());
+            }
+        },
+    };
+
+    if let Some(pending) = data.take_chat_permission(chat_id.0, &id).await {
+        if approve {
+            run_approved_permission(bot, data, user_id, &pending, None).await;
+        } else {
+            cancel_permission(bot, data, &pending, None).await;
+        }
+        return Ok(());
+    }
+    if id.starts_with("perm_") {
+        bot.send_message(chat_id, "Permission request expired or not found").await?;
+        return Ok(());
+    }
+
+    let msg = match resolve_plan(data, chat_id.0, &id, approve).await {
+        Ok(Some(confirmation)) => confirmation.to_string(),
+        Ok(None) => "Plan state updated".to_string(),
+        Err(e) => format!("❌ {}", e),
+    };
+    bot.send_message(chat_id, msg).await?;
+    Ok(())
 }
 
 /// Claude CLI response with usage info
@@ -987,6 +1836,31 @@ struct ClaudeResponse {
     cache_write_tokens: i64,
     model: String,
     session_id: Option<String>,
+    /// Token counts are local estimates (the CLI output wasn't JSON)
+    tokens_estimated: bool,
+    /// (requested, answered by) when the requested model was unavailable;
+    /// requested is None for the CLI's default model
+    fallback: Option<(Option<ModelHint>, ModelHint)>,
+}
+
+impl ClaudeResponse {
+    /// Text for the user, noting when a fallback model answered
+    fn display_text(&self) -> Cow<'_, str> {
+        match self.fallback {
+            Some((requested, used)) => Cow::Owned(format!(
+                "{}\n\n⚠️ {} was unavailable, so {} answered this.",
+                self.text,
+                model_label(requested),
+                used.as_str()
+            )),
+            None => Cow::Borrowed(&self.text),
+        }
+    }
+}
+
+/// Tier name, or "The default model" when the CLI picked it
+fn model_label(model: Option<ModelHint>) -> &'static str {
+    model.map_or("The default model", |m| m.as_str())
 }
 
 /// Process monitoring for Claude CLI execution
@@ -1004,8 +1878,18 @@ const STATUS_UPDATE_INTERVAL_SECS: u64 = 60; // Log "still working" every minute
 
 /// Invoke Claude Code CLI with JSON output for usage tracking
 ///
+/// `model` forces a tier with `--model`; None leaves the CLI's default.
+/// `read_only` runs in `--permission-mode plan` as if in safe mode and without
+/// `CLAUDE_EXTRA_ARGS`, so no tool that needs permission runs.
+///
 /// **NO TIMEOUT**: Tasks run until completion. ProcessingGuard protects active work.
-async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool) -> Result<ClaudeResponse> {
+async fn invoke_claude_cli(
+    prompt: &str,
+    working_dir: &PathBuf,
+    autonomous: bool,
+    model: Option<ModelHint>,
+    read_only: bool,
+) -> Result<ClaudeResponse> {
     let start = Instant::now();
     tracing::debug!("Invoking claude CLI with prompt length: {}, autonomous: {}", prompt.len(), autonomous);
 
@@ -1020,12 +1904,22 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
             cache_write_tokens: 0,
             model: "test-mode".to_string(),
             session_id: None,
+            tokens_estimated: false,
+            fallback: None,
         });
     }
 
     // Check for existing session to resume
     let session_file = working_dir.join(".claude_session");
-    let mut cmd = Command::new("claude");
+    // The first request after a compaction carries the earlier session's summary
+    let seeded = (!session_file.exists())
+        .then(|| session_compaction::pending_seed(working_dir))
+        .flatten()
+        .map(|summary| session_compaction::seeded_prompt(&summary, prompt));
+    let prompt = seeded.as_deref().unwrap_or(prompt);
+    let cli = ClaudeCli::from_env();
+    let cli = if read_only { cli.with_safe_mode(true) } else { cli };
+    let mut cmd = cli.command();
 
     cmd.arg("-p")
         .arg(prompt)
@@ -1033,11 +1927,18 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
         .arg("json")
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped());
+    if let Some(model) = model {
+        cmd.arg("--model").arg(model.as_str());
+    }
 
     // Always skip permission prompts - Telegram bot is non-interactive
-    // and can't respond to permission dialogs (they would hang forever)
-    cmd.arg("--dangerously-skip-permissions");
-    if autonomous {
+    // and can't respond to permission dialogs (they would hang forever).
+    // Safe mode overrides stored permission levels: no autonomous flags at all.
+    if read_only {
+        cmd.arg("--permission-mode").arg("plan");
+    } else if !cli.skip_permissions(&mut cmd, true) {
+        tracing::debug!("Safe mode: not passing autonomous flags");
+    } else if autonomous {
         tracing::info!("Autonomous mode: full access enabled");
     }
 
@@ -1050,11 +1951,14 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
             }
         }
     }
+    if !read_only {
+        cli.add_extra_args(&mut cmd);
+    }
 
     let mut child = cmd
         .current_dir(working_dir)
         .spawn()
-        .context("Failed to spawn claude CLI")?;
+        .with_context(|| format!("Failed to spawn claude CLI ({})", cli.path))?;
 
     // Take stdout/stderr for monitoring
     let stdout = child.stdout.take();
@@ -1151,9 +2055,9 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
 
                 if !status.success() {
                     let hint = OutputParser::extract_error_hint(&all_stderr);
-                    return Err(anyhow::anyhow!(
-                        "{}",
-                        TaskFeedback::format_error(&all_stderr.trim(), hint.as_deref())
+                    return Err(ClassifiedError::error(
+                        FailureReason::NonZeroExit,
+                        TaskFeedback::format_error(&cli_error_detail(&all_stderr, &all_stdout), hint.as_deref()),
                     ));
                 }
 
@@ -1172,9 +2076,9 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
             let status = child.wait().await.context("Failed to wait for claude CLI")?;
             if !status.success() {
                 let hint = OutputParser::extract_error_hint(&all_stderr);
-                return Err(anyhow::anyhow!(
-                    "{}",
-                    TaskFeedback::format_error(&all_stderr.trim(), hint.as_deref())
+                return Err(ClassifiedError::error(
+                    FailureReason::NonZeroExit,
+                    TaskFeedback::format_error(&cli_error_detail(&all_stderr, &all_stdout), hint.as_deref()),
                 ));
             }
             break;
@@ -1183,6 +2087,10 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
 
     // Try to parse JSON output
     match serde_json::from_str::<ClaudeJsonOutput>(&all_stdout) {
+        Ok(json) if json.is_error => Err(ClassifiedError::error(
+            FailureReason::classify_message(&json.result, FailureReason::Other),
+            format!("Claude CLI error: {}", json.result.trim()),
+        )),
         Ok(json) => {
             let usage = json.usage.unwrap_or_default();
 
@@ -1190,6 +2098,9 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
             if let Some(ref sid) = json.session_id {
                 let session_file = working_dir.join(".claude_session");
                 let _ = std::fs::write(&session_file, sid);
+                if seeded.is_some() {
+                    session_compaction::clear_seed(working_dir);
+                }
             }
 
             Ok(ClaudeResponse {
@@ -1200,24 +2111,49 @@ async fn invoke_claude_cli(prompt: &str, working_dir: &PathBuf, autonomous: bool
                 cache_write_tokens: usage.cache_creation_input_tokens,
                 model: json.model.unwrap_or_else(|| "claude-sonnet-4".to_string()),
                 session_id: json.session_id,
+                tokens_estimated: false,
+                fallback: None,
             })
         }
-        Err(_) => {
+        Err(e) => {
             // Fall back to plain text if JSON parsing fails
             let clean = strip_ansi_codes(&all_stdout);
+            tracing::warn!(
+                "Claude CLI output is not JSON ({}); using plain text with estimated tokens. Output starts: {:?}",
+                e,
+                clean.chars().take(200).collect::<String>()
+            );
+            // Estimate instead of recording zeros so the request's cost isn't lost.
+            // Resumed session context isn't visible here, so input is a lower bound.
+            let counter = TokenCounter::new();
             Ok(ClaudeResponse {
+                input_tokens: counter.count(prompt) as i64,
+                output_tokens: counter.count(&clean) as i64,
                 text: clean,
-                input_tokens: 0,
-                output_tokens: 0,
                 cache_read_tokens: 0,
                 cache_write_tokens: 0,
-                model: "unknown".to_string(),
+                model: model.map_or("unknown", |m| m.as_str()).to_string(),
                 session_id: None,
+                tokens_estimated: true,
+                fallback: None,
             })
         }
     }
 }
 
+/// What a failed CLI run reported: stderr, or the JSON `result` on stdout
+/// when stderr is empty (API errors such as overloads are reported there)
+fn cli_error_detail(stderr: &str, stdout: &str) -> String {
+    let stderr = stderr.trim();
+    if !stderr.is_empty() {
+        return stderr.to_string();
+    }
+    serde_json::from_str::<serde_json::Value>(stdout.trim())
+        .ok()
+        .and_then(|json| json.get("result")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| stdout.trim().to_string())
+}
+
 /// Strip ANSI escape codes from CLI output
 fn strip_ansi_codes(s: &str) -> String {
     let re = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
@@ -1231,22 +2167,25 @@ async fn handle_message(bot: Bot, msg: Message, data: Arc<BotData>) -> Result<()
     // Record activity for lifecycle management
     data.lifecycle.record_activity();
 
+    let language_code = msg.from.as_ref().and_then(|u| u.language_code.as_deref());
+    data.note_language_code(user_id, language_code).await;
+    let locale = data.locale(user_id).await;
+
     if !data.is_allowed(user_id) {
         tracing::warn!("Unauthorized user: {}", user_id);
-        bot.send_message(chat_id, "Unauthorized.").await?;
+        bot.send_message(chat_id, i18n::t(locale, "unauthorized")).await?;
         return Ok(());
     }
 
     // Rate limiting check (T3.3 - Security Hardening)
-    if !data.rate_limiter.check(user_id).await {
-        let remaining = data.rate_limiter.remaining(user_id).await;
+    let rate_limit = data.rate_limiter.check(&user_id.to_string()).await;
+    if !rate_limit.allowed {
+        let remaining = rate_limit.remaining;
         tracing::warn!("Rate limit exceeded for user {}", user_id);
-        bot.send_message(chat_id, format!(
-            "⚠️ Rate limit exceeded.\n\
-            Please wait a moment before sending more requests.\n\
-            Remaining: {}/min",
-            remaining
-        )).await?;
+        bot.send_message(
+            chat_id,
+            i18n::tf(locale, "rate_limited", &[("remaining", &remaining.to_string())]),
+        ).await?;
         return Ok(());
     }
 
@@ -1259,7 +2198,11 @@ async fn handle_message(bot: Bot, msg: Message, data: Arc<BotData>) -> Result<()
 
     // Handle text
     if let Some(text) = msg.text() {
-        return handle_text(&bot, chat_id, &data, text, &working_dir, user_id).await;
+        // Acts on the message it replies to, which commands don't see
+        if text.split_whitespace().next() == Some("/bookmark") {
+            return bookmark_replied(&bot, &msg, &data).await;
+        }
+        return handle_text(&bot, chat_id, &data, text, msg.id.0, &working_dir, user_id).await;
     }
 
     // Handle documents
@@ -1277,11 +2220,260 @@ async fn handle_message(bot: Bot, msg: Message, data: Arc<BotData>) -> Result<()
     Ok(())
 }
 
+/// Hold an oversized message and ask how to handle it
+async fn offer_oversize_options(bot: &Bot, chat_id: ChatId, data: &BotData, text: &str, user_id: i64) -> Result<()> {
+    let chars = text.chars().count();
+    let limits = &data.input_limits;
+    let chunks = input_limits::split_chunks(text, limits.chunk_chars).len();
+    data.pending_inputs.write().await.insert((chat_id.0, user_id), text.to_string());
+
+    let mut msg = format!(
+        "📏 This message is {} characters (limit {}, ~{} tokens).\n",
+        chars,
+        limits.max_chars,
+        TokenCounter::format_tokens(data.token_counter.count(text))
+    );
+    if limits.show_cost(text) {
+        let cost = data.token_counter.estimate_cost(text, 1000, &ModelHint::Sonnet, 0.0);
+        msg.push_str(&format!("Estimated cost to send it whole: {}\n", TokenCounter::format_cost(cost)));
+    }
+    msg.push_str(&format!(
+        "\nHow should I handle it?\n\
+        • Truncate - keep the first {} characters\n\
+        • Summarize - condense with Llama, then answer\n\
+        • Chunks - read it in {} parts, then answer once",
+        limits.max_chars, chunks
+    ));
+
+    let button = |label: &str, action: OversizeAction| {
+        teloxide::types::InlineKeyboardButton::callback(label, format!("oversize:{}", action.as_str()))
+    };
+    let keyboard = teloxide::types::InlineKeyboardMarkup::new(vec![
+        vec![
+            button("✂️ Truncate", OversizeAction::Truncate),
+            button("📝 Summarize", OversizeAction::Summarize),
+        ],
+        vec![
+            button(&format!("🧩 {} chunks", chunks), OversizeAction::Chunks),
+            button("❌ Cancel", OversizeAction::Cancel),
+        ],
+    ]);
+    bot.send_message(chat_id, msg).reply_markup(keyboard).await?;
+    Ok(())
+}
+
+/// Process an oversized message the way the user chose
+#[allow(clippy::too_many_arguments)]
+async fn handle_oversized_input(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    text: String,
+    action: OversizeAction,
+    message_id: i32,
+    working_dir: &PathBuf,
+    user_id: i64,
+) -> Result<()> {
+    let limits = &data.input_limits;
+    match action {
+        OversizeAction::Cancel => {
+            bot.send_message(chat_id, "Cancelled.").await?;
+        }
+        OversizeAction::Truncate => {
+            let head = input_limits::truncate_chars(&text, limits.max_chars);
+            bot.send_message(chat_id, format!(
+                "✂️ Using the first {} of {} characters.",
+                head.chars().count(),
+                text.chars().count()
+            )).await?;
+            handle_text(bot, chat_id, data, head, message_id, working_dir, user_id).await?;
+        }
+        OversizeAction::Summarize => {
+            if !data.llama_worker.is_available().await {
+                // Keep the message so another option can still be picked
+                data.pending_inputs.write().await.insert((chat_id.0, user_id), text);
+                bot.send_message(chat_id, "Llama isn't available to summarize. Pick Truncate or Chunks instead.").await?;
+                return Ok(());
+            }
+            bot.send_message(chat_id, "📝 Summarizing with Llama...").await?;
+            let mut summaries = Vec::new();
+            for chunk in input_limits::split_chunks(&text, limits.chunk_chars) {
+                match data.llama_worker.summarize_text(chunk).await {
+                    Ok(summary) => summaries.push(summary.trim().to_string()),
+                    Err(e) => {
+                        bot.send_message(chat_id, format!("Summarizing failed: {}", e)).await?;
+                        return Ok(());
+                    }
+                }
+            }
+            let prompt = format!(
+                "(The user sent a {}-character message; this is a summary of it.)\n\n{}",
+                text.chars().count(),
+                summaries.join("\n\n")
+            );
+            // The header counts too, or the prompt would be offered for shortening again
+            let prompt = input_limits::truncate_chars(&prompt, limits.max_chars);
+            handle_text(bot, chat_id, data, prompt, message_id, working_dir, user_id).await?;
+        }
+        OversizeAction::Chunks => {
+            if let Err(msg) = check_user_limits(data, user_id) {
+                bot.send_message(chat_id, msg).await?;
+                return Ok(());
+            }
+            let chunks = input_limits::split_chunks(&text, limits.chunk_chars);
+            let status = bot.send_message(chat_id, format!("🧩 Reading part 1/{}...", chunks.len())).await?;
+            let mut notes = Vec::with_capacity(chunks.len());
+            for (i, chunk) in chunks.iter().enumerate() {
+                if i > 0 {
+                    let _ = bot.edit_message_text(chat_id, status.id, format!("🧩 Reading part {}/{}...", i + 1, chunks.len())).await;
+                }
+                let prompt = input_limits::chunk_prompt(i + 1, chunks.len(), chunk);
+                // Read outside the session; only the combined answer belongs in it
+                let scratch = scratch_dir(data, &format!(".chunks_{}_{}", chat_id.0, user_id)).await?;
+                match data.invoke_claude_read_only(user_id, chat_id.0, &prompt, &scratch).await {
+                    Ok(response) => {
+                        record_usage(data, user_id, &response, ORIGIN_CHAT);
+                        notes.push(response.text);
+                    }
+                    Err(e) => {
+                        bot.send_message(chat_id, format!("Reading part {} failed: {}", i + 1, e)).await?;
+                        return Ok(());
+                    }
+                }
+            }
+            let _ = bot.edit_message_text(chat_id, status.id, format!("🧩 Read {} parts, answering...", chunks.len())).await;
+
+            let is_autonomous = matches!(
+                data.permission_manager.get_status(user_id).level,
+                crate::permissions::PermissionLevel::Autonomous
+            );
+            match data.invoke_claude(user_id, chat_id.0, &input_limits::combine_prompt(&notes), working_dir, is_autonomous).await {
+                Ok(response) => {
+                    record_usage(data, user_id, &response, ORIGIN_CHAT);
+                    send_long_message(bot, chat_id, &response.text).await?;
+                }
+                Err(e) => {
+                    bot.send_message(chat_id, format!("Combining the parts failed: {}", e)).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Summarize a file too large for one Claude call: each chunk is summarized
+/// by Llama (or Haiku), then the summaries are merged down to one
+async fn summarize_file(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    path: &str,
+    working_dir: &Path,
+    user_id: i64,
+) -> Result<()> {
+    let config = &data.file_summary;
+    let full_path = match data.working_dirs.resolve_file(user_id, working_dir, path) {
+        Ok(full_path) => full_path,
+        Err(e) => {
+            bot.send_message(chat_id, format!("Can't read {}: {}", path, e)).await?;
+            return Ok(());
+        }
+    };
+    match tokio::fs::metadata(&full_path).await {
+        Ok(meta) if !meta.is_file() => {
+            bot.send_message(chat_id, format!("{} isn't a file.", path)).await?;
+            return Ok(());
+        }
+        Ok(meta) if meta.len() > config.max_file_bytes => {
+            bot.send_message(chat_id, format!(
+                "{} is too large ({:.1} MB, limit {:.1} MB).",
+                path,
+                meta.len() as f64 / 1_048_576.0,
+                config.max_file_bytes as f64 / 1_048_576.0
+            )).await?;
+            return Ok(());
+        }
+        Ok(_) => {}
+        Err(e) => {
+            bot.send_message(chat_id, format!("Can't read {}: {}", path, e)).await?;
+            return Ok(());
+        }
+    }
+    let bytes = tokio::fs::read(&full_path).await?;
+    let text = String::from_utf8_lossy(&bytes);
+
+    let use_llama = data.llama_worker.is_available().await;
+    if !use_llama {
+        if let Err(msg) = check_user_limits(data, user_id) {
+            bot.send_message(chat_id, msg).await?;
+            return Ok(());
+        }
+    }
+    let summarizer = if use_llama { "Llama" } else { "Haiku" };
+    let counter = TokenCounter::new();
+    let max_tokens = config.chunk_tokens_for(use_llama);
+    let chunks = file_summary::split_by_tokens(&text, &counter, max_tokens);
+    let file_name = Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+    let status = bot.send_message(chat_id, format!(
+        "📄 Summarizing {} ({} tokens) in {} chunk(s) with {}...",
+        file_name,
+        TokenCounter::format_tokens(counter.count(&text)),
+        chunks.len(),
+        summarizer
+    )).await?;
+
+    // Each Haiku call gets a fresh CLI session in a scratch directory of its
+    // own under the system temp dir, so the chunks don't pile up in the chat
+    // session and nothing in the project is touched
+    let scratch = std::env::temp_dir().join(format!("claudebot-summarize-{}", uuid::Uuid::new_v4()));
+    let scratch_dir = &scratch;
+    let result = file_summary::map_reduce(&file_name, &chunks, &counter, max_tokens, move |prompt| async move {
+        if use_llama {
+            return Ok((data.llama_worker.generate(&prompt).await?, 0.0));
+        }
+        // A long file can run the user past their limit partway through
+        check_user_limits(data, user_id).map_err(anyhow::Error::msg)?;
+        tokio::fs::create_dir_all(scratch_dir).await?;
+        let _ = tokio::fs::remove_file(scratch_dir.join(".claude_session")).await;
+        let response = data
+            .invoke_claude_with_model(user_id, chat_id.0, &prompt, scratch_dir, false, Some(ModelHint::Haiku))
+            .await?;
+        record_usage(data, user_id, &response, ORIGIN_BACKGROUND);
+        let cost = response_cost(&response);
+        Ok((response.text, cost))
+    })
+    .await;
+    let _ = tokio::fs::remove_dir_all(&scratch).await;
+
+    match result {
+        Ok(result) => {
+            let _ = bot.edit_message_text(chat_id, status.id, format!("📄 Summarized {}.", file_name)).await;
+            send_long_message(bot, chat_id, &result.summary).await?;
+            bot.send_message(chat_id, format!(
+                "{} chunk(s), {} merge round(s), {} {} call(s), cost {}",
+                result.chunks,
+                result.reduce_rounds,
+                result.calls,
+                summarizer,
+                if use_llama { "$0 (local)".to_string() } else { TokenCounter::format_cost(result.cost_usd) }
+            )).await?;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, format!("Summarizing {} failed: {:#}", file_name, e)).await?;
+        }
+    }
+    Ok(())
+}
+
 async fn handle_text(
     bot: &Bot,
     chat_id: ChatId,
     data: &BotData,
     text: &str,
+    message_id: i32,
     working_dir: &PathBuf,
     user_id: i64,
 ) -> Result<()> {
@@ -1290,6 +2482,11 @@ async fn handle_text(
         return handle_command(bot, chat_id, data, text, working_dir, user_id).await;
     }
 
+    // Oversized messages wait for the user to pick truncate/summarize/chunks
+    if data.input_limits.exceeds(text) {
+        return offer_oversize_options(bot, chat_id, data, text, user_id).await;
+    }
+
     // Get UI context for this chat
     let ui_ctx = data.get_ui_context(chat_id.0).await;
 
@@ -1302,9 +2499,9 @@ async fn handle_text(
                     data.permission_manager.get_status(user_id).level,
                     crate::permissions::PermissionLevel::Autonomous
                 );
-                match invoke_claude_cli(&cmd, working_dir, is_autonomous).await {
+                match data.invoke_claude(user_id, chat_id.0, &cmd, working_dir, is_autonomous).await {
                     Ok(response) => {
-                        record_usage(data, user_id, &response);
+                        record_usage(data, user_id, &response, ORIGIN_CHAT);
                         send_long_message(bot, chat_id, &response.text).await?;
                     }
                     Err(e) => {
@@ -1322,9 +2519,9 @@ async fn handle_text(
                     data.permission_manager.get_status(user_id).level,
                     crate::permissions::PermissionLevel::Autonomous
                 );
-                match invoke_claude_cli(&fix_prompt, working_dir, is_autonomous).await {
+                match data.invoke_claude(user_id, chat_id.0, &fix_prompt, working_dir, is_autonomous).await {
                     Ok(response) => {
-                        record_usage(data, user_id, &response);
+                        record_usage(data, user_id, &response, ORIGIN_CHAT);
                         send_long_message(bot, chat_id, &response.text).await?;
                     }
                     Err(e) => {
@@ -1341,8 +2538,16 @@ async fn handle_text(
                 return Ok(());
             }
             Intent::Confirm => {
-                data.update_ui_context(chat_id.0, |ctx| ctx.clear_confirmation()).await;
-                bot.send_message(chat_id, "Confirmed.").await?;
+                let mut pending = None;
+                data.update_ui_context(chat_id.0, |ctx| pending = ctx.take_confirmation()).await;
+                match pending {
+                    Some(command) => {
+                        run_confirmed_command(bot, chat_id, data, &command, working_dir, user_id).await?;
+                    }
+                    None => {
+                        bot.send_message(chat_id, "Confirmed.").await?;
+                    }
+                }
                 return Ok(());
             }
             Intent::Deny => {
@@ -1380,6 +2585,10 @@ async fn handle_text(
 
     // Phase 6: Expand context references (e.g., "that file" -> actual path)
     let expanded_text = ContextParser::expand(text, &ui_ctx);
+    let mut trace = PipelineTrace::new(data.is_trace_on(user_id).await);
+    if expanded_text != text {
+        trace.add("input", format!("references expanded: {}", expanded_text));
+    }
 
     // ===== Phase 7: Autonomous Processing (non-blocking) =====
     // Runs in background to not block the response
@@ -1393,14 +2602,17 @@ async fn handle_text(
     let (extracted_goals, completed_goals) = tokio::join!(goals_fut, completion_fut);
     if !extracted_goals.is_empty() {
         tracing::info!("Auto-extracted {} goals from message", extracted_goals.len());
+        trace.add("learning", format!("goals extracted: {}", extracted_goals.len()));
     }
     if !completed_goals.is_empty() {
         tracing::info!("Auto-completed {} goals", completed_goals.len());
+        trace.add("learning", format!("goals completed: {}", completed_goals.len()));
     }
 
     // 7c. Detect user corrections (sync detection, async learning)
     if let Some(correction) = data.feedback_loop.detect_correction(&expanded_text) {
         tracing::info!("Detected correction: {}", &correction[..correction.len().min(50)]);
+        trace.add("learning", format!("correction detected: {}", correction));
         let recent_memory_ids: Vec<String> = Vec::new();
         if let Err(e) = data.feedback_loop.learn_correction(
             &correction,
@@ -1415,6 +2627,7 @@ async fn handle_text(
     // 7d. Simple preference detection (no LLM needed)
     // The autonomous_learner has a fast pattern-based preference detector
     if let Some(pref) = data.autonomous_learner.detect_preference_sync(&expanded_text, user_id) {
+        trace.add("learning", format!("preference detected: {}", pref.content));
         if let Err(e) = data.autonomous_learner.store_facts(&[pref], user_id, &data.memory_store).await {
             tracing::debug!("Failed to store preference: {}", e);
         }
@@ -1440,6 +2653,27 @@ async fn handle_text(
         tracing::warn!("Preflight warnings for user {}: {}", user_id, warnings);
     }
 
+    // Frequent prompts answered ahead of time by cache warming
+    if data.cache_warm.enabled && data.cache_warm.is_warmable(&expanded_text) {
+        if let Some(cached) = data.response_cache.get(&ResponseCache::frequent_query_key(&expanded_text, chat_id.0)).await {
+            tracing::info!("Serving cached answer to frequent prompt for user {}", user_id);
+            // Cached raw, so the transforms in force now apply, as for fresh answers
+            let content = if data.post_processor.is_empty() {
+                cached.content
+            } else {
+                data.post_processor.apply(&cached.content).await
+            };
+            store_conversation_exchange(data, chat_id.0, Some(message_id), text, &content, None);
+            send_long_message(bot, chat_id, &format!(
+                "{}\n\n⚡ Cached answer to a frequent question (rephrase for a fresh one)",
+                content
+            )).await?;
+            trace.add("cache", "served a warmed answer to a frequent prompt; no model ran");
+            send_trace(bot, chat_id, &trace).await;
+            return Ok(());
+        }
+    }
+
     // Send typing indicator
     bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
 
@@ -1449,6 +2683,7 @@ async fn handle_text(
     }).await;
 
     // Build enriched context using ContextManager (with HyDE, goals, identity)
+    let context_started = Instant::now();
     let enriched_context = data.context_manager.build_context(
         &expanded_text,
         user_id,
@@ -1465,23 +2700,44 @@ async fn handle_text(
         .iter()
         .map(|m| m.entry.id.clone())
         .collect();
-    data.feedback_loop.record_retrieval(&retrieved_memory_ids).await;
+    let retrieval_id = data.feedback_loop.record_retrieval(&retrieved_memory_ids).await;
 
     // Build enhanced prompt with enriched context
     let context_str = enriched_context.format_for_prompt();
-    let enhanced_prompt = if context_str.is_empty() {
+    let mut enhanced_prompt = if context_str.is_empty() {
         expanded_text.clone()
     } else {
         format!("{}{}", expanded_text, context_str)
     };
 
+    // Running /experiment: the user's variant instruction goes after the context
+    let experiment_variant = data.feedback_loop.experiments().assign(user_id);
+    if let Some((_, instruction)) = experiment_variant.as_ref().filter(|(_, i)| !i.is_empty()) {
+        enhanced_prompt.push_str("\n\n");
+        enhanced_prompt.push_str(instruction);
+    }
+
     tracing::debug!(
-        "Context: {} memories, {} entities, {} goals, ~{} tokens",
+        "Context: {} memories, {} entities, {} connected, {} goals, ~{} tokens",
         enriched_context.memories.len(),
         enriched_context.entities.len(),
+        enriched_context.connected.len(),
         enriched_context.goals.len(),
         enriched_context.estimated_tokens
     );
+    if trace.enabled() {
+        trace_context(&mut trace, &enriched_context, &context_str, context_started.elapsed());
+        if let Some((variant, instruction)) = &experiment_variant {
+            trace.add("context", format!("experiment variant {}: {}", variant.as_str(), instruction));
+        }
+    }
+    if data.context_manager.config().notify_trimmed && !enriched_context.trimmed.is_empty() {
+        bot.send_message(chat_id, format!(
+            "✂️ Context trimmed to {} tokens: dropped {}",
+            data.context_manager.config().max_context_tokens,
+            enriched_context.trimmed.summary()
+        )).await?;
+    }
 
     // Pre-flight token estimation
     let remaining_budget = data.get_remaining_budget(user_id);
@@ -1493,14 +2749,27 @@ async fn handle_text(
         0.5, // Assumed cache hit ratio
     );
 
-    match &budget_check {
-        BudgetCheck::Warning { estimated_cost, remaining_budget, .. } => {
+    trace.add("budget", match &budget_check {
+        BudgetCheck::Ok { estimated_cost, estimated_tokens } => {
+            format!("ok: ~{} tokens, est ${:.4}, remaining ${:.2}", estimated_tokens, estimated_cost, remaining_budget)
+        }
+        BudgetCheck::Warning { estimated_cost, estimated_tokens, .. } => {
+            format!("warning: ~{} tokens, est ${:.4}, remaining ${:.2}", estimated_tokens, estimated_cost, remaining_budget)
+        }
+        BudgetCheck::Exceeded { estimated_cost, estimated_tokens, .. } => {
+            format!("exceeded: ~{} tokens, est ${:.4}, remaining ${:.2}", estimated_tokens, estimated_cost, remaining_budget)
+        }
+    });
+
+    match &budget_check {
+        BudgetCheck::Warning { estimated_cost, remaining_budget, .. } => {
             tracing::warn!(
                 "User {} approaching budget: est ${:.4}, remaining ${:.2}",
                 user_id, estimated_cost, remaining_budget
             );
         }
         BudgetCheck::Exceeded { estimated_cost, remaining_budget, .. } => {
+            data.metrics.record_failure(FailureReason::BudgetExceeded);
             bot.send_message(
                 chat_id,
                 format!(
@@ -1509,6 +2778,7 @@ async fn handle_text(
                     estimated_cost, remaining_budget
                 )
             ).await?;
+            send_trace(bot, chat_id, &trace).await;
             return Ok(());
         }
         BudgetCheck::Ok { .. } => {}
@@ -1565,20 +2835,73 @@ async fn handle_text(
             .reply_markup(keyboard)
             .await?;
 
+            trace.add("permissions", format!("waiting for approval of: {}", predicted_ops.join(", ")));
+            send_trace(bot, chat_id, &trace).await;
             return Ok(());
         }
     }
 
-    // Process with Claude Code CLI
-    let result = invoke_claude_cli(&enhanced_prompt, working_dir, is_autonomous).await;
+    // With CLAUDEBOT_ROUTE_MODEL the routed tier (after the code floor) picks the
+    // model; otherwise the route is only classified, alongside, for /model stats
+    let claude_started = Instant::now();
+    let (result, route) = if data.router.selects_model() {
+        let route = data.router.route_with_worker(&expanded_text, &data.llama_worker).await;
+        let result = data
+            .invoke_claude_with_model(user_id, chat_id.0, &enhanced_prompt, working_dir, is_autonomous, Some(route.model))
+            .await;
+        (result, route)
+    } else {
+        tokio::join!(
+            data.invoke_claude(user_id, chat_id.0, &enhanced_prompt, working_dir, is_autonomous),
+            data.router.route_with_worker(&expanded_text, &data.llama_worker),
+        )
+    };
+    trace.add("route", format!(
+        "{} → {} ({}, {:.0}% confidence)",
+        route.target.as_str(),
+        route.model.as_str(),
+        route.complexity.as_str(),
+        route.confidence * 100.0
+    ));
+    trace.add("route", format!("reasoning: {}", route.reasoning));
 
     match result {
-        Ok(response) => {
+        Ok(mut response) => {
             // Record usage
-            record_usage(data, user_id, &response);
+            record_usage(data, user_id, &response, ORIGIN_CHAT);
+            let cost = response_cost(&response);
+            if trace.enabled() {
+                trace_response(&mut trace, &response, cost, claude_started.elapsed());
+            }
+
+            // Deployment transforms; history and learning see what the user sees
+            if !data.post_processor.is_empty() {
+                let processed = data.post_processor.apply(&response.text).await;
+                if processed != response.text {
+                    trace.add("postprocess", format!("{} → {} chars", response.text.len(), processed.len()));
+                    response.text = processed;
+                }
+            }
+            // Cost belongs to the model that answered: the CLI default, or a fallback's
+            let mut answered = route.clone();
+            if let Some((_, used)) = response.fallback {
+                answered.model = used;
+            } else if !data.router.selects_model() {
+                answered.model = ModelHint::from_model_name(&response.model);
+            }
+            data.router.record_decision(&answered, Some(cost));
+            data.update_ui_context(chat_id.0, |ctx| ctx.set_result(&response.model, cost)).await;
+            if let Some((variant, _)) = experiment_variant {
+                data.feedback_loop.experiments().record_response(&retrieval_id, variant, cost);
+            }
 
             // Store conversation exchange (user message + assistant response)
-            store_conversation_exchange(data, chat_id.0, text, &response.text);
+            let usage = TurnUsage {
+                input_tokens: response.input_tokens,
+                output_tokens: response.output_tokens,
+                cost_usd: cost,
+            };
+            store_conversation_exchange(data, chat_id.0, Some(message_id), text, &response.text, Some(usage));
 
             // Extract file paths mentioned in response for context
             if let Some(file_path) = extract_file_path(&response.text) {
@@ -1586,21 +2909,71 @@ async fn handle_text(
             }
 
             // Send response FIRST - don't block on slow background tasks
-            send_long_message(bot, chat_id, &response.text).await?;
+            let (first_id, sent) = send_long_message_parts(bot, chat_id, &response.display_text()).await?;
+            if let Err(e) = data.conversation_store.set_reply_messages(chat_id.0, message_id.into(), first_id.0.into(), sent.id.0.into()) {
+                tracing::debug!("Failed to record reply message ids: {}", e);
+            }
+
+            // Thumbs-up/down on substantive responses feeds back into memory confidence
+            if data.reflection_engine.should_evaluate(&response.text, false) {
+                if let Err(e) = bot
+                    .edit_message_reply_markup(chat_id, sent.id)
+                    .reply_markup(feedback_keyboard(&retrieval_id))
+                    .await
+                {
+                    tracing::debug!("Failed to attach feedback buttons: {}", e);
+                }
+            }
 
             // Extract facts for continuous learning (AFTER sending response)
             // This can be slow due to Ollama calls, so we do it after the user sees the response
-            extract_and_learn_facts_async(data, &response.text, user_id).await;
+            let facts = extract_and_learn_facts_async(data, &response.text, user_id).await;
+            trace.add("learning", format!("facts extracted from the response: {}", facts));
+
+            if let Err(e) = maybe_compact_session(bot, chat_id, data, working_dir).await {
+                tracing::warn!("Session compaction failed: {}", e);
+            }
 
-            // Phase 8: Reflection-based quality evaluation (non-blocking background task)
+            // Dataset example, recorded once its reflection score is known
+            let example = data.dataset.accepts(chat_id.0).then(|| DatasetExample {
+                id: retrieval_id.clone(),
+                prompt: sanitize_for_storage(&expanded_text),
+                context: (!context_str.is_empty()).then(|| sanitize_for_storage(&context_str)),
+                response: sanitize_for_storage(&response.text),
+                model: response.model.clone(),
+                input_tokens: response.input_tokens,
+                output_tokens: response.output_tokens,
+                cache_read_tokens: response.cache_read_tokens,
+                cache_write_tokens: response.cache_write_tokens,
+                quality: None,
+                timestamp: chrono::Utc::now().timestamp(),
+            });
+
+            // Phase 8: Reflection-based quality evaluation
             // Only evaluate substantive responses, not simple commands
-            if data.reflection_engine.should_evaluate(&response.text, false) {
+            if data.reflection_engine.should_evaluate(&response.text, false) && data.is_auto_reflect(user_id).await {
+                // Opt-in: evaluate inline and re-run once if the answer scored low
+                let quality = auto_improve_response(bot, chat_id, data, user_id, (message_id, text), &enhanced_prompt, &response.text, working_dir, is_autonomous).await?;
+                if let Some(quality) = quality {
+                    data.feedback_loop.experiments().record_quality(&retrieval_id, quality);
+                }
+                trace.add("learning", match quality {
+                    Some(quality) => format!("reflection: quality {:.0}% (auto)", quality * 100.0),
+                    None => "reflection: skipped (evaluation failed)".to_string(),
+                });
+                record_dataset_example(&data.dataset, chat_id.0, example, quality);
+            } else if data.reflection_engine.should_evaluate(&response.text, false) {
+                trace.add("learning", "reflection: running in the background (score is logged)");
+                // Non-blocking background task, only logs suggestions
                 let reflection_prompt = enhanced_prompt.clone();
                 let reflection_response = response.text.clone();
                 let reflection_engine = data.reflection_engine.clone();
                 let llama = data.llama_worker.clone();
+                let dataset = Arc::clone(&data.dataset);
+                let experiments = data.feedback_loop.experiments().clone();
+                let experiment_id = retrieval_id.clone();
                 tokio::spawn(async move {
-                    match reflection_engine.evaluate(&reflection_prompt, &reflection_response, &llama).await {
+                    let quality = match reflection_engine.evaluate(&reflection_prompt, &reflection_response, &llama).await {
                         Ok(score) => {
                             if score.should_retry {
                                 tracing::info!(
@@ -1611,24 +2984,34 @@ async fn handle_text(
                             } else {
                                 tracing::debug!("Reflection: response quality {:.2}", score.overall);
                             }
+                            Some(score.overall)
                         }
                         Err(e) => {
                             tracing::debug!("Reflection evaluation skipped: {}", e);
+                            None
                         }
+                    };
+                    if let Some(quality) = quality {
+                        experiments.record_quality(&experiment_id, quality);
                     }
+                    record_dataset_example(&dataset, chat_id.0, example, quality);
                 });
+            } else {
+                trace.add("learning", "reflection: skipped (short or trivial response)");
+                record_dataset_example(&data.dataset, chat_id.0, example, None);
             }
         }
         Err(e) => {
             // Store error in context for "fix it" support
             let error_msg = e.to_string();
+            trace.add("model", format!("failed after {:.1}s: {}", claude_started.elapsed().as_secs_f64(), error_msg));
             data.update_ui_context(chat_id.0, |ctx| ctx.set_error(&error_msg)).await;
 
             // Store failed attempt in conversation history for context continuity
             // This ensures the next Claude invocation knows what was attempted
             // Note: Tasks run until completion with NO timeout - failures are from crashes/errors only
             let failure_context = format!("[Task failed: {}]", error_msg.lines().next().unwrap_or("unknown error"));
-            store_conversation_exchange(data, chat_id.0, text, &failure_context);
+            store_conversation_exchange(data, chat_id.0, Some(message_id), text, &failure_context, None);
 
             // Send friendly error message with hints
             let friendly_msg = format_friendly_error(&error_msg);
@@ -1636,9 +3019,205 @@ async fn handle_text(
         }
     }
 
+    send_trace(bot, chat_id, &trace).await;
     Ok(())
 }
 
+/// Memories listed by a trace, best first
+const TRACE_MEMORIES: usize = 5;
+
+/// What `build_context` retrieved and added to the prompt
+fn trace_context(trace: &mut PipelineTrace, context: &EnrichedContext, context_str: &str, elapsed: Duration) {
+    trace.add("context", format!(
+        "{} strategy, built in {}ms{}: ~{} tokens, {} messages, {} memories, {} entities, {} connected facts, {} goals{}",
+        context.strategy.as_str(),
+        elapsed.as_millis(),
+        if context.hyde_used { " with HyDE" } else { "" },
+        context.estimated_tokens,
+        context.conversation.len(),
+        context.memories.len(),
+        context.entities.len(),
+        context.connected.len(),
+        context.goals.len(),
+        if context.identity.is_some() { ", identity" } else { "" }
+    ));
+    if !context.trimmed.is_empty() {
+        trace.add("context", format!("trimmed: {}", context.trimmed.summary()));
+    }
+    for m in context.memories.iter().take(TRACE_MEMORIES) {
+        trace.add("memories", format!(
+            "{:.3} (bm25 {:.2}, vector {:.2}) [{}] {}",
+            m.score, m.keyword_score, m.vector_score, m.entry.category, m.entry.content
+        ));
+    }
+    if context.memories.len() > TRACE_MEMORIES {
+        trace.add("memories", format!("… {} more", context.memories.len() - TRACE_MEMORIES));
+    }
+    for f in &context.connected {
+        trace.add("graph", format!("{:.2} via {}: {}", f.weight, f.path, f.entry.content));
+    }
+    if !context_str.is_empty() {
+        trace.add("context", format!("added to prompt: {}", context_str));
+    }
+}
+
+/// Which model answered and what it used
+fn trace_response(trace: &mut PipelineTrace, response: &ClaudeResponse, cost: f64, elapsed: Duration) {
+    trace.add("model", format!("{} answered in {:.1}s", response.model, elapsed.as_secs_f64()));
+    if let Some((requested, used)) = response.fallback {
+        trace.add("model", format!("fallback: {} unavailable, {} used", model_label(requested), used.as_str()));
+    }
+    trace.add("model", format!(
+        "tokens: {} in, {} out, {} cache read, {} cache write{}; ${:.4}",
+        response.input_tokens,
+        response.output_tokens,
+        response.cache_read_tokens,
+        response.cache_write_tokens,
+        if response.tokens_estimated { " (estimated)" } else { "" },
+        cost
+    ));
+}
+
+/// Send the trace as a follow-up; a trace that fails to send is only logged
+async fn send_trace(bot: &Bot, chat_id: ChatId, trace: &PipelineTrace) {
+    if trace.is_empty() {
+        return;
+    }
+    if let Err(e) = bot.send_message(chat_id, trace.to_html()).parse_mode(ParseMode::Html).await {
+        tracing::warn!("Failed to send pipeline trace: {}", e);
+    }
+}
+
+/// `/experiment start <name> <A> | <B>`, `stop`, `stats`
+///
+/// `-` as an instruction makes that variant the unchanged control prompt.
+/// Starting and stopping are admin-only.
+fn handle_experiment_command(data: &BotData, user_id: i64, args: &str) -> String {
+    const USAGE: &str = "Usage:\n\
+        /experiment start <name> <instruction A> | <instruction B>\n\
+        /experiment stats\n\
+        /experiment stop\n\n\
+        Use - as an instruction for the unchanged prompt.";
+    let experiments = data.feedback_loop.experiments();
+    let (sub, rest) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+    if matches!(sub, "start" | "stop") && !data.is_admin(user_id) {
+        return "Starting or stopping experiments requires admin permission (CLAUDEBOT_ADMIN_USERS).".to_string();
+    }
+    match sub {
+        "start" => {
+            let (name, variants) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+            let Some((a, b)) = variants.split_once('|') else {
+                return USAGE.to_string();
+            };
+            let instruction = |s: &str| if s.trim() == "-" { String::new() } else { s.trim().to_string() };
+            let (a, b) = (instruction(a), instruction(b));
+            if name.is_empty() || a == b {
+                return "An experiment needs a name and two different instructions.".to_string();
+            }
+            experiments.start(name, &a, &b);
+            format!("🧪 Experiment '{}' started. Each user gets variant A or B until /experiment stop.", name)
+        }
+        "stats" | "" => match (experiments.stats(), experiments.last_result()) {
+            (Some(stats), _) => stats.format(),
+            (None, Some(last)) => format!("No experiment running. Last results:\n\n{}\n\n{}", last.format(), USAGE),
+            (None, None) => format!("No experiment running.\n\n{}", USAGE),
+        },
+        "stop" => match experiments.stop() {
+            Some(stats) => format!("Experiment stopped. Final results:\n\n{}", stats.format()),
+            None => "No experiment running.".to_string(),
+        },
+        _ => USAGE.to_string(),
+    }
+}
+
+/// Append a dataset example with its reflection score, if one was prepared
+fn record_dataset_example(dataset: &DatasetSink, chat_id: i64, example: Option<DatasetExample>, quality: Option<f64>) {
+    let Some(mut example) = example else {
+        return;
+    };
+    example.quality = quality;
+    if let Err(e) = dataset.record_example(chat_id, &example) {
+        tracing::warn!("Failed to record dataset example: {}", e);
+    }
+}
+
+/// Evaluate a sent response and, if it scored below the retry threshold,
+/// re-run the prompt once with the improvement suggestions and send the result
+///
+/// The retry is skipped when the user is over their limits. An improved answer
+/// replaces the original in `exchange`'s stored history (message id and text).
+/// Returns the original response's quality score, if it could be evaluated.
+#[allow(clippy::too_many_arguments)]
+async fn auto_improve_response(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    user_id: i64,
+    exchange: (i32, &str),
+    prompt: &str,
+    response: &str,
+    working_dir: &PathBuf,
+    is_autonomous: bool,
+) -> Result<Option<f64>> {
+    let quality = match data.reflection_engine.evaluate(prompt, response, &data.llama_worker).await {
+        Ok(quality) => quality,
+        Err(e) => {
+            tracing::debug!("Reflection evaluation skipped: {}", e);
+            return Ok(None);
+        }
+    };
+    if !data.reflection_engine.should_auto_retry(&quality) {
+        tracing::debug!("Reflection: response quality {:.2}", quality.overall);
+        return Ok(Some(quality.overall));
+    }
+
+    if let Err(limit) = check_user_limits(data, user_id) {
+        tracing::info!("Reflection: quality {:.2}, not retrying: {}", quality.overall, limit);
+        return Ok(Some(quality.overall));
+    }
+
+    tracing::info!(
+        "Reflection: response quality {:.2}, auto-retrying with improvements: {:?}",
+        quality.overall,
+        quality.improvements
+    );
+    let reason = if quality.below_min.is_empty() {
+        String::new()
+    } else {
+        let dims: Vec<&str> = quality.below_min.iter().map(|d| d.as_str()).collect();
+        format!(" ({} below minimum)", dims.join(", "))
+    };
+    bot.send_message(chat_id, format!(
+        "🔁 That answer scored {:.0}% on self-review{}. Retrying once with improvements...",
+        quality.overall * 100.0,
+        reason
+    )).await?;
+
+    let retry_prompt = data.reflection_engine.retry_prompt(prompt, response, &quality);
+    match data.invoke_claude(user_id, chat_id.0, &retry_prompt, working_dir, is_autonomous).await {
+        Ok(mut improved) => {
+            record_usage(data, user_id, &improved, ORIGIN_REFLECTION);
+            if !data.post_processor.is_empty() {
+                improved.text = data.post_processor.apply(&improved.text).await;
+            }
+            // Follow-ups should build on the answer the user was given last
+            let (message_id, user_text) = exchange;
+            let usage = TurnUsage {
+                input_tokens: improved.input_tokens,
+                output_tokens: improved.output_tokens,
+                cost_usd: response_cost(&improved),
+            };
+            store_conversation_exchange(data, chat_id.0, Some(message_id), user_text, &improved.text, Some(usage));
+            send_long_message(bot, chat_id, &format!("✨ Auto-improved answer:\n\n{}", improved.text)).await?;
+        }
+        Err(e) => {
+            tracing::warn!("Reflection retry failed: {}", e);
+            bot.send_message(chat_id, "Retry failed - keeping the original answer.").await?;
+        }
+    }
+    Ok(Some(quality.overall))
+}
+
 /// Format error messages with friendly hints for common issues
 fn format_friendly_error(error: &str) -> String {
     let error_lower = error.to_lowercase();
@@ -1761,12 +3340,14 @@ fn format_circle_result(result: &PipelineResult) -> String {
         Mode: {:?}\n\
         Success: {}\n\
         Revisions: {}\n\
-        Duration: {}ms\n",
+        Duration: {}ms\n\
+        Cost: ${:.4}\n",
         result.feature,
         result.mode,
         if result.success { "YES" } else { "NO" },
         result.revisions,
-        result.total_duration_ms
+        result.total_duration_ms,
+        result.total_cost_usd()
     );
 
     if let Some(ref blocked) = result.blocked_at {
@@ -1777,10 +3358,12 @@ fn format_circle_result(result: &PipelineResult) -> String {
 
     for phase in &result.phases {
         msg.push_str(&format!(
-            "\n[{}] {} ({}ms)\n",
+            "\n[{}] {} ({}ms, {}, ${:.4})\n",
             phase.phase,
             phase.persona,
-            phase.duration_ms
+            phase.duration_ms,
+            phase.model,
+            phase.cost_usd
         ));
 
         if let Some(ref verdict) = phase.verdict {
@@ -1824,7 +3407,8 @@ fn check_user_limits(data: &BotData, user_id: i64) -> std::result::Result<(), St
     }
 }
 
-fn record_usage(data: &BotData, user_id: i64, response: &ClaudeResponse) {
+/// Record token usage, attributed to the feature that issued the request
+fn record_usage(data: &BotData, user_id: i64, response: &ClaudeResponse, origin: &str) {
     if response.input_tokens > 0 || response.output_tokens > 0 {
         let record = UsageRecord {
             user_id,
@@ -1834,6 +3418,8 @@ fn record_usage(data: &BotData, user_id: i64, response: &ClaudeResponse) {
             cache_write_tokens: response.cache_write_tokens,
             model: response.model.clone(),
             timestamp: chrono::Utc::now().timestamp(),
+            origin: origin.to_string(),
+            estimated: response.tokens_estimated,
         };
 
         if let Err(e) = data.usage_tracker.record_usage(&record) {
@@ -1842,6 +3428,273 @@ fn record_usage(data: &BotData, user_id: i64, response: &ClaudeResponse) {
     }
 }
 
+/// Calls and failures kept for the `/metrics` windows (oldest dropped first)
+const METRICS_HISTORY: usize = 10_000;
+
+/// `/metrics`: call volume, latency and failures by reason
+fn format_metrics(metrics: &MetricsCollector) -> String {
+    let quick = metrics.quick_stats();
+    let latency = metrics.latency_stats();
+    format!(
+        "📈 Claude Calls\n\n\
+        Succeeded since start: {} (${:.4})\n\
+        Latency p50/p90/max: {}/{}/{} ms\n\n\
+        {}",
+        quick.total_requests,
+        quick.total_cost_usd,
+        latency.p50_ms,
+        latency.p90_ms,
+        latency.max_ms,
+        metrics.failure_stats().format()
+    )
+}
+
+/// Answer the most repeated chat prompts that aren't cached yet, within the
+/// daily warming budget
+///
+/// Answers are generated in a scratch directory with no Claude session, so
+/// they carry neither the user's conversation nor their files, and nothing
+/// is added to the user's own session. Only questions are warmed, and they
+/// run read-only: nobody is there to see what a warmed prompt would change.
+/// They're stored under a key for the chat that kept asking, which
+/// `handle_text` checks before calling Claude; other chats never see them. A prompt is only attempted when its estimated
+/// cost fits what's left of the budget; the actual cost is what gets charged.
+async fn warm_response_cache(data: &BotData, budget: &std::sync::Mutex<WarmBudget>) -> Result<WarmReport> {
+    let config = &data.cache_warm;
+    let mut report = WarmReport::default();
+    if !config.enabled || config.top_n == 0 {
+        return Ok(report);
+    }
+
+    // Extra candidates, since short and command prompts are dropped below
+    let frequent = {
+        let store = &data.conversation_store;
+        store.frequent_user_messages(config.lookback_secs, config.min_count, config.top_n * 4)?
+    };
+    let candidates = frequent
+        .into_iter()
+        .filter(|m| !m.content.starts_with('/') && config.is_warmable(&m.content))
+        .take(config.top_n);
+
+    for message in candidates {
+        let key = ResponseCache::frequent_query_key(&message.content, message.chat_id);
+        if data.response_cache.contains(&key) {
+            report.already_cached += 1;
+            continue;
+        }
+        let estimate = data.token_counter.estimate_cost(&message.content, 1000, &ModelHint::Sonnet, 0.0);
+        let remaining = budget
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remaining(config.daily_budget_usd, chrono::Local::now().date_naive());
+        if estimate > remaining {
+            report.over_budget += 1;
+            continue;
+        }
+
+        // Private chats share the user's id, so usage lands on whoever keeps asking
+        let user_id = message.chat_id;
+        // Memories a fresh answer could draw on; forgetting one drops the warmed answer
+        let memory_ids: Vec<String> = data
+            .memory_store
+            .search(&message.content, WARM_MEMORY_MATCHES)
+            .map(|results| results.into_iter().map(|r| r.entry.id).collect())
+            .unwrap_or_default();
+        let scratch = scratch_dir(data, ".cache_warm").await?;
+        match data.invoke_claude_read_only(user_id, message.chat_id, &message.content, &scratch).await {
+            Ok(response) => {
+                record_usage(data, user_id, &response, ORIGIN_BACKGROUND);
+                let cost = response_cost(&response);
+                budget.lock().unwrap_or_else(|e| e.into_inner()).spend(cost);
+                report.cost_usd += cost;
+                if response.text.trim().is_empty() {
+                    report.failed += 1;
+                    continue;
+                }
+                data.response_cache.set(&key, CachedResponse {
+                    content: response.text,
+                    model: response.model,
+                    input_tokens: response.input_tokens.max(0) as usize,
+                    output_tokens: response.output_tokens.max(0) as usize,
+                    chat_id: Some(message.chat_id),
+                    memory_ids,
+                }).await;
+                report.warmed += 1;
+            }
+            Err(e) => {
+                tracing::warn!("Cache warming failed for a frequent prompt: {}", e);
+                report.failed += 1;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Memories recorded with each warmed answer
+const WARM_MEMORY_MATCHES: usize = 5;
+
+/// An empty directory for one call that must stay out of the user's session
+///
+/// Recreated each time, so the session the previous call saved there is
+/// never resumed. `/cd` can't reach it: it's in the base directory.
+async fn scratch_dir(data: &BotData, name: &str) -> Result<PathBuf> {
+    let dir = data.base_working_dir.join(name);
+    if dir.exists() {
+        tokio::fs::remove_dir_all(&dir).await?;
+    }
+    tokio::fs::create_dir_all(&dir).await?;
+    Ok(dir)
+}
+
+/// Re-run the chat's last command on a forced model and report the cost delta
+async fn retry_with_model(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    working_dir: &PathBuf,
+    user_id: i64,
+    model: ModelHint,
+) -> Result<()> {
+    let ctx = data.get_ui_context(chat_id.0).await;
+    let Some(command) = ctx.last_command else {
+        bot.send_message(chat_id, "No previous message to retry.").await?;
+        return Ok(());
+    };
+    if let Err(msg) = check_user_limits(data, user_id) {
+        bot.send_message(chat_id, msg).await?;
+        return Ok(());
+    }
+
+    bot.send_message(chat_id, format!("Retrying on {}: {}", model.as_str(), truncate(&command, 50))).await?;
+    bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
+    let is_autonomous = matches!(
+        data.permission_manager.get_status(user_id).level,
+        crate::permissions::PermissionLevel::Autonomous
+    );
+    let response = match data
+        .invoke_claude_with_model(user_id, chat_id.0, &command, working_dir, is_autonomous, Some(model))
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            bot.send_message(chat_id, format!("Retry failed: {}", e)).await?;
+            return Ok(());
+        }
+    };
+    record_usage(data, user_id, &response, ORIGIN_CHAT);
+    let cost = response_cost(&response);
+    data.update_ui_context(chat_id.0, |ctx| ctx.set_result(&response.model, cost)).await;
+
+    send_long_message(bot, chat_id, &response.display_text()).await?;
+    bot.send_message(chat_id, format_retry_cost(ctx.last_model.as_deref(), ctx.last_cost_usd, &response.model, cost)).await?;
+    Ok(())
+}
+
+/// Cost line for `/retry <model>`: new cost, and the delta when the original is known
+fn format_retry_cost(original_model: Option<&str>, original_cost: Option<f64>, model: &str, cost: f64) -> String {
+    match (original_model, original_cost) {
+        (Some(original_model), Some(original_cost)) => format!(
+            "💰 {}: ${:.4} (was ${:.4} on {}, {}${:.4})",
+            model,
+            cost,
+            original_cost,
+            original_model,
+            if cost >= original_cost { "+" } else { "-" },
+            (cost - original_cost).abs()
+        ),
+        _ => format!("💰 {}: ${:.4} (original cost unknown)", model, cost),
+    }
+}
+
+/// Cost of a response at its model's rates
+fn response_cost(response: &ClaudeResponse) -> f64 {
+    ModelPricing::for_model(&ModelHint::from_model_name(&response.model)).cost(
+        response.input_tokens,
+        response.output_tokens,
+        response.cache_read_tokens,
+        response.cache_write_tokens,
+    )
+}
+
+/// `/models`: tiers, their prices and what routes a message to each
+fn format_models(code_floor: &CodeFloor, fallback: &FallbackConfig) -> String {
+    let quoted = |keywords: &[&str]| keywords.iter().map(|k| format!("\"{}\"", k)).collect::<Vec<_>>().join(", ");
+    let mut msg = "🧠 Models (USD per million tokens)\n".to_string();
+    for model in [ModelHint::Haiku, ModelHint::Sonnet, ModelHint::Opus] {
+        let pricing = ModelPricing::for_model(&model);
+        let builtin = ModelPricing::builtin(&model);
+        let custom = pricing.input_per_million != builtin.input_per_million
+            || pricing.output_per_million != builtin.output_per_million;
+        msg.push_str(&format!(
+            "\n{} - ${} in / ${} out, cache read ${}{}\n",
+            model.as_str(),
+            pricing.input_per_million,
+            pricing.output_per_million,
+            pricing.cache_read_per_million,
+            if custom { " (CLAUDEBOT_PRICE_* override)" } else { "" }
+        ));
+        let rule = match model {
+            ModelHint::Haiku => format!("SIMPLE: {}", quoted(SIMPLE_KEYWORDS)),
+            ModelHint::Sonnet => {
+                "MODERATE: no complexity keyword (Llama decides when it's running); also explicit @targets".to_string()
+            }
+            ModelHint::Opus => format!("COMPLEX: {}; also /circle and @circle", quoted(COMPLEX_KEYWORDS)),
+        };
+        msg.push_str(&format!("  {}\n", rule));
+    }
+
+    msg.push_str("\nComplex keywords are checked first, then simple ones.\n");
+    if code_floor.enabled {
+        let lines = if code_floor.min_code_lines > 0 {
+            format!(", {}+ lines of code", code_floor.min_code_lines)
+        } else {
+            String::new()
+        };
+        msg.push_str(&format!(
+            "Code floor: code blocks, diffs{} or {} keywords keep a message on sonnet or above.\n",
+            lines,
+            code_floor.keywords.len()
+        ));
+    }
+    if fallback.max_depth > 0 && fallback.chain.len() > 1 {
+        let chain: Vec<&str> = fallback.chain.iter().map(|m| m.as_str()).collect();
+        msg.push_str(&format!(
+            "Overloaded or rate limited: {} (up to {} step(s)).\n",
+            chain.join(" → "),
+            fallback.max_depth
+        ));
+    }
+    msg.push_str(
+        "\nOverride: none per chat - /retry <haiku|sonnet|opus> re-runs your last message on a tier.\n\
+        /route <text> explains one message, /model stats shows the distribution.",
+    );
+    msg
+}
+
+/// One window of `/model stats`
+fn format_route_distribution(title: &str, dist: &RouteDistribution) -> String {
+    if dist.total == 0 {
+        return format!("{}\n  No routed requests\n", title);
+    }
+    let mut out = format!("{} ({} requests)\n", title, dist.total);
+    for (label, shares) in [("Target", &dist.by_target), ("Model", &dist.by_model)] {
+        out.push_str(&format!("  {}:\n", label));
+        for share in shares.iter() {
+            out.push_str(&format!(
+                "    {} {} ({:.0}%){}\n",
+                share.name,
+                share.count,
+                share.count as f64 * 100.0 / dist.total as f64,
+                share
+                    .avg_cost_usd
+                    .map(|c| format!(", avg ${:.4}", c))
+                    .unwrap_or_default()
+            ));
+        }
+    }
+    out
+}
+
 async fn handle_command(
     bot: &Bot,
     chat_id: ChatId,
@@ -1856,66 +3709,25 @@ async fn handle_command(
 
     match cmd {
         "/start" => {
-            bot.send_message(chat_id,
-                "ClaudeBot (Eliot Brain)\n\n\
-                I'm Claude Code with persistent memory and continuous learning.\n\n\
-                Commands:\n\
-                /help - Show help\n\
-                /usage - Token usage & costs\n\
-                /memory - View memories\n\
-                /goals - View tracked goals\n\
-                /feedback - Learning statistics\n\
-                /graph - View knowledge graph\n\n\
-                I learn autonomously from conversations!"
-            ).await?;
+            bot.send_message(chat_id, i18n::t(data.locale(user_id).await, "start")).await?;
         }
 
         "/help" => {
-            bot.send_message(chat_id,
-                "Help:\n\n\
-                Chat:\n\
-                - Send text: I process with full Claude Code\n\
-                - Send files: I analyze them\n\
-                - Send images: I describe them\n\n\
-                Conversation:\n\
-                /history - View recent conversation\n\
-                /clear - Clear conversation history\n\n\
-                Memory (Autonomous):\n\
-                /memory - View memory stats\n\
-                /memory search <query> - Search memories\n\
-                /goals - View/manage tracked goals\n\
-                /feedback - Learning statistics\n\
-                /context - Load system context\n\
-                /graph - View knowledge graph\n\n\
-                Budget & Stats:\n\
-                /usage - View token usage\n\
-                /limits - View/set limits\n\
-                /stats - System statistics\n\
-                /status - Check bot status\n\
-                /preflight [cmd] - Check tool availability\n\n\
-                Lifecycle:\n\
-                /sleep - Enter sleep mode (run background tasks)\n\
-                /wake - Force wake from sleep\n\n\
-                Planning & Scheduling:\n\
-                /plan <task> - Create execution plan\n\
-                /remind <time> <msg> - Set reminder\n\n\
-                Permissions:\n\
-                /interactive - Toggle pre-approval mode\n\
-                  → Shows Run/Stop buttons before executing\n\
-                  → Preview what operations will run\n\
-                /autonomous [duration] - Auto-approve all\n\
-                /supervised - Back to normal mode\n\
-                /perms - View current permission status\n\n\
-                Bypass Bridge (AR):\n\
-                /bypass <task> - Execute on AR server\n\
-                /bypass_file <path> - Analyze file on AR\n\
-                /bypass_cat <path> - Raw file content\n\
-                /bypass_status - Check bridge status"
-            ).await?;
+            let help = i18n::t(data.locale(user_id).await, "help");
+            let help = if data.permission_manager.is_safe_mode() {
+                help.lines()
+                    .filter(|line| !line.trim_start().starts_with("/autonomous"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            } else {
+                help.to_string()
+            };
+            bot.send_message(chat_id, help).await?;
         }
 
         "/status" => {
-            let status = Command::new("claude")
+            let status = ClaudeCli::from_env()
+                .command()
                 .arg("--version")
                 .output()
                 .await;
@@ -1952,9 +3764,85 @@ async fn handle_command(
                 msg.push_str(&format!("\n{}", result.format_warnings()));
             }
 
+            if let Some(ref report) = result.connectivity {
+                msg.push_str(&format!("\n{}", report.format()));
+            }
+
             bot.send_message(chat_id, msg).await?;
         }
 
+        "/diag" => {
+            bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
+            let report = run_diagnostics(data).await;
+            bot.send_message(chat_id, report.format()).await?;
+        }
+
+        "/model" => {
+            if args.trim() == "stats" {
+                let now = chrono::Local::now();
+                let today = now
+                    .date_naive()
+                    .and_hms_opt(0, 0, 0)
+                    .and_then(|t| t.and_local_timezone(chrono::Local).earliest())
+                    .map(|t| t.timestamp())
+                    .unwrap_or(now.timestamp() - 24 * 3600);
+                let week = now.timestamp() - 7 * 24 * 3600;
+                bot.send_message(chat_id, format!(
+                    "📊 Routing Distribution\n\n{}\n{}\nLast 7 days, kept across restarts; costs at each response's model rates.",
+                    format_route_distribution("Today", &data.router.route_distribution(today)),
+                    format_route_distribution("Last 7 days", &data.router.route_distribution(week)),
+                )).await?;
+            } else {
+                bot.send_message(chat_id,
+                    "Usage: /model stats\n\n\
+                    Shows how chat requests were routed (target and model tier)\n\
+                    today and over the last 7 days, with average cost per target."
+                ).await?;
+            }
+        }
+
+        "/models" => {
+            bot.send_message(chat_id, format_models(data.router.code_floor(), &data.model_fallback)).await?;
+        }
+
+        "/retry" => {
+            match ModelHint::parse(args) {
+                Some(model) => retry_with_model(bot, chat_id, data, working_dir, user_id, model).await?,
+                None => {
+                    bot.send_message(chat_id,
+                        "Usage: /retry <opus|sonnet|haiku>\n\n\
+                        Re-runs your last message on the given model,\n\
+                        regardless of routing, and shows the cost difference."
+                    ).await?;
+                }
+            }
+        }
+
+        "/route" => {
+            if args.is_empty() {
+                bot.send_message(chat_id,
+                    "Usage: /route <text>\n\n\
+                    Classifies the text without executing it and shows\n\
+                    which model would be chosen and why."
+                ).await?;
+            } else {
+                let route = data.router.route_with_worker(args, &data.llama_worker).await;
+                bot.send_message(chat_id, format!(
+                    "Route Classification\n\n\
+                    Target: {}\n\
+                    Model: {}\n\
+                    Complexity: {}\n\
+                    Confidence: {:.0}%\n\n\
+                    Reasoning: {}",
+                    route.target.as_str(),
+                    route.model.as_str(),
+                    route.complexity.as_str(),
+                    route.confidence * 100.0,
+                    route.reasoning
+                )).await?;
+            }
+        }
+
         "/ghcheck" | "/gh" => {
             bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
 
@@ -2042,7 +3930,34 @@ async fn handle_command(
             }
         }
 
+        "/cache" => match args.trim() {
+            "" => {
+                bot.send_message(chat_id, data.response_cache.stats().format()).await?;
+            }
+            "clear" => {
+                if !data.is_admin(user_id) {
+                    bot.send_message(chat_id, "Clearing the cache requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                    return Ok(());
+                }
+                let entries = data.response_cache.stats().entries;
+                data.response_cache.clear().await;
+                bot.send_message(chat_id, format!("Response cache cleared ({} entries)", entries)).await?;
+            }
+            _ => {
+                bot.send_message(chat_id,
+                    "Cache commands:\n\
+                    /cache - Hit rate, size and evictions\n\
+                    /cache clear - Drop all entries and reset counters"
+                ).await?;
+            }
+        },
+
         "/stats" => {
+            if args.trim() == "disk" {
+                bot.send_message(chat_id, format_storage_report(&storage_report(data))).await?;
+                return Ok(());
+            }
+
             let lifecycle_stats = data.lifecycle.get_stats();
             let llama_available = data.llama_worker.is_available().await;
 
@@ -2052,103 +3967,317 @@ async fn handle_command(
                 crate::lifecycle::State::Processing => "Processing 🔄",
             };
 
+            let lifecycle_config = data.lifecycle.config();
+            let on_off = |on: bool| if on { "on" } else { "off" };
+
+            let route_cache = data.router.cache_stats();
+            let index_health = data.memory_store.index_health();
+            let index_rebuilds = data.background_processor.stats().index_rebuilds.load(std::sync::atomic::Ordering::Relaxed);
+            let last_rebuild = match data.background_processor.last_index_rebuild() {
+                Some((ran_at, rebuild)) => format!(
+                    "{} ago, reclaimed {} in {}ms",
+                    format_duration(Duration::from_secs((chrono::Utc::now().timestamp() - ran_at).max(0) as u64)),
+                    rebuild.reclaimed,
+                    rebuild.duration_ms
+                ),
+                None => "never".to_string(),
+            };
+
+            let task_progress: String = data.background_processor.progress()
+                .into_iter()
+                .map(|(task, progress)| format!("\n- {}", progress.format(task)))
+                .collect();
+
             let msg = format!(
                 "System Statistics\n\n\
                 Lifecycle:\n\
                 - State: {}\n\
                 - Idle: {}s\n\
+                - Sleeps after: {} idle\n\
+                - Sleep tasks: every {} (consolidation {}, decay {}, compression {})\n\
                 - Wake cycles: {}\n\
                 - Sleep cycles: {}\n\n\
                 Background Tasks:\n\
                 - Consolidations: {}\n\
                 - Decay applied: {}\n\
-                - Compressions: {}\n\n\
+                - Compressions: {}{}\n\n\
+                Compression{}:\n\
+                {}\n\n\
+                Route Cache:\n\
+                - Hit rate: {:.0}% ({} hits, {} misses)\n\
+                - Entries: {}\n\n\
+                Vector Index:\n\
+                - Vectors: {} live, {} tombstones ({:.0}%)\n\
+                - Rebuilds: {} at {:.0}% (last: {})\n\n\
                 Services:\n\
                 - Llama: {}\n\
                 - Memory: Active\n\n\
-                Commands: /sleep /wake",
+                Commands: /sleep /wake /compression /stats disk",
                 state_str,
                 lifecycle_stats.idle_seconds,
+                format_duration(lifecycle_config.idle_timeout),
+                format_duration(lifecycle_config.sleep_task_interval),
+                on_off(lifecycle_config.enable_consolidation),
+                on_off(lifecycle_config.enable_decay),
+                on_off(lifecycle_config.enable_compression),
                 lifecycle_stats.wake_count,
                 lifecycle_stats.sleep_count,
                 lifecycle_stats.consolidations,
                 lifecycle_stats.decays_applied,
                 lifecycle_stats.compressions,
+                task_progress,
+                if data.lifecycle.compression_override(chat_id.0).is_some() { " (chat override)" } else { "" },
+                format_compression_settings(&data.lifecycle.compression_for_chat(chat_id.0)),
+                route_cache.hit_rate() * 100.0,
+                route_cache.hits,
+                route_cache.misses,
+                route_cache.entries,
+                index_health.live,
+                index_health.tombstones,
+                index_health.tombstone_ratio() * 100.0,
+                index_rebuilds,
+                data.background_processor.index_rebuild_threshold() * 100.0,
+                last_rebuild,
                 if llama_available { "Available" } else { "Unavailable" }
             );
             bot.send_message(chat_id, msg).await?;
         }
 
-        "/sleep" => {
-            let current = data.lifecycle.current_state();
-            if current == crate::lifecycle::State::Processing {
-                bot.send_message(chat_id, "Cannot sleep while processing a task. Try again when done.").await?;
-            } else if data.lifecycle.force_sleep() {
-                bot.send_message(chat_id, "💤 Entering sleep mode.\n\nBackground memory tasks will run.\nSend any message to wake.").await?;
-            } else {
-                bot.send_message(chat_id, "Already sleeping 💤").await?;
-            }
+        "/compression" | "/compress" if !args.trim().is_empty() && !data.is_admin(user_id) => {
+            bot.send_message(chat_id, "Changing compression settings requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
         }
 
-        "/wake" => {
-            let current = data.lifecycle.current_state();
-            if current == crate::lifecycle::State::Sleep {
-                data.lifecycle.force_wake();
-                bot.send_message(chat_id, "⚡ Awake and ready!").await?;
-            } else {
-                let state_str = match current {
-                    crate::lifecycle::State::Wake => "already awake ⚡",
-                    crate::lifecycle::State::Processing => "currently processing 🔄",
-                    _ => "in unknown state",
-                };
-                bot.send_message(chat_id, format!("Bot is {}", state_str)).await?;
-            }
+        "/compression" | "/compress" => {
+            let result = handle_compression_command(data, chat_id.0, args);
+            bot.send_message(chat_id, result).await?;
         }
 
-        "/usage" => {
-            let msg = format_usage(data, user_id)?;
+        "/dataset" => {
+            let msg = match (data.dataset.path(), args.trim()) {
+                (None, _) => "Dataset logging is off. Set CLAUDEBOT_DATASET_PATH to enable it.".to_string(),
+                (Some(_), "off") => {
+                    data.dataset.set_excluded(chat_id.0, true)?;
+                    "This chat is now excluded from the dataset.".to_string()
+                }
+                (Some(_), "on") if data.dataset.is_excluded_by_config(chat_id.0) => {
+                    "This chat is excluded by CLAUDEBOT_DATASET_EXCLUDE_CHATS and stays out of the dataset.".to_string()
+                }
+                (Some(_), "on") => {
+                    data.dataset.set_excluded(chat_id.0, false)?;
+                    "This chat is now included in the dataset.".to_string()
+                }
+                (Some(path), "") => format!(
+                    "Fine-tuning dataset: {}\n\
+                    This chat: {}\n\
+                    Records written since start: {}\n\n\
+                    Usage: /dataset on|off",
+                    path.display(),
+                    if data.dataset.is_excluded(chat_id.0) { "excluded" } else { "included" },
+                    data.dataset.records_written()
+                ),
+                (Some(_), _) => "Usage: /dataset [on|off]".to_string(),
+            };
             bot.send_message(chat_id, msg).await?;
         }
 
-        "/limits" => {
-            if args.is_empty() {
-                let msg = format_limits(data, user_id)?;
-                bot.send_message(chat_id, msg).await?;
+        "/experiment" => {
+            let result = handle_experiment_command(data, user_id, args);
+            bot.send_message(chat_id, result).await?;
+        }
+
+        "/retention" => {
+            if args.trim() == "run" {
+                let report = data.background_processor
+                    .run_retention(&data.conversation_store, &data.memory_store, &data.llama_worker)
+                    .await?;
+                bot.send_message(chat_id, format!("Retention run complete\n\n{}", format_retention_report(&report))).await?;
             } else {
-                let result = set_limits(data, user_id, args);
+                let result = handle_retention_command(data, chat_id.0, args);
                 bot.send_message(chat_id, result).await?;
             }
         }
 
-        "/dir" => {
-            bot.send_message(chat_id, format!("Working dir: {}", working_dir.display())).await?;
+        "/sleep" => {
+            if !data.is_admin(user_id) {
+                bot.send_message(chat_id, "Sleep requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                return Ok(());
+            }
+            bot.send_message(chat_id, "💤 Running background tasks...").await?;
+            let report = data.lifecycle.force_sleep().await;
+            bot.send_message(chat_id, format!(
+                "💤 Sleeping.\n\nBackground tasks ({:.1}s):\n{}\n\nSend any message to wake.",
+                report.elapsed.as_secs_f64(),
+                report.format()
+            )).await?;
         }
 
-        "/memory" | "/mem" => {
+        "/wake" => {
+            if !data.is_admin(user_id) {
+                bot.send_message(chat_id, "Wake requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                return Ok(());
+            }
+            // Any message already wakes the bot; this also cancels a pending /sleep
+            data.lifecycle.force_wake();
+            bot.send_message(chat_id, "⚡ Awake and ready!").await?;
+        }
+
+        "/restart" => {
+            if !data.is_admin(user_id) {
+                let msg = if data.admin_users.is_empty() {
+                    "Restart is disabled: no CLAUDEBOT_ADMIN_USERS configured."
+                } else {
+                    "Restart requires admin permission."
+                };
+                bot.send_message(chat_id, msg).await?;
+                return Ok(());
+            }
+            let running = data.task_registry.len();
+            if running > 0 && args.trim() != "now" {
+                bot.send_message(chat_id, format!(
+                    "⚠️ {} Claude task(s) still running and would be interrupted.\n\
+                    Wait for them to finish, or use /restart now.",
+                    running
+                )).await?;
+                return Ok(());
+            }
+            restart(bot, data, chat_id, user_id).await?;
+        }
+
+        "/allow" | "/deny" => {
+            if !data.is_admin(user_id) {
+                bot.send_message(chat_id, "Changing access requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                return Ok(());
+            }
+            let Ok(target) = args.trim().parse::<i64>() else {
+                bot.send_message(chat_id, format!("Usage: {} <telegram user id>", cmd)).await?;
+                return Ok(());
+            };
+            let msg = update_allowed_user(&data.allowed_users, cmd == "/allow", target);
+            tracing::info!("{} {} by admin {}", cmd, target, user_id);
+            bot.send_message(chat_id, msg).await?;
+        }
+
+        "/summarize_file" => {
+            let path = args.trim();
+            if path.is_empty() {
+                bot.send_message(chat_id, "Usage: /summarize_file <path> (relative to your working directory)").await?;
+                return Ok(());
+            }
+            summarize_file(bot, chat_id, data, path, working_dir, user_id).await?;
+        }
+
+        "/usage" if matches!(args.split_whitespace().next(), Some("adjust" | "reset" | "audit")) => {
+            // Corrections could zero a user's usage under their limits
+            if !data.is_admin(user_id) {
+                bot.send_message(chat_id, "Adjusting usage requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                return Ok(());
+            }
+            let msg = usage_admin(data, user_id, args.trim())?;
+            bot.send_message(chat_id, msg).await?;
+        }
+
+        "/usage" => {
+            let msg = format_usage(data, user_id)?;
+            bot.send_message(chat_id, msg).await?;
+        }
+
+        "/metrics" => {
+            if !data.is_admin(user_id) {
+                bot.send_message(chat_id, "Metrics require admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                return Ok(());
+            }
+            bot.send_message(chat_id, format_metrics(&data.metrics)).await?;
+        }
+
+        "/budget_forecast" | "/forecast" => {
+            let msg = format_budget_forecast(data, user_id)?;
+            bot.send_message(chat_id, msg).await?;
+        }
+
+        "/limits" => {
+            if args.is_empty() {
+                let msg = format_limits(data, user_id)?;
+                bot.send_message(chat_id, msg).await?;
+            } else {
+                let result = set_limits(data, user_id, args);
+                bot.send_message(chat_id, result).await?;
+            }
+        }
+
+        "/dir" | "/pwd" => {
+            let origin = if data.working_dirs.has_override(user_id) { "set with /cd" } else { "default" };
+            bot.send_message(chat_id, format!("Working dir: {} ({})", working_dir.display(), origin)).await?;
+        }
+
+        "/cd" => {
+            let msg = change_working_dir(data, user_id, args.trim());
+            bot.send_message(chat_id, msg).await?;
+        }
+
+        "/memory" | "/mem" => {
             if args.is_empty() {
                 let msg = format_memory_stats(data)?;
                 bot.send_message(chat_id, msg).await?;
             } else if args.starts_with("search ") {
                 let query = &args[7..];
-                let msg = search_memory(data, query)?;
+                let msg = search_memory(data, query, user_id)?;
+                bot.send_message(chat_id, msg).await?;
+            } else if let Some(id) = args.strip_prefix("similar_to ") {
+                // "More like this" for an existing memory
+                let msg = similar_to_memory(data, id.trim(), user_id).await;
                 bot.send_message(chat_id, msg).await?;
             } else if args.starts_with("similar ") {
                 // Pure semantic/vector search
                 let query = &args[8..];
-                let msg = search_memory_semantic(data, query).await;
+                let msg = search_memory_semantic(data, query, user_id).await;
                 bot.send_message(chat_id, msg).await?;
             } else if args.starts_with("hybrid ") {
                 // Hybrid BM25 + vector search
                 let query = &args[7..];
-                let msg = search_memory_hybrid(data, query).await;
+                let msg = search_memory_hybrid(data, query, user_id).await;
+                bot.send_message(chat_id, msg).await?;
+            } else if let Some(query) = args.strip_prefix("graph ") {
+                // Hybrid search plus the facts one graph relation away
+                let msg = search_memory_graph(data, query.trim(), user_id).await;
                 bot.send_message(chat_id, msg).await?;
             } else if args.starts_with("backfill") {
                 // Backfill embeddings for memories without them
                 let msg = backfill_memory_embeddings(data).await;
                 bot.send_message(chat_id, msg).await?;
             } else if args.starts_with("recent") {
-                let msg = get_recent_memories(data)?;
+                let msg = get_recent_memories(data, user_id)?;
+                bot.send_message(chat_id, msg).await?;
+            } else if let Some(id) = args.strip_prefix("forget ") {
+                let msg = forget_memory(data, id.trim(), user_id).await?;
+                bot.send_message(chat_id, msg).await?;
+            } else if let Some(id) = args.strip_prefix("share ") {
+                let msg = set_memory_shared(data, id.trim(), user_id, true)?;
                 bot.send_message(chat_id, msg).await?;
+            } else if let Some(id) = args.strip_prefix("unshare ") {
+                let msg = set_memory_shared(data, id.trim(), user_id, false)?;
+                bot.send_message(chat_id, msg).await?;
+            } else if let Some(rest) = args.strip_prefix("tag ") {
+                let msg = tag_memory(data, rest, user_id, true)?;
+                bot.send_message(chat_id, msg).await?;
+            } else if let Some(rest) = args.strip_prefix("untag ") {
+                let msg = tag_memory(data, rest, user_id, false)?;
+                bot.send_message(chat_id, msg).await?;
+            } else if let Some(tag) = args.strip_prefix("tagged ") {
+                let msg = list_tagged_memories(data, tag.trim(), user_id)?;
+                bot.send_message(chat_id, msg).await?;
+            } else if args == "tags" {
+                let msg = format_memory_tags(data)?;
+                bot.send_message(chat_id, msg).await?;
+            } else if args.starts_with("reembed") {
+                // Dimension migration - expensive, so confirm first
+                match reembed_estimate(data).await {
+                    Ok(estimate) => {
+                        request_cost_confirmation(bot, chat_id, data, &estimate, REEMBED_COMMAND).await?;
+                    }
+                    Err(msg) => {
+                        bot.send_message(chat_id, msg).await?;
+                    }
+                }
             } else if args.starts_with("embeddings") || args.starts_with("stats") {
                 let msg = format_embedding_stats(data)?;
                 bot.send_message(chat_id, msg).await?;
@@ -2158,18 +4287,74 @@ async fn handle_command(
                     /memory - View stats\n\
                     /memory search <query> - Keyword search (BM25)\n\
                     /memory similar <query> - Semantic search (vector)\n\
-                    /memory hybrid <query> - Hybrid search (keyword + vector)\n\
+                    /memory similar_to <id> - Memories like an existing one\n\
+                    /memory hybrid [#tag] <query> - Hybrid search (keyword + vector)\n\
+                    /memory graph <query> - Hybrid search plus facts connected through the graph\n\
                     /memory backfill - Generate embeddings for memories\n\
                     /memory embeddings - View embedding stats\n\
-                    /memory recent - View recent memories\n\n\
+                    /memory reembed - Re-embed everything after switching models\n\
+                    /memory recent - View recent memories\n\
+                    /memory forget <id> - Delete a memory (also /forget <id>)\n\
+                    /memory share <id> - Let all users see a memory\n\
+                    /memory unshare <id> - Make a memory private again\n\
+                    /memory tag <id> <tag> - Tag a memory (untag to remove)\n\
+                    /memory tagged <tag> - Memories with a tag\n\
+                    /memory tags - All tags in use\n\n\
                     Learning is now autonomous - I extract facts from conversations!"
                 ).await?;
             }
         }
 
+        "/skills" => {
+            let parts: Vec<&str> = args.split_whitespace().collect();
+            match parts.as_slice() {
+                ["sandbox"] => {
+                    bot.send_message(chat_id, format_sandbox_policy(data)).await?;
+                }
+                ["install", url, sha256] => {
+                    // Skills run shell and script code on the bot's machine
+                    if !data.is_admin(user_id) {
+                        bot.send_message(chat_id, "Installing skills requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                        return Ok(());
+                    }
+                    bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
+                    let result = install_skill_from_url(data, url, sha256).await;
+                    bot.send_message(chat_id, result).await?;
+                }
+                _ => {
+                    bot.send_message(chat_id,
+                        "Skills commands:\n\
+                        /skills sandbox - Show the active sandbox policy and recent blocks\n\
+                        /skills install <url> <sha256> - Install a TOML skill after verifying its checksum\n\
+                        and its <url>.sig signature against SKILLS_TRUSTED_KEYS (admins only)"
+                    ).await?;
+                }
+            }
+        }
+
         "/goals" => {
-            let result = handle_goals_command(data, args, user_id).await;
-            bot.send_message(chat_id, result).await?;
+            let (subcommand, query) = args.split_once(' ').unwrap_or((args, ""));
+            let status = match subcommand {
+                "complete" | "done" => Some(crate::autonomous::GoalStatus::Completed),
+                "pause" => Some(crate::autonomous::GoalStatus::Paused),
+                _ => None,
+            };
+            if let Some(status) = status {
+                let (result, keyboard) = handle_goal_status_command(data, user_id, status, query.trim()).await;
+                match keyboard {
+                    Some(keyboard) => bot.send_message(chat_id, result).reply_markup(keyboard).await?,
+                    None => bot.send_message(chat_id, result).await?,
+                };
+            } else {
+                let result = handle_goals_command(data, args, user_id).await;
+                bot.send_message(chat_id, result).await?;
+            }
+        }
+
+        "/feedback" | "/learning" if args.trim() == "reset" || args.starts_with("reset ") => {
+            let memory_id = args.trim()["reset".len()..].trim();
+            let msg = reset_feedback(data, user_id, Some(memory_id).filter(|id| !id.is_empty())).await?;
+            bot.send_message(chat_id, msg).await?;
         }
 
         "/feedback" | "/learning" => {
@@ -2177,11 +4362,62 @@ async fn handle_command(
             bot.send_message(chat_id, result).await?;
         }
 
+        "/graph" | "/entities" if args.trim() == "export" || args.starts_with("export ") => {
+            if !data.is_admin(user_id) {
+                bot.send_message(chat_id, "Graph export requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+                return Ok(());
+            }
+            let format = match args.trim().strip_prefix("export").map(str::trim) {
+                Some("") => Some(GraphFormat::Json),
+                Some(format) => GraphFormat::parse(format),
+                None => None,
+            };
+            let Some(format) = format else {
+                bot.send_message(chat_id, "Usage: /graph export [json|graphml]").await?;
+                return Ok(());
+            };
+            match export_graph(data, format) {
+                Ok((content, caption)) => {
+                    let filename = format!(
+                        "knowledge_graph_{}.{}",
+                        chrono::Utc::now().format("%Y%m%d_%H%M"),
+                        format.extension()
+                    );
+                    bot.send_document(chat_id, InputFile::memory(content.into_bytes()).file_name(filename))
+                        .caption(caption)
+                        .await?;
+                }
+                Err(e) => {
+                    bot.send_message(chat_id, format!("Graph export failed: {}", e)).await?;
+                }
+            }
+        }
+
         "/graph" | "/entities" => {
-            let result = format_graph_stats(data);
+            let result = match args.split_once(' ') {
+                Some(("merge", _)) if !data.is_admin(user_id) => {
+                    "Graph merge requires admin permission (CLAUDEBOT_ADMIN_USERS).".to_string()
+                }
+                Some(("merge", names)) => merge_graph_entities(data, names),
+                _ if args.trim() == "normalize" => normalize_graph_types(data),
+                _ if args.trim() == "merge" => "Usage: /graph merge <canonical> <alias>...\n\n\
+                    Quote names with spaces:\n\
+                    /graph merge Claude \"Claude Code\" claude-ai".to_string(),
+                _ => format_graph_stats(data),
+            };
             bot.send_message(chat_id, result).await?;
         }
 
+        "/whois" => {
+            let name = args.trim().trim_matches('"');
+            if name.is_empty() {
+                bot.send_message(chat_id, "Usage: /whois <name>").await?;
+            } else {
+                let profile = whois(data, name, user_id).await;
+                send_long_message(bot, chat_id, &profile).await?;
+            }
+        }
+
         "/extract" => {
             if args.is_empty() {
                 bot.send_message(chat_id, "Usage: /extract <text to analyze>").await?;
@@ -2201,7 +4437,13 @@ async fn handle_command(
                 parse_duration(args).unwrap_or(std::time::Duration::from_secs(3600))
             };
 
-            data.permission_manager.escalate_user(user_id, Some(duration));
+            if let Err(e) = data.permission_manager.escalate_user(user_id, Some(duration)) {
+                bot.send_message(chat_id, format!(
+                    "{}\n\nThis deployment does not allow full access.",
+                    e
+                )).await?;
+                return Ok(());
+            }
 
             let mins = duration.as_secs() / 60;
             bot.send_message(chat_id, format!(
@@ -2219,11 +4461,16 @@ async fn handle_command(
 
         "/supervised" | "/restrict" => {
             data.permission_manager.revoke_user(user_id);
-            bot.send_message(chat_id,
+            let hint = if data.permission_manager.is_safe_mode() {
+                "Safe mode is active - full access is disabled."
+            } else {
+                "Use /autonomous to enable full access."
+            };
+            bot.send_message(chat_id, format!(
                 "SUPERVISED MODE\n\n\
                 Changes require your approval.\n\
-                Use /autonomous to enable full access."
-            ).await?;
+                {}"
+            , hint)).await?;
         }
 
         "/perms" | "/permissions" => {
@@ -2238,16 +4485,21 @@ async fn handle_command(
                 .map(|d| format!("{} minutes", d.as_secs() / 60))
                 .unwrap_or_else(|| "N/A".to_string());
 
+            let (safe_mode, autonomous_cmd) = if status.safe_mode {
+                ("\nSafe mode: ACTIVE (autonomous mode disabled)", "")
+            } else {
+                ("", "/autonomous [duration] - Full access\n")
+            };
+
             bot.send_message(chat_id, format!(
                 "Permission Status\n\n\
                 Level: {}\n\
                 Escalation remaining: {}\n\
-                Approved operations: {}\n\n\
+                Approved operations: {}{}\n\n\
                 Commands:\n\
-                /autonomous [duration] - Full access\n\
-                /supervised - Require approval\n\
+                {}/supervised - Require approval\n\
                 /interactive - Toggle interactive permission prompts"
-            , level_str, remaining, status.approved_ops)).await?;
+            , level_str, remaining, status.approved_ops, safe_mode, autonomous_cmd)).await?;
         }
 
         "/interactive" => {
@@ -2280,100 +4532,210 @@ async fn handle_command(
             }
         }
 
+        "/trace" => {
+            let msg = match args {
+                "on" | "off" => {
+                    data.set_trace(user_id, args == "on").await;
+                    if args == "on" {
+                        "🔎 PIPELINE TRACE ON\n\n\
+                        Each response is followed by a trace: routing, retrieved memories,\n\
+                        context, budget check, model and tokens, and learning.\n\n\
+                        Use /trace off to stop."
+                    } else {
+                        "Pipeline trace off."
+                    }
+                }
+                _ => {
+                    if data.is_trace_on(user_id).await {
+                        "Pipeline trace: on\n\nUsage: /trace on|off"
+                    } else {
+                        "Pipeline trace: off\n\nUsage: /trace on|off"
+                    }
+                }
+            };
+            bot.send_message(chat_id, msg).await?;
+        }
+
+        "/reflect" => {
+            let parts: Vec<&str> = args.split_whitespace().collect();
+            let threshold = data.reflection_engine.config().retry_threshold * 100.0;
+            match parts.as_slice() {
+                ["auto", mode] if matches!(*mode, "on" | "off") => {
+                    let enabled = *mode == "on";
+                    data.set_auto_reflect(user_id, enabled).await;
+                    let msg = if enabled {
+                        format!(
+                            "🔁 AUTO-REFLECTION ENABLED\n\n\
+                            Answers scoring below {:.0}% on self-review are re-run once\n\
+                            with the suggested improvements.\n\n\
+                            This can double the cost of low-quality answers.\n\
+                            Use /reflect auto off to disable.",
+                            threshold
+                        )
+                    } else {
+                        "AUTO-REFLECTION DISABLED\n\nReview suggestions are only logged.".to_string()
+                    };
+                    bot.send_message(chat_id, msg).await?;
+                }
+                ["criteria"] => {
+                    bot.send_message(chat_id, data.reflection_engine.config().criteria.format()).await?;
+                }
+                [] => {
+                    let enabled = data.is_auto_reflect(user_id).await;
+                    bot.send_message(chat_id, format!(
+                        "Reflection\n\n\
+                        Auto-retry: {}\n\
+                        Retry below: {:.0}%\n\n\
+                        Usage: /reflect auto on|off\n\
+                        /reflect criteria - Show dimension weights",
+                        if enabled { "on" } else { "off" },
+                        threshold
+                    )).await?;
+                }
+                _ => {
+                    bot.send_message(chat_id, "Usage: /reflect auto on|off | /reflect criteria").await?;
+                }
+            }
+        }
+
+        "/lang" | "/language" => {
+            let available = Locale::ALL
+                .iter()
+                .map(|l| format!("{} ({})", l.code(), l.native_name()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let msg = match args.trim() {
+                "" => {
+                    let (locale, source) = data.locale_with_source(user_id).await;
+                    i18n::tf(locale, "lang_status", &[
+                        ("name", locale.native_name()),
+                        ("code", locale.code()),
+                        ("source", i18n::t(locale, source)),
+                        ("available", &available),
+                    ])
+                }
+                "auto" => {
+                    data.set_locale(user_id, None).await;
+                    let locale = data.locale(user_id).await;
+                    i18n::tf(locale, "lang_auto", &[("name", locale.native_name())])
+                }
+                code => match Locale::from_code(code) {
+                    Some(locale) => {
+                        data.set_locale(user_id, Some(locale)).await;
+                        i18n::tf(locale, "lang_set", &[("name", locale.native_name())])
+                    }
+                    None => i18n::tf(data.locale(user_id).await, "lang_unknown", &[
+                        ("code", code),
+                        ("available", &available),
+                    ]),
+                },
+            };
+            bot.send_message(chat_id, msg).await?;
+        }
+
         "/history" | "/conv" | "/conversation" => {
             let result = format_conversation_history(data, chat_id.0);
             bot.send_message(chat_id, result).await?;
         }
 
+        "/bookmarks" => {
+            handle_bookmarks_command(bot, chat_id, data, args).await?;
+        }
+
+        "/export_conversation" | "/export_chat" => {
+            match export_conversation_markdown(data, chat_id.0, args) {
+                Ok((markdown, count)) => {
+                    let filename = format!(
+                        "conversation_{}_{}.md",
+                        chat_id.0,
+                        chrono::Utc::now().format("%Y%m%d_%H%M")
+                    );
+                    bot.send_document(chat_id, InputFile::memory(markdown.into_bytes()).file_name(filename))
+                        .caption(format!("{} messages exported", count))
+                        .await?;
+                }
+                Err(msg) => {
+                    bot.send_message(chat_id, msg).await?;
+                }
+            }
+        }
+
+        "/forget" => {
+            let msg = if args.is_empty() {
+                "Usage: /forget <memory id> (see /memory recent)".to_string()
+            } else {
+                forget_memory(data, args.trim(), user_id).await?
+            };
+            bot.send_message(chat_id, msg).await?;
+        }
+
         "/clear" | "/clearhistory" => {
             let result = clear_conversation_history(data, chat_id.0);
+            // Cached answers built from the cleared history must not be served again
+            data.response_cache.invalidate_for_chat(chat_id.0).await;
             bot.send_message(chat_id, result).await?;
         }
 
         // Development Circle - Code Review & Security Audit
         "/circle" | "/review" | "/security" => {
             if args.is_empty() {
-                bot.send_message(chat_id,
+                let personas: String = data.circle_personas.all().iter()
+                    .map(|p| format!("{}. {} - {}\n", data.circle_personas.phase_of(p), p.name, p.role))
+                    .collect();
+                bot.send_message(chat_id, format!(
                     "Development Circle\n\n\
                     Multi-persona code quality pipeline:\n\
-                    1. Graydon - Implementation\n\
-                    2. Linus - Code Review\n\
-                    3. Maria - Testing\n\
-                    4. Kai - Optimization\n\
-                    5. Sentinel - Security Audit (OWASP)\n\n\
+                    {}\n\
                     Usage:\n\
-                    /circle full <feature> - Full 5-phase pipeline\n\
-                    /circle review <code> - Review only (Linus + Sentinel)\n\
+                    /circle full <feature> - Full pipeline\n\
+                    /circle review <code> - Review only\n\
                     /circle security <code> - Security audit only\n\
-                    /circle quick <task> - Quick fix (Graydon only)\n\n\
+                    /circle quick <task> - Quick fix\n\
+                    /circle personas - Active personas per mode\n\n\
                     Example:\n\
-                    /circle security check src/auth.rs for vulnerabilities"
-                ).await?;
-            } else {
-                // Parse mode and task
-                let (mode, task) = if args.starts_with("full ") {
-                    (PipelineMode::Full, args.strip_prefix("full ").unwrap())
-                } else if args.starts_with("review ") {
-                    (PipelineMode::ReviewOnly, args.strip_prefix("review ").unwrap())
-                } else if args.starts_with("security ") {
-                    (PipelineMode::SecurityOnly, args.strip_prefix("security ").unwrap())
-                } else if args.starts_with("quick ") {
-                    (PipelineMode::QuickFix, args.strip_prefix("quick ").unwrap())
-                } else {
-                    // Default to security audit
-                    (PipelineMode::SecurityOnly, args)
-                };
-
+                    /circle security check src/auth.rs for vulnerabilities",
+                    personas
+                )).await?;
+            } else if args.trim() == "personas" {
                 bot.send_message(chat_id, format!(
-                    "Starting Development Circle ({:?})...\n\n\
-                    Task: {}\n\n\
-                    This may take a few minutes.",
-                    mode, truncate(task, 100)
+                    "{}\nEdit circle_personas.toml or CIRCLE_PERSONAS_CONFIG and restart to change.",
+                    data.circle_personas.format()
                 )).await?;
+            } else {
+                let (mode, task) = parse_circle_args(args);
+                let context = circle_context(task, working_dir).await;
 
-                bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
-
-                // Get code context - either from the task description or read a file
-                let context = if task.contains(".rs") || task.contains(".ts") || task.contains(".vue") {
-                    // Try to extract file path and read it
-                    if let Some(file_path) = extract_file_path(task) {
-                        let full_path = working_dir.join(&file_path);
-                        match tokio::fs::read_to_string(&full_path).await {
-                            Ok(content) => format!("File: {}\n\n```\n{}\n```", file_path, content),
-                            Err(_) => task.to_string(),
-                        }
-                    } else {
-                        task.to_string()
-                    }
+                // Expensive runs show the estimate and wait for confirmation
+                let estimate = Circle::estimate(&data.circle_personas, task, &context, mode, &TokenCounter::new());
+                if mode == PipelineMode::Full || estimate.total_cost_usd >= cost_confirm_threshold() {
+                    request_cost_confirmation(bot, chat_id, data, &estimate.format(), &format!("/circle {}", args)).await?;
                 } else {
-                    task.to_string()
-                };
-
-                // Run the circle pipeline
-                let api_key = std::env::var("ANTHROPIC_API_KEY").ok();
-                let claude_client = crate::claude::ClaudeClient::new(api_key.as_deref());
-                let circle = Circle::new(claude_client);
-
-                match circle.run(task, &context, mode).await {
-                    Ok(result) => {
-                        let summary = format_circle_result(&result);
-                        send_long_message(bot, chat_id, &summary).await?;
-
-                        // Store in UI context
-                        data.update_ui_context(chat_id.0, |ctx| {
-                            ctx.set_command(&format!("/circle {:?} {}", mode, task));
-                        }).await;
-                    }
-                    Err(e) => {
-                        bot.send_message(chat_id, format!(
-                            "Circle pipeline failed:\n{}",
-                            e
-                        )).await?;
-                    }
+                    run_circle(bot, chat_id, data, mode, task, &context, user_id).await?;
                 }
             }
         }
 
+        // Pre-run cost estimate for the Circle pipeline or the bridge
+        "/cost" => {
+            if args.is_empty() {
+                bot.send_message(chat_id,
+                    "Cost Estimate\n\n\
+                    Usage:\n\
+                    /cost full <feature> - Estimate a Circle run (any /circle mode)\n\
+                    /cost bypass <task> - Estimate a bridge run\n\n\
+                    Prices follow CLAUDEBOT_PRICE_<MODEL>_INPUT/OUTPUT."
+                ).await?;
+            } else if let Some(task) = args.strip_prefix("bypass ") {
+                let estimate = bridge_estimate(data);
+                request_cost_confirmation(bot, chat_id, data, &estimate, &format!("/bypass {}", task.trim())).await?;
+            } else {
+                let (mode, task) = parse_circle_args(args);
+                let context = circle_context(task, working_dir).await;
+                let estimate = Circle::estimate(&data.circle_personas, task, &context, mode, &TokenCounter::new());
+                request_cost_confirmation(bot, chat_id, data, &estimate.format(), &format!("/circle {}", args)).await?;
+            }
+        }
+
         // Bypass bridge commands for remote AR execution
         "/bypass" | "/b" => {
             if args.is_empty() {
@@ -2382,12 +4744,22 @@ async fn handle_command(
                     Execute tasks on AR server with unleashed Claude Code.\n\n\
                     Usage:\n\
                     /bypass <task> - Execute task on AR\n\
+                    /bypass_exec <command> - Run a shell command on AR\n\
                     /bypass_status - Check bridge status\n\n\
                     Example:\n\
                     /bypass analyze this codebase and suggest improvements"
                 ).await?;
             } else {
-                handle_bypass(bot, chat_id, data, args, user_id).await?;
+                let expensive = data.usage_tracker.average_request_cost(BRIDGE_USAGE_MODEL)
+                    .ok()
+                    .flatten()
+                    .is_some_and(|(avg, _)| avg >= cost_confirm_threshold());
+                if expensive {
+                    let estimate = bridge_estimate(data);
+                    request_cost_confirmation(bot, chat_id, data, &estimate, &format!("/bypass {}", args)).await?;
+                } else {
+                    handle_bypass(bot, chat_id, data, args, user_id).await?;
+                }
             }
         }
 
@@ -2421,8 +4793,52 @@ async fn handle_command(
             }
         }
 
+        "/bypass_exec" | "/bx" => {
+            if args.is_empty() {
+                bot.send_message(chat_id,
+                    "BYPASS EXEC (Shell)\n\n\
+                    Run a shell command on AR and watch its output.\n\
+                    No shell is involved: pipes and redirects aren't interpreted, \
+                    and the bridge's command policy applies.\n\n\
+                    Usage: /bypass_exec <command>\n\
+                    Example: /bypass_exec journalctl -u claudebot-bridge -n 50"
+                ).await?;
+            } else {
+                handle_bypass_exec(bot, chat_id, data, args, user_id).await?;
+            }
+        }
+
+        "/bypass_write" | "/bw" => {
+            match parse_bypass_write(args) {
+                Ok((path, mode, content)) => {
+                    let summary = format!(
+                        "BYPASS WRITE\n\n\
+                        Path: {}\n\
+                        Size: {} bytes\n\
+                        Mode: {:o}\n\n\
+                        This overwrites the file on AR if it exists.",
+                        path,
+                        content.len(),
+                        mode.unwrap_or(0o644)
+                    );
+                    request_cost_confirmation(bot, chat_id, data, &summary, &format!("/bypass_write {}", args)).await?;
+                }
+                Err(usage) => {
+                    bot.send_message(chat_id, usage).await?;
+                }
+            }
+        }
+
         "/context" | "/ctx" => {
-            let msg = load_context(data);
+            let msg = if let Some(window_args) = args.strip_prefix("window") {
+                context_window_command(data, chat_id.0, window_args.trim())
+            } else if let Some(strategy_args) = args.strip_prefix("strategy") {
+                context_strategy_command(data, user_id, strategy_args.trim())
+            } else if args.trim() == "show" {
+                show_context_settings(data, chat_id.0, user_id)
+            } else {
+                load_context(data)
+            };
             bot.send_message(chat_id, msg).await?;
         }
 
@@ -2487,6 +4903,51 @@ async fn handle_command(
             }
         }
 
+        "/approve" => {
+            handle_approval_command(bot, chat_id, data, args, user_id, true).await?;
+        }
+
+        "/reject" => {
+            handle_approval_command(bot, chat_id, data, args, user_id, false).await?;
+        }
+
+        "/quiet" => {
+            let msg = match args.trim() {
+                "" => match data.scheduler.quiet_hours(user_id).await {
+                    Some(quiet) => format!(
+                        "🌙 Quiet hours: {}\n\n\
+                        Reminders and digests are held until the window ends; urgent alerts still come through.\n\
+                        Use /quiet off to disable.",
+                        quiet.format()
+                    ),
+                    None => "🌙 Quiet hours are off.\n\n\
+                        Usage: /quiet <start>-<end> [UTC offset]\n\
+                        Example: /quiet 22:00-08:00 +02:00".to_string(),
+                },
+                "off" => match data.scheduler.set_quiet_hours(user_id, None).await {
+                    Ok(()) => "🌙 Quiet hours off. Notifications are delivered immediately.".to_string(),
+                    Err(e) => {
+                        tracing::warn!("Failed to save quiet hours for user {}: {}", user_id, e);
+                        "🌙 Quiet hours off for now, but the change couldn't be saved and will be undone on restart.".to_string()
+                    }
+                },
+                spec => match QuietHours::parse(spec) {
+                    Ok(quiet) => match data.scheduler.set_quiet_hours(user_id, Some(quiet)).await {
+                        Ok(()) => format!("🌙 Quiet hours set: {}", quiet.format()),
+                        Err(e) => {
+                            tracing::warn!("Failed to save quiet hours for user {}: {}", user_id, e);
+                            format!(
+                                "🌙 Quiet hours set: {}\n\n⚠️ They couldn't be saved and will be lost on restart.",
+                                quiet.format()
+                            )
+                        }
+                    },
+                    Err(e) => format!("{}\n\nUsage: /quiet 22:00-08:00 [+02:00] or /quiet off", e),
+                },
+            };
+            bot.send_message(chat_id, msg).await?;
+        }
+
         // Phase 8: Reminder commands
         "/remind" | "/reminder" => {
             if args.is_empty() {
@@ -2510,6 +4971,14 @@ async fn handle_command(
                     msg.push_str("\nUse /remind <time> <message> to add more.");
                     bot.send_message(chat_id, msg).await?;
                 }
+            } else if args == "failed" || args == "failed clear" {
+                let msg = if args == "failed clear" {
+                    let removed = data.delivery_queue.clear_failed(user_id);
+                    format!("Cleared {} failed notification(s).", removed)
+                } else {
+                    format_failed_deliveries(data, user_id)
+                };
+                bot.send_message(chat_id, msg).await?;
             } else {
                 // Parse: /remind 30m Check the build
                 let parts: Vec<&str> = args.splitn(2, ' ').collect();
@@ -2535,7 +5004,7 @@ async fn handle_command(
                 };
 
                 let due_at = chrono::Utc::now().timestamp() + duration.as_secs() as i64;
-                let reminder = Reminder::once(user_id, chat_id.0, message, due_at);
+                let reminder = Reminder::once(user_id, chat_id.0, message, due_at).from_channel(ChannelType::Telegram);
                 let reminder_id = data.scheduler.schedule_reminder(reminder).await;
 
                 let due_time = chrono::DateTime::from_timestamp(due_at, 0)
@@ -2568,8 +5037,8 @@ async fn handle_command(
                 data.permission_manager.get_status(user_id).level,
                 crate::permissions::PermissionLevel::Autonomous
             );
-            let response = invoke_claude_cli(text, working_dir, is_autonomous).await?;
-            record_usage(data, user_id, &response);
+            let response = data.invoke_claude(user_id, chat_id.0, text, working_dir, is_autonomous).await?;
+            record_usage(data, user_id, &response, cmd);
             send_long_message(bot, chat_id, &response.text).await?;
         }
     }
@@ -2612,25 +5081,191 @@ fn format_usage(data: &BotData, user_id: i64) -> Result<String> {
         total.request_count,
         total.estimated_cost_usd,
     );
+    let msg = if monthly.estimated_requests > 0 {
+        format!(
+            "{}\n\n~ {} request(s) this month use estimated token counts (Claude CLI output wasn't JSON)",
+            msg, monthly.estimated_requests
+        )
+    } else {
+        msg
+    };
+
+    let by_origin = data.usage_tracker.get_monthly_usage_by_origin(user_id)?;
+    if by_origin.is_empty() {
+        return Ok(msg);
+    }
+
+    let mut msg = msg;
+    msg.push_str("\n\nThis Month by Feature:");
+    for usage in &by_origin {
+        msg.push_str(&format!(
+            "\n- {}: ${:.4} ({} requests)",
+            usage.origin, usage.summary.estimated_cost_usd, usage.summary.request_count
+        ));
+    }
 
     Ok(msg)
 }
 
-fn format_limits(data: &BotData, user_id: i64) -> Result<String> {
-    let limits = data.usage_tracker.get_user_limits(user_id)?;
-    let daily = data.usage_tracker.get_daily_usage(user_id)?;
-    let monthly = data.usage_tracker.get_monthly_usage(user_id)?;
-
-    let daily_tokens = daily.total_input_tokens + daily.total_output_tokens;
-    let monthly_tokens = monthly.total_input_tokens + monthly.total_output_tokens;
+fn format_failed_deliveries(data: &BotData, user_id: i64) -> String {
+    let failed = data.delivery_queue.failed_for_user(user_id);
+    let pending = data.delivery_queue.pending_for_user(user_id);
 
-    let daily_limit_str = limits.daily_token_limit
-        .map(|l| format!("{} / {}", format_tokens(daily_tokens), format_tokens(l)))
-        .unwrap_or_else(|| "unlimited".to_string());
+    if failed.is_empty() && pending.is_empty() {
+        return "⏰ Failed Notifications\n\nAll notifications were delivered.".to_string();
+    }
 
-    let monthly_limit_str = limits.monthly_token_limit
-        .map(|l| format!("{} / {}", format_tokens(monthly_tokens), format_tokens(l)))
-        .unwrap_or_else(|| "unlimited".to_string());
+    let mut msg = "⏰ Failed Notifications\n\n".to_string();
+    if !pending.is_empty() {
+        msg.push_str(&format!("Retrying ({}):\n", pending.len()));
+        for p in &pending {
+            let next = chrono::DateTime::from_timestamp(p.next_attempt_at, 0)
+                .map(|dt| dt.format("%H:%M:%S").to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            msg.push_str(&format!(
+                "• {} (attempt {}, next at {})\n",
+                truncate(&p.reminder.message, 60), p.attempts + 1, next
+            ));
+        }
+        msg.push('\n');
+    }
+    if !failed.is_empty() {
+        msg.push_str(&format!("Gave up ({}):\n", failed.len()));
+        for f in &failed {
+            msg.push_str(&format!("• {}\n", f.format()));
+        }
+        msg.push_str("\nUse /remind failed clear to dismiss.");
+    }
+    msg
+}
+
+/// `/usage adjust <user> <±usd>`, `/usage reset <user> [daily|monthly|all]`,
+/// `/usage audit` (admins only)
+fn usage_admin(data: &BotData, admin_id: i64, args: &str) -> Result<String> {
+    const USAGE: &str = "Usage:\n\
+        /usage adjust <user id> <±usd> - Correct a user's recorded cost\n\
+        /usage reset <user id> [daily|monthly|all] - Delete a user's usage (default: daily)\n\
+        /usage audit - Recent corrections";
+    let parts: Vec<&str> = args.split_whitespace().collect();
+    let tracker = &data.usage_tracker;
+    match parts.as_slice() {
+        ["adjust", user, delta] => {
+            let (Ok(user), Ok(delta)) = (user.parse::<i64>(), delta.trim_start_matches('$').parse::<f64>()) else {
+                return Ok(USAGE.to_string());
+            };
+            tracker.adjust_usage(user, delta, admin_id)?;
+            tracing::info!("Usage of {} adjusted by {:+.4} USD by admin {}", user, delta, admin_id);
+            let daily = tracker.get_daily_usage(user)?;
+            Ok(format!(
+                "Adjusted user {} by {:+.4} USD (today now ${:.4})",
+                user, delta, daily.estimated_cost_usd
+            ))
+        }
+        ["reset", user, rest @ ..] if rest.len() <= 1 => {
+            let Ok(user) = user.parse::<i64>() else {
+                return Ok(USAGE.to_string());
+            };
+            let Some(scope) = rest.first().map_or(Some(UsageScope::Daily), |s| UsageScope::parse(s)) else {
+                return Ok(USAGE.to_string());
+            };
+            let removed = tracker.reset_usage(user, scope, admin_id)?;
+            tracing::info!("Usage of {} reset ({}) by admin {}", user, scope.as_str(), admin_id);
+            Ok(format!(
+                "Reset {} usage of user {}: removed {} request(s), ${:.4}",
+                scope.as_str(),
+                user,
+                removed.request_count,
+                removed.estimated_cost_usd
+            ))
+        }
+        ["audit"] => {
+            let log = tracker.adjustment_log(10)?;
+            if log.is_empty() {
+                return Ok("No usage corrections yet".to_string());
+            }
+            let mut msg = "Usage corrections (newest first):".to_string();
+            for entry in log {
+                let when = chrono::DateTime::from_timestamp(entry.timestamp, 0)
+                    .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                msg.push_str(&format!(
+                    "\n{} {} user {} {:+.4} USD",
+                    when, entry.action, entry.user_id, entry.cost_usd
+                ));
+                if entry.requests > 0 {
+                    msg.push_str(&format!(", {} request(s)", entry.requests));
+                }
+                msg.push_str(&format!(" (by {})", entry.admin_id));
+            }
+            Ok(msg)
+        }
+        _ => Ok(USAGE.to_string()),
+    }
+}
+
+fn format_budget_forecast(data: &BotData, user_id: i64) -> Result<String> {
+    let forecast = data.usage_tracker.forecast_month(user_id)?;
+
+    let limit_str = forecast.limit
+        .map(|l| format!("${:.2}", l))
+        .unwrap_or_else(|| "unlimited".to_string());
+
+    let mut msg = format!(
+        "Budget Forecast\n\n\
+        Month to date: ${:.2}\n\
+        Daily burn rate: ${:.2}/day\n\
+        Days remaining: {:.1}\n\
+        Projected month end: ${:.2}\n\
+        Monthly limit: {}",
+        forecast.month_to_date_cost,
+        forecast.daily_rate,
+        forecast.days_remaining,
+        forecast.projected_cost,
+        limit_str,
+    );
+
+    match forecast.limit {
+        Some(limit) if !forecast.on_track => {
+            let overage = forecast.projected_cost - limit;
+            let days_to_limit = if forecast.daily_rate > 0.0 {
+                ((limit - forecast.month_to_date_cost) / forecast.daily_rate).max(0.0)
+            } else {
+                0.0
+            };
+            msg.push_str(&format!(
+                "\n\n⚠️ Projected to exceed the monthly limit by ${:.2}\n\
+                At this rate the limit is reached in {:.1} days.\n\
+                Use /limits to adjust your budget.",
+                overage, days_to_limit
+            ));
+        }
+        Some(limit) => {
+            msg.push_str(&format!(
+                "\n\n✅ On track (${:.2} headroom)",
+                limit - forecast.projected_cost
+            ));
+        }
+        None => {}
+    }
+
+    Ok(msg)
+}
+
+fn format_limits(data: &BotData, user_id: i64) -> Result<String> {
+    let limits = data.usage_tracker.get_user_limits(user_id)?;
+    let daily = data.usage_tracker.get_daily_usage(user_id)?;
+    let monthly = data.usage_tracker.get_monthly_usage(user_id)?;
+
+    let daily_tokens = daily.total_input_tokens + daily.total_output_tokens;
+    let monthly_tokens = monthly.total_input_tokens + monthly.total_output_tokens;
+
+    let daily_limit_str = limits.daily_token_limit
+        .map(|l| format!("{} / {}", format_tokens(daily_tokens), format_tokens(l)))
+        .unwrap_or_else(|| "unlimited".to_string());
+
+    let monthly_limit_str = limits.monthly_token_limit
+        .map(|l| format!("{} / {}", format_tokens(monthly_tokens), format_tokens(l)))
+        .unwrap_or_else(|| "unlimited".to_string());
 
     let daily_cost_str = limits.daily_cost_limit_usd
         .map(|l| format!("${:.2} / ${:.2}", daily.estimated_cost_usd, l))
@@ -2649,7 +5284,8 @@ fn format_limits(data: &BotData, user_id: i64) -> Result<String> {
         Set limits:\n\
         /limits daily 500K\n\
         /limits monthly 5M\n\
-        /limits cost 5.00",
+        /limits cost 5.00\n\
+        /limits monthlycost 100",
         daily_limit_str,
         monthly_limit_str,
         daily_cost_str,
@@ -2662,11 +5298,16 @@ fn format_limits(data: &BotData, user_id: i64) -> Result<String> {
 fn set_limits(data: &BotData, user_id: i64, args: &str) -> String {
     let parts: Vec<&str> = args.split_whitespace().collect();
     if parts.len() < 2 {
-        return "Usage: /limits <type> <value>\nTypes: daily, monthly, cost".to_string();
+        return "Usage: /limits <type> <value>\nTypes: daily, monthly, cost, monthlycost".to_string();
     }
 
-    let limit_type = parts[0];
-    let value_str = parts[1];
+    // `cost daily <v>` / `cost monthly <v>` are aliases of `cost` / `monthlycost`
+    let (limit_type, value_str) = match parts.as_slice() {
+        ["cost", "daily", value, ..] => ("cost", *value),
+        ["cost", "monthly", value, ..] => ("monthlycost", *value),
+        [limit_type, value, ..] => (*limit_type, *value),
+        _ => unreachable!(),
+    };
 
     // Get current limits
     let mut limits = match data.usage_tracker.get_user_limits(user_id) {
@@ -2711,6 +5352,18 @@ fn set_limits(data: &BotData, user_id: i64, args: &str) -> String {
                 Err(_) => "Invalid value. Use decimal like 5.00, 10.50".to_string(),
             }
         }
+        "monthlycost" => {
+            match value_str.parse::<f64>() {
+                Ok(v) => {
+                    limits.monthly_cost_limit_usd = Some(v);
+                    if let Err(e) = data.usage_tracker.set_user_limits(user_id, &limits) {
+                        return format!("Error: {}", e);
+                    }
+                    format!("Monthly cost limit set to ${:.2}", v)
+                }
+                Err(_) => "Invalid value. Use decimal like 50.00, 200".to_string(),
+            }
+        }
         "unlimited" | "none" | "off" => {
             limits.daily_token_limit = None;
             limits.monthly_token_limit = None;
@@ -2721,7 +5374,165 @@ fn set_limits(data: &BotData, user_id: i64, args: &str) -> String {
             }
             "All limits removed".to_string()
         }
-        _ => "Unknown limit type. Use: daily, monthly, cost, unlimited".to_string(),
+        _ => "Unknown limit type. Use: daily, monthly, cost, monthlycost, unlimited".to_string(),
+    }
+}
+
+/// Format effective compression settings for display
+fn format_compression_settings(settings: &CompressionConfig) -> String {
+    format!(
+        "- Min age: {}\n\
+        - Min messages: {}\n\
+        - Max per cycle: {}\n\
+        - Target ratio: {:.0}%\n\
+        - Min importance: {:.2}",
+        format_duration(settings.min_age),
+        settings.min_messages,
+        settings.max_per_cycle,
+        settings.target_ratio * 100.0,
+        settings.min_importance
+    )
+}
+
+/// Handle /compression command - view or override compression thresholds for this chat
+fn handle_compression_command(data: &BotData, chat_id: i64, args: &str) -> String {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    match parts.as_slice() {
+        [] => {
+            let source = if data.lifecycle.compression_override(chat_id).is_some() {
+                "chat override"
+            } else {
+                "global defaults"
+            };
+            format!(
+                "Compression Settings ({})\n\n{}\n\n\
+                Override for this chat (admin):\n\
+                /compression age 30m\n\
+                /compression min 50\n\
+                /compression ratio 0.5\n\
+                /compression reset",
+                source,
+                format_compression_settings(&data.lifecycle.compression_for_chat(chat_id))
+            )
+        }
+        ["reset"] => {
+            if data.lifecycle.clear_compression_override(chat_id) {
+                "Compression override removed. Using global defaults.".to_string()
+            } else {
+                "No compression override set for this chat.".to_string()
+            }
+        }
+        ["age", value] => match parse_duration(value) {
+            Some(d) => {
+                data.lifecycle.update_compression_override(chat_id, |o| o.min_age = Some(d));
+                format!("Compression min age set to {}", format_duration(d))
+            }
+            None => "Invalid duration. Use: 30m, 2h, 1d".to_string(),
+        },
+        ["min", value] => match value.parse::<usize>() {
+            Ok(v) => {
+                data.lifecycle.update_compression_override(chat_id, |o| o.min_messages = Some(v));
+                format!("Compression min messages set to {}", v)
+            }
+            Err(_) => "Invalid value. Use a whole number like 50".to_string(),
+        },
+        ["ratio", value] => match value.parse::<f32>() {
+            Ok(v) if v > 0.0 && v <= 1.0 => {
+                data.lifecycle.update_compression_override(chat_id, |o| o.target_ratio = Some(v));
+                format!("Compression target ratio set to {:.0}%", v * 100.0)
+            }
+            _ => "Invalid ratio. Use a decimal between 0 and 1, like 0.3".to_string(),
+        },
+        _ => "Usage: /compression [age <duration> | min <count> | ratio <0-1> | reset]".to_string(),
+    }
+}
+
+/// Format a retention window for display
+fn format_retention(retention: Option<std::time::Duration>) -> String {
+    match retention {
+        Some(d) => format_duration(d),
+        None => "keep forever".to_string(),
+    }
+}
+
+/// Format deleted counts from a retention run
+fn format_retention_report(report: &RetentionReport) -> String {
+    let mut msg = format!(
+        "- Messages deleted: {}\n\
+        - Chats expired: {}\n\
+        - Summaries saved: {}",
+        report.messages_deleted, report.chats_expired, report.summaries_saved
+    );
+    if report.chats_deferred > 0 {
+        msg.push_str(&format!(
+            "\n- Deferred: {} chats (summary unavailable, will retry)",
+            report.chats_deferred
+        ));
+    }
+    msg
+}
+
+/// Handle /retention command - view or override conversation retention for this chat
+fn handle_retention_command(data: &BotData, chat_id: i64, args: &str) -> String {
+    let parts: Vec<&str> = args.split_whitespace().collect();
+
+    match parts.as_slice() {
+        [] => {
+            let policy = data.background_processor.retention_config();
+            let source = if policy.chat_overrides.contains_key(&chat_id) {
+                "chat override"
+            } else {
+                "global policy"
+            };
+            let stats = data.background_processor.stats();
+            let mut msg = format!(
+                "Conversation Retention\n\n\
+                This chat: {} ({})\n\
+                Global: {}\n\
+                Summarize before deleting: {}\n\
+                Total messages expired: {}",
+                format_retention(policy.for_chat(chat_id)),
+                source,
+                format_retention(policy.max_age),
+                if policy.compress { "yes" } else { "no" },
+                stats.conversation_messages_expired.load(std::sync::atomic::Ordering::Relaxed),
+            );
+            if let Some((ran_at, report)) = data.background_processor.last_retention() {
+                let ran_at = chrono::DateTime::from_timestamp(ran_at, 0)
+                    .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                msg.push_str(&format!("\n\nLast run ({}):\n{}", ran_at, format_retention_report(&report)));
+            }
+            msg.push_str(
+                "\n\nOverride for this chat:\n\
+                /retention 30d\n\
+                /retention off - Keep forever\n\
+                /retention reset\n\
+                /retention run - Expire now",
+            );
+            msg
+        }
+        ["reset"] => {
+            if data.background_processor.clear_chat_retention(chat_id) {
+                "Retention override removed. Using the global policy.".to_string()
+            } else {
+                "No retention override set for this chat.".to_string()
+            }
+        }
+        ["off"] => {
+            data.background_processor.set_chat_retention(chat_id, None);
+            "This chat's messages will be kept forever.".to_string()
+        }
+        [value] => match parse_duration(value) {
+            Some(d) if d.as_secs() >= 3600 => {
+                data.background_processor.set_chat_retention(chat_id, Some(d));
+                format!("Messages in this chat will be kept for {}", format_duration(d))
+            }
+            Some(_) => "Retention must be at least 1h".to_string(),
+            None => "Invalid duration. Use: 12h, 7d, 30d".to_string(),
+        },
+        _ => "Usage: /retention [<duration> | off | reset | run]".to_string(),
     }
 }
 
@@ -2740,7 +5551,7 @@ fn parse_token_value(s: &str) -> Option<i64> {
 // ============ Memory Functions ============
 
 fn format_memory_stats(data: &BotData) -> Result<String> {
-    let store = data.memory_store.lock().unwrap();
+    let store = &data.memory_store;
     let stats = store.stats()?;
 
     let mut msg = format!("Memory Stats\n\nTotal: {} entries\n\nBy Category:", stats.total_entries);
@@ -2752,31 +5563,57 @@ fn format_memory_stats(data: &BotData) -> Result<String> {
     Ok(msg)
 }
 
-fn search_memory(data: &BotData, query: &str) -> Result<String> {
-    let store = data.memory_store.lock().unwrap();
-    let results = store.search(query, 5)?;
+/// Memories visible to a user under the configured scope mode
+fn memory_scope(data: &BotData, user_id: i64) -> MemoryScope {
+    data.context_manager.config().memory_scope.scope_for(user_id)
+}
+
+fn search_memory(data: &BotData, query: &str, user_id: i64) -> Result<String> {
+    let scope = memory_scope(data, user_id);
+    let store = &data.memory_store;
+    let results: Vec<_> = store
+        .search(query, 20)?
+        .into_iter()
+        .filter(|r| scope.allows(&r.entry))
+        .take(5)
+        .collect();
 
     if results.is_empty() {
         return Ok(format!("No memories found for: {}", query));
     }
 
+    let ids: Vec<&str> = results.iter().map(|r| r.entry.id.as_str()).collect();
+    let feedback = store.feedback_counts(&ids)?;
+
     let mut msg = format!("Memories matching '{}':\n", query);
     for (i, r) in results.iter().enumerate() {
+        let rated = feedback
+            .get(&r.entry.id)
+            .map(|counts| format!(", {}", counts.label()))
+            .unwrap_or_default();
         msg.push_str(&format!(
-            "\n{}. [{}] {}\n   (score: {:.2}, accessed: {}x)",
+            "\n{}. [{}] {}\n   (score: {:.2}, accessed: {}x, id: {}{})",
             i + 1,
             r.entry.category,
             truncate(&r.entry.content, 100),
             r.score,
-            r.entry.access_count
+            r.entry.access_count,
+            short_id(&r.entry.id),
+            rated
         ));
     }
     Ok(msg)
 }
 
-fn get_recent_memories(data: &BotData) -> Result<String> {
-    let store = data.memory_store.lock().unwrap();
-    let entries = store.get_recent(10)?;
+fn get_recent_memories(data: &BotData, user_id: i64) -> Result<String> {
+    let scope = memory_scope(data, user_id);
+    let store = &data.memory_store;
+    let entries: Vec<_> = store
+        .get_recent(50)?
+        .into_iter()
+        .filter(|e| scope.allows(e))
+        .take(10)
+        .collect();
 
     if entries.is_empty() {
         return Ok("No memories stored yet.\nI learn automatically from our conversations!".to_string());
@@ -2785,27 +5622,200 @@ fn get_recent_memories(data: &BotData) -> Result<String> {
     let mut msg = "Recent Memories:\n".to_string();
     for (i, e) in entries.iter().enumerate() {
         msg.push_str(&format!(
-            "\n{}. [{}] {}",
+            "\n{}. [{}] {} ({})",
+            i + 1,
+            e.category,
+            truncate(&e.content, 80),
+            short_id(&e.id)
+        ));
+    }
+    Ok(msg)
+}
+
+/// Mark a memory shared or private; under per-user scoping only its owner may
+fn set_memory_shared(data: &BotData, id: &str, user_id: i64, shared: bool) -> Result<String> {
+    let scope = memory_scope(data, user_id);
+    let store = &data.memory_store;
+    let entry = match store.resolve_id(id)? {
+        Some(entry) if scope.allows(&entry) => entry,
+        _ => return Ok(format!("No memory {}", id)),
+    };
+    if scope != MemoryScope::Global && entry.owner_id.is_some_and(|owner| owner != user_id) {
+        return Ok("Only the memory's owner can change its sharing".to_string());
+    }
+
+    store.set_shared(&entry.id, shared)?;
+    let state = if shared { "shared with all users" } else { "private to its owner" };
+    Ok(format!("Memory {} is now {}", short_id(&entry.id), state))
+}
+
+/// `/memory forget <id>` / `/forget <id>`: delete a memory and any cached
+/// answer whose context included it
+async fn forget_memory(data: &BotData, id: &str, user_id: i64) -> Result<String> {
+    let scope = memory_scope(data, user_id);
+    let store = &data.memory_store;
+    let entry = match store.resolve_id(id)? {
+        Some(entry) if scope.allows(&entry) => entry,
+        _ => return Ok(format!("No memory {}", id)),
+    };
+    if scope != MemoryScope::Global && entry.owner_id.is_some_and(|owner| owner != user_id) {
+        return Ok("Only the memory's owner can forget it".to_string());
+    }
+
+    if !store.forget(&entry.id)? {
+        return Ok(format!("No memory {}", id));
+    }
+    let invalidated = data.response_cache.invalidate_for_memory(&entry.id).await;
+    let mut msg = format!("Forgot memory {}", short_id(&entry.id));
+    if invalidated > 0 {
+        msg.push_str(&format!(" ({} cached answers dropped)", invalidated));
+    }
+    Ok(msg)
+}
+
+/// `/memory tag <id> <tag>` / `/memory untag <id> <tag>`
+fn tag_memory(data: &BotData, args: &str, user_id: i64, add: bool) -> Result<String> {
+    let verb = if add { "tag" } else { "untag" };
+    let Some((id, tag)) = args.trim().split_once(char::is_whitespace) else {
+        return Ok(format!("Usage: /memory {} <id> <tag>", verb));
+    };
+    let Some(tag) = normalize_tag(tag) else {
+        return Ok(format!(
+            "Tags are up to {} letters, digits or -_.: (e.g. project-x)",
+            MAX_TAG_CHARS
+        ));
+    };
+
+    let scope = memory_scope(data, user_id);
+    let store = &data.memory_store;
+    let entry = match store.resolve_id(id)? {
+        Some(entry) if scope.allows(&entry) => entry,
+        _ => return Ok(format!("No memory {}", id)),
+    };
+
+    let changed = if add { store.add_tag(&entry.id, &tag)? } else { store.remove_tag(&entry.id, &tag)? };
+    let tags = store.tags_for(&entry.id)?;
+    let state = match (add, changed) {
+        (true, true) => format!("Tagged {} with #{}", short_id(&entry.id), tag),
+        (true, false) => format!("{} already has #{}", short_id(&entry.id), tag),
+        (false, true) => format!("Removed #{} from {}", tag, short_id(&entry.id)),
+        (false, false) => format!("{} doesn't have #{}", short_id(&entry.id), tag),
+    };
+    Ok(format!("🏷 {}\nTags: {}", state, format_tags(&tags)))
+}
+
+/// `/memory tagged <tag>`
+fn list_tagged_memories(data: &BotData, tag: &str, user_id: i64) -> Result<String> {
+    let Some(tag) = normalize_tag(tag) else {
+        return Ok("Usage: /memory tagged <tag>".to_string());
+    };
+    let scope = memory_scope(data, user_id);
+    let store = &data.memory_store;
+    let entries: Vec<_> = store
+        .search_by_tag(&tag, 100)?
+        .into_iter()
+        .filter(|e| scope.allows(e))
+        .take(20)
+        .collect();
+
+    if entries.is_empty() {
+        return Ok(format!("No memories tagged #{}", tag));
+    }
+
+    let mut msg = format!("Memories tagged #{}:\n", tag);
+    for (i, e) in entries.iter().enumerate() {
+        msg.push_str(&format!(
+            "\n{}. [{}] {} ({})",
             i + 1,
             e.category,
-            truncate(&e.content, 80)
+            truncate(&e.content, 80),
+            short_id(&e.id)
         ));
     }
     Ok(msg)
 }
 
+/// `/memory tags`
+fn format_memory_tags(data: &BotData) -> Result<String> {
+    let counts = data.memory_store.tag_counts()?;
+    if counts.is_empty() {
+        return Ok("No tags yet. Add one with /memory tag <id> <tag>".to_string());
+    }
+    let lines: Vec<String> = counts.iter().map(|(tag, n)| format!("#{} ({})", tag, n)).collect();
+    Ok(format!("Memory tags:\n{}", lines.join("\n")))
+}
+
+fn format_tags(tags: &[String]) -> String {
+    if tags.is_empty() {
+        "none".to_string()
+    } else {
+        tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// Leading `#tag` of a search query, if any, and the rest of the query
+fn split_tag_filter(query: &str) -> (Option<String>, &str) {
+    let query = query.trim();
+    match query.split_once(char::is_whitespace) {
+        Some((first, rest)) if first.starts_with('#') => match normalize_tag(first) {
+            Some(tag) => (Some(tag), rest.trim()),
+            None => (None, query),
+        },
+        _ if query.starts_with('#') => match normalize_tag(query) {
+            Some(tag) => (Some(tag), ""),
+            None => (None, query),
+        },
+        _ => (None, query),
+    }
+}
+
+/// First 8 characters of a memory ID, as accepted by `/memory similar_to`
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+/// Memories nearest to an existing memory's embedding
+async fn similar_to_memory(data: &BotData, id: &str, user_id: i64) -> String {
+    if id.is_empty() {
+        return "Usage: /memory similar_to <id>".to_string();
+    }
+
+    let scope = memory_scope(data, user_id);
+    let results = data.memory_store.similar_to(id, 20)
+        .await
+        .map(|results| results.into_iter().filter(|r| scope.allows(&r.entry)).take(5).collect::<Vec<_>>());
+    match results {
+        Ok(results) if results.is_empty() => format!("No similar memories for {}", id),
+        Ok(results) => {
+            let mut msg = format!("Memories like {}:\n", id);
+            for (i, r) in results.iter().enumerate() {
+                msg.push_str(&format!(
+                    "\n{}. [{}] {}\n   (similarity: {:.1}%, id: {})",
+                    i + 1,
+                    r.entry.category,
+                    truncate(&r.entry.content, 100),
+                    r.score * 100.0,
+                    short_id(&r.entry.id)
+                ));
+            }
+            msg
+        }
+        Err(e) => format!("Similar search error: {}", e),
+    }
+}
+
 /// Semantic search using vector embeddings only
-async fn search_memory_semantic(data: &BotData, query: &str) -> String {
-    // Get embedder outside the lock
+async fn search_memory_semantic(data: &BotData, query: &str, user_id: i64) -> String {
+    // Get embedder
     let embedder = {
-        let store = data.memory_store.lock().unwrap();
+        let store = &data.memory_store;
         if !store.has_embeddings() {
             return "Semantic search unavailable - Ollama not running.\nUse /memory search for keyword search.".to_string();
         }
         store.get_embedder()
     };
 
-    // Compute embedding outside the lock (async)
+    // Compute embedding (async)
     let query_embedding = if let Some(embedder) = embedder {
         embedder.read().await.embed(query).await.ok()
     } else {
@@ -2813,8 +5823,9 @@ async fn search_memory_semantic(data: &BotData, query: &str) -> String {
     };
 
     // Now do the sync search with pre-computed embedding
-    let store = data.memory_store.lock().unwrap();
-    match store.search_hybrid_sync(query, query_embedding, 5, 0.0) {
+    let store = &data.memory_store;
+    // Lookups don't count as use; only memories put into prompts gain the access boost
+    match store.search_hybrid_sync_readonly(query, query_embedding, 5, 0.0, memory_scope(data, user_id)) {
         Ok(results) => {
             if results.is_empty() {
                 return format!("No semantically similar memories for: {}", query);
@@ -2836,15 +5847,56 @@ async fn search_memory_semantic(data: &BotData, query: &str) -> String {
     }
 }
 
+/// `/memory graph <query>`: what the `graph` context strategy would add
+async fn search_memory_graph(data: &BotData, query: &str, user_id: i64) -> String {
+    if query.is_empty() {
+        return "Usage: /memory graph <query>".to_string();
+    }
+    let store = &data.memory_store;
+    let query_embedding = match store.get_embedder() {
+        Some(embedder) => embedder.read().await.embed(query).await.ok(),
+        None => None,
+    };
+    let scope = memory_scope(data, user_id);
+    let results = match store.search_hybrid_sync_readonly(query, query_embedding, 5, 0.4, scope) {
+        Ok(results) => results,
+        Err(e) => return format!("Graph search error: {}", e),
+    };
+    if results.is_empty() {
+        return format!("No memories found for: {}", query);
+    }
+
+    let mut msg = format!("Results for '{}':\n", query);
+    for (i, r) in results.iter().enumerate() {
+        msg.push_str(&format!("\n{}. [{}] {}", i + 1, r.entry.category, truncate(&r.entry.content, 100)));
+    }
+    let connected = data.context_manager.connected_facts(&results, scope, store, &data.graph_store);
+    if connected.is_empty() {
+        msg.push_str("\n\nNo connected facts (the graph links none of these memories' entities onward).");
+    } else {
+        msg.push_str("\n\nConnected through the graph:");
+        for f in &connected {
+            msg.push_str(&format!("\n• {}\n   via {} ({:.2})", truncate(&f.entry.content, 100), f.path, f.weight));
+        }
+    }
+    msg
+}
+
 /// Hybrid search combining keyword (BM25) and vector similarity
-async fn search_memory_hybrid(data: &BotData, query: &str) -> String {
-    // Get embedder outside the lock
+async fn search_memory_hybrid(data: &BotData, query: &str, user_id: i64) -> String {
+    // `/memory hybrid #tag <query>` only searches memories with that tag
+    let (tag, query) = split_tag_filter(query);
+    if let Some(tag) = tag.as_deref().filter(|_| query.is_empty()) {
+        return list_tagged_memories(data, tag, user_id).unwrap_or_else(|e| format!("Hybrid search error: {}", e));
+    }
+
+    // Get embedder
     let (embedder, has_vectors) = {
-        let store = data.memory_store.lock().unwrap();
+        let store = &data.memory_store;
         (store.get_embedder(), store.has_embeddings())
     };
 
-    // Compute embedding outside the lock (async)
+    // Compute embedding (async)
     let query_embedding = if let Some(embedder) = embedder {
         embedder.read().await.embed(query).await.ok()
     } else {
@@ -2852,16 +5904,22 @@ async fn search_memory_hybrid(data: &BotData, query: &str) -> String {
     };
 
     // Now do the sync search with pre-computed embedding
-    let store = data.memory_store.lock().unwrap();
-    match store.search_hybrid_sync(query, query_embedding, 5, 0.4) {
+    let store = &data.memory_store;
+    let scope = memory_scope(data, user_id);
+    let results = match &tag {
+        Some(tag) => store.search_hybrid_tagged(query, query_embedding, 5, 0.4, scope, tag),
+        None => store.search_hybrid_sync_readonly(query, query_embedding, 5, 0.4, scope),
+    };
+    match results {
         Ok(results) => {
             if results.is_empty() {
                 return format!("No memories found for: {}", query);
             }
 
             let mode = if has_vectors { "hybrid (keyword + vector)" } else { "keyword only" };
+            let filter = tag.map(|t| format!(" in #{}", t)).unwrap_or_default();
 
-            let mut msg = format!("Results for '{}' ({}):\n", query, mode);
+            let mut msg = format!("Results for '{}'{} ({}):\n", query, filter, mode);
             for (i, r) in results.iter().enumerate() {
                 if has_vectors {
                     msg.push_str(&format!(
@@ -2889,11 +5947,79 @@ async fn search_memory_hybrid(data: &BotData, query: &str) -> String {
     }
 }
 
+/// Command stored for confirmation by `/memory reembed`
+const REEMBED_COMMAND: &str = "/memory reembed";
+
+/// Summary shown before a re-embed, or the reason it can't run
+async fn reembed_estimate(data: &BotData) -> std::result::Result<String, String> {
+    let (embedder, stats, stored) = {
+        let store = &data.memory_store;
+        let Some(embedder) = store.get_embedder() else {
+            return Err("Re-embed unavailable - Ollama not running.\nStart Ollama and restart the bot.".to_string());
+        };
+        let stats = store.embedding_stats().map_err(|e| format!("Failed to get stats: {}", e))?;
+        let stored = store.stored_dimensions().map_err(|e| format!("Failed to get dimensions: {}", e))?;
+        (embedder, stats, stored)
+    };
+
+    if stats.total_memories == 0 {
+        return Err("No memories to re-embed.".to_string());
+    }
+
+    let current = embedder.read().await.embed_uncached("dimension probe").await.ok().map(|e| e.len());
+    let report = DimensionReport { stored, current };
+    Ok(format!(
+        "Re-embed all {} memories with the current embedding model?\n\n\
+        {}\n\
+        Out of date: {}\n\n\
+        This embeds every memory again and rebuilds the search index.",
+        stats.total_memories,
+        report.format(),
+        report.stale_count()
+    ))
+}
+
+/// Re-embed all memories, editing a progress message as batches complete
+async fn reembed_memories(bot: &Bot, chat_id: ChatId, data: &BotData) -> Result<()> {
+    let status = bot.send_message(chat_id, "Re-embedding memories...").await?;
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let migrate = data.memory_store.reembed_all(25, move |done, total| {
+        let _ = tx.send((done, total));
+    });
+    let report_progress = async {
+        while let Some((done, total)) = rx.recv().await {
+            let _ = bot
+                .edit_message_text(chat_id, status.id, format!("Re-embedding memories... {}/{}", done, total))
+                .await;
+        }
+    };
+    let (result, ()) = tokio::join!(migrate, report_progress);
+
+    let msg = match result {
+        Ok(report) => format!(
+            "Re-embed complete\n\n\
+            Embedded: {}/{}\n\
+            Failed: {}\n\
+            Dimension: {}\n\n\
+            {}",
+            report.embedded,
+            report.total,
+            report.failed,
+            report.dimension.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string()),
+            if report.failed > 0 { "Run /memory backfill to retry failures." } else { "Semantic search is ready." }
+        ),
+        Err(e) => format!("Re-embed failed: {}", e),
+    };
+    bot.edit_message_text(chat_id, status.id, msg).await?;
+    Ok(())
+}
+
 /// Backfill embeddings for memories that don't have them
 async fn backfill_memory_embeddings(data: &BotData) -> String {
-    // Step 1: Get embedder and memories needing backfill (quick lock)
-    let (embedder, memories) = {
-        let store = data.memory_store.lock().unwrap();
+    // Step 1: Get embedder and memories needing backfill
+    let (embedder, batch) = {
+        let store = &data.memory_store;
         if !store.has_embeddings() {
             return "Backfill unavailable - Ollama not running.\nStart Ollama and restart the bot.".to_string();
         }
@@ -2901,20 +6027,20 @@ async fn backfill_memory_embeddings(data: &BotData) -> String {
             Some(e) => e,
             None => return "Backfill unavailable - no embedder configured.".to_string(),
         };
-        let memories = match store.get_memories_needing_embeddings(100) {
-            Ok(m) => m,
+        let batch = match store.next_backfill_batch(100) {
+            Ok(b) => b,
             Err(e) => return format!("Failed to get memories: {}", e),
         };
-        (embedder, memories)
+        (embedder, batch)
     };
 
-    if memories.is_empty() {
+    if batch.memories.is_empty() {
         return "All memories already have embeddings.".to_string();
     }
 
     // Step 2: Compute embeddings (async, no lock held)
     let mut embeddings: Vec<(String, Vec<f32>)> = Vec::new();
-    for (id, content) in &memories {
+    for (id, content) in &batch.memories {
         match embedder.read().await.embed(content).await {
             Ok(embedding) => {
                 embeddings.push((id.clone(), embedding));
@@ -2925,20 +6051,23 @@ async fn backfill_memory_embeddings(data: &BotData) -> String {
         }
     }
 
-    // Step 3: Store embeddings (quick lock per batch)
+    // Step 3: Store embeddings
     let embedded_count = {
-        let store = data.memory_store.lock().unwrap();
+        let store = &data.memory_store;
         let mut count = 0;
         for (id, embedding) in &embeddings {
             if store.store_embedding(id, embedding).is_ok() {
                 count += 1;
             }
         }
+        if let Err(e) = store.save_backfill_checkpoint(batch.checkpoint) {
+            tracing::warn!("Failed to save backfill checkpoint: {}", e);
+        }
         count
     };
 
     // Get updated stats
-    let store = data.memory_store.lock().unwrap();
+    let store = &data.memory_store;
     let stats = store.embedding_stats().unwrap_or_default();
     format!(
         "Backfilled {} memories with embeddings.\n\nCoverage: {}/{} ({:.1}%)",
@@ -2952,20 +6081,17 @@ async fn backfill_memory_embeddings(data: &BotData) -> String {
 /// Format embedding statistics (sync part)
 fn format_embedding_stats(data: &BotData) -> Result<String> {
     let (stats, status, embedder) = {
-        let store = data.memory_store.lock().unwrap();
+        let store = &data.memory_store;
         let stats = store.embedding_stats()?;
         let status = if store.has_embeddings() { "✓ Available" } else { "✗ Unavailable" };
         (stats, status, store.get_embedder())
     };
 
-    // Get cache stats if embedder available
-    let cache_info = if let Some(ref emb) = embedder {
-        // Can't await here (sync fn), so we'll show placeholder
-        // The actual stats would need an async version
-        "\nCache: enabled (1000 entries, 1h TTL)".to_string()
-    } else {
-        String::new()
-    };
+    // Sync fn: skip the cache line if the embedder is busy
+    let cache_info = embedder
+        .as_ref()
+        .and_then(|emb| emb.try_read().ok().map(|emb| format!("\nCache: {}", emb.cache_summary())))
+        .unwrap_or_default();
 
     Ok(format!(
         "Embedding Stats\n\n\
@@ -2988,20 +6114,19 @@ fn format_embedding_stats(data: &BotData) -> Result<String> {
 
 #[allow(dead_code)]
 fn learn_fact(data: &BotData, fact: &str, user_id: i64) -> String {
-    let store = data.memory_store.lock().unwrap();
+    let store = &data.memory_store;
 
     // Determine category from content
-    let category = categorize_fact(fact);
+    let category = categorize_by_keywords(fact);
     let source = format!("telegram_user_{}", user_id);
 
-    match store.learn(fact, &category, &source, 0.9) {
+    match store.learn(fact, category, &source, Some(user_id), 0.9) {
         Ok(id) => format!("Learned [{}]: {}\n(ID: {})", category, truncate(fact, 50), &id[..8]),
         Err(e) => format!("Failed to learn: {}", e),
     }
 }
 
-#[allow(dead_code)]
-async fn learn_fact_async(data: &BotData, fact: &str, user_id: i64) -> String {
+async fn learn_fact_async(data: &BotData, fact: &str, category: &str, user_id: i64) -> String {
     // T3.3 Security: Skip storing sensitive data as facts
     if contains_sensitive_data(fact) {
         tracing::info!("Skipping fact storage: contains sensitive data");
@@ -3011,17 +6136,12 @@ async fn learn_fact_async(data: &BotData, fact: &str, user_id: i64) -> String {
     // Sanitize fact before storage
     let sanitized_fact = sanitize_for_storage(fact);
 
-    // Get embedder outside the lock
-    let embedder = {
-        let store = data.memory_store.lock().unwrap();
-        store.get_embedder()
-    };
+    // Get embedder
+    let embedder = data.memory_store.get_embedder();
 
-    // Determine category from content
-    let category = categorize_fact(&sanitized_fact);
     let source = format!("telegram_user_{}", user_id);
 
-    // Compute embedding outside the lock (async)
+    // Compute embedding (async)
     let embedding = if let Some(embedder) = embedder {
         embedder.read().await.embed(&sanitized_fact).await.ok()
     } else {
@@ -3029,8 +6149,8 @@ async fn learn_fact_async(data: &BotData, fact: &str, user_id: i64) -> String {
     };
 
     // Store the fact with embedding
-    let store = data.memory_store.lock().unwrap();
-    let result = store.learn(&sanitized_fact, &category, &source, 0.9);
+    let store = &data.memory_store;
+    let result = store.learn(&sanitized_fact, category, &source, Some(user_id), 0.9);
 
     match result {
         Ok(id) => {
@@ -3045,18 +6165,28 @@ async fn learn_fact_async(data: &BotData, fact: &str, user_id: i64) -> String {
     }
 }
 
-fn categorize_fact(fact: &str) -> String {
-    let lower = fact.to_lowercase();
-    if lower.contains("prefer") || lower.contains("like") || lower.contains("want") {
-        "preference".to_string()
-    } else if lower.contains("project") || lower.contains("working on") || lower.contains("building") {
-        "project".to_string()
-    } else if lower.contains("remember") || lower.contains("note") || lower.contains("important") {
-        "note".to_string()
-    } else if lower.contains("api") || lower.contains("code") || lower.contains("function") {
-        "technical".to_string()
-    } else {
-        "fact".to_string()
+/// Download, verify and install a skill for `/skills install`
+async fn install_skill_from_url(data: &BotData, url: &str, sha256: &str) -> String {
+    let (definition, provenance) = match SkillLoader::new()
+        .load_verified(url, sha256, &data.trusted_skill_keys)
+        .await
+    {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            tracing::warn!("Rejected skill from {}: {:#}", url, e);
+            return format!("❌ Skill rejected: {:#}", e);
+        }
+    };
+    let kind = definition.execution.exec_type.clone();
+    match data.skill_registry.install_verified(definition, url, &provenance).await {
+        Ok(name) => format!(
+            "✅ Installed skill '{}' ({:?})\n\nSHA256: {}\nSigned by: {}…",
+            name,
+            kind,
+            provenance.sha256,
+            &provenance.signed_by[..16]
+        ),
+        Err(e) => format!("❌ Install failed: {:#}", e),
     }
 }
 
@@ -3074,11 +6204,19 @@ fn format_graph_stats(data: &BotData) -> String {
             Relations: {}\n\n\
             Entity Types:\n{}\n\n\
             Commands:\n\
-            /extract <text> - Extract entities from text",
+            /extract <text> - Extract entities from text\n\
+            /graph merge <canonical> <alias>... - Merge duplicate entities (admin)\n\
+            /graph normalize - Move entities onto their canonical types\n\
+            /whois <name> - Entity profile with relations and memories\n\
+            /graph export [json|graphml] - Download the graph (admin)\n\
+            Send a .json or .graphml file captioned /graph import to restore one (admin)",
             stats.entity_count,
             stats.relation_count,
             stats.by_type.iter()
-                .map(|(t, c)| format!("  {} {}", t, c))
+                .map(|(t, c)| {
+                    let flag = if store.taxonomy().is_known(t) { "" } else { " (not in taxonomy)" };
+                    format!("  {} {}{}", t, c, flag)
+                })
                 .collect::<Vec<_>>()
                 .join("\n")
         ),
@@ -3086,25 +6224,351 @@ fn format_graph_stats(data: &BotData) -> String {
     }
 }
 
-/// Extract entities from text using Llama
-async fn extract_entities(data: &BotData, text: &str) -> String {
-    if !data.llama_worker.is_available().await {
-        return "Entity extraction requires Llama (Ollama not available)".to_string();
+/// Re-type existing entities against the taxonomy
+fn normalize_graph_types(data: &BotData) -> String {
+    let store = match data.graph_store.lock() {
+        Ok(s) => s,
+        Err(_) => return "Failed to access graph store".to_string(),
+    };
+    match store.normalize_types() {
+        Ok(report) => {
+            let mut msg = format!(
+                "Entity types normalized\n\n\
+                Retyped: {}\n\
+                Merged into existing: {}\n\
+                Taxonomy: {}",
+                report.retyped,
+                report.merged,
+                store.taxonomy().types().join(", ")
+            );
+            if !report.unknown.is_empty() {
+                msg.push_str("\n\nNot in taxonomy (add with CLAUDEBOT_ENTITY_TYPES or CLAUDEBOT_ENTITY_SYNONYMS):\n");
+                msg.push_str(&report.unknown.iter()
+                    .map(|(t, c)| format!("  {} {}", t, c))
+                    .collect::<Vec<_>>()
+                    .join("\n"));
+            }
+            msg
+        }
+        Err(e) => format!("Normalize failed: {}", e),
     }
+}
 
-    match data.llama_worker.extract_entities(text).await {
-        Ok(entities) if entities.is_empty() => {
-            "No entities found in text".to_string()
+/// Apply /allow or /deny to the live allowlist; the reply explains the effect
+fn update_allowed_user(allowed_users: &AllowedUsers, allow: bool, target: i64) -> String {
+    let was_open = allowed_users.is_open();
+    let mut msg = if allow {
+        match allowed_users.allow(target) {
+            Ok(true) => format!("✅ User {} can now use the bot.", target),
+            Ok(false) => format!("User {} is already allowed.", target),
+            Err(e) => return format!("Allowed until restart, but saving failed: {}", e),
         }
-        Ok(entities) => {
-            // Store entities in graph
-            let mut stored = 0;
-            if let Ok(store) = data.graph_store.lock() {
-                for entity in &entities {
-                    // Build attributes from context and confidence
-                    let attrs = entity.context.as_ref().map(|ctx| {
-                        serde_json::json!({
-                            "context": ctx,
+    } else if was_open {
+        return "Everyone is allowed (no TELEGRAM_ALLOWED_USERS list), so there is nobody to deny.\n\
+            /allow the users who should keep access first."
+            .to_string();
+    } else {
+        match allowed_users.deny(target) {
+            Ok(true) => format!("🚫 User {} can no longer use the bot.", target),
+            Ok(false) => format!("User {} isn't on the allowed list.", target),
+            Err(e) => return format!("Not denied: {}.\n/allow someone else first.", e),
+        }
+    };
+    if allow && was_open {
+        msg.push_str("\n\n⚠️ The bot was open to everyone; access is now limited to the allowed list.");
+    }
+    if allowed_users.path().is_some() {
+        msg.push_str("\nSaved; the saved list replaces TELEGRAM_ALLOWED_USERS on restart.");
+    } else {
+        msg.push_str("\nThis lasts until restart; update TELEGRAM_ALLOWED_USERS to keep it.");
+    }
+    msg
+}
+
+/// Time given to the dispatcher to acknowledge the /restart update before
+/// exiting, so the restarted bot doesn't receive it again
+const RESTART_EXIT_DELAY: Duration = Duration::from_secs(3);
+
+/// Save in-memory state, tell allowed users, and exit with the restart code
+///
+/// Goals, memories and the delivery queue are already written as they
+/// change; the vector index is rebuilt from stored embeddings on start.
+/// Only pending reminders live solely in memory, so they're saved to
+/// `reminders_path` and restored on the next start.
+async fn restart(bot: &Bot, data: &BotData, chat_id: ChatId, user_id: i64) -> Result<()> {
+    let reminders = match data.scheduler.save_reminders(&data.reminders_path).await {
+        Ok(n) => format!("{} saved", n),
+        Err(e) => {
+            tracing::error!("Failed to save reminders before restart: {}", e);
+            bot.send_message(chat_id, format!("❌ Restart aborted: saving reminders failed: {}", e)).await?;
+            return Ok(());
+        }
+    };
+    let indexed = data.memory_store.index_health().live;
+    tracing::warn!("Restart requested by user {} (exit code {})", user_id, data.restart_exit_code);
+
+    let notice = format!(
+        "🔄 Restarting (requested by {}).\n\
+        - Reminders: {}\n\
+        - Goals, memories, delivery queue: already stored\n\
+        - Vector index: {} vectors, rebuilt on start\n\n\
+        Back in a few seconds.",
+        user_id, reminders, indexed
+    );
+    let mut recipients: Vec<ChatId> = data.allowed_users.list().into_iter().map(ChatId).collect();
+    if !recipients.contains(&chat_id) {
+        recipients.push(chat_id);
+    }
+    for recipient in recipients {
+        if let Err(e) = bot.send_message(recipient, &notice).await {
+            tracing::warn!("Failed to send restart notice to {}: {}", recipient, e);
+        }
+    }
+
+    let code = data.restart_exit_code;
+    tokio::spawn(async move {
+        tokio::time::sleep(RESTART_EXIT_DELAY).await;
+        tracing::warn!("Exiting for restart");
+        std::process::exit(code);
+    });
+    Ok(())
+}
+
+/// Memories shown by /whois
+const WHOIS_MEMORY_LIMIT: usize = 8;
+
+/// Vector similarity at which a memory counts as being about the name
+/// without mentioning it
+const WHOIS_MIN_SIMILARITY: f64 = 0.55;
+
+/// Consolidated profile: graph entity and neighbors plus memories that are
+/// linked to it, mention it, or are semantically close
+async fn whois(data: &BotData, name: &str, user_id: i64) -> String {
+    let profile = match data.graph_store.lock() {
+        Ok(store) => store.profile(name).unwrap_or_else(|e| {
+            tracing::warn!("Graph lookup for /whois failed: {}", e);
+            None
+        }),
+        Err(_) => None,
+    };
+    let display_name = profile.as_ref().map_or(name, |p| p.entity.name.as_str()).to_string();
+
+    // Compute the query embedding
+    let embedder = data.memory_store.get_embedder();
+    let query_embedding = match embedder {
+        Some(embedder) => embedder.read().await.embed(&display_name).await.ok(),
+        None => None,
+    };
+
+    let scope = memory_scope(data, user_id);
+    let needle = display_name.to_lowercase();
+    let mut memories: Vec<(MemoryEntry, &str)> = Vec::new();
+    {
+        let store = &data.memory_store;
+        for id in profile.iter().flat_map(|p| &p.memory_ids) {
+            if let Ok(Some(entry)) = store.get_by_id(id) {
+                if scope.allows(&entry) {
+                    memories.push((entry, "linked"));
+                }
+            }
+        }
+        // Hybrid search falls back to recent memories, so keep only real hits
+        let hits = store
+            .search_hybrid_sync_readonly(&display_name, query_embedding, WHOIS_MEMORY_LIMIT * 2, 0.4, scope)
+            .unwrap_or_default();
+        for hit in hits {
+            let source = if hit.entry.content.to_lowercase().contains(&needle) {
+                "mentions"
+            } else if hit.vector_score >= WHOIS_MIN_SIMILARITY {
+                "similar"
+            } else {
+                continue;
+            };
+            if !memories.iter().any(|(m, _)| m.id == hit.entry.id) {
+                memories.push((hit.entry, source));
+            }
+        }
+    }
+    memories.truncate(WHOIS_MEMORY_LIMIT);
+
+    if profile.is_none() && memories.is_empty() {
+        return format!("I don't know anything about {} yet.", name);
+    }
+
+    let mut msg = match &profile {
+        Some(p) => {
+            let mut header = format!(
+                "🔎 {} ({})\nKnown since {}",
+                p.entity.name,
+                p.entity.entity_type,
+                chrono::DateTime::from_timestamp(p.entity.created_at, 0)
+                    .map(|t| t.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "?".to_string())
+            );
+            if let Some(attributes) = p.entity.attributes.as_object().filter(|a| !a.is_empty()) {
+                let attrs: Vec<String> = attributes
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())))
+                    .collect();
+                header.push_str(&format!("\n{}", attrs.join(", ")));
+            }
+            if p.neighbors.is_empty() {
+                header.push_str("\n\nNo relations yet.");
+            } else {
+                header.push_str(&format!("\n\nRelations ({}):", p.neighbors.len()));
+                for (neighbor, relation) in &p.neighbors {
+                    header.push_str(&format!(
+                        "\n• {} ({})",
+                        p.describe_relation(neighbor, relation),
+                        neighbor.entity_type
+                    ));
+                }
+            }
+            header
+        }
+        None => format!("🔎 {}\nNot in the knowledge graph.", name),
+    };
+
+    if memories.is_empty() {
+        msg.push_str("\n\nNo memories mention it.");
+    } else {
+        msg.push_str(&format!("\n\nMemories ({}):", memories.len()));
+        for (i, (entry, source)) in memories.iter().enumerate() {
+            msg.push_str(&format!(
+                "\n{}. [{}] {}\n   ({}, id: {})",
+                i + 1,
+                entry.category,
+                truncate(&entry.content, 150),
+                source,
+                short_id(&entry.id)
+            ));
+        }
+    }
+    msg
+}
+
+/// Export the graph, returning the file contents and a caption
+fn export_graph(data: &BotData, format: GraphFormat) -> Result<(String, String)> {
+    let store = data.graph_store.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+    let stats = store.stats()?;
+    let content = store.export(format)?;
+    Ok((content, format!("{} entities, {} relations", stats.entity_count, stats.relation_count)))
+}
+
+/// Import a graph file sent with the caption `/graph import`
+async fn import_graph_document(bot: &Bot, chat_id: ChatId, data: &BotData, doc: &teloxide::types::Document) -> Result<()> {
+    let file_name = doc.file_name.clone().unwrap_or_default();
+    let format = GraphFormat::from_file_name(&file_name).unwrap_or(GraphFormat::Json);
+
+    let file = bot.get_file(&doc.file.id).await?;
+    let mut content = Vec::new();
+    bot.download_file(&file.path, &mut content).await?;
+
+    let result = String::from_utf8(content)
+        .map_err(|_| anyhow::anyhow!("File is not UTF-8 text"))
+        .and_then(|content| {
+            let store = data.graph_store.lock().map_err(|e| anyhow::anyhow!("Lock error: {}", e))?;
+            store.import(&content, format)
+        });
+    let msg = match result {
+        Ok(report) => format!(
+            "Imported {} ({:?})\n\n\
+            Entities: {} added, {} merged into existing\n\
+            Relations: {}\n\
+            Memory links: {}",
+            file_name, format, report.entities_added, report.entities_merged, report.relations, report.memory_links
+        ),
+        Err(e) => format!("Graph import failed (nothing was changed): {:#}", e),
+    };
+    bot.send_message(chat_id, msg).await?;
+    Ok(())
+}
+
+/// Split arguments on whitespace, keeping "double quoted" names together
+fn split_quoted_args(args: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in args.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    parts.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// Handle `/graph merge <canonical> <alias>...`
+fn merge_graph_entities(data: &BotData, args: &str) -> String {
+    let names = split_quoted_args(args);
+    let Some((canonical, aliases)) = names.split_first().filter(|(_, aliases)| !aliases.is_empty()) else {
+        return "Usage: /graph merge <canonical> <alias>...".to_string();
+    };
+    let aliases: Vec<&str> = aliases.iter().map(String::as_str).collect();
+
+    let store = match data.graph_store.lock() {
+        Ok(s) => s,
+        Err(_) => return "Failed to access graph store".to_string(),
+    };
+    let report = match store.merge_entities(canonical, &aliases) {
+        Ok(report) => report,
+        Err(e) => return format!("Merge failed: {}", e),
+    };
+
+    let mut msg = if report.merged.is_empty() {
+        format!("Nothing merged into {}.", report.canonical.name)
+    } else {
+        format!(
+            "Merged into {} ({}):\n  {}\n\n\
+            Relations: {} moved, {} combined, {} dropped\n\
+            Memory links moved: {}",
+            report.canonical.name,
+            report.canonical.entity_type,
+            report.merged.join(", "),
+            report.relations_moved,
+            report.relations_combined,
+            report.relations_dropped,
+            report.memories_moved
+        )
+    };
+    if !report.not_found.is_empty() {
+        msg.push_str(&format!("\n\nNot found: {}", report.not_found.join(", ")));
+    }
+    msg
+}
+
+/// Extract entities from text using Llama
+async fn extract_entities(data: &BotData, text: &str) -> String {
+    if !data.llama_worker.is_available().await {
+        return "Entity extraction requires Llama (Ollama not available)".to_string();
+    }
+
+    let types = match data.graph_store.lock() {
+        Ok(store) => store.taxonomy().types().to_vec(),
+        Err(_) => return "Failed to access graph store".to_string(),
+    };
+    match data.llama_worker.extract_entities(text, &types).await {
+        Ok(entities) if entities.is_empty() => {
+            "No entities found in text".to_string()
+        }
+        Ok(entities) => {
+            // Store entities in graph, linking those mentioned together
+            let mut stored = 0;
+            let mut linked = 0;
+            if let Ok(store) = data.graph_store.lock() {
+                for entity in &entities {
+                    // Build attributes from context and confidence
+                    let attrs = entity.context.as_ref().map(|ctx| {
+                        serde_json::json!({
+                            "context": ctx,
                             "confidence": entity.confidence
                         })
                     });
@@ -3112,6 +6576,11 @@ async fn extract_entities(data: &BotData, text: &str) -> String {
                         stored += 1;
                     }
                 }
+
+                let mentioned: Vec<(&str, &str)> = entities.iter()
+                    .map(|e| (e.entity_type.as_str(), e.name.as_str()))
+                    .collect();
+                linked = store.link_co_occurring(text, &mentioned).unwrap_or(0);
             }
 
             let entity_list = entities.iter()
@@ -3119,7 +6588,10 @@ async fn extract_entities(data: &BotData, text: &str) -> String {
                 .collect::<Vec<_>>()
                 .join("\n");
 
-            format!("Extracted {} entities ({} stored):\n{}", entities.len(), stored, entity_list)
+            format!(
+                "Extracted {} entities ({} stored, {} co-occurrence links):\n{}",
+                entities.len(), stored, linked, entity_list
+            )
         }
         Err(e) => format!("Extraction failed: {}", e),
     }
@@ -3188,41 +6660,6 @@ async fn handle_goals_command(data: &BotData, args: &str, user_id: i64) -> Strin
             }
         }
 
-        "complete" | "done" => {
-            // Mark a goal as complete by partial match
-            if parts.len() < 2 {
-                return "Usage: /goals complete <partial description>".to_string();
-            }
-            let search = parts[1..].join(" ").to_lowercase();
-            let goals = data.goal_tracker.get_active_goals(user_id).await;
-
-            for goal in goals {
-                if goal.description.to_lowercase().contains(&search) {
-                    if let Some(updated) = data.goal_tracker.update_status(&goal.id, crate::autonomous::GoalStatus::Completed).await {
-                        return format!("Completed: {}", updated.format_short());
-                    }
-                }
-            }
-            format!("No active goal matching '{}'", search)
-        }
-
-        "pause" => {
-            if parts.len() < 2 {
-                return "Usage: /goals pause <partial description>".to_string();
-            }
-            let search = parts[1..].join(" ").to_lowercase();
-            let goals = data.goal_tracker.get_active_goals(user_id).await;
-
-            for goal in goals {
-                if goal.description.to_lowercase().contains(&search) {
-                    if let Some(updated) = data.goal_tracker.update_status(&goal.id, crate::autonomous::GoalStatus::Paused).await {
-                        return format!("Paused: {}", updated.format_short());
-                    }
-                }
-            }
-            format!("No active goal matching '{}'", search)
-        }
-
         _ => {
             "Goal Commands:\n\n\
             /goals - Show active goals\n\
@@ -3235,6 +6672,111 @@ async fn handle_goals_command(data: &BotData, args: &str, user_id: i64) -> Strin
     }
 }
 
+/// Handle /goals complete|pause <text> - fuzzy match, with buttons when ambiguous
+async fn handle_goal_status_command(
+    data: &BotData,
+    user_id: i64,
+    status: crate::autonomous::GoalStatus,
+    query: &str,
+) -> (String, Option<teloxide::types::InlineKeyboardMarkup>) {
+    use crate::autonomous::{GoalResolution, GoalStatus};
+
+    let (verb, prefix) = match status {
+        GoalStatus::Paused => ("pause", "goal_pause"),
+        _ => ("complete", "goal_complete"),
+    };
+    if query.is_empty() {
+        return (format!("Usage: /goals {} <description>", verb), None);
+    }
+
+    match data.goal_tracker.resolve_goal(user_id, query).await {
+        GoalResolution::Unique(goal) => (set_goal_status(data, &goal.id, status).await, None),
+        GoalResolution::Ambiguous(matches) => {
+            let mut msg = format!("Several goals match '{}'. Which one?\n", query);
+            let mut buttons = Vec::new();
+            for (i, m) in matches.iter().take(5).enumerate() {
+                msg.push_str(&format!("\n{}. {} ({:.0}%)", i + 1, m.goal.description, m.score * 100.0));
+                buttons.push(vec![teloxide::types::InlineKeyboardButton::callback(
+                    format!("{}. {}", i + 1, truncate(&m.goal.description, 40)),
+                    format!("{}:{}", prefix, m.goal.id),
+                )]);
+            }
+            (msg, Some(teloxide::types::InlineKeyboardMarkup::new(buttons)))
+        }
+        GoalResolution::NotFound => (format!("No active goal matching '{}'", query), None),
+    }
+}
+
+/// Apply a goal status change and describe the result
+async fn set_goal_status(data: &BotData, goal_id: &str, status: crate::autonomous::GoalStatus) -> String {
+    let label = match status {
+        crate::autonomous::GoalStatus::Paused => "Paused",
+        _ => "Completed",
+    };
+    match data.goal_tracker.update_status(goal_id, status).await {
+        Some(updated) => format!("{}: {}", label, updated.format_short()),
+        None => "Goal not found".to_string(),
+    }
+}
+
+/// Active skill sandbox policy with recently blocked commands
+fn format_sandbox_policy(data: &BotData) -> String {
+    let mut msg = data.skills_sandbox.config().format();
+
+    let blocked: Vec<_> = data
+        .skills_sandbox
+        .recent_audit(50)
+        .into_iter()
+        .filter(|entry| !entry.allowed)
+        .take(5)
+        .collect();
+    if !blocked.is_empty() {
+        msg.push_str("\n\nRecently blocked:");
+        for entry in blocked {
+            let when = chrono::DateTime::from_timestamp(entry.timestamp, 0)
+                .map(|dt| dt.format("%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "?".to_string());
+            msg.push_str(&format!("\n• {} {} - {}", when, truncate(&entry.command, 60), entry.detail));
+        }
+    }
+
+    msg.push_str("\n\nEdit skills_sandbox.toml or SKILLS_SANDBOX_* and restart to change.");
+    msg
+}
+
+/// `/feedback reset [memory id]`: undo the user's thumbs-up/down adjustments
+async fn reset_feedback(data: &BotData, user_id: i64, memory_id: Option<&str>) -> Result<String> {
+    let store = &data.memory_store;
+    let entry = match memory_id {
+        Some(id) => match store.resolve_id(id)? {
+            Some(entry) => Some(entry),
+            None => return Ok(format!("No memory {}", id)),
+        },
+        None => None,
+    };
+
+    let reset = data
+        .feedback_loop
+        .reset(user_id, entry.as_ref().map(|e| e.id.as_str()), store)
+        .await?;
+    if reset.ratings == 0 {
+        return Ok("No feedback of yours to reset".to_string());
+    }
+    Ok(match entry {
+        Some(entry) => format!(
+            "Reset {} rating(s) on memory {}; confidence restored to {:.2}",
+            reset.ratings,
+            short_id(&entry.id),
+            store.get_by_id(&entry.id)?.map_or(entry.confidence, |e| e.confidence)
+        ),
+        None => format!(
+            "Reset {} rating(s) on {} memories; their confidence is restored",
+            reset.ratings,
+            reset.memory_ids.len()
+        ),
+    })
+}
+
 /// Handle /feedback command - show learning statistics
 async fn handle_feedback_command(data: &BotData, user_id: i64) -> String {
     let mut msg = String::from("Learning Statistics\n\n");
@@ -3243,11 +6785,14 @@ async fn handle_feedback_command(data: &BotData, user_id: i64) -> String {
     let fb_stats = data.feedback_loop.stats().await;
     msg.push_str(&format!(
         "Feedback Loop:\n\
+        • Responses rated: 👍 {} / 👎 {}\n\
         • Total signals: {}\n\
         • Positive: {}\n\
         • Negative: {}\n\
         • Corrections: {}\n\
         • Adjustments made: {}\n\n",
+        fb_stats.thumbs_up,
+        fb_stats.thumbs_down,
         fb_stats.total_signals,
         fb_stats.positive_count,
         fb_stats.negative_count,
@@ -3292,19 +6837,22 @@ async fn handle_feedback_command(data: &BotData, user_id: i64) -> String {
 /// Get conversation history as context for a prompt
 #[allow(dead_code)]
 fn get_conversation_context(data: &BotData, chat_id: i64) -> String {
-    let store = match data.conversation_store.lock() {
-        Ok(s) => s,
-        Err(_) => return String::new(),
-    };
-
-    match store.get_history_as_context(chat_id, 10) {
-        Ok(ctx) => ctx,
+    // Same window and token ceiling as the context manager's history
+    match data.context_manager.recent_history(chat_id, &data.conversation_store) {
+        Ok(messages) => ConversationStore::format_as_context(messages),
         Err(_) => String::new(),
     }
 }
 
 /// Store a conversation exchange (user message + assistant response)
-fn store_conversation_exchange(data: &BotData, chat_id: i64, user_msg: &str, assistant_msg: &str) {
+fn store_conversation_exchange(
+    data: &BotData,
+    chat_id: i64,
+    message_id: Option<i32>,
+    user_msg: &str,
+    assistant_msg: &str,
+    usage: Option<TurnUsage>,
+) {
     // T3.3 Security: Check for sensitive data before storage
     if contains_sensitive_data(user_msg) || contains_sensitive_data(assistant_msg) {
         tracing::info!("Skipping conversation storage: contains sensitive data");
@@ -3315,27 +6863,82 @@ fn store_conversation_exchange(data: &BotData, chat_id: i64, user_msg: &str, ass
     let sanitized_user = sanitize_for_storage(user_msg);
     let sanitized_assistant = sanitize_for_storage(assistant_msg);
 
-    let store = match data.conversation_store.lock() {
-        Ok(s) => s,
-        Err(e) => {
-            tracing::error!("Failed to lock conversation store: {}", e);
-            return;
-        }
-    };
+    let store = &data.conversation_store;
 
-    if let Err(e) = store.add_exchange(chat_id, &sanitized_user, &sanitized_assistant) {
+    let message_id = message_id.map(i64::from);
+    if let Err(e) = store.add_exchange_with_usage(chat_id, message_id, &sanitized_user, &sanitized_assistant, usage) {
         tracing::error!("Failed to store conversation: {}", e);
     }
 }
 
+/// `/bookmark` as a reply: bookmark the replied-to message
+async fn bookmark_replied(bot: &Bot, msg: &Message, data: &BotData) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let Some(target) = msg.reply_to_message() else {
+        bot.send_message(chat_id, "Reply to a message with /bookmark to bookmark it.\nSee them with /bookmarks.").await?;
+        return Ok(());
+    };
+    let reply = match data.conversation_store.bookmark(chat_id.0, target.id.0.into()) {
+        Ok(true) => "🔖 Bookmarked. It won't expire from the history; see /bookmarks.".to_string(),
+        Ok(false) => "That message isn't in the stored conversation, so it can't be bookmarked.".to_string(),
+        Err(e) => format!("Failed to bookmark: {}", e),
+    };
+    bot.send_message(chat_id, reply).await?;
+    Ok(())
+}
+
+/// `/bookmarks` lists them, `/bookmarks <n>` replies to one (tap the quote
+/// to jump), `/bookmarks remove <n>` removes it
+async fn handle_bookmarks_command(bot: &Bot, chat_id: ChatId, data: &BotData, args: &str) -> Result<()> {
+    let bookmarks = data.conversation_store.list_bookmarks(chat_id.0)?;
+    if bookmarks.is_empty() {
+        bot.send_message(chat_id, "No bookmarks yet. Reply to a message with /bookmark to add one.").await?;
+        return Ok(());
+    }
+
+    let (remove, index) = match args.strip_prefix("remove") {
+        Some(n) => (true, n.trim()),
+        None => (false, args.trim()),
+    };
+    if index.is_empty() && !remove {
+        let list = format!(
+            "{}\n/bookmarks <n> - Jump to a bookmark\n/bookmarks remove <n> - Remove one",
+            format_bookmarks(&bookmarks)
+        );
+        send_long_message(bot, chat_id, &list).await?;
+        return Ok(());
+    }
+    let Some(bookmark) = index.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| bookmarks.get(i)) else {
+        bot.send_message(chat_id, format!("Pick a bookmark from 1 to {}.", bookmarks.len())).await?;
+        return Ok(());
+    };
+    let Some(telegram_id) = bookmark.telegram_id.and_then(|id| i32::try_from(id).ok()) else {
+        bot.send_message(chat_id, "That bookmark has no Telegram message to jump to.").await?;
+        return Ok(());
+    };
+
+    if remove {
+        let removed = data.conversation_store.remove_bookmark(chat_id.0, telegram_id.into())?;
+        let msg = if removed { "Bookmark removed." } else { "Bookmark not found." };
+        bot.send_message(chat_id, msg).await?;
+        return Ok(());
+    }
+    let jumped = bot
+        .send_message(chat_id, format!("🔖 Bookmark {}", index))
+        .reply_parameters(ReplyParameters::new(MessageId(telegram_id)))
+        .await;
+    if jumped.is_err() {
+        // The original was deleted from the chat; show the stored text instead
+        send_long_message(bot, chat_id, &format!("🔖 Bookmark {}\n\n{}", index, bookmark.message.content)).await?;
+    }
+    Ok(())
+}
+
 /// Format conversation history for display
 fn format_conversation_history(data: &BotData, chat_id: i64) -> String {
-    let store = match data.conversation_store.lock() {
-        Ok(s) => s,
-        Err(_) => return "Failed to access conversation store".to_string(),
-    };
+    let store = &data.conversation_store;
 
-    let history = match store.get_history(chat_id, 10) {
+    let history = match store.get_history(chat_id, data.context_manager.window_for(chat_id)) {
         Ok(h) => h,
         Err(e) => return format!("Error: {}", e),
     };
@@ -3344,9 +6947,23 @@ fn format_conversation_history(data: &BotData, chat_id: i64) -> String {
         return "No conversation history yet.".to_string();
     }
 
-    let mut msg = format!("Recent Conversation ({} messages):\n", history.len());
+    let total_cost: f64 = history.iter().filter_map(|m| m.usage).map(|u| u.cost_usd).sum();
+    let mut msg = if total_cost > 0.0 {
+        format!("Recent Conversation ({} messages, ${:.4}):\n", history.len(), total_cost)
+    } else {
+        format!("Recent Conversation ({} messages):\n", history.len())
+    };
     for m in history {
-        let role = if m.role == "user" { "You" } else { "Bot" };
+        let role = match (m.role.as_str(), m.usage) {
+            ("user", _) => "You".to_string(),
+            (_, Some(usage)) if usage.input_tokens + usage.output_tokens > 0 => format!(
+                "Bot [${:.4}, {} tok]",
+                usage.cost_usd,
+                usage.input_tokens + usage.output_tokens
+            ),
+            (_, Some(usage)) => format!("Bot [${:.4}]", usage.cost_usd),
+            _ => "Bot".to_string(),
+        };
         let content = truncate(&m.content, 100);
         msg.push_str(&format!("\n{}: {}", role, content));
     }
@@ -3355,12 +6972,74 @@ fn format_conversation_history(data: &BotData, chat_id: i64) -> String {
     msg
 }
 
+/// Upper bound on messages read for an export
+const EXPORT_MAX_MESSAGES: usize = 10_000;
+
+/// Render this chat's history as Markdown for /export_conversation
+///
+/// Args: optional `N` (last N messages) or `A-B` (messages A through B,
+/// 1-based, oldest first), and `redact` to run each message through
+/// `sanitize_for_storage`. Returns the document and the message count.
+fn export_conversation_markdown(data: &BotData, chat_id: i64, args: &str) -> std::result::Result<(String, usize), String> {
+    const USAGE: &str = "Usage: /export_conversation [N | A-B] [redact]\n\n\
+        N - Last N messages\n\
+        A-B - Messages A through B (1 = oldest)\n\
+        redact - Mask API keys, tokens and URL passwords";
+
+    let mut redact = false;
+    let mut range: Option<&str> = None;
+    for part in args.split_whitespace() {
+        match part {
+            "redact" | "--redact" => redact = true,
+            _ if range.is_none() => range = Some(part),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let history = {
+        let store = &data.conversation_store;
+        store.get_history(chat_id, EXPORT_MAX_MESSAGES).map_err(|e| format!("Error: {}", e))?
+    };
+    if history.is_empty() {
+        return Err("No conversation history to export.".to_string());
+    }
+
+    let total = history.len();
+    let (start, end) = match range {
+        None => (0, total),
+        Some(range) => match range.split_once('-') {
+            Some((a, b)) => match (a.parse::<usize>(), b.parse::<usize>()) {
+                (Ok(a), Ok(b)) if a >= 1 && a <= b && a <= total => (a - 1, b.min(total)),
+                _ => return Err(format!("Invalid range. This chat has {} messages.\n\n{}", total, USAGE)),
+            },
+            None => match range.parse::<usize>() {
+                Ok(n) if n > 0 => (total.saturating_sub(n), total),
+                _ => return Err(USAGE.to_string()),
+            },
+        },
+    };
+
+    let mut messages = history[start..end].to_vec();
+    if redact {
+        for msg in &mut messages {
+            msg.content = sanitize_for_storage(&msg.content);
+        }
+    }
+
+    let title = format!(
+        "Conversation {} (messages {}-{} of {}{})",
+        chat_id,
+        start + 1,
+        end,
+        total,
+        if redact { ", redacted" } else { "" }
+    );
+    Ok((crate::conversation::format_markdown(&title, &messages), messages.len()))
+}
+
 /// Clear conversation history
 fn clear_conversation_history(data: &BotData, chat_id: i64) -> String {
-    let store = match data.conversation_store.lock() {
-        Ok(s) => s,
-        Err(_) => return "Failed to access conversation store".to_string(),
-    };
+    let store = &data.conversation_store;
 
     match store.clear(chat_id) {
         Ok(count) => format!(
@@ -3377,7 +7056,7 @@ fn clear_conversation_history(data: &BotData, chat_id: i64) -> String {
 /// Get relevant memories as context for a prompt
 #[allow(dead_code)]
 fn get_memory_context(data: &BotData, prompt: &str) -> String {
-    let store = data.memory_store.lock().unwrap();
+    let store = &data.memory_store;
 
     // Search for relevant memories
     let results = match store.search(prompt, 3) {
@@ -3398,11 +7077,8 @@ fn get_memory_context(data: &BotData, prompt: &str) -> String {
 
 #[allow(dead_code)]
 async fn get_memory_context_async(data: &BotData, prompt: &str) -> String {
-    // Get embedder outside the lock
-    let embedder = {
-        let store = data.memory_store.lock().unwrap();
-        store.get_embedder()
-    };
+    // Get embedder
+    let embedder = data.memory_store.get_embedder();
 
     // HyDE: Generate hypothetical answer for better retrieval
     // Only for question-like prompts (contains ? or starts with what/who/how/why/when/where)
@@ -3422,7 +7098,7 @@ async fn get_memory_context_async(data: &BotData, prompt: &str) -> String {
     // Use HyDE text for embedding if available, otherwise use original prompt
     let text_to_embed = hyde_text.as_deref().unwrap_or(prompt);
 
-    // Compute embedding outside the lock (async)
+    // Compute embedding (async)
     let (query_embedding, has_reranker) = if let Some(ref embedder) = embedder {
         let emb = embedder.read().await;
         (emb.embed(text_to_embed).await.ok(), emb.has_reranker())
@@ -3432,10 +7108,10 @@ async fn get_memory_context_async(data: &BotData, prompt: &str) -> String {
 
     // Get initial results with hybrid search
     let results = {
-        let store = data.memory_store.lock().unwrap();
+        let store = &data.memory_store;
         // Fetch more candidates if we'll rerank
         let fetch_limit = if has_reranker { 10 } else { 3 };
-        match store.search_hybrid_sync(prompt, query_embedding, fetch_limit, 0.4) {
+        match store.search_hybrid_sync(prompt, query_embedding, fetch_limit, 0.4, MemoryScope::Global) {
             Ok(r) => r,
             Err(_) => return String::new(),
         }
@@ -3482,14 +7158,65 @@ async fn get_memory_context_async(data: &BotData, prompt: &str) -> String {
     context
 }
 
+/// Summarize the chat's CLI session with Llama and start a fresh one once it
+/// has grown past the compaction threshold
+async fn maybe_compact_session(bot: &Bot, chat_id: ChatId, data: &BotData, working_dir: &Path) -> Result<()> {
+    let config = &data.session_compaction;
+    let size = session_compaction::load_size(working_dir);
+    if !config.needs_compaction(&size) {
+        return Ok(());
+    }
+    if !data.llama_worker.is_available().await {
+        tracing::debug!("Session needs compaction but Llama is unavailable; will retry");
+        return Ok(());
+    }
+
+    let messages = {
+        let store = &data.conversation_store;
+        store.get_history(chat_id.0, store.max_messages())?
+    };
+    // Prefer this session's exchanges; older sessions were summarized already
+    let in_session: Vec<_> = messages.iter().filter(|m| m.timestamp >= size.started_at).collect();
+    let messages: Vec<_> = if in_session.len() >= 2 { in_session } else { messages.iter().collect() };
+    if messages.is_empty() {
+        return Ok(());
+    }
+    let context: Vec<(&str, &str)> = messages.iter().map(|m| (m.role.as_str(), m.content.as_str())).collect();
+
+    let summary = data.llama_worker.compress_context(&context, config.target_ratio).await?;
+    if summary.trim().is_empty() {
+        anyhow::bail!("Llama returned an empty summary");
+    }
+    session_compaction::start_fresh(working_dir, &summary)?;
+    tracing::info!(
+        "Compacted session in {} (~{} tokens, {} exchanges) into a {}-char summary",
+        working_dir.display(),
+        size.tokens,
+        size.exchanges,
+        summary.len()
+    );
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "🗜 This session reached ~{} tokens over {} exchanges, so it was summarized. \
+            Your next message starts a fresh Claude session that begins from the summary.",
+            size.tokens, size.exchanges
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
 /// Extract facts from Claude's response for learning using LLM-based extraction
 ///
 /// Uses the autonomous learner module with Ollama for intelligent fact extraction,
-/// falling back to pattern matching if LLM is unavailable.
-async fn extract_and_learn_facts_async(data: &BotData, response: &str, user_id: i64) {
+/// falling back to pattern matching if LLM is unavailable. Returns how many
+/// facts were extracted.
+async fn extract_and_learn_facts_async(data: &BotData, response: &str, user_id: i64) -> usize {
     // Skip short responses
     if response.len() < 50 {
-        return;
+        return 0;
     }
 
     // Use autonomous learner for LLM-based fact extraction
@@ -3497,15 +7224,13 @@ async fn extract_and_learn_facts_async(data: &BotData, response: &str, user_id:
 
     if !facts.is_empty() {
         // Store extracted facts in memory
-        if let Ok(store) = data.memory_store.lock() {
-            let source = format!("auto_learn_response_{}", user_id);
-            for fact in &facts {
-                if let Ok(id) = store.learn(&fact.content, &fact.category, &source, fact.confidence as f64) {
-                    tracing::debug!("Auto-learned fact: {} ({})", &id[..8.min(id.len())], fact.category);
-                }
+        let source = format!("auto_learn_response_{}", user_id);
+        for fact in &facts {
+            if let Ok(id) = data.memory_store.learn(&fact.content, &fact.category, &source, Some(user_id), fact.confidence as f64) {
+                tracing::debug!("Auto-learned fact: {} ({})", &id[..8.min(id.len())], fact.category);
             }
         }
-        return;
+        return facts.len();
     }
 
     // Fallback: Pattern-based extraction if LLM extraction found nothing
@@ -3517,23 +7242,196 @@ async fn extract_and_learn_facts_async(data: &BotData, response: &str, user_id:
         "Key point:",
     ];
 
+    let mut found = Vec::new();
     for line in response.lines() {
         for pattern in &patterns {
             if line.contains(pattern) {
                 let fact = line.replace(pattern, "").trim().to_string();
                 if !fact.is_empty() && fact.len() > 10 {
-                    let _ = learn_fact_async(data, &fact, user_id).await;
+                    found.push(fact);
                 }
             }
         }
     }
+
+    // One classification call for all of them
+    let refs: Vec<&str> = found.iter().map(String::as_str).collect();
+    let categories = data.autonomous_learner.categorize_batch(&refs, &data.llama_worker).await;
+    for (fact, category) in found.iter().zip(categories) {
+        let _ = learn_fact_async(data, fact, &category.category, user_id).await;
+    }
+    found.len()
+}
+
+/// Show or change the chat's conversation context window
+fn context_window_command(data: &BotData, chat_id: i64, args: &str) -> String {
+    let manager = &data.context_manager;
+    let config = manager.config();
+    match args {
+        "" => format!(
+            "Context window: {} messages{}\n\
+            Token ceiling: {} tokens (oldest messages dropped first)\n\n\
+            /context window <N> - Set for this chat\n\
+            /context window reset - Use the default ({})",
+            manager.window_for(chat_id),
+            if manager.has_window_override(chat_id) { " (this chat)" } else { "" },
+            config.max_conversation_tokens,
+            config.context_window_messages
+        ),
+        "reset" | "default" => format!(
+            "Context window reset to {} messages.",
+            manager.set_window_override(chat_id, None)
+        ),
+        n => match n.parse::<usize>() {
+            Ok(n) => format!(
+                "Context window set to {} messages (max {} tokens).",
+                manager.set_window_override(chat_id, Some(n)),
+                config.max_conversation_tokens
+            ),
+            Err(_) => "Usage: /context window <N> | reset".to_string(),
+        },
+    }
+}
+
+/// `/cd [path|reset]`
+fn change_working_dir(data: &BotData, user_id: i64, args: &str) -> String {
+    let dirs = &data.working_dirs;
+    match args {
+        "" => {
+            let roots = std::iter::once(format!("- {} (yours)", dirs.default_for(user_id).display()))
+                .chain(dirs.allowed_roots().iter().map(|root| format!("- {}", root.display())))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!(
+                "Working dir: {}\n\n\
+                /cd <path> - Switch (absolute, or relative to the current dir)\n\
+                /cd reset - Back to {}\n\n\
+                Allowed directories (yours plus CLAUDEBOT_ALLOWED_DIRS):\n{}",
+                dirs.for_user(user_id).display(),
+                dirs.default_for(user_id).display(),
+                roots
+            )
+        }
+        "reset" => match dirs.reset(user_id) {
+            Ok(dir) => format!("Working dir reset to {}", dir.display()),
+            Err(e) => format!("Failed to save working dir: {}", e),
+        },
+        path => match dirs.set(user_id, path) {
+            Ok(dir) => format!("Working dir: {}", dir.display()),
+            Err(e) => format!("Can't switch: {}", e),
+        },
+    }
+}
+
+/// `/context strategy [full|fast|graph|none|reset]`
+fn context_strategy_command(data: &BotData, user_id: i64, args: &str) -> String {
+    let manager = &data.context_manager;
+    let options = ContextStrategy::ALL
+        .iter()
+        .map(|s| format!("{} - {}", s.as_str(), s.description()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    match args {
+        "" => {
+            let strategy = manager.strategy_for(user_id);
+            format!(
+                "Context strategy: {}{}\n\n{}\n\n\
+                /context strategy <name> - Set for you\n\
+                /context strategy reset - Use the default ({})",
+                strategy.as_str(),
+                if manager.has_strategy_override(user_id) { " (yours)" } else { "" },
+                options,
+                manager.config().strategy.as_str()
+            )
+        }
+        "reset" | "default" => format!(
+            "Context strategy reset to {}.",
+            manager.set_strategy_override(user_id, None).as_str()
+        ),
+        name => match ContextStrategy::parse(name) {
+            Some(strategy) => format!(
+                "Context strategy set to {}: {}.",
+                manager.set_strategy_override(user_id, Some(strategy)).as_str(),
+                strategy.description()
+            ),
+            None => format!("Unknown strategy '{}'. Options:\n{}", name, options),
+        },
+    }
+}
+
+/// `/context show`: settings context is built with for this chat and user
+fn show_context_settings(data: &BotData, chat_id: i64, user_id: i64) -> String {
+    let manager = &data.context_manager;
+    let config = manager.config();
+    let strategy = manager.strategy_for(user_id);
+    format!(
+        "Context settings\n\n\
+        Strategy: {}{} ({})\n\
+        Window: {} messages{}, max {} tokens\n\
+        Total budget: {}\n\
+        Memories: up to {} (min relevance {:.2}), memory scope: {:?}\n\
+        Graph expansion: up to {} facts, {} tokens (graph strategy)\n\
+        HyDE: {}, goals: {}, identity: {}",
+        strategy.as_str(),
+        if manager.has_strategy_override(user_id) { " (yours)" } else { "" },
+        strategy.description(),
+        manager.window_for(chat_id),
+        if manager.has_window_override(chat_id) { " (this chat)" } else { "" },
+        config.max_conversation_tokens,
+        if config.max_context_tokens == 0 { "unlimited".to_string() } else { format!("{} tokens", config.max_context_tokens) },
+        config.max_memories,
+        config.min_relevance,
+        config.memory_scope,
+        config.max_graph_facts,
+        config.max_graph_tokens,
+        if config.use_hyde { "on" } else { "off" },
+        if config.include_goals { "on" } else { "off" },
+        if config.include_identity { "on" } else { "off" },
+    )
+}
+
+/// Sizes of every store plus the vector index (stores that fail to report are skipped)
+fn storage_report(data: &BotData) -> StorageReport {
+    let (memory, index_bytes) = {
+        let store = &data.memory_store;
+        (store.storage_stats(), store.index_memory_bytes())
+    };
+    let databases = [
+        memory,
+        data.conversation_store.storage_stats(),
+        data.goal_tracker.storage_stats(),
+        data.usage_tracker.storage_stats(),
+    ]
+    .into_iter()
+    .filter_map(|stats| stats.map_err(|e| tracing::warn!("Storage stats failed: {}", e)).ok())
+    .collect();
+    StorageReport::new(databases, index_bytes)
+}
+
+fn format_storage_report(report: &StorageReport) -> String {
+    let mut msg = "Storage\n".to_string();
+    for db in &report.databases {
+        msg.push_str(&format!("\n{}: {}", db.name, format_bytes(db.bytes)));
+        if db.free_bytes > 0 {
+            msg.push_str(&format!(" ({} reclaimable)", format_bytes(db.free_bytes)));
+        }
+        msg.push('\n');
+        for table in &db.tables {
+            msg.push_str(&format!("- {}: {} rows\n", table.table, table.rows));
+        }
+    }
+    msg.push_str(&format!(
+        "\nVector index: ~{} in memory\nTotal: {}",
+        format_bytes(report.vector_index_bytes),
+        format_bytes(report.total_bytes)
+    ));
+    msg
 }
 
 /// Load system context and store key facts
 fn load_context(data: &BotData) -> String {
     // Store key facts as memories
-    let mut learned_count = 0;
-    let store = data.memory_store.lock().unwrap();
+    let store = &data.memory_store;
 
     let key_facts = [
         ("identity", "I am Eliot, an AI coding assistant powered by Claude, operating as a Telegram bot with persistent memory"),
@@ -3545,11 +7443,17 @@ fn load_context(data: &BotData) -> String {
         ("team", "CEO: Technical (can code), marketing genius, delegates to workers/AI, prefers results over status updates"),
     ];
 
-    for (category, fact) in key_facts {
-        if store.learn(fact, category, "context_load", 0.95).is_ok() {
-            learned_count += 1;
+    let entries: Vec<_> = key_facts
+        .iter()
+        .map(|(category, fact)| (*fact, *category, "context_load", None, 0.95, None))
+        .collect();
+    let learned_count = match store.learn_batch(&entries) {
+        Ok(batch) => batch.ids.len(),
+        Err(e) => {
+            tracing::warn!("Failed to store context facts: {}", e);
+            0
         }
-    }
+    };
 
     format!(
         "Context Loaded\n\n\
@@ -3708,21 +7612,7 @@ fn contains_sensitive_data(text: &str) -> bool {
 
 /// Sanitize text for safe storage/logging by redacting sensitive patterns
 fn sanitize_for_storage(text: &str) -> String {
-    let mut result = text.to_string();
-
-    // Redact API keys (sk-xxx... → sk-[REDACTED])
-    let api_key_re = regex::Regex::new(r"(sk-|pk-)[a-zA-Z0-9]{10,}").unwrap();
-    result = api_key_re.replace_all(&result, "$1[REDACTED]").to_string();
-
-    // Redact bearer tokens
-    let bearer_re = regex::Regex::new(r"(?i)(bearer\s+)[a-zA-Z0-9._-]+").unwrap();
-    result = bearer_re.replace_all(&result, "$1[REDACTED]").to_string();
-
-    // Redact passwords in URLs
-    let url_pass_re = regex::Regex::new(r"(://[^:]+:)[^@]+(@)").unwrap();
-    result = url_pass_re.replace_all(&result, "$1[REDACTED]$2").to_string();
-
-    result
+    postprocess::redact_secrets(text)
 }
 
 async fn handle_document(
@@ -3734,6 +7624,14 @@ async fn handle_document(
     working_dir: &PathBuf,
     user_id: i64,
 ) -> Result<()> {
+    if msg.caption().map(str::trim) == Some("/graph import") {
+        if !data.is_admin(user_id) {
+            bot.send_message(chat_id, "Graph import requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+            return Ok(());
+        }
+        return import_graph_document(bot, chat_id, data, doc).await;
+    }
+
     // Check limits
     if let Err(limit_msg) = check_user_limits(data, user_id) {
         bot.send_message(chat_id, limit_msg).await?;
@@ -3765,8 +7663,8 @@ async fn handle_document(
         data.permission_manager.get_status(user_id).level,
         crate::permissions::PermissionLevel::Autonomous
     );
-    let response = invoke_claude_cli(&prompt, working_dir, is_autonomous).await?;
-    record_usage(data, user_id, &response);
+    let response = data.invoke_claude(user_id, chat_id.0, &prompt, working_dir, is_autonomous).await?;
+    record_usage(data, user_id, &response, "document");
     send_long_message(bot, chat_id, &response.text).await?;
 
     Ok(())
@@ -3807,146 +7705,57 @@ async fn handle_photo(
         data.permission_manager.get_status(user_id).level,
         crate::permissions::PermissionLevel::Autonomous
     );
-    let response = invoke_claude_cli(&prompt, working_dir, is_autonomous).await?;
-    record_usage(data, user_id, &response);
+    let response = data.invoke_claude(user_id, chat_id.0, &prompt, working_dir, is_autonomous).await?;
+    record_usage(data, user_id, &response, "photo");
     send_long_message(bot, chat_id, &response.text).await?;
 
+    if let Some(text) = data.ocr.text_for(&file_path, &response.text).await {
+        let stored = data.memory_store.learn(
+            &ocr::memory_content(&file_path, &text),
+            ocr::OCR_CATEGORY,
+            &ocr::memory_source(&file_path, user_id),
+            Some(user_id),
+            0.8,
+        );
+        match stored {
+            Ok(id) => tracing::info!("Stored image text from {} as memory {}", file_name, id),
+            Err(e) => tracing::warn!("Failed to store image text: {}", e),
+        }
+    }
+
     Ok(())
 }
 
-async fn send_long_message(bot: &Bot, chat_id: ChatId, text: &str) -> Result<()> {
-    const MAX: usize = 4000;
+async fn send_long_message(bot: &Bot, chat_id: ChatId, text: &str) -> Result<Message> {
+    Ok(send_long_message_parts(bot, chat_id, text).await?.1)
+}
 
+/// `send_long_message`, also returning the id of the first part
+async fn send_long_message_parts(bot: &Bot, chat_id: ChatId, text: &str) -> Result<(MessageId, Message)> {
     if text.is_empty() {
-        bot.send_message(chat_id, "(no response)").await?;
-        return Ok(());
+        let sent = bot.send_message(chat_id, "(no response)").await?;
+        return Ok((sent.id, sent));
     }
 
-    // Convert markdown to HTML for proper code formatting
-    let html_text = markdown_to_telegram_html(text);
-
-    if html_text.len() <= MAX {
-        // Try HTML first, fall back to plain text if it fails
-        match bot.send_message(chat_id, &html_text)
+    // Markdown code blocks become HTML; each chunk falls back to plain text
+    // if Telegram rejects its HTML
+    let mut first = None;
+    let mut last = None;
+    for chunk in channels::format_response(text, channels::ParseMode::Html) {
+        let sent = match bot.send_message(chat_id, &chunk.text)
             .parse_mode(ParseMode::Html)
             .await
         {
-            Ok(_) => {}
-            Err(_) => {
-                // HTML failed (probably malformed), send as plain text
-                bot.send_message(chat_id, text).await?;
-            }
-        }
-    } else {
-        // For long messages, split and send as plain text to avoid breaking HTML tags
-        let mut remaining = text;
-        while !remaining.is_empty() {
-            let split_at = remaining
-                .char_indices()
-                .take_while(|(i, _)| *i < MAX)
-                .last()
-                .map(|(i, c)| i + c.len_utf8())
-                .unwrap_or(remaining.len());
-            let (chunk, rest) = remaining.split_at(split_at);
-
-            // Try HTML for each chunk
-            let html_chunk = markdown_to_telegram_html(chunk);
-            match bot.send_message(chat_id, &html_chunk)
-                .parse_mode(ParseMode::Html)
-                .await
-            {
-                Ok(_) => {}
-                Err(_) => {
-                    bot.send_message(chat_id, chunk).await?;
-                }
-            }
-            remaining = rest;
-        }
-    }
-    Ok(())
-}
-
-/// Convert markdown code blocks to Telegram HTML format
-fn markdown_to_telegram_html(text: &str) -> String {
-    let mut result = String::with_capacity(text.len() + 100);
-    let mut chars = text.chars().peekable();
-    let mut in_code_block = false;
-    let mut in_inline_code = false;
-
-    while let Some(c) = chars.next() {
-        if c == '`' {
-            // Check for code block (```)
-            if chars.peek() == Some(&'`') {
-                chars.next(); // consume second `
-                if chars.peek() == Some(&'`') {
-                    chars.next(); // consume third `
-
-                    if in_code_block {
-                        result.push_str("</code></pre>");
-                        in_code_block = false;
-                    } else {
-                        // Skip language identifier if present (e.g., ```rust)
-                        while let Some(&ch) = chars.peek() {
-                            if ch == '\n' {
-                                chars.next();
-                                break;
-                            } else if ch.is_alphanumeric() || ch == '_' || ch == '-' {
-                                chars.next();
-                            } else if ch == '\n' || ch == '\r' {
-                                chars.next();
-                                break;
-                            } else {
-                                break;
-                            }
-                        }
-                        result.push_str("<pre><code>");
-                        in_code_block = true;
-                    }
-                    continue;
-                }
-            }
-
-            // Single backtick - inline code
-            if !in_code_block {
-                if in_inline_code {
-                    result.push_str("</code>");
-                    in_inline_code = false;
-                } else {
-                    result.push_str("<code>");
-                    in_inline_code = true;
-                }
-                continue;
-            }
-        }
-
-        // Escape HTML special characters (but not inside code blocks for readability)
-        if !in_code_block && !in_inline_code {
-            match c {
-                '<' => result.push_str("&lt;"),
-                '>' => result.push_str("&gt;"),
-                '&' => result.push_str("&amp;"),
-                _ => result.push(c),
-            }
-        } else {
-            // Inside code: still escape < and > to prevent HTML injection
-            match c {
-                '<' => result.push_str("&lt;"),
-                '>' => result.push_str("&gt;"),
-                '&' => result.push_str("&amp;"),
-                _ => result.push(c),
-            }
-        }
-    }
-
-    // Close any unclosed tags
-    if in_inline_code {
-        result.push_str("</code>");
+            Ok(sent) => sent,
+            Err(_) => bot.send_message(chat_id, &chunk.plain).await?,
+        };
+        first.get_or_insert(sent.id);
+        last = Some(sent);
     }
-    if in_code_block {
-        result.push_str("</code></pre>");
+    match (first, last) {
+        (Some(first), Some(last)) => Ok((first, last)),
+        _ => anyhow::bail!("Nothing sent"),
     }
-
-    result
 }
 
 // ============ Bypass Bridge Functions ============
@@ -3983,28 +7792,39 @@ async fn handle_bypass(
     // Execute on bridge via gRPC streaming
     bot.send_message(chat_id, "Sending to AR bridge (gRPC)...").await?;
 
-    match client.execute_full(chat_id.0, task, None).await {
+    let bridge_task = data.task_registry.start(TaskKind::Bridge, user_id, chat_id.0, task);
+    let start = Instant::now();
+    let result = client.execute_full(chat_id.0, task, None).await;
+    drop(bridge_task);
+    match result {
         Ok(result) => {
             if result.success {
+                data.metrics.record(BRIDGE_USAGE_MODEL, 0, 0, 0, start.elapsed(), false, Some("bridge"));
                 // Format response with metadata
                 let mut reply = result.text.clone();
                 if let Some(cost) = result.cost_usd {
+                    if let Err(e) = data.usage_tracker.record_cost(user_id, BRIDGE_USAGE_MODEL, cost, ORIGIN_BYPASS) {
+                        tracing::error!("Failed to record bridge cost: {}", e);
+                    }
                     reply.push_str(&format!("\n\n[Cost: ${:.4}, Duration: {}ms]", cost, result.duration_ms));
                 } else {
                     reply.push_str(&format!("\n\n[Duration: {}ms]", result.duration_ms));
                 }
 
                 // Store in conversation
-                store_conversation_exchange(data, chat_id.0, task, &result.text);
+                let usage = result.cost_usd.map(|cost_usd| TurnUsage { input_tokens: 0, output_tokens: 0, cost_usd });
+                store_conversation_exchange(data, chat_id.0, None, task, &result.text, usage);
 
                 send_long_message(bot, chat_id, &reply).await?;
             } else {
                 let error_msg = result.error.unwrap_or_else(|| "Unknown error".to_string());
+                data.metrics.record_failure(FailureReason::classify_message(&error_msg, FailureReason::BridgeError));
                 bot.send_message(chat_id, format!("Bridge execution failed:\n{}", error_msg)).await?;
             }
         }
         Err(e) => {
             tracing::error!("Bridge execution error: {}", e);
+            data.metrics.record_failure(FailureReason::classify_message(&e.to_string(), FailureReason::BridgeError));
             bot.send_message(chat_id, format!("Bridge connection error:\n{}", e)).await?;
         }
     }
@@ -4012,6 +7832,313 @@ async fn handle_bypass(
     Ok(())
 }
 
+/// UI action id for confirmations created by cost estimates
+const COST_CONFIRM_ACTION: &str = "cost";
+
+/// Usage model name for bridge runs (cost only, no token breakdown)
+const BRIDGE_USAGE_MODEL: &str = "bridge";
+
+/// Estimated cost above which Circle and bridge runs ask first
+/// (`CLAUDEBOT_COST_CONFIRM_USD`, default $0.50)
+fn cost_confirm_threshold() -> f64 {
+    std::env::var("CLAUDEBOT_COST_CONFIRM_USD")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0.50)
+}
+
+/// Parse `/circle` arguments into mode and task
+fn parse_circle_args(args: &str) -> (PipelineMode, &str) {
+    if let Some(task) = args.strip_prefix("full ") {
+        (PipelineMode::Full, task)
+    } else if let Some(task) = args.strip_prefix("review ") {
+        (PipelineMode::ReviewOnly, task)
+    } else if let Some(task) = args.strip_prefix("security ") {
+        (PipelineMode::SecurityOnly, task)
+    } else if let Some(task) = args.strip_prefix("quick ") {
+        (PipelineMode::QuickFix, task)
+    } else {
+        // Default to security audit
+        (PipelineMode::SecurityOnly, args)
+    }
+}
+
+/// Code context for a Circle task - either the task description or a file it names
+async fn circle_context(task: &str, working_dir: &std::path::Path) -> String {
+    if task.contains(".rs") || task.contains(".ts") || task.contains(".vue") {
+        // Try to extract file path and read it
+        if let Some(file_path) = extract_file_path(task) {
+            let full_path = working_dir.join(&file_path);
+            if let Ok(content) = tokio::fs::read_to_string(&full_path).await {
+                return format!("File: {}\n\n```\n{}\n```", file_path, content);
+            }
+        }
+    }
+    task.to_string()
+}
+
+/// Run the Circle pipeline and report the result
+async fn run_circle(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    mode: PipelineMode,
+    task: &str,
+    context: &str,
+    user_id: i64,
+) -> Result<()> {
+    bot.send_message(chat_id, format!(
+        "Starting Development Circle ({:?})...\n\n\
+        Task: {}\n\n\
+        This may take a few minutes.",
+        mode, truncate(task, 100)
+    )).await?;
+
+    bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
+
+    let api_key = std::env::var("ANTHROPIC_API_KEY").ok();
+    let claude_client = crate::claude::ClaudeClient::new(api_key.as_deref());
+    let circle = Circle::new(claude_client, data.circle_personas.clone());
+
+    let circle_task = data.task_registry.start(TaskKind::Circle, user_id, chat_id.0, task);
+    let result = circle.run(task, context, mode).await;
+    drop(circle_task);
+    match result {
+        Ok(result) => {
+            record_circle_usage(data, user_id, &result);
+            let summary = format_circle_result(&result);
+            send_long_message(bot, chat_id, &summary).await?;
+
+            // Store in UI context
+            data.update_ui_context(chat_id.0, |ctx| {
+                ctx.set_command(&format!("/circle {:?} {}", mode, task));
+            }).await;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, format!(
+                "Circle pipeline failed:\n{}",
+                e
+            )).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Record each Circle phase as a separate request
+fn record_circle_usage(data: &BotData, user_id: i64, result: &PipelineResult) {
+    let timestamp = chrono::Utc::now().timestamp();
+    for phase in &result.phases {
+        if phase.input_tokens == 0 && phase.output_tokens == 0 {
+            continue;
+        }
+        let record = UsageRecord {
+            user_id,
+            input_tokens: phase.input_tokens as i64,
+            output_tokens: phase.output_tokens as i64,
+            cache_read_tokens: phase.cache_read_tokens as i64,
+            cache_write_tokens: phase.cache_write_tokens as i64,
+            model: phase.model.clone(),
+            timestamp,
+            origin: ORIGIN_CIRCLE.to_string(),
+            estimated: false,
+        };
+        if let Err(e) = data.usage_tracker.record_usage(&record) {
+            tracing::error!("Failed to record Circle usage: {}", e);
+        }
+    }
+}
+
+/// Bridge cost estimate from the historical average of past runs
+fn bridge_estimate(data: &BotData) -> String {
+    match data.usage_tracker.average_request_cost(BRIDGE_USAGE_MODEL) {
+        Ok(Some((avg, count))) => format!(
+            "Bridge estimate:\n  Average cost per run: {} (from {} runs)",
+            TokenCounter::format_cost(avg),
+            count
+        ),
+        Ok(None) => "Bridge estimate:\n  No bridge runs recorded yet, cost unknown".to_string(),
+        Err(e) => format!("Bridge estimate unavailable: {}", e),
+    }
+}
+
+/// Show an estimate with Confirm/Cancel buttons, remembering the command to run
+async fn request_cost_confirmation(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    estimate: &str,
+    command: &str,
+) -> Result<()> {
+    data.update_ui_context(chat_id.0, |ctx| ctx.set_confirmation(command)).await;
+    bot.send_message(chat_id, format!("{}\n\nProceed with {}?", estimate, truncate(command, 80)))
+        .reply_markup(confirmation_keyboard(COST_CONFIRM_ACTION))
+        .await?;
+    Ok(())
+}
+
+/// Run a command the user confirmed after seeing its cost estimate
+async fn run_confirmed_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    command: &str,
+    working_dir: &std::path::Path,
+    user_id: i64,
+) -> Result<()> {
+    if let Some(args) = command.strip_prefix("/circle ") {
+        let (mode, task) = parse_circle_args(args);
+        let context = circle_context(task, working_dir).await;
+        run_circle(bot, chat_id, data, mode, task, &context, user_id).await
+    } else if let Some(task) = command.strip_prefix("/bypass ") {
+        handle_bypass(bot, chat_id, data, task, user_id).await
+    } else if let Some(args) = command.strip_prefix("/bypass_write ") {
+        match parse_bypass_write(args) {
+            Ok((path, mode, content)) => handle_bypass_write(bot, chat_id, data, path, mode, content, user_id).await,
+            Err(usage) => {
+                bot.send_message(chat_id, usage).await?;
+                Ok(())
+            }
+        }
+    } else if command == REEMBED_COMMAND {
+        reembed_memories(bot, chat_id, data).await
+    } else {
+        bot.send_message(chat_id, "Confirmed.").await?;
+        Ok(())
+    }
+}
+
+/// Run every health check for /diag, reusing the individual checkers
+async fn run_diagnostics(data: &BotData) -> DiagReport {
+    let mut report = DiagReport::default();
+
+    let (claude, preflight, missing_models, bridge, disk) = tokio::join!(
+        async { ClaudeCli::from_env().command().arg("--version").output().await },
+        data.preflight_checker.check_all(),
+        data.llama_worker.missing_models(),
+        async {
+            match &data.bridge_client {
+                Some(client) => Some(client.test_connection().await),
+                None => None,
+            }
+        },
+        available_disk_bytes(&data.base_working_dir),
+    );
+
+    match claude {
+        Ok(output) if output.status.success() => {
+            report.push("Claude CLI", DiagStatus::Pass, String::from_utf8_lossy(&output.stdout).trim());
+        }
+        _ => report.push(
+            "Claude CLI",
+            DiagStatus::Fail,
+            "not found (npm install -g @anthropic-ai/claude-code)",
+        ),
+    }
+
+    if preflight.missing_tools.is_empty() && preflight.missing_creds.is_empty() {
+        report.push("Tools & credentials", DiagStatus::Pass, "all found");
+    } else {
+        let missing: Vec<&str> = preflight
+            .missing_tools
+            .iter()
+            .chain(&preflight.missing_creds)
+            .map(String::as_str)
+            .collect();
+        report.push("Tools & credentials", DiagStatus::Warn, format!("missing {}", missing.join("; ")));
+    }
+
+    for endpoint in preflight.connectivity.iter().flat_map(|c| &c.endpoints) {
+        let (status, detail) = match (endpoint.name, endpoint.reachable, &missing_models) {
+            ("Ollama", true, Ok(missing)) if !missing.is_empty() => (
+                DiagStatus::Warn,
+                format!("missing models: {} (ollama pull <model>)", missing.join(", ")),
+            ),
+            (_, true, _) => (DiagStatus::Pass, endpoint.format_line()),
+            (_, false, _) if endpoint.required => (DiagStatus::Fail, endpoint.format_line()),
+            (_, false, _) => (DiagStatus::Warn, endpoint.format_line()),
+        };
+        report.push(endpoint.name, status, detail);
+    }
+
+    match disk {
+        Ok(bytes) => {
+            let status = if bytes < DISK_FAIL_BYTES {
+                DiagStatus::Fail
+            } else if bytes < DISK_WARN_BYTES {
+                DiagStatus::Warn
+            } else {
+                DiagStatus::Pass
+            };
+            report.push(
+                "Disk space",
+                status,
+                format!("{:.1} GB free in {}", bytes as f64 / 1e9, data.base_working_dir.display()),
+            );
+        }
+        Err(e) => report.push("Disk space", DiagStatus::Warn, e),
+    }
+
+    let writable = [
+        ("memory", data.memory_store.check_writable()),
+        ("conversations", data.conversation_store.check_writable()),
+    ];
+    let failed: Vec<String> = writable
+        .iter()
+        .filter_map(|(name, result)| result.as_ref().err().map(|e| format!("{}: {}", name, e)))
+        .collect();
+    if failed.is_empty() {
+        report.push("Databases", DiagStatus::Pass, "memory and conversations writable");
+    } else {
+        report.push("Databases", DiagStatus::Fail, failed.join("; "));
+    }
+
+    match bridge {
+        None => report.push("Bridge", DiagStatus::Pass, "not configured (optional)"),
+        Some(Ok(_)) => report.push("Bridge", DiagStatus::Pass, "connected"),
+        Some(Err(e)) => report.push("Bridge", DiagStatus::Fail, format!("disconnected: {}", e)),
+    }
+
+    let embeddings = data.memory_store.embedding_stats();
+    match embeddings {
+        Ok(stats) if stats.total_memories == 0 => {
+            report.push("Embeddings", DiagStatus::Pass, "no memories yet");
+        }
+        Ok(stats) => {
+            let status = if stats.coverage_percent >= 90.0 { DiagStatus::Pass } else { DiagStatus::Warn };
+            let mut detail = format!(
+                "{:.1}% coverage ({}/{})",
+                stats.coverage_percent, stats.with_embeddings, stats.total_memories
+            );
+            if status == DiagStatus::Warn {
+                detail.push_str(" - run /memory backfill");
+            }
+            report.push("Embeddings", status, detail);
+        }
+        Err(e) => report.push("Embeddings", DiagStatus::Fail, e.to_string()),
+    }
+
+    let ocr_config = data.ocr.config();
+    match data.ocr.backend() {
+        Some(backend) => report.push("Image OCR", DiagStatus::Pass, backend.name()),
+        None if !ocr_config.enabled => report.push("Image OCR", DiagStatus::Pass, "disabled"),
+        None => report.push(
+            "Image OCR",
+            DiagStatus::Warn,
+            format!("{} backend unavailable", ocr_config.backend.name()),
+        ),
+    }
+
+    let lifecycle = data.lifecycle.get_stats();
+    report.push(
+        "Lifecycle",
+        DiagStatus::Pass,
+        format!("{:?}, idle {}s", lifecycle.current_state, lifecycle.idle_seconds),
+    );
+
+    report
+}
+
 /// Handle bypass status command - check gRPC bridge health
 async fn handle_bypass_status(
     bot: &Bot,
@@ -4043,6 +8170,177 @@ async fn handle_bypass_status(
     Ok(())
 }
 
+/// Parse `/bypass_write <path> [mode]` with the file content on the following lines
+fn parse_bypass_write(args: &str) -> std::result::Result<(&str, Option<u32>, &str), String> {
+    const USAGE: &str = "BYPASS WRITE\n\n\
+        Write a file on the AR server (paths must be in BRIDGE_WRITE_ALLOWED_PATHS).\n\n\
+        Usage: /bypass_write <path> [mode]\n\
+        <file content on the following lines>\n\n\
+        Example:\n\
+        /bypass_write /etc/claudebot/deploy/app.toml 600\n\
+        port = 8080";
+
+    let (header, content) = args.split_once('\n').unwrap_or((args, ""));
+    let mut parts = header.split_whitespace();
+    let path = parts.next().ok_or_else(|| USAGE.to_string())?;
+    let mode = match parts.next() {
+        Some(m) => Some(
+            u32::from_str_radix(m.trim_start_matches("0o"), 8)
+                .ok()
+                .filter(|m| *m <= 0o777)
+                .ok_or_else(|| format!("Invalid mode '{}'. Use octal, e.g. 644 or 600", m))?,
+        ),
+        None => None,
+    };
+    if parts.next().is_some() || content.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    Ok((path, mode, content))
+}
+
+/// Handle confirmed bypass file write via gRPC
+async fn handle_bypass_write(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    path: &str,
+    mode: Option<u32>,
+    content: &str,
+    user_id: i64,
+) -> Result<()> {
+    let Some(client) = &data.bridge_client else {
+        bot.send_message(chat_id,
+            "Bridge not configured.\n\n\
+            Set BRIDGE_GRPC_URL and BRIDGE_API_KEY environment variables."
+        ).await?;
+        return Ok(());
+    };
+
+    if !data.is_allowed(user_id) {
+        bot.send_message(chat_id, "Bypass requires admin permission.").await?;
+        return Ok(());
+    }
+
+    bot.send_chat_action(chat_id, teloxide::types::ChatAction::Typing).await?;
+
+    match client.write_file(chat_id.0, path, content.as_bytes(), mode).await {
+        Ok(resp) if resp.success => {
+            bot.send_message(chat_id, format!("Wrote {} bytes to {} on AR", resp.bytes_written, path)).await?;
+        }
+        Ok(resp) => {
+            let error_msg = resp.error.unwrap_or_else(|| "Unknown error".to_string());
+            bot.send_message(chat_id, format!("File write failed:\n{}", error_msg)).await?;
+        }
+        Err(e) => {
+            tracing::error!("Bridge file write error: {}", e);
+            bot.send_message(chat_id, format!("Bridge connection error:\n{}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// `/bypass_exec` output is edited in place at most this often
+const EXEC_EDIT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Output characters shown in the live message; longer output is also sent as a file
+const EXEC_VIEW_CHARS: usize = 3500;
+
+/// Live `/bypass_exec` message: the command, the tail of its output and a status line
+fn exec_view(command: &str, output: &str, status: &str) -> String {
+    let total = output.chars().count();
+    let shown: String = if total > EXEC_VIEW_CHARS {
+        let tail: String = output.chars().skip(total - EXEC_VIEW_CHARS).collect();
+        format!("...{}", tail)
+    } else if output.is_empty() {
+        "(no output)".to_string()
+    } else {
+        output.to_string()
+    };
+    format!("$ {}\n\n{}\n\n{}", truncate(command, 200), shown.trim_end(), status)
+}
+
+/// Status line for a finished shell command
+fn exec_status(chunk: &crate::bridge::proto::ShellChunk) -> String {
+    if let Some(ref error) = chunk.error {
+        return format!("Failed: {}", error);
+    }
+    let mut status = match chunk.exit_code {
+        Some(0) => "✅ exit 0".to_string(),
+        Some(code) => format!("❌ exit {}", code),
+        None => "⛔ killed".to_string(),
+    };
+    if chunk.timed_out {
+        status.push_str(", timed out");
+    }
+    if chunk.truncated {
+        status.push_str(", output limit reached");
+    }
+    status.push_str(&format!(" ({}ms)", chunk.duration_ms.unwrap_or(0)));
+    status
+}
+
+/// Handle bypass shell command via gRPC, editing one message as output arrives
+async fn handle_bypass_exec(
+    bot: &Bot,
+    chat_id: ChatId,
+    data: &BotData,
+    command: &str,
+    user_id: i64,
+) -> Result<()> {
+    let Some(client) = &data.bridge_client else {
+        bot.send_message(chat_id,
+            "Bridge not configured.\n\n\
+            Set BRIDGE_GRPC_URL and BRIDGE_API_KEY environment variables."
+        ).await?;
+        return Ok(());
+    };
+
+    if !data.is_admin(user_id) {
+        bot.send_message(chat_id, "Bypass requires admin permission (CLAUDEBOT_ADMIN_USERS).").await?;
+        return Ok(());
+    }
+
+    let msg = bot.send_message(chat_id, exec_view(command, "", "⏳ Running on AR...")).await?;
+    let _task = data.task_registry.start(TaskKind::Bridge, user_id, chat_id.0, command);
+
+    let mut stream = match client.exec_shell(chat_id.0, command).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::error!("Bridge shell error: {}", e);
+            bot.edit_message_text(chat_id, msg.id, exec_view(command, "", &format!("Bridge error: {}", e))).await?;
+            return Ok(());
+        }
+    };
+
+    let mut output = String::new();
+    let mut last_edit = Instant::now();
+    let status = loop {
+        match stream.message().await {
+            Ok(Some(chunk)) if chunk.is_final => break exec_status(&chunk),
+            Ok(Some(chunk)) => {
+                output.push_str(&chunk.data);
+                if last_edit.elapsed() >= EXEC_EDIT_INTERVAL {
+                    let _ = bot.edit_message_text(chat_id, msg.id, exec_view(command, &output, "⏳ Running on AR...")).await;
+                    last_edit = Instant::now();
+                }
+            }
+            Ok(None) => break "Stream ended without a result".to_string(),
+            Err(e) => {
+                tracing::error!("Bridge shell stream error: {}", e);
+                break format!("Bridge error: {}", e.message());
+            }
+        }
+    };
+
+    let _ = bot.edit_message_text(chat_id, msg.id, exec_view(command, &output, &status)).await;
+    if output.chars().count() > EXEC_VIEW_CHARS {
+        bot.send_document(chat_id, InputFile::memory(output.into_bytes()).file_name("output.txt")).await?;
+    }
+
+    Ok(())
+}
+
 /// Handle bypass file read command via gRPC
 async fn handle_bypass_file(
     bot: &Bot,
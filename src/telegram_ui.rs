@@ -30,6 +30,8 @@ pub enum ButtonAction {
     Confirm(String),       // action_id
     Deny(String),          // action_id
     SelectOption(String),  // option_id
+    ThumbsUp(String),      // retrieval_id
+    ThumbsDown(String),    // retrieval_id
 }
 
 impl ButtonAction {
@@ -46,6 +48,8 @@ impl ButtonAction {
             Self::Confirm(id) => format!("confirm:{}", id),
             Self::Deny(id) => format!("deny:{}", id),
             Self::SelectOption(id) => format!("select:{}", id),
+            Self::ThumbsUp(id) => format!("thumbs_up:{}", id),
+            Self::ThumbsDown(id) => format!("thumbs_down:{}", id),
         }
     }
 
@@ -68,6 +72,8 @@ impl ButtonAction {
             "confirm" => Some(Self::Confirm(id)),
             "deny" => Some(Self::Deny(id)),
             "select" => Some(Self::SelectOption(id)),
+            "thumbs_up" => Some(Self::ThumbsUp(id)),
+            "thumbs_down" => Some(Self::ThumbsDown(id)),
             _ => None,
         }
     }
@@ -106,6 +112,14 @@ pub fn confirmation_keyboard(action_id: &str) -> InlineKeyboardMarkup {
     ]])
 }
 
+/// Build thumbs-up/down keyboard for rating a response
+pub fn feedback_keyboard(retrieval_id: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback("👍", ButtonAction::ThumbsUp(retrieval_id.to_string()).encode()),
+        InlineKeyboardButton::callback("👎", ButtonAction::ThumbsDown(retrieval_id.to_string()).encode()),
+    ]])
+}
+
 /// Build options keyboard
 pub fn options_keyboard(options: &[(&str, &str)]) -> InlineKeyboardMarkup {
     let buttons: Vec<Vec<InlineKeyboardButton>> = options
@@ -318,6 +332,9 @@ pub struct ConversationContext {
     pub last_file: Option<String>,
     pub last_error: Option<String>,
     pub last_command: Option<String>,
+    /// Model and cost of the last command's response (for `/retry <model>`)
+    pub last_model: Option<String>,
+    pub last_cost_usd: Option<f64>,
     pub last_task_id: Option<String>,
     pub last_diff: Option<String>,
     pub last_mentioned_files: Vec<String>,
@@ -349,6 +366,14 @@ impl ConversationContext {
     /// Update context after a command
     pub fn set_command(&mut self, command: &str) {
         self.last_command = Some(command.to_string());
+        self.last_model = None;
+        self.last_cost_usd = None;
+    }
+
+    /// Record which model answered the last command and what it cost
+    pub fn set_result(&mut self, model: &str, cost_usd: f64) {
+        self.last_model = Some(model.to_string());
+        self.last_cost_usd = Some(cost_usd);
     }
 
     /// Update context after starting a task
@@ -361,6 +386,16 @@ impl ConversationContext {
         self.last_diff = Some(diff.to_string());
     }
 
+    /// Remember a command to run once the user confirms
+    pub fn set_confirmation(&mut self, command: &str) {
+        self.pending_confirmation = Some(command.to_string());
+    }
+
+    /// Take the pending confirmation, clearing it
+    pub fn take_confirmation(&mut self) -> Option<String> {
+        self.pending_confirmation.take()
+    }
+
     /// Clear pending confirmation
     pub fn clear_confirmation(&mut self) {
         self.pending_confirmation = None;
@@ -632,6 +667,11 @@ mod tests {
         let decoded = ButtonAction::decode(&encoded);
 
         assert!(matches!(decoded, Some(ButtonAction::ViewLogs(id)) if id == "task123"));
+
+        let encoded = ButtonAction::ThumbsDown("1700000000000-3".to_string()).encode();
+        assert!(encoded.len() <= 64);
+        let decoded = ButtonAction::decode(&encoded);
+        assert!(matches!(decoded, Some(ButtonAction::ThumbsDown(id)) if id == "1700000000000-3"));
     }
 
     #[test]
@@ -4,16 +4,37 @@
 //! - Warn users about budget impact
 //! - Prevent accidental budget overruns
 //! - Enable smart context pruning
+//!
+//! Counting is fully offline: the BPE table is embedded in the binary, with
+//! a character heuristic as fallback if the table fails to load.
+
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use tiktoken_rs::CoreBPE;
+use tracing::warn;
 
 use crate::router::ModelHint;
 
+/// Embedded cl100k_base table, loaded once on first use
+static CL100K: Lazy<Option<Arc<CoreBPE>>> = Lazy::new(|| match tiktoken_rs::cl100k_base() {
+    Ok(bpe) => Some(Arc::new(bpe)),
+    Err(e) => {
+        warn!("Failed to load BPE table, using heuristic token counts: {}", e);
+        None
+    }
+});
+
 /// Token counter using Claude's tokenization approximation
 ///
-/// Claude uses a BPE tokenizer similar to cl100k_base.
-/// This provides a good approximation without external dependencies.
+/// Claude uses a BPE tokenizer similar to cl100k_base. Counts come from the
+/// embedded cl100k_base table when available, otherwise from a
+/// chars-per-token heuristic.
 pub struct TokenCounter {
     /// Average characters per token (Claude: ~4 chars/token for English)
     chars_per_token: f32,
+    /// BPE encoder (None = heuristic only)
+    encoder: Option<Arc<CoreBPE>>,
 }
 
 /// Budget check result
@@ -91,13 +112,51 @@ impl ModelPricing {
         cache_write_per_million: 3.75,
     };
 
+    /// Pricing for a model, honoring environment overrides
+    ///
+    /// `CLAUDEBOT_PRICE_<MODEL>_INPUT` and `CLAUDEBOT_PRICE_<MODEL>_OUTPUT`
+    /// (USD per million tokens) replace the built-in rates. Cache rates
+    /// follow the input rate.
     pub fn for_model(model: &ModelHint) -> Self {
+        Self::builtin(model).with_overrides(model, |key| std::env::var(key).ok())
+    }
+
+    /// Built-in list pricing for a model
+    pub fn builtin(model: &ModelHint) -> Self {
         match model {
             ModelHint::Haiku => Self::HAIKU,
             ModelHint::Sonnet => Self::SONNET,
             ModelHint::Opus => Self::OPUS,
         }
     }
+
+    /// Cost in USD of a request with the given token counts
+    pub fn cost(&self, input: i64, output: i64, cache_read: i64, cache_write: i64) -> f64 {
+        (input as f64 * self.input_per_million
+            + output as f64 * self.output_per_million
+            + cache_read as f64 * self.cache_read_per_million
+            + cache_write as f64 * self.cache_write_per_million)
+            / 1_000_000.0
+    }
+
+    fn with_overrides(mut self, model: &ModelHint, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let prefix = format!("CLAUDEBOT_PRICE_{}", model.as_str().to_uppercase());
+        let rate = |suffix: &str| {
+            lookup(&format!("{}_{}", prefix, suffix))
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v >= 0.0)
+        };
+
+        if let Some(input) = rate("INPUT") {
+            self.input_per_million = input;
+            self.cache_read_per_million = input * 0.10;
+            self.cache_write_per_million = input * 0.25;
+        }
+        if let Some(output) = rate("OUTPUT") {
+            self.output_per_million = output;
+        }
+        self
+    }
 }
 
 impl Default for TokenCounter {
@@ -107,24 +166,62 @@ impl Default for TokenCounter {
 }
 
 impl TokenCounter {
-    /// Create new token counter
+    /// Create new token counter (default model encoding)
     pub fn new() -> Self {
+        Self::new_with_model(&ModelHint::default())
+    }
+
+    /// Create a token counter using the encoding for a model
+    ///
+    /// All current Claude models share one tokenizer, approximated by
+    /// cl100k_base.
+    pub fn new_with_model(model: &ModelHint) -> Self {
+        let encoder = match model {
+            ModelHint::Haiku | ModelHint::Sonnet | ModelHint::Opus => CL100K.clone(),
+        };
+        Self {
+            encoder,
+            ..Self::heuristic()
+        }
+    }
+
+    /// Create a heuristic-only counter (no BPE table)
+    pub fn heuristic() -> Self {
         Self {
             // Claude averages ~4 characters per token for English text
             // Code tends to be ~3.5 chars/token due to symbols
             chars_per_token: 3.8,
+            encoder: None,
         }
     }
 
-    /// Count approximate tokens in text
+    /// True if counts come from the BPE table rather than the heuristic
+    pub fn is_exact(&self) -> bool {
+        self.encoder.is_some()
+    }
+
+    /// Count tokens in text
     ///
-    /// Uses character-based approximation suitable for Claude models.
-    /// Accuracy: ±10% for typical text, ±15% for code.
+    /// Uses the BPE encoder when loaded, otherwise the heuristic.
     pub fn count(&self, text: &str) -> usize {
         if text.is_empty() {
             return 0;
         }
 
+        match &self.encoder {
+            Some(bpe) => bpe.encode_ordinary(text).len(),
+            None => self.count_heuristic(text),
+        }
+    }
+
+    /// Character-based approximation suitable for Claude models
+    ///
+    /// Accuracy: ±10% for typical text, ±15% for code.
+    pub fn count_heuristic(&self, text: &str) -> usize {
+        if text.is_empty() {
+            return 0;
+        }
+
         // Basic approximation
         let char_count = text.chars().count();
         let base_tokens = (char_count as f32 / self.chars_per_token).ceil() as usize;
@@ -344,6 +441,18 @@ mod tests {
         assert!(code_tokens < 30);
     }
 
+    #[test]
+    fn test_bpe_and_heuristic_counts() {
+        let counter = TokenCounter::new_with_model(&ModelHint::Opus);
+        assert!(counter.is_exact());
+        // cl100k_base: "Hello" "," " world" "!"
+        assert_eq!(counter.count("Hello, world!"), 4);
+
+        let heuristic = TokenCounter::heuristic();
+        assert!(!heuristic.is_exact());
+        assert_eq!(heuristic.count("Hello, world!"), heuristic.count_heuristic("Hello, world!"));
+    }
+
     #[test]
     fn test_cost_estimation() {
         let counter = TokenCounter::new();
@@ -372,6 +481,22 @@ mod tests {
         assert!(check.should_warn());
     }
 
+    #[test]
+    fn test_pricing_overrides() {
+        let lookup = |key: &str| match key {
+            "CLAUDEBOT_PRICE_SONNET_INPUT" => Some("2.0".to_string()),
+            "CLAUDEBOT_PRICE_SONNET_OUTPUT" => Some("not a number".to_string()),
+            _ => None,
+        };
+        let pricing = ModelPricing::SONNET.with_overrides(&ModelHint::Sonnet, lookup);
+        assert_eq!(pricing.input_per_million, 2.0);
+        assert!((pricing.cache_read_per_million - 0.2).abs() < 1e-9);
+        assert_eq!(pricing.output_per_million, ModelPricing::SONNET.output_per_million);
+
+        let opus = ModelPricing::OPUS.with_overrides(&ModelHint::Opus, lookup);
+        assert_eq!(opus.input_per_million, ModelPricing::OPUS.input_per_million);
+    }
+
     #[test]
     fn test_format() {
         assert_eq!(TokenCounter::format_tokens(500), "500");
@@ -12,13 +12,15 @@ use std::time::Duration;
 use tracing::info;
 
 use crate::cache::ResponseCache;
-use crate::circle::{Circle, PipelineMode};
+use crate::circle::{Circle, CirclePersonas, PipelineMode};
 use crate::claude::ClaudeClient;
 use crate::config::Config;
-use crate::graph::GraphStore;
-use crate::memory::MemoryStore;
+use crate::graph::{EntityTaxonomy, GraphStore};
+use crate::mcp::ProgressReporter;
+use crate::memory::MemoryScope;
+use crate::memory_backend::{MemoryBackend, MemoryBackendUrl};
 use crate::metrics::MetricsCollector;
-use crate::router::TaskRouter;
+use crate::router::{CodeFloor, TaskRouter};
 
 /// Tool definition for MCP
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,12 +31,41 @@ pub struct ToolDefinition {
     pub input_schema: serde_json::Value,
 }
 
+/// Output format requested via a tool's `format` argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Typed JSON, also returned as MCP structured content
+    #[default]
+    Json,
+    /// Human-readable text
+    Text,
+}
+
+impl OutputFormat {
+    /// Read the `format` argument, defaulting to JSON
+    pub fn from_args(args: &serde_json::Value) -> Self {
+        match args.get("format").and_then(|v| v.as_str()) {
+            Some(f) if f.eq_ignore_ascii_case("text") => Self::Text,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Rendered tool result
+#[derive(Debug, Clone)]
+pub struct ToolOutput {
+    /// Text content (serialized JSON or human-readable)
+    pub text: String,
+    /// Structured content, present in JSON mode
+    pub structured: Option<serde_json::Value>,
+}
+
 /// Tool registry with all components
 pub struct ToolRegistry {
     config: Arc<Config>,
     router: TaskRouter,
     cache: ResponseCache,
-    memory: MemoryStore,
+    memory: Box<dyn MemoryBackend>,
     graph: GraphStore,
     claude: ClaudeClient,
     circle: Circle,
@@ -44,16 +75,16 @@ pub struct ToolRegistry {
 impl ToolRegistry {
     /// Create new tool registry
     pub async fn new(config: Arc<Config>) -> Result<Self> {
-        let router = TaskRouter::new(config.ollama_url.clone());
+        let router = TaskRouter::new(config.ollama_url.clone()).with_code_floor(CodeFloor::from_env());
         let cache = ResponseCache::new(1000, config.cache_ttl_secs, config.cache_enabled);
-        let memory = MemoryStore::open(&config.db_path)?;
+        let memory = MemoryBackendUrl::from_env(&config.db_path)?.open()?;
 
         // Open separate connection for graph (same db)
         let graph_conn = Connection::open(&config.db_path)?;
-        let graph = GraphStore::new(graph_conn)?;
+        let graph = GraphStore::new(graph_conn)?.with_taxonomy(EntityTaxonomy::from_env());
 
         let claude = ClaudeClient::new(config.anthropic_api_key.as_deref());
-        let circle = Circle::new(claude.clone());
+        let circle = Circle::new(claude.clone(), CirclePersonas::from_env()?);
         let metrics = Arc::new(MetricsCollector::new(10000));
 
         Ok(Self {
@@ -70,7 +101,7 @@ impl ToolRegistry {
 
     /// List all tool definitions
     pub fn list_definitions(&self) -> Vec<ToolDefinition> {
-        vec![
+        let mut tools = vec![
             // ========== Router Tools ==========
             ToolDefinition {
                 name: "router_classify".to_string(),
@@ -407,11 +438,64 @@ impl ToolRegistry {
                     "required": ["prompt"]
                 }),
             },
-        ]
+        ];
+
+        for tool in &mut tools {
+            if let Some(props) = tool.input_schema["properties"].as_object_mut() {
+                props.insert("format".to_string(), json!({
+                    "type": "string",
+                    "enum": ["json", "text"],
+                    "description": "Output format: json (structured) or text (human-readable)",
+                    "default": "json"
+                }));
+            }
+        }
+        tools
     }
 
-    /// Call a tool by name
+    /// Call a tool by name, returning the JSON result serialized as a string
     pub async fn call(&self, name: &str, args: serde_json::Value) -> Result<String> {
+        Ok(self.call_value(name, args).await?.to_string())
+    }
+
+    /// Call a tool and render the result according to its `format` argument
+    pub async fn call_formatted(&self, name: &str, args: serde_json::Value) -> Result<ToolOutput> {
+        self.call_formatted_with_progress(name, args, None).await
+    }
+
+    /// `call_formatted`, letting long-running tools report progress
+    pub async fn call_formatted_with_progress(
+        &self,
+        name: &str,
+        args: serde_json::Value,
+        progress: Option<&ProgressReporter>,
+    ) -> Result<ToolOutput> {
+        let format = OutputFormat::from_args(&args);
+        let value = self.call_value_with_progress(name, args, progress).await?;
+        Ok(match format {
+            OutputFormat::Json => ToolOutput {
+                text: value.to_string(),
+                structured: Some(value),
+            },
+            OutputFormat::Text => ToolOutput {
+                text: format_text(name, &value),
+                structured: None,
+            },
+        })
+    }
+
+    /// Call a tool by name, returning the structured JSON result
+    pub async fn call_value(&self, name: &str, args: serde_json::Value) -> Result<serde_json::Value> {
+        self.call_value_with_progress(name, args, None).await
+    }
+
+    /// Call a tool by name; `circle_run` reports each phase to `progress`
+    pub async fn call_value_with_progress(
+        &self,
+        name: &str,
+        args: serde_json::Value,
+        progress: Option<&ProgressReporter>,
+    ) -> Result<serde_json::Value> {
         info!("Tool call: {} with args: {}", name, args);
         let start = std::time::Instant::now();
 
@@ -425,8 +509,7 @@ impl ToolRegistry {
                     "model": result.model.as_str(),
                     "reasoning": result.reasoning,
                     "confidence": result.confidence
-                })
-                .to_string())
+                }))
             }
 
             // ========== Memory ==========
@@ -434,20 +517,20 @@ impl ToolRegistry {
                 let content = args["content"].as_str().unwrap_or("");
                 let category = args["category"].as_str().unwrap_or("facts");
                 let confidence = args["confidence"].as_f64().unwrap_or(0.8);
-                let id = self.memory.learn(content, category, "mcp", confidence)?;
+                let id = self.memory.learn(content, category, "mcp", None, confidence)?;
 
                 // Auto-extract entities
                 let entities = GraphStore::extract_entities_simple(content);
                 if !entities.is_empty() {
                     self.graph.store_extracted(&id, &entities, &[])?;
+                    self.graph.link_co_occurring(content, &entity_refs(&entities))?;
                 }
 
                 Ok(json!({
                     "id": id,
                     "status": "learned",
                     "entities_extracted": entities.len()
-                })
-                .to_string())
+                }))
             }
             "memory_search" => {
                 let query = args["query"].as_str().unwrap_or("");
@@ -464,12 +547,12 @@ impl ToolRegistry {
                         })
                     })
                     .collect();
-                Ok(json!({ "results": entries }).to_string())
+                Ok(json!({ "results": entries }))
             }
             "memory_recall" => {
                 let limit = args["limit"].as_u64().unwrap_or(10) as usize;
                 let entries = if let Some(category) = args["category"].as_str() {
-                    self.memory.get_by_category(category, limit)?
+                    self.memory.get_by_category(category, limit, MemoryScope::Global)?
                 } else {
                     self.memory.get_recent(limit)?
                 };
@@ -484,20 +567,22 @@ impl ToolRegistry {
                         })
                     })
                     .collect();
-                Ok(json!({ "memories": results }).to_string())
+                Ok(json!({ "memories": results }))
             }
             "memory_forget" => {
                 let id = args["id"].as_str().unwrap_or("");
                 let deleted = self.memory.forget(id)?;
-                Ok(json!({ "deleted": deleted }).to_string())
+                if deleted {
+                    self.cache.invalidate_for_memory(id).await;
+                }
+                Ok(json!({ "deleted": deleted }))
             }
             "memory_stats" => {
                 let stats = self.memory.stats()?;
                 Ok(json!({
                     "total": stats.total_entries,
                     "by_category": stats.by_category
-                })
-                .to_string())
+                }))
             }
 
             // ========== Graph (E3) ==========
@@ -506,7 +591,7 @@ impl ToolRegistry {
                 let name = args["name"].as_str().unwrap_or("");
                 let attributes = args.get("attributes").cloned();
                 let id = self.graph.add_entity(entity_type, name, attributes)?;
-                Ok(json!({ "id": id, "status": "created" }).to_string())
+                Ok(json!({ "id": id, "status": "created" }))
             }
             "graph_add_relation" => {
                 let source_id = args["source_id"].as_str().unwrap_or("");
@@ -516,7 +601,7 @@ impl ToolRegistry {
                 let id = self
                     .graph
                     .add_relation(source_id, target_id, relation_type, weight)?;
-                Ok(json!({ "id": id, "status": "created" }).to_string())
+                Ok(json!({ "id": id, "status": "created" }))
             }
             "graph_find_entity" => {
                 let name = args["name"].as_str().unwrap_or("");
@@ -530,9 +615,8 @@ impl ToolRegistry {
                             "name": e.name,
                             "attributes": e.attributes
                         }
-                    })
-                    .to_string()),
-                    None => Ok(json!({ "found": false }).to_string()),
+                    })),
+                    None => Ok(json!({ "found": false })),
                 }
             }
             "graph_traverse" => {
@@ -559,7 +643,7 @@ impl ToolRegistry {
                         })
                     })
                     .collect();
-                Ok(json!({ "nodes": nodes }).to_string())
+                Ok(json!({ "nodes": nodes }))
             }
             "graph_entities_by_type" => {
                 let entity_type = args["entity_type"].as_str().unwrap_or("");
@@ -575,21 +659,22 @@ impl ToolRegistry {
                         })
                     })
                     .collect();
-                Ok(json!({ "entities": results }).to_string())
+                Ok(json!({ "entities": results }))
             }
             "graph_extract" => {
                 let text = args["text"].as_str().unwrap_or("");
                 let memory_id = args["memory_id"].as_str().unwrap_or("manual");
                 let entities = GraphStore::extract_entities_simple(text);
                 self.graph.store_extracted(memory_id, &entities, &[])?;
+                let linked = self.graph.link_co_occurring(text, &entity_refs(&entities))?;
                 Ok(json!({
                     "extracted": entities.len(),
+                    "relations": linked,
                     "entities": entities.iter().map(|e| json!({
                         "type": e.entity_type,
                         "name": e.name
                     })).collect::<Vec<_>>()
-                })
-                .to_string())
+                }))
             }
             "graph_stats" => {
                 let stats = self.graph.stats()?;
@@ -597,24 +682,14 @@ impl ToolRegistry {
                     "entities": stats.entity_count,
                     "relations": stats.relation_count,
                     "by_type": stats.by_type
-                })
-                .to_string())
+                }))
             }
 
             // ========== Cache ==========
-            "cache_stats" => {
-                let stats = self.cache.stats();
-                Ok(json!({
-                    "entries": stats.entries,
-                    "hits": stats.hits,
-                    "misses": stats.misses,
-                    "hit_rate_percent": stats.hit_rate_percent
-                })
-                .to_string())
-            }
+            "cache_stats" => Ok(serde_json::to_value(self.cache.stats())?),
             "cache_clear" => {
                 self.cache.clear().await;
-                Ok(json!({ "status": "cleared" }).to_string())
+                Ok(json!({ "status": "cleared" }))
             }
 
             // ========== Circle (E5) ==========
@@ -630,7 +705,18 @@ impl ToolRegistry {
                     _ => PipelineMode::Full,
                 };
 
-                let result = self.circle.run(feature, context, mode).await?;
+                let result = self
+                    .circle
+                    .run_with_progress(feature, context, mode, &|phase| {
+                        if let Some(progress) = progress {
+                            let message = match phase.revision {
+                                0 => format!("Phase {}: {}", phase.step, phase.persona),
+                                n => format!("Phase {}: {} (revision {})", phase.step, phase.persona, n),
+                            };
+                            progress.report(phase.step as f64 - 1.0, Some(phase.planned as f64), &message);
+                        }
+                    })
+                    .await?;
                 let summary = Circle::summarize(&result);
 
                 Ok(json!({
@@ -638,10 +724,16 @@ impl ToolRegistry {
                     "revisions": result.revisions,
                     "blocked_at": result.blocked_at,
                     "duration_ms": result.total_duration_ms,
+                    "cost_usd": result.total_cost_usd(),
                     "phases": result.phases.len(),
+                    "phase_costs": result.phases.iter().map(|p| json!({
+                        "persona": p.persona,
+                        "model": p.model,
+                        "temperature": p.temperature,
+                        "cost_usd": p.cost_usd,
+                    })).collect::<Vec<_>>(),
                     "summary": summary
-                })
-                .to_string())
+                }))
             }
 
             // ========== Metrics (E6) ==========
@@ -651,8 +743,7 @@ impl ToolRegistry {
                     "total_requests": stats.total_requests,
                     "total_cost_usd": stats.total_cost_usd,
                     "cache_hit_rate": stats.cache_hit_rate
-                })
-                .to_string())
+                }))
             }
             "metrics_cost" => {
                 let cost = self.metrics.cost_breakdown();
@@ -662,8 +753,7 @@ impl ToolRegistry {
                     "this_month_usd": cost.this_month_usd,
                     "by_model": cost.by_model,
                     "savings_from_cache_usd": cost.savings_from_cache_usd
-                })
-                .to_string())
+                }))
             }
             "metrics_latency" => {
                 let latency = self.metrics.latency_stats();
@@ -673,13 +763,12 @@ impl ToolRegistry {
                     "p99_ms": latency.p99_ms,
                     "min_ms": latency.min_ms,
                     "max_ms": latency.max_ms
-                })
-                .to_string())
+                }))
             }
-            "metrics_export" => Ok(self.metrics.export_json()),
+            "metrics_export" => Ok(serde_json::from_str(&self.metrics.export_json())?),
             "metrics_reset" => {
                 self.metrics.reset();
-                Ok(json!({ "status": "reset" }).to_string())
+                Ok(json!({ "status": "reset" }))
             }
 
             // ========== Claude ==========
@@ -697,7 +786,8 @@ impl ToolRegistry {
                     .await?;
 
                 // Record metrics
-                self.metrics.record(
+                self.metrics.record_with_origin(
+                    Some("claude_complete"),
                     &result.model,
                     result.input_tokens,
                     result.output_tokens,
@@ -715,8 +805,7 @@ impl ToolRegistry {
                     "cache_read_tokens": result.cache_read_tokens,
                     "cache_efficiency_percent": result.cache_efficiency(),
                     "estimated_cost_usd": result.estimated_cost()
-                })
-                .to_string())
+                }))
             }
 
             _ => anyhow::bail!("Unknown tool: {}", name),
@@ -731,3 +820,193 @@ impl ToolRegistry {
         result
     }
 }
+
+/// (entity_type, name) pairs for `GraphStore::link_co_occurring`
+fn entity_refs(entities: &[crate::graph::ExtractedEntity]) -> Vec<(&str, &str)> {
+    entities
+        .iter()
+        .map(|e| (e.entity_type.as_str(), e.name.as_str()))
+        .collect()
+}
+
+/// Render a tool's JSON result as human-readable text
+///
+/// Memory, graph and metrics tools get dedicated layouts; everything else
+/// falls back to pretty-printed JSON.
+pub fn format_text(name: &str, value: &serde_json::Value) -> String {
+    let str_of = |v: &serde_json::Value, key: &str| v[key].as_str().unwrap_or("").to_string();
+    let num_of = |v: &serde_json::Value, key: &str| v[key].as_f64().unwrap_or(0.0);
+
+    match name {
+        "memory_search" | "memory_recall" => {
+            let key = if name == "memory_search" { "results" } else { "memories" };
+            let items = value[key].as_array().cloned().unwrap_or_default();
+            if items.is_empty() {
+                return "No memories found.".to_string();
+            }
+            let mut out = format!("{} memories:\n", items.len());
+            for (i, m) in items.iter().enumerate() {
+                let score = if name == "memory_search" {
+                    format!("score {:.2}", num_of(m, "score"))
+                } else {
+                    format!("confidence {:.2}", num_of(m, "confidence"))
+                };
+                out.push_str(&format!(
+                    "{}. [{}] {} ({}, id {})\n",
+                    i + 1,
+                    str_of(m, "category"),
+                    str_of(m, "content"),
+                    score,
+                    str_of(m, "id").get(..8).unwrap_or(""),
+                ));
+            }
+            out.trim_end().to_string()
+        }
+        "memory_learn" => format!(
+            "Learned memory {} ({} entities extracted)",
+            str_of(value, "id"),
+            value["entities_extracted"].as_u64().unwrap_or(0)
+        ),
+        "memory_forget" => {
+            if value["deleted"].as_bool().unwrap_or(false) {
+                "Memory deleted.".to_string()
+            } else {
+                "Memory not found.".to_string()
+            }
+        }
+        "memory_stats" | "graph_stats" => {
+            let (total_label, total, breakdown) = if name == "memory_stats" {
+                ("Memories", value["total"].as_u64().unwrap_or(0), &value["by_category"])
+            } else {
+                ("Entities", value["entities"].as_u64().unwrap_or(0), &value["by_type"])
+            };
+            let mut out = format!("{}: {}\n", total_label, total);
+            if name == "graph_stats" {
+                out.push_str(&format!("Relations: {}\n", value["relations"].as_u64().unwrap_or(0)));
+            }
+            for pair in breakdown.as_array().into_iter().flatten() {
+                out.push_str(&format!(
+                    "  {}: {}\n",
+                    pair[0].as_str().unwrap_or("?"),
+                    pair[1].as_i64().unwrap_or(0)
+                ));
+            }
+            out.trim_end().to_string()
+        }
+        "graph_find_entity" => {
+            if value["found"].as_bool().unwrap_or(false) {
+                let e = &value["entity"];
+                format!("{} ({}) id {}", str_of(e, "name"), str_of(e, "type"), str_of(e, "id"))
+            } else {
+                "Entity not found.".to_string()
+            }
+        }
+        "graph_traverse" => {
+            let nodes = value["nodes"].as_array().cloned().unwrap_or_default();
+            if nodes.is_empty() {
+                return "No connected entities.".to_string();
+            }
+            let lines: Vec<String> = nodes
+                .iter()
+                .map(|n| {
+                    let e = &n["entity"];
+                    format!(
+                        "• {} ({}) score {:.2}",
+                        str_of(e, "name"),
+                        str_of(e, "type"),
+                        num_of(n, "score")
+                    )
+                })
+                .collect();
+            lines.join("\n")
+        }
+        "graph_entities_by_type" => {
+            let entities = value["entities"].as_array().cloned().unwrap_or_default();
+            if entities.is_empty() {
+                return "No entities found.".to_string();
+            }
+            let lines: Vec<String> = entities
+                .iter()
+                .map(|e| format!("• {} (id {})", str_of(e, "name"), str_of(e, "id")))
+                .collect();
+            lines.join("\n")
+        }
+        "graph_extract" => {
+            let names: Vec<String> = value["entities"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|e| format!("{} ({})", str_of(e, "name"), str_of(e, "type")))
+                .collect();
+            format!(
+                "Extracted {} entities ({} co-occurrence links): {}",
+                names.len(),
+                value["relations"].as_u64().unwrap_or(0),
+                names.join(", ")
+            )
+        }
+        "metrics_quick" => format!(
+            "Requests: {}\nCost: ${:.4}\nCache hit rate: {:.1}%",
+            value["total_requests"].as_u64().unwrap_or(0),
+            num_of(value, "total_cost_usd"),
+            num_of(value, "cache_hit_rate")
+        ),
+        "metrics_cost" => {
+            let mut out = format!(
+                "Today: ${:.4}\nThis week: ${:.4}\nThis month: ${:.4}\nCache savings: ${:.4}\n",
+                num_of(value, "today_usd"),
+                num_of(value, "this_week_usd"),
+                num_of(value, "this_month_usd"),
+                num_of(value, "savings_from_cache_usd")
+            );
+            if let Some(by_model) = value["by_model"].as_object() {
+                for (model, cost) in by_model {
+                    out.push_str(&format!("  {}: ${:.4}\n", model, cost.as_f64().unwrap_or(0.0)));
+                }
+            }
+            out.trim_end().to_string()
+        }
+        "metrics_latency" => format!(
+            "p50: {}ms, p90: {}ms, p99: {}ms (min {}ms, max {}ms)",
+            value["p50_ms"], value["p90_ms"], value["p99_ms"], value["min_ms"], value["max_ms"]
+        ),
+        _ => serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_format_from_args() {
+        assert_eq!(OutputFormat::from_args(&json!({})), OutputFormat::Json);
+        assert_eq!(OutputFormat::from_args(&json!({ "format": "json" })), OutputFormat::Json);
+        assert_eq!(OutputFormat::from_args(&json!({ "format": "TEXT" })), OutputFormat::Text);
+    }
+
+    #[test]
+    fn test_format_text_memory_search() {
+        let value = json!({
+            "results": [
+                { "id": "abcdef0123456789", "content": "Rust is fast", "category": "facts", "score": 0.42 }
+            ]
+        });
+        let text = format_text("memory_search", &value);
+        assert!(text.starts_with("1 memories:"));
+        assert!(text.contains("[facts] Rust is fast (score 0.42, id abcdef01)"));
+
+        assert_eq!(format_text("memory_search", &json!({ "results": [] })), "No memories found.");
+    }
+
+    #[test]
+    fn test_format_text_stats_and_fallback() {
+        let value = json!({ "total": 3, "by_category": [["facts", 2], ["preferences", 1]] });
+        let text = format_text("memory_stats", &value);
+        assert!(text.contains("Memories: 3"));
+        assert!(text.contains("facts: 2"));
+
+        let other = json!({ "status": "cleared" });
+        assert!(format_text("cache_clear", &other).contains("\"status\": \"cleared\""));
+    }
+}
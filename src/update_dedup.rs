@@ -0,0 +1,131 @@
+//! Telegram Update Deduplication
+//!
+//! Long polling only acknowledges an update once the next `getUpdates` call
+//! passes its offset, so an update being processed when the bot crashed or
+//! restarted is delivered again on startup. The most recent update ids are
+//! kept in a bounded ring saved to disk; an id already in it is skipped
+//! instead of invoking Claude (and paying) twice.
+
+use anyhow::Result;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Update ids remembered (oldest dropped first)
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+#[derive(Debug, Default)]
+struct Seen {
+    order: VecDeque<u32>,
+    ids: HashSet<u32>,
+}
+
+/// Recently handled update ids, persisted across restarts
+#[derive(Debug)]
+pub struct UpdateDedup {
+    path: Option<PathBuf>,
+    capacity: usize,
+    seen: Mutex<Seen>,
+}
+
+impl UpdateDedup {
+    /// Not persisted (tests, or when no path is configured)
+    pub fn in_memory(capacity: usize) -> Self {
+        Self {
+            path: None,
+            capacity: capacity.max(1),
+            seen: Mutex::new(Seen::default()),
+        }
+    }
+
+    /// Load the ids saved at `path`; a missing or unreadable file starts empty
+    pub fn open(path: &Path, capacity: usize) -> Self {
+        let mut dedup = Self::in_memory(capacity);
+        dedup.path = Some(path.to_path_buf());
+        let saved: Vec<u32> = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        {
+            let mut seen = dedup.seen.lock().unwrap_or_else(|e| e.into_inner());
+            for id in saved {
+                dedup.remember(&mut seen, id);
+            }
+        }
+        dedup
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.lock().unwrap_or_else(|e| e.into_inner()).order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Record `id`, returning false if it was already handled
+    ///
+    /// The id is saved before the update is processed: an update that was
+    /// interrupted is dropped rather than run a second time.
+    pub fn first_seen(&self, id: u32) -> bool {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.ids.contains(&id) {
+            return false;
+        }
+        self.remember(&mut seen, id);
+        if let Err(e) = self.save(&seen) {
+            tracing::warn!("Failed to save seen update ids: {}", e);
+        }
+        true
+    }
+
+    fn remember(&self, seen: &mut Seen, id: u32) {
+        if !seen.ids.insert(id) {
+            return;
+        }
+        seen.order.push_back(id);
+        while seen.order.len() > self.capacity {
+            if let Some(oldest) = seen.order.pop_front() {
+                seen.ids.remove(&oldest);
+            }
+        }
+    }
+
+    fn save(&self, seen: &Seen) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&seen.order)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("seen_updates.json");
+
+        let dedup = UpdateDedup::open(&path, 3);
+        assert!(dedup.first_seen(10));
+        assert!(!dedup.first_seen(10));
+        assert!(dedup.first_seen(11));
+
+        // Redelivered after a restart
+        let restarted = UpdateDedup::open(&path, 3);
+        assert!(!restarted.first_seen(11));
+        assert!(restarted.first_seen(12));
+        assert!(restarted.first_seen(13));
+        // Ring is bounded: the oldest id is forgotten
+        assert_eq!(restarted.len(), 3);
+        assert!(restarted.first_seen(10));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(UpdateDedup::open(&path, 3).is_empty());
+    }
+}
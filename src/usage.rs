@@ -1,12 +1,31 @@
 //! Token Usage Tracking
 //!
 //! Tracks token usage per user with SQLite storage.
-//! Supports daily/monthly limits and cost estimation.
+//! Supports daily/monthly limits, cost estimation and spend spike detection.
 
 use anyhow::Result;
 use rusqlite::{params, Connection};
 use std::path::Path;
 use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::router::ModelHint;
+use crate::tokenizer::ModelPricing;
+
+/// Origin for regular chat messages
+pub const ORIGIN_CHAT: &str = "chat";
+/// Origin for autonomous background work
+pub const ORIGIN_BACKGROUND: &str = "background";
+/// Origin for Development Circle runs
+pub const ORIGIN_CIRCLE: &str = "circle";
+/// Origin for bridge (/bypass) runs
+pub const ORIGIN_BYPASS: &str = "bypass";
+/// Origin for automatic reflection retries
+pub const ORIGIN_REFLECTION: &str = "reflection";
+/// Label for rows recorded before origins were tracked
+pub const ORIGIN_UNTAGGED: &str = "untagged";
+/// Origin (and model) of admin cost corrections; not counted as requests
+pub const ORIGIN_ADJUSTMENT: &str = "adjustment";
 
 /// Token usage record
 #[derive(Debug, Clone)]
@@ -18,6 +37,10 @@ pub struct UsageRecord {
     pub cache_write_tokens: i64,
     pub model: String,
     pub timestamp: i64,
+    /// Feature that issued the request (command name or one of the `ORIGIN_*` constants)
+    pub origin: String,
+    /// Token counts were estimated locally because the CLI reported no usage
+    pub estimated: bool,
 }
 
 /// Usage summary for a user
@@ -29,6 +52,59 @@ pub struct UsageSummary {
     pub total_cache_write_tokens: i64,
     pub request_count: i64,
     pub estimated_cost_usd: f64,
+    /// Requests whose token counts were estimated rather than reported
+    pub estimated_requests: i64,
+}
+
+/// Usage attributed to one origin
+#[derive(Debug, Clone)]
+pub struct OriginUsage {
+    pub origin: String,
+    pub summary: UsageSummary,
+}
+
+/// Period cleared by `UsageTracker::reset_usage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageScope {
+    /// Since local midnight
+    Daily,
+    /// Since the first of the month
+    Monthly,
+    All,
+}
+
+impl UsageScope {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "daily" | "day" | "today" => Some(Self::Daily),
+            "monthly" | "month" => Some(Self::Monthly),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+            Self::All => "all",
+        }
+    }
+}
+
+/// One entry of the usage audit trail
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageAdjustment {
+    pub user_id: i64,
+    /// Admin who made the change
+    pub admin_id: i64,
+    /// "adjust", or "reset:<scope>"
+    pub action: String,
+    /// Cost added (negative: removed)
+    pub cost_usd: f64,
+    /// Requests removed by a reset
+    pub requests: i64,
+    pub timestamp: i64,
 }
 
 /// User limits
@@ -51,6 +127,207 @@ impl Default for UserLimits {
     }
 }
 
+/// Month-end cost projection
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    /// Cost so far this month
+    pub month_to_date_cost: f64,
+    /// Blended daily burn rate used for the projection
+    pub daily_rate: f64,
+    /// Projected cost at month end
+    pub projected_cost: f64,
+    /// Days elapsed this month (fractional)
+    pub days_elapsed: f64,
+    /// Days remaining this month (fractional)
+    pub days_remaining: f64,
+    /// Monthly cost limit, if any
+    pub limit: Option<f64>,
+    /// True if the projection stays within the monthly cost limit
+    pub on_track: bool,
+}
+
+impl Forecast {
+    /// Weight given to the recent window when blending burn rates
+    const RECENT_WEIGHT: f64 = 0.5;
+
+    /// Linear projection from the month-to-date average, blended with the
+    /// recent burn rate so a change in usage shows up quickly
+    pub fn project(
+        month_to_date_cost: f64,
+        days_elapsed: f64,
+        recent_cost: f64,
+        recent_days: f64,
+        days_in_month: f64,
+        limit: Option<f64>,
+    ) -> Self {
+        // Avoid wild extrapolation from the first few hours of a month
+        let elapsed = days_elapsed.max(1.0);
+        let month_rate = month_to_date_cost / elapsed;
+        let daily_rate = if recent_days >= 1.0 && recent_days < elapsed {
+            let recent_rate = recent_cost / recent_days;
+            month_rate * (1.0 - Self::RECENT_WEIGHT) + recent_rate * Self::RECENT_WEIGHT
+        } else {
+            month_rate
+        };
+
+        let days_remaining = (days_in_month - days_elapsed).max(0.0);
+        let projected_cost = month_to_date_cost + daily_rate * days_remaining;
+        let on_track = limit.map(|l| projected_cost <= l).unwrap_or(true);
+
+        Self {
+            month_to_date_cost,
+            daily_rate,
+            projected_cost,
+            days_elapsed,
+            days_remaining,
+            limit,
+            on_track,
+        }
+    }
+}
+
+/// Spend spike detection settings
+#[derive(Debug, Clone)]
+pub struct SpikeConfig {
+    pub enabled: bool,
+    /// Alert when the last hour costs this many times the baseline hourly rate
+    pub multiplier: f64,
+    /// Hours before the current one averaged into the baseline
+    pub baseline_hours: i64,
+    /// Never alert below this hourly cost, so a quiet baseline can't trigger on pennies
+    pub min_cost_usd: f64,
+    /// How often to check
+    pub check_interval: Duration,
+}
+
+impl Default for SpikeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            multiplier: 3.0,
+            baseline_hours: 24,
+            min_cost_usd: 1.0,
+            check_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl SpikeConfig {
+    /// Load from `CLAUDEBOT_SPIKE_*` environment variables
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |key: &str| std::env::var(key).ok();
+        Self {
+            enabled: var("CLAUDEBOT_SPIKE_ALERTS")
+                .map(|v| !matches!(v.to_lowercase().as_str(), "0" | "false" | "off"))
+                .unwrap_or(defaults.enabled),
+            multiplier: var("CLAUDEBOT_SPIKE_MULTIPLIER")
+                .and_then(|v| v.parse().ok())
+                .filter(|m: &f64| *m > 1.0)
+                .unwrap_or(defaults.multiplier),
+            baseline_hours: var("CLAUDEBOT_SPIKE_BASELINE_HOURS")
+                .and_then(|v| v.parse().ok())
+                .filter(|h: &i64| *h > 0)
+                .unwrap_or(defaults.baseline_hours),
+            min_cost_usd: var("CLAUDEBOT_SPIKE_MIN_USD")
+                .and_then(|v| v.parse().ok())
+                .filter(|c: &f64| *c >= 0.0)
+                .unwrap_or(defaults.min_cost_usd),
+            check_interval: var("CLAUDEBOT_SPIKE_CHECK_SECS")
+                .and_then(|v| v.parse().ok())
+                .filter(|s: &u64| *s > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.check_interval),
+        }
+    }
+}
+
+/// Spend in one user/origin bucket
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpendContributor {
+    pub user_id: i64,
+    pub origin: String,
+    pub cost_usd: f64,
+    pub request_count: i64,
+}
+
+/// A detected spend spike
+#[derive(Debug, Clone)]
+pub struct SpendSpike {
+    /// Cost over the last hour
+    pub current_hour_cost: f64,
+    /// Average hourly cost over the baseline window
+    pub baseline_hourly_cost: f64,
+    /// Cost that triggers an alert
+    pub threshold: f64,
+    pub baseline_hours: i64,
+    /// Largest user/origin buckets in the last hour, most expensive first
+    pub top_contributors: Vec<SpendContributor>,
+}
+
+impl SpendSpike {
+    /// Format as an alert
+    pub fn format(&self) -> String {
+        let ratio = if self.baseline_hourly_cost > 0.0 {
+            format!("{:.1}x", self.current_hour_cost / self.baseline_hourly_cost)
+        } else {
+            "no baseline".to_string()
+        };
+        let mut out = format!(
+            "🚨 Spend spike\n\n\
+            Last hour: ${:.2} ({})\n\
+            Baseline: ${:.2}/h over {}h\n\
+            Alert threshold: ${:.2}",
+            self.current_hour_cost, ratio, self.baseline_hourly_cost, self.baseline_hours, self.threshold
+        );
+        if !self.top_contributors.is_empty() {
+            out.push_str("\n\nTop contributors:");
+            for c in &self.top_contributors {
+                out.push_str(&format!(
+                    "\n  user {} / {}: ${:.2} ({} requests)",
+                    c.user_id, c.origin, c.cost_usd, c.request_count
+                ));
+            }
+        }
+        out.push_str("\n\nCheck /usage; /autonomous loops and bridge runs are common causes.");
+        out
+    }
+}
+
+/// Fires one alert per spike
+///
+/// After an alert, the monitor stays quiet until the last hour's spend drops
+/// back under the threshold.
+pub struct SpikeMonitor {
+    config: SpikeConfig,
+    in_spike: Mutex<bool>,
+}
+
+impl SpikeMonitor {
+    pub fn new(config: SpikeConfig) -> Self {
+        Self {
+            config,
+            in_spike: Mutex::new(false),
+        }
+    }
+
+    pub fn config(&self) -> &SpikeConfig {
+        &self.config
+    }
+
+    /// Check spend at `now`; Some only when a new spike starts
+    pub fn check(&self, tracker: &UsageTracker, now: i64) -> Result<Option<SpendSpike>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        let spike = tracker.detect_spike(&self.config, now)?;
+        let mut in_spike = self.in_spike.lock().unwrap();
+        let is_new = spike.is_some() && !*in_spike;
+        *in_spike = spike.is_some();
+        Ok(spike.filter(|_| is_new))
+    }
+}
+
 /// Usage tracker with SQLite backend
 pub struct UsageTracker {
     conn: Mutex<Connection>,
@@ -84,20 +361,46 @@ impl UsageTracker {
             );
 
             CREATE INDEX IF NOT EXISTS idx_usage_user_time ON usage(user_id, timestamp);
+
+            -- Audit trail of admin corrections (/usage adjust, /usage reset)
+            CREATE TABLE IF NOT EXISTS usage_adjustments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                user_id INTEGER NOT NULL,
+                admin_id INTEGER NOT NULL,
+                action TEXT NOT NULL,
+                cost_usd REAL NOT NULL,
+                requests INTEGER NOT NULL DEFAULT 0,
+                timestamp INTEGER NOT NULL
+            );
             "#,
         )?;
 
+        // Migration: reported cost for requests billed outside the token tally (bridge)
+        let _ = conn.execute("ALTER TABLE usage ADD COLUMN cost_usd REAL", []);
+        // Migration: which feature issued the request
+        let _ = conn.execute("ALTER TABLE usage ADD COLUMN origin TEXT", []);
+        // Migration: token counts estimated locally (CLI output wasn't JSON)
+        let _ = conn.execute("ALTER TABLE usage ADD COLUMN estimated INTEGER NOT NULL DEFAULT 0", []);
+        // Spike detection queries all users by time
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_usage_time ON usage(timestamp)", [])?;
+
         Ok(Self {
             conn: Mutex::new(conn),
         })
     }
 
+    /// Database size and row counts per table
+    pub fn storage_stats(&self) -> Result<crate::storage::DbStorage> {
+        let conn = self.conn.lock().unwrap();
+        crate::storage::sqlite_storage(&conn, "usage")
+    }
+
     /// Record token usage
     pub fn record_usage(&self, record: &UsageRecord) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO usage (user_id, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, model, timestamp)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO usage (user_id, input_tokens, output_tokens, cache_read_tokens, cache_write_tokens, model, timestamp, origin, estimated)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 record.user_id,
                 record.input_tokens,
@@ -106,11 +409,121 @@ impl UsageTracker {
                 record.cache_write_tokens,
                 record.model,
                 record.timestamp,
+                record.origin,
+                record.estimated,
             ],
         )?;
         Ok(())
     }
 
+    /// Record a request with a known cost but no token breakdown
+    ///
+    /// Used for bridge runs, where the remote side reports only the cost.
+    /// These rows carry zero tokens so they don't affect token-based limits.
+    pub fn record_cost(&self, user_id: i64, model: &str, cost_usd: f64, origin: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO usage (user_id, input_tokens, output_tokens, model, timestamp, cost_usd, origin)
+             VALUES (?1, 0, 0, ?2, ?3, ?4, ?5)",
+            params![user_id, model, chrono::Utc::now().timestamp(), cost_usd, origin],
+        )?;
+        Ok(())
+    }
+
+    /// Add `delta_usd` (may be negative) to a user's recorded cost
+    ///
+    /// Stored as a zero-token row, so it counts against cost limits from now
+    /// on (it's dated now, not back to the usage it corrects) and not against
+    /// token limits or the request count. Logged to the audit trail.
+    pub fn adjust_usage(&self, user_id: i64, delta_usd: f64, admin_id: i64) -> Result<()> {
+        if !delta_usd.is_finite() {
+            anyhow::bail!("Invalid adjustment: {}", delta_usd);
+        }
+        let now = chrono::Utc::now().timestamp();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO usage (user_id, input_tokens, output_tokens, model, timestamp, cost_usd, origin)
+             VALUES (?1, 0, 0, ?2, ?3, ?4, ?2)",
+            params![user_id, ORIGIN_ADJUSTMENT, now, delta_usd],
+        )?;
+        tx.execute(
+            "INSERT INTO usage_adjustments (user_id, admin_id, action, cost_usd, timestamp)
+             VALUES (?1, ?2, 'adjust', ?3, ?4)",
+            params![user_id, admin_id, delta_usd, now],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete a user's usage for `scope`, returning what was removed
+    ///
+    /// Logged to the audit trail with the removed cost and request count.
+    pub fn reset_usage(&self, user_id: i64, scope: UsageScope, admin_id: i64) -> Result<UsageSummary> {
+        let since = match scope {
+            UsageScope::Daily => Self::start_of_day(),
+            UsageScope::Monthly => Self::start_of_month(),
+            UsageScope::All => 0,
+        };
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let removed = Self::summary_since(&tx, user_id, since)?;
+        tx.execute(
+            "DELETE FROM usage WHERE user_id = ?1 AND timestamp >= ?2",
+            params![user_id, since],
+        )?;
+        tx.execute(
+            "INSERT INTO usage_adjustments (user_id, admin_id, action, cost_usd, requests, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                user_id,
+                admin_id,
+                format!("reset:{}", scope.as_str()),
+                -removed.estimated_cost_usd,
+                removed.request_count,
+                chrono::Utc::now().timestamp(),
+            ],
+        )?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Most recent audit trail entries, newest first
+    pub fn adjustment_log(&self, limit: usize) -> Result<Vec<UsageAdjustment>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT user_id, admin_id, action, cost_usd, requests, timestamp
+             FROM usage_adjustments ORDER BY id DESC LIMIT ?1",
+        )?;
+        let entries = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok(UsageAdjustment {
+                    user_id: row.get(0)?,
+                    admin_id: row.get(1)?,
+                    action: row.get(2)?,
+                    cost_usd: row.get(3)?,
+                    requests: row.get(4)?,
+                    timestamp: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(entries)
+    }
+
+    /// Average cost per request for a model across all users
+    ///
+    /// Returns the average and the number of requests it is based on, or
+    /// None if there is no history for the model.
+    pub fn average_request_cost(&self, model: &str) -> Result<Option<(f64, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let (avg, count): (Option<f64>, i64) = conn.query_row(
+            "SELECT AVG(cost_usd), COUNT(cost_usd) FROM usage WHERE model = ?1 AND cost_usd IS NOT NULL",
+            params![model],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(avg.filter(|_| count > 0).map(|avg| (avg, count)))
+    }
+
     /// Get usage summary for today
     pub fn get_daily_usage(&self, user_id: i64) -> Result<UsageSummary> {
         let start_of_day = Self::start_of_day();
@@ -128,33 +541,172 @@ impl UsageTracker {
         self.get_usage_since(user_id, 0)
     }
 
+    /// Get this month's usage broken down by origin, most expensive first
+    pub fn get_monthly_usage_by_origin(&self, user_id: i64) -> Result<Vec<OriginUsage>> {
+        self.get_usage_by_origin_since(user_id, Self::start_of_month())
+    }
+
+    /// Usage grouped by origin since a timestamp
+    ///
+    /// Cost includes both the token estimate and any reported cost (bridge
+    /// runs), so bypass spend shows up even though it carries no tokens.
+    pub fn get_usage_by_origin_since(&self, user_id: i64, since_timestamp: i64) -> Result<Vec<OriginUsage>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT
+                COALESCE(origin, ?3),
+                COALESCE(SUM(input_tokens), 0),
+                COALESCE(SUM(output_tokens), 0),
+                COALESCE(SUM(cache_read_tokens), 0),
+                COALESCE(SUM(cache_write_tokens), 0),
+                COUNT(*),
+                COALESCE(SUM(cost_usd), 0.0)
+             FROM usage
+             WHERE user_id = ?1 AND timestamp >= ?2
+             GROUP BY COALESCE(origin, ?3)",
+        )?;
+
+        let rows = stmt.query_map(params![user_id, since_timestamp, ORIGIN_UNTAGGED], |row| {
+            let summary = UsageSummary {
+                total_input_tokens: row.get(1)?,
+                total_output_tokens: row.get(2)?,
+                total_cache_read_tokens: row.get(3)?,
+                total_cache_write_tokens: row.get(4)?,
+                request_count: row.get(5)?,
+                estimated_cost_usd: row.get(6)?,
+                ..UsageSummary::default()
+            };
+            Ok(OriginUsage {
+                origin: row.get(0)?,
+                summary,
+            })
+        })?;
+
+        let mut by_origin = Vec::new();
+        for row in rows {
+            let mut usage = row?;
+            usage.summary.estimated_cost_usd += Self::estimate_cost(&usage.summary);
+            by_origin.push(usage);
+        }
+        by_origin.sort_by(|a, b| {
+            b.summary
+                .estimated_cost_usd
+                .total_cmp(&a.summary.estimated_cost_usd)
+                .then_with(|| a.origin.cmp(&b.origin))
+        });
+        Ok(by_origin)
+    }
+
+    /// Spend across all users in `[since, until)`, grouped by user and origin,
+    /// most expensive first
+    pub fn spend_by_contributor(&self, since: i64, until: i64) -> Result<Vec<SpendContributor>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT
+                user_id,
+                COALESCE(origin, ?3),
+                COALESCE(SUM(input_tokens), 0),
+                COALESCE(SUM(output_tokens), 0),
+                COALESCE(SUM(cache_read_tokens), 0),
+                COALESCE(SUM(cache_write_tokens), 0),
+                COUNT(*),
+                COALESCE(SUM(cost_usd), 0.0)
+             FROM usage
+             WHERE timestamp >= ?1 AND timestamp < ?2
+             GROUP BY user_id, COALESCE(origin, ?3)",
+        )?;
+
+        let rows = stmt.query_map(params![since, until, ORIGIN_UNTAGGED], |row| {
+            let summary = UsageSummary {
+                total_input_tokens: row.get(2)?,
+                total_output_tokens: row.get(3)?,
+                total_cache_read_tokens: row.get(4)?,
+                total_cache_write_tokens: row.get(5)?,
+                request_count: row.get(6)?,
+                estimated_cost_usd: row.get(7)?,
+                ..UsageSummary::default()
+            };
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, summary))
+        })?;
+
+        let mut contributors = Vec::new();
+        for row in rows {
+            let (user_id, origin, summary) = row?;
+            contributors.push(SpendContributor {
+                user_id,
+                origin,
+                cost_usd: summary.estimated_cost_usd + Self::estimate_cost(&summary),
+                request_count: summary.request_count,
+            });
+        }
+        contributors.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+        Ok(contributors)
+    }
+
+    /// Compare the last hour's spend with the baseline hourly rate
+    pub fn detect_spike(&self, config: &SpikeConfig, now: i64) -> Result<Option<SpendSpike>> {
+        const HOUR: i64 = 3600;
+        const TOP_CONTRIBUTORS: usize = 3;
+
+        let current = self.spend_by_contributor(now - HOUR, now + 1)?;
+        let current_hour_cost: f64 = current.iter().map(|c| c.cost_usd).sum();
+
+        let baseline_start = now - HOUR * (config.baseline_hours + 1);
+        let baseline_cost: f64 = self
+            .spend_by_contributor(baseline_start, now - HOUR)?
+            .iter()
+            .map(|c| c.cost_usd)
+            .sum();
+        let baseline_hourly_cost = baseline_cost / config.baseline_hours as f64;
+
+        let threshold = (baseline_hourly_cost * config.multiplier).max(config.min_cost_usd);
+        if current_hour_cost <= threshold {
+            return Ok(None);
+        }
+
+        Ok(Some(SpendSpike {
+            current_hour_cost,
+            baseline_hourly_cost,
+            threshold,
+            baseline_hours: config.baseline_hours,
+            top_contributors: current.into_iter().take(TOP_CONTRIBUTORS).collect(),
+        }))
+    }
+
     fn get_usage_since(&self, user_id: i64, since_timestamp: i64) -> Result<UsageSummary> {
         let conn = self.conn.lock().unwrap();
+        Self::summary_since(&conn, user_id, since_timestamp)
+    }
+
+    fn summary_since(conn: &Connection, user_id: i64, since_timestamp: i64) -> Result<UsageSummary> {
         let mut stmt = conn.prepare(
             "SELECT
                 COALESCE(SUM(input_tokens), 0),
                 COALESCE(SUM(output_tokens), 0),
                 COALESCE(SUM(cache_read_tokens), 0),
                 COALESCE(SUM(cache_write_tokens), 0),
-                COUNT(*)
+                COUNT(*) - COALESCE(SUM(origin = ?3), 0),
+                COALESCE(SUM(cost_usd), 0.0),
+                COALESCE(SUM(estimated), 0)
              FROM usage
              WHERE user_id = ?1 AND timestamp >= ?2",
         )?;
 
-        let summary = stmt.query_row(params![user_id, since_timestamp], |row| {
+        let summary = stmt.query_row(params![user_id, since_timestamp, ORIGIN_ADJUSTMENT], |row| {
             Ok(UsageSummary {
                 total_input_tokens: row.get(0)?,
                 total_output_tokens: row.get(1)?,
                 total_cache_read_tokens: row.get(2)?,
                 total_cache_write_tokens: row.get(3)?,
                 request_count: row.get(4)?,
-                estimated_cost_usd: 0.0,
+                estimated_cost_usd: row.get(5)?,
+                estimated_requests: row.get(6)?,
             })
         })?;
 
-        // Calculate cost
+        // Known costs (bridge runs) plus the token-based estimate
         let mut summary = summary;
-        summary.estimated_cost_usd = Self::estimate_cost(&summary);
+        summary.estimated_cost_usd += Self::estimate_cost(&summary);
         Ok(summary)
     }
 
@@ -256,17 +808,50 @@ impl UsageTracker {
         Ok(LimitCheck::Ok(remaining))
     }
 
-    /// Estimate cost based on Sonnet pricing (default)
+    /// Project month-end cost from this month's burn rate
+    pub fn forecast_month(&self, user_id: i64) -> Result<Forecast> {
+        use chrono::{Datelike, Local, NaiveDate};
+
+        const RECENT_WINDOW_DAYS: i64 = 7;
+
+        let now = Local::now();
+        let month_start = Self::start_of_month();
+        let monthly = self.get_monthly_usage(user_id)?;
+        let days_elapsed = (now.timestamp() - month_start) as f64 / 86400.0;
+
+        let recent_start = (now.timestamp() - RECENT_WINDOW_DAYS * 86400).max(month_start);
+        let recent = self.get_usage_since(user_id, recent_start)?;
+        let recent_days = (now.timestamp() - recent_start) as f64 / 86400.0;
+
+        let (next_year, next_month) = if now.month() == 12 {
+            (now.year() + 1, 1)
+        } else {
+            (now.year(), now.month() + 1)
+        };
+        let days_in_month = NaiveDate::from_ymd_opt(next_year, next_month, 1)
+            .and_then(|d| d.pred_opt())
+            .map(|d| d.day())
+            .unwrap_or(30) as f64;
+
+        let limits = self.get_user_limits(user_id)?;
+        Ok(Forecast::project(
+            monthly.estimated_cost_usd,
+            days_elapsed,
+            recent.estimated_cost_usd,
+            recent_days,
+            days_in_month,
+            limits.monthly_cost_limit_usd,
+        ))
+    }
+
+    /// Estimate cost based on Sonnet pricing (default, configurable)
     fn estimate_cost(summary: &UsageSummary) -> f64 {
-        // Claude Sonnet 4 pricing (per million tokens)
-        const INPUT_PRICE: f64 = 3.0;
-        const OUTPUT_PRICE: f64 = 15.0;
-        const CACHE_READ_DISCOUNT: f64 = 0.1; // 90% cheaper
+        let pricing = ModelPricing::for_model(&ModelHint::Sonnet);
 
-        let input_cost = (summary.total_input_tokens as f64 / 1_000_000.0) * INPUT_PRICE;
-        let output_cost = (summary.total_output_tokens as f64 / 1_000_000.0) * OUTPUT_PRICE;
+        let input_cost = (summary.total_input_tokens as f64 / 1_000_000.0) * pricing.input_per_million;
+        let output_cost = (summary.total_output_tokens as f64 / 1_000_000.0) * pricing.output_per_million;
         let cache_savings = (summary.total_cache_read_tokens as f64 / 1_000_000.0)
-            * INPUT_PRICE * (1.0 - CACHE_READ_DISCOUNT);
+            * (pricing.input_per_million - pricing.cache_read_per_million);
 
         input_cost + output_cost - cache_savings
     }
@@ -368,6 +953,8 @@ mod tests {
             cache_write_tokens: 100,
             model: "claude-sonnet-4".to_string(),
             timestamp: chrono::Utc::now().timestamp(),
+            origin: ORIGIN_CHAT.to_string(),
+            estimated: false,
         };
 
         tracker.record_usage(&record).unwrap();
@@ -376,6 +963,115 @@ mod tests {
         assert_eq!(summary.total_input_tokens, 1000);
         assert_eq!(summary.total_output_tokens, 500);
         assert_eq!(summary.request_count, 1);
+        assert_eq!(summary.estimated_requests, 0);
+
+        tracker.record_usage(&UsageRecord { estimated: true, ..record }).unwrap();
+        let summary = tracker.get_total_usage(12345).unwrap();
+        assert_eq!((summary.request_count, summary.estimated_requests), (2, 1));
+    }
+
+    #[test]
+    fn test_average_request_cost() {
+        let temp = NamedTempFile::new().unwrap();
+        let tracker = UsageTracker::new(temp.path()).unwrap();
+
+        assert!(tracker.average_request_cost("bridge").unwrap().is_none());
+
+        tracker.record_cost(1, "bridge", 0.10, ORIGIN_BYPASS).unwrap();
+        tracker.record_cost(2, "bridge", 0.30, ORIGIN_BYPASS).unwrap();
+        tracker.record_cost(1, "other", 5.0, ORIGIN_BYPASS).unwrap();
+
+        let (avg, count) = tracker.average_request_cost("bridge").unwrap().unwrap();
+        assert!((avg - 0.20).abs() < 1e-9);
+        assert_eq!(count, 2);
+        assert_eq!(tracker.get_total_usage(1).unwrap().total_input_tokens, 0);
+    }
+
+    #[test]
+    fn test_usage_by_origin() {
+        let temp = NamedTempFile::new().unwrap();
+        let tracker = UsageTracker::new(temp.path()).unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        let record = |tokens: i64, origin: &str| UsageRecord {
+            user_id: 7,
+            input_tokens: tokens,
+            output_tokens: tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            model: "claude-sonnet-4".to_string(),
+            timestamp: now,
+            origin: origin.to_string(),
+            estimated: false,
+        };
+        tracker.record_usage(&record(1000, ORIGIN_CHAT)).unwrap();
+        tracker.record_usage(&record(1000, ORIGIN_CHAT)).unwrap();
+        tracker.record_usage(&record(100_000, ORIGIN_CIRCLE)).unwrap();
+        tracker.record_cost(7, "bridge", 0.5, ORIGIN_BYPASS).unwrap();
+        // Row from before origins were tracked
+        tracker
+            .conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO usage (user_id, input_tokens, output_tokens, model, timestamp) VALUES (7, 10, 10, 'm', ?1)",
+                params![now],
+            )
+            .unwrap();
+
+        let by_origin = tracker.get_usage_by_origin_since(7, 0).unwrap();
+        let origins: Vec<&str> = by_origin.iter().map(|o| o.origin.as_str()).collect();
+        assert_eq!(origins, vec![ORIGIN_CIRCLE, ORIGIN_BYPASS, ORIGIN_CHAT, ORIGIN_UNTAGGED]);
+        assert_eq!(by_origin[2].summary.request_count, 2);
+        assert!((by_origin[1].summary.estimated_cost_usd - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_spend_spike_fires_once() {
+        let temp = NamedTempFile::new().unwrap();
+        let tracker = UsageTracker::new(temp.path()).unwrap();
+        let now = 1_700_000_000;
+        let record = |user_id: i64, output_tokens: i64, timestamp: i64, origin: &str| UsageRecord {
+            user_id,
+            input_tokens: 0,
+            output_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            model: "claude-sonnet-4".to_string(),
+            timestamp,
+            origin: origin.to_string(),
+            estimated: false,
+        };
+
+        // Steady baseline: one modest request per hour for the past day
+        for hour in 2..=25 {
+            tracker.record_usage(&record(1, 100_000, now - hour * 3600, ORIGIN_CHAT)).unwrap();
+        }
+        let monitor = SpikeMonitor::new(SpikeConfig::default());
+        assert!(monitor.check(&tracker, now).unwrap().is_none());
+
+        // A runaway background loop in the last hour
+        for minute in 0..10 {
+            tracker.record_usage(&record(2, 200_000, now - minute * 60, ORIGIN_BACKGROUND)).unwrap();
+        }
+        tracker.record_usage(&record(1, 100_000, now - 60, ORIGIN_CHAT)).unwrap();
+
+        let spike = monitor.check(&tracker, now).unwrap().expect("spike");
+        assert!(spike.current_hour_cost > spike.threshold);
+        assert_eq!(spike.top_contributors[0].user_id, 2);
+        assert_eq!(spike.top_contributors[0].origin, ORIGIN_BACKGROUND);
+        assert_eq!(spike.top_contributors[0].request_count, 10);
+        assert!(spike.format().contains("user 2 / background"));
+
+        // Same spike - no second alert; re-armed once spend falls back
+        assert!(monitor.check(&tracker, now + 60).unwrap().is_none());
+        assert!(monitor.check(&tracker, now + 2 * 3600).unwrap().is_none());
+        tracker.record_cost(3, "bridge", 50.0, ORIGIN_BYPASS).unwrap();
+        let later = chrono::Utc::now().timestamp();
+        assert!(monitor.check(&tracker, later).unwrap().is_some());
+
+        let disabled = SpikeMonitor::new(SpikeConfig { enabled: false, ..SpikeConfig::default() });
+        assert!(disabled.check(&tracker, later).unwrap().is_none());
     }
 
     #[test]
@@ -400,6 +1096,8 @@ mod tests {
             cache_write_tokens: 0,
             model: "claude-sonnet-4".to_string(),
             timestamp: chrono::Utc::now().timestamp(),
+            origin: ORIGIN_CHAT.to_string(),
+            estimated: false,
         };
         tracker.record_usage(&record).unwrap();
 
@@ -409,6 +1107,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_monthly_cost_limit_enforced() {
+        let temp = NamedTempFile::new().unwrap();
+        let tracker = UsageTracker::new(temp.path()).unwrap();
+
+        tracker.set_user_limits(12345, &UserLimits {
+            daily_token_limit: None,
+            monthly_token_limit: None,
+            daily_cost_limit_usd: None,
+            monthly_cost_limit_usd: Some(1.0),
+        }).unwrap();
+        assert!(matches!(tracker.check_limits(12345).unwrap(), LimitCheck::Ok(_)));
+
+        tracker.record_cost(12345, "bridge", 1.5, ORIGIN_CHAT).unwrap();
+        match tracker.check_limits(12345).unwrap() {
+            LimitCheck::Exceeded(LimitType::MonthlyCost { limit, .. }) => assert_eq!(limit, 1.0),
+            other => panic!("Expected monthly cost limit exceeded, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_adjust_and_reset_usage_are_audited() {
+        let temp = NamedTempFile::new().unwrap();
+        let tracker = UsageTracker::new(temp.path()).unwrap();
+        let old = chrono::Utc::now().timestamp() - 400 * 86400;
+        tracker.record_usage(&UsageRecord {
+            user_id: 12345,
+            input_tokens: 1000,
+            output_tokens: 500,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            model: "claude-sonnet-4".to_string(),
+            timestamp: old,
+            origin: ORIGIN_CHAT.to_string(),
+            estimated: false,
+        }).unwrap();
+        tracker.record_cost(12345, "bridge", 3.0, ORIGIN_BYPASS).unwrap();
+        tracker.record_cost(999, "bridge", 1.0, ORIGIN_BYPASS).unwrap();
+
+        tracker.adjust_usage(12345, -2.5, 1).unwrap();
+        let daily = tracker.get_daily_usage(12345).unwrap();
+        assert!((daily.estimated_cost_usd - 0.5).abs() < 1e-9);
+        // The correction isn't a request
+        assert_eq!(daily.request_count, 1);
+        assert!(tracker.adjust_usage(12345, f64::NAN, 1).is_err());
+
+        let removed = tracker.reset_usage(12345, UsageScope::Daily, 1).unwrap();
+        assert!((removed.estimated_cost_usd - 0.5).abs() < 1e-9);
+        assert_eq!(removed.request_count, 1);
+        assert_eq!(tracker.get_daily_usage(12345).unwrap().request_count, 0);
+        // Older usage and other users are untouched
+        assert_eq!(tracker.get_total_usage(12345).unwrap().total_input_tokens, 1000);
+        assert_eq!(tracker.get_daily_usage(999).unwrap().request_count, 1);
+
+        tracker.reset_usage(12345, UsageScope::All, 1).unwrap();
+        assert_eq!(tracker.get_total_usage(12345).unwrap().request_count, 0);
+
+        let log = tracker.adjustment_log(10).unwrap();
+        let actions: Vec<&str> = log.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, vec!["reset:all", "reset:daily", "adjust"]);
+        assert_eq!((log[1].requests, log[1].admin_id), (1, 1));
+        assert!((log[2].cost_usd + 2.5).abs() < 1e-9);
+        assert_eq!(UsageScope::parse("Month"), Some(UsageScope::Monthly));
+        assert_eq!(UsageScope::parse("week"), None);
+    }
+
     #[test]
     fn test_format_tokens() {
         assert_eq!(format_tokens(500), "500");
@@ -425,9 +1189,41 @@ mod tests {
             total_cache_write_tokens: 0,
             request_count: 10,
             estimated_cost_usd: 0.0,
+            estimated_requests: 0,
         };
 
         let cost = UsageTracker::estimate_cost(&summary);
         assert!((cost - 4.5).abs() < 0.01); // $3 + $1.5 = $4.5
     }
+
+    #[test]
+    fn test_forecast_projection() {
+        // $10 over 10 days, steady: $1/day -> $30 for a 30-day month
+        let steady = Forecast::project(10.0, 10.0, 7.0, 7.0, 30.0, Some(50.0));
+        assert!((steady.daily_rate - 1.0).abs() < 0.01);
+        assert!((steady.projected_cost - 30.0).abs() < 0.01);
+        assert!(steady.on_track);
+
+        // Recent week is hotter ($3/day): blended rate is $2/day
+        let hot = Forecast::project(10.0, 10.0, 21.0, 7.0, 30.0, Some(40.0));
+        assert!((hot.daily_rate - 2.0).abs() < 0.01);
+        assert!((hot.projected_cost - 50.0).abs() < 0.01);
+        assert!(!hot.on_track);
+
+        // No limit is always on track
+        let unlimited = Forecast::project(100.0, 2.0, 100.0, 2.0, 30.0, None);
+        assert!(unlimited.on_track);
+    }
+
+    #[test]
+    fn test_forecast_month_empty() {
+        let temp = NamedTempFile::new().unwrap();
+        let tracker = UsageTracker::new(temp.path()).unwrap();
+
+        let forecast = tracker.forecast_month(12345).unwrap();
+        assert_eq!(forecast.projected_cost, 0.0);
+        assert_eq!(forecast.limit, Some(200.0));
+        assert!(forecast.on_track);
+        assert!(forecast.days_remaining <= 31.0);
+    }
 }
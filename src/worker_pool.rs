@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::process::{Child, Command};
+use tokio::process::Child;
 use tokio::sync::{mpsc, Mutex, RwLock};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tracing::{debug, info, warn};
@@ -154,6 +154,19 @@ impl Worker {
         }
     }
 
+    /// Claude CLI command for a task; Elevated and above skip permission
+    /// prompts unless safe mode is on
+    fn claude_command(&self, cli: &crate::claude_cli::ClaudeCli, task: &str) -> tokio::process::Command {
+        let mut cmd = cli.command();
+        cmd.arg("-p")
+            .arg(task)
+            .arg("--output-format")
+            .arg("json");
+        cli.skip_permissions(&mut cmd, self.config.permission_level >= PermissionLevel::Elevated);
+        cli.add_extra_args(&mut cmd);
+        cmd
+    }
+
     /// Execute a task on this worker
     pub async fn execute(&mut self, task: &str) -> Result<WorkerResult, WorkerError> {
         if self.status != WorkerStatus::Idle {
@@ -164,18 +177,7 @@ impl Worker {
         self.last_activity = Some(Instant::now());
         let start = Instant::now();
 
-        // Build Claude CLI command
-        let mut cmd = Command::new("claude");
-        cmd.arg("-p")
-            .arg(task)
-            .arg("--output-format")
-            .arg("json");
-
-        // Apply permission level restrictions
-        if self.config.permission_level >= PermissionLevel::Elevated {
-            cmd.arg("--dangerously-skip-permissions");
-        }
-
+        let mut cmd = self.claude_command(&crate::claude_cli::ClaudeCli::from_env(), task);
         cmd.current_dir(&self.config.working_dir)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped());
@@ -624,6 +626,20 @@ mod tests {
         assert!(root.allows(&WorkerOperation::AccessCredentials));
     }
 
+    #[test]
+    fn test_elevated_worker_honours_safe_mode() {
+        let worker = Worker::new(WorkerConfig {
+            permission_level: PermissionLevel::Elevated,
+            ..Default::default()
+        });
+        let skips = |cli: &crate::claude_cli::ClaudeCli| {
+            worker.claude_command(cli, "task").as_std().get_args()
+                .any(|a| a == crate::claude_cli::SKIP_PERMISSIONS_FLAG)
+        };
+        assert!(skips(&crate::claude_cli::ClaudeCli::default()));
+        assert!(!skips(&crate::claude_cli::ClaudeCli::default().with_safe_mode(true)));
+    }
+
     #[tokio::test]
     async fn test_worker_pool_creation() {
         let pool = WorkerPool::new(PoolConfig::default());
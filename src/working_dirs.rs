@@ -0,0 +1,260 @@
+//! Per-User Working Directories
+//!
+//! Every user starts in their own `base/user_<id>` directory. `/cd <path>`
+//! points the bot at an existing project instead: Claude CLI runs, file
+//! commands and `/circle` all work there. Only the user's own directory and
+//! directories under `CLAUDEBOT_ALLOWED_DIRS` can be chosen, and the choices
+//! are saved so they survive restarts. The base directory itself and other
+//! users' directories are never allowed: the base holds the databases and
+//! state files, and a user's directory holds their Claude session.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// Working directory per user, persisted across restarts
+#[derive(Debug)]
+pub struct WorkingDirs {
+    base: PathBuf,
+    /// Canonical roots from `CLAUDEBOT_ALLOWED_DIRS`, besides the user's own directory
+    allowed_roots: Vec<PathBuf>,
+    path: Option<PathBuf>,
+    chosen: Mutex<HashMap<i64, PathBuf>>,
+}
+
+impl WorkingDirs {
+    /// Not persisted (tests, or when no path is configured)
+    ///
+    /// Roots that don't exist are ignored.
+    pub fn in_memory(base: &Path, allowed_roots: &[PathBuf]) -> Self {
+        let mut roots: Vec<PathBuf> = allowed_roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .collect();
+        roots.dedup();
+        Self {
+            base: base.to_path_buf(),
+            allowed_roots: roots,
+            path: None,
+            chosen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Load the choices saved at `path`; a missing or unreadable file starts
+    /// empty, and saved directories that are gone or no longer allowed are
+    /// dropped
+    pub fn open(base: &Path, allowed_roots: &[PathBuf], path: &Path) -> Self {
+        let mut dirs = Self::in_memory(base, allowed_roots);
+        dirs.path = Some(path.to_path_buf());
+        let saved: HashMap<i64, PathBuf> = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        let valid = saved
+            .into_iter()
+            .filter_map(|(user_id, dir)| match dirs.validate(user_id, &dir) {
+                Ok(dir) => Some((user_id, dir)),
+                Err(e) => {
+                    tracing::warn!("Dropping working dir of user {}: {}", user_id, e);
+                    None
+                }
+            })
+            .collect();
+        *dirs.chosen.lock().unwrap_or_else(|e| e.into_inner()) = valid;
+        dirs
+    }
+
+    /// `CLAUDEBOT_ALLOWED_DIRS`: comma-separated roots besides the base directory
+    pub fn allowed_roots_from_env() -> Vec<PathBuf> {
+        std::env::var("CLAUDEBOT_ALLOWED_DIRS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    pub fn allowed_roots(&self) -> &[PathBuf] {
+        &self.allowed_roots
+    }
+
+    /// The per-user directory used when the user hasn't chosen one
+    pub fn default_for(&self, user_id: i64) -> PathBuf {
+        self.base.join(format!("user_{}", user_id))
+    }
+
+    /// The user's chosen directory, else their default one
+    pub fn for_user(&self, user_id: i64) -> PathBuf {
+        self.chosen
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&user_id)
+            .cloned()
+            .unwrap_or_else(|| self.default_for(user_id))
+    }
+
+    /// True if the user picked their own directory
+    pub fn has_override(&self, user_id: i64) -> bool {
+        self.chosen.lock().unwrap_or_else(|e| e.into_inner()).contains_key(&user_id)
+    }
+
+    /// Switch the user to `requested`, relative to their current directory
+    /// unless absolute; returns the resolved directory
+    pub fn set(&self, user_id: i64, requested: &str) -> Result<PathBuf> {
+        let requested = match (requested.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(rest), Ok(home)) => Path::new(&home).join(rest),
+            _ => PathBuf::from(requested),
+        };
+        let dir = self.validate(user_id, &self.for_user(user_id).join(requested))?;
+        let mut chosen = self.chosen.lock().unwrap_or_else(|e| e.into_inner());
+        if self.default_for(user_id).canonicalize().is_ok_and(|default| default == dir) {
+            chosen.remove(&user_id);
+        } else {
+            chosen.insert(user_id, dir.clone());
+        }
+        self.save(&chosen)?;
+        Ok(dir)
+    }
+
+    /// Back to the default directory
+    pub fn reset(&self, user_id: i64) -> Result<PathBuf> {
+        let mut chosen = self.chosen.lock().unwrap_or_else(|e| e.into_inner());
+        if chosen.remove(&user_id).is_some() {
+            self.save(&chosen)?;
+        }
+        Ok(self.default_for(user_id))
+    }
+
+    /// Canonical form of `dir` if it's an existing directory the user may use
+    fn validate(&self, user_id: i64, dir: &Path) -> Result<PathBuf> {
+        let resolved = dir
+            .canonicalize()
+            .with_context(|| format!("{} doesn't exist", dir.display()))?;
+        if !resolved.is_dir() {
+            anyhow::bail!("{} is not a directory", resolved.display());
+        }
+        if !self.is_allowed(user_id, &resolved) {
+            anyhow::bail!("{} is outside the allowed directories", resolved.display());
+        }
+        Ok(resolved)
+    }
+
+    /// Canonical path of a file the user named relative to `dir`
+    ///
+    /// Absolute paths and `..` are refused, and the file (after following
+    /// symlinks) must stay under `dir`, which must itself be allowed.
+    pub fn resolve_file(&self, user_id: i64, dir: &Path, relative: &str) -> Result<PathBuf> {
+        let relative = Path::new(relative);
+        if !relative.components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+            anyhow::bail!("Use a path inside the working dir, without .. or a leading /");
+        }
+        let root = dir
+            .canonicalize()
+            .with_context(|| format!("{} doesn't exist", dir.display()))?;
+        let resolved = root
+            .join(relative)
+            .canonicalize()
+            .with_context(|| format!("{} doesn't exist", relative.display()))?;
+        if !resolved.starts_with(&root) || !self.is_allowed(user_id, &resolved) {
+            anyhow::bail!("{} is outside the working dir", relative.display());
+        }
+        Ok(resolved)
+    }
+
+    /// Whether the canonical path `resolved` lies in the user's own directory
+    /// or under an allowed root
+    ///
+    /// Anything else under the base directory is refused even when an
+    /// allowed root contains it.
+    pub fn is_allowed(&self, user_id: i64, resolved: &Path) -> bool {
+        let own = self.default_for(user_id).canonicalize().ok();
+        if own.as_ref().is_some_and(|own| resolved.starts_with(own)) {
+            return true;
+        }
+        if self.base.canonicalize().is_ok_and(|base| resolved.starts_with(base)) {
+            return false;
+        }
+        self.allowed_roots.iter().any(|root| resolved.starts_with(root))
+    }
+
+    fn save(&self, chosen: &HashMap<i64, PathBuf>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(chosen)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_working_dir_allowlist_and_persistence() {
+        let base = tempfile::tempdir().unwrap();
+        let projects = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        std::fs::create_dir(projects.path().join("repo")).unwrap();
+        std::fs::create_dir_all(base.path().join("user_7").join("src")).unwrap();
+        std::fs::create_dir(base.path().join("user_8")).unwrap();
+        std::fs::write(projects.path().join("notes.txt"), "").unwrap();
+        let path = base.path().join("working_dirs.json");
+        let roots = vec![projects.path().to_path_buf()];
+
+        let dirs = WorkingDirs::open(base.path(), &roots, &path);
+        assert_eq!(dirs.for_user(7), base.path().join("user_7"));
+        let repo = dirs.set(7, projects.path().join("repo").to_str().unwrap()).unwrap();
+        assert_eq!(repo, projects.path().canonicalize().unwrap().join("repo"));
+        assert!(dirs.has_override(7));
+
+        // Relative to the current directory, and never outside the roots
+        assert_eq!(dirs.set(7, "..").unwrap(), projects.path().canonicalize().unwrap());
+        assert!(dirs.set(7, "../..").unwrap_err().to_string().contains("outside the allowed"));
+        assert!(dirs.set(7, outside.path().to_str().unwrap()).is_err());
+        assert!(dirs.set(7, "notes.txt").unwrap_err().to_string().contains("not a directory"));
+        assert!(dirs.set(7, "missing").unwrap_err().to_string().contains("doesn't exist"));
+        dirs.set(7, "repo").unwrap();
+        assert_eq!(dirs.for_user(8), base.path().join("user_8"));
+
+        // Survives a restart, unless the root is no longer allowed
+        assert_eq!(WorkingDirs::open(base.path(), &roots, &path).for_user(7), repo);
+        assert!(!WorkingDirs::open(base.path(), &[], &path).has_override(7));
+
+        assert_eq!(dirs.reset(7).unwrap(), base.path().join("user_7"));
+        assert!(!WorkingDirs::open(base.path(), &roots, &path).has_override(7));
+
+        // The base directory and other users' directories are off limits, own subdirs aren't
+        assert!(dirs.set(7, "..").unwrap_err().to_string().contains("outside the allowed"));
+        assert!(dirs.set(7, "../user_8").unwrap_err().to_string().contains("outside the allowed"));
+        assert_eq!(dirs.set(7, "src").unwrap(), base.path().canonicalize().unwrap().join("user_7/src"));
+        let base_as_root = WorkingDirs::in_memory(base.path(), &[base.path().to_path_buf()]);
+        assert!(base_as_root.set(7, base.path().join("user_8").to_str().unwrap()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_file_stays_in_working_dir() {
+        let base = tempfile::tempdir().unwrap();
+        let own = base.path().join("user_7");
+        std::fs::create_dir_all(own.join("src")).unwrap();
+        std::fs::create_dir(base.path().join("user_8")).unwrap();
+        std::fs::write(own.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(base.path().join("user_8/secrets.txt"), "hunter2").unwrap();
+        std::os::unix::fs::symlink(base.path().join("user_8/secrets.txt"), own.join("link.txt")).unwrap();
+        let dirs = WorkingDirs::in_memory(base.path(), &[]);
+
+        let file = dirs.resolve_file(7, &own, "src/main.rs").unwrap();
+        assert_eq!(file, own.canonicalize().unwrap().join("src/main.rs"));
+        assert!(dirs.resolve_file(7, &own, "./src/main.rs").is_ok());
+        for path in ["/etc/passwd", "../user_8/secrets.txt", "src/../../user_8/secrets.txt", "link.txt", "missing.rs"] {
+            assert!(dirs.resolve_file(7, &own, path).is_err(), "{}", path);
+        }
+        // A directory that isn't the user's own can't be read from either
+        assert!(dirs.resolve_file(7, &base.path().join("user_8"), "secrets.txt").is_err());
+    }
+}
@@ -8,6 +8,8 @@ async fn test_grpc_execute() {
         timeout_seconds: 60,
         ca_cert_path: None,
         domain: None,
+        max_retries: 0,
+        ..Default::default()
     };
 
     let client = GrpcBridgeClient::new(config).await.expect("Failed to connect");
@@ -42,6 +44,8 @@ async fn test_grpc_health() {
         timeout_seconds: 10,
         ca_cert_path: None,
         domain: None,
+        max_retries: 0,
+        ..Default::default()
     };
 
     let client = GrpcBridgeClient::new(config).await.expect("Failed to connect");
@@ -58,6 +62,8 @@ async fn test_grpc_status() {
         timeout_seconds: 10,
         ca_cert_path: None,
         domain: None,
+        max_retries: 0,
+        ..Default::default()
     };
 
     let client = GrpcBridgeClient::new(config).await.expect("Failed to connect");
@@ -17,9 +17,9 @@ async fn test_hybrid_search_with_ollama() {
     };
     
     // Learn some facts (sync, no embeddings)
-    store.learn("Rust is a systems programming language focused on safety", "technical", "test", 0.9).unwrap();
-    store.learn("Python is great for machine learning and data science", "technical", "test", 0.9).unwrap();
-    store.learn("The user prefers dark mode for coding", "preference", "test", 0.8).unwrap();
+    store.learn("Rust is a systems programming language focused on safety", "technical", "test", None, 0.9).unwrap();
+    store.learn("Python is great for machine learning and data science", "technical", "test", None, 0.9).unwrap();
+    store.learn("The user prefers dark mode for coding", "preference", "test", None, 0.8).unwrap();
     
     println!("Has embedder: {}", store.has_embeddings());
     
@@ -71,7 +71,7 @@ mod initialization {
         assert!(env.working_dir().exists());
 
         // Memory store works
-        let mem_result = env.memory_store.learn("test fact", "test", "test_source", 0.9);
+        let mem_result = env.memory_store.learn("test fact", "test", "test_source", None, 0.9);
         assert!(mem_result.is_ok());
 
         // Conversation store works
@@ -91,6 +91,7 @@ mod initialization {
             enable_consolidation: false,
             enable_decay: false,
             enable_compression: false,
+            compression: Default::default(),
         });
 
         // Initially should be in Wake state after activity
@@ -141,7 +142,7 @@ mod message_flow {
 
         // Simulate learning from a response
         let fact = "The user prefers Rust over Python";
-        let _id = env.memory_store.learn(fact, "preference", "test", 0.9).unwrap();
+        let _id = env.memory_store.learn(fact, "preference", "test", None, 0.9).unwrap();
 
         // Verify retrieval
         let results = env.memory_store.search("Rust preference", 5).unwrap();
@@ -187,6 +188,8 @@ mod budget_tracking {
             cache_write_tokens: 100,
             model: "claude-3-sonnet".to_string(),
             timestamp: chrono::Utc::now().timestamp(),
+            origin: claudebot_mcp::usage::ORIGIN_CHAT.to_string(),
+            estimated: false,
         };
 
         env.usage_tracker.record_usage(&record).unwrap();
@@ -475,7 +478,7 @@ mod e2e_simulation {
 
         // Step 6: Learn from response
         env.memory_store
-            .learn("Rust has ownership system for memory management", "technical", "chat", 0.85)
+            .learn("Rust has ownership system for memory management", "technical", "chat", None, 0.85)
             .unwrap();
 
         // Step 7: Record usage
@@ -487,6 +490,8 @@ mod e2e_simulation {
             cache_write_tokens: 0,
             model: "claude-3-sonnet".to_string(),
             timestamp: chrono::Utc::now().timestamp(),
+            origin: claudebot_mcp::usage::ORIGIN_CHAT.to_string(),
+            estimated: false,
         };
         env.usage_tracker.record_usage(&record).unwrap();
 